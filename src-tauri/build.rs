@@ -17,7 +17,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let proto_path = "proto/youtube_live_chat.proto";
     if std::path::Path::new(proto_path).exists() {
         tonic_build::configure()
-            .build_server(false) // We only need client
+            // サーバーはテスト用のモックサーバーでのみ使用
+            .build_server(true)
+            .server_mod_attribute(".", "#[cfg(test)]")
             .compile_protos(&[proto_path], &["proto/"])?;
     }
 
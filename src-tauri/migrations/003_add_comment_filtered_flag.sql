@@ -0,0 +1,3 @@
+-- ブロックリストに一致したコメントのフラグを追加
+-- フィルタされたコメントもログには残し、オーバーレイへの配信のみ止める
+ALTER TABLE comment_logs ADD COLUMN is_filtered INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,12 @@
+-- 視聴者数グラフ用のKPI履歴
+-- 統計情報を取得するたびに1件記録する（video_idごとに1配信分の系列になる）
+CREATE TABLE IF NOT EXISTS kpi_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    video_id TEXT NOT NULL,
+    recorded_at TEXT NOT NULL,
+    concurrent_viewers INTEGER,
+    like_count INTEGER,
+    view_count INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_kpi_history_video ON kpi_history(video_id, recorded_at);
@@ -0,0 +1,10 @@
+-- 配信セッション（ポーリングの開始〜停止）の記録
+-- セッションごとのスパチャ集計履歴で、この時間範囲のcomment_logsを集計する
+CREATE TABLE IF NOT EXISTS live_sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    video_id TEXT,  -- 公式APIをlive_chat_id指定で開始した場合はNULL
+    started_at TEXT NOT NULL,
+    ended_at TEXT   -- 配信中はNULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_live_sessions_started_at ON live_sessions(started_at);
@@ -0,0 +1,3 @@
+-- 楽曲のキー（調）を追加
+-- 既存の楽曲はNULL（未設定）のまま
+ALTER TABLE songs ADD COLUMN song_key TEXT;
@@ -0,0 +1,13 @@
+-- 歌唱履歴（曲が現在の曲になるたびに1件記録）
+-- 楽曲・セットリストを削除しても履歴を残すため、曲名等は記録時点の値を保存し外部キーは設定しない
+CREATE TABLE IF NOT EXISTS song_play_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    setlist_id TEXT NOT NULL,
+    setlist_song_id TEXT NOT NULL,
+    song_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    artist TEXT,
+    played_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_song_play_log_played_at ON song_play_log(played_at);
@@ -0,0 +1,3 @@
+-- 初見（初めてコメントした投稿者）の判定用インデックス
+-- 投稿者ごとに過去のコメントの有無をpublished_atで絞って調べる
+CREATE INDEX IF NOT EXISTS idx_comment_logs_author ON comment_logs(author_channel_id, published_at);
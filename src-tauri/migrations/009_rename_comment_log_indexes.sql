@@ -0,0 +1,8 @@
+-- comment_logsのインデックスを対象カラムがわかる名前に揃える
+-- エクスポート・検索・古いログの削除はpublished_atの範囲、初見判定はauthor_channel_idで絞り込む
+DROP INDEX IF EXISTS idx_comment_logs_published;
+CREATE INDEX IF NOT EXISTS idx_comment_logs_published_at ON comment_logs(published_at);
+
+-- 投稿者ごとの検索はpublished_atでも絞るため複合インデックスのまま
+DROP INDEX IF EXISTS idx_comment_logs_author;
+CREATE INDEX IF NOT EXISTS idx_comment_logs_author_channel_id ON comment_logs(author_channel_id, published_at);
@@ -0,0 +1,15 @@
+-- スパチャの記録（配信セッションごとの支援者ランキング用）
+-- 円換算額は記録時点の換算レートで保存し、ランキングの集計はamount_jpyの合計で行う
+CREATE TABLE IF NOT EXISTS superchat_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id INTEGER NOT NULL,  -- live_sessions.id
+    message_id TEXT NOT NULL UNIQUE,
+    author_channel_id TEXT NOT NULL,
+    author_name TEXT NOT NULL,
+    amount_micros INTEGER NOT NULL,
+    currency TEXT NOT NULL,
+    amount_jpy INTEGER NOT NULL,
+    received_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_superchat_log_session_id ON superchat_log(session_id, author_channel_id);
@@ -0,0 +1,4 @@
+-- スパチャの記録時刻を受信時刻からメッセージの投稿時刻に変更
+-- 目標金額の集計をコメント（comment_logs.published_at）と同じ基準で行い、集計開始時刻以降の絞り込みにインデックスを使う
+ALTER TABLE superchat_log RENAME COLUMN received_at TO published_at;
+CREATE INDEX IF NOT EXISTS idx_superchat_log_published_at ON superchat_log(published_at);
@@ -1,12 +1,14 @@
 /**
  * SuperchatCard - スーパーチャット専用表示コンポーネント
  *
- * 配置: left.lower
+ * 配置: left.lower（デフォルト。superchat:addのslotで変更可能）
  * 機能: スパチャを専用ウィジェットで目立たせて表示
  *
  * WebSocket連携:
  *   - superchat:add: スパチャを表示キューに追加
  *   - superchat:remove: 表示完了したスパチャを削除
+ *   - membership:add: メンバーシップ・ギフトを即時表示（キューに入れない）
+ *   - membership:remove: 表示完了したメンバーシップを削除
  *
  * style設定:
  *   - maxDisplay: number (同時表示最大数、デフォルト: 1)
@@ -15,6 +17,9 @@
 // デバッグモード: URLパラメータ ?debug=true で有効化
 const SUPERCHAT_DEBUG = new URLSearchParams(window.location.search).get('debug') === 'true';
 
+// メンバーシップの同時表示最大数（超えた場合は古いものから削除）
+const MAX_MEMBERSHIP_DISPLAY = 3;
+
 class SuperchatCard extends BaseComponent {
   constructor(config) {
     super(config);
@@ -23,6 +28,8 @@ class SuperchatCard extends BaseComponent {
     this.displayedSuperchats = new Map();
     // 待機中のスパチャキュー
     this.queue = [];
+    // 表示中のメンバーシップ（IDをキーにしたMap）
+    this.displayedMemberships = new Map();
   }
 
   render() {
@@ -80,6 +87,47 @@ class SuperchatCard extends BaseComponent {
     }
   }
 
+  /**
+   * メンバーシップを追加（membership:addイベントで呼び出される）
+   * スパチャのキューとは独立して即時表示する
+   * @param {object} data - MembershipPayload
+   */
+  addMembership(data) {
+    if (!data || !data.id) {
+      console.warn('[SuperchatCard] Invalid membership data:', data);
+      return;
+    }
+    if (this.displayedMemberships.has(data.id)) return;
+
+    // 同時表示の上限を超えた場合は最も古いカードを削除
+    if (this.displayedMemberships.size >= MAX_MEMBERSHIP_DISPLAY) {
+      const oldestId = this.displayedMemberships.keys().next().value;
+      this.removeMembership(oldestId);
+    }
+
+    const card = this._createMembershipCard(data);
+    this.element.appendChild(card);
+    this.displayedMemberships.set(data.id, card);
+
+    requestAnimationFrame(() => {
+      card.classList.add('visible');
+    });
+  }
+
+  /**
+   * メンバーシップを削除（membership:removeイベントで呼び出される）
+   * @param {string} id - メンバーシップのメッセージID
+   */
+  removeMembership(id) {
+    const el = this.displayedMemberships.get(id);
+    if (!el) return;
+    // 削除アニメーション中に同じIDが再度削除されないよう先にMapから外す
+    this.displayedMemberships.delete(id);
+    this._animateOut(el, () => {
+      el.remove();
+    });
+  }
+
   /**
    * キューから次のスパチャを表示
    */
@@ -149,7 +197,8 @@ class SuperchatCard extends BaseComponent {
 
     const amount = this.createElement('span', {
       className: 'superchat-amount',
-      textContent: data.amount || '',
+      // 通貨ごとに整形した金額を優先（旧バージョンのペイロードはamountのみ）
+      textContent: data.amountDisplay || data.amount || '',
     });
 
     nameAmount.appendChild(name);
@@ -168,6 +217,96 @@ class SuperchatCard extends BaseComponent {
       card.appendChild(message);
     }
 
+    // スーパーステッカー画像
+    if (data.stickerImageUrl) {
+      const sticker = this.createElement('img', {
+        className: 'superchat-sticker',
+        attrs: {
+          src: data.stickerImageUrl,
+          alt: 'Super Sticker',
+        },
+      });
+      sticker.onerror = () => {
+        sticker.style.display = 'none';
+      };
+      card.appendChild(sticker);
+    }
+
+    return card;
+  }
+
+  /**
+   * メンバーシップカードを生成
+   * ギフトは件数（×N）、メンバー継続は月数を大きく表示する
+   * @param {object} data - MembershipPayload
+   * @returns {HTMLElement}
+   */
+  _createMembershipCard(data) {
+    const kind = data.kind || 'join';
+    const card = this.createElement('div', {
+      className: `superchat-card membership-card membership-${kind}`,
+    });
+    card.dataset.id = data.id;
+
+    const header = this.createElement('div', {
+      className: 'superchat-header',
+    });
+
+    const avatar = this.createElement('img', {
+      className: 'superchat-avatar',
+      attrs: {
+        src: data.authorImageUrl || '',
+        alt: data.authorName || '',
+      },
+    });
+    avatar.onerror = () => {
+      avatar.style.display = 'none';
+    };
+
+    const nameLabel = this.createElement('div', {
+      className: 'superchat-name-amount',
+    });
+
+    const name = this.createElement('span', {
+      className: 'superchat-author',
+      textContent: data.authorName || 'Anonymous',
+    });
+
+    const label = this.createElement('span', {
+      className: 'membership-label',
+      textContent: data.label || '',
+    });
+
+    nameLabel.appendChild(name);
+    nameLabel.appendChild(label);
+    header.appendChild(avatar);
+    header.appendChild(nameLabel);
+
+    // ギフト件数・継続月数の強調表示
+    let highlight = '';
+    if (kind === 'gift' && data.giftCount) {
+      highlight = `×${data.giftCount}`;
+    } else if (kind === 'milestone' && data.milestoneMonths) {
+      highlight = `${data.milestoneMonths}`;
+    }
+    if (highlight) {
+      const count = this.createElement('span', {
+        className: 'membership-count',
+        textContent: highlight,
+      });
+      header.appendChild(count);
+    }
+
+    card.appendChild(header);
+
+    if (data.message) {
+      const message = this.createElement('div', {
+        className: 'superchat-message',
+        textContent: data.message,
+      });
+      card.appendChild(message);
+    }
+
     return card;
   }
 
@@ -249,6 +388,7 @@ class SuperchatCard extends BaseComponent {
 
   destroy() {
     this.displayedSuperchats.clear();
+    this.displayedMemberships.clear();
     this.queue = [];
     super.destroy();
   }
@@ -346,6 +486,14 @@ class SuperchatCard extends BaseComponent {
       overflow: hidden;
     }
 
+    .superchat-sticker {
+      display: block;
+      width: 72px;
+      height: 72px;
+      object-fit: contain;
+      margin: 4px auto 0;
+    }
+
     /* Tier別の微調整 */
     .superchat-card.tier-4,
     .superchat-card.tier-5 {
@@ -361,6 +509,44 @@ class SuperchatCard extends BaseComponent {
     .superchat-card.tier-5 .superchat-message {
       background: rgba(0, 0, 0, 0.1);
     }
+
+    /* メンバーシップ（YouTubeのメンバー表示に合わせた緑系） */
+    .membership-card {
+      --sc-bg-color: #0F9D58;
+    }
+
+    .membership-card.membership-gift {
+      --sc-bg-color: linear-gradient(135deg, #0F9D58, #2E7D32);
+    }
+
+    .membership-card .superchat-header {
+      margin-bottom: 0;
+    }
+
+    .membership-card .superchat-message {
+      margin-top: 8px;
+    }
+
+    .membership-label {
+      font-size: 13px;
+      white-space: nowrap;
+      overflow: hidden;
+      text-overflow: ellipsis;
+      opacity: 0.9;
+    }
+
+    .membership-count {
+      font-size: 28px;
+      font-weight: bold;
+      line-height: 1;
+      text-shadow: 0 2px 4px rgba(0, 0, 0, 0.3);
+    }
+
+    .membership-card.membership-milestone .membership-count::after {
+      content: 'か月';
+      font-size: 13px;
+      margin-left: 2px;
+    }
   `;
   document.head.appendChild(style);
 })();
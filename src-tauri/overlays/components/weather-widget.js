@@ -32,14 +32,17 @@ const FADE_IN_DURATION_MS = 300;
  * style設定:
  *   - icon: string (天気アイコン、デフォルト: '☀️')
  *   - temp: number (気温、デフォルト: 25)
+ *   - tempUnit: string (気温の単位記号、デフォルト: '°C')
  *   - description: string (説明、デフォルト: '晴れ')
  *   - location: string (地域名、デフォルト: '')
  *
  * update()で受け取るデータ:
  *   - icon: string
  *   - temp: number
+ *   - tempUnit: string (省略時は前回の単位を維持)
  *   - description: string
  *   - location: string
+ *   - stale: boolean (取得に失敗し前回の値を表示している場合はtrue、半透明で表示)
  *
  * updateMulti()で受け取るデータ:
  *   - cities: CityWeatherData[]
@@ -51,6 +54,7 @@ class WeatherWidget extends BaseComponent {
     // スタブ用デフォルト値
     this.icon = this.style.icon || '☀️';
     this.temp = this.style.temp ?? 25;
+    this.tempUnit = this.style.tempUnit || '°C';
     this.description = this.style.description || '晴れ';
     this.location = this.style.location || '';
 
@@ -78,7 +82,7 @@ class WeatherWidget extends BaseComponent {
 
     this.tempEl = this.createElement('span', {
       className: 'weather-temp',
-      textContent: `${this.temp}°C`,
+      textContent: `${this.temp}${this.tempUnit}`,
     });
 
     this.descEl = this.createElement('span', {
@@ -110,9 +114,19 @@ class WeatherWidget extends BaseComponent {
     this._stopRotation();
     this.multiMode = false;
 
+    this.element?.classList.toggle('weather-stale', data.stale === true);
     this._updateDisplay(data);
   }
 
+  /**
+   * 天気の自動更新の成否を反映（失敗中は減光）
+   * status:update WebSocketメッセージで呼び出される
+   * @param {boolean} weatherOk - 直近の自動更新が成功したか
+   */
+  setStatus(weatherOk) {
+    this.element?.classList.toggle('weather-offline', weatherOk === false);
+  }
+
   /**
    * マルチシティモードの更新
    * weather:multi-update WebSocketメッセージで呼び出される
@@ -127,6 +141,7 @@ class WeatherWidget extends BaseComponent {
       : DEFAULT_ROTATION_INTERVAL_SEC;
     this.rotationInterval = Math.max(intervalSec, MIN_ROTATION_INTERVAL_SEC) * 1000;
     this.multiMode = true;
+    this.element?.classList.remove('weather-stale');
 
     // 既存のタイマーをクリア
     this._stopRotation();
@@ -193,6 +208,7 @@ class WeatherWidget extends BaseComponent {
     this._updateDisplay({
       icon: cityData.icon,
       temp: cityData.temp,
+      tempUnit: cityData.tempUnit,
       description: cityData.description,
       location: cityData.cityName, // 表示名を使用
     });
@@ -207,9 +223,12 @@ class WeatherWidget extends BaseComponent {
       this.icon = data.icon;
       this.iconEl.textContent = data.icon;
     }
+    if (data.tempUnit) {
+      this.tempUnit = data.tempUnit;
+    }
     if (data.temp !== undefined) {
       this.temp = data.temp;
-      this.tempEl.textContent = `${data.temp}°C`;
+      this.tempEl.textContent = `${data.temp}${this.tempUnit}`;
     }
     if (data.description !== undefined) {
       this.description = data.description;
@@ -209,6 +209,66 @@ function renderMessageWithEmoji(container, messageRuns) {
   });
 }
 
+// =============================================================================
+// 翻訳
+// =============================================================================
+
+// コメントより先に届いた翻訳（コメントID → 翻訳、表示時に付ける）
+const pendingTranslations = new Map();
+const MAX_PENDING_TRANSLATIONS = 200;
+
+/**
+ * コメントの本文の下に翻訳を追加（追加済みの場合は何もしない）
+ * @param {HTMLElement} content - コメントの.content要素
+ * @param {string} translation - 翻訳
+ */
+function appendTranslation(content, translation) {
+  if (!content || content.querySelector('.translation')) return;
+  const el = document.createElement('div');
+  el.className = 'translation';
+  el.textContent = translation;
+  content.appendChild(el);
+}
+
+/**
+ * comment:translationを表示中のコメントに反映
+ * コメントがまだ表示されていない場合（バッファリング中等）は保持し、表示時に付ける
+ * @param {{id: string, translation: string}} payload
+ */
+function applyCommentTranslation(payload) {
+  if (!payload?.id || !payload.translation) return;
+  const el = document.querySelector(`.comment[data-id="${CSS.escape(payload.id)}"]`);
+  if (el) {
+    appendTranslation(el.querySelector('.content'), payload.translation);
+    return;
+  }
+  pendingTranslations.set(payload.id, payload.translation);
+  if (pendingTranslations.size > MAX_PENDING_TRANSLATIONS) {
+    // 古いものから削除（Mapは挿入順を保持）
+    pendingTranslations.delete(pendingTranslations.keys().next().value);
+  }
+}
+
+// =============================================================================
+// 表示テーマ
+// =============================================================================
+
+// 組み込みテーマ（バックエンドのCommentThemeと一致させる）
+const COMMENT_THEMES = ['standard', 'minimal', 'bubble', 'terminal'];
+
+/**
+ * comment:themeのテーマをbodyのクラス（comment-theme-{name}）として反映
+ * 未知のテーマ名は無視する
+ * @param {{theme: string}} payload
+ */
+function applyCommentTheme(payload) {
+  const theme = payload?.theme;
+  if (!COMMENT_THEMES.includes(theme)) return;
+  COMMENT_THEMES.forEach((name) => {
+    document.body.classList.toggle(`comment-theme-${name}`, name === theme);
+  });
+}
+
 // =============================================================================
 // コメント要素生成
 // =============================================================================
@@ -277,6 +337,20 @@ function createCommentElement(comment, showAvatar) {
     header.appendChild(badge);
   }
 
+  // 不適切度が重度のコメントは本文・翻訳をぼかし表示
+  if (comment.severity === 'strong') {
+    div.classList.add('severity-strong');
+  }
+
+  // 初見（初めてコメントした投稿者）
+  if (comment.isFirstTime) {
+    div.classList.add('first-time');
+    const badge = document.createElement('span');
+    badge.className = 'badge badge-first-time';
+    badge.textContent = '初見';
+    header.appendChild(badge);
+  }
+
   // スーパーチャットの金額表示
   if (messageType === 'superChat' && comment.messageType.amount) {
     const amount = document.createElement('span');
@@ -329,6 +403,13 @@ function createCommentElement(comment, showAvatar) {
   content.appendChild(header);
   content.appendChild(message);
 
+  // 翻訳（再送時はcomment.translation、新着は後からcomment:translationで届く）
+  const translation = comment.translation || pendingTranslations.get(comment.id);
+  pendingTranslations.delete(comment.id);
+  if (translation) {
+    appendTranslation(content, translation);
+  }
+
   div.appendChild(avatar);
   div.appendChild(content);
 
@@ -586,6 +667,8 @@ window.CommentRenderer = {
   getSuperChatColor,
   renderMessageWithEmoji,
   createCommentElement,
+  applyCommentTranslation,
+  applyCommentTheme,
   removeCommentWithAnimation,
   CommentQueueManager
 };
@@ -13,8 +13,21 @@
 // デバッグモード: URLパラメータ ?debug=true で有効化
 const DEBUG = new URLSearchParams(window.location.search).get('debug') === 'true';
 
-const WS_URL = 'ws://localhost:19801/ws';
-const API_BASE_URL = 'http://localhost:19800/api';
+// HTTPSで配信されている場合（TLSモード）はWebSocketもWSSで接続する
+const SECURE = window.location.protocol === 'https:';
+// WebSocketのポート: 既定のポートが使用中で別のポートで起動した場合、アプリがURLに ?wsPort= を付与する
+const WS_PORT_PARAM = new URLSearchParams(window.location.search).get('wsPort');
+const WS_PORT = /^\d{1,5}$/.test(WS_PORT_PARAM || '') ? WS_PORT_PARAM : '19801';
+// /overlay/以下で配信されている場合は、ページのホスト・ポートのサーバーに接続する（別PCのOBSから参照する場合を含む）
+const SERVED_BY_OVERLAY_SERVER = window.location.pathname.startsWith('/overlay/');
+const SERVER_HOST = SERVED_BY_OVERLAY_SERVER && window.location.hostname ? window.location.hostname : 'localhost';
+const HTTP_PORT = SERVED_BY_OVERLAY_SERVER && window.location.port ? window.location.port : '19800';
+const WS_URL = `${SECURE ? 'wss' : 'ws'}://${SERVER_HOST}:${WS_PORT}/ws`;
+// WebSocket認証トークン: オーバーレイURLの ?token= をWebSocket接続に引き継ぐ
+const AUTH_TOKEN = new URLSearchParams(window.location.search).get('token');
+// 購読トピック: オーバーレイURLの ?topics=weather,kpi をWebSocket接続に引き継ぐ（未指定時は全メッセージ）
+const TOPICS = new URLSearchParams(window.location.search).get('topics');
+const API_BASE_URL = `${SECURE ? 'https' : 'http'}://${SERVER_HOST}:${HTTP_PORT}/api`;
 const SETTINGS_FETCH_TIMEOUT = 3000;
 const MAX_RECONNECT_DELAY = 30000;
 const INITIAL_RECONNECT_DELAY = 1000;
@@ -40,6 +53,28 @@ function validateTimeout(timeout, defaultValue = SETTINGS_FETCH_TIMEOUT) {
   return Number.isFinite(timeout) && timeout > 0 ? timeout : defaultValue;
 }
 
+/**
+ * WebSocket URLに認証トークンを付与（トークンがない場合はそのまま）
+ * @param {string} url - WebSocket URL
+ * @returns {string} - トークン付きのURL
+ */
+function withAuthToken(url) {
+  if (!AUTH_TOKEN) return url;
+  const separator = url.includes('?') ? '&' : '?';
+  return `${url}${separator}token=${encodeURIComponent(AUTH_TOKEN)}`;
+}
+
+/**
+ * WebSocket URLに購読トピックを付与（指定がない場合はそのまま）
+ * @param {string} url - WebSocket URL
+ * @returns {string} - トピック付きのURL
+ */
+function withTopics(url) {
+  if (!TOPICS) return url;
+  const separator = url.includes('?') ? '&' : '?';
+  return `${url}${separator}topics=${encodeURIComponent(TOPICS)}`;
+}
+
 // =============================================================================
 // WebSocket接続マネージャー
 // =============================================================================
@@ -69,7 +104,7 @@ class WebSocketManager {
   connect() {
     if (this.isShuttingDown) return;
 
-    this.ws = new WebSocket(this.url);
+    this.ws = new WebSocket(withTopics(withAuthToken(this.url)));
 
     this.ws.onopen = () => {
       if (DEBUG) console.log('WebSocket connected');
@@ -80,7 +115,12 @@ class WebSocketManager {
     this.ws.onmessage = (event) => {
       try {
         const data = JSON.parse(event.data);
-        this.onMessage(data);
+        // バンドルフレームは内包メッセージを順に処理
+        if (data.type === 'bundle' && Array.isArray(data.messages)) {
+          data.messages.forEach((message) => this.onMessage(message));
+        } else {
+          this.onMessage(data);
+        }
       } catch (e) {
         console.error('WebSocket message handling error:', e);
       }
@@ -108,6 +148,19 @@ class WebSocketManager {
     };
   }
 
+  /**
+   * バックエンドにメッセージを送信（ack / request_snapshot / report_error）
+   * @param {Object} message - typeフィールドを持つメッセージ
+   * @returns {boolean} 送信できた場合はtrue（未接続時はfalse）
+   */
+  send(message) {
+    if (!this.ws || this.ws.readyState !== WebSocket.OPEN) {
+      return false;
+    }
+    this.ws.send(JSON.stringify(message));
+    return true;
+  }
+
   /**
    * 接続をクリーンアップ
    */
@@ -524,6 +577,8 @@ window.OverlayCore = {
   PostMessageHandler,
 
   // ヘルパー関数
+  withAuthToken,
+  withTopics,
   updateSetlistDisplay,
   fetchLatestSetlist,
   setupBfcacheHandlers
@@ -0,0 +1,90 @@
+//! データベースのバックアップ・復元コマンド
+//!
+//! PCの移行や障害からの復旧用に、DB全体のバックアップを作成・復元する。
+//! 復元は次回起動時に適用されるため、復元後はアプリの再起動が必要。
+//! 設定（settingsテーブル）のみのエクスポート・インポートも行える。
+
+use std::path::Path;
+
+use crate::db::backup;
+use crate::db::settings_backup::{self, SettingsImportReport};
+use crate::AppState;
+
+/// DB全体のバックアップを作成
+///
+/// アプリ実行中でも一貫したコピーを`dest_path`に作成する（既存ファイルは上書きしない）
+#[tauri::command(rename_all = "snake_case")]
+pub async fn backup_database(
+    dest_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    if dest_path.trim().is_empty() {
+        return Err("出力先のパスを指定してください".to_string());
+    }
+
+    backup::backup_to(&state.db, Path::new(&dest_path)).await?;
+    log::info!("Database backed up to {}", dest_path);
+    Ok(())
+}
+
+/// バックアップからDBを復元（アプリの再起動後に適用）
+///
+/// 復元元のスキーマを検証し、このアプリと互換性がない場合（新しいバージョンで作成されたDB等）はエラーを返す。
+/// 差し替え前のDBは`app.db.before-restore`として残る。
+#[tauri::command(rename_all = "snake_case")]
+pub async fn restore_database(
+    src_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    if src_path.trim().is_empty() {
+        return Err("復元元のパスを指定してください".to_string());
+    }
+
+    let db_path = backup::database_path(&state.db).await?;
+    backup::stage_restore(Path::new(&src_path), &db_path).await?;
+    log::info!("Database restore staged from {} (applied on restart)", src_path);
+    Ok(())
+}
+
+/// 設定をJSONファイルに書き出し、書き出した件数を返す
+///
+/// APIキーなどの秘密情報は含めない（既存ファイルは上書きしない）
+#[tauri::command(rename_all = "snake_case")]
+pub async fn export_settings(
+    dest_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<usize, String> {
+    if dest_path.trim().is_empty() {
+        return Err("出力先のパスを指定してください".to_string());
+    }
+
+    let count = settings_backup::export_to(&state.db, Path::new(&dest_path)).await?;
+    log::info!("Exported {} settings to {}", count, dest_path);
+    Ok(count)
+}
+
+/// JSONファイルから設定をインポート
+///
+/// `dry_run`の場合は変更せず、変更される内容のみを返す。
+/// 設定はアプリの再起動後に反映される
+#[tauri::command(rename_all = "snake_case")]
+pub async fn import_settings(
+    src_path: String,
+    dry_run: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<SettingsImportReport, String> {
+    if src_path.trim().is_empty() {
+        return Err("読み込むファイルのパスを指定してください".to_string());
+    }
+
+    let report = settings_backup::import_from(&state.db, Path::new(&src_path), dry_run).await?;
+    if report.applied {
+        log::info!(
+            "Imported settings from {} (added: {}, updated: {})",
+            src_path,
+            report.added.len(),
+            report.updated.len()
+        );
+    }
+    Ok(report)
+}
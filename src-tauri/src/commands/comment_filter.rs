@@ -0,0 +1,411 @@
+//! コメントのブロックリスト・チャンネルBAN設定コマンド
+//!
+//! ブロックリスト（1行1パターン）の設定・取得と、
+//! 投稿者（チャンネルID）のBAN・BAN解除・一覧取得、不適切語マスク・不適切度判定の設定・取得を提供する。
+//! データはDBのsettingsテーブルに保存される。
+
+use once_cell::sync::Lazy;
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+use tokio::sync::Mutex as TokioMutex;
+
+use crate::youtube::channel_ban;
+use crate::youtube::comment_filter::{self, CommentFilter};
+use crate::youtube::profanity::{self, ProfanityConfig, ProfanityMasker};
+use crate::youtube::severity::{self, SeverityClassifier, SeverityConfig};
+use crate::AppState;
+
+/// ブロックリストの保存キー
+const COMMENT_BLOCKLIST_KEY: &str = "comment_blocklist";
+
+/// BAN中のチャンネルIDの保存キー（JSON配列）
+const BANNED_CHANNELS_KEY: &str = "banned_channels";
+
+/// 不適切語マスク設定の保存キー（JSON）
+const PROFANITY_MASK_KEY: &str = "profanity_mask_config";
+
+/// 不適切度判定の設定の保存キー（JSON）
+const SEVERITY_CONFIG_KEY: &str = "comment_severity_config";
+
+/// BANリスト更新の排他ロック
+/// 読み込み→変更→保存の間に別の更新が割り込むと片方の変更が失われるため直列化する
+static BANNED_CHANNELS_UPDATE_LOCK: Lazy<TokioMutex<()>> = Lazy::new(|| TokioMutex::new(()));
+
+/// 保存済みのブロックリストをDBから読み込み
+///
+/// 未保存の場合は空文字列を返す。
+async fn load_blocklist_text(pool: &SqlitePool) -> Result<String, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(COMMENT_BLOCKLIST_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    Ok(result.map(|(value,)| value).unwrap_or_default())
+}
+
+/// 保存済みのブロックリストをDBから読み込み、フィルタに変換
+///
+/// 不正なパターンを含む場合は空のフィルタを返す。
+/// 起動時の設定反映に使用する。
+pub async fn load_comment_filter(pool: &SqlitePool) -> Result<CommentFilter, String> {
+    let blocklist = load_blocklist_text(pool).await?;
+    match CommentFilter::parse(&blocklist) {
+        Ok(filter) => Ok(filter),
+        Err(e) => {
+            log::warn!("Stored comment blocklist is invalid, filter disabled: {}", e);
+            Ok(CommentFilter::default())
+        }
+    }
+}
+
+/// コメントのブロックリストを保存
+///
+/// ## 書式
+/// - 1行に1パターン（空行は無視）
+/// - 通常の行は部分一致（大文字小文字を区別しない）
+/// - `/pattern/`形式の行は正規表現
+///
+/// 保存後は以降に取得したコメントから適用される
+#[tauri::command]
+pub async fn set_comment_blocklist(
+    blocklist: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let filter = CommentFilter::parse(&blocklist)?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(COMMENT_BLOCKLIST_KEY)
+    .bind(&blocklist)
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    comment_filter::set_filter(filter);
+    log::info!("Comment blocklist saved");
+    Ok(())
+}
+
+/// コメントのブロックリストを取得（保存時の文字列そのまま）
+#[tauri::command]
+pub async fn get_comment_blocklist(state: tauri::State<'_, AppState>) -> Result<String, String> {
+    load_blocklist_text(&state.db).await
+}
+
+/// 保存済みの不適切語マスク設定をDBから読み込み
+///
+/// 未保存の場合はデフォルト（無効）を返す。
+async fn load_profanity_config(pool: &SqlitePool) -> Result<ProfanityConfig, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(PROFANITY_MASK_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    match result {
+        Some((json,)) => serde_json::from_str(&json).map_err(|e| format!("JSON parse error: {}", e)),
+        None => Ok(ProfanityConfig::default()),
+    }
+}
+
+/// 保存済みの不適切語マスク設定をDBから読み込み、マスクに変換
+///
+/// 不正な設定の場合はマスクしない。起動時の設定反映に使用する。
+pub async fn load_profanity_masker(pool: &SqlitePool) -> Result<ProfanityMasker, String> {
+    let config = load_profanity_config(pool).await?;
+    match ProfanityMasker::new(&config) {
+        Ok(masker) => Ok(masker),
+        Err(e) => {
+            log::warn!("Stored profanity mask config is invalid, masking disabled: {}", e);
+            Ok(ProfanityMasker::default())
+        }
+    }
+}
+
+/// 不適切語マスクの設定を保存
+///
+/// 一致した語を同じ文字数の`*`に置き換えてブロードキャストする（DBには元の本文を保存）。
+/// 保存後は以降に取得したコメントから適用される
+#[tauri::command]
+pub async fn set_profanity_mask_config(
+    config: ProfanityConfig,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let masker = ProfanityMasker::new(&config)?;
+    let json = serde_json::to_string(&config).map_err(|e| format!("JSON serialize error: {}", e))?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(PROFANITY_MASK_KEY)
+    .bind(&json)
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    profanity::set_masker(masker);
+    log::info!("Profanity mask config saved: enabled={}", config.enabled);
+    Ok(())
+}
+
+/// 不適切語マスクの設定を取得
+#[tauri::command]
+pub async fn get_profanity_mask_config(
+    state: tauri::State<'_, AppState>,
+) -> Result<ProfanityConfig, String> {
+    load_profanity_config(&state.db).await
+}
+
+/// 保存済みの不適切度判定の設定をDBから読み込み
+///
+/// 未保存の場合はデフォルト（無効）を返す。
+async fn load_severity_config(pool: &SqlitePool) -> Result<SeverityConfig, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(SEVERITY_CONFIG_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    match result {
+        Some((json,)) => serde_json::from_str(&json).map_err(|e| format!("JSON parse error: {}", e)),
+        None => Ok(SeverityConfig::default()),
+    }
+}
+
+/// 保存済みの不適切度判定の設定をDBから読み込み、判定器に変換
+///
+/// 不正な設定の場合は判定しない。起動時の設定反映に使用する。
+pub async fn load_severity_classifier(pool: &SqlitePool) -> Result<SeverityClassifier, String> {
+    let config = load_severity_config(pool).await?;
+    match SeverityClassifier::new(&config) {
+        Ok(classifier) => Ok(classifier),
+        Err(e) => {
+            log::warn!("Stored severity config is invalid, classification disabled: {}", e);
+            Ok(SeverityClassifier::default())
+        }
+    }
+}
+
+/// 不適切度判定の設定（語ごとの重み・閾値）を保存
+///
+/// 一致した語の重みの合計でコメントを`clean` / `mild` / `strong`に分類し、`comment:add`に付けて配信する。
+/// ブロックリストに一致したコメントは従来どおり配信しない。保存後は以降に取得したコメントから適用される
+#[tauri::command]
+pub async fn set_severity_config(
+    config: SeverityConfig,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let classifier = SeverityClassifier::new(&config)?;
+    let json = serde_json::to_string(&config).map_err(|e| format!("JSON serialize error: {}", e))?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(SEVERITY_CONFIG_KEY)
+    .bind(&json)
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    severity::set_classifier(classifier);
+    log::info!(
+        "Severity config saved: enabled={}, words={}",
+        config.enabled,
+        config.words.len()
+    );
+    Ok(())
+}
+
+/// 不適切度判定の設定を取得
+#[tauri::command]
+pub async fn get_severity_config(
+    state: tauri::State<'_, AppState>,
+) -> Result<SeverityConfig, String> {
+    load_severity_config(&state.db).await
+}
+
+/// BAN中のチャンネルIDをDBから読み込み
+///
+/// 未保存の場合は空のセットを返す。起動時の設定反映にも使用する。
+pub async fn load_banned_channels(pool: &SqlitePool) -> Result<HashSet<String>, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(BANNED_CHANNELS_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    match result {
+        Some((json,)) => serde_json::from_str(&json).map_err(|e| format!("JSON parse error: {}", e)),
+        None => Ok(HashSet::new()),
+    }
+}
+
+/// BAN中のチャンネルIDを保存し、フィルタに反映
+async fn save_banned_channels(pool: &SqlitePool, channel_ids: HashSet<String>) -> Result<(), String> {
+    let mut sorted: Vec<&String> = channel_ids.iter().collect();
+    sorted.sort();
+    let json = serde_json::to_string(&sorted).map_err(|e| format!("JSON serialize error: {}", e))?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(BANNED_CHANNELS_KEY)
+    .bind(&json)
+    .bind(&now)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    channel_ban::set_banned_channels(channel_ids);
+    Ok(())
+}
+
+/// BANリストを排他的に読み込み・更新し、変更があれば保存
+///
+/// `update`が`true`を返した場合のみ保存する。保存済みの値が壊れている場合は
+/// 警告を出して空のリストから更新する（BAN操作自体は失敗させない）。
+///
+/// # Returns
+/// 変更があったかどうか
+async fn update_banned_channels<F>(pool: &SqlitePool, update: F) -> Result<bool, String>
+where
+    F: FnOnce(&mut HashSet<String>) -> bool,
+{
+    let _guard = BANNED_CHANNELS_UPDATE_LOCK.lock().await;
+
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(BANNED_CHANNELS_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+    let mut channel_ids: HashSet<String> = match result {
+        Some((json,)) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Invalid banned channels data, resetting to empty: {}", e);
+            HashSet::new()
+        }),
+        None => HashSet::new(),
+    };
+
+    if !update(&mut channel_ids) {
+        return Ok(false);
+    }
+    save_banned_channels(pool, channel_ids).await?;
+    Ok(true)
+}
+
+/// チャンネルをBAN（以降のコメントをオーバーレイ・UIに表示しない）
+#[tauri::command(rename_all = "snake_case")]
+pub async fn ban_channel(channel_id: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let channel_id = channel_id.trim().to_string();
+    if channel_id.is_empty() {
+        return Err("チャンネルIDを指定してください".to_string());
+    }
+
+    if update_banned_channels(&state.db, |ids| ids.insert(channel_id.clone())).await? {
+        log::info!("Channel banned: {}", channel_id);
+    }
+    Ok(())
+}
+
+/// チャンネルのBANを解除
+#[tauri::command(rename_all = "snake_case")]
+pub async fn unban_channel(channel_id: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let channel_id = channel_id.trim();
+    if update_banned_channels(&state.db, |ids| ids.remove(channel_id)).await? {
+        log::info!("Channel unbanned: {}", channel_id);
+    }
+    Ok(())
+}
+
+/// BAN中のチャンネルID一覧を取得（昇順）
+#[tauri::command]
+pub async fn list_banned_channels(state: tauri::State<'_, AppState>) -> Result<Vec<String>, String> {
+    let mut channel_ids: Vec<String> = load_banned_channels(&state.db).await?.into_iter().collect();
+    channel_ids.sort();
+    Ok(channel_ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[tokio::test]
+    async fn test_banned_channels_roundtrip() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        assert!(load_banned_channels(&pool).await.unwrap().is_empty());
+
+        let channel_ids: HashSet<String> = ["UC_b".to_string(), "UC_a".to_string()].into_iter().collect();
+        save_banned_channels(&pool, channel_ids.clone()).await.unwrap();
+        assert_eq!(load_banned_channels(&pool).await.unwrap(), channel_ids);
+
+        // 保存形式はソート済みのJSON配列
+        let (json,): (String,) = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+            .bind(BANNED_CHANNELS_KEY)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(json, r#"["UC_a","UC_b"]"#);
+    }
+
+    #[tokio::test]
+    async fn test_ban_then_unban() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        assert!(update_banned_channels(&pool, |ids| ids.insert("UC_a".to_string()))
+            .await
+            .unwrap());
+        // 既にBAN済みなら変更なし
+        assert!(!update_banned_channels(&pool, |ids| ids.insert("UC_a".to_string()))
+            .await
+            .unwrap());
+        assert!(update_banned_channels(&pool, |ids| ids.remove("UC_a"))
+            .await
+            .unwrap());
+        assert!(load_banned_channels(&pool).await.unwrap().is_empty());
+
+        // 壊れた保存値は空のリストとして扱い、BAN操作は成功させる
+        sqlx::query("UPDATE settings SET value = 'not json' WHERE key = ?")
+            .bind(BANNED_CHANNELS_KEY)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(update_banned_channels(&pool, |ids| ids.insert("UC_b".to_string()))
+            .await
+            .unwrap());
+        let expected: HashSet<String> = ["UC_b".to_string()].into_iter().collect();
+        assert_eq!(load_banned_channels(&pool).await.unwrap(), expected);
+    }
+}
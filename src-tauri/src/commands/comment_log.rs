@@ -0,0 +1,321 @@
+//! コメントログの保持期間設定・集計コマンド
+//!
+//! comment_logsの保持日数の設定・取得と、保持期間を過ぎたログの削除を提供する。
+//! 保持日数はDBのsettingsテーブルに保存され、起動時にも古いログを削除する。
+//! チャット盛り上がりグラフ用に、時間帯ごとのコメント数の集計も提供する。
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+use super::export::normalize_bound;
+use crate::youtube::db;
+use crate::youtube::write_queue::{self, WriteQueueStats};
+use crate::AppState;
+
+/// 保持日数の保存キー
+const COMMENT_LOG_RETENTION_DAYS_KEY: &str = "comment_log_retention_days";
+
+/// 保持日数のデフォルト値
+const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+/// 保持日数の最大値（約10年）
+pub(crate) const MAX_RETENTION_DAYS: u32 = 3650;
+
+/// ヒストグラムの集計間隔の最大値（1日）
+const MAX_HISTOGRAM_BUCKET_SECONDS: u32 = 86_400;
+
+/// ヒストグラムの最大バケット数（最初と最後のコメントの間の区間数）
+const MAX_HISTOGRAM_BUCKETS: i64 = 10_000;
+
+/// コメント数ヒストグラムの1区間
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentHistogramBucket {
+    /// 区間の開始時刻（UTC、RFC3339）
+    pub bucket_start: String,
+    /// 区間内のコメント数
+    pub count: i64,
+}
+
+/// 保存済みの保持日数をDBから読み込み（0は無期限）
+///
+/// 未保存・不正な値の場合はデフォルト（30日）を返す。
+pub async fn load_retention_days(pool: &SqlitePool) -> Result<u32, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(COMMENT_LOG_RETENTION_DAYS_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    let Some((value,)) = result else {
+        return Ok(DEFAULT_RETENTION_DAYS);
+    };
+
+    match value.parse::<u32>() {
+        Ok(days) if days <= MAX_RETENTION_DAYS => Ok(days),
+        _ => {
+            log::warn!(
+                "Stored comment log retention is invalid, falling back to default: {}",
+                value
+            );
+            Ok(DEFAULT_RETENTION_DAYS)
+        }
+    }
+}
+
+/// 保持日数を過ぎたコメントログを削除し、削除した件数を返す
+///
+/// 保持日数が0（無期限）の場合は何もしない。起動時の削除にも使用する。
+pub async fn prune_expired_comment_logs(pool: &SqlitePool) -> Result<u64, String> {
+    let days = load_retention_days(pool).await?;
+    if days == 0 {
+        return Ok(0);
+    }
+
+    let cutoff = (chrono::Utc::now() - chrono::Duration::days(i64::from(days))).to_rfc3339();
+    let removed = db::prune_comment_logs(pool, &cutoff).await?;
+    if removed > 0 {
+        log::info!("Pruned {} comment logs older than {} days", removed, days);
+    }
+    Ok(removed)
+}
+
+/// 時間帯ごとのコメント数を集計
+///
+/// `since`は正規化済みのUTC RFC3339文字列。配信から除外したコメント（`is_filtered`）は数えない。
+/// 最初と最後のコメントの間でコメントがない区間は0件として埋める。コメントがなければ空。
+async fn comment_histogram(
+    pool: &SqlitePool,
+    bucket_seconds: u32,
+    since: Option<&str>,
+) -> Result<Vec<CommentHistogramBucket>, String> {
+    let rows: Vec<(i64, i64)> = sqlx::query_as(
+        r#"
+        SELECT (CAST(strftime('%s', published_at) AS INTEGER) / ?1) * ?1 AS bucket, COUNT(*)
+        FROM comment_logs
+        WHERE is_filtered = 0 AND (?2 IS NULL OR published_at >= ?2)
+        GROUP BY bucket
+        ORDER BY bucket
+        "#,
+    )
+    .bind(i64::from(bucket_seconds))
+    .bind(since)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    let (Some(&(first, _)), Some(&(last, _))) = (rows.first(), rows.last()) else {
+        return Ok(Vec::new());
+    };
+
+    let step = i64::from(bucket_seconds);
+    if (last - first) / step >= MAX_HISTOGRAM_BUCKETS {
+        return Err(format!(
+            "集計区間が多すぎます（{}区間以下になるよう集計間隔を長くしてください）",
+            MAX_HISTOGRAM_BUCKETS
+        ));
+    }
+
+    let mut counts = rows.into_iter().peekable();
+    let mut buckets = Vec::new();
+    let mut bucket = first;
+    while bucket <= last {
+        let count = match counts.peek() {
+            Some(&(start, count)) if start == bucket => {
+                counts.next();
+                count
+            }
+            _ => 0,
+        };
+        let bucket_start = chrono::DateTime::from_timestamp(bucket, 0)
+            .ok_or_else(|| format!("Invalid timestamp: {}", bucket))?
+            .to_rfc3339();
+        buckets.push(CommentHistogramBucket { bucket_start, count });
+        bucket += step;
+    }
+    Ok(buckets)
+}
+
+/// コメントログの保持日数を保存（0で無期限）
+///
+/// ## 入力検証
+/// - 0〜3650日
+#[tauri::command]
+pub async fn set_comment_log_retention_days(
+    days: u32,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    if days > MAX_RETENTION_DAYS {
+        return Err(format!(
+            "保持日数は0〜{}日で指定してください: {}",
+            MAX_RETENTION_DAYS, days
+        ));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(COMMENT_LOG_RETENTION_DAYS_KEY)
+    .bind(days.to_string())
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    log::info!("Comment log retention saved: {} days", days);
+    Ok(())
+}
+
+/// コメントログの保持日数を取得（0は無期限）
+#[tauri::command]
+pub async fn get_comment_log_retention_days(
+    state: tauri::State<'_, AppState>,
+) -> Result<u32, String> {
+    load_retention_days(&state.db).await
+}
+
+/// 保持日数を過ぎたコメントログを今すぐ削除し、削除した件数を返す
+#[tauri::command]
+pub async fn prune_comment_logs(state: tauri::State<'_, AppState>) -> Result<u64, String> {
+    prune_expired_comment_logs(&state.db).await
+}
+
+/// 時間帯ごとのコメント数を取得（チャット盛り上がりグラフ用）
+///
+/// ## 引数
+/// - `bucket_seconds`: 集計間隔（1〜86400秒）
+/// - `since`: 集計開始時刻（RFC3339）。省略時は全期間
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_comment_histogram(
+    bucket_seconds: u32,
+    since: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<CommentHistogramBucket>, String> {
+    if bucket_seconds == 0 || bucket_seconds > MAX_HISTOGRAM_BUCKET_SECONDS {
+        return Err(format!(
+            "集計間隔は1〜{}秒で指定してください: {}",
+            MAX_HISTOGRAM_BUCKET_SECONDS, bucket_seconds
+        ));
+    }
+    let since = normalize_bound(since, "開始")?;
+    comment_histogram(&state.db, bucket_seconds, since.as_deref()).await
+}
+
+/// コメントログの書き込みキューの状態（書き込み待ち件数・満杯で破棄したコメント数）を取得
+#[tauri::command]
+pub async fn get_comment_write_queue_stats() -> Result<WriteQueueStats, String> {
+    Ok(write_queue::stats())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    async fn create_test_pool(temp_file: &NamedTempFile) -> SqlitePool {
+        crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn save_retention_value(pool: &SqlitePool, value: &str) {
+        sqlx::query("INSERT INTO settings (key, value, updated_at) VALUES (?, ?, datetime('now'))")
+            .bind(COMMENT_LOG_RETENTION_DAYS_KEY)
+            .bind(value)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_retention_days_defaults_to_30() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = create_test_pool(&temp_file).await;
+        assert_eq!(load_retention_days(&pool).await.unwrap(), DEFAULT_RETENTION_DAYS);
+
+        save_retention_value(&pool, "abc").await;
+        assert_eq!(load_retention_days(&pool).await.unwrap(), DEFAULT_RETENTION_DAYS);
+    }
+
+    #[tokio::test]
+    async fn test_zero_retention_keeps_logs() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = create_test_pool(&temp_file).await;
+        save_retention_value(&pool, "0").await;
+
+        sqlx::query(
+            "INSERT INTO comment_logs (id, youtube_id, message, author_name, author_channel_id, published_at)
+             VALUES ('old', 'old', 'old', 'Old', 'UC_old', '2000-01-01T00:00:00+00:00')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(prune_expired_comment_logs(&pool).await.unwrap(), 0);
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM comment_logs")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_comment_histogram_counts_per_bucket() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = create_test_pool(&temp_file).await;
+        assert!(comment_histogram(&pool, 60, None).await.unwrap().is_empty());
+
+        for (id, published_at) in [
+            ("c1", "2025-01-01T00:00:05+00:00"),
+            ("c2", "2025-01-01T00:00:59.500+00:00"),
+            ("c3", "2025-01-01T00:01:00+00:00"),
+            ("c4", "2025-01-01T00:03:30+00:00"),
+            ("c5", "2025-01-01T00:03:40+00:00"),
+            ("c6", "2025-01-01T00:03:50+00:00"),
+        ] {
+            sqlx::query(
+                "INSERT INTO comment_logs (id, youtube_id, message, author_name, author_channel_id, published_at)
+                 VALUES (?1, ?1, 'msg', 'Viewer', 'UC_viewer', ?2)",
+            )
+            .bind(id)
+            .bind(published_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query("UPDATE comment_logs SET is_filtered = 1 WHERE youtube_id = 'c6'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let histogram = comment_histogram(&pool, 60, None).await.unwrap();
+        let counts: Vec<(&str, i64)> = histogram
+            .iter()
+            .map(|b| (b.bucket_start.as_str(), b.count))
+            .collect();
+        // コメントがない区間（00:02）は0件で埋める
+        assert_eq!(
+            counts,
+            vec![
+                ("2025-01-01T00:00:00+00:00", 2),
+                ("2025-01-01T00:01:00+00:00", 1),
+                ("2025-01-01T00:02:00+00:00", 0),
+                ("2025-01-01T00:03:00+00:00", 2),
+            ]
+        );
+
+        let since = Some("2025-01-01T00:01:00+00:00");
+        let histogram = comment_histogram(&pool, 120, since).await.unwrap();
+        let counts: Vec<i64> = histogram.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![1, 2]);
+
+        // 範囲外のみ指定した場合は空
+        let since = Some("2025-01-02T00:00:00+00:00");
+        assert!(comment_histogram(&pool, 60, since).await.unwrap().is_empty());
+    }
+}
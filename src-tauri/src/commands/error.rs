@@ -0,0 +1,196 @@
+//! Tauriコマンドのエラー型
+//!
+//! フロントエンドがエラーの種類（クォータ超過・ネットワーク・DB等）で案内を出し分けられるよう、
+//! `{ "kind": "quotaExceeded", "message": "..." }`の形でシリアライズする。
+//! `message`は従来の文字列エラーと同じ内容のため、表示だけなら`message`を使えばよい。
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::weather::WeatherError;
+use crate::youtube::errors::YouTubeError;
+
+/// Tauriコマンドのエラー
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum CommandError {
+    /// 入力値が不正
+    #[error("{0}")]
+    Validation(String),
+
+    /// APIキーが無効・未設定
+    #[error("{0}")]
+    InvalidApiKey(String),
+
+    /// APIクォータ超過（翌日まで回復しない）
+    #[error("{0}")]
+    QuotaExceeded(String),
+
+    /// レート制限（時間をおけば回復する）
+    #[error("{0}")]
+    RateLimited(String),
+
+    /// 動画・ライブチャット・都市などが見つからない
+    #[error("{0}")]
+    NotFound(String),
+
+    /// 通信エラー・タイムアウト
+    #[error("{0}")]
+    Network(String),
+
+    /// 外部APIのエラー応答・レスポンスの解析失敗
+    #[error("{0}")]
+    Api(String),
+
+    /// DBエラー
+    #[error("{0}")]
+    Database(String),
+
+    /// その他のエラー
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl CommandError {
+    /// 種類を保ったまま、メッセージの前に失敗した処理の説明を付ける
+    ///
+    /// 例: `"初期化に失敗しました: Request timeout: ..."`
+    pub fn with_context(self, context: &str) -> Self {
+        let wrap = |message: String| format!("{}: {}", context, message);
+        match self {
+            Self::Validation(m) => Self::Validation(wrap(m)),
+            Self::InvalidApiKey(m) => Self::InvalidApiKey(wrap(m)),
+            Self::QuotaExceeded(m) => Self::QuotaExceeded(wrap(m)),
+            Self::RateLimited(m) => Self::RateLimited(wrap(m)),
+            Self::NotFound(m) => Self::NotFound(wrap(m)),
+            Self::Network(m) => Self::Network(wrap(m)),
+            Self::Api(m) => Self::Api(wrap(m)),
+            Self::Database(m) => Self::Database(wrap(m)),
+            Self::Internal(m) => Self::Internal(wrap(m)),
+        }
+    }
+}
+
+impl From<YouTubeError> for CommandError {
+    fn from(err: YouTubeError) -> Self {
+        let message = err.to_string();
+        match err {
+            YouTubeError::InvalidApiKey => Self::InvalidApiKey(message),
+            YouTubeError::QuotaExceeded => Self::QuotaExceeded(message),
+            YouTubeError::RateLimitExceeded => Self::RateLimited(message),
+            YouTubeError::VideoNotFound
+            | YouTubeError::LiveChatNotFound
+            | YouTubeError::LiveChatDisabled => Self::NotFound(message),
+            YouTubeError::HttpError(_) | YouTubeError::NetworkError(_) | YouTubeError::Timeout => {
+                Self::Network(message)
+            }
+            YouTubeError::InvalidPageToken
+            | YouTubeError::ParseError(_)
+            | YouTubeError::ApiError(_)
+            | YouTubeError::InnerTubeNotInitialized
+            | YouTubeError::InnerTubeContinuationExpired => Self::Api(message),
+            YouTubeError::PollerAlreadyRunning => Self::Internal(message),
+        }
+    }
+}
+
+impl From<WeatherError> for CommandError {
+    fn from(err: WeatherError) -> Self {
+        let message = err.to_string();
+        match err {
+            WeatherError::CityNotConfigured => Self::Validation(message),
+            WeatherError::CityNotFound(_) => Self::NotFound(message),
+            WeatherError::HttpError(_) | WeatherError::Timeout => Self::Network(message),
+            WeatherError::ApiError { .. } | WeatherError::ParseError(_) => Self::Api(message),
+        }
+    }
+}
+
+impl From<sqlx::Error> for CommandError {
+    fn from(err: sqlx::Error) -> Self {
+        Self::Database(format!("DB error: {}", err))
+    }
+}
+
+/// 分類されていない文字列エラー（設定の読み込み等の共通処理）
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::Internal(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_kind_and_message() {
+        let json = serde_json::to_value(CommandError::from(YouTubeError::QuotaExceeded)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "kind": "quotaExceeded",
+                "message": "Quota exceeded - please try again tomorrow"
+            })
+        );
+
+        let json = serde_json::to_value(CommandError::Validation("動画IDを指定してください".to_string())).unwrap();
+        assert_eq!(json["kind"], "validation");
+        assert_eq!(json["message"], "動画IDを指定してください");
+    }
+
+    #[test]
+    fn test_from_youtube_error() {
+        assert!(matches!(
+            CommandError::from(YouTubeError::InvalidApiKey),
+            CommandError::InvalidApiKey(_)
+        ));
+        assert!(matches!(
+            CommandError::from(YouTubeError::RateLimitExceeded),
+            CommandError::RateLimited(_)
+        ));
+        assert!(matches!(
+            CommandError::from(YouTubeError::LiveChatNotFound),
+            CommandError::NotFound(_)
+        ));
+        assert!(matches!(CommandError::from(YouTubeError::Timeout), CommandError::Network(_)));
+        // メッセージは従来の文字列エラーと同じ
+        assert_eq!(
+            CommandError::from(YouTubeError::VideoNotFound).to_string(),
+            YouTubeError::VideoNotFound.to_string()
+        );
+    }
+
+    #[test]
+    fn test_with_context_keeps_kind() {
+        let err = CommandError::from(YouTubeError::Timeout).with_context("初期化に失敗しました");
+        assert!(matches!(err, CommandError::Network(_)));
+        assert_eq!(
+            err.to_string(),
+            "初期化に失敗しました: Request timeout: API応答がありません"
+        );
+    }
+
+    #[test]
+    fn test_from_weather_error() {
+        assert!(matches!(
+            CommandError::from(WeatherError::CityNotFound("Atlantis".to_string())),
+            CommandError::NotFound(_)
+        ));
+        assert!(matches!(CommandError::from(WeatherError::Timeout), CommandError::Network(_)));
+        assert!(matches!(
+            CommandError::from(WeatherError::ApiError {
+                status: 500,
+                message: "error".to_string()
+            }),
+            CommandError::Api(_)
+        ));
+    }
+
+    #[test]
+    fn test_from_sqlx_error() {
+        let err = CommandError::from(sqlx::Error::RowNotFound);
+        assert!(matches!(err, CommandError::Database(_)));
+        assert!(err.to_string().starts_with("DB error: "));
+    }
+}
@@ -0,0 +1,264 @@
+//! コメントログのエクスポートコマンド
+//!
+//! comment_logsを配信後の分析用にCSV / NDJSONでファイルへ書き出す。
+//! 大量のログでもメモリを圧迫しないよう、SQLiteから1行ずつ読み込んで書き込む。
+
+use futures_util::TryStreamExt;
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use tokio::io::{AsyncWrite, AsyncWriteExt, BufWriter};
+
+use crate::db::models::CommentLog;
+use crate::AppState;
+
+/// エクスポート形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// CSV（ヘッダー行付き）
+    Csv,
+    /// 改行区切りJSON（NDJSON、1行1コメント）
+    Json,
+}
+
+/// CSVのヘッダー行
+const CSV_HEADER: &str = "id,youtube_id,published_at,author_name,author_channel_id,author_image_url,\
+message,message_type,message_data,is_owner,is_moderator,is_member,is_filtered\n";
+
+/// 期間指定（RFC3339）をUTCに正規化
+///
+/// `published_at`はUTCのRFC3339文字列で保存されているため、文字列比較で範囲を絞れる形にそろえる
+pub(crate) fn normalize_bound(value: Option<String>, label: &str) -> Result<Option<String>, String> {
+    let Some(value) = value.filter(|v| !v.trim().is_empty()) else {
+        return Ok(None);
+    };
+    chrono::DateTime::parse_from_rfc3339(value.trim())
+        .map(|dt| Some(dt.with_timezone(&chrono::Utc).to_rfc3339()))
+        .map_err(|e| format!("{}の日時が不正です（RFC3339形式で指定してください）: {}", label, e))
+}
+
+/// CSVのフィールドをエスケープ
+///
+/// カンマ・ダブルクォート・改行を含む場合はダブルクォートで囲み、内部のダブルクォートを二重にする
+fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// 1行分のCSVを生成（末尾に改行を含む）
+fn csv_row(log: &CommentLog) -> String {
+    let fields = [
+        escape_csv_field(&log.id),
+        escape_csv_field(&log.youtube_id),
+        escape_csv_field(&log.published_at),
+        escape_csv_field(&log.author_name),
+        escape_csv_field(&log.author_channel_id),
+        escape_csv_field(log.author_image_url.as_deref().unwrap_or_default()),
+        escape_csv_field(&log.message),
+        escape_csv_field(&log.message_type),
+        escape_csv_field(log.message_data.as_deref().unwrap_or_default()),
+        log.is_owner.to_string(),
+        log.is_moderator.to_string(),
+        log.is_member.to_string(),
+        log.is_filtered.to_string(),
+    ];
+    let mut row = fields.join(",");
+    row.push('\n');
+    row
+}
+
+/// コメントログを指定形式で書き出し、書き出した件数を返す
+///
+/// `start`/`end`は正規化済みのUTC RFC3339文字列（両端を含む）
+async fn write_comment_logs<W: AsyncWrite + Unpin>(
+    pool: &SqlitePool,
+    format: ExportFormat,
+    start: Option<&str>,
+    end: Option<&str>,
+    writer: &mut W,
+) -> Result<u64, String> {
+    if format == ExportFormat::Csv {
+        writer
+            .write_all(CSV_HEADER.as_bytes())
+            .await
+            .map_err(|e| format!("File error: {}", e))?;
+    }
+
+    let mut rows = sqlx::query_as::<_, CommentLog>(
+        r#"
+        SELECT id, youtube_id, message, author_name, author_channel_id, author_image_url,
+               is_owner, is_moderator, is_member, message_type, message_data,
+               published_at, created_at, is_filtered
+        FROM comment_logs
+        WHERE (?1 IS NULL OR published_at >= ?1) AND (?2 IS NULL OR published_at <= ?2)
+        ORDER BY published_at, rowid
+        "#,
+    )
+    .bind(start)
+    .bind(end)
+    .fetch(pool);
+
+    let mut count = 0u64;
+    while let Some(log) = rows.try_next().await.map_err(|e| format!("DB error: {}", e))? {
+        let line = match format {
+            ExportFormat::Csv => csv_row(&log),
+            ExportFormat::Json => {
+                let mut line = serde_json::to_string(&log)
+                    .map_err(|e| format!("JSON serialize error: {}", e))?;
+                line.push('\n');
+                line
+            }
+        };
+        writer
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|e| format!("File error: {}", e))?;
+        count += 1;
+    }
+
+    writer.flush().await.map_err(|e| format!("File error: {}", e))?;
+    Ok(count)
+}
+
+/// コメントログをファイルにエクスポート
+///
+/// ## 引数
+/// - `format`: `"csv"` または `"json"`（NDJSON）
+/// - `start`/`end`: 期間（RFC3339、`published_at`で絞り込み・両端を含む）。省略時は全期間
+/// - `path`: 出力先ファイルパス（既存ファイルは上書き）
+///
+/// 書き出した件数を返す。途中で失敗した場合は書きかけのファイルを削除する。
+#[tauri::command(rename_all = "snake_case")]
+pub async fn export_comment_logs(
+    format: ExportFormat,
+    start: Option<String>,
+    end: Option<String>,
+    path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<u64, String> {
+    let start = normalize_bound(start, "開始")?;
+    let end = normalize_bound(end, "終了")?;
+    if path.trim().is_empty() {
+        return Err("出力先のパスを指定してください".to_string());
+    }
+
+    let file = tokio::fs::File::create(&path)
+        .await
+        .map_err(|e| format!("File error: {}", e))?;
+    let mut writer = BufWriter::new(file);
+
+    match write_comment_logs(&state.db, format, start.as_deref(), end.as_deref(), &mut writer).await {
+        Ok(count) => {
+            log::info!("Exported {} comment logs to {} ({:?})", count, path, format);
+            Ok(count)
+        }
+        Err(e) => {
+            drop(writer);
+            if let Err(remove_err) = tokio::fs::remove_file(&path).await {
+                log::warn!("Failed to remove incomplete export file: {}", remove_err);
+            }
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::youtube::db::{mark_comments_filtered, save_comments_to_db};
+    use crate::youtube::types::ChatMessage;
+    use tempfile::NamedTempFile;
+
+    fn message_at(id: &str, text: &str, published_at: &str) -> ChatMessage {
+        ChatMessage::test_message(id).with_text(text).with_published_at(
+            chrono::DateTime::parse_from_rfc3339(published_at)
+                .unwrap()
+                .with_timezone(&chrono::Utc),
+        )
+    }
+
+    async fn create_pool_with_logs(temp_file: &NamedTempFile) -> SqlitePool {
+        let pool = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        let messages = vec![
+            message_at("c1", "おはよう", "2025-01-01T00:00:00Z"),
+            message_at("c2", "a, \"quoted\"\nline", "2025-01-01T01:00:00Z"),
+            message_at("c3", "おやすみ", "2025-01-01T02:00:00Z"),
+        ];
+        save_comments_to_db(&pool, &messages).await;
+        pool
+    }
+
+    #[test]
+    fn test_escape_csv_field() {
+        assert_eq!(escape_csv_field("plain"), "plain");
+        assert_eq!(escape_csv_field("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_csv_field("line1\nline2"), "\"line1\nline2\"");
+    }
+
+    #[test]
+    fn test_normalize_bound() {
+        assert_eq!(normalize_bound(None, "開始").unwrap(), None);
+        assert_eq!(normalize_bound(Some(" ".to_string()), "開始").unwrap(), None);
+        // タイムゾーン付きの指定はUTCにそろえる
+        assert_eq!(
+            normalize_bound(Some("2025-01-01T09:00:00+09:00".to_string()), "開始").unwrap(),
+            Some("2025-01-01T00:00:00+00:00".to_string())
+        );
+        assert!(normalize_bound(Some("2025/01/01".to_string()), "開始").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_export_csv_escapes_fields() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = create_pool_with_logs(&temp_file).await;
+        mark_comments_filtered(&pool, &["c3".to_string()]).await.unwrap();
+
+        let mut output = Vec::new();
+        let count = write_comment_logs(&pool, ExportFormat::Csv, None, None, &mut output)
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+
+        let csv = String::from_utf8(output).unwrap();
+        assert!(csv.starts_with(CSV_HEADER));
+        assert!(csv.contains(",\"a, \"\"quoted\"\"\nline\",text,"));
+        assert!(csv.ends_with(",false,false,false,true\n"));
+    }
+
+    #[tokio::test]
+    async fn test_export_ndjson_with_date_range() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = create_pool_with_logs(&temp_file).await;
+
+        let start = normalize_bound(Some("2025-01-01T10:00:00+09:00".to_string()), "開始").unwrap();
+        let end = normalize_bound(Some("2025-01-01T02:00:00Z".to_string()), "終了").unwrap();
+
+        let mut output = Vec::new();
+        let count = write_comment_logs(
+            &pool,
+            ExportFormat::Json,
+            start.as_deref(),
+            end.as_deref(),
+            &mut output,
+        )
+        .await
+        .unwrap();
+        assert_eq!(count, 2);
+
+        let ids: Vec<String> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let value: serde_json::Value = serde_json::from_str(line).unwrap();
+                value["youtubeId"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(ids, vec!["c2", "c3"]);
+    }
+}
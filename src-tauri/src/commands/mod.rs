@@ -1,9 +1,17 @@
+pub mod backup;
 pub mod brand;
+pub mod comment_filter;
+pub mod comment_log;
+pub mod error;
+pub mod export;
 pub mod keyring;
 pub mod overlay;
+pub mod pin;
 pub mod promo;
 pub mod queue;
 pub mod setlist;
+pub mod song_import;
+pub mod superchat;
 pub mod system;
 pub mod template;
 pub mod weather;
@@ -1,12 +1,44 @@
 use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
 use std::sync::Arc;
 
+use crate::server::chat_rate::{self, DEFAULT_CHAT_RATE_WINDOW_SECS};
+use crate::server::comment_theme;
+use crate::server::comment_throttle;
+use crate::server::milestone::MilestoneThresholds;
+use crate::server::websocket::{DEFAULT_REPLAY_BUFFER_SIZE, MAX_REPLAY_BUFFER_SIZE};
+use crate::server::addresses::{self, ServerAddresses, ServerPorts};
+use crate::server::self_test::{self, SelfTestReport};
+use crate::server::{cors, tls, ws_auth};
 use crate::server::types::{
-    CommentSettings, LayoutPreset, SetlistSettings, SettingsUpdatePayload, SuperchatSettings,
+    ChatRatePayload, CommentSettings, CommentTheme, ConnectedClient, LayoutPreset, SetlistSettings, SettingsUpdatePayload, SuperchatSettings,
     ThemeSettings, WeatherSettings, WidgetVisibilitySettings, WsMessage,
 };
+use crate::youtube::first_time::{self, FirstTimeScope};
+use crate::youtube::translation::{self, TranslationSettings};
 use crate::AppState;
 
+/// マイルストーン閾値の保存キー（JSON）
+const MILESTONE_THRESHOLDS_KEY: &str = "milestone_thresholds";
+
+/// 再送バッファの件数の保存キー
+const REPLAY_BUFFER_SIZE_KEY: &str = "ws_replay_buffer_size";
+
+/// アバター画像のない投稿者にidenticonを割り当てるかの保存キー
+const FALLBACK_AVATAR_ENABLED_KEY: &str = "fallback_avatar_enabled";
+
+/// チャット流速の集計期間（秒）の保存キー
+const CHAT_RATE_WINDOW_KEY: &str = "chat_rate_window_secs";
+
+/// コメント配信の1秒あたりの最大件数の保存キー（0は無効）
+const COMMENT_THROTTLE_RATE_KEY: &str = "comment_throttle_rate";
+
+/// 初見の判定範囲の保存キー（"session" / "all_time"）
+const FIRST_TIME_SCOPE_KEY: &str = "first_time_chatter_scope";
+
+/// コメント翻訳の設定の保存キー（JSON）
+const TRANSLATION_SETTINGS_KEY: &str = "comment_translation";
+
 /// HEXカラーコードのバリデーション (#RRGGBB形式)
 fn is_valid_hex_color(color: &str) -> bool {
     color.len() == 7
@@ -108,11 +140,25 @@ pub struct CommonSettings {
     pub border_radius: u32,
 }
 
+/// 保存するオーバーレイ設定の形式のバージョン
+///
+/// 保存形式を変更する場合はバージョンを上げ、`migrate_overlay_settings`に旧バージョンからの変換を追加する
+/// - 1: バージョン番号なし（weather/widget/superchat/themeSettingsは省略されうる）
+/// - 2: バージョン番号を付与し、省略されたセクションをデフォルト値で補完
+pub const OVERLAY_SETTINGS_VERSION: u32 = 2;
+
+fn current_overlay_settings_version() -> u32 {
+    OVERLAY_SETTINGS_VERSION
+}
+
 /// オーバーレイ設定全体
 /// NOTE: WidgetVisibilitySettingsはcrate::server::typesから再利用
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OverlaySettings {
+    /// 保存形式のバージョン（フロントエンドからの設定では省略され、保存時は常に現在のバージョン）
+    #[serde(default = "current_overlay_settings_version")]
+    pub version: u32,
     pub theme: String,
     pub layout: LayoutPreset,
     pub common: CommonSettings,
@@ -129,17 +175,83 @@ pub struct OverlaySettings {
     pub theme_settings: Option<ThemeSettings>,
 }
 
-/// オーバーレイ設定を保存
-#[tauri::command]
-pub async fn save_overlay_settings(
-    settings: OverlaySettings,
-    state: tauri::State<'_, AppState>,
+/// 保存された設定（JSON）を現在のバージョンの形式に変換
+///
+/// 変換した場合は`true`を返す。未対応の新しいバージョンはそのまま読み込む
+fn migrate_overlay_settings(value: &mut serde_json::Value) -> Result<bool, String> {
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| "Overlay settings must be a JSON object".to_string())?;
+
+    // バージョン番号のない設定はバージョン1
+    let mut version = match object.get("version") {
+        None => 1,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| format!("Invalid overlay settings version: {}", v))?,
+    };
+    if version > OVERLAY_SETTINGS_VERSION {
+        log::warn!(
+            "Overlay settings version {} is newer than supported version {}, loading as-is",
+            version,
+            OVERLAY_SETTINGS_VERSION
+        );
+        return Ok(false);
+    }
+
+    let from_version = version;
+    while version < OVERLAY_SETTINGS_VERSION {
+        match version {
+            1 => migrate_overlay_settings_v1_to_v2(object)?,
+            _ => return Err(format!("Unsupported overlay settings version: {}", version)),
+        }
+        version += 1;
+    }
+    object.insert("version".to_string(), serde_json::Value::from(version));
+
+    if from_version < version {
+        log::info!("Overlay settings migrated: v{} -> v{}", from_version, version);
+    }
+    Ok(from_version < version)
+}
+
+/// v1 → v2: 省略された（またはnullの）セクションをデフォルト値で補完
+fn migrate_overlay_settings_v1_to_v2(
+    object: &mut serde_json::Map<String, serde_json::Value>,
 ) -> Result<(), String> {
-    // サーバーサイドバリデーション
-    validate_overlay_settings(&settings)?;
+    let defaults = [
+        ("weather", serde_json::to_value(WeatherSettings::default())),
+        ("widget", serde_json::to_value(WidgetVisibilitySettings::default())),
+        ("superchat", serde_json::to_value(SuperchatSettings::default())),
+        ("themeSettings", serde_json::to_value(ThemeSettings::default())),
+    ];
+    for (key, default) in defaults {
+        if matches!(object.get(key), None | Some(serde_json::Value::Null)) {
+            let default = default.map_err(|e| format!("JSON serialize error: {}", e))?;
+            object.insert(key.to_string(), default);
+        }
+    }
+    Ok(())
+}
 
-    let pool = &state.db;
+/// 保存されたオーバーレイ設定（JSON文字列）を読み込み、旧バージョンの形式は現在の形式に変換
+///
+/// 変換した場合は2番目の値が`true`（呼び出し元で必要に応じて保存し直す）
+pub fn parse_overlay_settings(json_str: &str) -> Result<(OverlaySettings, bool), String> {
+    let mut value: serde_json::Value = serde_json::from_str(json_str).map_err(|e| e.to_string())?;
+    let migrated = migrate_overlay_settings(&mut value)?;
+    let settings = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    Ok((settings, migrated))
+}
+
+/// オーバーレイ設定をDBに保存（現在のバージョン番号を付与）
+async fn store_overlay_settings(pool: &SqlitePool, settings: &OverlaySettings) -> Result<(), String> {
     let now = chrono::Utc::now().to_rfc3339();
+    let settings = OverlaySettings {
+        version: OVERLAY_SETTINGS_VERSION,
+        ..settings.clone()
+    };
 
     let settings_str =
         serde_json::to_string(&settings).map_err(|e| format!("JSON serialize error: {}", e))?;
@@ -157,12 +269,29 @@ pub async fn save_overlay_settings(
     .await
     .map_err(|e| format!("DB error: {}", e))?;
 
+    Ok(())
+}
+
+/// オーバーレイ設定を保存
+#[tauri::command]
+pub async fn save_overlay_settings(
+    settings: OverlaySettings,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    // サーバーサイドバリデーション
+    validate_overlay_settings(&settings)?;
+
+    store_overlay_settings(&state.db, &settings).await?;
+
     log::info!("Overlay settings saved");
     Ok(())
 }
 
 /// オーバーレイ設定を読み込み
 ///
+/// ## 旧バージョンの形式
+/// `parse_overlay_settings`で現在の形式に変換し、変換後の設定を保存し直す（変換は1回のみ）
+///
 /// ## JSON破損時のフォールバック
 /// 保存されているJSONが破損している場合:
 /// 1. 破損データを`overlay_settings_backup_{timestamp}`キーに退避保存
@@ -184,8 +313,15 @@ pub async fn load_overlay_settings(
             .map_err(|e| format!("DB error: {}", e))?;
 
     if let Some((json_str,)) = result {
-        match serde_json::from_str::<OverlaySettings>(&json_str) {
-            Ok(settings) => Ok(Some(settings)),
+        match parse_overlay_settings(&json_str) {
+            Ok((settings, migrated)) => {
+                if migrated {
+                    if let Err(e) = store_overlay_settings(pool, &settings).await {
+                        log::warn!("Failed to save migrated overlay settings: {}", e);
+                    }
+                }
+                Ok(Some(settings))
+            }
             Err(e) => {
                 // JSON破損時: 破損データを退避してNoneを返す
                 log::warn!(
@@ -310,3 +446,757 @@ pub async fn broadcast_settings_update(
 
     Ok(())
 }
+
+/// WebSocketのバンドル送信（バースト時の結合）を切り替え
+///
+/// 有効時はKPI更新やコメントなどの短時間に集中した送信を
+/// `bundle`フレームにまとめ、オーバーレイへの送信回数を削減する
+#[tauri::command]
+pub async fn set_broadcast_bundling(
+    enabled: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    state.server.read().await.set_bundling(enabled);
+    Ok(())
+}
+
+/// WebSocketのバンドル送信が有効かを取得
+#[tauri::command]
+pub async fn get_broadcast_bundling(state: tauri::State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.server.read().await.is_bundling())
+}
+
+/// コメント表示テーマを設定し、変更時はオーバーレイへブロードキャスト
+///
+/// テーマ名は組み込みテーマ（standard/minimal/bubble/terminal）のいずれか
+#[tauri::command]
+pub async fn set_comment_theme(
+    name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<CommentTheme, String> {
+    comment_theme::apply_comment_theme(&state.db, &state.server, &name).await
+}
+
+/// 現在のコメント表示テーマを取得（未設定の場合はstandard）
+#[tauri::command]
+pub async fn get_comment_theme(state: tauri::State<'_, AppState>) -> Result<CommentTheme, String> {
+    Ok(comment_theme::load_comment_theme(&state.db)
+        .await?
+        .unwrap_or_default())
+}
+
+/// 保存済みのマイルストーン閾値をDBから読み込み
+///
+/// 未保存・不正な値の場合はデフォルト（コメント100/500/1000件、スパチャ1万/5万円）を返す。
+/// 起動時の設定反映に使用する。
+pub async fn load_milestone_thresholds(pool: &SqlitePool) -> Result<MilestoneThresholds, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(MILESTONE_THRESHOLDS_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    let Some((json,)) = result else {
+        return Ok(MilestoneThresholds::default());
+    };
+
+    match serde_json::from_str::<MilestoneThresholds>(&json)
+        .map_err(|e| e.to_string())
+        .and_then(MilestoneThresholds::normalize)
+    {
+        Ok(thresholds) => Ok(thresholds),
+        Err(e) => {
+            log::warn!("Stored milestone thresholds are invalid, falling back to default: {}", e);
+            Ok(MilestoneThresholds::default())
+        }
+    }
+}
+
+/// マイルストーンの閾値を保存し、以降のコメントから適用
+///
+/// ## 入力検証
+/// - 種別ごとに最大20件、0は不可（昇順・重複なしに正規化して保存）
+#[tauri::command]
+pub async fn set_milestone_thresholds(
+    thresholds: MilestoneThresholds,
+    state: tauri::State<'_, AppState>,
+) -> Result<MilestoneThresholds, String> {
+    let thresholds = thresholds.normalize()?;
+    let json = serde_json::to_string(&thresholds).map_err(|e| format!("JSON serialize error: {}", e))?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(MILESTONE_THRESHOLDS_KEY)
+    .bind(&json)
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    state.server.read().await.set_milestone_thresholds(thresholds.clone());
+    log::info!("Milestone thresholds saved: {:?}", thresholds);
+    Ok(thresholds)
+}
+
+/// マイルストーンの閾値を取得
+#[tauri::command]
+pub async fn get_milestone_thresholds(
+    state: tauri::State<'_, AppState>,
+) -> Result<MilestoneThresholds, String> {
+    Ok(state.server.read().await.milestone_thresholds())
+}
+
+/// マイルストーンの集計をリセット（ポーリング開始時にも自動でリセットされる）
+#[tauri::command]
+pub async fn reset_milestones(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    state.server.read().await.reset_milestones();
+    Ok(())
+}
+
+/// 保存済みの再送バッファの件数をDBから読み込み
+///
+/// 未保存・不正な値の場合はデフォルト（50件）を返す。起動時の設定反映に使用する。
+pub async fn load_replay_buffer_size(pool: &SqlitePool) -> Result<usize, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(REPLAY_BUFFER_SIZE_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    Ok(result
+        .and_then(|(value,)| value.parse::<usize>().ok())
+        .filter(|size| *size <= MAX_REPLAY_BUFFER_SIZE)
+        .unwrap_or(DEFAULT_REPLAY_BUFFER_SIZE))
+}
+
+/// 再送バッファの件数を保存し、即座に適用
+///
+/// OBSでオーバーレイを再読み込みした際に、直近のコメント・表示中のスパチャを
+/// 何件まで再送するか（0で再送しない）
+///
+/// ## 入力検証
+/// - 0〜200件
+#[tauri::command]
+pub async fn set_replay_buffer_size(
+    size: usize,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    if size > MAX_REPLAY_BUFFER_SIZE {
+        return Err(format!(
+            "再送バッファの件数は{}件以下で指定してください: {}件",
+            MAX_REPLAY_BUFFER_SIZE, size
+        ));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(REPLAY_BUFFER_SIZE_KEY)
+    .bind(size.to_string())
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    state.server.read().await.set_replay_buffer_size(size).await;
+    Ok(())
+}
+
+/// 再送バッファの件数を取得
+#[tauri::command]
+pub async fn get_replay_buffer_size(state: tauri::State<'_, AppState>) -> Result<usize, String> {
+    Ok(state.server.read().await.replay_buffer_size())
+}
+
+/// 保存済みのidenticon割り当ての有効/無効をDBから読み込み
+///
+/// 未保存の場合は有効を返す。起動時の設定反映に使用する。
+pub async fn load_fallback_avatar_enabled(pool: &SqlitePool) -> Result<bool, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(FALLBACK_AVATAR_ENABLED_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    Ok(result.map_or(true, |(value,)| value != "false"))
+}
+
+/// アバター画像のない投稿者にidenticonを割り当てるかを保存し、即座に適用
+///
+/// 無効にするとアバターURLを空のまま配信する
+#[tauri::command]
+pub async fn set_fallback_avatar_enabled(
+    enabled: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(FALLBACK_AVATAR_ENABLED_KEY)
+    .bind(enabled.to_string())
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    state.server.read().await.set_fallback_avatar_enabled(enabled);
+    Ok(())
+}
+
+/// アバター画像のない投稿者にidenticonを割り当てるかを取得
+#[tauri::command]
+pub async fn get_fallback_avatar_enabled(state: tauri::State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.server.read().await.is_fallback_avatar_enabled())
+}
+
+/// 保存済みのチャット流速の集計期間（秒）をDBから読み込み
+///
+/// 未保存・不正な値の場合はデフォルト（60秒）を返す。起動時の設定反映に使用する。
+pub async fn load_chat_rate_window(pool: &SqlitePool) -> Result<u32, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(CHAT_RATE_WINDOW_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    Ok(result
+        .and_then(|(value,)| value.parse::<u32>().ok())
+        .filter(|secs| chat_rate::validate_window_secs(*secs).is_ok())
+        .unwrap_or(DEFAULT_CHAT_RATE_WINDOW_SECS))
+}
+
+/// チャット流速の集計期間（秒）を保存し、次回の集計から適用
+///
+/// ## 入力検証
+/// - 10〜600秒
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_chat_rate_window(
+    window_secs: u32,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    chat_rate::validate_window_secs(window_secs)?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(CHAT_RATE_WINDOW_KEY)
+    .bind(window_secs.to_string())
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    state.server.read().await.set_chat_rate_window_secs(window_secs);
+    Ok(())
+}
+
+/// チャット流速の集計期間（秒）を取得
+#[tauri::command]
+pub async fn get_chat_rate_window(state: tauri::State<'_, AppState>) -> Result<u32, String> {
+    Ok(state.server.read().await.chat_rate_window_secs())
+}
+
+/// 保存済みのコメント配信の1秒あたりの最大件数をDBから読み込み
+///
+/// 未保存・不正な値の場合は0（無効）を返す。起動時の設定反映に使用する。
+pub async fn load_comment_throttle(pool: &SqlitePool) -> Result<u32, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(COMMENT_THROTTLE_RATE_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    Ok(result
+        .and_then(|(value,)| value.parse::<u32>().ok())
+        .filter(|rate| comment_throttle::validate_rate(*rate).is_ok())
+        .unwrap_or(0))
+}
+
+/// コメント配信のスローモード（1秒あたりの最大件数）を保存し、以降のコメントから適用
+///
+/// 超過分は順に遅らせて配信し、溜まりすぎた場合は古いコメントから破棄する。
+/// スパチャ・メンバーシップ等は制限の対象外。ポーリング間隔には影響しない。
+///
+/// ## 入力検証
+/// - 0（無効）または1〜50件
+#[tauri::command]
+pub async fn set_comment_throttle(rate: u32, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    comment_throttle::validate_rate(rate)?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(COMMENT_THROTTLE_RATE_KEY)
+    .bind(rate.to_string())
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    state.server.read().await.set_comment_throttle_rate(rate);
+    Ok(())
+}
+
+/// コメント配信のスローモード（1秒あたりの最大件数、0は無効）を取得
+#[tauri::command]
+pub async fn get_comment_throttle(state: tauri::State<'_, AppState>) -> Result<u32, String> {
+    Ok(state.server.read().await.comment_throttle_rate())
+}
+
+/// 現在のチャット流速（1分あたりのコメント数）を取得
+#[tauri::command]
+pub async fn get_chat_rate(state: tauri::State<'_, AppState>) -> Result<ChatRatePayload, String> {
+    Ok(state.server.read().await.chat_rate())
+}
+
+/// 保存済みの初見の判定範囲をDBから読み込み
+///
+/// 未保存・不正な値の場合はデフォルト（今回の配信）を返す。起動時の設定反映に使用する。
+pub async fn load_first_time_scope(pool: &SqlitePool) -> Result<FirstTimeScope, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(FIRST_TIME_SCOPE_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    Ok(result
+        .and_then(|(value,)| FirstTimeScope::parse(&value))
+        .unwrap_or_default())
+}
+
+/// 初見の判定範囲を保存し、以降のコメントから適用
+///
+/// - `session`: 今回の配信（ポーリング開始以降）で初めてのコメント
+/// - `all_time`: comment_logsに過去のコメントがない投稿者の初めてのコメント
+#[tauri::command]
+pub async fn set_first_time_scope(
+    scope: FirstTimeScope,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(FIRST_TIME_SCOPE_KEY)
+    .bind(scope.as_str())
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    first_time::set_scope(scope);
+    Ok(())
+}
+
+/// 初見の判定範囲を取得
+#[tauri::command]
+pub async fn get_first_time_scope() -> Result<FirstTimeScope, String> {
+    Ok(first_time::scope())
+}
+
+/// 保存済みのコメント翻訳の設定をDBから読み込み
+///
+/// 未保存・JSON破損・不正な値の場合はデフォルト（無効）を返す。起動時の設定反映に使用する。
+pub async fn load_translation_settings(pool: &SqlitePool) -> Result<TranslationSettings, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(TRANSLATION_SETTINGS_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    Ok(result
+        .and_then(|(json_str,)| serde_json::from_str::<TranslationSettings>(&json_str).ok())
+        .filter(|settings| settings.validate().is_ok())
+        .unwrap_or_default())
+}
+
+/// コメント翻訳の設定を保存し、以降のコメントから適用
+///
+/// 有効時はコメント本文が外部の翻訳APIに送信される（デフォルト: 無効）
+///
+/// ## 入力検証
+/// - 翻訳先言語: "ja" "en" "zh-TW" 等の言語コード
+/// - 翻訳APIのURL: http(s)のURL
+#[tauri::command]
+pub async fn set_translation_settings(
+    settings: TranslationSettings,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    settings.validate()?;
+    let json_str = serde_json::to_string(&settings).map_err(|e| format!("JSON error: {}", e))?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(TRANSLATION_SETTINGS_KEY)
+    .bind(&json_str)
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    log::info!(
+        "Comment translation: {} (target: {}, api: {})",
+        settings.enabled,
+        settings.target_language,
+        settings.api_url
+    );
+    translation::set_settings(settings);
+    Ok(())
+}
+
+/// コメント翻訳の設定を取得
+#[tauri::command]
+pub async fn get_translation_settings() -> Result<TranslationSettings, String> {
+    Ok(translation::settings())
+}
+
+/// 接続中のオーバーレイ（OBSブラウザソース等）の一覧を取得
+///
+/// 接続元・接続時刻・最後のPong受信時刻・購読トピックを返す。UIの「オーバーレイ接続数」表示に使用する
+#[tauri::command]
+pub async fn get_connected_overlays(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<ConnectedClient>, String> {
+    Ok(state.server.read().await.connected_clients().await)
+}
+
+/// 接続中のオーバーレイ数を取得
+///
+/// 生存確認（Ping/Pong）に応答しない接続は切断済みのため含まない
+#[tauri::command]
+pub async fn connected_overlay_count(state: tauri::State<'_, AppState>) -> Result<usize, String> {
+    Ok(state.server.read().await.peer_count().await)
+}
+
+/// WebSocket認証の設定
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WsAuthSettings {
+    /// 認証が有効か
+    pub enabled: bool,
+    /// オーバーレイURLに`?token=`として付与するトークン（無効の場合はNone）
+    pub token: Option<String>,
+}
+
+/// WebSocket認証の設定を取得
+#[tauri::command]
+pub async fn get_ws_auth_settings(
+    state: tauri::State<'_, AppState>,
+) -> Result<WsAuthSettings, String> {
+    let token = state.server.read().await.auth_token();
+    Ok(WsAuthSettings {
+        enabled: token.is_some(),
+        token,
+    })
+}
+
+/// WebSocket認証の有効/無効を保存
+///
+/// 有効にすると初回はトークンを生成してkeyringに保存する。
+/// 有効にした時点で接続中のオーバーレイは切断し、トークン付きでの再接続を要求する
+#[tauri::command]
+pub async fn set_ws_auth_enabled(
+    enabled: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<WsAuthSettings, String> {
+    ws_auth::save_ws_auth_enabled(&state.db, enabled).await?;
+
+    let token = if enabled {
+        Some(ws_auth::load_or_create_token().await)
+    } else {
+        None
+    };
+    let server = state.server.read().await;
+    server.set_auth_token(token.clone());
+    if enabled {
+        server.disconnect_all_unauthorized().await;
+    }
+    Ok(WsAuthSettings { enabled, token })
+}
+
+/// WebSocket認証トークンを再生成してkeyringに保存
+///
+/// 認証が有効な場合は古いトークンで接続中のオーバーレイを切断する
+/// （OBSのブラウザソースのURLを新しいトークンに更新する必要がある）
+#[tauri::command]
+pub async fn rotate_ws_auth_token(state: tauri::State<'_, AppState>) -> Result<String, String> {
+    let token = ws_auth::rotate_token().await?;
+
+    let server = state.server.read().await;
+    if server.auth_token().is_some() {
+        server.set_auth_token(Some(token.clone()));
+        server.disconnect_all_unauthorized().await;
+    }
+    log::info!("WebSocket auth token rotated");
+    Ok(token)
+}
+
+/// オーバーレイ配信のTLS（HTTPS/WSS）設定
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerTlsSettings {
+    /// 保存済みの設定（次回起動時に反映）
+    pub enabled: bool,
+    /// 起動中のサーバーがTLSで配信しているか
+    pub active: bool,
+    /// 起動中のサーバーのオーバーレイページのベースURL（起動できなかった場合はNone）
+    pub http_base_url: Option<String>,
+    /// 起動中のサーバーのWebSocket URL（起動できなかった場合はNone）
+    pub ws_url: Option<String>,
+    /// 自己署名証明書のパス（ブラウザ・OSで信頼する場合に使用）
+    pub cert_path: Option<String>,
+}
+
+async fn server_tls_settings(pool: &sqlx::SqlitePool) -> Result<ServerTlsSettings, String> {
+    let current = addresses::current();
+    let cert_path = dirs::data_dir()
+        .map(|dir| dir.join(crate::APP_IDENTIFIER).join(tls::TLS_DIR).join(tls::CERT_FILE))
+        .filter(|path| path.exists())
+        .map(|path| path.to_string_lossy().into_owned());
+    Ok(ServerTlsSettings {
+        enabled: tls::load_server_tls_enabled(pool).await?,
+        active: current.tls,
+        http_base_url: current.http_base_url,
+        ws_url: current.ws_url,
+        cert_path,
+    })
+}
+
+/// 配信前のセルフテストを実行
+///
+/// HTTPサーバーの応答、WebSocketの送受信（自分自身に接続して確認用メッセージをブロードキャスト）、
+/// DBへの書き込みを確認し、項目ごとの結果を返す
+#[tauri::command]
+pub async fn run_self_test(state: tauri::State<'_, AppState>) -> Result<SelfTestReport, String> {
+    let tls_dir = dirs::data_dir()
+        .map(|dir| dir.join(crate::APP_IDENTIFIER).join(tls::TLS_DIR))
+        .ok_or_else(|| "データディレクトリを取得できませんでした".to_string())?;
+    let report = self_test::run(&state.server, &state.db, &tls_dir).await;
+    if report.ok {
+        log::info!("Self test passed");
+    } else {
+        log::warn!("Self test failed: {:?}", report);
+    }
+    Ok(report)
+}
+
+/// オーバーレイ配信のTLS設定を取得
+#[tauri::command]
+pub async fn get_server_tls_settings(state: tauri::State<'_, AppState>) -> Result<ServerTlsSettings, String> {
+    server_tls_settings(&state.db).await
+}
+
+/// オーバーレイ配信のTLS（HTTPS/WSS）の有効/無効を保存
+///
+/// サーバーはポートを変えずに配信方式を切り替えるため、アプリの再起動後に反映される。
+/// 有効にすると初回起動時に自己署名証明書を生成する
+#[tauri::command]
+pub async fn set_server_tls_enabled(
+    enabled: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<ServerTlsSettings, String> {
+    tls::save_server_tls_enabled(&state.db, enabled).await?;
+    log::info!("Server TLS {} (applies after restart)", if enabled { "enabled" } else { "disabled" });
+    server_tls_settings(&state.db).await
+}
+
+/// 起動中のHTTP/WebSocketサーバーのアドレスを取得
+///
+/// 保存済みのポートが使用中で別のポートで起動した場合は実際のポートを返す。
+/// 起動できなかったサーバーはポートがNoneになり、理由をエラーとして返す
+#[tauri::command]
+pub async fn get_server_addresses() -> Result<ServerAddresses, String> {
+    Ok(addresses::current())
+}
+
+/// 保存済みのサーバーのポート設定を取得
+#[tauri::command]
+pub async fn get_server_ports(state: tauri::State<'_, AppState>) -> Result<ServerPorts, String> {
+    addresses::load_server_ports(&state.db).await
+}
+
+/// サーバーのポート設定を保存（アプリ再起動後に反映）
+///
+/// ## 入力検証
+/// - 1024〜65535
+/// - HTTPとWebSocketで異なるポート
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_server_ports(
+    http_port: u16,
+    ws_port: u16,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let ports = ServerPorts {
+        http: http_port,
+        ws: ws_port,
+    };
+    addresses::save_server_ports(&state.db, ports).await?;
+    log::info!("Server ports saved: HTTP {}, WebSocket {} (applies after restart)", http_port, ws_port);
+    Ok(())
+}
+
+/// 保存済みのサーバーの待ち受けアドレスを取得（デフォルト: 127.0.0.1）
+#[tauri::command]
+pub async fn get_server_bind_address(state: tauri::State<'_, AppState>) -> Result<String, String> {
+    addresses::load_bind_address(&state.db).await.map(|address| address.to_string())
+}
+
+/// サーバーの待ち受けアドレスを保存（アプリ再起動後に反映）
+///
+/// 別PCのOBSから参照する場合は0.0.0.0（全インターフェース）やLANのアドレスを指定する
+///
+/// ## 入力検証
+/// - ループバック・0.0.0.0・::・プライベートアドレス・リンクローカルのみ
+#[tauri::command]
+pub async fn set_server_bind_address(
+    address: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let address = addresses::parse_bind_address(&address)?;
+    addresses::save_bind_address(&state.db, address).await?;
+    log::info!("Server bind address saved: {} (applies after restart)", address);
+    Ok(())
+}
+
+/// CORSで追加許可しているオリジンを取得（ループバックのオリジンは常に許可）
+#[tauri::command]
+pub async fn get_cors_allowed_origins() -> Result<Vec<String>, String> {
+    Ok(cors::allowed_origins())
+}
+
+/// CORSで追加許可するオリジンを保存（即時反映）
+///
+/// ## 入力検証
+/// - 最大20件
+/// - `http(s)://ホスト[:ポート]`形式
+#[tauri::command]
+pub async fn set_cors_allowed_origins(
+    origins: Vec<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let origins = cors::normalize_origins(origins)?;
+    cors::save_allowed_origins(&state.db, &origins).await?;
+    cors::set_allowed_origins(origins.clone());
+    log::info!("CORS allowed origins saved: {:?}", origins);
+    Ok(origins)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// バージョン番号・weather/widget/superchat/themeSettingsのない旧形式（v1）の設定
+    fn v1_settings_json() -> serde_json::Value {
+        serde_json::json!({
+            "theme": "default",
+            "layout": "three-column",
+            "common": {
+                "primaryColor": "#6366f1",
+                "fontFamily": "'Yu Gothic', 'Meiryo', sans-serif",
+                "borderRadius": 8
+            },
+            "comment": {
+                "enabled": true,
+                "position": "bottom-right",
+                "showAvatar": true,
+                "fontSize": 16
+            },
+            "setlist": {
+                "enabled": true,
+                "position": "bottom",
+                "showArtist": true,
+                "fontSize": 24
+            }
+        })
+    }
+
+    #[test]
+    fn test_parse_migrates_v1_settings() {
+        let (settings, migrated) = parse_overlay_settings(&v1_settings_json().to_string()).unwrap();
+        assert!(migrated);
+        assert_eq!(settings.version, OVERLAY_SETTINGS_VERSION);
+        assert_eq!(settings.theme, "default");
+        assert_eq!(settings.common.border_radius, 8);
+        // 省略されたセクションはデフォルト値で補完
+        assert!(settings.weather.unwrap().enabled);
+        assert!(settings.widget.unwrap().clock);
+        assert!(settings.superchat.is_some());
+        assert!(settings.theme_settings.is_some());
+    }
+
+    #[test]
+    fn test_migration_keeps_existing_sections() {
+        let mut json = v1_settings_json();
+        json["weather"] = serde_json::json!({ "enabled": false, "position": "right-top" });
+        json["widget"] = serde_json::Value::Null;
+
+        let (settings, migrated) = parse_overlay_settings(&json.to_string()).unwrap();
+        assert!(migrated);
+        assert!(!settings.weather.unwrap().enabled);
+        assert!(settings.widget.is_some());
+    }
+
+    #[test]
+    fn test_current_version_is_not_migrated() {
+        let (settings, _) = parse_overlay_settings(&v1_settings_json().to_string()).unwrap();
+        let json = serde_json::to_string(&settings).unwrap();
+
+        let (reloaded, migrated) = parse_overlay_settings(&json).unwrap();
+        assert!(!migrated);
+        assert_eq!(reloaded.version, OVERLAY_SETTINGS_VERSION);
+    }
+
+    #[test]
+    fn test_newer_version_is_loaded_as_is() {
+        let mut json = v1_settings_json();
+        json["version"] = serde_json::json!(OVERLAY_SETTINGS_VERSION + 1);
+
+        let (settings, migrated) = parse_overlay_settings(&json.to_string()).unwrap();
+        assert!(!migrated);
+        assert_eq!(settings.version, OVERLAY_SETTINGS_VERSION + 1);
+        assert!(settings.weather.is_none());
+    }
+
+    #[test]
+    fn test_invalid_version_is_rejected() {
+        let mut json = v1_settings_json();
+        json["version"] = serde_json::json!("two");
+        assert!(parse_overlay_settings(&json.to_string()).is_err());
+    }
+}
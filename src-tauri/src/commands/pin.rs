@@ -0,0 +1,106 @@
+//! 固定表示コマンド
+//!
+//! コメント・告知をオーバーレイに固定表示する。固定は同時に1つで、新しい固定は前の固定を置き換える。
+//! 固定はメモリ上のみで保持し（アプリ再起動で解除）、接続時のスナップショットで再送する。
+
+use crate::server::types::{PinKind, PinPayload, WsMessage};
+use crate::youtube::db::get_comment_by_id;
+use crate::AppState;
+
+/// 告知の最大文字数
+const MAX_ANNOUNCEMENT_CHARS: usize = 200;
+
+/// 告知の文言を検証し、前後の空白を除いて返す
+///
+/// ## 入力検証
+/// - 空白のみ不可
+/// - 200文字以内
+fn normalize_announcement(text: &str) -> Result<String, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("告知の文言を入力してください".to_string());
+    }
+    let chars = text.chars().count();
+    if chars > MAX_ANNOUNCEMENT_CHARS {
+        return Err(format!(
+            "告知は{}文字以内で入力してください: {}文字",
+            MAX_ANNOUNCEMENT_CHARS, chars
+        ));
+    }
+    Ok(text.to_string())
+}
+
+/// 固定表示を配信（前の固定を置き換える）
+async fn broadcast_pin(state: &AppState, payload: PinPayload) -> PinPayload {
+    log::info!("Pinned {:?}: {}", payload.kind, payload.id);
+    state
+        .server
+        .read()
+        .await
+        .broadcast(WsMessage::PinMessage { payload: payload.clone() })
+        .await;
+    payload
+}
+
+/// comment_logsに保存されたコメントを固定表示
+///
+/// ブロックリストに一致したコメントは固定できない
+#[tauri::command(rename_all = "snake_case")]
+pub async fn pin_comment(message_id: String, state: tauri::State<'_, AppState>) -> Result<PinPayload, String> {
+    let comment = get_comment_by_id(&state.db, &message_id)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?
+        .ok_or_else(|| format!("固定するコメントが見つかりません: {}", message_id))?;
+
+    let payload = PinPayload {
+        id: comment.id.clone(),
+        kind: PinKind::Comment,
+        text: comment.message.clone(),
+        comment: Some(comment),
+        pinned_at: chrono::Utc::now(),
+    };
+    Ok(broadcast_pin(&state, payload).await)
+}
+
+/// 任意の告知を固定表示（コメントに紐づかない）
+#[tauri::command]
+pub async fn announce(text: String, state: tauri::State<'_, AppState>) -> Result<PinPayload, String> {
+    let payload = PinPayload {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind: PinKind::Announcement,
+        text: normalize_announcement(&text)?,
+        comment: None,
+        pinned_at: chrono::Utc::now(),
+    };
+    Ok(broadcast_pin(&state, payload).await)
+}
+
+/// 固定表示を解除
+#[tauri::command]
+pub async fn unpin_message(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let server = state.server.read().await;
+    if server.pinned_message().is_some() {
+        log::info!("Unpinned message");
+        server.broadcast(WsMessage::UnpinMessage).await;
+    }
+    Ok(())
+}
+
+/// 現在の固定表示を取得（固定していない場合はNone）
+#[tauri::command]
+pub async fn get_pinned_message(state: tauri::State<'_, AppState>) -> Result<Option<PinPayload>, String> {
+    Ok(state.server.read().await.pinned_message())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_announcement() {
+        assert_eq!(normalize_announcement("  次は歌枠です  ").unwrap(), "次は歌枠です");
+        assert!(normalize_announcement("   ").is_err());
+        assert!(normalize_announcement(&"あ".repeat(MAX_ANNOUNCEMENT_CHARS)).is_ok());
+        assert!(normalize_announcement(&"あ".repeat(MAX_ANNOUNCEMENT_CHARS + 1)).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::db::models::{
     Setlist, SetlistSongWithDetails, SetlistWithSongs, Song, SongStatus,
 };
+use crate::db::play_log::{self, SongPlay};
 use crate::server::types::{SetlistUpdatePayload, SongItem, WsMessage};
 use crate::AppState;
 use chrono::Utc;
@@ -9,13 +10,19 @@ use std::collections::HashSet;
 use std::sync::Arc;
 use uuid::Uuid;
 
+/// 曲の長さの上限（秒）
+const MAX_DURATION_SECONDS: i64 = 24 * 60 * 60;
+
+/// キー（調）の最大文字数
+const MAX_SONG_KEY_CHARS: usize = 16;
+
 /// 楽曲一覧を取得
 #[tauri::command]
 pub async fn get_songs(state: tauri::State<'_, AppState>) -> Result<Vec<Song>, String> {
     let pool = &state.db;
     let songs = sqlx::query_as!(
         Song,
-        r#"SELECT id as "id!", title as "title!", artist, category, tags, duration_seconds, created_at as "created_at!", updated_at as "updated_at!" FROM songs ORDER BY created_at DESC"#
+        r#"SELECT id as "id!", title as "title!", artist, category, tags, duration_seconds, song_key, created_at as "created_at!", updated_at as "updated_at!" FROM songs ORDER BY created_at DESC"#
     )
     .fetch_all(pool)
     .await
@@ -32,6 +39,7 @@ pub async fn create_song(
     category: Option<String>,
     tags: Option<Vec<String>>,
     duration_seconds: Option<i64>,
+    song_key: Option<String>,
     state: tauri::State<'_, AppState>,
 ) -> Result<Song, String> {
     // 入力バリデーション
@@ -41,12 +49,15 @@ pub async fn create_song(
     if title.len() > 255 {
         return Err("Title is too long (max 255 characters)".to_string());
     }
+    validate_duration_seconds(duration_seconds)?;
+    let song_key = normalize_song_key(song_key)?;
 
     let pool = &state.db;
     let mut song = Song::new(title);
     song.artist = artist;
     song.category = category;
     song.duration_seconds = duration_seconds;
+    song.song_key = song_key;
 
     // tagsをJSON文字列に変換
     let tags_json = match tags {
@@ -56,28 +67,83 @@ pub async fn create_song(
         ),
         None => None,
     };
-    song.tags = tags_json.clone();
+    song.tags = tags_json;
+
+    insert_song(pool, &song).await?;
+
+    Ok(song)
+}
 
+/// 楽曲をDBに追加
+pub(crate) async fn insert_song<'e, E: sqlx::SqliteExecutor<'e>>(
+    executor: E,
+    song: &Song,
+) -> Result<(), String> {
     sqlx::query!(
-        "INSERT INTO songs (id, title, artist, category, tags, duration_seconds, created_at, updated_at)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        "INSERT INTO songs (id, title, artist, category, tags, duration_seconds, song_key, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
         song.id,
         song.title,
         song.artist,
         song.category,
-        tags_json,
+        song.tags,
         song.duration_seconds,
+        song.song_key,
         song.created_at,
         song.updated_at
     )
-    .execute(pool)
+    .execute(executor)
     .await
     .map_err(|e| e.to_string())?;
 
-    Ok(song)
+    Ok(())
+}
+
+/// IDを指定して楽曲を取得
+async fn fetch_song(pool: &sqlx::SqlitePool, id: &str) -> Result<Song, String> {
+    sqlx::query_as!(
+        Song,
+        r#"SELECT id as "id!", title as "title!", artist, category, tags, duration_seconds, song_key, created_at as "created_at!", updated_at as "updated_at!" FROM songs WHERE id = ?"#,
+        id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Song not found: {}", e))
+}
+
+/// 曲の長さ（秒）を検証
+///
+/// ## 入力検証
+/// - 0以上、24時間以下
+pub(crate) fn validate_duration_seconds(duration_seconds: Option<i64>) -> Result<(), String> {
+    match duration_seconds {
+        Some(seconds) if !(0..=MAX_DURATION_SECONDS).contains(&seconds) => Err(format!(
+            "Duration must be between 0 and {} seconds",
+            MAX_DURATION_SECONDS
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// キー（調）を検証して正規化（前後の空白を除去し、空文字はNone）
+///
+/// ## 入力検証
+/// - 最大16文字
+fn normalize_song_key(song_key: Option<String>) -> Result<Option<String>, String> {
+    let Some(key) = song_key.map(|key| key.trim().to_string()).filter(|key| !key.is_empty()) else {
+        return Ok(None);
+    };
+    if key.chars().count() > MAX_SONG_KEY_CHARS {
+        return Err(format!(
+            "Key is too long (max {} characters)",
+            MAX_SONG_KEY_CHARS
+        ));
+    }
+    Ok(Some(key))
 }
 
 /// 楽曲を更新
+#[allow(clippy::too_many_arguments)] // Tauriコマンドの引数はフロントエンドの入力項目に対応するため
 #[tauri::command(rename_all = "snake_case")]
 pub async fn update_song(
     id: String,
@@ -86,6 +152,7 @@ pub async fn update_song(
     category: Option<String>,
     tags: Option<Vec<String>>,
     duration_seconds: Option<i64>,
+    song_key: Option<String>,
     state: tauri::State<'_, AppState>,
 ) -> Result<Song, String> {
     // 入力バリデーション
@@ -97,6 +164,8 @@ pub async fn update_song(
             return Err("Title is too long (max 255 characters)".to_string());
         }
     }
+    validate_duration_seconds(duration_seconds)?;
+    let song_key = normalize_song_key(song_key)?;
 
     let pool = &state.db;
     let now = Utc::now().to_rfc3339();
@@ -119,6 +188,7 @@ pub async fn update_song(
              category = ?,
              tags = ?,
              duration_seconds = ?,
+             song_key = ?,
              updated_at = ?
          WHERE id = ?",
         title,
@@ -126,6 +196,7 @@ pub async fn update_song(
         category,
         tags_json,
         duration_seconds,
+        song_key,
         now,
         id
     )
@@ -134,16 +205,7 @@ pub async fn update_song(
     .map_err(|e| e.to_string())?;
 
     // 更新後の楽曲を取得
-    let song = sqlx::query_as!(
-        Song,
-        r#"SELECT id as "id!", title as "title!", artist, category, tags, duration_seconds, created_at as "created_at!", updated_at as "updated_at!" FROM songs WHERE id = ?"#,
-        id
-    )
-    .fetch_one(pool)
-    .await
-    .map_err(|e| format!("Song not found: {}", e))?;
-
-    Ok(song)
+    fetch_song(pool, &id).await
 }
 
 /// 楽曲を削除
@@ -364,7 +426,7 @@ pub async fn get_setlist_with_songs(
         r#"
         SELECT
             ss.id as "ss_id!", ss.position as "position!", ss.started_at, ss.ended_at,
-            s.id as "song_id!", s.title as "title!", s.artist, s.category, s.tags, s.duration_seconds,
+            s.id as "song_id!", s.title as "title!", s.artist, s.category, s.tags, s.duration_seconds, s.song_key,
             s.created_at as "song_created_at!", s.updated_at as "song_updated_at!"
         FROM setlist_songs ss
         JOIN songs s ON ss.song_id = s.id
@@ -397,6 +459,7 @@ pub async fn get_setlist_with_songs(
                 category: row.category,
                 tags: row.tags,
                 duration_seconds: row.duration_seconds,
+                song_key: row.song_key,
                 created_at: row.song_created_at,
                 updated_at: row.song_updated_at,
             };
@@ -437,14 +500,77 @@ pub async fn set_current_song(
     position: i64,
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
-    let pool = &state.db;
-    let now = Utc::now().to_rfc3339();
+    set_current_song_in_db(&state.db, &setlist_id, position).await?;
+
+    // WebSocketでセットリスト更新をブロードキャスト
+    broadcast_setlist_update_internal(setlist_id, &state).await
+}
+
+/// 曲順の何番目か（0始まり）を指定して現在の曲にジャンプ
+///
+/// リクエスト対応中などに任意の曲へ直接移動するためのコマンド。
+/// 現在の曲の扱い・ブロードキャストは`set_current_song`と同じ
+#[tauri::command(rename_all = "snake_case")]
+pub async fn jump_to_song_index(
+    setlist_id: String,
+    index: i64,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let position = song_position_at_index(&state.db, &setlist_id, index).await?;
+    set_current_song_in_db(&state.db, &setlist_id, position).await?;
+
+    // WebSocketでセットリスト更新をブロードキャスト
+    broadcast_setlist_update_internal(setlist_id, &state).await
+}
+
+/// 曲順の何番目か（0始まり）からpositionを取得
+///
+/// ## 入力検証
+/// - 0以上、セットリストの曲数未満
+async fn song_position_at_index(
+    pool: &sqlx::SqlitePool,
+    setlist_id: &str,
+    index: i64,
+) -> Result<i64, String> {
+    let count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM setlist_songs WHERE setlist_id = ?"
+    )
+    .bind(setlist_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if index < 0 || index >= count {
+        return Err(format!(
+            "曲の番号が範囲外です（index: {}, 曲数: {}）",
+            index, count
+        ));
+    }
+
+    sqlx::query_scalar(
+        "SELECT position FROM setlist_songs WHERE setlist_id = ? ORDER BY position LIMIT 1 OFFSET ?"
+    )
+    .bind(setlist_id)
+    .bind(index)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 指定位置の曲を現在の曲としてDBに記録
+async fn set_current_song_in_db(
+    pool: &sqlx::SqlitePool,
+    setlist_id: &str,
+    position: i64,
+) -> Result<(), String> {
+    let played_at = Utc::now();
+    let now = played_at.to_rfc3339();
 
     // 指定されたpositionが存在するか確認
     let exists: i64 = sqlx::query_scalar(
         "SELECT COUNT(*) FROM setlist_songs WHERE setlist_id = ? AND position = ?"
     )
-    .bind(&setlist_id)
+    .bind(setlist_id)
     .bind(position)
     .fetch_one(pool)
     .await
@@ -494,12 +620,12 @@ pub async fn set_current_song(
     .await
     .map_err(|e| e.to_string())?;
 
+    // 4. 歌唱履歴に記録
+    play_log::record_play(&mut tx, setlist_id, position, played_at).await?;
+
     // トランザクションコミット
     tx.commit().await.map_err(|e| e.to_string())?;
 
-    // WebSocketでセットリスト更新をブロードキャスト
-    broadcast_setlist_update_internal(setlist_id, &state).await?;
-
     Ok(())
 }
 
@@ -527,7 +653,8 @@ pub async fn next_song(
     };
 
     // 単一トランザクション内で全ての更新を実行
-    let now = Utc::now().to_rfc3339();
+    let played_at = Utc::now();
+    let now = played_at.to_rfc3339();
     let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
 
     // 次の曲が存在するか確認
@@ -581,6 +708,9 @@ pub async fn next_song(
     .await
     .map_err(|e| e.to_string())?;
 
+    // 4. 歌唱履歴に記録
+    play_log::record_play(&mut tx, &setlist_id, next_position, played_at).await?;
+
     // 全ての更新を単一トランザクションでコミット
     tx.commit().await.map_err(|e| e.to_string())?;
 
@@ -612,7 +742,8 @@ pub async fn previous_song(
         Some(pos) if pos > 0 => {
             // 前の曲が存在する場合
             // 単一トランザクション内で全ての更新を実行
-            let now = Utc::now().to_rfc3339();
+            let played_at = Utc::now();
+            let now = played_at.to_rfc3339();
             let prev_pos = pos - 1;
             let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
 
@@ -653,6 +784,9 @@ pub async fn previous_song(
             .await
             .map_err(|e| e.to_string())?;
 
+            // 4. 歌唱履歴に記録
+            play_log::record_play(&mut tx, &setlist_id, prev_pos, played_at).await?;
+
             // 全ての更新を単一トランザクションでコミット
             tx.commit().await.map_err(|e| e.to_string())?;
 
@@ -665,26 +799,83 @@ pub async fn previous_song(
     }
 }
 
+/// 歌唱履歴（曲が現在の曲になった記録）を古い順に取得
+///
+/// `from`以上・`to`未満（RFC3339）の記録を返す。省略した側は制限しない
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_play_history(
+    from: Option<String>,
+    to: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<SongPlay>, String> {
+    let from = parse_history_time(from.as_deref())?;
+    let to = parse_history_time(to.as_deref())?;
+    if let (Some(from), Some(to)) = (from, to) {
+        if from >= to {
+            return Err("期間の開始は終了より前を指定してください".to_string());
+        }
+    }
+    play_log::load_history(&state.db, from, to).await
+}
+
+/// 履歴の期間指定（RFC3339）を解析（未指定・空文字はNone）
+fn parse_history_time(value: Option<&str>) -> Result<Option<chrono::DateTime<Utc>>, String> {
+    match value.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(value) => chrono::DateTime::parse_from_rfc3339(value)
+            .map(|time| Some(time.with_timezone(&Utc)))
+            .map_err(|e| format!("日時の形式が正しくありません（{}）: {}", value, e)),
+        None => Ok(None),
+    }
+}
+
 /// セットリスト内の曲順を並び替え
+///
+/// `setlist_song_ids`はセットリストの全曲（setlist_songsのID）を並び替え後の順に指定する。
+/// 並び替え後の曲順（0始まりの連続したposition順）を返し、オーバーレイへブロードキャストする
 #[tauri::command(rename_all = "snake_case")]
 pub async fn reorder_setlist_songs(
     setlist_id: String,
     setlist_song_ids: Vec<String>,
     state: tauri::State<'_, AppState>,
-) -> Result<(), String> {
-    let pool = &state.db;
+) -> Result<Vec<String>, String> {
+    let ordered_ids = reorder_setlist_songs_in_db(&state.db, &setlist_id, &setlist_song_ids).await?;
 
+    // WebSocketでセットリスト更新をブロードキャスト
+    broadcast_setlist_update_internal(setlist_id, &state).await?;
+
+    Ok(ordered_ids)
+}
+
+/// セットリスト内の曲順をトランザクション内で並び替え、並び替え後の曲順を返す
+///
+/// ## 検証
+/// - 指定したIDがセットリストの現在の曲の並べ替え（過不足・重複なし）であること
+/// - 更新後のpositionが0始まりで連続していること（満たさない場合はロールバック）
+async fn reorder_setlist_songs_in_db(
+    pool: &sqlx::SqlitePool,
+    setlist_id: &str,
+    setlist_song_ids: &[String],
+) -> Result<Vec<String>, String> {
     // 入力バリデーション：空配列チェック
     if setlist_song_ids.is_empty() {
         return Err("曲IDリストが空です".to_string());
     }
 
+    // 入力バリデーション：重複チェック
+    let passed_set: HashSet<&String> = setlist_song_ids.iter().collect();
+    if passed_set.len() != setlist_song_ids.len() {
+        return Err("曲IDが重複しています".to_string());
+    }
+
+    // トランザクション開始（検証から更新までの間に曲が追加・削除されないようにする）
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
     // 入力バリデーション：セットリストの実際の曲IDリストを取得
     let actual_ids: Vec<String> = sqlx::query_scalar(
         "SELECT id FROM setlist_songs WHERE setlist_id = ? ORDER BY position"
     )
-    .bind(&setlist_id)
-    .fetch_all(pool)
+    .bind(setlist_id)
+    .fetch_all(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
 
@@ -694,8 +885,8 @@ pub async fn reorder_setlist_songs(
         let setlist_exists: i64 = sqlx::query_scalar(
             "SELECT COUNT(*) FROM setlists WHERE id = ?"
         )
-        .bind(&setlist_id)
-        .fetch_one(pool)
+        .bind(setlist_id)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| e.to_string())?;
 
@@ -705,26 +896,17 @@ pub async fn reorder_setlist_songs(
         return Err("セットリストに曲がありません".to_string());
     }
 
-    // 曲数チェック
-    if actual_ids.len() != setlist_song_ids.len() {
+    // IDの所属確認：渡されたIDがセットリストの曲と過不足なく一致するかチェック
+    let actual_set: HashSet<&String> = actual_ids.iter().collect();
+    let missing = actual_set.difference(&passed_set).count();
+    let unknown = passed_set.difference(&actual_set).count();
+    if missing > 0 || unknown > 0 {
         return Err(format!(
-            "曲数が一致しません（期待: {}, 実際: {}）",
-            actual_ids.len(),
-            setlist_song_ids.len()
+            "曲IDリストがセットリストの曲と一致しません（不足: {}件, 無効なID: {}件）",
+            missing, unknown
         ));
     }
 
-    // IDの所属確認：渡されたIDがすべてこのセットリストに属しているかチェック
-    let passed_set: HashSet<_> = setlist_song_ids.iter().collect();
-    let actual_set: HashSet<_> = actual_ids.iter().collect();
-
-    if passed_set != actual_set {
-        return Err("無効な曲IDが含まれています".to_string());
-    }
-
-    // トランザクション開始
-    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
-
     // 2フェーズ更新でユニーク制約違反を回避
     // Phase 1: 一時的なオフセット値に移動（既存のpositionと重複しないように）
     let offset = 10000i64;
@@ -757,6 +939,23 @@ pub async fn reorder_setlist_songs(
         .map_err(|e| e.to_string())?;
     }
 
+    // 更新後の曲順を検証（positionが0始まりで連続し、指定した順序と一致すること）
+    let ordered: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT id, position FROM setlist_songs WHERE setlist_id = ? ORDER BY position"
+    )
+    .bind(setlist_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let contiguous = ordered
+        .iter()
+        .enumerate()
+        .all(|(index, (id, position))| *position == index as i64 && *id == setlist_song_ids[index]);
+    if ordered.len() != setlist_song_ids.len() || !contiguous {
+        // コミットせずに戻るとロールバックされる
+        return Err("曲順の更新に失敗しました（positionが連続していません）".to_string());
+    }
+
     // セットリストのupdated_atを更新
     let now = Utc::now().to_rfc3339();
     sqlx::query!(
@@ -770,10 +969,7 @@ pub async fn reorder_setlist_songs(
     // コミット
     tx.commit().await.map_err(|e| e.to_string())?;
 
-    // WebSocketでセットリスト更新をブロードキャスト
-    broadcast_setlist_update_internal(setlist_id, &state).await?;
-
-    Ok(())
+    Ok(ordered.into_iter().map(|(id, _)| id).collect())
 }
 
 /// セットリスト更新をWebSocketでブロードキャスト（公開コマンド）
@@ -795,7 +991,7 @@ pub async fn broadcast_setlist_update(
 /// - 呼び出し元はブロードキャスト完了を待たずに即座に`Ok(())`を返す
 /// - ブロードキャスト失敗はログ出力のみで、呼び出し元のコマンド成功には影響しない
 /// - これにより、WebSocket接続がない場合でもコマンド自体は正常に完了する
-async fn broadcast_setlist_update_internal(
+pub(crate) async fn broadcast_setlist_update_internal(
     setlist_id: String,
     state: &tauri::State<'_, AppState>,
 ) -> Result<(), String> {
@@ -822,6 +1018,8 @@ async fn broadcast_setlist_update_internal(
                     title: song_detail.song.title,
                     artist: song_detail.song.artist.unwrap_or_default(),
                     status,
+                    duration_seconds: song_detail.song.duration_seconds,
+                    song_key: song_detail.song.song_key,
                 }
             })
             .collect(),
@@ -840,3 +1038,126 @@ async fn broadcast_setlist_update_internal(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::create_test_setlist;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_song_metadata_round_trip() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let db = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        let mut song = Song::new("Song".to_string());
+        song.artist = Some("Artist".to_string());
+        song.duration_seconds = Some(245);
+        song.song_key = normalize_song_key(Some(" F#m ".to_string())).unwrap();
+        insert_song(&db, &song).await.unwrap();
+
+        let saved = fetch_song(&db, &song.id).await.unwrap();
+        assert_eq!(saved.artist.as_deref(), Some("Artist"));
+        assert_eq!(saved.duration_seconds, Some(245));
+        assert_eq!(saved.song_key.as_deref(), Some("F#m"));
+
+        // 未設定の項目はnullのまま
+        let plain = Song::new("Plain".to_string());
+        insert_song(&db, &plain).await.unwrap();
+        let saved = fetch_song(&db, &plain.id).await.unwrap();
+        assert_eq!((saved.artist, saved.duration_seconds, saved.song_key), (None, None, None));
+    }
+
+    #[test]
+    fn test_song_metadata_validation() {
+        assert_eq!(normalize_song_key(Some("  ".to_string())), Ok(None));
+        assert!(normalize_song_key(Some("C".repeat(MAX_SONG_KEY_CHARS + 1))).is_err());
+        assert!(validate_duration_seconds(Some(-1)).is_err());
+        assert!(validate_duration_seconds(Some(MAX_DURATION_SECONDS + 1)).is_err());
+        assert!(validate_duration_seconds(None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_reorder_setlist_songs_renumbers_positions() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let db = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        create_test_setlist(&db, 3).await;
+
+        let ordered = reorder_setlist_songs_in_db(&db, "setlist1", &ids(&["ss2", "ss0", "ss1"]))
+            .await
+            .unwrap();
+        assert_eq!(ordered, ids(&["ss2", "ss0", "ss1"]));
+
+        let positions: Vec<(String, i64)> =
+            sqlx::query_as("SELECT id, position FROM setlist_songs WHERE setlist_id = 'setlist1' ORDER BY position")
+                .fetch_all(&db)
+                .await
+                .unwrap();
+        assert_eq!(
+            positions,
+            vec![("ss2".to_string(), 0), ("ss0".to_string(), 1), ("ss1".to_string(), 2)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_jump_to_song_index() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let db = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        create_test_setlist(&db, 3).await;
+        reorder_setlist_songs_in_db(&db, "setlist1", &ids(&["ss2", "ss0", "ss1"]))
+            .await
+            .unwrap();
+
+        // 範囲外は拒否
+        assert!(song_position_at_index(&db, "setlist1", -1).await.is_err());
+        assert!(song_position_at_index(&db, "setlist1", 3).await.is_err());
+        assert!(song_position_at_index(&db, "missing", 0).await.is_err());
+
+        // 2番目（0始まりで1）にジャンプすると、その曲だけが再生中になる
+        let position = song_position_at_index(&db, "setlist1", 1).await.unwrap();
+        set_current_song_in_db(&db, "setlist1", position).await.unwrap();
+        let playing: Vec<String> = sqlx::query_scalar(
+            "SELECT id FROM setlist_songs WHERE setlist_id = 'setlist1' AND started_at IS NOT NULL AND ended_at IS NULL",
+        )
+        .fetch_all(&db)
+        .await
+        .unwrap();
+        assert_eq!(playing, ids(&["ss0"]));
+    }
+
+    #[tokio::test]
+    async fn test_reorder_setlist_songs_rejects_non_permutation() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let db = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        create_test_setlist(&db, 3).await;
+
+        // 不足・余分・重複はいずれも拒否し、曲順は変わらない
+        for invalid in [
+            ids(&["ss0", "ss1"]),
+            ids(&["ss0", "ss1", "ss2", "other"]),
+            ids(&["ss0", "ss1", "other"]),
+            ids(&["ss0", "ss0", "ss1"]),
+        ] {
+            assert!(reorder_setlist_songs_in_db(&db, "setlist1", &invalid).await.is_err());
+        }
+        assert!(reorder_setlist_songs_in_db(&db, "missing", &ids(&["ss0"])).await.is_err());
+
+        let order: Vec<String> =
+            sqlx::query_scalar("SELECT id FROM setlist_songs WHERE setlist_id = 'setlist1' ORDER BY position")
+                .fetch_all(&db)
+                .await
+                .unwrap();
+        assert_eq!(order, ids(&["ss0", "ss1", "ss2"]));
+    }
+}
@@ -0,0 +1,421 @@
+//! 楽曲のインポートコマンド
+//!
+//! 1行1曲のテキスト / CSV（`タイトル, アーティスト, 曲の長さ`）から楽曲をまとめて登録する。
+//! 登録済みの楽曲（タイトル・アーティストが一致）は作成せずに再利用し、
+//! セットリスト名を指定した場合は行の順にセットリストの末尾へ追加する。
+//! 不正な行は取り込まずにエラー一覧へ集め、残りの行のインポートは続ける。
+
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use super::setlist::{broadcast_setlist_update_internal, insert_song, validate_duration_seconds};
+use crate::db::models::{Setlist, Song};
+use crate::AppState;
+
+/// 1回のインポートで取り込める最大行数
+const MAX_IMPORT_ROWS: usize = 500;
+
+/// 1行の最大列数（タイトル, アーティスト, 曲の長さ）
+const MAX_IMPORT_COLUMNS: usize = 3;
+
+/// ヘッダー行とみなす1列目の値
+const HEADER_TITLES: &[&str] = &["title", "タイトル", "曲名"];
+
+/// インポート結果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SongImportResult {
+    /// 新規作成した楽曲数
+    pub created: usize,
+    /// 登録済みのため作成しなかった楽曲数
+    pub skipped: usize,
+    /// セットリストに追加した曲数
+    pub added_to_setlist: usize,
+    /// 追加先のセットリストID（セットリスト名を指定した場合）
+    pub setlist_id: Option<String>,
+    /// 取り込まなかった行のエラー
+    pub errors: Vec<SongImportError>,
+}
+
+/// 取り込まなかった行のエラー
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SongImportError {
+    /// 行番号（1始まり）
+    pub line: usize,
+    pub message: String,
+}
+
+/// 解析済みの1行
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ImportRow {
+    title: String,
+    artist: Option<String>,
+    duration_seconds: Option<i64>,
+}
+
+/// テキスト / CSVから楽曲をインポート
+///
+/// - 1行1曲: `タイトル[, アーティスト[, 曲の長さ]]`（カンマを含む値はダブルクォートで囲む）
+/// - 曲の長さは秒数、`分:秒`、`時:分:秒`のいずれか
+/// - 空行・`#`で始まる行・1行目のヘッダー行は読み飛ばす
+/// - `setlist_name`を指定した場合は同名のセットリスト（なければ作成）の末尾に行の順で追加する
+///
+/// ## 入力検証
+/// - 取り込む行は最大500行
+#[tauri::command(rename_all = "snake_case")]
+pub async fn import_songs(
+    text: String,
+    setlist_name: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<SongImportResult, String> {
+    let setlist_name = setlist_name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+    if setlist_name.as_ref().is_some_and(|name| name.len() > 255) {
+        return Err("Name is too long (max 255 characters)".to_string());
+    }
+
+    let (rows, errors) = parse_import_text(&text);
+    if rows.len() > MAX_IMPORT_ROWS {
+        return Err(format!(
+            "一度にインポートできるのは{}行までです: {}行",
+            MAX_IMPORT_ROWS,
+            rows.len()
+        ));
+    }
+
+    let mut result = import_rows(&state.db, &rows, setlist_name.as_deref()).await?;
+    result.errors = errors;
+
+    if let Some(setlist_id) = result.setlist_id.clone().filter(|_| result.added_to_setlist > 0) {
+        // WebSocketでセットリスト更新をブロードキャスト
+        broadcast_setlist_update_internal(setlist_id, &state).await?;
+    }
+
+    Ok(result)
+}
+
+/// 楽曲の作成とセットリストへの追加を1トランザクションで実行
+async fn import_rows(
+    pool: &SqlitePool,
+    rows: &[ImportRow],
+    setlist_name: Option<&str>,
+) -> Result<SongImportResult, String> {
+    let mut result = SongImportResult::default();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    // 追加先のセットリスト（同名が複数ある場合は最初に作成したもの）
+    if let Some(name) = setlist_name {
+        let existing: Option<String> =
+            sqlx::query_scalar("SELECT id FROM setlists WHERE name = ? ORDER BY created_at LIMIT 1")
+                .bind(name)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+        let setlist_id = match existing {
+            Some(id) => id,
+            None => {
+                let setlist = Setlist::new(name.to_string(), None);
+                sqlx::query(
+                    "INSERT INTO setlists (id, name, description, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+                )
+                .bind(&setlist.id)
+                .bind(&setlist.name)
+                .bind(&setlist.description)
+                .bind(&setlist.created_at)
+                .bind(&setlist.updated_at)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+                setlist.id
+            }
+        };
+        result.setlist_id = Some(setlist_id);
+    }
+
+    let mut next_position: i64 = match &result.setlist_id {
+        Some(setlist_id) => {
+            let max_position: Option<i64> =
+                sqlx::query_scalar("SELECT MAX(position) FROM setlist_songs WHERE setlist_id = ?")
+                    .bind(setlist_id)
+                    .fetch_one(&mut *tx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            max_position.unwrap_or(-1) + 1
+        }
+        None => 0,
+    };
+
+    for row in rows {
+        // タイトル・アーティストが一致する楽曲は作成せずに再利用（アーティスト未設定同士も一致とみなす）
+        let existing: Option<String> =
+            sqlx::query_scalar("SELECT id FROM songs WHERE title = ? AND artist IS ? ORDER BY created_at LIMIT 1")
+                .bind(&row.title)
+                .bind(&row.artist)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+        let song_id = match existing {
+            Some(id) => {
+                result.skipped += 1;
+                id
+            }
+            None => {
+                let mut song = Song::new(row.title.clone());
+                song.artist = row.artist.clone();
+                song.duration_seconds = row.duration_seconds;
+                insert_song(&mut *tx, &song).await?;
+                result.created += 1;
+                song.id
+            }
+        };
+
+        if let Some(setlist_id) = &result.setlist_id {
+            sqlx::query("INSERT INTO setlist_songs (id, setlist_id, song_id, position) VALUES (?, ?, ?, ?)")
+                .bind(Uuid::new_v4().to_string())
+                .bind(setlist_id)
+                .bind(&song_id)
+                .bind(next_position)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+            next_position += 1;
+            result.added_to_setlist += 1;
+        }
+    }
+
+    if let Some(setlist_id) = &result.setlist_id {
+        sqlx::query("UPDATE setlists SET updated_at = ? WHERE id = ?")
+            .bind(Utc::now().to_rfc3339())
+            .bind(setlist_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+/// インポートするテキストを行ごとに解析
+///
+/// 不正な行は行番号付きのエラーとして集め、残りの行の解析を続ける
+fn parse_import_text(text: &str) -> (Vec<ImportRow>, Vec<SongImportError>) {
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    let mut first_row = true;
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let is_first_row = std::mem::replace(&mut first_row, false);
+
+        let parsed = split_csv_line(line).and_then(|fields| {
+            // 1行目のヘッダー行は読み飛ばす
+            if is_first_row
+                && HEADER_TITLES
+                    .iter()
+                    .any(|header| header.eq_ignore_ascii_case(&fields[0]))
+            {
+                return Ok(None);
+            }
+            parse_row(fields).map(Some)
+        });
+        match parsed {
+            Ok(Some(row)) => rows.push(row),
+            Ok(None) => {}
+            Err(message) => errors.push(SongImportError {
+                line: index + 1,
+                message,
+            }),
+        }
+    }
+
+    (rows, errors)
+}
+
+/// 1行分の列から楽曲を作成
+fn parse_row(fields: Vec<String>) -> Result<ImportRow, String> {
+    if fields.len() > MAX_IMPORT_COLUMNS {
+        return Err(format!(
+            "列が多すぎます（タイトル, アーティスト, 曲の長さの{}列まで）",
+            MAX_IMPORT_COLUMNS
+        ));
+    }
+    let mut fields = fields.into_iter();
+
+    let title = fields.next().unwrap_or_default();
+    if title.is_empty() {
+        return Err("Title cannot be empty".to_string());
+    }
+    if title.len() > 255 {
+        return Err("Title is too long (max 255 characters)".to_string());
+    }
+    let artist = fields.next().filter(|artist| !artist.is_empty());
+    let duration_seconds = match fields.next().filter(|duration| !duration.is_empty()) {
+        Some(duration) => Some(parse_duration(&duration)?),
+        None => None,
+    };
+    validate_duration_seconds(duration_seconds)?;
+
+    Ok(ImportRow {
+        title,
+        artist,
+        duration_seconds,
+    })
+}
+
+/// 曲の長さ（秒数、`分:秒`、`時:分:秒`）を秒に変換
+fn parse_duration(value: &str) -> Result<i64, String> {
+    let invalid = || format!("曲の長さの形式が正しくありません: {}", value);
+    let parts: Vec<&str> = value.split(':').collect();
+    if parts.len() > 3 {
+        return Err(invalid());
+    }
+    let mut seconds: i64 = 0;
+    for (index, part) in parts.iter().enumerate() {
+        let number: i64 = part.trim().parse().map_err(|_| invalid())?;
+        // 先頭以外（分・秒）は0〜59
+        if number < 0 || (index > 0 && number >= 60) {
+            return Err(invalid());
+        }
+        seconds = seconds.checked_mul(60).and_then(|s| s.checked_add(number)).ok_or_else(invalid)?;
+    }
+    Ok(seconds)
+}
+
+/// CSVの1行を列に分割（前後の空白を除去）
+///
+/// ダブルクォートで囲んだ列はカンマを含められ、`""`はダブルクォート1文字になる
+fn split_csv_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut in_quotes = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err("ダブルクォートが閉じられていません".to_string());
+    }
+    fields.push(field.trim().to_string());
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(title: &str, artist: Option<&str>, duration_seconds: Option<i64>) -> ImportRow {
+        ImportRow {
+            title: title.to_string(),
+            artist: artist.map(str::to_string),
+            duration_seconds,
+        }
+    }
+
+    #[test]
+    fn test_split_csv_line() {
+        assert_eq!(split_csv_line("Song, Artist, 4:05").unwrap(), vec!["Song", "Artist", "4:05"]);
+        assert_eq!(
+            split_csv_line(r#""Hello, World", "say ""hi""""#).unwrap(),
+            vec!["Hello, World", r#"say "hi""#]
+        );
+        assert_eq!(split_csv_line("Song").unwrap(), vec!["Song"]);
+        assert!(split_csv_line(r#""unterminated, Artist"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("245"), Ok(245));
+        assert_eq!(parse_duration("4:05"), Ok(245));
+        assert_eq!(parse_duration("1:02:03"), Ok(3723));
+        assert!(parse_duration("4:60").is_err());
+        assert!(parse_duration("abc").is_err());
+        assert!(parse_duration("1:2:3:4").is_err());
+    }
+
+    #[test]
+    fn test_parse_import_text_collects_errors() {
+        let text = "タイトル,アーティスト,長さ\n\
+                    Song A, Artist A, 3:30\n\
+                    \n\
+                    # コメント\n\
+                    Song B\n\
+                    , Artist only\n\
+                    Song C, Artist C, 4:99\n\
+                    Song D, Artist D, 1:00, extra\n\
+                    \"Song, E\",,200\n";
+        let (rows, errors) = parse_import_text(text);
+        assert_eq!(
+            rows,
+            vec![
+                row("Song A", Some("Artist A"), Some(210)),
+                row("Song B", None, None),
+                row("Song, E", None, Some(200)),
+            ]
+        );
+        let lines: Vec<usize> = errors.iter().map(|error| error.line).collect();
+        assert_eq!(lines, vec![6, 7, 8]);
+    }
+
+    #[tokio::test]
+    async fn test_import_rows_dedupes_and_appends_to_setlist() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let pool = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        let rows = vec![
+            row("Song A", Some("Artist A"), Some(210)),
+            row("Song B", None, None),
+            row("Song A", Some("Artist A"), None),
+        ];
+        let result = import_rows(&pool, &rows, Some("Live")).await.unwrap();
+        assert_eq!((result.created, result.skipped, result.added_to_setlist), (2, 1, 3));
+
+        // 2回目は全て登録済み。既存のセットリストの末尾に追加する
+        let result2 = import_rows(&pool, &rows[..2], Some("Live")).await.unwrap();
+        assert_eq!((result2.created, result2.skipped, result2.added_to_setlist), (0, 2, 2));
+        assert_eq!(result2.setlist_id, result.setlist_id);
+
+        let titles: Vec<String> = sqlx::query_scalar(
+            "SELECT s.title FROM setlist_songs ss JOIN songs s ON ss.song_id = s.id WHERE ss.setlist_id = ? ORDER BY ss.position",
+        )
+        .bind(result.setlist_id.as_deref())
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(titles, vec!["Song A", "Song B", "Song A", "Song A", "Song B"]);
+
+        // セットリスト名を指定しない場合は楽曲の登録のみ
+        let result3 = import_rows(&pool, &[row("Song C", None, None)], None).await.unwrap();
+        assert_eq!((result3.created, result3.added_to_setlist, result3.setlist_id), (1, 0, None));
+        let song_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM songs")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(song_count, 3);
+    }
+}
@@ -0,0 +1,381 @@
+//! スパチャ表示設定コマンド
+//!
+//! Tier別の表示時間・Tier判定閾値・表示先slot・目標金額の設定・取得と、閾値のチェック、表示キューの状態取得を提供する。
+//! データはDBのsettingsテーブルに保存される。
+
+use sqlx::SqlitePool;
+
+use crate::server::types::{default_superchat_slot, SlotId};
+use crate::superchat::goal::{self, SuperchatGoal, SuperchatGoalStatus};
+use crate::superchat::{self, ConfigWarning, SuperchatConfig, TierThresholds};
+use crate::AppState;
+
+/// スパチャ表示設定の保存キー
+const SUPERCHAT_CONFIG_KEY: &str = "superchat_config";
+
+/// Tier判定閾値の保存キー
+const TIER_THRESHOLDS_KEY: &str = "superchat_tier_thresholds";
+
+/// スパチャウィジェット表示先slotの保存キー
+const SUPERCHAT_SLOT_KEY: &str = "superchat_slot";
+
+/// 保存済みのスパチャ表示設定をDBから読み込み
+///
+/// 未保存・JSON破損・範囲外の値の場合はデフォルト設定を返す。
+/// 起動時の設定反映にも使用する。
+pub async fn load_superchat_config(pool: &SqlitePool) -> Result<SuperchatConfig, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(SUPERCHAT_CONFIG_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    let Some((json_str,)) = result else {
+        return Ok(SuperchatConfig::default());
+    };
+
+    match serde_json::from_str::<SuperchatConfig>(&json_str) {
+        Ok(config) => match config.validate() {
+            Ok(()) => Ok(config),
+            Err(e) => {
+                log::warn!("Stored superchat config is invalid, falling back to default: {}", e);
+                Ok(SuperchatConfig::default())
+            }
+        },
+        Err(e) => {
+            log::warn!(
+                "Superchat config JSON corrupted, falling back to default. Error: {}",
+                e
+            );
+            Ok(SuperchatConfig::default())
+        }
+    }
+}
+
+/// Tier別の表示時間・投稿者クールダウンを保存
+///
+/// ## 入力検証
+/// - 各Tierの表示時間: 1秒〜30分（ミリ秒で指定）
+/// - 投稿者クールダウン: 0（無効）〜10分（ミリ秒で指定）
+///
+/// 保存後は以降に受信したスパチャから新しい設定が適用される
+#[tauri::command]
+pub async fn set_superchat_durations(
+    config: SuperchatConfig,
+    state: tauri::State<'_, AppState>,
+) -> Result<SuperchatConfig, String> {
+    config.validate()?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let json_str =
+        serde_json::to_string(&config).map_err(|e| format!("JSON serialize error: {}", e))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(SUPERCHAT_CONFIG_KEY)
+    .bind(&json_str)
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    superchat::set_config(config.clone());
+    log::info!(
+        "Superchat durations saved: {:?} (author cooldown: {}ms)",
+        config.tier_durations_ms,
+        config.author_cooldown_ms
+    );
+
+    // 表示中のスパチャを再送してオーバーレイの表示を揃える（削除タイマーはそのまま）
+    superchat::rebroadcast_current_superchat(&state.server).await;
+    Ok(config)
+}
+
+/// Tier別の表示時間・投稿者クールダウンを取得
+#[tauri::command]
+pub async fn get_superchat_durations() -> Result<SuperchatConfig, String> {
+    Ok(superchat::get_config())
+}
+
+/// 保存済みのTier判定閾値をDBから読み込み
+///
+/// 未保存・JSON破損・不正な値の場合はNone（デフォルト値を使用）を返す。
+/// 起動時の設定反映にも使用する。
+pub async fn load_tier_thresholds(pool: &SqlitePool) -> Result<Option<TierThresholds>, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(TIER_THRESHOLDS_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    let Some((json_str,)) = result else {
+        return Ok(None);
+    };
+
+    match serde_json::from_str::<TierThresholds>(&json_str) {
+        Ok(thresholds) => match superchat::validate_tier_thresholds(&thresholds) {
+            Ok(()) => Ok(Some(thresholds)),
+            Err(e) => {
+                log::warn!("Stored tier thresholds are invalid, falling back to default: {}", e);
+                Ok(None)
+            }
+        },
+        Err(e) => {
+            log::warn!(
+                "Tier thresholds JSON corrupted, falling back to default. Error: {}",
+                e
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Tier判定の閾値を保存
+///
+/// ## 入力検証
+/// - `(日本円換算の下限額, Tier)`のリストを金額の厳密な降順で指定
+/// - Tier 1〜7をそれぞれ1回ずつ含むこと
+///
+/// 空のリストを渡すとデフォルト（YouTube公式の金額帯）に戻す。
+/// 表示中のスパチャは新しい閾値のTierで再送する。
+#[tauri::command]
+pub async fn set_tier_thresholds(
+    thresholds: TierThresholds,
+    state: tauri::State<'_, AppState>,
+) -> Result<TierThresholds, String> {
+    if thresholds.is_empty() {
+        sqlx::query("DELETE FROM settings WHERE key = ?")
+            .bind(TIER_THRESHOLDS_KEY)
+            .execute(&state.db)
+            .await
+            .map_err(|e| format!("DB error: {}", e))?;
+
+        superchat::set_tier_thresholds(None);
+        log::info!("Tier thresholds reset to default");
+        superchat::rebroadcast_current_superchat(&state.server).await;
+        return Ok(superchat::get_tier_thresholds());
+    }
+
+    superchat::validate_tier_thresholds(&thresholds)?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let json_str =
+        serde_json::to_string(&thresholds).map_err(|e| format!("JSON serialize error: {}", e))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(TIER_THRESHOLDS_KEY)
+    .bind(&json_str)
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    superchat::set_tier_thresholds(Some(thresholds.clone()));
+    log::info!("Tier thresholds saved: {:?}", thresholds);
+
+    // 表示中のスパチャを新しい閾値のTierで再表示（削除タイマーはそのまま）
+    superchat::rebroadcast_current_superchat(&state.server).await;
+    Ok(thresholds)
+}
+
+/// 現在有効なTier判定の閾値を取得
+#[tauri::command]
+pub async fn get_tier_thresholds() -> Result<TierThresholds, String> {
+    Ok(superchat::get_tier_thresholds())
+}
+
+/// Tier判定の閾値をYouTube公式の金額帯と比較してチェック
+///
+/// `thresholds`を省略した場合は現在有効な閾値をチェックする。
+/// 編集中の閾値を渡せば、保存前に順序の逆転や公式の金額帯からの乖離を確認できる。
+#[tauri::command]
+pub async fn validate_superchat_config(
+    thresholds: Option<TierThresholds>,
+) -> Result<Vec<ConfigWarning>, String> {
+    let thresholds = thresholds.unwrap_or_else(superchat::get_tier_thresholds);
+    Ok(superchat::check_tier_thresholds(&thresholds))
+}
+
+/// 保存済みのスパチャウィジェット表示先slotをDBから読み込み
+///
+/// 未保存・不正な値の場合はデフォルト（left.lower）を返す。
+/// 起動時の設定反映にも使用する。
+pub async fn load_superchat_slot(pool: &SqlitePool) -> Result<SlotId, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(SUPERCHAT_SLOT_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    let Some((json_str,)) = result else {
+        return Ok(default_superchat_slot());
+    };
+
+    match serde_json::from_str::<SlotId>(&json_str) {
+        Ok(slot) => Ok(slot),
+        Err(e) => {
+            log::warn!(
+                "Stored superchat slot is invalid, falling back to default. Error: {}",
+                e
+            );
+            Ok(default_superchat_slot())
+        }
+    }
+}
+
+/// スパチャウィジェット表示先slotをDBに保存
+async fn save_superchat_slot(pool: &SqlitePool, slot: SlotId) -> Result<(), String> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let json_str =
+        serde_json::to_string(&slot).map_err(|e| format!("JSON serialize error: {}", e))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(SUPERCHAT_SLOT_KEY)
+    .bind(&json_str)
+    .bind(&now)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    Ok(())
+}
+
+/// スパチャウィジェットの表示先slotを保存
+///
+/// slotは既知のslot ID（"left.lower"等）のみ受け付ける。
+/// 保存後は以降に受信したスパチャのペイロードに新しいslotが含まれ、
+/// オーバーレイ側でウィジェットが移動される。
+/// slotが変わった場合は表示中のスパチャも残りの表示時間で再送する。
+#[tauri::command]
+pub async fn set_superchat_slot(
+    slot: SlotId,
+    state: tauri::State<'_, AppState>,
+) -> Result<SlotId, String> {
+    save_superchat_slot(&state.db, slot).await?;
+
+    let changed = superchat::get_slot() != slot;
+    superchat::set_slot(slot);
+    log::info!("Superchat slot saved: {:?}", slot);
+
+    // 表示中のスパチャを新しいslotで再表示（削除タイマーはそのまま）
+    if changed {
+        superchat::rebroadcast_current_superchat(&state.server).await;
+    }
+    Ok(slot)
+}
+
+/// スパチャウィジェットの表示先slotを取得
+#[tauri::command]
+pub async fn get_superchat_slot() -> Result<SlotId, String> {
+    Ok(superchat::get_slot())
+}
+
+/// スパチャの目標金額を保存し、集計開始時刻以降の合計を集計し直して`goal:update`を配信
+///
+/// ## 入力検証
+/// - 目標金額は1〜100,000,000円、ラベルは50文字以内
+/// - メンバーシップ1件あたりの金額は100,000円以下
+///
+/// 集計開始時刻を省略した場合は保存した時刻から集計する
+#[tauri::command]
+pub async fn set_superchat_goal(
+    goal: SuperchatGoal,
+    state: tauri::State<'_, AppState>,
+) -> Result<SuperchatGoalStatus, String> {
+    let goal = goal.normalize()?;
+    goal::save_goal(&state.db, &goal).await?;
+    let status = goal::apply_goal(&state.db, &state.server, goal).await?;
+    log::info!(
+        "Superchat goal saved: {:?} (current: {} JPY)",
+        status.goal,
+        status.current_jpy
+    );
+    Ok(status)
+}
+
+/// スパチャの目標金額の設定と現在の合計を取得
+#[tauri::command]
+pub async fn get_superchat_goal() -> Result<SuperchatGoalStatus, String> {
+    Ok(goal::status())
+}
+
+/// 表示待ちのスパチャ件数を取得（表示中のスパチャは含まない）
+#[tauri::command]
+pub async fn superchat_queue_length() -> Result<usize, String> {
+    Ok(superchat::queue_length())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    async fn create_test_pool(temp_file: &NamedTempFile) -> SqlitePool {
+        crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_superchat_slot_defaults_to_left_lower() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = create_test_pool(&temp_file).await;
+
+        assert_eq!(load_superchat_slot(&pool).await.unwrap(), SlotId::LeftLower);
+    }
+
+    #[tokio::test]
+    async fn test_superchat_slot_round_trip() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = create_test_pool(&temp_file).await;
+
+        save_superchat_slot(&pool, SlotId::RightLowerLeft).await.unwrap();
+        assert_eq!(load_superchat_slot(&pool).await.unwrap(), SlotId::RightLowerLeft);
+
+        // 上書き保存
+        save_superchat_slot(&pool, SlotId::LeftMiddle).await.unwrap();
+        assert_eq!(load_superchat_slot(&pool).await.unwrap(), SlotId::LeftMiddle);
+    }
+
+    #[tokio::test]
+    async fn test_superchat_slot_invalid_value_falls_back_to_default() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = create_test_pool(&temp_file).await;
+
+        sqlx::query("INSERT INTO settings (key, value, updated_at) VALUES (?, ?, ?)")
+            .bind(SUPERCHAT_SLOT_KEY)
+            .bind("\"left.unknown\"")
+            .bind(chrono::Utc::now().to_rfc3339())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(load_superchat_slot(&pool).await.unwrap(), SlotId::LeftLower);
+    }
+
+    #[test]
+    fn test_superchat_slot_rejects_unknown_slot() {
+        assert!(serde_json::from_str::<SlotId>("\"left.unknown\"").is_err());
+        assert_eq!(
+            serde_json::from_str::<SlotId>("\"right.lowerLeft\"").unwrap(),
+            SlotId::RightLowerLeft
+        );
+    }
+}
@@ -1,12 +1,38 @@
 use font_kit::source::SystemSource;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tokio::sync::Mutex;
 
 /// フォント名の最大長（セキュリティ対策）
 const MAX_FONT_NAME_LENGTH: usize = 200;
 
+/// 日本語（CJK）対応の判定に使う文字（ひらがな・カタカナ・漢字）
+const CJK_SAMPLE_CHARS: &[char] = &['あ', 'ア', '漢'];
+
+/// システムフォントの情報
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemFont {
+    /// フォントファミリー名
+    pub family: String,
+    /// 等幅フォントか
+    pub monospace: bool,
+    /// 日本語（CJK）の文字を含むか
+    pub cjk: bool,
+}
+
+/// 取得済みのシステムフォント一覧
+///
+/// フォントの列挙はインストール数が多いと遅いため、初回取得時にキャッシュする。
+/// Mutexで同時に呼ばれた場合も列挙を1回にする
+static SYSTEM_FONTS_CACHE: Lazy<Mutex<Option<Vec<SystemFont>>>> = Lazy::new(|| Mutex::new(None));
+
 /// システムにインストールされているフォント一覧を取得
 ///
+/// 2回目以降はキャッシュを返す（フォントを追加した場合は`refresh_system_fonts`で再取得）
+///
 /// # Returns
-/// フォントファミリー名のリスト（アルファベット順）
+/// フォント情報のリスト（ファミリー名のアルファベット順）
 /// 空リストは許容される（極めて稀だが技術的には可能）
 ///
 /// # Security
@@ -19,7 +45,32 @@ const MAX_FONT_NAME_LENGTH: usize = 200;
 /// - タスク結合エラー: エラーメッセージとして返却
 /// - 空リスト: 警告ログを出力するが、正常な結果として返却（フロントエンド側でフォールバック対応）
 #[tauri::command]
-pub async fn get_system_fonts() -> Result<Vec<String>, String> {
+pub async fn get_system_fonts() -> Result<Vec<SystemFont>, String> {
+    let mut cache = SYSTEM_FONTS_CACHE.lock().await;
+    if let Some(fonts) = cache.as_ref() {
+        return Ok(fonts.clone());
+    }
+    let fonts = scan_system_fonts().await?;
+    // 空リストはキャッシュせず、次回に再取得する
+    if !fonts.is_empty() {
+        *cache = Some(fonts.clone());
+    }
+    Ok(fonts)
+}
+
+/// システムフォントを再取得してキャッシュを更新
+///
+/// アプリ起動後にフォントをインストールした場合に使う
+#[tauri::command]
+pub async fn refresh_system_fonts() -> Result<Vec<SystemFont>, String> {
+    let mut cache = SYSTEM_FONTS_CACHE.lock().await;
+    let fonts = scan_system_fonts().await?;
+    *cache = (!fonts.is_empty()).then(|| fonts.clone());
+    Ok(fonts)
+}
+
+/// システムフォントを列挙し、各ファミリーの等幅・日本語対応を調べる
+async fn scan_system_fonts() -> Result<Vec<SystemFont>, String> {
     // font-kitはブロッキング操作なので、spawn_blockingで実行
     tokio::task::spawn_blocking(|| {
         let source = SystemSource::new();
@@ -32,13 +83,7 @@ pub async fn get_system_fonts() -> Result<Vec<String>, String> {
         // フィルタリング: 制御文字や異常に長い名前を除外（セキュリティ対策）
         let mut fonts: Vec<String> = families
             .into_iter()
-            .filter(|name| {
-                // 空文字や異常に長い名前を除外
-                !name.is_empty()
-                    && name.len() <= MAX_FONT_NAME_LENGTH
-                    // 制御文字を含むフォント名を除外
-                    && !name.chars().any(|c| c.is_control())
-            })
+            .filter(|name| is_valid_font_name(name))
             .collect();
 
         // フィルタリングで除外されたフォントがあればログ出力
@@ -50,8 +95,9 @@ pub async fn get_system_fonts() -> Result<Vec<String>, String> {
             );
         }
 
-        // アルファベット順でソート
+        // アルファベット順でソート（同名のファミリーは1件にまとめる）
         fonts.sort();
+        fonts.dedup();
 
         // 空リストは許容するが警告ログを出力（フロントエンド側でフォールバック対応）
         if fonts.is_empty() {
@@ -62,8 +108,59 @@ pub async fn get_system_fonts() -> Result<Vec<String>, String> {
             log::info!("Found {} system fonts", fonts.len());
         }
 
-        Ok(fonts)
+        Ok(fonts
+            .into_iter()
+            .map(|family| inspect_font_family(&source, family))
+            .collect())
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
+
+/// フォント名として扱えるか
+///
+/// 空文字・異常に長い名前（MAX_FONT_NAME_LENGTH文字超）・制御文字を含む名前を除外
+fn is_valid_font_name(name: &str) -> bool {
+    !name.is_empty() && name.len() <= MAX_FONT_NAME_LENGTH && !name.chars().any(|c| c.is_control())
+}
+
+/// ファミリーの先頭のフォントを読み込み、等幅・日本語対応を調べる
+///
+/// 読み込めない場合はどちらもfalseとする（一覧からは除外しない）
+fn inspect_font_family(source: &SystemSource, family: String) -> SystemFont {
+    let font = source
+        .select_family_by_name(&family)
+        .ok()
+        .and_then(|handle| handle.fonts().first().and_then(|font| font.load().ok()));
+    let (monospace, cjk) = match font {
+        Some(font) => (
+            font.is_monospace(),
+            CJK_SAMPLE_CHARS
+                .iter()
+                .all(|c| font.glyph_for_char(*c).is_some_and(|glyph| glyph != 0)),
+        ),
+        None => {
+            log::debug!("Failed to load font family: {}", family);
+            (false, false)
+        }
+    };
+    SystemFont {
+        family,
+        monospace,
+        cjk,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_font_name() {
+        assert!(is_valid_font_name("Noto Sans JP"));
+        assert!(is_valid_font_name("游ゴシック"));
+        assert!(!is_valid_font_name(""));
+        assert!(!is_valid_font_name("Bad\nFont"));
+        assert!(!is_valid_font_name(&"a".repeat(MAX_FONT_NAME_LENGTH + 1)));
+    }
+}
@@ -2,13 +2,105 @@
 //!
 //! テンプレート設定のバリデーション・保存・読み込み
 
-use crate::server::template_types::Template;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::server::template_types::{
+    all_placeholders, render_text_template, validate_placeholders, validate_text_template,
+    Template, TemplateComponent, TemplateError, TextTemplateKind, CONDITION_FLAGS,
+};
+
+/// コンポーネント設定内のプレースホルダーの検証エラー
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentPlaceholderError {
+    /// コンポーネントID
+    pub component_id: String,
+    /// エラーのあった項目（例: "style.format", "style.lines[0]"）
+    pub field: String,
+    /// エラー内容（`position`は項目の文字列内の位置）
+    pub error: TemplateError,
+}
+
+/// テンプレートの検証結果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateValidationResult {
+    /// クランプ済みのテンプレート
+    pub template: Template,
+    /// 未知・タイプミスのプレースホルダー（描画時に空になる）
+    pub placeholder_errors: Vec<ComponentPlaceholderError>,
+}
+
+/// コンポーネントのstyle内の文字列から`{{...}}`を抽出して検証
+///
+/// コンポーネントが表示するデータの種別のプレースホルダー・条件フラグのみ許可する
+/// （対応するデータがないコンポーネントは全種別のプレースホルダーと全フラグを許可）
+fn validate_component_placeholders(
+    component: &TemplateComponent,
+) -> Vec<ComponentPlaceholderError> {
+    let Some(style) = &component.style else {
+        return Vec::new();
+    };
+    let (allowed, flags) = match component.component_type.text_template_kind() {
+        Some(kind) => (kind.placeholders().to_vec(), kind.condition_flags()),
+        None => (all_placeholders(), &CONDITION_FLAGS[..]),
+    };
+
+    let mut errors = Vec::new();
+    collect_placeholder_errors(
+        style,
+        "style".to_string(),
+        &allowed,
+        flags,
+        &mut |field, error| {
+            errors.push(ComponentPlaceholderError {
+                component_id: component.id.clone(),
+                field,
+                error,
+            })
+        },
+    );
+    errors
+}
+
+/// JSON値を再帰的に走査し、文字列ごとにプレースホルダーを検証
+fn collect_placeholder_errors(
+    value: &serde_json::Value,
+    field: String,
+    allowed: &[&str],
+    flags: &[&str],
+    report: &mut impl FnMut(String, TemplateError),
+) {
+    match value {
+        serde_json::Value::String(text) => {
+            for error in validate_placeholders(allowed, flags, text) {
+                report(field.clone(), error);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                let field = format!("{}[{}]", field, index);
+                collect_placeholder_errors(item, field, allowed, flags, report);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for (key, item) in map {
+                let field = format!("{}.{}", field, key);
+                collect_placeholder_errors(item, field, allowed, flags, report);
+            }
+        }
+        _ => {}
+    }
+}
 
 /// テンプレートをバリデーション＆クランプ
 ///
-/// 不正な値はクランプして適用し、検証済みのテンプレートを返す
+/// 不正な値はクランプして適用し、検証済みのテンプレートを返す。
+/// コンポーネントのstyle内の`{{variable}}`は使用可能なプレースホルダーに対して検証し、
+/// 未知・タイプミスの変数を位置付きで返す（テンプレート自体はエラーにしない）
 #[tauri::command]
-pub fn validate_template(mut template: Template) -> Result<Template, String> {
+pub fn validate_template(mut template: Template) -> Result<TemplateValidationResult, String> {
     // バリデーション＆クランプ
     template.validate_and_clamp();
 
@@ -39,7 +131,22 @@ pub fn validate_template(mut template: Template) -> Result<Template, String> {
         );
     }
 
-    Ok(template)
+    let placeholder_errors: Vec<ComponentPlaceholderError> = template
+        .components
+        .iter()
+        .flat_map(validate_component_placeholders)
+        .collect();
+    if !placeholder_errors.is_empty() {
+        log::warn!(
+            "テンプレートに未知のプレースホルダーがあります: {}件",
+            placeholder_errors.len()
+        );
+    }
+
+    Ok(TemplateValidationResult {
+        template,
+        placeholder_errors,
+    })
 }
 
 /// テンプレートのデフォルト設定を取得
@@ -48,6 +155,41 @@ pub fn get_default_template() -> Template {
     Template::default()
 }
 
+/// テキストテンプレートを一括バリデーション
+///
+/// テンプレート名（"comment", "superchat", "kpi", "weather"）から種別を判定し、
+/// 種別ごとのプレースホルダーで検証する。「すべて保存」フロー用に
+/// 全テンプレートの結果を1回で返す（エラーがないテンプレートは空のVec）
+#[tauri::command]
+pub fn validate_all_templates(
+    templates: HashMap<String, String>,
+) -> HashMap<String, Vec<TemplateError>> {
+    templates
+        .into_iter()
+        .map(|(name, text)| {
+            let errors = match TextTemplateKind::from_name(&name) {
+                Some(kind) => validate_text_template(kind, &text),
+                None => vec![TemplateError::UnknownTemplate { name: name.clone() }],
+            };
+            (name, errors)
+        })
+        .collect()
+}
+
+/// テキストテンプレートをプレビュー用に描画
+///
+/// `values`はプレースホルダー名 → 値、`flags`は真とする条件フラグ（例: "is_member"）。
+/// `{{#if}}`/`{{/if}}`の対応が取れていない場合は検証エラーを返す
+#[tauri::command]
+pub fn preview_text_template(
+    text: String,
+    values: HashMap<String, String>,
+    flags: Vec<String>,
+) -> Result<String, Vec<TemplateError>> {
+    let flags: Vec<&str> = flags.iter().map(String::as_str).collect();
+    render_text_template(&text, &values, &flags)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,7 +259,7 @@ mod tests {
             }],
         };
 
-        let result = validate_template(template).unwrap();
+        let result = validate_template(template).unwrap().template;
 
         assert_eq!(result.layout.left_pct, 0.18);
         assert_eq!(result.layout.center_pct, 0.64);
@@ -130,6 +272,48 @@ mod tests {
         assert_eq!(comp.tuning.as_ref().unwrap().offset_y, Some(40));
     }
 
+    #[test]
+    fn test_validate_template_reports_typo_variable() {
+        let mut template = create_test_template();
+        template.components[0].style = Some(serde_json::json!({
+            "format": "{{authorName}}: {{mesage}}",
+            "badges": ["{{tier}}"],
+        }));
+
+        let result = validate_template(template).unwrap();
+
+        // ChatLogではコメントのプレースホルダーのみ使用可能
+        assert_eq!(
+            result.placeholder_errors,
+            vec![
+                ComponentPlaceholderError {
+                    component_id: "test-chatlog".to_string(),
+                    field: "style.badges[0]".to_string(),
+                    error: TemplateError::UnknownPlaceholder {
+                        name: "tier".to_string(),
+                        position: 0,
+                        suggestion: None,
+                    },
+                },
+                ComponentPlaceholderError {
+                    component_id: "test-chatlog".to_string(),
+                    field: "style.format".to_string(),
+                    error: TemplateError::UnknownPlaceholder {
+                        name: "mesage".to_string(),
+                        position: 16,
+                        suggestion: Some("message".to_string()),
+                    },
+                },
+            ]
+        );
+
+        // プレースホルダーのないテンプレートはエラーなし
+        assert!(validate_template(create_test_template())
+            .unwrap()
+            .placeholder_errors
+            .is_empty());
+    }
+
     #[test]
     fn test_validate_template_rejects_empty_components() {
         let template = Template {
@@ -216,6 +400,40 @@ mod tests {
         assert_eq!(result.unwrap_err(), "コンポーネントIDが重複しています");
     }
 
+    #[test]
+    fn test_validate_all_templates_per_template_results() {
+        let mut templates = HashMap::new();
+        templates.insert(
+            "comment".to_string(),
+            "{{authorName}}: {{message}}".to_string(),
+        );
+        templates.insert(
+            "superchat".to_string(),
+            "{{authorName}} {{amount}} {{temp}}".to_string(),
+        );
+        templates.insert("banner".to_string(), "{{message}}".to_string());
+
+        let results = validate_all_templates(templates);
+
+        assert_eq!(results.len(), 3);
+        assert!(results["comment"].is_empty());
+        // 天気用のプレースホルダーはスパチャでは使用不可
+        assert_eq!(
+            results["superchat"],
+            vec![TemplateError::UnknownPlaceholder {
+                name: "temp".to_string(),
+                position: 26,
+                suggestion: None,
+            }]
+        );
+        assert_eq!(
+            results["banner"],
+            vec![TemplateError::UnknownTemplate {
+                name: "banner".to_string(),
+            }]
+        );
+    }
+
     // 注: test_validate_template_forces_layout_type は削除
     // LayoutType がenum化されたため、不正な値はコンパイル時に検出される
 
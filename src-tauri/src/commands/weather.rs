@@ -6,14 +6,22 @@
 // =============================================================================
 
 use std::sync::Arc;
+use sqlx::SqlitePool;
 use tauri::State;
 
 use serde::Serialize;
 
+use super::error::CommandError;
 use crate::server::types::{
-    CityWeatherData, WeatherMultiUpdatePayload, WeatherUpdatePayload, WsMessage,
+    CityWeatherData, ForecastUpdatePayload, WeatherMultiUpdatePayload, WeatherUpdatePayload,
+    WsMessage,
+};
+use crate::weather::{
+    clamp_cache_ttl_minutes, clamp_update_interval_minutes, CityCandidate, ForecastData, PinnedCityCoords, WeatherData,
+    WeatherIconMap,
+    WeatherRetryPolicy, WeatherUnits, DEFAULT_CACHE_TTL_MINUTES, DEFAULT_UPDATE_INTERVAL_MINUTES,
+    MAX_CONCURRENT_REQUESTS_LIMIT, MAX_RETRY_ATTEMPTS_LIMIT, MAX_RETRY_BASE_DELAY_MS,
 };
-use crate::weather::WeatherData;
 use crate::AppState;
 
 /// マルチシティ配信結果
@@ -29,31 +37,478 @@ pub struct BroadcastMultiResult {
 /// UIで最小3秒を設定しているが、0が渡された場合の防御的ガード
 const MIN_ROTATION_INTERVAL_SEC: u32 = 1;
 
+/// アイコン上書き設定の保存キー
+const WEATHER_ICON_MAP_KEY: &str = "weather_icon_map";
+
+/// 固定した緯度経度の保存キー
+const WEATHER_PINNED_COORDS_KEY: &str = "weather_pinned_coords";
+
+/// 天気自動更新間隔（分）の保存キー
+const WEATHER_UPDATE_INTERVAL_KEY: &str = "weather_update_interval_minutes";
+
+/// 天気キャッシュTTL（分）の保存キー
+const WEATHER_CACHE_TTL_KEY: &str = "weather_cache_ttl_minutes";
+
+/// アイコン上書き設定の絵文字最大長（文字）
+/// 異体字セレクタやZWJ結合を含む絵文字を考慮した値
+const MAX_ICON_EMOJI_LENGTH: usize = 16;
+
+/// アイコン上書き設定の説明最大長（文字）
+const MAX_ICON_DESCRIPTION_LENGTH: usize = 50;
+
 /// 都市名を設定
 #[tauri::command(rename_all = "snake_case")]
-pub async fn set_weather_city(state: State<'_, AppState>, city: String) -> Result<(), String> {
+pub async fn set_weather_city(state: State<'_, AppState>, city: String) -> Result<(), CommandError> {
     state.weather.set_city(city).await;
     Ok(())
 }
 
 /// 現在の都市名を取得
 #[tauri::command]
-pub async fn get_weather_city(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn get_weather_city(state: State<'_, AppState>) -> Result<String, CommandError> {
     Ok(state.weather.get_city().await)
 }
 
+/// 都市名で検索し、同名都市を含む候補（最大10件）を取得
+///
+/// UIで候補を選ばせ、選んだ候補の緯度経度を`set_city_coords`で固定する
+#[tauri::command]
+pub async fn search_cities(
+    state: State<'_, AppState>,
+    query: String,
+) -> Result<Vec<CityCandidate>, CommandError> {
+    Ok(state.weather.search_cities(&query).await?)
+}
+
+/// 都市名に対する緯度経度を固定（以降はジオコーディングしない）
+///
+/// 固定した緯度経度は設定に保存され、次回起動時にも反映される
+///
+/// ## 入力検証
+/// - 都市名は空白のみ不可
+/// - 緯度は-90〜90、経度は-180〜180
+/// - 表示名は省略時に都市名を使う
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_city_coords(
+    state: State<'_, AppState>,
+    city: String,
+    latitude: f64,
+    longitude: f64,
+    display_name: Option<String>,
+) -> Result<(), CommandError> {
+    let city = city.trim();
+    let display_name = display_name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| city.to_string());
+    let coords = PinnedCityCoords {
+        city: city.to_string(),
+        latitude,
+        longitude,
+        display_name,
+    };
+    coords.validate().map_err(CommandError::Validation)?;
+
+    let mut pinned = state.weather.pinned_city_coords().await;
+    pinned.retain(|p| p.city != coords.city);
+    pinned.push(coords.clone());
+    save_pinned_city_coords(&state.db, &pinned).await?;
+
+    state
+        .weather
+        .pin_city_coords(&coords.city, coords.latitude, coords.longitude, coords.display_name)
+        .await;
+    Ok(())
+}
+
+/// 都市名に対する緯度経度の固定を解除（以降はジオコーディングする）
+///
+/// 固定していない都市の場合は何もしない
+#[tauri::command(rename_all = "snake_case")]
+pub async fn unpin_city_coords(state: State<'_, AppState>, city: String) -> Result<(), CommandError> {
+    let city = city.trim();
+    let mut pinned = state.weather.pinned_city_coords().await;
+    let before = pinned.len();
+    pinned.retain(|p| p.city != city);
+    if pinned.len() == before {
+        return Ok(());
+    }
+    save_pinned_city_coords(&state.db, &pinned).await?;
+
+    state.weather.unpin_city_coords(city).await;
+    Ok(())
+}
+
+/// 固定した緯度経度の一覧を取得
+#[tauri::command]
+pub async fn get_pinned_city_coords(
+    state: State<'_, AppState>,
+) -> Result<Vec<PinnedCityCoords>, CommandError> {
+    Ok(state.weather.pinned_city_coords().await)
+}
+
+/// 保存済みの固定した緯度経度をDBから読み込み
+///
+/// 未保存・JSON破損時は空（固定なし）、範囲外の要素は読み飛ばす。起動時の設定反映に使用する。
+pub async fn load_pinned_city_coords(pool: &SqlitePool) -> Result<Vec<PinnedCityCoords>, CommandError> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(WEATHER_PINNED_COORDS_KEY)
+        .fetch_optional(pool)
+        .await?;
+
+    match result {
+        Some((json_str,)) => match serde_json::from_str::<Vec<PinnedCityCoords>>(&json_str) {
+            Ok(pinned) => Ok(pinned
+                .into_iter()
+                .filter(|coords| match coords.validate() {
+                    Ok(()) => true,
+                    Err(e) => {
+                        log::warn!("Skipping invalid pinned weather coords: {}", e);
+                        false
+                    }
+                })
+                .collect()),
+            Err(e) => {
+                log::warn!(
+                    "Pinned weather coords JSON corrupted, falling back to geocoding. Error: {}",
+                    e
+                );
+                Ok(Vec::new())
+            }
+        },
+        None => Ok(Vec::new()),
+    }
+}
+
+/// 固定した緯度経度の一覧をDBに保存
+async fn save_pinned_city_coords(
+    pool: &SqlitePool,
+    pinned: &[PinnedCityCoords],
+) -> Result<(), CommandError> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let json_str = serde_json::to_string(pinned)
+        .map_err(|e| CommandError::Internal(format!("JSON serialize error: {}", e)))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(WEATHER_PINNED_COORDS_KEY)
+    .bind(&json_str)
+    .bind(&now)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// 単位系を設定（"metric" or "imperial"）
+///
+/// 変更時は天気キャッシュがクリアされる
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_weather_units(
+    state: State<'_, AppState>,
+    units: WeatherUnits,
+) -> Result<(), CommandError> {
+    state.weather.set_units(units).await;
+    Ok(())
+}
+
+/// 現在の単位系を取得
+#[tauri::command]
+pub async fn get_weather_units(state: State<'_, AppState>) -> Result<WeatherUnits, CommandError> {
+    Ok(state.weather.get_units().await)
+}
+
+/// 天気APIへの同時リクエスト数の上限を設定
+///
+/// 上限を超えたリクエストは空きが出るまで待機して順番に送信される
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_weather_max_concurrent_requests(
+    state: State<'_, AppState>,
+    limit: usize,
+) -> Result<(), CommandError> {
+    if !(1..=MAX_CONCURRENT_REQUESTS_LIMIT).contains(&limit) {
+        return Err(CommandError::Validation(format!(
+            "同時リクエスト数は1〜{}で指定してください: {}",
+            MAX_CONCURRENT_REQUESTS_LIMIT, limit
+        )));
+    }
+    state.weather.set_max_concurrent_requests(limit).await;
+    Ok(())
+}
+
+/// 天気APIへの同時リクエスト数の上限を取得
+#[tauri::command]
+pub async fn get_weather_max_concurrent_requests(state: State<'_, AppState>) -> Result<usize, CommandError> {
+    Ok(state.weather.get_max_concurrent_requests().await)
+}
+
+/// 天気APIの一時的なエラー（タイムアウト・接続エラー・5xx）時のリトライ設定を更新
+///
+/// `max_attempts`は初回を含む試行回数、`base_delay_ms`は初回リトライまでの待機時間（以降2倍ずつ増やす）
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_weather_retry_policy(
+    state: State<'_, AppState>,
+    max_attempts: u32,
+    base_delay_ms: u64,
+) -> Result<(), CommandError> {
+    if !(1..=MAX_RETRY_ATTEMPTS_LIMIT).contains(&max_attempts) {
+        return Err(CommandError::Validation(format!(
+            "試行回数は1〜{}で指定してください: {}",
+            MAX_RETRY_ATTEMPTS_LIMIT, max_attempts
+        )));
+    }
+    if base_delay_ms > MAX_RETRY_BASE_DELAY_MS {
+        return Err(CommandError::Validation(format!(
+            "リトライの待機時間は{}ミリ秒以下で指定してください: {}",
+            MAX_RETRY_BASE_DELAY_MS, base_delay_ms
+        )));
+    }
+    state
+        .weather
+        .set_retry_policy(WeatherRetryPolicy {
+            max_attempts,
+            base_delay_ms,
+        })
+        .await;
+    Ok(())
+}
+
+/// 天気APIのリトライ設定を取得
+#[tauri::command]
+pub async fn get_weather_retry_policy(state: State<'_, AppState>) -> Result<WeatherRetryPolicy, CommandError> {
+    Ok(state.weather.get_retry_policy().await)
+}
+
+/// 保存済みのアイコン上書き設定をDBから読み込み
+///
+/// 未保存またはJSON破損時は空の設定（組み込みの変換表のみ使用）を返す。
+/// 起動時の`WeatherClient`初期化にも使用する。
+pub async fn load_weather_icon_map(pool: &SqlitePool) -> Result<WeatherIconMap, CommandError> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(WEATHER_ICON_MAP_KEY)
+        .fetch_optional(pool)
+        .await?;
+
+    match result {
+        Some((json_str,)) => match serde_json::from_str::<WeatherIconMap>(&json_str) {
+            Ok(icon_map) => Ok(icon_map),
+            Err(e) => {
+                log::warn!(
+                    "Weather icon map JSON corrupted, falling back to builtin icons. Error: {}",
+                    e
+                );
+                Ok(WeatherIconMap::new())
+            }
+        },
+        None => Ok(WeatherIconMap::new()),
+    }
+}
+
+/// アイコン上書き設定を検証・正規化
+///
+/// 絵文字・説明は前後の空白を除去し、空文字列や最大長超過はエラーとする
+fn validate_icon_map(icon_map: WeatherIconMap) -> Result<WeatherIconMap, CommandError> {
+    icon_map
+        .into_iter()
+        .map(|(code, (emoji, description))| {
+            let emoji = emoji.trim().to_string();
+            let description = description.trim().to_string();
+            if emoji.is_empty() || description.is_empty() {
+                return Err(CommandError::Validation(format!("WMOコード{}の絵文字または説明が空です", code)));
+            }
+            if emoji.chars().count() > MAX_ICON_EMOJI_LENGTH {
+                return Err(CommandError::Validation(format!(
+                    "WMOコード{}の絵文字が長すぎます（最大{}文字）",
+                    code, MAX_ICON_EMOJI_LENGTH
+                )));
+            }
+            if description.chars().count() > MAX_ICON_DESCRIPTION_LENGTH {
+                return Err(CommandError::Validation(format!(
+                    "WMOコード{}の説明が長すぎます（最大{}文字）",
+                    code, MAX_ICON_DESCRIPTION_LENGTH
+                )));
+            }
+            Ok((code, (emoji, description)))
+        })
+        .collect()
+}
+
+/// WMOコードのアイコン・説明の上書き設定を保存
+///
+/// 設定はsettingsテーブルに保存され、天気クライアントにも即時反映される。
+/// 空のマップを渡すと組み込みの変換表に戻る。
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_weather_icon_map(
+    state: State<'_, AppState>,
+    icon_map: WeatherIconMap,
+) -> Result<(), CommandError> {
+    let validated = validate_icon_map(icon_map)?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let json_str = serde_json::to_string(&validated)
+        .map_err(|e| CommandError::Internal(format!("JSON serialize error: {}", e)))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(WEATHER_ICON_MAP_KEY)
+    .bind(&json_str)
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    let count = validated.len();
+    state.weather.set_icon_map(validated).await;
+    log::info!("Weather icon map saved ({} overrides)", count);
+    Ok(())
+}
+
+/// WMOコードのアイコン・説明の上書き設定を取得
+#[tauri::command]
+pub async fn get_weather_icon_map(state: State<'_, AppState>) -> Result<WeatherIconMap, CommandError> {
+    load_weather_icon_map(&state.db).await
+}
+
+/// 保存済みの天気自動更新間隔（分）をDBから読み込み
+///
+/// 未保存・不正な値の場合はデフォルト（15分）を返す。範囲外の値は1〜120分に丸める。
+/// 起動時の`WeatherAutoUpdater`開始にも使用する。
+pub async fn load_weather_update_interval(pool: &SqlitePool) -> Result<u32, CommandError> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(WEATHER_UPDATE_INTERVAL_KEY)
+        .fetch_optional(pool)
+        .await?;
+
+    let Some((value,)) = result else {
+        return Ok(DEFAULT_UPDATE_INTERVAL_MINUTES);
+    };
+
+    match value.parse::<u32>() {
+        Ok(minutes) => Ok(clamp_update_interval_minutes(minutes)),
+        Err(_) => {
+            log::warn!(
+                "Stored weather update interval is invalid, falling back to default: {}",
+                value
+            );
+            Ok(DEFAULT_UPDATE_INTERVAL_MINUTES)
+        }
+    }
+}
+
+/// 天気の自動更新間隔（分）を保存し、実行中の自動更新に反映
+///
+/// 1〜120分に丸めて保存し、丸めた値を返す。待機中のタイマーは新しい間隔で待ち直す。
+#[tauri::command]
+pub async fn set_weather_update_interval(
+    state: State<'_, AppState>,
+    minutes: u32,
+) -> Result<u32, CommandError> {
+    let minutes = clamp_update_interval_minutes(minutes);
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(WEATHER_UPDATE_INTERVAL_KEY)
+    .bind(minutes.to_string())
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    Ok(state.weather_updater.set_interval_minutes(minutes))
+}
+
+/// 天気の自動更新間隔（分）を取得
+#[tauri::command]
+pub async fn get_weather_update_interval(state: State<'_, AppState>) -> Result<u32, CommandError> {
+    Ok(state.weather_updater.interval_minutes())
+}
+
+/// 保存済みの天気キャッシュTTL（分）をDBから読み込み
+///
+/// 未保存または不正な値の場合はデフォルト（15分）を返す。
+/// 起動時の`WeatherClient`初期化に使用する。
+pub async fn load_weather_cache_ttl(pool: &SqlitePool) -> Result<u32, CommandError> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(WEATHER_CACHE_TTL_KEY)
+        .fetch_optional(pool)
+        .await?;
+
+    let Some((value,)) = result else {
+        return Ok(DEFAULT_CACHE_TTL_MINUTES);
+    };
+
+    match value.parse::<u32>() {
+        Ok(minutes) => Ok(clamp_cache_ttl_minutes(minutes)),
+        Err(_) => {
+            log::warn!(
+                "Stored weather cache TTL is invalid, falling back to default: {}",
+                value
+            );
+            Ok(DEFAULT_CACHE_TTL_MINUTES)
+        }
+    }
+}
+
+/// 天気キャッシュTTL（分）を保存し、キャッシュに反映
+///
+/// 1〜180分に丸めて保存し、丸めた値を返す。キャッシュ済みのデータにもすぐに反映される。
+#[tauri::command]
+pub async fn set_weather_cache_ttl(
+    state: State<'_, AppState>,
+    minutes: u32,
+) -> Result<u32, CommandError> {
+    let minutes = clamp_cache_ttl_minutes(minutes);
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(WEATHER_CACHE_TTL_KEY)
+    .bind(minutes.to_string())
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    Ok(state.weather.set_cache_ttl_minutes(minutes))
+}
+
+/// 天気キャッシュTTLの設定値（分）を取得
+///
+/// 残り時間は`get_weather_cache_ttl`で取得する
+#[tauri::command]
+pub async fn get_weather_cache_ttl_minutes(state: State<'_, AppState>) -> Result<u32, CommandError> {
+    Ok(state.weather.cache_ttl_minutes())
+}
+
 /// 天気情報を取得（キャッシュ優先）
 #[tauri::command]
-pub async fn get_weather(state: State<'_, AppState>) -> Result<WeatherData, String> {
-    state.weather.get_weather().await.map_err(|e| e.to_string())
+pub async fn get_weather(state: State<'_, AppState>) -> Result<WeatherData, CommandError> {
+    Ok(state.weather.get_weather().await?)
 }
 
 /// 天気情報を強制取得（キャッシュ無視）
 #[tauri::command]
-pub async fn fetch_weather(state: State<'_, AppState>) -> Result<WeatherData, String> {
-    // キャッシュをクリアしてから取得
-    state.weather.clear_cache().await;
-    state.weather.get_weather().await.map_err(|e| e.to_string())
+pub async fn fetch_weather(state: State<'_, AppState>) -> Result<WeatherData, CommandError> {
+    // キャッシュを期限切れにしてから取得
+    state.weather.expire_cache().await;
+    Ok(state.weather.get_weather().await?)
 }
 
 /// 天気情報をWebSocketでブロードキャスト
@@ -68,16 +523,16 @@ pub async fn fetch_weather(state: State<'_, AppState>) -> Result<WeatherData, St
 pub async fn broadcast_weather_update(
     state: State<'_, AppState>,
     force_refresh: Option<bool>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let force = force_refresh.unwrap_or(false);
     let weather_data = if force {
-        // 強制リフレッシュ: キャッシュをクリアしてから取得
-        state.weather.clear_cache().await;
+        // 強制リフレッシュ: キャッシュを期限切れにしてから取得
+        state.weather.expire_cache().await;
         log::info!("Force refresh requested for weather broadcast");
-        state.weather.get_weather().await.map_err(|e| e.to_string())?
+        state.weather.get_weather().await?
     } else {
         // 通常: キャッシュ優先
-        state.weather.get_weather().await.map_err(|e| e.to_string())?
+        state.weather.get_weather().await?
     };
 
     // WebSocketでブロードキャスト（Fire-and-forget）
@@ -103,16 +558,55 @@ pub async fn broadcast_weather_update(
     Ok(())
 }
 
-/// 天気キャッシュをクリア
+/// 3日間の天気予報を取得（キャッシュ優先）
+#[tauri::command]
+pub async fn get_weather_forecast(state: State<'_, AppState>) -> Result<ForecastData, CommandError> {
+    Ok(state.weather.get_forecast().await?)
+}
+
+/// 天気予報をWebSocketでブロードキャスト
+///
+/// ## 設計ノート
+/// - Fire-and-forgetパターン: ブロードキャストは`tokio::spawn`でバックグラウンド実行
+/// - RwLockガードをawait境界をまたいで保持しないようにtokio::spawnで分離
+#[tauri::command]
+pub async fn broadcast_weather_forecast(state: State<'_, AppState>) -> Result<(), CommandError> {
+    let forecast = state.weather.get_forecast().await?;
+    let day_count = forecast.days.len();
+
+    // WebSocketでブロードキャスト（Fire-and-forget）
+    let server = Arc::clone(&state.server);
+    let message = WsMessage::ForecastUpdate {
+        payload: ForecastUpdatePayload::from(&forecast),
+    };
+    tokio::spawn(async move {
+        let peers_arc = {
+            let ws_state = server.read().await;
+            ws_state.get_peers_arc()
+        };
+        let peers_guard = peers_arc.read().await;
+        let peers: Vec<_> = peers_guard
+            .iter()
+            .map(|(id, tx)| (*id, tx.clone()))
+            .collect();
+        drop(peers_guard);
+        crate::server::websocket::WebSocketState::send_to_peers(&peers, &message);
+        log::info!("Weather forecast broadcasted ({} days)", day_count);
+    });
+
+    Ok(())
+}
+
+/// 天気キャッシュをクリア（取得失敗時に返す前回の値も破棄する）
 #[tauri::command]
-pub async fn clear_weather_cache(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn clear_weather_cache(state: State<'_, AppState>) -> Result<(), CommandError> {
     state.weather.clear_cache().await;
     Ok(())
 }
 
 /// 天気キャッシュの残りTTLを取得（秒）
 #[tauri::command]
-pub async fn get_weather_cache_ttl(state: State<'_, AppState>) -> Result<u64, String> {
+pub async fn get_weather_cache_ttl(state: State<'_, AppState>) -> Result<u64, CommandError> {
     Ok(state.weather.cache_ttl_remaining().await)
 }
 
@@ -124,11 +618,15 @@ pub async fn get_weather_cache_ttl(state: State<'_, AppState>) -> Result<u64, St
 ///
 /// UIの「更新」ボタン用。最新の天気データを取得し、自動更新タイマーをリセットする。
 #[tauri::command]
-pub async fn refresh_weather(state: State<'_, AppState>) -> Result<WeatherData, String> {
-    state.weather.clear_cache().await;
-    let data = state.weather.get_weather().await.map_err(|e| e.to_string())?;
+pub async fn refresh_weather(state: State<'_, AppState>) -> Result<WeatherData, CommandError> {
+    state.weather.expire_cache().await;
+    let data = state.weather.get_weather().await?;
     state.weather_updater.reset_timer();
-    log::info!("Weather manually refreshed: {}°C, timer reset", data.temp);
+    log::info!(
+        "Weather manually refreshed: {}{}, timer reset",
+        data.temp,
+        data.temp_unit
+    );
     Ok(data)
 }
 
@@ -140,9 +638,9 @@ pub async fn refresh_weather(state: State<'_, AppState>) -> Result<WeatherData,
 /// - Fire-and-forgetパターン: ブロードキャストは`tokio::spawn`でバックグラウンド実行
 /// - RwLockガードをawait境界をまたいで保持しないようにtokio::spawnで分離
 #[tauri::command]
-pub async fn broadcast_weather(state: State<'_, AppState>) -> Result<(), String> {
-    let weather_data = state.weather.get_weather().await.map_err(|e| e.to_string())?;
-    let temp = weather_data.temp;
+pub async fn broadcast_weather(state: State<'_, AppState>) -> Result<(), CommandError> {
+    let weather_data = state.weather.get_weather().await?;
+    let temp = format!("{}{}", weather_data.temp, weather_data.temp_unit);
 
     // WebSocketでブロードキャスト（Fire-and-forget）
     let server = Arc::clone(&state.server);
@@ -161,7 +659,7 @@ pub async fn broadcast_weather(state: State<'_, AppState>) -> Result<(), String>
             .collect();
         drop(peers_guard);
         crate::server::websocket::WebSocketState::send_to_peers(&peers, &message);
-        log::info!("Weather broadcasted to overlay: {}°C", temp);
+        log::info!("Weather broadcasted to overlay: {}", temp);
     });
 
     Ok(())
@@ -178,12 +676,12 @@ pub async fn broadcast_weather(state: State<'_, AppState>) -> Result<(), String>
 pub async fn set_weather_city_and_broadcast(
     state: State<'_, AppState>,
     city: String,
-) -> Result<WeatherData, String> {
+) -> Result<WeatherData, CommandError> {
     // 都市名を設定（キャッシュは自動クリアされる）
     state.weather.set_city(city.clone()).await;
 
     // 最新の天気を取得
-    let weather_data = state.weather.get_weather().await.map_err(|e| e.to_string())?;
+    let weather_data = state.weather.get_weather().await?;
 
     // WebSocketでブロードキャスト（Fire-and-forget）
     let server = Arc::clone(&state.server);
@@ -191,7 +689,7 @@ pub async fn set_weather_city_and_broadcast(
         payload: WeatherUpdatePayload::from(&weather_data),
     };
     let city_for_log = city.clone();
-    let temp = weather_data.temp;
+    let temp = format!("{}{}", weather_data.temp, weather_data.temp_unit);
     tokio::spawn(async move {
         let peers_arc = {
             let ws_state = server.read().await;
@@ -205,7 +703,7 @@ pub async fn set_weather_city_and_broadcast(
         drop(peers_guard);
         crate::server::websocket::WebSocketState::send_to_peers(&peers, &message);
         log::info!(
-            "Weather city set to '{}', fetched and broadcasted: {}°C",
+            "Weather city set to '{}', fetched and broadcasted: {}",
             city_for_log,
             temp
         );
@@ -232,7 +730,7 @@ pub async fn set_weather_city_and_broadcast(
 pub async fn get_weather_multi(
     state: State<'_, AppState>,
     cities: Vec<(String, String, String)>, // (id, name, displayName)
-) -> Result<Vec<CityWeatherData>, String> {
+) -> Result<Vec<CityWeatherData>, CommandError> {
     let city_pairs: Vec<(String, String)> = cities
         .iter()
         .map(|(id, name, _)| (id.clone(), name.clone()))
@@ -262,9 +760,14 @@ pub async fn get_weather_multi(
                     city_name: display_name,
                     icon: data.icon,
                     temp: data.temp,
+                    temp_unit: data.temp_unit,
                     description: data.description,
                     location: data.location,
                     humidity: Some(data.humidity),
+                    wind_speed: data.wind_speed,
+                    wind_speed_unit: data.wind_speed_unit,
+                    wind_direction: data.wind_direction,
+                    wind_compass: data.wind_compass,
                 });
             }
             Err(e) => {
@@ -310,7 +813,7 @@ pub async fn broadcast_weather_multi(
     state: State<'_, AppState>,
     cities: Vec<(String, String, String)>, // (id, name, displayName)
     rotation_interval_sec: u32,
-) -> Result<BroadcastMultiResult, String> {
+) -> Result<BroadcastMultiResult, CommandError> {
     // 最小値ガード: 0が渡された場合は1秒に
     let rotation_interval_sec = rotation_interval_sec.max(MIN_ROTATION_INTERVAL_SEC);
 
@@ -322,7 +825,7 @@ pub async fn broadcast_weather_multi(
     let success_count = weather_data.len();
 
     if weather_data.is_empty() {
-        return Err("すべての都市の天気取得に失敗しました".to_string());
+        return Err(CommandError::Network("すべての都市の天気取得に失敗しました".to_string()));
     }
 
     // WebSocketでブロードキャスト（Fire-and-forget）
@@ -371,7 +874,7 @@ pub async fn set_multi_city_mode(
     enabled: bool,
     cities: Vec<(String, String, String)>,
     rotation_interval_sec: u32,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     // 最小値ガード: 0が渡された場合は1秒に
     let rotation_interval_sec = rotation_interval_sec.max(MIN_ROTATION_INTERVAL_SEC);
 
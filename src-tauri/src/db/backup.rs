@@ -0,0 +1,324 @@
+//! データベースのバックアップ・復元
+//!
+//! バックアップは`VACUUM INTO`でアプリ実行中でも一貫したコピーを作成する。
+//! 復元は実行中のDBを直接置き換えられないため、検証済みのファイルを`<DB>.restore`として
+//! 配置しておき、次回起動時の接続前に[`apply_pending_restore`]で差し替える。
+
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// 復元待ちファイルの拡張子（DBファイル名の末尾に付ける）
+const PENDING_RESTORE_SUFFIX: &str = ".restore";
+
+/// 差し替え前のDBを残すファイルの拡張子
+const PRE_RESTORE_SUFFIX: &str = ".before-restore";
+
+/// DBファイル名の末尾に拡張子を付けたパス
+fn with_suffix(db_path: &Path, suffix: &str) -> PathBuf {
+    let mut path = db_path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// 接続中のDBファイルのパスを取得
+pub async fn database_path(pool: &SqlitePool) -> Result<PathBuf, String> {
+    let (file,): (String,) =
+        sqlx::query_as("SELECT file FROM pragma_database_list WHERE name = 'main'")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| format!("DB error: {}", e))?;
+    if file.is_empty() {
+        return Err("インメモリDBはバックアップ・復元できません".to_string());
+    }
+    Ok(PathBuf::from(file))
+}
+
+/// DBの一貫したコピーを`dest`に作成
+///
+/// 既存ファイルへの上書きはしない
+pub async fn backup_to(pool: &SqlitePool, dest: &Path) -> Result<(), String> {
+    if dest.exists() {
+        return Err(format!("出力先のファイルが既に存在します: {}", dest.display()));
+    }
+    let dest = dest
+        .to_str()
+        .ok_or_else(|| "出力先のパスが不正です".to_string())?;
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(dest)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+    Ok(())
+}
+
+/// 復元元のDBを検証し、適用済みのマイグレーションバージョンを返す
+///
+/// - SQLiteとして破損していないこと
+/// - 適用済みのマイグレーションがすべてこのアプリに含まれ、内容（チェックサム）が一致すること
+///   （新しいバージョンのアプリで作成したDBは受け付けない。古いDBは次回起動時にマイグレーションされる）
+pub async fn validate_backup(src: &Path) -> Result<i64, String> {
+    if !src.is_file() {
+        return Err(format!("復元元のファイルが見つかりません: {}", src.display()));
+    }
+    let src_str = src
+        .to_str()
+        .ok_or_else(|| "復元元のパスが不正です".to_string())?;
+
+    let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", src_str))
+        .map_err(|e| format!("DB error: {}", e))?
+        .read_only(true);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .map_err(|e| format!("復元元のファイルを開けません: {}", e))?;
+
+    let result = validate_schema(&pool).await;
+    pool.close().await;
+    result
+}
+
+/// 復元元のスキーマがこのアプリのマイグレーションと互換か検証
+async fn validate_schema(pool: &SqlitePool) -> Result<i64, String> {
+    let (check,): (String,) = sqlx::query_as("PRAGMA quick_check")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("復元元のファイルはSQLiteのDBではありません: {}", e))?;
+    if check != "ok" {
+        return Err(format!("復元元のDBが破損しています: {}", check));
+    }
+
+    let applied: Vec<(i64, Vec<u8>)> = sqlx::query_as(
+        "SELECT version, checksum FROM _sqlx_migrations WHERE success = 1 ORDER BY version",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("復元元はこのアプリのDBではありません: {}", e))?;
+
+    let migrator = sqlx::migrate!("./migrations");
+    for (version, checksum) in &applied {
+        match migrator.iter().find(|m| m.version == *version) {
+            Some(migration) if migration.checksum.as_ref() == checksum.as_slice() => {}
+            Some(_) => {
+                return Err(format!(
+                    "復元元のDBのスキーマ（バージョン{}）がこのアプリと一致しません",
+                    version
+                ))
+            }
+            None => {
+                return Err(format!(
+                    "復元元のDBは新しいバージョンのアプリで作成されています（スキーマバージョン{}）",
+                    version
+                ))
+            }
+        }
+    }
+
+    applied
+        .last()
+        .map(|(version, _)| *version)
+        .ok_or_else(|| "復元元のDBにマイグレーション履歴がありません".to_string())
+}
+
+/// 検証済みの復元元を復元待ちとして配置（次回起動時に差し替える）
+pub async fn stage_restore(src: &Path, db_path: &Path) -> Result<(), String> {
+    validate_backup(src).await?;
+    tokio::fs::copy(src, with_suffix(db_path, PENDING_RESTORE_SUFFIX))
+        .await
+        .map_err(|e| format!("File error: {}", e))?;
+    Ok(())
+}
+
+/// 復元待ちのファイルがあればDBを差し替える（DB接続前に呼び出す）
+///
+/// 差し替え前のDBは`<DB>.before-restore`として残す（前回の復元で残したものは置き換える）。
+/// 差し替えた場合はtrue。
+///
+/// 差し替え前にWALをチェックポイントしてDB本体に書き戻す。それでも残ったWAL・共有メモリファイルは
+/// 削除せず、差し替え前のDBと一緒に移す（新しいDBに古いWALが適用されないようにする）
+pub async fn apply_pending_restore(db_path: &Path) -> Result<bool, String> {
+    let pending = with_suffix(db_path, PENDING_RESTORE_SUFFIX);
+    if !pending.exists() {
+        return Ok(false);
+    }
+
+    if db_path.exists() {
+        // 破損したDBからも復元できるよう、チェックポイントに失敗しても続行する
+        // （書き戻せなかったWALは差し替え前のDBと一緒に残る）
+        let _ = checkpoint_wal(db_path).await;
+
+        let pre_restore = with_suffix(db_path, PRE_RESTORE_SUFFIX);
+        for suffix in ["", "-wal", "-shm"] {
+            let from = with_suffix(db_path, suffix);
+            let to = with_suffix(&pre_restore, suffix);
+            // Windowsでは移動先が存在するとrenameが失敗するため先に削除
+            remove_if_exists(&to)?;
+            if from.exists() {
+                std::fs::rename(&from, &to).map_err(|e| format!("File error: {}", e))?;
+            }
+        }
+    }
+    std::fs::rename(&pending, db_path).map_err(|e| format!("File error: {}", e))?;
+    Ok(true)
+}
+
+/// WALの内容をDB本体に書き戻す
+async fn checkpoint_wal(db_path: &Path) -> Result<(), String> {
+    let options = SqliteConnectOptions::new().filename(db_path);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    let result = sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(&pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("DB error: {}", e));
+    pool.close().await;
+    result
+}
+
+/// ファイルがあれば削除
+fn remove_if_exists(path: &Path) -> Result<(), String> {
+    if path.exists() {
+        std::fs::remove_file(path).map_err(|e| format!("File error: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_pool, expected_migration_version};
+    use tempfile::TempDir;
+
+    async fn count(pool: &SqlitePool, table: &str) -> i64 {
+        let (count,): (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        count
+    }
+
+    #[tokio::test]
+    async fn test_backup_produces_readable_copy() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("app.db");
+        let pool = create_pool(db_path.to_str().unwrap()).await.unwrap();
+        assert_eq!(database_path(&pool).await.unwrap(), db_path);
+
+        sqlx::query("INSERT INTO settings (key, value, updated_at) VALUES ('k', 'v', datetime('now'))")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO comment_logs (id, youtube_id, message, author_name, author_channel_id, published_at)
+             VALUES ('c1', 'c1', 'hello', 'Viewer', 'UC_viewer', '2025-01-01T00:00:00+00:00')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let dest = dir.path().join("backup.db");
+        backup_to(&pool, &dest).await.unwrap();
+        // 既存ファイルは上書きしない
+        assert!(backup_to(&pool, &dest).await.is_err());
+
+        let expected = expected_migration_version().unwrap();
+        assert_eq!(validate_backup(&dest).await.unwrap(), expected);
+
+        let copy = create_pool(dest.to_str().unwrap()).await.unwrap();
+        for table in ["settings", "comment_logs", "songs", "kpi_history"] {
+            assert_eq!(count(&copy, table).await, count(&pool, table).await, "{}", table);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restore_rejects_incompatible_schema() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("app.db");
+
+        // 新しいバージョンのアプリで作成されたDB
+        let newer = dir.path().join("newer.db");
+        let pool = create_pool(newer.to_str().unwrap()).await.unwrap();
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+             VALUES (9999, 'future', 1, x'00', 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool.close().await;
+        let err = stage_restore(&newer, &db_path).await.unwrap_err();
+        assert!(err.contains("新しいバージョン"), "{}", err);
+
+        // SQLiteではないファイル
+        let text = dir.path().join("text.db");
+        std::fs::write(&text, "not a database").unwrap();
+        assert!(stage_restore(&text, &db_path).await.is_err());
+
+        assert!(!apply_pending_restore(&db_path).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_pending_restore_swaps_database() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("app.db");
+        let pool = create_pool(db_path.to_str().unwrap()).await.unwrap();
+
+        let backup = dir.path().join("backup.db");
+        sqlx::query("INSERT INTO settings (key, value, updated_at) VALUES ('k', 'v', datetime('now'))")
+            .execute(&pool)
+            .await
+            .unwrap();
+        backup_to(&pool, &backup).await.unwrap();
+        sqlx::query("DELETE FROM settings").execute(&pool).await.unwrap();
+        pool.close().await;
+
+        stage_restore(&backup, &db_path).await.unwrap();
+        assert!(apply_pending_restore(&db_path).await.unwrap());
+        assert!(with_suffix(&db_path, PRE_RESTORE_SUFFIX).exists());
+
+        let restored = create_pool(db_path.to_str().unwrap()).await.unwrap();
+        assert_eq!(count(&restored, "settings").await, 1);
+        // 差し替えは1回だけ
+        assert!(!apply_pending_restore(&db_path).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_pending_restore_keeps_uncheckpointed_writes() {
+        let dir = TempDir::new().unwrap();
+        let source_path = dir.path().join("source.db");
+        let pool = create_pool(source_path.to_str().unwrap()).await.unwrap();
+        let backup = dir.path().join("backup.db");
+        backup_to(&pool, &backup).await.unwrap();
+
+        // 閉じずに終了した状態（書き込みがWALに残っている）のDBを再現
+        sqlx::query("INSERT INTO settings (key, value, updated_at) VALUES ('k', 'v', datetime('now'))")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let db_path = dir.path().join("app.db");
+        for suffix in ["", "-wal"] {
+            std::fs::copy(with_suffix(&source_path, suffix), with_suffix(&db_path, suffix)).unwrap();
+        }
+        pool.close().await;
+
+        stage_restore(&backup, &db_path).await.unwrap();
+        // 前回の復元で残った差し替え前のDBがあっても置き換える
+        std::fs::write(with_suffix(&db_path, PRE_RESTORE_SUFFIX), "old").unwrap();
+        assert!(apply_pending_restore(&db_path).await.unwrap());
+
+        // WALの書き込みは差し替え前のDBに含まれ、新しいDBには適用されない
+        let pre_restore = with_suffix(&db_path, PRE_RESTORE_SUFFIX);
+        let previous = create_pool(pre_restore.to_str().unwrap()).await.unwrap();
+        assert_eq!(count(&previous, "settings").await, 1);
+        let restored = create_pool(db_path.to_str().unwrap()).await.unwrap();
+        assert_eq!(count(&restored, "settings").await, 0);
+    }
+}
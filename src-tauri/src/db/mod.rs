@@ -1,23 +1,40 @@
 use sqlx::{
-    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous},
     SqlitePool,
 };
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
+use thiserror::Error;
 
+pub mod backup;
 pub mod models;
+pub mod play_log;
+pub mod settings_backup;
+pub mod superchat_log;
 
 /// busy_timeout設定（ミリ秒）
 /// SQLiteのロック競合時に待機する最大時間
 /// 5秒あれば通常の競合は解消される
 const SQLITE_BUSY_TIMEOUT_MS: u64 = 5000;
 
+/// ジャーナルモード
+/// WALでは書き込み中も読み取りがブロックされないため、
+/// コメント保存とコメントログ検索・KPI集計等が並行してもロック競合が起きにくい
+const SQLITE_JOURNAL_MODE: SqliteJournalMode = SqliteJournalMode::Wal;
+
+/// 同期モード
+/// WALではNORMALでもDBは破損しない（電源断時に直前のコミットが失われる可能性のみ）
+const SQLITE_SYNCHRONOUS: SqliteSynchronous = SqliteSynchronous::Normal;
+
 /// データベース接続プールを作成し、マイグレーションを実行
 pub async fn create_pool(db_path: &str) -> Result<SqlitePool, sqlx::Error> {
-    // SqliteConnectOptionsを使用してbusy_timeoutを明示的に設定
+    // SqliteConnectOptionsを使用してbusy_timeout・ジャーナルモード・同期モードを明示的に設定
     // URIパラメータではなくAPIを使用することで、設定が確実に適用される
     let connect_options = SqliteConnectOptions::from_str(&format!("sqlite:{}?mode=rwc", db_path))?
-        .busy_timeout(Duration::from_millis(SQLITE_BUSY_TIMEOUT_MS));
+        .busy_timeout(Duration::from_millis(SQLITE_BUSY_TIMEOUT_MS))
+        .journal_mode(SQLITE_JOURNAL_MODE)
+        .synchronous(SQLITE_SYNCHRONOUS);
 
     let pool = SqlitePoolOptions::new()
         .max_connections(5)
@@ -30,11 +47,139 @@ pub async fn create_pool(db_path: &str) -> Result<SqlitePool, sqlx::Error> {
     Ok(pool)
 }
 
+/// 接続プールを閉じる（アプリ終了時に使用）
+///
+/// WALの内容をDB本体に書き戻して（チェックポイント）から閉じるため、
+/// 終了直後にDBファイルだけをコピーしても最後の書き込みが含まれる
+pub async fn close_pool(pool: &SqlitePool) {
+    if let Err(e) = sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)").execute(pool).await {
+        log::warn!("Failed to checkpoint WAL: {}", e);
+    }
+    pool.close().await;
+}
+
+/// 起動時に存在を確認するテーブル（マイグレーションで作成されるもの）
+const REQUIRED_TABLES: &[&str] = &[
+    "settings",
+    "songs",
+    "setlists",
+    "setlist_songs",
+    "comment_logs",
+    "kpi_history",
+    "live_sessions",
+    "song_play_log",
+    "superchat_log",
+];
+
+/// スキーマ自己診断の結果（起動時に`set_schema_ready`で設定）
+///
+/// 診断に失敗した場合はfalseとなり、コメント保存等のDB書き込みをスキップする。
+/// 診断前（テスト等）は利用可能とみなす。
+static SCHEMA_READY: AtomicBool = AtomicBool::new(true);
+
+/// スキーマ自己診断の結果を設定
+pub fn set_schema_ready(ready: bool) {
+    SCHEMA_READY.store(ready, Ordering::Relaxed);
+}
+
+/// スキーマが利用可能か（自己診断に成功しているか）
+pub fn is_schema_ready() -> bool {
+    SCHEMA_READY.load(Ordering::Relaxed)
+}
+
+/// スキーマ検証エラー
+///
+/// マイグレーションの埋め込み漏れ等でスキーマが不完全な場合に、
+/// 後続のクエリが"no such table"で失敗する前に原因を特定するためのもの
+#[derive(Debug, Error)]
+pub enum SchemaError {
+    #[error("Migration history table (_sqlx_migrations) not found: migrations were not applied ({0})")]
+    MigrationsNotApplied(String),
+
+    #[error("Latest migration not applied: expected version {expected}, found {applied:?}. Check that the migrations directory is embedded via sqlx::migrate!(\"./migrations\")")]
+    MigrationBehind { expected: i64, applied: Option<i64> },
+
+    #[error("Required table missing after migration: {0}")]
+    MissingTable(String),
+
+    #[error("DB error: {0}")]
+    Query(#[from] sqlx::Error),
+}
+
+/// 埋め込まれたマイグレーションの最新バージョン
+fn expected_migration_version() -> Option<i64> {
+    sqlx::migrate!("./migrations")
+        .iter()
+        .map(|migration| migration.version)
+        .max()
+}
+
+/// スキーマの自己診断
+///
+/// `create_pool`の後に呼び出し、最新のマイグレーションが適用済みであることと
+/// 必要なテーブルが存在することを確認する。
+pub async fn verify_schema(pool: &SqlitePool) -> Result<(), SchemaError> {
+    let applied: (Option<i64>,) =
+        sqlx::query_as("SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| SchemaError::MigrationsNotApplied(e.to_string()))?;
+
+    if let Some(expected) = expected_migration_version() {
+        if applied.0.map_or(true, |version| version < expected) {
+            return Err(SchemaError::MigrationBehind {
+                expected,
+                applied: applied.0,
+            });
+        }
+    }
+
+    for table in REQUIRED_TABLES {
+        let exists: Option<(String,)> =
+            sqlx::query_as("SELECT name FROM sqlite_master WHERE type = 'table' AND name = ?")
+                .bind(table)
+                .fetch_optional(pool)
+                .await?;
+        if exists.is_none() {
+            return Err(SchemaError::MissingTable((*table).to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+/// テスト用のセットリスト（ID`setlist1`・名前`Live`）と曲を作成
+///
+/// 曲のIDは`song0`〜、setlist_songsのIDは`ss0`〜
+#[cfg(test)]
+pub(crate) async fn create_test_setlist(pool: &SqlitePool, song_count: usize) {
+    sqlx::query("INSERT INTO setlists (id, name) VALUES ('setlist1', 'Live')")
+        .execute(pool)
+        .await
+        .unwrap();
+    for index in 0..song_count {
+        sqlx::query("INSERT INTO songs (id, title, artist) VALUES (?, ?, 'Artist')")
+            .bind(format!("song{}", index))
+            .bind(format!("Song {}", index))
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO setlist_songs (id, setlist_id, song_id, position) VALUES (?, 'setlist1', ?, ?)")
+            .bind(format!("ss{}", index))
+            .bind(format!("song{}", index))
+            .bind(index as i64)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::env;
     use std::fs;
+    use std::path::PathBuf;
     use std::time::{SystemTime, UNIX_EPOCH};
 
     /// ユニークなテスト用DBパスを生成
@@ -117,4 +262,196 @@ mod tests {
         drop(pool);
         let _ = fs::remove_file(&db_path);
     }
+
+    /// WALモード・synchronous=NORMALで接続されていることを検証
+    #[tokio::test]
+    async fn test_wal_mode_is_enabled() {
+        let db_path = unique_test_db_path("test_wal_mode");
+        let pool = create_pool(db_path.to_str().unwrap())
+            .await
+            .expect("Pool creation should succeed");
+
+        let (journal_mode,): (String,) = sqlx::query_as("PRAGMA journal_mode")
+            .fetch_one(&pool)
+            .await
+            .expect("PRAGMA query should succeed");
+        assert_eq!(journal_mode.to_lowercase(), "wal");
+
+        // synchronous: 0=OFF, 1=NORMAL, 2=FULL, 3=EXTRA
+        let (synchronous,): (i64,) = sqlx::query_as("PRAGMA synchronous")
+            .fetch_one(&pool)
+            .await
+            .expect("PRAGMA query should succeed");
+        assert_eq!(synchronous, 1);
+
+        pool.close().await;
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = fs::remove_file(format!("{}{}", db_path.display(), suffix));
+        }
+    }
+
+    /// close_poolはWALをチェックポイントしてからプールを閉じる
+    #[tokio::test]
+    async fn test_close_pool_checkpoints_wal() {
+        let db_path = unique_test_db_path("test_close_pool");
+        let pool = create_pool(db_path.to_str().unwrap())
+            .await
+            .expect("Pool creation should succeed");
+        // 別の接続を開いたままにし、最後の接続のクローズによる自動チェックポイントを起こさない
+        let other = create_pool(db_path.to_str().unwrap())
+            .await
+            .expect("Pool creation should succeed");
+        sqlx::query("INSERT INTO settings (key, value) VALUES ('close_pool_test', '1')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let wal_path = PathBuf::from(format!("{}-wal", db_path.display()));
+        assert!(fs::metadata(&wal_path).unwrap().len() > 0);
+
+        close_pool(&pool).await;
+        assert!(pool.is_closed());
+
+        // チェックポイント済みのためWALファイルは空（または削除済み）
+        assert!(fs::metadata(&wal_path).map(|m| m.len() == 0).unwrap_or(true));
+
+        other.close().await;
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = fs::remove_file(format!("{}{}", db_path.display(), suffix));
+        }
+    }
+
+    /// マイグレーション済みのDBはスキーマ検証に成功する
+    #[tokio::test]
+    async fn test_verify_schema_after_migration() {
+        let db_path = unique_test_db_path("test_verify_schema");
+        let pool = create_pool(db_path.to_str().unwrap())
+            .await
+            .expect("Pool creation should succeed");
+
+        assert!(verify_schema(&pool).await.is_ok());
+
+        drop(pool);
+        let _ = fs::remove_file(&db_path);
+    }
+
+    /// EXPLAIN QUERY PLANの詳細（detail列）を取得
+    async fn query_plan(pool: &SqlitePool, sql: &str) -> Vec<String> {
+        let rows: Vec<(i64, i64, i64, String)> = sqlx::query_as(&format!("EXPLAIN QUERY PLAN {}", sql))
+            .fetch_all(pool)
+            .await
+            .unwrap();
+        rows.into_iter().map(|(_, _, _, detail)| detail).collect()
+    }
+
+    /// comment_logsのpublished_at・author_channel_idでの絞り込みがインデックスを使う
+    #[tokio::test]
+    async fn test_comment_log_queries_use_indexes() {
+        let db_path = unique_test_db_path("test_comment_log_indexes");
+        let pool = create_pool(db_path.to_str().unwrap())
+            .await
+            .expect("Pool creation should succeed");
+
+        let plan = query_plan(
+            &pool,
+            "SELECT * FROM comment_logs WHERE published_at >= '2025-01-01' AND published_at < '2025-01-02'",
+        )
+        .await;
+        assert!(
+            plan.iter().any(|detail| detail.contains("USING INDEX idx_comment_logs_published_at")),
+            "published_at range query should use index: {:?}",
+            plan
+        );
+
+        let plan = query_plan(
+            &pool,
+            "SELECT 1 FROM comment_logs WHERE author_channel_id = 'UC_a' AND published_at < '2025-01-01' LIMIT 1",
+        )
+        .await;
+        assert!(
+            plan.iter().any(|detail| detail.contains("INDEX idx_comment_logs_author_channel_id")),
+            "author_channel_id query should use index: {:?}",
+            plan
+        );
+
+        drop(pool);
+        let _ = fs::remove_file(&db_path);
+    }
+
+    /// superchat_logの投稿時刻での絞り込み（目標金額の集計）がインデックスを使う
+    #[tokio::test]
+    async fn test_superchat_log_published_at_query_uses_index() {
+        let db_path = unique_test_db_path("test_superchat_log_index");
+        let pool = create_pool(db_path.to_str().unwrap())
+            .await
+            .expect("Pool creation should succeed");
+
+        let plan = query_plan(
+            &pool,
+            "SELECT COALESCE(SUM(amount_jpy), 0) FROM superchat_log WHERE published_at >= '2025-01-01'",
+        )
+        .await;
+        assert!(
+            plan.iter().any(|detail| detail.contains("INDEX idx_superchat_log_published_at")),
+            "published_at query should use index: {:?}",
+            plan
+        );
+
+        drop(pool);
+        let _ = fs::remove_file(&db_path);
+    }
+
+    /// 必要なテーブルが欠けている場合は明確なエラーを返す
+    #[tokio::test]
+    async fn test_verify_schema_reports_missing_table() {
+        let db_path = unique_test_db_path("test_verify_schema_missing");
+        let pool = create_pool(db_path.to_str().unwrap())
+            .await
+            .expect("Pool creation should succeed");
+
+        sqlx::query("DROP TABLE comment_logs")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let err = verify_schema(&pool).await.unwrap_err();
+        assert!(matches!(err, SchemaError::MissingTable(ref table) if table == "comment_logs"));
+        assert!(err.to_string().contains("comment_logs"));
+
+        drop(pool);
+        let _ = fs::remove_file(&db_path);
+    }
+
+    /// マイグレーション未適用のDBはマイグレーション起因のエラーを返す
+    #[tokio::test]
+    async fn test_verify_schema_without_migrations() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        let err = verify_schema(&pool).await.unwrap_err();
+        assert!(matches!(err, SchemaError::MigrationsNotApplied(_)));
+        assert!(err.to_string().contains("_sqlx_migrations"));
+
+        // 履歴テーブルはあるが最新バージョンが未適用
+        sqlx::query(
+            "CREATE TABLE _sqlx_migrations (version BIGINT PRIMARY KEY, success BOOLEAN NOT NULL)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let expected = expected_migration_version().unwrap();
+        sqlx::query("INSERT INTO _sqlx_migrations (version, success) VALUES (?, 1)")
+            .bind(expected - 1)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let err = verify_schema(&pool).await.unwrap_err();
+        assert!(matches!(
+            err,
+            SchemaError::MigrationBehind { expected: e, applied: Some(a) } if e == expected && a == expected - 1
+        ));
+    }
 }
@@ -13,6 +13,7 @@ pub struct Song {
     pub category: Option<String>,
     pub tags: Option<String>, // JSON array string
     pub duration_seconds: Option<i64>,
+    pub song_key: Option<String>, // キー（調）: 例 "C", "F#m"
     pub created_at: String,
     pub updated_at: String,
 }
@@ -27,6 +28,7 @@ impl Song {
             category: None,
             tags: None,
             duration_seconds: None,
+            song_key: None,
             created_at: now.clone(),
             updated_at: now,
         }
@@ -134,4 +136,5 @@ pub struct CommentLog {
     pub message_data: Option<String>, // JSON
     pub published_at: String,
     pub created_at: String,
+    pub is_filtered: bool, // ブロックリスト・BANで配信から除外
 }
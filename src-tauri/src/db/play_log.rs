@@ -0,0 +1,188 @@
+//! 歌唱履歴
+//!
+//! `set_current_song`・`next_song`等で曲が現在の曲になるたびにsong_play_logテーブルへ記録し、
+//! 配信後の「今日歌った曲」一覧を提供する。
+//! 同じ曲を短時間に繰り返し現在の曲にした場合（操作のやり直し等）は重複して記録しない。
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, SqliteConnection, SqlitePool};
+
+/// 同じ曲の重複記録とみなす間隔（秒）
+pub const PLAY_LOG_DEDUP_SECONDS: i64 = 60;
+
+/// 1回の取得で返す履歴の最大件数
+pub const MAX_PLAY_HISTORY: i64 = 1000;
+
+/// 歌唱履歴の1件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SongPlay {
+    pub id: i64,
+    pub setlist_id: String,
+    /// セットリスト名（セットリスト削除済みの場合はNone）
+    pub setlist_name: Option<String>,
+    pub song_id: String,
+    /// 記録時点の曲名
+    pub title: String,
+    /// 記録時点のアーティスト
+    pub artist: Option<String>,
+    /// 現在の曲になった時刻（UTC、RFC3339）
+    pub played_at: String,
+}
+
+/// セットリストの指定位置の曲を歌唱履歴に記録
+///
+/// 直前の記録が同じ曲で、`PLAY_LOG_DEDUP_SECONDS`以内の場合は記録しない。
+/// 曲の切り替えと同じトランザクション内で呼び出す
+pub async fn record_play(
+    conn: &mut SqliteConnection,
+    setlist_id: &str,
+    position: i64,
+    played_at: DateTime<Utc>,
+) -> Result<(), String> {
+    let song: Option<(String, String, String, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT ss.id, s.id, s.title, s.artist
+        FROM setlist_songs ss
+        JOIN songs s ON ss.song_id = s.id
+        WHERE ss.setlist_id = ? AND ss.position = ?
+        "#,
+    )
+    .bind(setlist_id)
+    .bind(position)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+    let Some((setlist_song_id, song_id, title, artist)) = song else {
+        return Ok(());
+    };
+
+    let last: Option<(String, String)> = sqlx::query_as(
+        "SELECT song_id, played_at FROM song_play_log WHERE setlist_id = ? ORDER BY id DESC LIMIT 1",
+    )
+    .bind(setlist_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+    if let Some((last_song_id, last_played_at)) = last {
+        let recent = DateTime::parse_from_rfc3339(&last_played_at).is_ok_and(|last_played_at| {
+            played_at.signed_duration_since(last_played_at) < Duration::seconds(PLAY_LOG_DEDUP_SECONDS)
+        });
+        if last_song_id == song_id && recent {
+            log::debug!("Skipped duplicate play log: {} ({})", title, setlist_id);
+            return Ok(());
+        }
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO song_play_log (setlist_id, setlist_song_id, song_id, title, artist, played_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(setlist_id)
+    .bind(&setlist_song_id)
+    .bind(&song_id)
+    .bind(&title)
+    .bind(&artist)
+    .bind(played_at.to_rfc3339())
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+    Ok(())
+}
+
+/// 期間を指定して歌唱履歴を古い順に取得（最大`MAX_PLAY_HISTORY`件）
+///
+/// `from`以上・`to`未満の記録を返す。省略した側は制限しない
+pub async fn load_history(
+    pool: &SqlitePool,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<Vec<SongPlay>, String> {
+    // 記録時刻の表記（小数秒の桁数等）の違いに影響されないようjulianday()で比較する
+    sqlx::query_as::<_, SongPlay>(
+        r#"
+        SELECT l.id, l.setlist_id, sl.name AS setlist_name, l.song_id, l.title, l.artist, l.played_at
+        FROM song_play_log l
+        LEFT JOIN setlists sl ON l.setlist_id = sl.id
+        WHERE (?1 IS NULL OR julianday(l.played_at) >= julianday(?1))
+          AND (?2 IS NULL OR julianday(l.played_at) < julianday(?2))
+        ORDER BY julianday(l.played_at), l.id
+        LIMIT ?3
+        "#,
+    )
+    .bind(from.map(|from| from.to_rfc3339()))
+    .bind(to.map(|to| to.to_rfc3339()))
+    .bind(MAX_PLAY_HISTORY)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::create_test_setlist;
+
+    async fn record(pool: &SqlitePool, position: i64, played_at: DateTime<Utc>) {
+        let mut conn = pool.acquire().await.unwrap();
+        record_play(&mut conn, "setlist1", position, played_at).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_record_play_skips_repeated_song() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let pool = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        create_test_setlist(&pool, 2).await;
+        let start = Utc::now();
+
+        record(&pool, 0, start).await;
+        // 同じ曲を短時間に再度設定しても記録しない
+        record(&pool, 0, start + Duration::seconds(10)).await;
+        record(&pool, 1, start + Duration::seconds(20)).await;
+        // 間隔を空けて戻った場合は記録する
+        record(&pool, 0, start + Duration::seconds(20 + PLAY_LOG_DEDUP_SECONDS)).await;
+        // 存在しない位置は記録しない
+        record(&pool, 5, start + Duration::seconds(200)).await;
+
+        let history = load_history(&pool, None, None).await.unwrap();
+        let titles: Vec<&str> = history.iter().map(|play| play.title.as_str()).collect();
+        assert_eq!(titles, vec!["Song 0", "Song 1", "Song 0"]);
+        assert_eq!(history[0].setlist_name.as_deref(), Some("Live"));
+        assert_eq!(history[0].artist.as_deref(), Some("Artist"));
+    }
+
+    #[tokio::test]
+    async fn test_load_history_filters_by_range() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let pool = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        create_test_setlist(&pool, 2).await;
+        let start = Utc::now();
+
+        record(&pool, 0, start).await;
+        record(&pool, 1, start + Duration::hours(1)).await;
+        record(&pool, 0, start + Duration::hours(2)).await;
+
+        let history = load_history(
+            &pool,
+            Some(start + Duration::minutes(30)),
+            Some(start + Duration::hours(2)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].song_id, "song1");
+
+        // セットリストを削除しても履歴は残る
+        sqlx::query("DELETE FROM setlists").execute(&pool).await.unwrap();
+        let history = load_history(&pool, Some(start), None).await.unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].setlist_name, None);
+    }
+}
@@ -0,0 +1,138 @@
+//! スパチャの記録
+//!
+//! 配信中に受信したスパチャを配信セッションID（live_sessionsのID）とともにsuperchat_logテーブルへ記録し、
+//! 過去のセッションの支援者ランキングを提供する。
+//! 同じメッセージIDのスパチャは1回だけ記録する（ポーリングの再取得等による重複を防ぐ）。
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+
+use crate::server::types::{LeaderboardEntry, SuperchatPayload};
+
+/// スパチャを1件記録（記録した場合はtrue、記録済みの場合はfalse）
+///
+/// `published_at`はスパチャのメッセージの投稿時刻
+pub async fn record_superchat(
+    pool: &SqlitePool,
+    session_id: i64,
+    payload: &SuperchatPayload,
+    amount_jpy: u64,
+    published_at: DateTime<Utc>,
+) -> Result<bool, String> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO superchat_log
+            (session_id, message_id, author_channel_id, author_name, amount_micros, currency, amount_jpy, published_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(message_id) DO NOTHING
+        "#,
+    )
+    .bind(session_id)
+    .bind(&payload.id)
+    .bind(&payload.author_channel_id)
+    .bind(&payload.author_name)
+    .bind(i64::try_from(payload.amount_micros).unwrap_or(i64::MAX))
+    .bind(&payload.currency)
+    .bind(i64::try_from(amount_jpy).unwrap_or(i64::MAX))
+    .bind(published_at.to_rfc3339())
+    .execute(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// 配信セッションの支援者ランキングを合計額（日本円換算）の多い順に取得
+///
+/// 同額の場合は先にスパチャした投稿者を上位にする。投稿者名は最後のスパチャ時点の名前
+pub async fn load_leaderboard(
+    pool: &SqlitePool,
+    session_id: i64,
+    limit: u32,
+) -> Result<Vec<LeaderboardEntry>, String> {
+    let rows: Vec<(String, String, i64, i64)> = sqlx::query_as(
+        r#"
+        SELECT
+            l.author_channel_id,
+            (SELECT n.author_name FROM superchat_log n
+             WHERE n.session_id = l.session_id AND n.author_channel_id = l.author_channel_id
+             ORDER BY n.id DESC LIMIT 1) AS author_name,
+            SUM(l.amount_jpy) AS total_jpy,
+            COUNT(*) AS count
+        FROM superchat_log l
+        WHERE l.session_id = ?
+        GROUP BY l.author_channel_id
+        ORDER BY total_jpy DESC, MIN(l.id)
+        LIMIT ?
+        "#,
+    )
+    .bind(session_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(author_channel_id, author_name, total_jpy, count)| LeaderboardEntry {
+            author_name,
+            author_channel_id,
+            total_jpy: u64::try_from(total_jpy).unwrap_or(0),
+            count: u32::try_from(count).unwrap_or(u32::MAX),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::create_pool;
+    use tempfile::TempDir;
+
+    fn superchat(id: &str, channel_id: &str, name: &str, amount_micros: u64, currency: &str) -> SuperchatPayload {
+        SuperchatPayload {
+            amount_micros,
+            currency: currency.to_string(),
+            ..SuperchatPayload::test_payload(id, channel_id, name)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_leaderboard_groups_by_author_within_session() {
+        let dir = TempDir::new().unwrap();
+        let pool = create_pool(dir.path().join("app.db").to_str().unwrap()).await.unwrap();
+        let now = Utc::now();
+
+        let records = [
+            (1, superchat("sc1", "UC_a", "Alice", 500_000_000, "JPY"), 500),
+            (1, superchat("sc2", "UC_b", "Bob", 1_000_000_000, "JPY"), 1000),
+            (1, superchat("sc3", "UC_a", "Alice2", 5_000_000, "USD"), 750),
+            (1, superchat("sc4", "UC_c", "Carol", 250_000_000, "JPY"), 250),
+            (2, superchat("sc5", "UC_c", "Carol", 10_000_000_000, "JPY"), 10_000),
+        ];
+        for (session_id, payload, jpy) in &records {
+            assert!(record_superchat(&pool, *session_id, payload, *jpy, now).await.unwrap());
+        }
+        // 同じメッセージIDは記録しない
+        assert!(!record_superchat(&pool, 1, &records[0].1, 500, now).await.unwrap());
+
+        let entries = load_leaderboard(&pool, 1, 2).await.unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                LeaderboardEntry {
+                    author_name: "Alice2".to_string(),
+                    author_channel_id: "UC_a".to_string(),
+                    total_jpy: 1250,
+                    count: 2,
+                },
+                LeaderboardEntry {
+                    author_name: "Bob".to_string(),
+                    author_channel_id: "UC_b".to_string(),
+                    total_jpy: 1000,
+                    count: 1,
+                },
+            ]
+        );
+        assert!(load_leaderboard(&pool, 99, 10).await.unwrap().is_empty());
+    }
+}
@@ -18,6 +18,9 @@ const SERVICE_NAME: &str = "com.vtuber-overlay-suite.desktop";
 /// YouTube APIキー用のエントリ名
 const YOUTUBE_API_KEY_ENTRY: &str = "youtube_api_key";
 
+/// WebSocket認証トークン用のエントリ名
+const WS_AUTH_TOKEN_ENTRY: &str = "websocket_auth_token";
+
 #[derive(Debug, Error)]
 pub enum KeyringError {
     #[error("Keyring error: {0}")]
@@ -90,6 +93,28 @@ pub fn has_api_key() -> Result<bool, KeyringError> {
     }
 }
 
+// =============================================================================
+// WebSocket認証トークン操作
+// =============================================================================
+
+/// WebSocket認証トークンをセキュアストレージに保存
+pub fn save_ws_auth_token(token: &str) -> Result<(), KeyringError> {
+    let entry = Entry::new(SERVICE_NAME, WS_AUTH_TOKEN_ENTRY)?;
+    entry.set_password(token)?;
+    log::info!("WebSocket auth token saved to secure storage");
+    Ok(())
+}
+
+/// WebSocket認証トークンをセキュアストレージから取得
+pub fn get_ws_auth_token() -> Result<String, KeyringError> {
+    let entry = Entry::new(SERVICE_NAME, WS_AUTH_TOKEN_ENTRY)?;
+    match entry.get_password() {
+        Ok(token) => Ok(token),
+        Err(keyring::Error::NoEntry) => Err(KeyringError::NotFound),
+        Err(e) => Err(KeyringError::KeyringError(e)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
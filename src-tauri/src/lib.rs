@@ -2,7 +2,9 @@ mod commands;
 mod config;
 mod db;
 mod keyring;
+mod membership;
 mod server;
+mod shutdown;
 mod superchat;
 pub mod util; // doctestのためpubにする
 mod weather;
@@ -33,20 +35,36 @@ pub fn run() {
   // manageに渡す用にcloneしておく
   let server_state_for_manage = Arc::clone(&server_state);
 
+  // HTTPS/WSS用の自己署名証明書の保存先
+  let tls_dir = dirs::data_dir()
+    .expect("Failed to get data directory")
+    .join(APP_IDENTIFIER)
+    .join(server::tls::TLS_DIR);
+
   // データベース初期化（setup前に実行）
-  let db_pool = {
+  let (db_pool, restore_result) = {
     let app_dir = dirs::data_dir()
       .expect("Failed to get data directory");
     let app_dir_path = app_dir.join(APP_IDENTIFIER);
     std::fs::create_dir_all(&app_dir_path).expect("Failed to create app data directory");
     let db_path = app_dir_path.join("app.db");
-    tauri::async_runtime::block_on(async {
+    // 復元待ちのバックアップがあれば接続前に差し替える（restore_database）
+    // ロガー初期化前のため、結果はsetupでログに出力する
+    let restore_result = tauri::async_runtime::block_on(db::backup::apply_pending_restore(&db_path));
+    let pool = tauri::async_runtime::block_on(async {
       db::create_pool(db_path.to_str().unwrap())
         .await
         .expect("Failed to create database pool")
-    })
+    });
+    (pool, restore_result)
   };
 
+  // スキーマ自己診断（マイグレーション未適用のまま後続クエリが失敗するのを防ぐ）
+  // 失敗時はDB依存の起動処理（保存済み設定の読み込み・サーバー起動・コメント保存）をスキップする
+  let schema_error = tauri::async_runtime::block_on(db::verify_schema(&db_pool)).err();
+  let schema_ready = schema_error.is_none();
+  db::set_schema_ready(schema_ready);
+
   // HTTPサーバー用にdb_poolをclone
   let db_pool_for_http = db_pool.clone();
   // WebSocketサーバー用にdb_poolをclone
@@ -65,6 +83,38 @@ pub fn run() {
         )?;
       }
 
+      match &restore_result {
+        Ok(true) => log::info!("Database restored from backup"),
+        Ok(false) => {}
+        Err(e) => log::error!("Failed to restore database from backup: {}", e),
+      }
+
+      // 同梱キーなしのビルドではここで警告する
+      match youtube::api_key_manager::get_api_key_manager().read() {
+        Ok(manager) => manager.log_startup_summary(),
+        Err(e) => log::error!("Failed to read API key manager: {}", e),
+      }
+
+      if let Some(e) = &schema_error {
+        log::error!(
+          "Database schema check failed, skipped loading saved settings and starting servers: {}",
+          e
+        );
+      }
+
+      // スキーマが不完全な状態では以降のDB依存の起動処理（ログ削除・HTTP/WebSocketサーバー等）を行わない
+      if !schema_ready {
+        return Ok(());
+      }
+
+      // 保持期間を過ぎたコメントログを削除（起動をブロックしないようバックグラウンドで実行）
+      let prune_db = db_pool_for_http.clone();
+      tauri::async_runtime::spawn(async move {
+        if let Err(e) = commands::comment_log::prune_expired_comment_logs(&prune_db).await {
+          log::warn!("Failed to prune comment logs: {}", e);
+        }
+      });
+
       // HTTPサーバーを起動（DB接続付き）
       let http_db = db_pool_for_http.clone();
       
@@ -81,34 +131,227 @@ pub fn run() {
       };
       
       log::info!("Overlays directory: {:?}", overlays_dir);
-      
-      tauri::async_runtime::spawn(async move {
-        if let Err(e) = server::start_http_server_with_db(http_db, overlays_dir).await {
-          log::error!("HTTP server error: {}", e);
-        }
+
+      // HTTPS/WSSが有効なら証明書を読み込み（初回は生成）、失敗時はHTTP/WSで起動する
+      let tls_acceptor =
+        match tauri::async_runtime::block_on(server::tls::load_server_tls_enabled(&db_pool_for_http)) {
+          Ok(true) => match server::tls::load_or_create_acceptor(&tls_dir) {
+            Ok(acceptor) => Some(acceptor),
+            Err(e) => {
+              log::error!("Failed to set up TLS, serving overlays over plain HTTP: {}", e);
+              None
+            }
+          },
+          Ok(false) => None,
+          Err(e) => {
+            log::warn!("Failed to load server TLS setting: {}", e);
+            None
+          }
+        };
+
+      // 保存済みのポートで待ち受け（使用中の場合は続く空きポート）、実際のアドレスを記録する
+      let server_ports =
+        tauri::async_runtime::block_on(server::addresses::load_server_ports(&db_pool_for_http))
+          .unwrap_or_else(|e| {
+            log::warn!("Failed to load server ports: {}", e);
+            server::addresses::ServerPorts::default()
+          });
+      // 待ち受けアドレスはデフォルトでループバックのみ（別PCのOBSから参照する場合は0.0.0.0等を設定）
+      let bind_address =
+        tauri::async_runtime::block_on(server::addresses::load_bind_address(&db_pool_for_http))
+          .unwrap_or_else(|e| {
+            log::warn!("Failed to load server bind address: {}", e);
+            server::addresses::DEFAULT_BIND_ADDRESS
+          });
+      log::info!("Server bind address: {}", bind_address);
+      let (http_listener, ws_listener) = tauri::async_runtime::block_on(async {
+        let http = server::addresses::bind_with_fallback(bind_address, server_ports.http).await;
+        let ws = server::addresses::bind_with_fallback(bind_address, server_ports.ws).await;
+        (http, ws)
       });
+      let bound_port = |listener: &Result<tokio::net::TcpListener, String>| -> Result<u16, String> {
+        let listener = listener.as_ref().map_err(Clone::clone)?;
+        listener.local_addr().map(|addr| addr.port()).map_err(|e| e.to_string())
+      };
+      server::addresses::record(
+        tls_acceptor.is_some(),
+        bind_address,
+        bound_port(&http_listener),
+        bound_port(&ws_listener),
+      );
+
+      // オーバーレイページの認証にはWebSocketサーバーと同じトークンを使用
+      match http_listener {
+        Ok(listener) => {
+          let http_server_state = Arc::clone(&server_state);
+          let http_tls = tls_acceptor.clone();
+          tauri::async_runtime::spawn(async move {
+            let auth_token = http_server_state.read().await.auth_token_handle();
+            if let Err(e) =
+              server::start_http_server_with_db(listener, http_db, overlays_dir, auth_token, http_tls).await
+            {
+              log::error!("HTTP server error: {}", e);
+            }
+          });
+        }
+        Err(e) => log::error!("Failed to start HTTP server: {}", e),
+      }
 
       // WebSocketサーバーを起動（Tauriのランタイム内で起動）
-      {
-        let state_clone = Arc::clone(&server_state);
-        let ws_db = db_pool_for_ws.clone();
-        tauri::async_runtime::spawn(async move {
-          if let Err(e) = server::start_websocket_server(state_clone, ws_db).await {
-            log::error!("WebSocket server error: {}", e);
-          }
-        });
+      match ws_listener {
+        Ok(listener) => {
+          let state_clone = Arc::clone(&server_state);
+          let ws_db = db_pool_for_ws.clone();
+          tauri::async_runtime::spawn(async move {
+            if let Err(e) = server::start_websocket_server(listener, state_clone, ws_db, tls_acceptor).await {
+              log::error!("WebSocket server error: {}", e);
+            }
+          });
+        }
+        Err(e) => log::error!("Failed to start WebSocket server: {}", e),
       }
 
+      // チャット流速を定期的にブロードキャスト
+      tauri::async_runtime::spawn(server::chat_rate::run_chat_rate_broadcaster(Arc::clone(&server_state)));
+
       Ok(())
     })
     .manage({
       // 天気クライアントを作成（Open-Meteo APIはAPIキー不要）
       let weather_client = Arc::new(weather::WeatherClient::new());
 
-      // 天気自動更新タスクを開始（15分ごとにブロードキャスト）
+      // 保存済みのアイコン上書き設定・固定した緯度経度・スパチャ表示設定・ブロックリスト・BANリストを反映
+      let weather_update_interval = tauri::async_runtime::block_on(async {
+        if !schema_ready {
+          return weather::DEFAULT_UPDATE_INTERVAL_MINUTES;
+        }
+        match commands::weather::load_weather_icon_map(&db_pool).await {
+          Ok(icon_map) => weather_client.set_icon_map(icon_map).await,
+          Err(e) => log::warn!("Failed to load weather icon map: {}", e),
+        }
+        match commands::weather::load_pinned_city_coords(&db_pool).await {
+          Ok(pinned) => {
+            for coords in pinned {
+              weather_client
+                .pin_city_coords(&coords.city, coords.latitude, coords.longitude, coords.display_name)
+                .await;
+            }
+          }
+          Err(e) => log::warn!("Failed to load pinned weather coords: {}", e),
+        }
+        match commands::weather::load_weather_cache_ttl(&db_pool).await {
+          Ok(minutes) => {
+            weather_client.set_cache_ttl_minutes(minutes);
+          }
+          Err(e) => log::warn!("Failed to load weather cache TTL: {}", e),
+        }
+        match commands::superchat::load_superchat_config(&db_pool).await {
+          Ok(config) => superchat::set_config(config),
+          Err(e) => log::warn!("Failed to load superchat config: {}", e),
+        }
+        match commands::superchat::load_tier_thresholds(&db_pool).await {
+          Ok(thresholds) => superchat::set_tier_thresholds(thresholds),
+          Err(e) => log::warn!("Failed to load tier thresholds: {}", e),
+        }
+        // WebSocket認証が有効ならトークンを読み込み（初回は生成してkeyringに保存）
+        match server::ws_auth::load_ws_auth_enabled(&db_pool).await {
+          Ok(true) => {
+            let token = server::ws_auth::load_or_create_token().await;
+            server_state_for_manage.read().await.set_auth_token(Some(token));
+          }
+          Ok(false) => {}
+          Err(e) => log::warn!("Failed to load WebSocket auth setting: {}", e),
+        }
+        match commands::overlay::load_replay_buffer_size(&db_pool).await {
+          Ok(size) => server_state_for_manage.read().await.set_replay_buffer_size(size).await,
+          Err(e) => log::warn!("Failed to load replay buffer size: {}", e),
+        }
+        match commands::overlay::load_fallback_avatar_enabled(&db_pool).await {
+          Ok(enabled) => server_state_for_manage.read().await.set_fallback_avatar_enabled(enabled),
+          Err(e) => log::warn!("Failed to load fallback avatar setting: {}", e),
+        }
+        match server::cors::load_allowed_origins(&db_pool).await {
+          Ok(origins) => server::cors::set_allowed_origins(origins),
+          Err(e) => log::warn!("Failed to load CORS allowed origins: {}", e),
+        }
+        match commands::overlay::load_milestone_thresholds(&db_pool).await {
+          Ok(thresholds) => server_state_for_manage.read().await.set_milestone_thresholds(thresholds),
+          Err(e) => log::warn!("Failed to load milestone thresholds: {}", e),
+        }
+        match commands::overlay::load_chat_rate_window(&db_pool).await {
+          Ok(window_secs) => server_state_for_manage.read().await.set_chat_rate_window_secs(window_secs),
+          Err(e) => log::warn!("Failed to load chat rate window: {}", e),
+        }
+        match commands::overlay::load_comment_throttle(&db_pool).await {
+          Ok(rate) => server_state_for_manage.read().await.set_comment_throttle_rate(rate),
+          Err(e) => log::warn!("Failed to load comment throttle: {}", e),
+        }
+        match commands::overlay::load_first_time_scope(&db_pool).await {
+          Ok(scope) => youtube::first_time::set_scope(scope),
+          Err(e) => log::warn!("Failed to load first-time chatter scope: {}", e),
+        }
+        match commands::overlay::load_translation_settings(&db_pool).await {
+          Ok(settings) => youtube::translation::set_settings(settings),
+          Err(e) => log::warn!("Failed to load translation settings: {}", e),
+        }
+        match commands::superchat::load_superchat_slot(&db_pool).await {
+          Ok(slot) => superchat::set_slot(slot),
+          Err(e) => log::warn!("Failed to load superchat slot: {}", e),
+        }
+        match superchat::goal::load_goal(&db_pool).await {
+          Ok(goal) => {
+            if let Err(e) = superchat::goal::apply_goal(&db_pool, &server_state_for_manage, goal).await {
+              log::warn!("Failed to restore superchat goal: {}", e);
+            }
+          }
+          Err(e) => log::warn!("Failed to load superchat goal: {}", e),
+        }
+        match commands::comment_filter::load_comment_filter(&db_pool).await {
+          Ok(filter) => youtube::comment_filter::set_filter(filter),
+          Err(e) => log::warn!("Failed to load comment blocklist: {}", e),
+        }
+        match commands::comment_filter::load_profanity_masker(&db_pool).await {
+          Ok(masker) => youtube::profanity::set_masker(masker),
+          Err(e) => log::warn!("Failed to load profanity mask config: {}", e),
+        }
+        match commands::comment_filter::load_severity_classifier(&db_pool).await {
+          Ok(classifier) => youtube::severity::set_classifier(classifier),
+          Err(e) => log::warn!("Failed to load severity config: {}", e),
+        }
+        match commands::comment_filter::load_banned_channels(&db_pool).await {
+          Ok(channel_ids) => youtube::channel_ban::set_banned_channels(channel_ids),
+          Err(e) => log::warn!("Failed to load banned channels: {}", e),
+        }
+        match commands::youtube::load_label_locale(&db_pool).await {
+          Ok(locale) => youtube::labels::set_locale(locale),
+          Err(e) => log::warn!("Failed to load chat label locale: {}", e),
+        }
+        match commands::youtube::load_quota_budget(&db_pool).await {
+          Ok(budget) => youtube::state::set_daily_quota_budget(budget),
+          Err(e) => log::warn!("Failed to load daily quota budget: {}", e),
+        }
+        match commands::youtube::load_dedupe_settings(&db_pool).await {
+          Ok(settings) => youtube::dedupe::set_dedupe_settings(settings),
+          Err(e) => log::warn!("Failed to load InnerTube dedupe settings: {}", e),
+        }
+        match commands::youtube::load_innertube_overrides(&db_pool).await {
+          Ok(overrides) => youtube::innertube::overrides::set_overrides(overrides),
+          Err(e) => log::warn!("Failed to load InnerTube overrides: {}", e),
+        }
+        match commands::weather::load_weather_update_interval(&db_pool).await {
+          Ok(minutes) => minutes,
+          Err(e) => {
+            log::warn!("Failed to load weather update interval: {}", e);
+            weather::DEFAULT_UPDATE_INTERVAL_MINUTES
+          }
+        }
+      });
+
+      // 天気自動更新タスクを開始（保存済みの間隔、デフォルト15分ごとにブロードキャスト）
       let weather_updater = Arc::new(weather::WeatherAutoUpdater::start(
         Arc::clone(&weather_client),
         Arc::clone(&server_state_for_manage),
+        weather_update_interval,
       ));
 
       AppState {
@@ -129,14 +372,20 @@ pub fn run() {
           commands::youtube::get_chat_messages,
           commands::youtube::start_polling,
           commands::youtube::stop_polling,
+          commands::youtube::pause_polling,
+          commands::youtube::resume_polling,
+          commands::youtube::is_polling_paused,
           commands::youtube::get_polling_state,
           commands::youtube::get_quota_info,
           commands::youtube::is_polling_running,
           commands::youtube::send_test_comment,
+          commands::youtube::send_test_superchat,
           commands::youtube::save_polling_state,
           commands::youtube::load_polling_state,
           commands::youtube::save_wizard_settings,
           commands::youtube::load_wizard_settings,
+          commands::youtube::get_wizard_settings_expiry_hours,
+          commands::youtube::set_wizard_settings_expiry_hours,
           commands::setlist::get_songs,
           commands::setlist::create_song,
           commands::setlist::update_song,
@@ -148,6 +397,9 @@ pub fn run() {
           commands::setlist::remove_song_from_setlist,
           commands::setlist::get_setlist_with_songs,
           commands::setlist::set_current_song,
+          commands::setlist::jump_to_song_index,
+          commands::setlist::get_play_history,
+          commands::song_import::import_songs,
           commands::setlist::next_song,
           commands::setlist::previous_song,
           commands::setlist::reorder_setlist_songs,
@@ -159,6 +411,45 @@ pub fn run() {
           commands::overlay::save_overlay_settings,
           commands::overlay::load_overlay_settings,
           commands::overlay::broadcast_settings_update,
+          commands::overlay::set_broadcast_bundling,
+          commands::overlay::get_broadcast_bundling,
+          commands::overlay::set_replay_buffer_size,
+          commands::overlay::get_replay_buffer_size,
+          commands::overlay::set_fallback_avatar_enabled,
+          commands::overlay::get_fallback_avatar_enabled,
+          commands::overlay::set_comment_theme,
+          commands::overlay::get_comment_theme,
+          commands::overlay::set_milestone_thresholds,
+          commands::overlay::get_milestone_thresholds,
+          commands::overlay::reset_milestones,
+          commands::overlay::set_chat_rate_window,
+          commands::overlay::get_chat_rate_window,
+          commands::overlay::set_comment_throttle,
+          commands::overlay::get_comment_throttle,
+          commands::pin::pin_comment,
+          commands::pin::announce,
+          commands::pin::unpin_message,
+          commands::pin::get_pinned_message,
+          commands::overlay::get_chat_rate,
+          commands::overlay::set_first_time_scope,
+          commands::overlay::get_first_time_scope,
+          commands::overlay::set_translation_settings,
+          commands::overlay::get_translation_settings,
+          commands::overlay::get_connected_overlays,
+          commands::overlay::connected_overlay_count,
+          commands::overlay::get_ws_auth_settings,
+          commands::overlay::set_ws_auth_enabled,
+          commands::overlay::rotate_ws_auth_token,
+          commands::overlay::get_server_tls_settings,
+          commands::overlay::set_server_tls_enabled,
+          commands::overlay::get_server_addresses,
+          commands::overlay::get_server_ports,
+          commands::overlay::set_server_ports,
+          commands::overlay::get_server_bind_address,
+          commands::overlay::set_server_bind_address,
+          commands::overlay::get_cors_allowed_origins,
+          commands::overlay::set_cors_allowed_origins,
+          commands::overlay::run_self_test,
           commands::queue::get_queue_state,
           commands::queue::save_queue_state,
           commands::queue::add_queue_item,
@@ -178,10 +469,22 @@ pub fn run() {
           commands::promo::save_and_broadcast_promo,
           commands::brand::get_brand_settings,
           commands::brand::save_brand_settings,
+          commands::superchat::set_superchat_durations,
+          commands::superchat::get_superchat_durations,
+          commands::superchat::set_tier_thresholds,
+          commands::superchat::get_tier_thresholds,
+          commands::superchat::validate_superchat_config,
+          commands::superchat::set_superchat_slot,
+          commands::superchat::get_superchat_slot,
+          commands::superchat::set_superchat_goal,
+          commands::superchat::get_superchat_goal,
+          commands::superchat::superchat_queue_length,
           commands::brand::broadcast_brand_update,
           commands::brand::save_and_broadcast_brand,
           commands::template::validate_template,
           commands::template::get_default_template,
+          commands::template::validate_all_templates,
+          commands::template::preview_text_template,
           commands::youtube::save_api_mode,
           commands::youtube::load_api_mode,
           commands::youtube::test_innertube_connection,
@@ -190,6 +493,7 @@ pub fn run() {
           commands::youtube::is_polling_innertube_running,
           commands::youtube::get_api_key_status,
           commands::youtube::has_bundled_api_key,
+          commands::youtube::get_api_key_sources,
           commands::youtube::set_byok_key,
           commands::youtube::get_active_api_key,
           commands::youtube::switch_to_secondary_key,
@@ -199,18 +503,80 @@ pub fn run() {
           commands::youtube::is_unified_polling_running,
           commands::youtube::get_unified_polling_mode,
           commands::youtube::get_live_stream_stats,
+          commands::youtube::get_stream_uptime,
+          commands::youtube::broadcast_stream_uptime,
           commands::youtube::broadcast_kpi_update,
           commands::youtube::fetch_and_broadcast_viewer_count,
+          commands::youtube::get_kpi_history,
+          commands::youtube::start_kpi_sampler,
+          commands::youtube::stop_kpi_sampler,
+          commands::youtube::is_kpi_sampler_running,
+          commands::youtube::broadcast_session_recap,
+          commands::youtube::get_session_superchat_history,
+          commands::youtube::get_superchat_leaderboard,
+          commands::youtube::preview_comment_render,
+          commands::youtube::get_chat_settings,
+          commands::youtube::set_chat_label_locale,
+          commands::youtube::get_chat_label_locale,
+          commands::youtube::set_quota_budget,
+          commands::youtube::get_quota_budget,
+          commands::youtube::set_dedupe_settings,
+          commands::youtube::get_dedupe_settings,
+          commands::youtube::get_dedupe_stats,
+          commands::youtube::set_innertube_overrides,
+          commands::youtube::reset_innertube_overrides,
+          commands::youtube::get_innertube_overrides,
+          commands::youtube::diagnose_innertube,
+          commands::comment_filter::set_comment_blocklist,
+          commands::comment_filter::get_comment_blocklist,
+          commands::comment_filter::set_profanity_mask_config,
+          commands::comment_filter::get_profanity_mask_config,
+          commands::comment_filter::set_severity_config,
+          commands::comment_filter::get_severity_config,
+          commands::comment_filter::ban_channel,
+          commands::comment_filter::unban_channel,
+          commands::comment_filter::list_banned_channels,
+          commands::export::export_comment_logs,
+          commands::backup::backup_database,
+          commands::backup::restore_database,
+          commands::backup::export_settings,
+          commands::backup::import_settings,
+          commands::comment_log::set_comment_log_retention_days,
+          commands::comment_log::get_comment_log_retention_days,
+          commands::comment_log::prune_comment_logs,
+          commands::comment_log::get_comment_histogram,
+          commands::comment_log::get_comment_write_queue_stats,
+          commands::youtube::fetch_emoji_catalog,
           // fetch_viewer_count_innertube: デバッグ用（InnerTube APIでviewCount取得）
           // 本番ではKPI取得は常に同梱APIキーを使用するため、フロントエンドからは呼ばれない
           commands::youtube::fetch_viewer_count_innertube,
+          // get_emoji_cache_stats: デバッグ用（絵文字キャッシュのサイズ・ヒット率）
+          commands::youtube::get_emoji_cache_stats,
           commands::weather::set_weather_city,
           commands::weather::get_weather_city,
+          commands::weather::search_cities,
+          commands::weather::set_city_coords,
+          commands::weather::unpin_city_coords,
+          commands::weather::get_pinned_city_coords,
+          commands::weather::set_weather_units,
+          commands::weather::get_weather_units,
+          commands::weather::set_weather_max_concurrent_requests,
+          commands::weather::get_weather_max_concurrent_requests,
+          commands::weather::set_weather_retry_policy,
+          commands::weather::get_weather_retry_policy,
+          commands::weather::set_weather_icon_map,
+          commands::weather::get_weather_icon_map,
+          commands::weather::set_weather_update_interval,
+          commands::weather::get_weather_update_interval,
           commands::weather::get_weather,
           commands::weather::fetch_weather,
           commands::weather::broadcast_weather_update,
           commands::weather::clear_weather_cache,
           commands::weather::get_weather_cache_ttl,
+          commands::weather::set_weather_cache_ttl,
+          commands::weather::get_weather_cache_ttl_minutes,
+          commands::weather::get_weather_forecast,
+          commands::weather::broadcast_weather_forecast,
           commands::weather::refresh_weather,
           commands::weather::broadcast_weather,
           commands::weather::set_weather_city_and_broadcast,
@@ -218,6 +584,7 @@ pub fn run() {
           commands::weather::broadcast_weather_multi,
           commands::weather::set_multi_city_mode,
           commands::system::get_system_fonts,
+          commands::system::refresh_system_fonts,
         ]
       }
       // リリースビルドではtest_innertube_connection, fetch_viewer_count_innertubeを除外
@@ -231,14 +598,20 @@ pub fn run() {
           commands::youtube::get_chat_messages,
           commands::youtube::start_polling,
           commands::youtube::stop_polling,
+          commands::youtube::pause_polling,
+          commands::youtube::resume_polling,
+          commands::youtube::is_polling_paused,
           commands::youtube::get_polling_state,
           commands::youtube::get_quota_info,
           commands::youtube::is_polling_running,
           commands::youtube::send_test_comment,
+          commands::youtube::send_test_superchat,
           commands::youtube::save_polling_state,
           commands::youtube::load_polling_state,
           commands::youtube::save_wizard_settings,
           commands::youtube::load_wizard_settings,
+          commands::youtube::get_wizard_settings_expiry_hours,
+          commands::youtube::set_wizard_settings_expiry_hours,
           commands::setlist::get_songs,
           commands::setlist::create_song,
           commands::setlist::update_song,
@@ -250,6 +623,9 @@ pub fn run() {
           commands::setlist::remove_song_from_setlist,
           commands::setlist::get_setlist_with_songs,
           commands::setlist::set_current_song,
+          commands::setlist::jump_to_song_index,
+          commands::setlist::get_play_history,
+          commands::song_import::import_songs,
           commands::setlist::next_song,
           commands::setlist::previous_song,
           commands::setlist::reorder_setlist_songs,
@@ -261,6 +637,45 @@ pub fn run() {
           commands::overlay::save_overlay_settings,
           commands::overlay::load_overlay_settings,
           commands::overlay::broadcast_settings_update,
+          commands::overlay::set_broadcast_bundling,
+          commands::overlay::get_broadcast_bundling,
+          commands::overlay::set_replay_buffer_size,
+          commands::overlay::get_replay_buffer_size,
+          commands::overlay::set_fallback_avatar_enabled,
+          commands::overlay::get_fallback_avatar_enabled,
+          commands::overlay::set_comment_theme,
+          commands::overlay::get_comment_theme,
+          commands::overlay::set_milestone_thresholds,
+          commands::overlay::get_milestone_thresholds,
+          commands::overlay::reset_milestones,
+          commands::overlay::set_chat_rate_window,
+          commands::overlay::get_chat_rate_window,
+          commands::overlay::set_comment_throttle,
+          commands::overlay::get_comment_throttle,
+          commands::pin::pin_comment,
+          commands::pin::announce,
+          commands::pin::unpin_message,
+          commands::pin::get_pinned_message,
+          commands::overlay::get_chat_rate,
+          commands::overlay::set_first_time_scope,
+          commands::overlay::get_first_time_scope,
+          commands::overlay::set_translation_settings,
+          commands::overlay::get_translation_settings,
+          commands::overlay::get_connected_overlays,
+          commands::overlay::connected_overlay_count,
+          commands::overlay::get_ws_auth_settings,
+          commands::overlay::set_ws_auth_enabled,
+          commands::overlay::rotate_ws_auth_token,
+          commands::overlay::get_server_tls_settings,
+          commands::overlay::set_server_tls_enabled,
+          commands::overlay::get_server_addresses,
+          commands::overlay::get_server_ports,
+          commands::overlay::set_server_ports,
+          commands::overlay::get_server_bind_address,
+          commands::overlay::set_server_bind_address,
+          commands::overlay::get_cors_allowed_origins,
+          commands::overlay::set_cors_allowed_origins,
+          commands::overlay::run_self_test,
           commands::queue::get_queue_state,
           commands::queue::save_queue_state,
           commands::queue::add_queue_item,
@@ -280,10 +695,22 @@ pub fn run() {
           commands::promo::save_and_broadcast_promo,
           commands::brand::get_brand_settings,
           commands::brand::save_brand_settings,
+          commands::superchat::set_superchat_durations,
+          commands::superchat::get_superchat_durations,
+          commands::superchat::set_tier_thresholds,
+          commands::superchat::get_tier_thresholds,
+          commands::superchat::validate_superchat_config,
+          commands::superchat::set_superchat_slot,
+          commands::superchat::get_superchat_slot,
+          commands::superchat::set_superchat_goal,
+          commands::superchat::get_superchat_goal,
+          commands::superchat::superchat_queue_length,
           commands::brand::broadcast_brand_update,
           commands::brand::save_and_broadcast_brand,
           commands::template::validate_template,
           commands::template::get_default_template,
+          commands::template::validate_all_templates,
+          commands::template::preview_text_template,
           commands::youtube::save_api_mode,
           commands::youtube::load_api_mode,
           commands::youtube::start_polling_innertube,
@@ -291,6 +718,7 @@ pub fn run() {
           commands::youtube::is_polling_innertube_running,
           commands::youtube::get_api_key_status,
           commands::youtube::has_bundled_api_key,
+          commands::youtube::get_api_key_sources,
           commands::youtube::set_byok_key,
           commands::youtube::get_active_api_key,
           commands::youtube::switch_to_secondary_key,
@@ -300,17 +728,78 @@ pub fn run() {
           commands::youtube::is_unified_polling_running,
           commands::youtube::get_unified_polling_mode,
           commands::youtube::get_live_stream_stats,
+          commands::youtube::get_stream_uptime,
+          commands::youtube::broadcast_stream_uptime,
           commands::youtube::broadcast_kpi_update,
           commands::youtube::fetch_and_broadcast_viewer_count,
+          commands::youtube::get_kpi_history,
+          commands::youtube::start_kpi_sampler,
+          commands::youtube::stop_kpi_sampler,
+          commands::youtube::is_kpi_sampler_running,
+          commands::youtube::broadcast_session_recap,
+          commands::youtube::get_session_superchat_history,
+          commands::youtube::get_superchat_leaderboard,
+          commands::youtube::preview_comment_render,
+          commands::youtube::get_chat_settings,
+          commands::youtube::set_chat_label_locale,
+          commands::youtube::get_chat_label_locale,
+          commands::youtube::set_quota_budget,
+          commands::youtube::get_quota_budget,
+          commands::youtube::set_dedupe_settings,
+          commands::youtube::get_dedupe_settings,
+          commands::youtube::get_dedupe_stats,
+          commands::youtube::set_innertube_overrides,
+          commands::youtube::reset_innertube_overrides,
+          commands::youtube::get_innertube_overrides,
+          commands::youtube::diagnose_innertube,
+          commands::comment_filter::set_comment_blocklist,
+          commands::comment_filter::get_comment_blocklist,
+          commands::comment_filter::set_profanity_mask_config,
+          commands::comment_filter::get_profanity_mask_config,
+          commands::comment_filter::set_severity_config,
+          commands::comment_filter::get_severity_config,
+          commands::comment_filter::ban_channel,
+          commands::comment_filter::unban_channel,
+          commands::comment_filter::list_banned_channels,
+          commands::export::export_comment_logs,
+          commands::backup::backup_database,
+          commands::backup::restore_database,
+          commands::backup::export_settings,
+          commands::backup::import_settings,
+          commands::comment_log::set_comment_log_retention_days,
+          commands::comment_log::get_comment_log_retention_days,
+          commands::comment_log::prune_comment_logs,
+          commands::comment_log::get_comment_histogram,
+          commands::comment_log::get_comment_write_queue_stats,
+          commands::youtube::fetch_emoji_catalog,
           // fetch_viewer_count_innertube: リリースビルドでは除外
           // KPI取得は常に同梱APIキーを使用するため不要
+          // get_emoji_cache_stats: リリースビルドでは除外（デバッグ用）
           commands::weather::set_weather_city,
           commands::weather::get_weather_city,
+          commands::weather::search_cities,
+          commands::weather::set_city_coords,
+          commands::weather::unpin_city_coords,
+          commands::weather::get_pinned_city_coords,
+          commands::weather::set_weather_units,
+          commands::weather::get_weather_units,
+          commands::weather::set_weather_max_concurrent_requests,
+          commands::weather::get_weather_max_concurrent_requests,
+          commands::weather::set_weather_retry_policy,
+          commands::weather::get_weather_retry_policy,
+          commands::weather::set_weather_icon_map,
+          commands::weather::get_weather_icon_map,
+          commands::weather::set_weather_update_interval,
+          commands::weather::get_weather_update_interval,
           commands::weather::get_weather,
           commands::weather::fetch_weather,
           commands::weather::broadcast_weather_update,
           commands::weather::clear_weather_cache,
           commands::weather::get_weather_cache_ttl,
+          commands::weather::set_weather_cache_ttl,
+          commands::weather::get_weather_cache_ttl_minutes,
+          commands::weather::get_weather_forecast,
+          commands::weather::broadcast_weather_forecast,
           commands::weather::refresh_weather,
           commands::weather::broadcast_weather,
           commands::weather::set_weather_city_and_broadcast,
@@ -318,9 +807,17 @@ pub fn run() {
           commands::weather::broadcast_weather_multi,
           commands::weather::set_multi_city_mode,
           commands::system::get_system_fonts,
+          commands::system::refresh_system_fonts,
         ]
       }
     })
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|app, event| {
+      // 終了時にコメントの保存を待ってからDBを閉じる
+      if let tauri::RunEvent::Exit = event {
+        let state = app.state::<AppState>();
+        tauri::async_runtime::block_on(shutdown::graceful_shutdown(&state));
+      }
+    });
 }
@@ -0,0 +1,229 @@
+//! メンバーシップ専用ウィジェット管理モジュール
+//!
+//! 新規メンバー・メンバー継続（マイルストーン）・メンバーシップギフトを
+//! コメント欄とは別に、スパチャと同じお祝い用のウィジェットで表示する。
+//!
+//! ## 機能
+//! - メンバーシップレベル文字列から継続月数を判定（"メンバー歴 6 か月"・"Member for 1 year" 等）
+//! - 種別に応じた表示時間の決定（ギフトは件数を大きく表示するため長め）
+//! - 表示完了時のremoveメッセージ送信
+
+use crate::server::types::{MembershipKind, MembershipPayload, MembershipRemovePayload, WsMessage};
+use crate::server::websocket::WebSocketState;
+use crate::youtube::labels::LabelLocale;
+use crate::youtube::types::{ChatMessage, MessageType};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// 新規メンバー・メンバー継続の表示時間（ミリ秒）
+const MEMBERSHIP_DISPLAY_DURATION_MS: u64 = 8_000;
+
+/// メンバーシップギフトの表示時間（ミリ秒）
+const GIFT_DISPLAY_DURATION_MS: u64 = 12_000;
+
+/// 年を表す単位（継続月数の判定用）
+const YEAR_UNITS: &[&str] = &["年", "year"];
+
+/// 月を表す単位（継続月数の判定用）
+const MONTH_UNITS: &[&str] = &["か月", "ヶ月", "ヵ月", "カ月", "ケ月", "month"];
+
+/// メンバーシップレベル文字列から継続月数を取得
+///
+/// 例: "メンバー歴 6 か月" → 6、"Member for 1 year" → 12
+/// 数字または単位（年・月）がない場合（新規メンバーのレベル名等）はNone
+pub fn parse_milestone_months(level: &str) -> Option<u32> {
+    let digits: String = level
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    let value: u32 = digits.parse().ok()?;
+
+    let lower = level.to_lowercase();
+    if YEAR_UNITS.iter().any(|unit| lower.contains(unit)) {
+        value.checked_mul(12)
+    } else if MONTH_UNITS.iter().any(|unit| lower.contains(unit)) {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+/// レベル文字列がメンバー継続のデフォルトラベル（公式APIのmemberMilestoneChatEvent）か
+fn is_milestone_label(level: &str) -> bool {
+    [LabelLocale::Ja, LabelLocale::En]
+        .iter()
+        .any(|locale| locale.member_milestone() == level)
+}
+
+/// メンバーシップのレベル文字列から新規メンバーかメンバー継続かを判定
+pub fn membership_kind(level: &str) -> MembershipKind {
+    if parse_milestone_months(level).is_some() || is_milestone_label(level) {
+        MembershipKind::Milestone
+    } else {
+        MembershipKind::Join
+    }
+}
+
+/// ChatMessageからMembershipPayloadを生成
+/// メンバーシップ・メンバーシップギフトでない場合はNoneを返す
+pub fn create_membership_payload(message: &ChatMessage) -> Option<MembershipPayload> {
+    let (kind, label, milestone_months, gift_count, text, display_duration_ms) =
+        match &message.message_type {
+            MessageType::Membership { level } => (
+                membership_kind(level),
+                level.clone(),
+                parse_milestone_months(level),
+                None,
+                message.message.clone(),
+                MEMBERSHIP_DISPLAY_DURATION_MS,
+            ),
+            // ギフトの本文は表示文字列（"5件のメンバーシップをギフトしました"）なので見出しにする
+            MessageType::MembershipGift { count } => (
+                MembershipKind::Gift,
+                message.message.clone(),
+                None,
+                Some(*count),
+                String::new(),
+                GIFT_DISPLAY_DURATION_MS,
+            ),
+            _ => return None,
+        };
+
+    Some(MembershipPayload {
+        id: message.id.clone(),
+        kind,
+        author_name: message.author_name.clone(),
+        author_channel_id: message.author_channel_id.clone(),
+        author_image_url: message.author_image_url.clone(),
+        label,
+        milestone_months,
+        gift_count,
+        message: text,
+        display_duration_ms,
+    })
+}
+
+/// メンバーシップをWebSocketでブロードキャストし、表示時間経過後に削除する
+pub async fn show_membership(ws_state: &Arc<RwLock<WebSocketState>>, payload: MembershipPayload) {
+    let id = payload.id.clone();
+    let duration_ms = payload.display_duration_ms;
+    log::info!(
+        "メンバーシップをブロードキャスト: {} ({:?}, {})",
+        payload.author_name,
+        payload.kind,
+        payload.label
+    );
+    ws_state
+        .read()
+        .await
+        .broadcast(WsMessage::MembershipAdd { payload })
+        .await;
+
+    schedule_membership_removal(Arc::clone(ws_state), id, duration_ms);
+}
+
+/// メンバーシップの表示タイマーを開始
+/// 表示時間経過後にmembership:removeメッセージを送信する
+fn schedule_membership_removal(ws_state: Arc<RwLock<WebSocketState>>, id: String, duration_ms: u64) {
+    tokio::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_millis(duration_ms)).await;
+        ws_state
+            .read()
+            .await
+            .broadcast(WsMessage::MembershipRemove {
+                payload: MembershipRemovePayload { id: id.clone() },
+            })
+            .await;
+        log::debug!("メンバーシップ削除をブロードキャスト: {}", id);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_message(message_type: MessageType, text: &str) -> ChatMessage {
+        ChatMessage {
+            author_image_url: "https://example.com/icon.png".to_string(),
+            is_member: true,
+            ..ChatMessage::test_message("member-1")
+                .with_text(text)
+                .with_author("TestMember", "UC123")
+                .with_type(message_type)
+        }
+    }
+
+    #[test]
+    fn test_parse_milestone_months() {
+        assert_eq!(parse_milestone_months("メンバー歴 6 か月"), Some(6));
+        assert_eq!(parse_milestone_months("メンバー歴 2 年"), Some(24));
+        assert_eq!(parse_milestone_months("Member for 13 months"), Some(13));
+        assert_eq!(parse_milestone_months("Member for 1 year"), Some(12));
+        assert_eq!(parse_milestone_months("新規メンバー"), None);
+        assert_eq!(parse_milestone_months("Tier 2"), None);
+    }
+
+    #[test]
+    fn test_create_membership_payload_from_membership() {
+        let message = create_message(
+            MessageType::Membership {
+                level: "メンバー歴 6 か月".to_string(),
+            },
+            "いつもありがとう",
+        );
+        let payload = create_membership_payload(&message).unwrap();
+        assert_eq!(payload.id, "member-1");
+        assert_eq!(payload.kind, MembershipKind::Milestone);
+        assert_eq!(payload.label, "メンバー歴 6 か月");
+        assert_eq!(payload.milestone_months, Some(6));
+        assert_eq!(payload.gift_count, None);
+        assert_eq!(payload.message, "いつもありがとう");
+        assert_eq!(payload.display_duration_ms, MEMBERSHIP_DISPLAY_DURATION_MS);
+
+        // 新規メンバー
+        let message = create_message(
+            MessageType::Membership {
+                level: "新規メンバー".to_string(),
+            },
+            "",
+        );
+        let payload = create_membership_payload(&message).unwrap();
+        assert_eq!(payload.kind, MembershipKind::Join);
+        assert_eq!(payload.milestone_months, None);
+
+        // 公式APIのメンバー継続（月数なし）
+        let message = create_message(
+            MessageType::Membership {
+                level: "Member Milestone".to_string(),
+            },
+            "",
+        );
+        assert_eq!(create_membership_payload(&message).unwrap().kind, MembershipKind::Milestone);
+    }
+
+    #[test]
+    fn test_create_membership_payload_from_gift() {
+        let message = create_message(
+            MessageType::MembershipGift { count: 5 },
+            "5件のメンバーシップをギフトしました",
+        );
+        let payload = create_membership_payload(&message).unwrap();
+        assert_eq!(payload.kind, MembershipKind::Gift);
+        assert_eq!(payload.gift_count, Some(5));
+        assert_eq!(payload.label, "5件のメンバーシップをギフトしました");
+        assert_eq!(payload.message, "");
+        assert_eq!(payload.display_duration_ms, GIFT_DISPLAY_DURATION_MS);
+
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["kind"], "gift");
+        assert_eq!(json["giftCount"], 5);
+        assert!(json.get("milestoneMonths").is_none());
+    }
+
+    #[test]
+    fn test_create_membership_payload_ignores_other_messages() {
+        let message = create_message(MessageType::Text, "hello");
+        assert!(create_membership_payload(&message).is_none());
+    }
+}
@@ -0,0 +1,349 @@
+//! HTTP/WebSocketサーバーの待ち受けアドレス・ポート
+//!
+//! 使用するポートはsettingsテーブルに保存する（デフォルト: HTTP 19800、WebSocket 19801、変更はアプリ再起動後に反映）。
+//! 待ち受けアドレスはデフォルトでループバック（127.0.0.1）のみ。別PCのOBSから参照する場合は0.0.0.0等を指定する。
+//! 起動時に指定のポートが使用中の場合は、続く空きポートで起動し、実際のアドレスを記録する。
+//! オーバーレイURLのコピー等は`get_server_addresses`で実際のアドレスを参照する。
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::RwLock;
+use tokio::net::TcpListener;
+
+/// HTTPサーバーのデフォルトポート
+pub const DEFAULT_HTTP_PORT: u16 = 19800;
+
+/// WebSocketサーバーのデフォルトポート
+pub const DEFAULT_WS_PORT: u16 = 19801;
+
+/// 指定できるポートの最小値（特権ポートは不可）
+pub const MIN_SERVER_PORT: u16 = 1024;
+
+/// デフォルトの待ち受けアドレス（ループバックのみ）
+pub const DEFAULT_BIND_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+/// 指定のポートが使用中の場合に試す後続ポートの数
+const PORT_FALLBACK_ATTEMPTS: u16 = 10;
+
+/// ポート設定の保存キー
+const HTTP_PORT_KEY: &str = "http_server_port";
+const WS_PORT_KEY: &str = "ws_server_port";
+
+/// 待ち受けアドレスの保存キー
+const BIND_ADDRESS_KEY: &str = "server_bind_address";
+
+/// 使用するポートの設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerPorts {
+    pub http: u16,
+    pub ws: u16,
+}
+
+impl Default for ServerPorts {
+    fn default() -> Self {
+        Self {
+            http: DEFAULT_HTTP_PORT,
+            ws: DEFAULT_WS_PORT,
+        }
+    }
+}
+
+impl ServerPorts {
+    /// ポート設定を検証
+    ///
+    /// ## 入力検証
+    /// - 1024〜65535
+    /// - HTTPとWebSocketで異なるポート
+    pub fn validate(&self) -> Result<(), String> {
+        for (label, port) in [("HTTP", self.http), ("WebSocket", self.ws)] {
+            if port < MIN_SERVER_PORT {
+                return Err(format!(
+                    "{}サーバーのポートは{}以上で指定してください: {}",
+                    label, MIN_SERVER_PORT, port
+                ));
+            }
+        }
+        if self.http == self.ws {
+            return Err(format!(
+                "HTTPサーバーとWebSocketサーバーには異なるポートを指定してください: {}",
+                self.http
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 起動中のサーバーのアドレス
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerAddresses {
+    /// TLS（HTTPS/WSS）で配信しているか
+    pub tls: bool,
+    /// 待ち受けアドレス（例: `127.0.0.1`、`0.0.0.0`）
+    pub bind_address: String,
+    /// HTTPサーバーのポート（起動できなかった場合はNone）
+    pub http_port: Option<u16>,
+    /// WebSocketサーバーのポート（起動できなかった場合はNone）
+    pub ws_port: Option<u16>,
+    /// オーバーレイページのベースURL（例: `http://localhost:19800`）
+    pub http_base_url: Option<String>,
+    /// WebSocketのURL（例: `ws://localhost:19801/ws`）
+    pub ws_url: Option<String>,
+    /// HTTPサーバーを起動できなかった理由
+    pub http_error: Option<String>,
+    /// WebSocketサーバーを起動できなかった理由
+    pub ws_error: Option<String>,
+}
+
+static SERVER_ADDRESSES: Lazy<RwLock<ServerAddresses>> =
+    Lazy::new(|| RwLock::new(ServerAddresses::default()));
+
+/// 起動中のサーバーのアドレスを取得
+pub fn current() -> ServerAddresses {
+    match SERVER_ADDRESSES.read() {
+        Ok(addresses) => addresses.clone(),
+        Err(e) => e.into_inner().clone(),
+    }
+}
+
+/// サーバーの起動結果を記録
+///
+/// `http`・`ws`は実際に待ち受けたポート、または起動できなかった理由
+pub fn record(tls: bool, bind_address: IpAddr, http: Result<u16, String>, ws: Result<u16, String>) {
+    let mut addresses = SERVER_ADDRESSES.write().unwrap_or_else(|e| e.into_inner());
+    addresses.tls = tls;
+    addresses.bind_address = bind_address.to_string();
+    addresses.http_port = http.as_ref().ok().copied();
+    addresses.http_base_url = addresses.http_port.map(|port| http_base_url(tls, port));
+    addresses.http_error = http.err();
+    addresses.ws_port = ws.as_ref().ok().copied();
+    addresses.ws_url = addresses.ws_port.map(|port| ws_url(tls, port));
+    addresses.ws_error = ws.err();
+}
+
+/// オーバーレイページのベースURL（例: `https://localhost:19800`）
+pub fn http_base_url(tls: bool, port: u16) -> String {
+    format!("{}://localhost:{}", if tls { "https" } else { "http" }, port)
+}
+
+/// WebSocketのURL（例: `wss://localhost:19801/ws`）
+pub fn ws_url(tls: bool, port: u16) -> String {
+    format!("{}://localhost:{}/ws", if tls { "wss" } else { "ws" }, port)
+}
+
+/// 指定のポートで待ち受け、使用中の場合は続くポートを順に試す
+///
+/// `preferred`から最大10個のポートを試し、いずれも使用できない場合はエラー
+pub async fn bind_with_fallback(address: IpAddr, preferred: u16) -> Result<TcpListener, String> {
+    let mut last_error = None;
+    for port in (preferred..=u16::MAX).take(usize::from(PORT_FALLBACK_ATTEMPTS)) {
+        match TcpListener::bind((address, port)).await {
+            Ok(listener) => {
+                if port != preferred {
+                    log::warn!("Port {} is in use, listening on {} instead", preferred, port);
+                }
+                return Ok(listener);
+            }
+            Err(e) => {
+                log::debug!("Failed to bind port {}: {}", port, e);
+                last_error = Some(e);
+            }
+        }
+    }
+    Err(format!(
+        "ポート{}〜{}がすべて使用中のため起動できませんでした: {}",
+        preferred,
+        preferred.saturating_add(PORT_FALLBACK_ATTEMPTS - 1),
+        last_error.map(|e| e.to_string()).unwrap_or_default()
+    ))
+}
+
+/// 待ち受けアドレスを検証
+///
+/// ## 入力検証
+/// - ループバック・全インターフェース（0.0.0.0、::）・プライベートアドレス・リンクローカルのみ
+///   （マルチキャスト・ブロードキャスト・グローバルアドレスは不可）
+pub fn validate_bind_address(address: IpAddr) -> Result<(), String> {
+    let allowed = match address {
+        IpAddr::V4(v4) => v4.is_loopback() || v4.is_unspecified() || v4.is_private() || v4.is_link_local(),
+        IpAddr::V6(v6) => {
+            let segment = v6.segments()[0];
+            // ユニークローカル（fc00::/7）・リンクローカル（fe80::/10）
+            v6.is_loopback() || v6.is_unspecified() || (segment & 0xfe00) == 0xfc00 || (segment & 0xffc0) == 0xfe80
+        }
+    };
+    if allowed {
+        Ok(())
+    } else {
+        Err(format!(
+            "待ち受けアドレスにはループバック・0.0.0.0・ローカルネットワークのアドレスを指定してください: {}",
+            address
+        ))
+    }
+}
+
+/// 待ち受けアドレスを文字列から解析して検証
+pub fn parse_bind_address(address: &str) -> Result<IpAddr, String> {
+    let address: IpAddr = address
+        .trim()
+        .parse()
+        .map_err(|_| format!("IPアドレスの形式が正しくありません: {}", address))?;
+    validate_bind_address(address)?;
+    Ok(address)
+}
+
+/// 保存済みの待ち受けアドレスをDBから読み込み（未保存・不正な値の場合はループバック）
+pub async fn load_bind_address(pool: &SqlitePool) -> Result<IpAddr, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(BIND_ADDRESS_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    let Some((value,)) = result else {
+        return Ok(DEFAULT_BIND_ADDRESS);
+    };
+    match parse_bind_address(&value) {
+        Ok(address) => Ok(address),
+        Err(e) => {
+            log::warn!("Invalid server bind address setting, using loopback: {}", e);
+            Ok(DEFAULT_BIND_ADDRESS)
+        }
+    }
+}
+
+/// 待ち受けアドレスを保存
+pub async fn save_bind_address(pool: &SqlitePool, address: IpAddr) -> Result<(), String> {
+    validate_bind_address(address)?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(BIND_ADDRESS_KEY)
+    .bind(address.to_string())
+    .bind(&now)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+    Ok(())
+}
+
+/// 保存済みのポート設定をDBから読み込み（未保存・不正な値の場合はデフォルト）
+pub async fn load_server_ports(pool: &SqlitePool) -> Result<ServerPorts, String> {
+    let rows: Vec<(String, String)> = sqlx::query_as("SELECT key, value FROM settings WHERE key IN (?, ?)")
+        .bind(HTTP_PORT_KEY)
+        .bind(WS_PORT_KEY)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    let mut ports = ServerPorts::default();
+    for (key, value) in rows {
+        let Ok(port) = value.parse::<u16>() else {
+            log::warn!("Invalid server port setting {}: {}", key, value);
+            continue;
+        };
+        if key == HTTP_PORT_KEY {
+            ports.http = port;
+        } else {
+            ports.ws = port;
+        }
+    }
+
+    if let Err(e) = ports.validate() {
+        log::warn!("Invalid server port settings, using defaults: {}", e);
+        return Ok(ServerPorts::default());
+    }
+    Ok(ports)
+}
+
+/// ポート設定を保存
+pub async fn save_server_ports(pool: &SqlitePool, ports: ServerPorts) -> Result<(), String> {
+    ports.validate()?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tx = pool.begin().await.map_err(|e| format!("DB error: {}", e))?;
+    for (key, port) in [(HTTP_PORT_KEY, ports.http), (WS_PORT_KEY, ports.ws)] {
+        sqlx::query(
+            r#"
+            INSERT INTO settings (key, value, updated_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(key)
+        .bind(port.to_string())
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+    }
+    tx.commit().await.map_err(|e| format!("DB error: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bind_with_fallback_skips_port_in_use() {
+        let occupied = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = occupied.local_addr().unwrap().port();
+
+        // 使用中のポートを避けて後続のポートで待ち受ける
+        let listener = bind_with_fallback(DEFAULT_BIND_ADDRESS, port).await.unwrap();
+        let bound = listener.local_addr().unwrap().port();
+        assert!(bound > port);
+    }
+
+    #[tokio::test]
+    async fn test_server_ports_roundtrip() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let db = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(load_server_ports(&db).await.unwrap(), ServerPorts::default());
+
+        let ports = ServerPorts { http: 20800, ws: 20801 };
+        save_server_ports(&db, ports).await.unwrap();
+        assert_eq!(load_server_ports(&db).await.unwrap(), ports);
+
+        // 不正な設定は保存しない
+        assert!(save_server_ports(&db, ServerPorts { http: 80, ws: 20801 }).await.is_err());
+        assert!(save_server_ports(&db, ServerPorts { http: 20800, ws: 20800 }).await.is_err());
+        assert_eq!(load_server_ports(&db).await.unwrap(), ports);
+    }
+
+    #[test]
+    fn test_parse_bind_address() {
+        assert_eq!(parse_bind_address("127.0.0.1"), Ok(DEFAULT_BIND_ADDRESS));
+        assert!(parse_bind_address("0.0.0.0").is_ok());
+        assert!(parse_bind_address("192.168.1.5").is_ok());
+        assert!(parse_bind_address("::1").is_ok());
+        assert!(parse_bind_address("fd00::1").is_ok());
+
+        assert!(parse_bind_address("localhost").is_err());
+        assert!(parse_bind_address("8.8.8.8").is_err());
+        assert!(parse_bind_address("224.0.0.1").is_err());
+        assert!(parse_bind_address("255.255.255.255").is_err());
+        assert!(parse_bind_address("2001:4860::8888").is_err());
+    }
+
+    #[test]
+    fn test_urls() {
+        assert_eq!(http_base_url(false, DEFAULT_HTTP_PORT), "http://localhost:19800");
+        assert_eq!(ws_url(false, DEFAULT_WS_PORT), "ws://localhost:19801/ws");
+        assert_eq!(http_base_url(true, 20800), "https://localhost:20800");
+        assert_eq!(ws_url(true, 20801), "wss://localhost:20801/ws");
+    }
+}
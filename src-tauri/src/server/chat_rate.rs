@@ -0,0 +1,166 @@
+//! チャットの流速（1分あたりのコメント数）
+//!
+//! ブロードキャストしたコメント（テスト送信のコメントを除く）の時刻を直近の集計期間（デフォルト60秒）分だけ保持し、
+//! 1分あたりのコメント数に換算して`chat:rate`メッセージで定期的に配信する。
+//! 集計はポーリングの開始・停止時にリセットする。
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::RwLock;
+
+use super::types::{ChatRatePayload, WsMessage};
+use super::websocket::WebSocketState;
+
+/// 集計期間のデフォルト（秒）
+pub const DEFAULT_CHAT_RATE_WINDOW_SECS: u32 = 60;
+
+/// 集計期間の設定範囲（秒）
+pub const MIN_CHAT_RATE_WINDOW_SECS: u32 = 10;
+pub const MAX_CHAT_RATE_WINDOW_SECS: u32 = 600;
+
+/// `chat:rate`の配信間隔
+const CHAT_RATE_BROADCAST_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 集計期間を検証
+///
+/// ## 入力検証
+/// - 10〜600秒
+pub fn validate_window_secs(window_secs: u32) -> Result<(), String> {
+    if !(MIN_CHAT_RATE_WINDOW_SECS..=MAX_CHAT_RATE_WINDOW_SECS).contains(&window_secs) {
+        return Err(format!(
+            "流速の集計期間は{}〜{}秒で指定してください: {}",
+            MIN_CHAT_RATE_WINDOW_SECS, MAX_CHAT_RATE_WINDOW_SECS, window_secs
+        ));
+    }
+    Ok(())
+}
+
+/// チャット流速の集計状態（WebSocketStateごとに1つ）
+#[derive(Debug)]
+pub struct ChatRateTracker {
+    window_secs: u32,
+    /// 集計期間内にブロードキャストしたコメントの時刻（古い順）
+    timestamps: VecDeque<Instant>,
+}
+
+impl Default for ChatRateTracker {
+    fn default() -> Self {
+        Self {
+            window_secs: DEFAULT_CHAT_RATE_WINDOW_SECS,
+            timestamps: VecDeque::new(),
+        }
+    }
+}
+
+impl ChatRateTracker {
+    /// 集計期間（秒）
+    pub fn window_secs(&self) -> u32 {
+        self.window_secs
+    }
+
+    /// 集計期間を更新（記録済みの時刻は保持し、次回の集計から適用）
+    pub fn set_window_secs(&mut self, window_secs: u32) {
+        self.window_secs = window_secs;
+    }
+
+    /// 集計をリセット（ポーリングの開始・停止時）
+    pub fn reset(&mut self) {
+        self.timestamps.clear();
+    }
+
+    /// コメント1件を記録
+    pub fn record_at(&mut self, now: Instant) {
+        self.evict(now);
+        self.timestamps.push_back(now);
+    }
+
+    /// 現在の流速を取得
+    pub fn rate_at(&mut self, now: Instant) -> ChatRatePayload {
+        self.evict(now);
+        let count = self.timestamps.len() as u64;
+        ChatRatePayload {
+            messages_per_minute: count as f64 * 60.0 / f64::from(self.window_secs),
+            count,
+            window_secs: self.window_secs,
+        }
+    }
+
+    /// 集計期間を過ぎた時刻を破棄
+    fn evict(&mut self, now: Instant) {
+        let window = Duration::from_secs(u64::from(self.window_secs));
+        while let Some(oldest) = self.timestamps.front() {
+            if now.saturating_duration_since(*oldest) < window {
+                break;
+            }
+            self.timestamps.pop_front();
+        }
+    }
+}
+
+/// `chat:rate`を定期的に配信するタスク（アプリ起動時に1つだけ起動）
+///
+/// 前回の配信から値が変わった場合のみ配信する
+/// （コメントがない間も、集計期間を過ぎて0になるまでは減っていく値を配信する）
+pub async fn run_chat_rate_broadcaster(state: Arc<RwLock<WebSocketState>>) {
+    let mut last: Option<ChatRatePayload> = None;
+    loop {
+        tokio::time::sleep(CHAT_RATE_BROADCAST_INTERVAL).await;
+        let state = state.read().await;
+        let rate = state.chat_rate();
+        if last != Some(rate) {
+            state.broadcast(WsMessage::ChatRate { payload: rate }).await;
+            last = Some(rate);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_counts_comments_within_window() {
+        let start = Instant::now();
+        let mut tracker = ChatRateTracker::default();
+        for i in 0..30 {
+            tracker.record_at(start + Duration::from_secs(i));
+        }
+
+        let rate = tracker.rate_at(start + Duration::from_secs(30));
+        assert_eq!(rate.count, 30);
+        assert_eq!(rate.messages_per_minute, 30.0);
+        assert_eq!(rate.window_secs, DEFAULT_CHAT_RATE_WINDOW_SECS);
+
+        // 60秒を過ぎたコメントは集計から外れる
+        let rate = tracker.rate_at(start + Duration::from_secs(75));
+        assert_eq!(rate.count, 14);
+
+        tracker.reset();
+        assert_eq!(tracker.rate_at(start + Duration::from_secs(75)).count, 0);
+    }
+
+    #[test]
+    fn test_rate_is_converted_to_per_minute() {
+        let start = Instant::now();
+        let mut tracker = ChatRateTracker::default();
+        tracker.set_window_secs(15);
+        for _ in 0..10 {
+            tracker.record_at(start);
+        }
+
+        let rate = tracker.rate_at(start + Duration::from_secs(10));
+        assert_eq!(rate.count, 10);
+        assert_eq!(rate.messages_per_minute, 40.0);
+    }
+
+    #[test]
+    fn test_validate_window_secs() {
+        assert!(validate_window_secs(DEFAULT_CHAT_RATE_WINDOW_SECS).is_ok());
+        assert!(validate_window_secs(MIN_CHAT_RATE_WINDOW_SECS).is_ok());
+        assert!(validate_window_secs(MAX_CHAT_RATE_WINDOW_SECS).is_ok());
+        assert!(validate_window_secs(9).is_err());
+        assert!(validate_window_secs(601).is_err());
+    }
+}
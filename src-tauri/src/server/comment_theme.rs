@@ -0,0 +1,156 @@
+//! コメント表示テーマの保存・配信
+//!
+//! 組み込みテーマ（[`CommentTheme`]）から選んだテーマをsettingsテーブルに保存し、
+//! 変更時に`comment:theme`メッセージでオーバーレイへ配信する。
+//! 保存済みのテーマは接続時・再送要求時のスナップショットにも含める。
+
+use sqlx::SqlitePool;
+
+use super::types::{CommentTheme, CommentThemePayload, ServerState, WsMessage};
+
+/// コメント表示テーマの保存キー
+const COMMENT_THEME_KEY: &str = "comment_theme";
+
+/// 保存済みのテーマをDBから読み込み（未保存の場合はNone）
+///
+/// 未知のテーマ名が保存されている場合はデフォルトを返す
+pub async fn load_comment_theme(pool: &SqlitePool) -> Result<Option<CommentTheme>, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(COMMENT_THEME_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    Ok(result.map(|(name,)| {
+        CommentTheme::from_name(&name).unwrap_or_else(|| {
+            log::warn!(
+                "Stored comment theme is unknown, falling back to default: {}",
+                name
+            );
+            CommentTheme::default()
+        })
+    }))
+}
+
+/// テーマを保存し、変化があればオーバーレイにブロードキャスト
+///
+/// 未知のテーマ名はエラー（保存・配信しない）
+pub async fn apply_comment_theme(
+    pool: &SqlitePool,
+    server: &ServerState,
+    name: &str,
+) -> Result<CommentTheme, String> {
+    let theme = CommentTheme::from_name(name).ok_or_else(|| {
+        let names: Vec<&str> = CommentTheme::ALL.iter().map(|t| t.name()).collect();
+        format!(
+            "未知のコメントテーマです: {}（{}のいずれかを指定してください）",
+            name,
+            names.join(", ")
+        )
+    })?;
+
+    let changed = load_comment_theme(pool).await?.unwrap_or_default() != theme;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(COMMENT_THEME_KEY)
+    .bind(theme.name())
+    .bind(&now)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    if changed {
+        log::info!("Comment theme changed: {}", theme.name());
+        let state = server.read().await;
+        state.broadcast(comment_theme_message(theme)).await;
+    }
+    Ok(theme)
+}
+
+/// テーマ変更メッセージを生成
+pub fn comment_theme_message(theme: CommentTheme) -> WsMessage {
+    WsMessage::CommentTheme {
+        payload: CommentThemePayload { theme },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::create_server_state;
+    use tempfile::NamedTempFile;
+    use tokio::sync::mpsc;
+    use tokio_tungstenite::tungstenite::Message;
+
+    async fn setup() -> (
+        NamedTempFile,
+        SqlitePool,
+        ServerState,
+        mpsc::UnboundedReceiver<Message>,
+    ) {
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        let server = create_server_state();
+        let (tx, rx) = mpsc::unbounded_channel();
+        server.read().await.add_peer(1, tx).await;
+        (temp_file, pool, server, rx)
+    }
+
+    fn drain_themes(rx: &mut mpsc::UnboundedReceiver<Message>) -> Vec<String> {
+        let mut themes = Vec::new();
+        while let Ok(Message::Text(json)) = rx.try_recv() {
+            let frame: serde_json::Value = serde_json::from_str(&json).unwrap();
+            assert_eq!(frame["type"], "comment:theme");
+            themes.push(frame["payload"]["theme"].as_str().unwrap().to_string());
+        }
+        themes
+    }
+
+    #[tokio::test]
+    async fn test_theme_broadcast_on_change() {
+        let (_temp_file, pool, server, mut rx) = setup().await;
+        assert_eq!(load_comment_theme(&pool).await.unwrap(), None);
+
+        apply_comment_theme(&pool, &server, "bubble").await.unwrap();
+        assert_eq!(drain_themes(&mut rx), vec!["bubble"]);
+        assert_eq!(
+            load_comment_theme(&pool).await.unwrap(),
+            Some(CommentTheme::Bubble)
+        );
+
+        // 同じテーマでは配信しない
+        apply_comment_theme(&pool, &server, "bubble").await.unwrap();
+        assert!(drain_themes(&mut rx).is_empty());
+
+        apply_comment_theme(&pool, &server, "terminal")
+            .await
+            .unwrap();
+        assert_eq!(drain_themes(&mut rx), vec!["terminal"]);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_theme_rejected() {
+        let (_temp_file, pool, server, mut rx) = setup().await;
+
+        let err = apply_comment_theme(&pool, &server, "neon")
+            .await
+            .unwrap_err();
+        assert!(err.contains("minimal"), "{}", err);
+        // 大文字小文字は区別する
+        assert!(apply_comment_theme(&pool, &server, "Minimal")
+            .await
+            .is_err());
+
+        assert!(drain_themes(&mut rx).is_empty());
+        assert_eq!(load_comment_theme(&pool).await.unwrap(), None);
+    }
+}
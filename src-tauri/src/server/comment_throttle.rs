@@ -0,0 +1,254 @@
+//! コメント配信のスローモード（流量制限）
+//!
+//! 有効時は`comment:add`の配信を1秒あたり最大N件に制限し、超過分はキューに溜めて
+//! 一定間隔（1/N秒）で順に配信する。キューが上限（N件×`THROTTLE_BUFFER_SECS`秒分）を
+//! 超えた場合は古いコメントから破棄し、表示が実際のチャットから遅れすぎないようにする。
+//! スパチャ・スーパーステッカー・メンバーシップ・ギフトは制限の対象外（即時配信）。
+//! ポーリング間隔（取得頻度）とは独立した、オーバーレイへの表示頻度の制限。
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::types::WsMessage;
+use crate::youtube::types::MessageType;
+
+/// 1秒あたりの最大配信件数の上限（0は無効）
+pub const MAX_COMMENT_THROTTLE_RATE: u32 = 50;
+
+/// キューに溜める上限（秒数分）
+const THROTTLE_BUFFER_SECS: u32 = 5;
+
+/// 1秒あたりの最大配信件数を検証
+///
+/// ## 入力検証
+/// - 0（無効）または1〜50件
+pub fn validate_rate(rate: u32) -> Result<(), String> {
+    if rate > MAX_COMMENT_THROTTLE_RATE {
+        return Err(format!(
+            "コメントの配信件数は0〜{}件/秒で指定してください: {}",
+            MAX_COMMENT_THROTTLE_RATE, rate
+        ));
+    }
+    Ok(())
+}
+
+/// 流量制限の対象か（通常のテキストコメントのみ）
+pub fn is_throttled(message: &WsMessage) -> bool {
+    matches!(
+        message,
+        WsMessage::CommentAdd { payload, .. } if matches!(payload.message_type, MessageType::Text)
+    )
+}
+
+/// 流量制限の判定結果
+#[derive(Debug)]
+pub enum Admission {
+    /// すぐに配信する
+    SendNow(Box<WsMessage>),
+    /// キューに積んだ（`start_drain`がtrueの場合は送出タスクの起動が必要）
+    Queued { start_drain: bool },
+}
+
+/// 送出タスクが次に行うこと
+#[derive(Debug)]
+pub enum ThrottlePoll {
+    /// 配信する
+    Ready(Box<WsMessage>),
+    /// 指定時刻まで待つ
+    Wait(Instant),
+    /// キューが空になった（送出タスクを終了）
+    Idle,
+}
+
+/// コメント配信の流量制限の状態（WebSocketStateごとに1つ）
+#[derive(Debug, Default)]
+pub struct CommentThrottle {
+    /// 1秒あたりの最大配信件数（0は無効）
+    rate: u32,
+    /// 配信待ちのコメント（古い順）
+    queue: VecDeque<WsMessage>,
+    /// 次にコメントを配信できる時刻
+    next_send_at: Option<Instant>,
+    /// 送出タスクが動作中か
+    draining: bool,
+    /// キューの上限を超えて破棄した件数（ログ用）
+    dropped: u64,
+}
+
+impl CommentThrottle {
+    /// 1秒あたりの最大配信件数（0は無効）
+    pub fn rate(&self) -> u32 {
+        self.rate
+    }
+
+    /// 1秒あたりの最大配信件数を更新
+    ///
+    /// 無効化した場合、キューに残ったコメントは動作中の送出タスクがすぐに配信する
+    pub fn set_rate(&mut self, rate: u32) {
+        self.rate = rate;
+        self.next_send_at = None;
+        self.trim();
+    }
+
+    /// コメント1件を受け付け、すぐに配信するかキューに積むかを判定
+    pub fn admit(&mut self, message: WsMessage, now: Instant) -> Admission {
+        if self.queue.is_empty() && (self.rate == 0 || self.next_send_at.map_or(true, |at| at <= now)) {
+            self.mark_sent(now);
+            return Admission::SendNow(Box::new(message));
+        }
+
+        self.queue.push_back(message);
+        self.trim();
+        let start_drain = !self.draining;
+        self.draining = true;
+        Admission::Queued { start_drain }
+    }
+
+    /// 配信待ちのコメントを削除（`comment:remove`で削除されたコメントを表示しない）
+    pub fn remove(&mut self, id: &str) {
+        self.queue.retain(|message| match message {
+            WsMessage::CommentAdd { payload, .. } => payload.id != id,
+            _ => true,
+        });
+    }
+
+    /// 配信待ちのコメントに翻訳を付ける（配信時に再送キャッシュへ翻訳ごと記録するため）
+    pub fn set_translation(&mut self, id: &str, translation: &str) {
+        for message in self.queue.iter_mut() {
+            if let WsMessage::CommentAdd { payload, translation: queued, .. } = message {
+                if payload.id == id {
+                    *queued = Some(translation.to_string());
+                }
+            }
+        }
+    }
+
+    /// 送出タスクから呼び出し、次に配信するコメントまたは待ち時間を返す
+    pub fn poll(&mut self, now: Instant) -> ThrottlePoll {
+        if self.queue.is_empty() {
+            self.draining = false;
+            return ThrottlePoll::Idle;
+        }
+        if self.rate > 0 {
+            if let Some(at) = self.next_send_at.filter(|at| *at > now) {
+                return ThrottlePoll::Wait(at);
+            }
+        }
+
+        self.mark_sent(now);
+        match self.queue.pop_front() {
+            Some(message) => ThrottlePoll::Ready(Box::new(message)),
+            None => ThrottlePoll::Idle,
+        }
+    }
+
+    /// 配信した時刻から次に配信できる時刻を設定
+    fn mark_sent(&mut self, now: Instant) {
+        self.next_send_at = (self.rate > 0).then(|| now + Duration::from_secs(1) / self.rate);
+    }
+
+    /// キューの上限を超えた古いコメントを破棄
+    fn trim(&mut self) {
+        if self.rate == 0 {
+            return;
+        }
+        let capacity = (self.rate * THROTTLE_BUFFER_SECS) as usize;
+        while self.queue.len() > capacity {
+            self.queue.pop_front();
+            self.dropped += 1;
+            if self.dropped % 100 == 1 {
+                log::info!("Comment throttle dropped {} comments so far", self.dropped);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::youtube::severity::Severity;
+    use crate::youtube::types::ChatMessage;
+
+    fn comment(id: &str, message_type: MessageType) -> WsMessage {
+        WsMessage::CommentAdd {
+            payload: ChatMessage::test_message(id).with_text("").with_type(message_type),
+            instant: false,
+            buffer_interval_ms: None,
+            is_first_time: false,
+            translation: None,
+            severity: Severity::Clean,
+        }
+    }
+
+    fn comment_id(message: &WsMessage) -> &str {
+        match message {
+            WsMessage::CommentAdd { payload, .. } => &payload.id,
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_throttle_spaces_comments_and_drops_oldest() {
+        let start = Instant::now();
+        let mut throttle = CommentThrottle::default();
+        throttle.set_rate(2);
+
+        // 1件目はすぐに配信、以降はキューに積む（送出タスクの起動は1回だけ）
+        assert!(matches!(throttle.admit(comment("c0", MessageType::Text), start), Admission::SendNow(_)));
+        assert!(matches!(
+            throttle.admit(comment("c1", MessageType::Text), start),
+            Admission::Queued { start_drain: true }
+        ));
+        for i in 2..=11 {
+            assert!(matches!(
+                throttle.admit(comment(&format!("c{}", i), MessageType::Text), start),
+                Admission::Queued { start_drain: false }
+            ));
+        }
+        // 上限（2件×5秒）を超えた古いコメントは破棄
+        assert_eq!(throttle.queue.len(), 10);
+
+        // 0.5秒間隔で順に配信
+        assert!(matches!(throttle.poll(start), ThrottlePoll::Wait(at) if at == start + Duration::from_millis(500)));
+        let half = start + Duration::from_millis(500);
+        match throttle.poll(half) {
+            ThrottlePoll::Ready(message) => assert_eq!(comment_id(&message), "c2"),
+            other => panic!("unexpected poll: {:?}", other),
+        }
+        assert!(matches!(throttle.poll(half), ThrottlePoll::Wait(_)));
+
+        // 削除されたコメントは配信しない
+        throttle.remove("c3");
+        match throttle.poll(start + Duration::from_secs(1)) {
+            ThrottlePoll::Ready(message) => assert_eq!(comment_id(&message), "c4"),
+            other => panic!("unexpected poll: {:?}", other),
+        }
+
+        // 無効化すると残りをすぐに配信し、空になったら送出タスクを終了
+        throttle.set_rate(0);
+        let mut rest = 0;
+        while let ThrottlePoll::Ready(_) = throttle.poll(start + Duration::from_secs(1)) {
+            rest += 1;
+        }
+        assert_eq!(rest, 7);
+        assert!(matches!(throttle.poll(start), ThrottlePoll::Idle));
+        assert!(matches!(throttle.admit(comment("c12", MessageType::Text), start), Admission::SendNow(_)));
+    }
+
+    #[test]
+    fn test_only_text_comments_are_throttled() {
+        assert!(is_throttled(&comment("c1", MessageType::Text)));
+        assert!(!is_throttled(&comment(
+            "c2",
+            MessageType::SuperChat {
+                amount: "¥500".to_string(),
+                currency: "JPY".to_string(),
+                amount_micros: None,
+            }
+        )));
+        assert!(!is_throttled(&comment("c3", MessageType::Membership { level: "Member".to_string() })));
+        assert!(validate_rate(0).is_ok());
+        assert!(validate_rate(MAX_COMMENT_THROTTLE_RATE).is_ok());
+        assert!(validate_rate(MAX_COMMENT_THROTTLE_RATE + 1).is_err());
+    }
+}
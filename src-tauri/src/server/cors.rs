@@ -0,0 +1,183 @@
+//! HTTPサーバーのCORS許可リスト
+//!
+//! オーバーレイページは同一オリジンのAPIを参照するため、通常CORSは不要。
+//! 同じPCの他のWebページからAPIを読み取られないよう、許可するオリジンを限定する。
+//!
+//! - ループバック（`localhost`・`127.0.0.1`・`[::1]`）のオリジンは常に許可
+//! - それ以外は設定した許可リストのオリジンのみ許可（settingsテーブルにJSONで保存）
+
+use axum::http::HeaderValue;
+use once_cell::sync::Lazy;
+use sqlx::SqlitePool;
+use std::sync::RwLock;
+
+/// 許可リストの保存キー（JSON配列）
+const CORS_ALLOWED_ORIGINS_KEY: &str = "cors_allowed_origins";
+
+/// 許可リストの最大件数
+pub const MAX_CORS_ALLOWED_ORIGINS: usize = 20;
+
+/// ループバックのホスト
+const LOOPBACK_HOSTS: &[&str] = &["localhost", "127.0.0.1", "[::1]"];
+
+static ALLOWED_ORIGINS: Lazy<RwLock<Vec<String>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// 許可リストを更新（HTTPサーバーは次のリクエストから反映）
+pub fn set_allowed_origins(origins: Vec<String>) {
+    *ALLOWED_ORIGINS.write().unwrap_or_else(|e| e.into_inner()) = origins;
+}
+
+/// 現在の許可リスト
+pub fn allowed_origins() -> Vec<String> {
+    ALLOWED_ORIGINS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// リクエストのオリジンを許可するか
+pub fn is_allowed_origin(origin: &HeaderValue) -> bool {
+    let Ok(origin) = origin.to_str() else {
+        return false;
+    };
+    if is_loopback_origin(origin) {
+        return true;
+    }
+    ALLOWED_ORIGINS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .any(|allowed| allowed.eq_ignore_ascii_case(origin))
+}
+
+/// `http(s)://<ループバック>[:port]`形式か
+fn is_loopback_origin(origin: &str) -> bool {
+    let Some(host_port) = origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+    else {
+        return false;
+    };
+    let host = match host_port.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => host,
+        _ => host_port,
+    };
+    LOOPBACK_HOSTS.iter().any(|loopback| loopback.eq_ignore_ascii_case(host))
+}
+
+/// オリジンを検証して正規化（末尾の`/`を除去）
+///
+/// ## 入力検証
+/// - `http://`または`https://`で始まる
+/// - パス・クエリ・空白を含まない
+fn normalize_origin(origin: &str) -> Result<String, String> {
+    let origin = origin.trim().trim_end_matches('/').to_ascii_lowercase();
+    let host = origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+        .ok_or_else(|| format!("オリジンはhttp://またはhttps://で始めてください: {}", origin))?;
+    if host.is_empty() || host.contains(['/', '?', '#', ' ']) {
+        return Err(format!("オリジンにはスキーム・ホスト・ポートのみを指定してください: {}", origin));
+    }
+    Ok(origin)
+}
+
+/// 許可リストを検証して正規化（重複を除去）
+///
+/// ## 入力検証
+/// - 最大20件
+pub fn normalize_origins(origins: Vec<String>) -> Result<Vec<String>, String> {
+    if origins.len() > MAX_CORS_ALLOWED_ORIGINS {
+        return Err(format!(
+            "許可するオリジンは{}件以下で指定してください: {}件",
+            MAX_CORS_ALLOWED_ORIGINS,
+            origins.len()
+        ));
+    }
+    let mut normalized: Vec<String> = Vec::with_capacity(origins.len());
+    for origin in origins {
+        let origin = normalize_origin(&origin)?;
+        if !normalized.contains(&origin) {
+            normalized.push(origin);
+        }
+    }
+    Ok(normalized)
+}
+
+/// 保存済みの許可リストをDBから読み込み（未保存の場合は空）
+pub async fn load_allowed_origins(pool: &SqlitePool) -> Result<Vec<String>, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(CORS_ALLOWED_ORIGINS_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    match result {
+        Some((json,)) => {
+            let origins: Vec<String> =
+                serde_json::from_str(&json).map_err(|e| format!("JSON parse error: {}", e))?;
+            normalize_origins(origins)
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+/// 許可リストを保存
+pub async fn save_allowed_origins(pool: &SqlitePool, origins: &[String]) -> Result<(), String> {
+    let json = serde_json::to_string(origins).map_err(|e| format!("JSON serialize error: {}", e))?;
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(CORS_ALLOWED_ORIGINS_KEY)
+    .bind(&json)
+    .bind(&now)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loopback_origin() {
+        assert!(is_loopback_origin("http://localhost:19800"));
+        assert!(is_loopback_origin("https://127.0.0.1:19800"));
+        assert!(is_loopback_origin("http://[::1]:5173"));
+        assert!(is_loopback_origin("http://localhost"));
+        assert!(!is_loopback_origin("http://localhost.example.com"));
+        assert!(!is_loopback_origin("http://192.168.1.5:19800"));
+        assert!(!is_loopback_origin("null"));
+    }
+
+    #[test]
+    fn test_is_allowed_origin() {
+        let remote = HeaderValue::from_static("http://192.168.1.5:19800");
+        assert!(is_allowed_origin(&HeaderValue::from_static("http://localhost:19800")));
+        assert!(!is_allowed_origin(&remote));
+
+        // 許可リストに追加したオリジンのみ許可
+        set_allowed_origins(vec!["http://192.168.1.5:19800".to_string()]);
+        assert!(is_allowed_origin(&remote));
+        assert!(!is_allowed_origin(&HeaderValue::from_static("https://evil.example.com")));
+        set_allowed_origins(Vec::new());
+    }
+
+    #[test]
+    fn test_normalize_origins() {
+        let origins = normalize_origins(vec![
+            "http://192.168.1.5:19800/".to_string(),
+            "HTTP://192.168.1.5:19800".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(origins, vec!["http://192.168.1.5:19800"]);
+
+        assert!(normalize_origins(vec!["192.168.1.5".to_string()]).is_err());
+        assert!(normalize_origins(vec!["http://example.com/path".to_string()]).is_err());
+        assert!(normalize_origins(vec!["http://".to_string()]).is_err());
+    }
+}
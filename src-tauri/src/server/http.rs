@@ -1,35 +1,50 @@
 use axum::{
-    extract::{Path, State},
-    response::{Html, IntoResponse, Json},
+    extract::{Path, Query, State},
+    response::{Html, IntoResponse, Json, Response},
     routing::get,
     Router,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::SqlitePool;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tower_http::cors::CorsLayer;
+use tokio_rustls::TlsAcceptor;
+use tower_http::cors::{AllowOrigin, CorsLayer};
 use tower_http::services::ServeDir;
 
 use super::types::{
     CommentPosition, CommentSettings, LayoutPreset, SetlistPosition, SetlistSettings,
-    ThemeSettings, WeatherPosition, WeatherSettings, WidgetVisibilitySettings,
+    ThemeSettings, WeatherSettings, WidgetVisibilitySettings,
 };
-use crate::commands::overlay::OverlaySettings;
+use super::cors;
+use super::ws_auth::{self, AuthToken};
+use crate::commands::overlay::{parse_overlay_settings, OverlaySettings};
 
 /// HTTPサーバー用の共有状態
 #[derive(Clone)]
 pub struct HttpState {
     pub db: Arc<SqlitePool>,
     pub overlays_dir: PathBuf,
+    /// オーバーレイページの表示に必要な認証トークン（WebSocketサーバーと共有）
+    pub auth_token: AuthToken,
 }
 
 /// HTTPサーバーを起動（DB接続付き）
-pub async fn start_http_server_with_db(db: SqlitePool, overlays_dir: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+///
+/// `listener`は起動時に確保したポート（`addresses::bind_with_fallback`）。
+/// `tls`を指定した場合はHTTPSで配信する
+pub async fn start_http_server_with_db(
+    listener: tokio::net::TcpListener,
+    db: SqlitePool,
+    overlays_dir: PathBuf,
+    auth_token: AuthToken,
+    tls: Option<TlsAcceptor>,
+) -> Result<(), Box<dyn std::error::Error>> {
     let state = HttpState {
         db: Arc::new(db),
         overlays_dir,
+        auth_token,
     };
 
     // 静的ファイル配信
@@ -52,16 +67,52 @@ pub async fn start_http_server_with_db(db: SqlitePool, overlays_dir: PathBuf) ->
         .nest_service("/overlay/shared", serve_shared)
         .nest_service("/overlay/components", serve_components)
         .nest_service("/overlay/styles", serve_styles)
-        .layer(CorsLayer::permissive())
+        .layer(
+            // ループバックと許可リストのオリジンのみ許可（APIは読み取り専用）
+            CorsLayer::new()
+                .allow_origin(AllowOrigin::predicate(|origin, _| cors::is_allowed_origin(origin)))
+                .allow_methods([axum::http::Method::GET]),
+        )
         .with_state(state);
 
-    let addr = "127.0.0.1:19800";
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    log::info!("HTTP server listening on http://{}", addr);
+    let addr = listener.local_addr()?;
+
+    let Some(acceptor) = tls else {
+        log::info!("HTTP server listening on http://{}", addr);
+        axum::serve(listener, app).await?;
+        return Ok(());
+    };
 
-    axum::serve(listener, app).await?;
+    log::info!("HTTP server listening on https://{}", addr);
+    serve_tls(listener, app, acceptor).await
+}
 
-    Ok(())
+/// TLSでHTTPリクエストを処理（axum::serveはTLS非対応のため接続ごとにhyperで処理）
+async fn serve_tls(
+    listener: tokio::net::TcpListener,
+    app: Router,
+    acceptor: TlsAcceptor,
+) -> Result<(), Box<dyn std::error::Error>> {
+    loop {
+        let (stream, peer_addr) = listener.accept().await?;
+        let acceptor = acceptor.clone();
+        let service = hyper_util::service::TowerToHyperService::new(app.clone());
+        tokio::spawn(async move {
+            let stream = match acceptor.accept(stream).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    // 証明書を信頼していないブラウザは握手を中断するため、警告にとどめる
+                    log::warn!("TLS handshake failed from {}: {}", peer_addr, e);
+                    return;
+                }
+            };
+            let connection = hyper::server::conn::http1::Builder::new()
+                .serve_connection(hyper_util::rt::TokioIo::new(stream), service);
+            if let Err(e) = connection.await {
+                log::debug!("HTTPS connection error from {}: {}", peer_addr, e);
+            }
+        });
+    }
 }
 
 /// ヘルスチェックエンドポイント
@@ -73,8 +124,32 @@ async fn health_check() -> impl IntoResponse {
     }))
 }
 
+/// オーバーレイページのクエリパラメータ
+#[derive(Debug, Deserialize)]
+struct OverlayQuery {
+    /// WebSocket認証トークン（オーバーレイのJSがWebSocket接続に引き継ぐ）
+    token: Option<String>,
+}
+
+/// オーバーレイページの表示を認証（認証が無効の場合は常に許可）
+///
+/// 拒否する場合は401レスポンスを返す
+fn reject_unauthorized_overlay(state: &HttpState, query: &OverlayQuery) -> Option<Response> {
+    if ws_auth::is_authorized(&state.auth_token, query.token.as_deref()) {
+        return None;
+    }
+    log::warn!("Rejected overlay page request without valid token");
+    Some((axum::http::StatusCode::UNAUTHORIZED, "Unauthorized").into_response())
+}
+
 /// コメントオーバーレイHTML
-async fn overlay_comment(State(state): State<HttpState>) -> impl IntoResponse {
+async fn overlay_comment(
+    State(state): State<HttpState>,
+    Query(query): Query<OverlayQuery>,
+) -> impl IntoResponse {
+    if let Some(response) = reject_unauthorized_overlay(&state, &query) {
+        return response;
+    }
     let path = state.overlays_dir.join("comment.html");
     match tokio::fs::read_to_string(&path).await {
         Ok(content) => Html(content).into_response(),
@@ -90,7 +165,13 @@ async fn overlay_comment(State(state): State<HttpState>) -> impl IntoResponse {
 }
 
 /// セットリストオーバーレイHTML
-async fn overlay_setlist(State(state): State<HttpState>) -> impl IntoResponse {
+async fn overlay_setlist(
+    State(state): State<HttpState>,
+    Query(query): Query<OverlayQuery>,
+) -> impl IntoResponse {
+    if let Some(response) = reject_unauthorized_overlay(&state, &query) {
+        return response;
+    }
     let path = state.overlays_dir.join("setlist.html");
     match tokio::fs::read_to_string(&path).await {
         Ok(content) => Html(content).into_response(),
@@ -106,7 +187,13 @@ async fn overlay_setlist(State(state): State<HttpState>) -> impl IntoResponse {
 }
 
 /// 統合オーバーレイHTML（コメント＋セットリスト）
-async fn overlay_combined(State(state): State<HttpState>) -> impl IntoResponse {
+async fn overlay_combined(
+    State(state): State<HttpState>,
+    Query(query): Query<OverlayQuery>,
+) -> impl IntoResponse {
+    if let Some(response) = reject_unauthorized_overlay(&state, &query) {
+        return response;
+    }
     let path = state.overlays_dir.join("combined.html");
     match tokio::fs::read_to_string(&path).await {
         Ok(content) => Html(content).into_response(),
@@ -121,7 +208,13 @@ async fn overlay_combined(State(state): State<HttpState>) -> impl IntoResponse {
 }
 
 /// 3カラム統合オーバーレイHTML v2（22%/56%/22%固定レイアウト）
-async fn overlay_combined_v2(State(state): State<HttpState>) -> impl IntoResponse {
+async fn overlay_combined_v2(
+    State(state): State<HttpState>,
+    Query(query): Query<OverlayQuery>,
+) -> impl IntoResponse {
+    if let Some(response) = reject_unauthorized_overlay(&state, &query) {
+        return response;
+    }
     let path = state.overlays_dir.join("combined-v2.html");
     match tokio::fs::read_to_string(&path).await {
         Ok(content) => Html(content).into_response(),
@@ -159,6 +252,8 @@ struct SongInfo {
     title: String,
     artist: Option<String>,
     status: String,
+    duration_seconds: Option<i64>,
+    song_key: Option<String>,
 }
 
 /// セットリスト詳細を取得する共通関数
@@ -192,10 +287,10 @@ async fn fetch_setlist_by_id(
     };
 
     // 楽曲リスト取得
-    let songs_result = sqlx::query_as::<_, (String, i64, String, Option<String>, Option<String>, Option<String>)>(
+    let songs_result = sqlx::query_as::<_, (String, i64, String, Option<String>, Option<String>, Option<String>, Option<i64>, Option<String>)>(
         r#"
         SELECT
-            ss.id, ss.position, s.title, s.artist, ss.started_at, ss.ended_at
+            ss.id, ss.position, s.title, s.artist, ss.started_at, ss.ended_at, s.duration_seconds, s.song_key
         FROM setlist_songs ss
         JOIN songs s ON ss.song_id = s.id
         WHERE ss.setlist_id = ?
@@ -246,6 +341,8 @@ async fn fetch_setlist_by_id(
                 title: row.2,
                 artist: row.3,
                 status,
+                duration_seconds: row.6,
+                song_key: row.7,
             }
         })
         .collect();
@@ -338,22 +435,8 @@ fn default_overlay_settings() -> OverlaySettingsApiResponse {
             show_artist: true,
             font_size: 24,
         },
-        weather: Some(WeatherSettings {
-            enabled: true,
-            position: WeatherPosition::LeftTop,
-            multi_city: None,
-        }),
-        widget: Some(WidgetVisibilitySettings {
-            clock: true,
-            weather: true,
-            comment: true,
-            superchat: true,
-            logo: true,
-            setlist: true,
-            kpi: true,
-            tanzaku: true,
-            announcement: true,
-        }),
+        weather: Some(WeatherSettings::default()),
+        widget: Some(WidgetVisibilitySettings::default()),
         // デフォルト値を使用（ThemeSettings::default()はすでに正規化済みの値）
         theme_settings: Some(ThemeSettings::default()),
     }
@@ -362,7 +445,7 @@ fn default_overlay_settings() -> OverlaySettingsApiResponse {
 /// 保存されているオーバーレイ設定を取得
 ///
 /// ## 実装ノート
-/// - DBに保存されている`OverlaySettings`を直接デシリアライズ
+/// - DBに保存されている`OverlaySettings`をデシリアライズ（旧バージョンの形式は現在の形式に変換）
 /// - `From<OverlaySettings>`トレイトでAPI応答形式に変換
 /// - 手動パースを排除しシンプル化（PR#95レビュー対応）
 async fn get_overlay_settings_api(
@@ -377,9 +460,9 @@ async fn get_overlay_settings_api(
 
     match result {
         Ok(Some((json_str,))) => {
-            // OverlaySettingsを直接デシリアライズ
-            match serde_json::from_str::<OverlaySettings>(&json_str) {
-                Ok(settings) => {
+            // 旧バージョンの形式は現在の形式に変換してデシリアライズ（DBへの書き戻しはload_overlay_settingsで行う）
+            match parse_overlay_settings(&json_str) {
+                Ok((settings, _)) => {
                     // From トレイトでAPI応答形式に変換
                     let response: OverlaySettingsApiResponse = settings.into();
                     Json(response).into_response()
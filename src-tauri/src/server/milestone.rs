@@ -0,0 +1,221 @@
+//! 配信中のマイルストーン（コメント数・スパチャ金額の節目）
+//!
+//! ブロードキャストしたコメント数とスパチャ合計額（円換算）を数え（テスト送信のコメントは数えない）、
+//! 閾値を超えた時点で`milestone`メッセージをオーバーレイへ配信する。
+//! 各閾値は1セッションにつき1回だけ発火する（ポーリング開始時にリセット）。
+
+use serde::{Deserialize, Serialize};
+
+use super::types::{MilestoneKind, MilestonePayload, WsMessage};
+use crate::superchat::{convert_to_jpy, create_superchat_payload};
+use crate::youtube::types::ChatMessage;
+
+/// 種別ごとの閾値の最大件数
+pub const MAX_MILESTONE_THRESHOLDS: usize = 20;
+
+/// マイルストーンの閾値設定
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MilestoneThresholds {
+    /// コメント数の閾値
+    pub comments: Vec<u64>,
+    /// スパチャ合計額（円換算）の閾値
+    pub superchat_jpy: Vec<u64>,
+}
+
+impl Default for MilestoneThresholds {
+    fn default() -> Self {
+        Self {
+            comments: vec![100, 500, 1000],
+            superchat_jpy: vec![10_000, 50_000],
+        }
+    }
+}
+
+impl MilestoneThresholds {
+    /// 閾値を検証し、昇順・重複なしに正規化
+    ///
+    /// ## 入力検証
+    /// - 種別ごとに最大20件
+    /// - 0は不可
+    pub fn normalize(mut self) -> Result<Self, String> {
+        for (label, thresholds) in [
+            ("コメント数", &mut self.comments),
+            ("スパチャ金額", &mut self.superchat_jpy),
+        ] {
+            if thresholds.len() > MAX_MILESTONE_THRESHOLDS {
+                return Err(format!(
+                    "{}の閾値は{}件以下で指定してください: {}件",
+                    label,
+                    MAX_MILESTONE_THRESHOLDS,
+                    thresholds.len()
+                ));
+            }
+            if thresholds.contains(&0) {
+                return Err(format!("{}の閾値は1以上で指定してください", label));
+            }
+            thresholds.sort_unstable();
+            thresholds.dedup();
+        }
+        Ok(self)
+    }
+}
+
+/// マイルストーンの集計状態（WebSocketStateごとに1つ）
+#[derive(Debug, Default)]
+pub struct MilestoneTracker {
+    thresholds: MilestoneThresholds,
+    /// セッション中にブロードキャストしたコメント数
+    comment_count: u64,
+    /// セッション中のスパチャ合計額（円換算）
+    superchat_jpy: u64,
+}
+
+impl MilestoneTracker {
+    /// 閾値を更新（集計値は保持し、既に超えた閾値は発火しない）
+    pub fn set_thresholds(&mut self, thresholds: MilestoneThresholds) {
+        self.thresholds = thresholds;
+    }
+
+    /// 現在の閾値
+    pub fn thresholds(&self) -> &MilestoneThresholds {
+        &self.thresholds
+    }
+
+    /// 集計をリセット（新しいセッションの開始）
+    pub fn reset(&mut self) {
+        self.comment_count = 0;
+        self.superchat_jpy = 0;
+    }
+
+    /// コメント1件を集計し、新たに超えた閾値のマイルストーンを返す
+    ///
+    /// スパチャ・金額付きスーパーステッカーは円換算額も加算する
+    pub fn record_comment(&mut self, message: &ChatMessage) -> Vec<WsMessage> {
+        let mut milestones = Vec::new();
+
+        let previous = self.comment_count;
+        self.comment_count += 1;
+        milestones.extend(
+            crossed(&self.thresholds.comments, previous, self.comment_count)
+                .map(|value| milestone_message(MilestoneKind::Comments, value)),
+        );
+
+        if let Some(payload) = create_superchat_payload(message) {
+            let previous = self.superchat_jpy;
+            self.superchat_jpy = self
+                .superchat_jpy
+                .saturating_add(convert_to_jpy(payload.amount_micros, &payload.currency));
+            milestones.extend(
+                crossed(&self.thresholds.superchat_jpy, previous, self.superchat_jpy)
+                    .map(|value| milestone_message(MilestoneKind::SuperchatJpy, value)),
+            );
+        }
+
+        milestones
+    }
+}
+
+/// `previous`から`current`に増えた際に超えた閾値（`previous < t <= current`）
+fn crossed(thresholds: &[u64], previous: u64, current: u64) -> impl Iterator<Item = u64> + '_ {
+    thresholds
+        .iter()
+        .copied()
+        .filter(move |&t| previous < t && t <= current)
+}
+
+fn milestone_message(kind: MilestoneKind, value: u64) -> WsMessage {
+    log::info!("Milestone reached: {:?} {}", kind, value);
+    WsMessage::Milestone {
+        payload: MilestonePayload { kind, value },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::youtube::types::MessageType;
+
+    fn comment(message_type: MessageType) -> ChatMessage {
+        ChatMessage::test_message("c1").with_type(message_type)
+    }
+
+    fn superchat(yen: u64) -> ChatMessage {
+        comment(MessageType::SuperChat {
+            amount: format!("¥{}", yen),
+            currency: "JPY".to_string(),
+            amount_micros: Some(yen * 1_000_000),
+        })
+    }
+
+    fn milestones(messages: &[WsMessage]) -> Vec<(MilestoneKind, u64)> {
+        messages
+            .iter()
+            .map(|message| match message {
+                WsMessage::Milestone { payload } => (payload.kind, payload.value),
+                other => panic!("unexpected message: {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_comment_milestone_fires_once() {
+        let mut tracker = MilestoneTracker::default();
+
+        let fired: Vec<_> = (0..150)
+            .flat_map(|_| milestones(&tracker.record_comment(&comment(MessageType::Text))))
+            .collect();
+        // 100件目で1回だけ発火し、以降のコメントでは再発火しない
+        assert_eq!(fired, vec![(MilestoneKind::Comments, 100)]);
+
+        // リセット後は再び発火する
+        tracker.reset();
+        let fired: Vec<_> = (0..100)
+            .flat_map(|_| milestones(&tracker.record_comment(&comment(MessageType::Text))))
+            .collect();
+        assert_eq!(fired, vec![(MilestoneKind::Comments, 100)]);
+    }
+
+    #[test]
+    fn test_superchat_milestone_fires_once_per_threshold() {
+        let mut tracker = MilestoneTracker::default();
+        tracker.set_thresholds(MilestoneThresholds {
+            comments: Vec::new(),
+            superchat_jpy: vec![10_000, 50_000],
+        });
+
+        assert!(tracker.record_comment(&superchat(5_000)).is_empty());
+        assert_eq!(
+            milestones(&tracker.record_comment(&superchat(6_000))),
+            vec![(MilestoneKind::SuperchatJpy, 10_000)]
+        );
+        assert!(tracker.record_comment(&superchat(1_000)).is_empty());
+        // 1件で複数の閾値を超えた場合はそれぞれ発火
+        tracker.reset();
+        assert_eq!(
+            milestones(&tracker.record_comment(&superchat(60_000))),
+            vec![
+                (MilestoneKind::SuperchatJpy, 10_000),
+                (MilestoneKind::SuperchatJpy, 50_000)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_thresholds_normalize() {
+        let thresholds = MilestoneThresholds {
+            comments: vec![500, 100, 500],
+            superchat_jpy: Vec::new(),
+        }
+        .normalize()
+        .unwrap();
+        assert_eq!(thresholds.comments, vec![100, 500]);
+
+        assert!(MilestoneThresholds {
+            comments: vec![0],
+            superchat_jpy: Vec::new(),
+        }
+        .normalize()
+        .is_err());
+    }
+}
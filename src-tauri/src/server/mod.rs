@@ -1,7 +1,17 @@
+pub mod addresses;
+pub mod chat_rate;
+pub mod comment_theme;
+pub mod comment_throttle;
+pub mod cors;
 mod http;
+pub mod milestone;
+pub mod self_test;
 pub mod template_types;
+pub mod tls;
 pub mod types;
 pub mod websocket;
+pub mod ws_auth;
+pub mod ws_topics;
 
 pub use http::start_http_server_with_db;
 pub use types::ServerState;
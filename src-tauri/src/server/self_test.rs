@@ -0,0 +1,405 @@
+//! 配信前のセルフテスト
+//!
+//! 「オーバーレイに何も表示されない」原因になりやすい設定ミス（ポートの誤り・サーバーの起動失敗等）を
+//! 配信前に見つけられるよう、アプリ自身のサーバーに接続して一通りの経路を確認する。
+//!
+//! - HTTP: `/api/health`が応答するか
+//! - WebSocket: 自分自身のサーバーに接続し、ブロードキャストした確認用メッセージを受信できるか
+//! - DB: 書き込みできるか（確認用の書き込みはロールバックして残さない）
+
+use futures_util::StreamExt;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::net::IpAddr;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+use super::addresses::{self, ServerAddresses};
+use super::tls;
+use super::types::{SelfTestPayload, ServerState, WsMessage};
+
+/// 各確認のタイムアウト
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 確認用の書き込みに使うsettingsのキー（ロールバックするため保存されない）
+const SELF_TEST_SETTINGS_KEY: &str = "self_test";
+
+/// 1項目の確認結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestCheck {
+    pub ok: bool,
+    /// 結果の説明（失敗時は原因）
+    pub message: String,
+    /// 確認にかかった時間（ミリ秒）
+    pub elapsed_ms: u64,
+}
+
+impl SelfTestCheck {
+    fn from_result(result: Result<String, String>, started: Instant) -> Self {
+        let elapsed_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+        match result {
+            Ok(message) => Self { ok: true, message, elapsed_ms },
+            Err(message) => Self { ok: false, message, elapsed_ms },
+        }
+    }
+}
+
+/// セルフテストの結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestReport {
+    /// すべての確認に成功したか
+    pub ok: bool,
+    pub http: SelfTestCheck,
+    pub websocket: SelfTestCheck,
+    pub database: SelfTestCheck,
+}
+
+/// 時間を計りながら確認を実行（タイムアウトは失敗として扱う）
+async fn timed_check<F>(check: F) -> SelfTestCheck
+where
+    F: std::future::Future<Output = Result<String, String>>,
+{
+    let started = Instant::now();
+    let result = tokio::time::timeout(CHECK_TIMEOUT, check)
+        .await
+        .unwrap_or_else(|_| Err(format!("{}秒以内に応答がありませんでした", CHECK_TIMEOUT.as_secs())));
+    SelfTestCheck::from_result(result, started)
+}
+
+/// 自分自身のサーバーに接続するホスト名
+///
+/// 全インターフェース（0.0.0.0等）・ループバックで待ち受けている場合はオーバーレイURLと同じlocalhost
+fn connect_host(bind_address: &str) -> String {
+    match bind_address.parse::<IpAddr>() {
+        Ok(address) if !address.is_unspecified() && !address.is_loopback() => match address {
+            IpAddr::V4(v4) => v4.to_string(),
+            IpAddr::V6(v6) => format!("[{}]", v6),
+        },
+        _ => "localhost".to_string(),
+    }
+}
+
+/// セルフテストを実行
+///
+/// `tls_dir`はTLSで配信している場合に信頼する自己署名証明書の保存先
+pub async fn run(server: &ServerState, pool: &SqlitePool, tls_dir: &Path) -> SelfTestReport {
+    let addresses = addresses::current();
+    let host = connect_host(&addresses.bind_address);
+
+    let http = timed_check(check_http_from(&addresses, &host)).await;
+    let websocket = timed_check(check_websocket_from(server, &addresses, &host, tls_dir)).await;
+    let database = timed_check(check_database(pool)).await;
+
+    SelfTestReport {
+        ok: http.ok && websocket.ok && database.ok,
+        http,
+        websocket,
+        database,
+    }
+}
+
+async fn check_http_from(addresses: &ServerAddresses, host: &str) -> Result<String, String> {
+    let port = addresses.http_port.ok_or_else(|| {
+        format!(
+            "HTTPサーバーが起動していません: {}",
+            addresses.http_error.as_deref().unwrap_or("原因不明")
+        )
+    })?;
+    let scheme = if addresses.tls { "https" } else { "http" };
+    check_http(&format!("{}://{}:{}", scheme, host, port)).await
+}
+
+/// HTTPサーバーの`/api/health`が応答するか確認
+///
+/// 自分自身への接続のため、TLSの自己署名証明書は検証しない
+pub async fn check_http(base_url: &str) -> Result<String, String> {
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .timeout(CHECK_TIMEOUT)
+        .build()
+        .map_err(|e| format!("HTTPクライアントを作成できませんでした: {}", e))?;
+    let url = format!("{}/api/health", base_url);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("{}に接続できませんでした: {}", base_url, e.without_url()))?;
+    if !response.status().is_success() {
+        return Err(format!("{}がエラーを返しました: {}", url, response.status()));
+    }
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("{}の応答を解析できませんでした: {}", url, e.without_url()))?;
+    if body["status"] != "ok" {
+        return Err(format!("{}の応答が不正です: {}", url, body));
+    }
+    Ok(format!("{}が応答しました", base_url))
+}
+
+async fn check_websocket_from(
+    server: &ServerState,
+    addresses: &ServerAddresses,
+    host: &str,
+    tls_dir: &Path,
+) -> Result<String, String> {
+    let port = addresses.ws_port.ok_or_else(|| {
+        format!(
+            "WebSocketサーバーが起動していません: {}",
+            addresses.ws_error.as_deref().unwrap_or("原因不明")
+        )
+    })?;
+    let connector = if addresses.tls {
+        Some(tls::load_client_connector(tls_dir)?)
+    } else {
+        None
+    };
+    check_websocket(server, host, port, connector).await
+}
+
+/// WebSocketサーバーに接続し、ブロードキャストした確認用メッセージを受信できるか確認
+///
+/// `connector`はTLSで配信している場合の接続（証明書はlocalhost向けのため常にlocalhostとして検証）
+pub async fn check_websocket(
+    server: &ServerState,
+    host: &str,
+    port: u16,
+    connector: Option<tokio_rustls::TlsConnector>,
+) -> Result<String, String> {
+    // 認証が有効な場合はオーバーレイと同じトークンを提示（結果のメッセージにはトークンを含めない）
+    let display_url = format!("{}://{}:{}/ws", if connector.is_some() { "wss" } else { "ws" }, host, port);
+    let url = match server.read().await.auth_token() {
+        Some(token) => format!("{}?token={}", display_url, token),
+        None => display_url.clone(),
+    };
+
+    let tcp = tokio::net::TcpStream::connect((host.trim_matches(['[', ']']), port))
+        .await
+        .map_err(|e| format!("{}に接続できませんでした: {}", display_url, e))?;
+    match connector {
+        Some(connector) => {
+            let server_name = ServerName::try_from("localhost")
+                .map_err(|e| format!("TLS config error: {}", e))?;
+            let stream = connector
+                .connect(server_name, tcp)
+                .await
+                .map_err(|e| format!("{}とのTLS接続に失敗しました: {}", display_url, e))?;
+            loopback(server, &url, &display_url, stream).await
+        }
+        None => loopback(server, &url, &display_url, tcp).await,
+    }
+}
+
+/// 接続してから確認用メッセージをブロードキャストし、受信できるまで待つ
+async fn loopback<S>(server: &ServerState, url: &str, display_url: &str, stream: S) -> Result<String, String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (mut client, _) = tokio_tungstenite::client_async(url, stream)
+        .await
+        .map_err(|e| format!("{}とのハンドシェイクに失敗しました: {}", display_url, e))?;
+
+    // 接続時のスナップショット（先頭は接続状態）を受信した時点でピアとして登録済み
+    next_text(&mut client, display_url).await?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    server
+        .read()
+        .await
+        .broadcast(WsMessage::SelfTest {
+            payload: SelfTestPayload { id: id.clone() },
+        })
+        .await;
+
+    loop {
+        let text = next_text(&mut client, display_url).await?;
+        let Ok(message) = serde_json::from_str::<serde_json::Value>(&text) else {
+            continue;
+        };
+        if contains_self_test(&message, &id) {
+            let _ = client.close(None).await;
+            return Ok(format!("{}でメッセージを送受信できました", display_url));
+        }
+    }
+}
+
+/// 次のテキストフレームを受信（切断された場合はエラー）
+async fn next_text<S>(client: &mut WebSocketStream<S>, display_url: &str) -> Result<String, String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    while let Some(frame) = client.next().await {
+        match frame.map_err(|e| format!("{}からの受信に失敗しました: {}", display_url, e))? {
+            Message::Text(text) => return Ok(text),
+            Message::Close(frame) => {
+                let reason = match frame {
+                    Some(frame) if u16::from(frame.code) == super::ws_auth::UNAUTHORIZED_CLOSE_CODE => {
+                        "認証に失敗しました".to_string()
+                    }
+                    Some(frame) => frame.reason.to_string(),
+                    None => String::new(),
+                };
+                return Err(format!("{}から切断されました: {}", display_url, reason));
+            }
+            _ => {}
+        }
+    }
+    Err(format!("{}から切断されました", display_url))
+}
+
+/// 受信したメッセージ（バンドル送信の場合は結合フレーム内も含む）が指定IDの確認用メッセージか
+fn contains_self_test(message: &serde_json::Value, id: &str) -> bool {
+    match message["type"].as_str() {
+        Some("selftest:ping") => message["payload"]["id"] == id,
+        Some("bundle") => message["messages"]
+            .as_array()
+            .is_some_and(|messages| messages.iter().any(|message| contains_self_test(message, id))),
+        _ => false,
+    }
+}
+
+/// DBに書き込めるか確認（書き込みはロールバックして残さない）
+pub async fn check_database(pool: &SqlitePool) -> Result<String, String> {
+    let mut tx = pool.begin().await.map_err(|e| format!("DB error: {}", e))?;
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(SELF_TEST_SETTINGS_KEY)
+    .bind("1")
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("DBに書き込めませんでした: {}", e))?;
+    tx.rollback().await.map_err(|e| format!("DB error: {}", e))?;
+    Ok("DBに書き込めました".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{create_server_state, start_http_server_with_db, start_websocket_server};
+    use std::sync::Arc;
+    use tokio::net::TcpListener;
+    use tokio_rustls::TlsAcceptor;
+
+    /// WebSocketサーバーを空いているポートで起動してポートを返す
+    async fn spawn_websocket_server(server: &ServerState, db: SqlitePool, tls: Option<TlsAcceptor>) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = Arc::clone(server);
+        tokio::spawn(async move {
+            if let Err(e) = start_websocket_server(listener, server, db, tls).await {
+                log::error!("WebSocket server error: {}", e);
+            }
+        });
+        port
+    }
+
+    async fn create_db() -> (tempfile::NamedTempFile, SqlitePool) {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let pool = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        (temp_file, pool)
+    }
+
+    #[test]
+    fn test_connect_host() {
+        assert_eq!(connect_host("127.0.0.1"), "localhost");
+        assert_eq!(connect_host("0.0.0.0"), "localhost");
+        assert_eq!(connect_host("::"), "localhost");
+        assert_eq!(connect_host("192.168.1.10"), "192.168.1.10");
+        assert_eq!(connect_host("fe80::1"), "[fe80::1]");
+    }
+
+    #[test]
+    fn test_contains_self_test_in_bundle() {
+        let ping = serde_json::json!({ "type": "selftest:ping", "payload": { "id": "abc" } });
+        assert!(contains_self_test(&ping, "abc"));
+        assert!(!contains_self_test(&ping, "other"));
+
+        let bundle = serde_json::json!({
+            "type": "bundle",
+            "messages": [{ "type": "kpi:update", "payload": {} }, ping]
+        });
+        assert!(contains_self_test(&bundle, "abc"));
+    }
+
+    #[tokio::test]
+    async fn test_http_health() {
+        let (_db_file, db) = create_db().await;
+        let server = create_server_state();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let auth_token = server.read().await.auth_token_handle();
+        let overlays_dir = tempfile::tempdir().unwrap();
+        let overlays_path = overlays_dir.path().to_path_buf();
+        tokio::spawn(async move {
+            if let Err(e) = start_http_server_with_db(listener, db, overlays_path, auth_token, None).await {
+                log::error!("HTTP server error: {}", e);
+            }
+        });
+
+        check_http(&format!("http://127.0.0.1:{}", port)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_websocket_loopback() {
+        let (_db_file, db) = create_db().await;
+        let server = create_server_state();
+        let port = spawn_websocket_server(&server, db, None).await;
+
+        let message = check_websocket(&server, "127.0.0.1", port, None).await.unwrap();
+        assert!(message.contains(&port.to_string()));
+
+        // 認証が有効な場合もサーバーのトークンで接続できる
+        server.read().await.set_auth_token(Some("secret".to_string()));
+        check_websocket(&server, "127.0.0.1", port, None).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_websocket_loopback_over_tls() {
+        let (_db_file, db) = create_db().await;
+        let server = create_server_state();
+        let cert_dir = tempfile::tempdir().unwrap();
+        let acceptor = tls::load_or_create_acceptor(cert_dir.path()).unwrap();
+        let port = spawn_websocket_server(&server, db, Some(acceptor)).await;
+
+        let connector = tls::load_client_connector(cert_dir.path()).unwrap();
+        let message = check_websocket(&server, "127.0.0.1", port, Some(connector)).await.unwrap();
+        assert!(message.contains("wss://"));
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_servers_fail() {
+        let server = create_server_state();
+        // 空いているポートを取得してから閉じる（接続先がない状態）
+        let port = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port();
+
+        assert!(check_websocket(&server, "127.0.0.1", port, None).await.is_err());
+        assert!(check_http(&format!("http://127.0.0.1:{}", port)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_database_check_leaves_no_row() {
+        let (_db_file, db) = create_db().await;
+        check_database(&db).await.unwrap();
+
+        let value: Option<String> = sqlx::query_scalar("SELECT value FROM settings WHERE key = ?")
+            .bind(SELF_TEST_SETTINGS_KEY)
+            .fetch_optional(&db)
+            .await
+            .unwrap();
+        assert_eq!(value, None);
+    }
+}
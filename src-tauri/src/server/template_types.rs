@@ -6,6 +6,7 @@
 //! Schema: src-tauri/schemas/template-mvp-1.0.json
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use super::types::SlotId;
 
@@ -256,6 +257,354 @@ impl Default for Template {
     }
 }
 
+// ===== テキストテンプレート（プレースホルダー） =====
+
+/// テキストテンプレートの種別
+///
+/// 種別ごとに使用可能な`{{placeholder}}`が異なる
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextTemplateKind {
+    /// コメント表示
+    Comment,
+    /// スーパーチャット表示
+    Superchat,
+    /// KPI表示
+    Kpi,
+    /// 天気表示
+    Weather,
+}
+
+impl TextTemplateKind {
+    /// 全種別
+    pub const ALL: [TextTemplateKind; 4] = [
+        TextTemplateKind::Comment,
+        TextTemplateKind::Superchat,
+        TextTemplateKind::Kpi,
+        TextTemplateKind::Weather,
+    ];
+
+    /// テンプレート名から種別を取得
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "comment" => Some(Self::Comment),
+            "superchat" => Some(Self::Superchat),
+            "kpi" => Some(Self::Kpi),
+            "weather" => Some(Self::Weather),
+            _ => None,
+        }
+    }
+
+    /// 種別ごとに使用可能なプレースホルダー一覧
+    ///
+    /// 各オーバーレイに配信されるペイロードのフィールド名（camelCase）に対応
+    pub fn placeholders(&self) -> &'static [&'static str] {
+        match self {
+            Self::Comment => &["authorName", "message", "timestamp", "authorChannelId"],
+            Self::Superchat => &["authorName", "message", "amount", "currency", "tier"],
+            Self::Kpi => &["main", "label", "sub", "subLabel"],
+            Self::Weather => &[
+                "icon",
+                "temp",
+                "tempUnit",
+                "description",
+                "location",
+                "humidity",
+                "windSpeed",
+                "windSpeedUnit",
+                "windCompass",
+            ],
+        }
+    }
+}
+
+/// 条件ブロック（`{{#if flag}}...{{/if}}`）で使用可能なフラグ
+///
+/// メッセージの種別・投稿者の属性を表す（ペイロードのフィールド名ではない）
+pub const CONDITION_FLAGS: [&str; 4] = ["is_owner", "is_moderator", "is_member", "is_superchat"];
+
+impl TextTemplateKind {
+    /// 種別ごとに条件ブロックで使用可能なフラグ（メッセージ以外の種別はなし）
+    pub fn condition_flags(&self) -> &'static [&'static str] {
+        match self {
+            Self::Comment | Self::Superchat => &CONDITION_FLAGS,
+            Self::Kpi | Self::Weather => &[],
+        }
+    }
+}
+
+/// 全種別で使用可能なプレースホルダーの一覧（正規の変数名カタログ、重複なし）
+pub fn all_placeholders() -> Vec<&'static str> {
+    let mut names: Vec<&'static str> = Vec::new();
+    for kind in TextTemplateKind::ALL {
+        for name in kind.placeholders() {
+            if !names.contains(name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+impl ComponentType {
+    /// コンポーネントが表示するデータに対応するテキストテンプレート種別
+    ///
+    /// 対応するデータがないコンポーネントはNone（全種別のプレースホルダーを許可する）
+    pub fn text_template_kind(&self) -> Option<TextTemplateKind> {
+        match self {
+            Self::ChatLog => Some(TextTemplateKind::Comment),
+            Self::SuperChatCard => Some(TextTemplateKind::Superchat),
+            Self::KpiBlock => Some(TextTemplateKind::Kpi),
+            Self::WeatherWidget => Some(TextTemplateKind::Weather),
+            _ => None,
+        }
+    }
+}
+
+/// テキストテンプレートの検証エラー
+///
+/// `position`はテンプレート文字列先頭からの文字数（`{{`の位置）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TemplateError {
+    /// 未知のテンプレート名（種別を判定できない）
+    #[serde(rename_all = "camelCase")]
+    UnknownTemplate { name: String },
+    /// この種別では使用できないプレースホルダー
+    ///
+    /// 使用可能な名前のタイプミスと思われる場合は`suggestion`に候補を入れる
+    #[serde(rename_all = "camelCase")]
+    UnknownPlaceholder {
+        name: String,
+        position: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        suggestion: Option<String>,
+    },
+    /// `{{`に対応する`}}`がない
+    #[serde(rename_all = "camelCase")]
+    UnclosedPlaceholder { position: usize },
+    /// `{{}}`のように名前が空
+    #[serde(rename_all = "camelCase")]
+    EmptyPlaceholder { position: usize },
+    /// `{{#if flag}}`のフラグがこの種別では使用できない
+    #[serde(rename_all = "camelCase")]
+    UnknownCondition {
+        name: String,
+        position: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        suggestion: Option<String>,
+    },
+    /// `{{#if}}`に対応する`{{/if}}`がない（`position`は`{{#if}}`の位置）
+    #[serde(rename_all = "camelCase")]
+    UnclosedCondition { position: usize },
+    /// 対応する`{{#if}}`がない`{{/if}}`
+    #[serde(rename_all = "camelCase")]
+    UnmatchedEndIf { position: usize },
+}
+
+/// テンプレート文字列の字句
+enum TemplateToken<'a> {
+    /// そのまま出力する文字列
+    Text(&'a str),
+    /// `{{name}}`
+    Placeholder { name: &'a str, position: usize },
+    /// `{{#if flag}}`
+    If { flag: &'a str, position: usize },
+    /// `{{/if}}`
+    EndIf { position: usize },
+    /// 字句レベルのエラー（空・閉じていない`{{`）
+    Invalid(TemplateError),
+}
+
+/// テンプレート文字列を字句に分割
+///
+/// 閉じていない`{{`以降は解析しない
+fn tokenize(text: &str) -> Vec<TemplateToken<'_>> {
+    let mut tokens = Vec::new();
+    let mut cursor = 0;
+
+    while let Some(offset) = text[cursor..].find("{{") {
+        let open = cursor + offset;
+        let position = text[..open].chars().count();
+        let inner_start = open + 2;
+        if open > cursor {
+            tokens.push(TemplateToken::Text(&text[cursor..open]));
+        }
+
+        let Some(close_offset) = text[inner_start..].find("}}") else {
+            tokens.push(TemplateToken::Invalid(TemplateError::UnclosedPlaceholder {
+                position,
+            }));
+            return tokens;
+        };
+
+        let name = text[inner_start..inner_start + close_offset].trim();
+        let token = if name.is_empty() {
+            TemplateToken::Invalid(TemplateError::EmptyPlaceholder { position })
+        } else if name == "/if" {
+            TemplateToken::EndIf { position }
+        } else if let Some(flag) = name
+            .strip_prefix("#if")
+            .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+        {
+            TemplateToken::If {
+                flag: flag.trim(),
+                position,
+            }
+        } else {
+            TemplateToken::Placeholder { name, position }
+        };
+        tokens.push(token);
+
+        cursor = inner_start + close_offset + 2;
+    }
+
+    if cursor < text.len() {
+        tokens.push(TemplateToken::Text(&text[cursor..]));
+    }
+    tokens
+}
+
+/// テキストテンプレートをプレースホルダーカタログに対して検証
+///
+/// エラーがなければ空のVecを返す
+pub fn validate_text_template(kind: TextTemplateKind, text: &str) -> Vec<TemplateError> {
+    validate_placeholders(kind.placeholders(), kind.condition_flags(), text)
+}
+
+/// 文字列中の`{{...}}`を抽出し、使用可能なプレースホルダー・条件フラグに対して検証
+///
+/// 条件ブロックは入れ子にでき、`{{#if}}`と`{{/if}}`の対応も検証する。
+/// エラーがなければ空のVecを返す
+pub fn validate_placeholders(allowed: &[&str], flags: &[&str], text: &str) -> Vec<TemplateError> {
+    let mut errors = Vec::new();
+    let mut open_conditions = Vec::new();
+
+    for token in tokenize(text) {
+        match token {
+            TemplateToken::Text(_) => {}
+            TemplateToken::Placeholder { name, position } => {
+                if !allowed.contains(&name) {
+                    errors.push(TemplateError::UnknownPlaceholder {
+                        name: name.to_string(),
+                        position,
+                        suggestion: suggest_placeholder(allowed, name).map(str::to_string),
+                    });
+                }
+            }
+            TemplateToken::If { flag, position } => {
+                if !flags.contains(&flag) {
+                    errors.push(TemplateError::UnknownCondition {
+                        name: flag.to_string(),
+                        position,
+                        suggestion: suggest_placeholder(flags, flag).map(str::to_string),
+                    });
+                }
+                open_conditions.push(position);
+            }
+            TemplateToken::EndIf { position } => {
+                if open_conditions.pop().is_none() {
+                    errors.push(TemplateError::UnmatchedEndIf { position });
+                }
+            }
+            TemplateToken::Invalid(error) => errors.push(error),
+        }
+    }
+
+    errors.extend(
+        open_conditions
+            .into_iter()
+            .map(|position| TemplateError::UnclosedCondition { position }),
+    );
+    errors
+}
+
+/// テキストテンプレートを描画
+///
+/// プレースホルダーは`values`の値に置き換え（ない場合は空文字列）、
+/// 条件ブロックは`flags`に含まれるフラグの場合のみ出力する。
+/// ブロックの対応が取れていないなど構造が不正な場合はエラーを返す
+pub fn render_text_template(
+    text: &str,
+    values: &HashMap<String, String>,
+    flags: &[&str],
+) -> Result<String, Vec<TemplateError>> {
+    let mut output = String::new();
+    let mut errors = Vec::new();
+    // 開いている条件ブロック（位置, 条件が真か）
+    let mut conditions: Vec<(usize, bool)> = Vec::new();
+
+    for token in tokenize(text) {
+        let visible = conditions.iter().all(|(_, enabled)| *enabled);
+        match token {
+            TemplateToken::Text(text) if visible => output.push_str(text),
+            TemplateToken::Placeholder { name, .. } if visible => {
+                if let Some(value) = values.get(name) {
+                    output.push_str(value);
+                }
+            }
+            TemplateToken::Text(_) | TemplateToken::Placeholder { .. } => {}
+            TemplateToken::If { flag, position } => {
+                conditions.push((position, flags.contains(&flag)));
+            }
+            TemplateToken::EndIf { position } => {
+                if conditions.pop().is_none() {
+                    errors.push(TemplateError::UnmatchedEndIf { position });
+                }
+            }
+            TemplateToken::Invalid(error) => errors.push(error),
+        }
+    }
+
+    errors.extend(
+        conditions
+            .into_iter()
+            .map(|(position, _)| TemplateError::UnclosedCondition { position }),
+    );
+    if errors.is_empty() {
+        Ok(output)
+    } else {
+        Err(errors)
+    }
+}
+
+/// タイプミスとみなす編集距離の上限
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// 未知のプレースホルダーに最も近い使用可能な名前を探す
+///
+/// 大文字小文字・`_`/`-`の違いは無視する（`author_name` → `authorName`）
+fn suggest_placeholder<'a>(allowed: &[&'a str], name: &str) -> Option<&'a str> {
+    let normalize = |s: &str| -> Vec<char> {
+        s.chars()
+            .filter(|c| *c != '_' && *c != '-')
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let target = normalize(name);
+
+    allowed
+        .iter()
+        .map(|candidate| (*candidate, edit_distance(&normalize(candidate), &target)))
+        .filter(|(_, distance)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| candidate)
+}
+
+/// 2つの文字列の編集距離（レーベンシュタイン距離）
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
 // ===== クランプユーティリティ =====
 
 /// クランプ関数群
@@ -678,6 +1027,132 @@ mod tests {
         assert_eq!(parsed, LayoutType::ThreeColumn);
     }
 
+    #[test]
+    fn test_text_template_kind_from_name() {
+        assert_eq!(
+            TextTemplateKind::from_name("superchat"),
+            Some(TextTemplateKind::Superchat)
+        );
+        assert_eq!(TextTemplateKind::from_name("unknown"), None);
+    }
+
+    #[test]
+    fn test_validate_text_template_valid() {
+        let errors = validate_text_template(
+            TextTemplateKind::Weather,
+            "{{icon}} {{ location }}: {{temp}}{{tempUnit}}",
+        );
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_validate_text_template_errors() {
+        // KPI用のプレースホルダーはコメントでは使用不可
+        let errors = validate_text_template(
+            TextTemplateKind::Comment,
+            "名前{{authorName}} {{main}} {{}} {{message",
+        );
+        assert_eq!(
+            errors,
+            vec![
+                TemplateError::UnknownPlaceholder {
+                    name: "main".to_string(),
+                    position: 17,
+                    suggestion: None,
+                },
+                TemplateError::EmptyPlaceholder { position: 26 },
+                TemplateError::UnclosedPlaceholder { position: 31 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_placeholder_suggests_close_name() {
+        let errors = validate_text_template(
+            TextTemplateKind::Superchat,
+            "{{author_name}} {{amout}} {{color}}",
+        );
+        let suggestions: Vec<(&str, Option<&str>)> = errors
+            .iter()
+            .map(|e| match e {
+                TemplateError::UnknownPlaceholder {
+                    name, suggestion, ..
+                } => (name.as_str(), suggestion.as_deref()),
+                other => panic!("unexpected error: {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            suggestions,
+            vec![
+                ("author_name", Some("authorName")),
+                ("amout", Some("amount")),
+                ("color", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_conditional_blocks_balanced() {
+        let text = "{{#if is_member}}[M]{{/if}}{{authorName}}\
+                    {{#if is_superchat}} {{amount}}{{#if is_owner}}!{{/if}}{{/if}}";
+        assert!(validate_text_template(TextTemplateKind::Superchat, text).is_empty());
+
+        let values: HashMap<String, String> = [
+            ("authorName".to_string(), "Viewer".to_string()),
+            ("amount".to_string(), "¥500".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            render_text_template(text, &values, &["is_member", "is_superchat"]).unwrap(),
+            "[M]Viewer ¥500"
+        );
+        // 外側が偽の場合は内側が真でも出力しない
+        assert_eq!(
+            render_text_template(text, &values, &["is_owner"]).unwrap(),
+            "Viewer"
+        );
+    }
+
+    #[test]
+    fn test_conditional_blocks_unbalanced() {
+        // 閉じていない{{#if}}
+        let errors = validate_text_template(
+            TextTemplateKind::Comment,
+            "{{#if is_owner}}{{#if is_member}}{{authorName}}{{/if}}",
+        );
+        assert_eq!(
+            errors,
+            vec![TemplateError::UnclosedCondition { position: 0 }]
+        );
+
+        // 対応する{{#if}}がない{{/if}}
+        let text = "{{message}}{{/if}}";
+        assert_eq!(
+            validate_text_template(TextTemplateKind::Comment, text),
+            vec![TemplateError::UnmatchedEndIf { position: 11 }]
+        );
+        assert_eq!(
+            render_text_template(text, &HashMap::new(), &[]),
+            Err(vec![TemplateError::UnmatchedEndIf { position: 11 }])
+        );
+
+        // 未知のフラグ・メッセージ以外の種別では条件を使用できない
+        let errors = validate_text_template(TextTemplateKind::Comment, "{{#if superchat}}{{/if}}");
+        assert_eq!(
+            errors,
+            vec![TemplateError::UnknownCondition {
+                name: "superchat".to_string(),
+                position: 0,
+                suggestion: Some("is_superchat".to_string()),
+            }]
+        );
+        assert_eq!(
+            validate_text_template(TextTemplateKind::Kpi, "{{#if is_owner}}{{main}}{{/if}}").len(),
+            1
+        );
+    }
+
     #[test]
     fn test_layout_type_invalid_deserialization() {
         // 不正な値はデシリアライズ時にエラーになる（型レベル検証）
@@ -0,0 +1,167 @@
+//! HTTPS/WSSでのオーバーレイ配信（自己署名証明書）
+//!
+//! HTTPSのページに埋め込む場合など、混在コンテンツを拒否するブラウザソースでは
+//! オーバーレイとWebSocketをTLSで配信する必要がある。
+//!
+//! - 有効/無効はsettingsテーブルに保存する（デフォルト: 無効、変更はアプリ再起動後に反映）
+//! - 証明書は初回にlocalhost・127.0.0.1向けの自己署名証明書を生成し、アプリデータディレクトリに保存する
+//! - ポートは変えずに、HTTPサーバー・WebSocketサーバーの両方をTLSにする
+
+use std::path::Path;
+use std::sync::Arc;
+
+use sqlx::SqlitePool;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::{crypto, ClientConfig, RootCertStore, ServerConfig};
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+/// TLSの有効/無効の保存キー
+const SERVER_TLS_ENABLED_KEY: &str = "server_tls_enabled";
+
+/// 証明書の保存先（アプリデータディレクトリ内）
+pub const TLS_DIR: &str = "tls";
+
+/// 証明書ファイル名（PEM）
+pub const CERT_FILE: &str = "cert.pem";
+
+/// 秘密鍵ファイル名（PEM）
+const KEY_FILE: &str = "key.pem";
+
+/// 証明書の対象ホスト
+const CERT_SUBJECT_NAMES: &[&str] = &["localhost", "127.0.0.1"];
+
+/// 保存済みの証明書を読み込み、なければ生成して保存したうえでTLSの受付を作成
+///
+/// `dir`はアプリデータディレクトリ内の証明書の保存先
+pub fn load_or_create_acceptor(dir: &Path) -> Result<TlsAcceptor, String> {
+    let (cert_path, key_path) = (dir.join(CERT_FILE), dir.join(KEY_FILE));
+    if !cert_path.exists() || !key_path.exists() {
+        create_self_signed_cert(&cert_path, &key_path)?;
+    }
+
+    let cert = CertificateDer::from_pem_file(&cert_path)
+        .map_err(|e| format!("Failed to read TLS certificate {:?}: {}", cert_path, e))?;
+    let key = PrivateKeyDer::from_pem_file(&key_path)
+        .map_err(|e| format!("Failed to read TLS private key {:?}: {}", key_path, e))?;
+
+    let config = ServerConfig::builder_with_provider(Arc::new(crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("TLS config error: {}", e))?
+        .with_no_client_auth()
+        .with_single_cert(vec![cert], key)
+        .map_err(|e| format!("TLS config error: {}", e))?;
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// 保存済みの自己署名証明書を信頼するTLSクライアントを作成（セルフテストでの自分自身への接続用）
+pub fn load_client_connector(dir: &Path) -> Result<TlsConnector, String> {
+    let cert_path = dir.join(CERT_FILE);
+    let cert = CertificateDer::from_pem_file(&cert_path)
+        .map_err(|e| format!("Failed to read TLS certificate {:?}: {}", cert_path, e))?;
+    let mut roots = RootCertStore::empty();
+    roots
+        .add(cert)
+        .map_err(|e| format!("TLS config error: {}", e))?;
+
+    let config = ClientConfig::builder_with_provider(Arc::new(crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("TLS config error: {}", e))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(TlsConnector::from(Arc::new(config)))
+}
+
+/// localhost向けの自己署名証明書と秘密鍵を生成して保存
+fn create_self_signed_cert(cert_path: &Path, key_path: &Path) -> Result<(), String> {
+    let subject_names = CERT_SUBJECT_NAMES.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+    let certified = rcgen::generate_simple_self_signed(subject_names)
+        .map_err(|e| format!("Failed to generate TLS certificate: {}", e))?;
+
+    if let Some(dir) = cert_path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create TLS directory: {}", e))?;
+    }
+    write_private_key(key_path, &certified.key_pair.serialize_pem())
+        .map_err(|e| format!("Failed to write TLS private key: {}", e))?;
+    std::fs::write(cert_path, certified.cert.pem())
+        .map_err(|e| format!("Failed to write TLS certificate: {}", e))?;
+
+    log::info!("Generated self-signed TLS certificate: {:?}", cert_path);
+    Ok(())
+}
+
+/// 秘密鍵を所有者のみ読み書き可能なファイルとして書き込む
+///
+/// 作成時にパーミッションを指定し、他のユーザーから読める状態を経由しない。
+/// 既存のファイルは権限が異なる可能性があるため作り直す。
+fn write_private_key(path: &Path, pem: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(pem.as_bytes())
+}
+
+/// TLSが有効かをDBから読み込み（未保存の場合は無効）
+pub async fn load_server_tls_enabled(pool: &SqlitePool) -> Result<bool, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(SERVER_TLS_ENABLED_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    Ok(result.is_some_and(|(value,)| value == "true"))
+}
+
+/// TLSの有効/無効を保存
+pub async fn save_server_tls_enabled(pool: &SqlitePool, enabled: bool) -> Result<(), String> {
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(SERVER_TLS_ENABLED_KEY)
+    .bind(enabled.to_string())
+    .bind(&now)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_or_create_acceptor_reuses_certificate() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // 初回は証明書を生成して保存する
+        assert!(load_or_create_acceptor(dir.path()).is_ok());
+        let cert = std::fs::read_to_string(dir.path().join(CERT_FILE)).unwrap();
+        assert!(cert.starts_with("-----BEGIN CERTIFICATE-----"));
+        // 秘密鍵は所有者のみ読み書き可能
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dir.path().join(KEY_FILE)).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // 2回目以降は保存済みの証明書を使う
+        assert!(load_or_create_acceptor(dir.path()).is_ok());
+        assert_eq!(std::fs::read_to_string(dir.path().join(CERT_FILE)).unwrap(), cert);
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use super::websocket::WebSocketState;
-use crate::weather::WeatherData;
+use crate::weather::{ForecastData, ForecastDay, WeatherData};
 
 /// サーバー共有状態
 pub type ServerState = Arc<RwLock<WebSocketState>>;
@@ -22,12 +22,25 @@ pub enum WsMessage {
         /// バッファ間隔（ミリ秒）。InnerTubeは1000、公式APIはNone（デフォルト5000）
         #[serde(skip_serializing_if = "Option::is_none")]
         buffer_interval_ms: Option<u32>,
+        /// 初見（判定範囲内で初めてコメントした投稿者）かどうか
+        #[serde(default)]
+        is_first_time: bool,
+        /// 翻訳先言語以外で書かれたコメントの翻訳（再送時のみ。新着コメントの翻訳は`comment:translation`で届く）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        translation: Option<String>,
+        /// 不適切度（"clean" / "mild" / "strong"）。オーバーレイは"strong"の本文・翻訳をぼかし表示にする
+        #[serde(default)]
+        severity: crate::youtube::severity::Severity,
     },
 
     /// コメント削除（モデレーション）
     #[serde(rename = "comment:remove")]
     CommentRemove { payload: CommentRemovePayload },
 
+    /// コメントの翻訳（`comment:add`の配信後、翻訳できたコメントのみ）
+    #[serde(rename = "comment:translation")]
+    CommentTranslation { payload: CommentTranslationPayload },
+
     /// セットリスト更新
     #[serde(rename = "setlist:update")]
     SetlistUpdate { payload: SetlistUpdatePayload },
@@ -56,6 +69,16 @@ pub enum WsMessage {
     #[serde(rename = "weather:multi-update")]
     WeatherMultiUpdate { payload: WeatherMultiUpdatePayload },
 
+    /// 天気予報更新（3日間）
+    #[serde(rename = "weather:forecast")]
+    ForecastUpdate { payload: ForecastUpdatePayload },
+
+    /// チャット設定変更（メンバー限定モード等）
+    #[serde(rename = "chat:settings")]
+    ChatSettings {
+        payload: crate::youtube::chat_settings::ChatSettings,
+    },
+
     /// スパチャ追加（専用ウィジェット表示用）
     #[serde(rename = "superchat:add")]
     SuperchatAdd { payload: SuperchatPayload },
@@ -64,9 +87,145 @@ pub enum WsMessage {
     #[serde(rename = "superchat:remove")]
     SuperchatRemove { payload: SuperchatRemovePayload },
 
+    /// メンバーシップ加入・継続・ギフト（専用ウィジェット表示用）
+    #[serde(rename = "membership:add")]
+    MembershipAdd { payload: MembershipPayload },
+
+    /// メンバーシップ表示の削除（表示完了時）
+    #[serde(rename = "membership:remove")]
+    MembershipRemove { payload: MembershipRemovePayload },
+
     /// ブランド（ロゴ）更新
     #[serde(rename = "brand:update")]
     BrandUpdate { payload: BrandUpdatePayload },
+
+    /// 配信セッションのまとめ（エンド画面表示用）
+    #[serde(rename = "session:recap")]
+    SessionRecap { payload: SessionRecapPayload },
+
+    /// コメント表示テーマの変更
+    #[serde(rename = "comment:theme")]
+    CommentTheme { payload: CommentThemePayload },
+
+    /// マイルストーン到達（コメント数・スパチャ金額の節目）
+    #[serde(rename = "milestone")]
+    Milestone { payload: MilestonePayload },
+
+    /// スパチャの支援者ランキング（配信セッション中の合計額が変わった場合に配信）
+    #[serde(rename = "leaderboard:update")]
+    LeaderboardUpdate { payload: LeaderboardPayload },
+
+    /// スパチャの目標金額の進捗（対象のスパチャ・メンバーシップの受信時と目標の変更時に配信）
+    #[serde(rename = "goal:update")]
+    GoalUpdate { payload: GoalPayload },
+
+    /// 固定表示（コメントまたは告知）。固定は同時に1つで、新しい固定は前の固定を置き換える
+    /// 接続時にも現在の固定を送信する
+    #[serde(rename = "pin:set")]
+    PinMessage { payload: PinPayload },
+
+    /// 固定表示の解除
+    #[serde(rename = "pin:clear")]
+    UnpinMessage,
+
+    /// チャットの流速（1分あたりのコメント数、値が変わった場合のみ数秒ごとに配信）
+    #[serde(rename = "chat:rate")]
+    ChatRate { payload: ChatRatePayload },
+
+    /// 配信の経過時間（オーバーレイは`uptimeSeconds`からローカルでカウントアップする）
+    #[serde(rename = "stream:uptime")]
+    Uptime { payload: UptimePayload },
+
+    /// 接続状態（ポーリング中か・天気の自動更新が成功しているか）
+    /// 状態が変わった場合と接続時に配信する（購読トピックに関係なく全オーバーレイが受信）
+    #[serde(rename = "status:update")]
+    Status { payload: StatusPayload },
+
+    /// セルフテストの確認用メッセージ（オーバーレイは無視する）
+    #[serde(rename = "selftest:ping")]
+    SelfTest { payload: SelfTestPayload },
+
+    /// 複数メッセージの結合フレーム（バースト時の送信回数削減用）
+    /// オーバーレイは`messages`を先頭から順に処理する
+    #[serde(rename = "bundle")]
+    Bundle { messages: Vec<WsMessage> },
+}
+
+impl WsMessage {
+    /// 購読トピック（種別の`:`より前の部分、`Bundle`はNone）
+    ///
+    /// トピックの一覧は[`super::ws_topics::WS_TOPICS`]
+    pub fn topic(&self) -> Option<&'static str> {
+        let topic = match self {
+            Self::CommentAdd { .. }
+            | Self::CommentRemove { .. }
+            | Self::CommentTranslation { .. }
+            | Self::CommentTheme { .. } => "comment",
+            Self::SetlistUpdate { .. } => "setlist",
+            Self::SettingsUpdate { .. } => "settings",
+            Self::KpiUpdate { .. } => "kpi",
+            Self::QueueUpdate { .. } => "queue",
+            Self::PromoUpdate { .. } => "promo",
+            Self::WeatherUpdate { .. } | Self::WeatherMultiUpdate { .. } | Self::ForecastUpdate { .. } => {
+                "weather"
+            }
+            Self::ChatSettings { .. } | Self::ChatRate { .. } => "chat",
+            Self::SuperchatAdd { .. } | Self::SuperchatRemove { .. } => "superchat",
+            Self::MembershipAdd { .. } | Self::MembershipRemove { .. } => "membership",
+            Self::BrandUpdate { .. } => "brand",
+            Self::SessionRecap { .. } => "session",
+            Self::Milestone { .. } => "milestone",
+            Self::LeaderboardUpdate { .. } => "leaderboard",
+            Self::GoalUpdate { .. } => "goal",
+            Self::PinMessage { .. } | Self::UnpinMessage => "pin",
+            Self::Uptime { .. } => "stream",
+            Self::Status { .. } => "status",
+            Self::SelfTest { .. } => "selftest",
+            Self::Bundle { .. } => return None,
+        };
+        Some(topic)
+    }
+}
+
+/// オーバーレイからバックエンドへのWebSocketメッセージ種別
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// 受信確認（"ready"等の通知にも使用）
+    Ack {
+        #[serde(default)]
+        id: Option<String>,
+    },
+
+    /// 初期表示用データ（セットリスト・ブランド・キャッシュ済みコメント）の再送要求
+    RequestSnapshot,
+
+    /// 認証トークンの提示（`?token=`クエリを使わない場合は接続直後の最初のメッセージで送る）
+    Auth { token: String },
+
+    /// オーバーレイ側で発生したエラーの報告（ログに記録）
+    ReportError {
+        message: String,
+        /// 発生元（ウィジェット名等）
+        #[serde(default)]
+        source: Option<String>,
+    },
+}
+
+/// 接続中のオーバーレイ（WebSocketクライアント）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectedClient {
+    /// ピアID（接続順の連番）
+    pub peer_id: usize,
+    /// 接続元アドレス（"127.0.0.1:54321"等）
+    pub remote_addr: String,
+    /// 接続時刻（RFC3339）
+    pub connected_at: String,
+    /// 最後にPongを受信した時刻（RFC3339、未受信ならnull）
+    pub last_pong: Option<String>,
+    /// 購読トピック（全メッセージを受信する場合はnull）
+    pub topics: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -75,6 +234,14 @@ pub struct CommentRemovePayload {
     pub id: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentTranslationPayload {
+    /// 翻訳したコメントのID
+    pub id: String,
+    pub translation: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SetlistUpdatePayload {
@@ -90,6 +257,12 @@ pub struct SongItem {
     pub title: String,
     pub artist: String,
     pub status: SongStatus,
+    /// 曲の長さ（秒）。未設定の場合はnull
+    #[serde(default)]
+    pub duration_seconds: Option<i64>,
+    /// キー（調）。未設定の場合はnull
+    #[serde(default)]
+    pub song_key: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -168,6 +341,16 @@ pub struct WeatherSettings {
     pub multi_city: Option<MultiCitySettings>,
 }
 
+impl Default for WeatherSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            position: WeatherPosition::LeftTop,
+            multi_city: None,
+        }
+    }
+}
+
 /// ウィジェット表示設定（共通型）
 /// - DB保存用（overlay.rs）
 /// - WebSocket配信用（SettingsUpdatePayload）
@@ -186,6 +369,23 @@ pub struct WidgetVisibilitySettings {
     pub announcement: bool,
 }
 
+/// すべてのウィジェットを表示
+impl Default for WidgetVisibilitySettings {
+    fn default() -> Self {
+        Self {
+            clock: true,
+            weather: true,
+            comment: true,
+            superchat: true,
+            logo: true,
+            setlist: true,
+            kpi: true,
+            tanzaku: true,
+            announcement: true,
+        }
+    }
+}
+
 /// スパチャウィジェット設定（共通型）
 /// - DB保存用（overlay.rs）
 /// - WebSocket配信用（SettingsUpdatePayload）
@@ -557,14 +757,31 @@ pub struct PromoItem {
 pub struct WeatherUpdatePayload {
     /// 天気アイコン（絵文字）
     pub icon: String,
-    /// 気温（摂氏）
+    /// 気温（単位はtemp_unitを参照）
     pub temp: f64,
+    /// 気温の単位記号（"°C" or "°F"）
+    pub temp_unit: String,
     /// 天気の説明
     pub description: String,
     /// 地域名
     pub location: String,
     /// 湿度（%）
     pub humidity: Option<i32>,
+    /// 風速（単位はwind_speed_unitを参照）
+    #[serde(default)]
+    pub wind_speed: Option<f64>,
+    /// 風速の単位記号（"km/h" or "mph"）
+    #[serde(default)]
+    pub wind_speed_unit: Option<String>,
+    /// 風向（度）
+    #[serde(default)]
+    pub wind_direction: Option<f64>,
+    /// 風向の8方位表記（"N", "NE", ...）
+    #[serde(default)]
+    pub wind_compass: Option<String>,
+    /// 最新の取得に失敗し、前回取得した値を配信しているかどうか
+    #[serde(default)]
+    pub stale: bool,
 }
 
 impl From<&WeatherData> for WeatherUpdatePayload {
@@ -572,9 +789,37 @@ impl From<&WeatherData> for WeatherUpdatePayload {
         Self {
             icon: data.icon.clone(),
             temp: data.temp,
+            temp_unit: data.temp_unit.clone(),
             description: data.description.clone(),
             location: data.location.clone(),
             humidity: Some(data.humidity),
+            wind_speed: data.wind_speed,
+            wind_speed_unit: data.wind_speed_unit.clone(),
+            wind_direction: data.wind_direction,
+            wind_compass: data.wind_compass.clone(),
+            stale: data.stale,
+        }
+    }
+}
+
+/// 天気予報更新ペイロード
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastUpdatePayload {
+    /// 地域名
+    pub location: String,
+    /// 気温の単位記号（"°C" or "°F"）
+    pub temp_unit: String,
+    /// 日別予報（当日から順）
+    pub days: Vec<ForecastDay>,
+}
+
+impl From<&ForecastData> for ForecastUpdatePayload {
+    fn from(data: &ForecastData) -> Self {
+        Self {
+            location: data.location.clone(),
+            temp_unit: data.temp_unit.clone(),
+            days: data.days.clone(),
         }
     }
 }
@@ -599,14 +844,28 @@ pub struct CityWeatherData {
     pub city_name: String,
     /// 天気アイコン（絵文字）
     pub icon: String,
-    /// 気温（摂氏）
+    /// 気温（単位はtemp_unitを参照）
     pub temp: f64,
+    /// 気温の単位記号（"°C" or "°F"）
+    pub temp_unit: String,
     /// 天気の説明
     pub description: String,
     /// 地域名
     pub location: String,
     /// 湿度（%）
     pub humidity: Option<i32>,
+    /// 風速（単位はwind_speed_unitを参照）
+    #[serde(default)]
+    pub wind_speed: Option<f64>,
+    /// 風速の単位記号（"km/h" or "mph"）
+    #[serde(default)]
+    pub wind_speed_unit: Option<String>,
+    /// 風向（度）
+    #[serde(default)]
+    pub wind_direction: Option<f64>,
+    /// 風向の8方位表記（"N", "NE", ...）
+    #[serde(default)]
+    pub wind_compass: Option<String>,
 }
 
 /// スパチャペイロード（専用ウィジェット表示用）
@@ -617,10 +876,16 @@ pub struct SuperchatPayload {
     pub id: String,
     /// 送信者名
     pub author_name: String,
+    /// 送信者チャンネルID（同一投稿者の連投判定に使用）
+    #[serde(default)]
+    pub author_channel_id: String,
     /// 送信者アイコンURL
     pub author_image_url: String,
-    /// 金額表示文字列（"¥1,000" 等）
+    /// 金額表示文字列（YouTubeから取得したままの "¥1,000" "5,00" 等）
     pub amount: String,
+    /// 通貨ごとの表記に整形した金額（"¥1,000" "$5.00" "1.234,50 €" 等）
+    #[serde(default)]
+    pub amount_display: String,
     /// 金額（マイクロ単位）
     /// 例: ¥1,000 = 1_000_000_000 micros
     pub amount_micros: u64,
@@ -634,6 +899,40 @@ pub struct SuperchatPayload {
     pub tier: u8,
     /// 表示時間（ミリ秒）
     pub display_duration_ms: u64,
+    /// スーパーステッカーの画像URL（スパチャ・取得できないステッカーはNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sticker_image_url: Option<String>,
+    /// 表示先のslot（未指定時はleft.lower）
+    #[serde(default = "default_superchat_slot")]
+    pub slot: SlotId,
+}
+
+/// スパチャウィジェットのデフォルト表示slot
+pub fn default_superchat_slot() -> SlotId {
+    SlotId::LeftLower
+}
+
+/// テスト用のスパチャペイロードを組み立てる
+/// 既定値は金額0・JPY・Tier1。ここにない項目は構造体更新構文で変更する
+#[cfg(test)]
+impl SuperchatPayload {
+    pub(crate) fn test_payload(id: &str, author_channel_id: &str, author_name: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            author_name: author_name.to_string(),
+            author_channel_id: author_channel_id.to_string(),
+            author_image_url: String::new(),
+            amount: String::new(),
+            amount_display: String::new(),
+            amount_micros: 0,
+            currency: "JPY".to_string(),
+            message: String::new(),
+            tier: 1,
+            display_duration_ms: 10_000,
+            sticker_image_url: None,
+            slot: default_superchat_slot(),
+        }
+    }
 }
 
 /// スパチャ削除ペイロード
@@ -644,6 +943,244 @@ pub struct SuperchatRemovePayload {
     pub id: String,
 }
 
+/// メンバーシップの種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MembershipKind {
+    /// 新規メンバー
+    Join,
+    /// メンバー継続（マイルストーン）
+    Milestone,
+    /// メンバーシップギフト
+    Gift,
+}
+
+/// メンバーシップペイロード（専用ウィジェット表示用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MembershipPayload {
+    /// メッセージID（コメントIDと同一）
+    pub id: String,
+    /// 種別
+    pub kind: MembershipKind,
+    /// 投稿者名
+    pub author_name: String,
+    /// 投稿者チャンネルID
+    #[serde(default)]
+    pub author_channel_id: String,
+    /// 投稿者アイコンURL
+    pub author_image_url: String,
+    /// 見出し（レベル名・"メンバー歴 6 か月"・"5件のメンバーシップをギフトしました" 等）
+    pub label: String,
+    /// メンバー継続の月数（継続期間が取得できた場合のみ）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub milestone_months: Option<u32>,
+    /// ギフトした件数（ギフトのみ、ウィジェットで大きく表示）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gift_count: Option<u32>,
+    /// 投稿者のメッセージ（メンバー継続時のコメント、なければ空）
+    #[serde(default)]
+    pub message: String,
+    /// 表示時間（ミリ秒）
+    pub display_duration_ms: u64,
+}
+
+/// メンバーシップ表示の削除ペイロード
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MembershipRemovePayload {
+    /// 削除するメンバーシップ表示のID
+    pub id: String,
+}
+
+/// コメントオーバーレイの組み込みテーマ
+/// オーバーレイ側でテーマ名に対応するスタイルを適用する
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentTheme {
+    /// 標準（従来の表示）
+    #[default]
+    Standard,
+    /// 背景なしの簡素な表示
+    Minimal,
+    /// 吹き出し表示
+    Bubble,
+    /// ターミナル風の等幅表示
+    Terminal,
+}
+
+impl CommentTheme {
+    /// 組み込みテーマの一覧
+    pub const ALL: [CommentTheme; 4] = [
+        CommentTheme::Standard,
+        CommentTheme::Minimal,
+        CommentTheme::Bubble,
+        CommentTheme::Terminal,
+    ];
+
+    /// テーマ名
+    pub fn name(self) -> &'static str {
+        match self {
+            CommentTheme::Standard => "standard",
+            CommentTheme::Minimal => "minimal",
+            CommentTheme::Bubble => "bubble",
+            CommentTheme::Terminal => "terminal",
+        }
+    }
+
+    /// テーマ名から組み込みテーマを取得（未知の名前はNone）
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|theme| theme.name() == name)
+    }
+}
+
+/// コメント表示テーマ変更ペイロード
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentThemePayload {
+    /// テーマ名
+    pub theme: CommentTheme,
+}
+
+/// マイルストーンの種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MilestoneKind {
+    /// コメント数
+    Comments,
+    /// スパチャ合計額（円換算）
+    SuperchatJpy,
+}
+
+/// マイルストーン到達ペイロード
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MilestonePayload {
+    /// 種別
+    pub kind: MilestoneKind,
+    /// 到達した閾値（コメント数または円）
+    pub value: u64,
+}
+
+/// スパチャの支援者ランキングの1件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderboardEntry {
+    /// 投稿者名（最後にスパチャした時点の名前）
+    pub author_name: String,
+    /// チャンネルID
+    pub author_channel_id: String,
+    /// スパチャ合計（日本円換算）
+    pub total_jpy: u64,
+    /// スパチャの件数
+    pub count: u32,
+}
+
+/// スパチャの支援者ランキングペイロード
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderboardPayload {
+    /// 配信セッションID（live_sessionsのID。セッションの記録に失敗した場合はNone）
+    pub session_id: Option<i64>,
+    /// 合計額の多い順（同額の場合は先にスパチャした順）
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+/// スパチャの目標金額の進捗ペイロード
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalPayload {
+    /// 集計開始以降の合計（日本円換算）
+    pub current_jpy: u64,
+    /// 目標金額（円）。目標を無効にした場合は0（オーバーレイは非表示にする）
+    pub target_jpy: u64,
+    /// 表示ラベル（"目標 ¥50,000" 等）
+    pub label: String,
+}
+
+/// 固定表示の種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PinKind {
+    /// コメント（comment_logsに保存されたコメント）
+    Comment,
+    /// 告知（コメントに紐づかない任意の文言）
+    Announcement,
+}
+
+/// 固定表示ペイロード
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinPayload {
+    /// 固定表示のID（コメントの場合はメッセージID、告知の場合は生成したID）
+    pub id: String,
+    pub kind: PinKind,
+    /// 表示する本文
+    pub text: String,
+    /// 固定したコメント（告知の場合はNone）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<crate::youtube::types::ChatMessage>,
+    /// 固定した時刻
+    pub pinned_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// チャット流速ペイロード
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatRatePayload {
+    /// 1分あたりのコメント数（集計期間のコメント数から換算）
+    pub messages_per_minute: f64,
+    /// 集計期間内のコメント数
+    pub count: u64,
+    /// 集計期間（秒）
+    pub window_secs: u32,
+}
+
+/// 配信経過時間ペイロード
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UptimePayload {
+    /// 実際の配信開始時刻（RFC3339）
+    pub started_at: String,
+    /// 取得時点での経過秒数
+    pub uptime_seconds: u64,
+    /// 配信が終了しているか（終了済みの場合はカウントアップしない）
+    pub ended: bool,
+}
+
+/// 接続状態ペイロード
+///
+/// オーバーレイはメッセージが届かない理由を判別し、「チャット停止中」の表示や天気ウィジェットの減光に使う
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusPayload {
+    /// コメントを取得中か（一時停止中もtrue）
+    pub polling: bool,
+    /// 取得中のAPIモード（停止中はNone）
+    pub mode: Option<crate::commands::youtube::ApiMode>,
+    /// 直近の天気の自動更新が成功したか（失敗して前回の値を配信した場合もfalse）
+    pub weather_ok: bool,
+}
+
+impl Default for StatusPayload {
+    fn default() -> Self {
+        Self {
+            polling: false,
+            mode: None,
+            // 最初の自動更新までは正常として扱う（起動直後に減光しない）
+            weather_ok: true,
+        }
+    }
+}
+
+/// セルフテストの確認用ペイロード
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestPayload {
+    /// 受信を照合するためのID（セルフテストごとに生成）
+    pub id: String,
+}
+
 /// ブランド（ロゴ）更新ペイロード
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -663,3 +1200,45 @@ pub struct BrandSettings {
     /// 代替テキスト
     pub text: Option<String>,
 }
+
+/// 配信セッションまとめペイロード
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionRecapPayload {
+    /// 集計開始時刻（RFC3339）
+    pub since: String,
+    /// コメント総数
+    pub comment_count: i64,
+    /// ユニークコメント投稿者数（チャンネルID基準）
+    pub unique_chatters: i64,
+    /// 通貨別スパチャ合計
+    pub superchat_totals: Vec<CurrencyTotal>,
+    /// 最多支援者（日本円換算のスパチャ合計が最大の投稿者）
+    pub top_supporter: Option<TopSupporter>,
+    /// 新規メンバー名一覧（重複なし、加入順）
+    pub new_members: Vec<String>,
+}
+
+/// 通貨別の金額合計
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyTotal {
+    /// 通貨コード（"JPY", "USD" 等）
+    pub currency: String,
+    /// 合計金額（マイクロ単位）
+    pub amount_micros: u64,
+    /// 件数
+    pub count: u32,
+}
+
+/// 最多支援者
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TopSupporter {
+    /// 投稿者名
+    pub author_name: String,
+    /// チャンネルID
+    pub author_channel_id: String,
+    /// スパチャ合計（日本円換算）
+    pub total_jpy: u64,
+}
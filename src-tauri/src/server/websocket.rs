@@ -1,36 +1,279 @@
 use futures_util::{SinkExt, StreamExt};
 use sqlx::SqlitePool;
+use std::borrow::Cow;
 use std::collections::{HashMap, VecDeque};
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
 use tokio::sync::{mpsc, RwLock};
-use tokio_tungstenite::{accept_async, tungstenite::Message};
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_rustls::TlsAcceptor;
+use tokio_tungstenite::{accept_hdr_async, tungstenite::Message, WebSocketStream};
 
-use super::types::{BrandSettings, BrandUpdatePayload, SetlistUpdatePayload, SongItem, SongStatus, WsMessage};
+use super::chat_rate::ChatRateTracker;
+use super::comment_theme;
+use super::comment_throttle::{self, Admission, CommentThrottle, ThrottlePoll};
+use super::milestone::{MilestoneThresholds, MilestoneTracker};
+use super::types::{
+    BrandSettings, BrandUpdatePayload, ChatRatePayload, ClientMessage, ConnectedClient, SetlistUpdatePayload, SongItem,
+    PinPayload, SongStatus, StatusPayload, SuperchatPayload, WsMessage,
+};
+use super::ws_auth::{self, AuthToken};
+use super::ws_topics::{self, Topics};
+use crate::commands::youtube::ApiMode;
+use crate::util::identicon_svg;
+use crate::youtube::severity::Severity;
 use crate::youtube::types::ChatMessage;
 
 type Tx = mpsc::UnboundedSender<Message>;
-type PeerMap = Arc<RwLock<HashMap<usize, Tx>>>;
+type PeerMap = Arc<RwLock<HashMap<usize, Peer>>>;
 
-/// コメントキャッシュの最大数
-const MAX_COMMENT_CACHE: usize = 50;
+/// 接続中のピア（送信チャネルと購読トピック）
+#[derive(Debug, Clone)]
+pub struct Peer {
+    tx: Tx,
+    /// 購読トピック（Noneの場合は全メッセージを受信）
+    topics: Option<Arc<Topics>>,
+}
+
+impl Peer {
+    /// 購読トピックに一致するメッセージのみを残す（全メッセージ受信の場合はそのまま）
+    fn filter<'a>(&self, message: &'a WsMessage) -> Option<Cow<'a, WsMessage>> {
+        match &self.topics {
+            Some(topics) => ws_topics::filter_message(topics, message),
+            None => Some(Cow::Borrowed(message)),
+        }
+    }
+}
+
+/// 再送バッファ（新規接続時に送信するコメント・スパチャ）のデフォルト件数
+pub const DEFAULT_REPLAY_BUFFER_SIZE: usize = 50;
+
+/// 再送バッファの最大件数
+pub const MAX_REPLAY_BUFFER_SIZE: usize = 200;
+
+/// Pingの送信間隔（生存確認）
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Ping送信後、Pongを待つ時間（超えたら切断）
+const PONG_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// バンドル送信の待機時間（ミリ秒）
+/// この間に`broadcast`されたメッセージを1フレームにまとめる
+const BUNDLE_TICK_MS: u64 = 50;
+
+/// 再送用にキャッシュしたコメント（配信時に付けた判定結果を含む）
+#[derive(Debug, Clone)]
+pub struct CachedComment {
+    pub payload: ChatMessage,
+    /// 初見（判定範囲内で初めてコメントした投稿者）かどうか
+    pub is_first_time: bool,
+    /// コメントの翻訳（`comment:translation`の配信時に記録）
+    pub translation: Option<String>,
+    /// 不適切度
+    pub severity: Severity,
+}
+
+impl From<ChatMessage> for CachedComment {
+    fn from(payload: ChatMessage) -> Self {
+        Self {
+            payload,
+            is_first_time: false,
+            translation: None,
+            severity: Severity::Clean,
+        }
+    }
+}
+
+type PendingBundle = Arc<std::sync::Mutex<Vec<WsMessage>>>;
+type SharedThrottle = Arc<std::sync::Mutex<CommentThrottle>>;
+
+/// コメントキャッシュに追加（上限を超えた古いコメントは削除）
+async fn push_to_cache(cache: &RwLock<VecDeque<CachedComment>>, size: usize, comment: CachedComment) {
+    let mut cache = cache.write().await;
+    while !cache.is_empty() && cache.len() >= size {
+        cache.pop_front();
+    }
+    if size > 0 {
+        cache.push_back(comment);
+    }
+}
+
+/// 配信したコメントの記録先（再送キャッシュ・流速・マイルストーン）
+///
+/// スローモードの送出タスクからも記録するため、状態を共有する
+struct SentComments {
+    cache: Arc<RwLock<VecDeque<CachedComment>>>,
+    replay_buffer_size: Arc<AtomicUsize>,
+    chat_rate: Arc<std::sync::Mutex<ChatRateTracker>>,
+    milestones: Arc<std::sync::Mutex<MilestoneTracker>>,
+}
+
+impl SentComments {
+    /// 配信したコメントを記録し、閾値を超えたマイルストーンのメッセージを返す
+    ///
+    /// コメント以外のメッセージは何もしない
+    async fn record(&self, message: &WsMessage) -> Vec<WsMessage> {
+        let WsMessage::CommentAdd { payload, is_first_time, translation, severity, .. } = message else {
+            return Vec::new();
+        };
+        let comment = CachedComment {
+            payload: payload.clone(),
+            is_first_time: *is_first_time,
+            translation: translation.clone(),
+            severity: *severity,
+        };
+        push_to_cache(&self.cache, self.replay_buffer_size.load(Ordering::SeqCst), comment).await;
+        self.chat_rate
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .record_at(Instant::now());
+        self.milestones
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .record_comment(payload)
+    }
+}
+
+/// 生存確認の設定
+#[derive(Debug, Clone, Copy)]
+struct Heartbeat {
+    /// Pingの送信間隔
+    interval: Duration,
+    /// Pongを待つ時間
+    timeout: Duration,
+}
 
 /// WebSocket接続管理状態
 pub struct WebSocketState {
     peers: PeerMap,
+    /// 接続中クライアントの情報（接続元・接続時刻・Pong受信時刻）
+    clients: Arc<RwLock<HashMap<usize, ConnectedClient>>>,
     next_peer_id: AtomicUsize,
     /// コメントキャッシュ（新規接続時に送信）
-    comment_cache: Arc<RwLock<VecDeque<ChatMessage>>>,
+    comment_cache: Arc<RwLock<VecDeque<CachedComment>>>,
+    /// 表示中のスパチャと表示開始時刻（新規接続時に残り時間で送信）
+    active_superchats: std::sync::Mutex<VecDeque<(SuperchatPayload, Instant)>>,
+    /// 再送バッファの件数（コメント・スパチャそれぞれの上限）
+    replay_buffer_size: Arc<AtomicUsize>,
+    /// バンドル送信が有効か（デフォルト: 無効）
+    bundling_enabled: AtomicBool,
+    /// アバター画像のない投稿者にidenticonを割り当てるか（デフォルト: 有効）
+    fallback_avatar_enabled: AtomicBool,
+    /// バンドル送信待ちのメッセージ（送信順）
+    pending_bundle: PendingBundle,
+    /// コメント配信の流量制限（スローモード）
+    comment_throttle: SharedThrottle,
+    /// 現在の固定表示（新規接続時に送信）
+    pinned: std::sync::Mutex<Option<PinPayload>>,
+    /// マイルストーンの集計状態
+    milestones: Arc<std::sync::Mutex<MilestoneTracker>>,
+    /// チャット流速の集計状態
+    chat_rate: Arc<std::sync::Mutex<ChatRateTracker>>,
+    /// 接続状態（ポーリング・天気の自動更新）
+    status: std::sync::Mutex<StatusPayload>,
+    /// 接続に必要な認証トークン（HTTPサーバーと共有）
+    auth_token: AuthToken,
+    /// 生存確認（Ping/Pong）の設定
+    heartbeat: Heartbeat,
 }
 
 impl WebSocketState {
     pub fn new() -> Self {
         Self {
             peers: Arc::new(RwLock::new(HashMap::new())),
+            clients: Arc::new(RwLock::new(HashMap::new())),
             next_peer_id: AtomicUsize::new(0),
-            comment_cache: Arc::new(RwLock::new(VecDeque::with_capacity(MAX_COMMENT_CACHE))),
+            comment_cache: Arc::new(RwLock::new(VecDeque::with_capacity(DEFAULT_REPLAY_BUFFER_SIZE))),
+            active_superchats: std::sync::Mutex::new(VecDeque::new()),
+            replay_buffer_size: Arc::new(AtomicUsize::new(DEFAULT_REPLAY_BUFFER_SIZE)),
+            bundling_enabled: AtomicBool::new(false),
+            fallback_avatar_enabled: AtomicBool::new(true),
+            pending_bundle: Arc::new(std::sync::Mutex::new(Vec::new())),
+            comment_throttle: Arc::new(std::sync::Mutex::new(CommentThrottle::default())),
+            pinned: std::sync::Mutex::new(None),
+            milestones: Arc::new(std::sync::Mutex::new(MilestoneTracker::default())),
+            chat_rate: Arc::new(std::sync::Mutex::new(ChatRateTracker::default())),
+            status: std::sync::Mutex::new(StatusPayload::default()),
+            auth_token: Arc::new(std::sync::RwLock::new(None)),
+            heartbeat: Heartbeat {
+                interval: HEARTBEAT_INTERVAL,
+                timeout: PONG_TIMEOUT,
+            },
+        }
+    }
+
+    /// 接続に必要な認証トークンを設定（Noneで認証を無効化）
+    ///
+    /// 接続中のピアには影響しない（以降の接続から適用）
+    pub fn set_auth_token(&self, token: Option<String>) {
+        match self.auth_token.write() {
+            Ok(mut current) => {
+                log::info!("WebSocket authentication: {}", token.is_some());
+                *current = token;
+            }
+            Err(e) => log::error!("Failed to update WebSocket auth token: {}", e),
+        }
+    }
+
+    /// 現在の認証トークン（認証が無効の場合はNone）
+    pub fn auth_token(&self) -> Option<String> {
+        match self.auth_token.read() {
+            Ok(token) => token.clone(),
+            Err(e) => {
+                log::error!("Failed to read WebSocket auth token: {}", e);
+                None
+            }
+        }
+    }
+
+    /// 認証トークンの共有ハンドル（HTTPサーバーのオーバーレイページの認証に使用）
+    pub fn auth_token_handle(&self) -> AuthToken {
+        Arc::clone(&self.auth_token)
+    }
+
+    /// 接続中の全ピアを認証エラー（4401）で切断
+    ///
+    /// トークンのローテーション時に、古いトークンで接続済みのオーバーレイを切断する
+    pub async fn disconnect_all_unauthorized(&self) {
+        let peers = self.peers.read().await;
+        for peer in peers.values() {
+            let _ = peer.tx.send(Message::Close(Some(unauthorized_close_frame())));
         }
+        log::info!("Disconnecting {} WebSocket peers (auth token rotated)", peers.len());
+    }
+
+    /// バンドル送信の有効/無効を切り替え
+    ///
+    /// 有効時は`broadcast`されたメッセージを短いティック内でまとめ、
+    /// 複数ある場合は`WsMessage::Bundle`として1フレームで送信する。
+    /// `send_to_peers`による直接送信は対象外。
+    pub fn set_bundling(&self, enabled: bool) {
+        self.bundling_enabled.store(enabled, Ordering::SeqCst);
+        log::info!("WebSocket broadcast bundling: {}", enabled);
+    }
+
+    /// バンドル送信が有効か
+    pub fn is_bundling(&self) -> bool {
+        self.bundling_enabled.load(Ordering::SeqCst)
+    }
+
+    /// アバター画像のない投稿者へのidenticon割り当ての有効/無効を切り替え
+    ///
+    /// 無効時はアバターURLを空のまま配信する（オーバーレイ側の表示に任せる）
+    pub fn set_fallback_avatar_enabled(&self, enabled: bool) {
+        self.fallback_avatar_enabled.store(enabled, Ordering::SeqCst);
+        log::info!("WebSocket fallback avatar: {}", enabled);
+    }
+
+    /// アバター画像のない投稿者にidenticonを割り当てるか
+    pub fn is_fallback_avatar_enabled(&self) -> bool {
+        self.fallback_avatar_enabled.load(Ordering::SeqCst)
     }
 
     /// 新しいピアIDを取得
@@ -38,33 +281,117 @@ impl WebSocketState {
         self.next_peer_id.fetch_add(1, Ordering::SeqCst)
     }
 
-    /// ピアを追加
+    /// ピアを追加（全メッセージを受信）
     pub async fn add_peer(&self, peer_id: usize, tx: Tx) {
+        self.add_peer_with_topics(peer_id, tx, None).await;
+    }
+
+    /// 購読トピックを指定してピアを追加（Noneの場合は全メッセージを受信）
+    pub async fn add_peer_with_topics(&self, peer_id: usize, tx: Tx, topics: Option<Topics>) {
+        if let Some(topics) = &topics {
+            log::info!("WebSocket peer {} subscribed to topics: {:?}", peer_id, topics);
+        }
         let mut peers = self.peers.write().await;
-        peers.insert(peer_id, tx);
+        peers.insert(
+            peer_id,
+            Peer {
+                tx,
+                topics: topics.map(Arc::new),
+            },
+        );
         log::info!("WebSocket peer {} connected. Total peers: {}", peer_id, peers.len());
     }
 
+    /// 指定IDのピアを取得
+    async fn peer(&self, peer_id: usize) -> Option<Peer> {
+        self.peers.read().await.get(&peer_id).cloned()
+    }
+
     /// ピアを削除
     pub async fn remove_peer(&self, peer_id: usize) {
+        self.clients.write().await.remove(&peer_id);
         let mut peers = self.peers.write().await;
         peers.remove(&peer_id);
         log::info!("WebSocket peer {} disconnected. Total peers: {}", peer_id, peers.len());
     }
 
+    /// 接続中クライアントの情報を登録
+    ///
+    /// 購読トピックは登録済みのピアから取得する（`add_peer_with_topics`の後に呼び出す）
+    pub async fn register_client(&self, peer_id: usize, remote_addr: SocketAddr) {
+        let topics = self.peer(peer_id).await.and_then(|peer| peer.topics).map(|topics| {
+            let mut topics: Vec<String> = topics.iter().map(|topic| topic.to_string()).collect();
+            topics.sort();
+            topics
+        });
+        let client = ConnectedClient {
+            peer_id,
+            remote_addr: remote_addr.to_string(),
+            connected_at: chrono::Utc::now().to_rfc3339(),
+            last_pong: None,
+            topics,
+        };
+        self.clients.write().await.insert(peer_id, client);
+    }
+
+    /// Pongの受信時刻を記録
+    pub async fn record_pong(&self, peer_id: usize) {
+        if let Some(client) = self.clients.write().await.get_mut(&peer_id) {
+            client.last_pong = Some(chrono::Utc::now().to_rfc3339());
+        }
+    }
+
+    /// 接続中のピア数
+    pub async fn peer_count(&self) -> usize {
+        self.peers.read().await.len()
+    }
+
+    /// 接続中クライアントの一覧（接続順）
+    pub async fn connected_clients(&self) -> Vec<ConnectedClient> {
+        let mut clients: Vec<ConnectedClient> = self.clients.read().await.values().cloned().collect();
+        clients.sort_by_key(|client| client.peer_id);
+        clients
+    }
+
+    /// 再送バッファの件数を変更（超過分は古い順に破棄）
+    ///
+    /// 0の場合は新規接続時にコメント・スパチャを再送しない
+    pub async fn set_replay_buffer_size(&self, size: usize) {
+        let size = size.min(MAX_REPLAY_BUFFER_SIZE);
+        self.replay_buffer_size.store(size, Ordering::SeqCst);
+        let mut cache = self.comment_cache.write().await;
+        while cache.len() > size {
+            cache.pop_front();
+        }
+        let mut superchats = self.active_superchats.lock().unwrap_or_else(|e| e.into_inner());
+        while superchats.len() > size {
+            superchats.pop_front();
+        }
+        log::info!("WebSocket replay buffer size: {}", size);
+    }
+
+    /// 再送バッファの件数
+    pub fn replay_buffer_size(&self) -> usize {
+        self.replay_buffer_size.load(Ordering::SeqCst)
+    }
+
     /// キャッシュされたコメントを取得
-    pub async fn get_cached_comments(&self) -> Vec<ChatMessage> {
+    pub async fn get_cached_comments(&self) -> Vec<CachedComment> {
         let cache = self.comment_cache.read().await;
         cache.iter().cloned().collect()
     }
 
     /// コメントをキャッシュに追加
-    pub async fn add_to_cache(&self, comment: ChatMessage) {
+    pub async fn add_to_cache(&self, comment: CachedComment) {
+        push_to_cache(&self.comment_cache, self.replay_buffer_size(), comment).await;
+    }
+
+    /// キャッシュ済みコメントに翻訳を記録（キャッシュにない場合は何もしない）
+    async fn set_cached_translation(&self, id: &str, translation: &str) {
         let mut cache = self.comment_cache.write().await;
-        if cache.len() >= MAX_COMMENT_CACHE {
-            cache.pop_front();
+        if let Some(comment) = cache.iter_mut().rev().find(|comment| comment.payload.id == id) {
+            comment.translation = Some(translation.to_string());
         }
-        cache.push_back(comment);
     }
 
     /// 複数コメントをキャッシュに追加
@@ -72,40 +399,354 @@ impl WebSocketState {
     /// Note: 現在は未使用だが、バッチインポート機能で使用予定
     #[allow(dead_code)]
     pub async fn add_comments_to_cache(&self, comments: Vec<ChatMessage>) {
-        let mut cache = self.comment_cache.write().await;
         for comment in comments {
-            if cache.len() >= MAX_COMMENT_CACHE {
-                cache.pop_front();
+            self.add_to_cache(comment.into()).await;
+        }
+    }
+
+    /// 表示中のスパチャを、残りの表示時間に置き換えて取得
+    ///
+    /// 表示時間を過ぎたスパチャは破棄する
+    pub fn get_active_superchats(&self) -> Vec<SuperchatPayload> {
+        let now = Instant::now();
+        let mut superchats = self.active_superchats.lock().unwrap_or_else(|e| e.into_inner());
+        superchats.retain(|(payload, shown_at)| {
+            now.duration_since(*shown_at) < Duration::from_millis(payload.display_duration_ms)
+        });
+        superchats
+            .iter()
+            .map(|(payload, shown_at)| {
+                let elapsed = now.duration_since(*shown_at).as_millis() as u64;
+                SuperchatPayload {
+                    display_duration_ms: payload.display_duration_ms.saturating_sub(elapsed),
+                    ..payload.clone()
+                }
+            })
+            .collect()
+    }
+
+    /// スパチャの表示開始・終了を記録（再送用）
+    fn track_superchat(&self, message: &WsMessage) {
+        let mut superchats = self.active_superchats.lock().unwrap_or_else(|e| e.into_inner());
+        match message {
+            WsMessage::SuperchatAdd { payload } => {
+                let size = self.replay_buffer_size();
+                superchats.retain(|(active, _)| active.id != payload.id);
+                while !superchats.is_empty() && superchats.len() >= size {
+                    superchats.pop_front();
+                }
+                if size > 0 {
+                    superchats.push_back((payload.clone(), Instant::now()));
+                }
             }
-            cache.push_back(comment);
+            WsMessage::SuperchatRemove { payload } => {
+                superchats.retain(|(active, _)| active.id != payload.id);
+            }
+            _ => {}
+        }
+    }
+
+    /// 固定表示の配信・解除を記録（新規接続時に現在の固定を送信するため）
+    fn track_pin(&self, message: &WsMessage) {
+        let mut pinned = self.pinned.lock().unwrap_or_else(|e| e.into_inner());
+        match message {
+            WsMessage::PinMessage { payload } => *pinned = Some(payload.clone()),
+            WsMessage::UnpinMessage => *pinned = None,
+            _ => {}
         }
     }
 
+    /// 現在の固定表示
+    pub fn pinned_message(&self) -> Option<PinPayload> {
+        self.pinned.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
     /// 全ピアにメッセージをブロードキャスト
-    pub async fn broadcast(&self, message: WsMessage) {
-        // コメントの場合はキャッシュに追加
-        if let WsMessage::CommentAdd { ref payload, .. } = message {
-            self.add_to_cache(payload.clone()).await;
+    ///
+    /// バンドル送信が有効な場合はキューに積み、ティック経過後にまとめて送信する。
+    /// コメントは実際に配信した時点で再送キャッシュ・流速・マイルストーンに記録し
+    /// （スローモードで破棄したコメントは記録しない）、閾値を超えたらコメントに続けて配信する
+    pub async fn broadcast(&self, mut message: WsMessage) {
+        if self.is_fallback_avatar_enabled() {
+            fill_fallback_avatar(&mut message);
         }
 
-        let json = match serde_json::to_string(&message) {
-            Ok(j) => j,
-            Err(e) => {
-                log::error!("Failed to serialize WebSocket message: {}", e);
-                return;
-            }
+        if let WsMessage::CommentRemove { ref payload } = message {
+            self.lock_throttle().remove(&payload.id);
+        }
+        if let WsMessage::CommentTranslation { ref payload } = message {
+            self.lock_throttle().set_translation(&payload.id, &payload.translation);
+            self.set_cached_translation(&payload.id, &payload.translation).await;
+        }
+        self.track_superchat(&message);
+        self.track_pin(&message);
+
+        if comment_throttle::is_throttled(&message) {
+            self.send_throttled(message).await;
+        } else {
+            self.send_and_record(message).await;
+        }
+    }
+
+    /// テスト送信のコメントをブロードキャスト
+    ///
+    /// 実際のチャットではないため、再送キャッシュ・流速・マイルストーンに記録せず、
+    /// スローモードの対象にもしない（すぐに配信する）
+    pub async fn broadcast_test_comment(&self, mut message: WsMessage) {
+        if self.is_fallback_avatar_enabled() {
+            fill_fallback_avatar(&mut message);
+        }
+        self.send_or_bundle(message).await;
+    }
+
+    /// 配信したコメントの記録先
+    fn sent_comments(&self) -> SentComments {
+        SentComments {
+            cache: Arc::clone(&self.comment_cache),
+            replay_buffer_size: Arc::clone(&self.replay_buffer_size),
+            chat_rate: Arc::clone(&self.chat_rate),
+            milestones: Arc::clone(&self.milestones),
+        }
+    }
+
+    /// メッセージを送信し、コメントの場合は記録して閾値を超えたマイルストーンを続けて送信
+    async fn send_and_record(&self, message: WsMessage) {
+        let milestones = self.sent_comments().record(&message).await;
+        self.send_or_bundle(message).await;
+        for milestone in milestones {
+            self.send_or_bundle(milestone).await;
+        }
+    }
+
+    /// メッセージを全ピアに送信（バンドル送信が有効な場合はキューに積む）
+    async fn send_or_bundle(&self, message: WsMessage) {
+        if self.bundle_if_enabled(&message) {
+            return;
+        }
+
+        let peers: Vec<_> = {
+            let peers_guard = self.peers.read().await;
+            peers_guard
+                .iter()
+                .map(|(id, peer)| (*id, peer.clone()))
+                .collect()
         };
+        Self::send_to_peers(&peers, &message);
+    }
 
-        let peers = self.peers.read().await;
-        let msg = Message::Text(json);
+    /// コメントを流量制限に従って送信（超過分はキューに積み、送出タスクが順に送信する）
+    async fn send_throttled(&self, message: WsMessage) {
+        let admission = self.lock_throttle().admit(message, Instant::now());
+        match admission {
+            Admission::SendNow(message) => self.send_and_record(*message).await,
+            Admission::Queued { start_drain: true } => self.spawn_throttle_drain(),
+            Admission::Queued { start_drain: false } => {}
+        }
+    }
 
-        for (peer_id, tx) in peers.iter() {
-            if let Err(e) = tx.send(msg.clone()) {
-                log::warn!("Failed to send message to peer {}: {}", peer_id, e);
+    /// 流量制限のキューを一定間隔で送出するタスクを起動（キューが空になったら終了）
+    ///
+    /// 送出は1件ずつのためバンドル送信の対象外
+    fn spawn_throttle_drain(&self) {
+        let peers = Arc::clone(&self.peers);
+        let throttle = Arc::clone(&self.comment_throttle);
+        let sent_comments = self.sent_comments();
+        tokio::spawn(async move {
+            loop {
+                let poll = throttle
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .poll(Instant::now());
+                match poll {
+                    ThrottlePoll::Ready(message) => {
+                        let milestones = sent_comments.record(&message).await;
+                        let peers: Vec<_> = {
+                            let peers_guard = peers.read().await;
+                            peers_guard
+                                .iter()
+                                .map(|(id, peer)| (*id, peer.clone()))
+                                .collect()
+                        };
+                        Self::send_to_peers(&peers, &message);
+                        for milestone in &milestones {
+                            Self::send_to_peers(&peers, milestone);
+                        }
+                    }
+                    ThrottlePoll::Wait(until) => {
+                        tokio::time::sleep_until(tokio::time::Instant::from_std(until)).await;
+                    }
+                    ThrottlePoll::Idle => break,
+                }
             }
+        });
+    }
+
+    fn lock_throttle(&self) -> std::sync::MutexGuard<'_, CommentThrottle> {
+        self.comment_throttle.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// コメント配信の1秒あたりの最大件数（0は無効）
+    pub fn comment_throttle_rate(&self) -> u32 {
+        self.lock_throttle().rate()
+    }
+
+    /// コメント配信の1秒あたりの最大件数を更新（0で無効化）
+    ///
+    /// スパチャ・メンバーシップ等のコメントは制限の対象外。
+    /// キューに残ったコメントは動作中の送出タスクが新しい件数で送信する
+    pub fn set_comment_throttle_rate(&self, rate: u32) {
+        self.lock_throttle().set_rate(rate);
+        log::info!("Comment throttle rate: {}/s", rate);
+    }
+
+    /// マイルストーンの閾値を更新
+    pub fn set_milestone_thresholds(&self, thresholds: MilestoneThresholds) {
+        self.milestones
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .set_thresholds(thresholds);
+    }
+
+    /// 現在のマイルストーンの閾値
+    pub fn milestone_thresholds(&self) -> MilestoneThresholds {
+        self.milestones
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .thresholds()
+            .clone()
+    }
+
+    /// マイルストーンの集計をリセット（新しい配信セッションの開始時）
+    pub fn reset_milestones(&self) {
+        self.milestones
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .reset();
+    }
+
+    /// 現在のチャット流速
+    pub fn chat_rate(&self) -> ChatRatePayload {
+        self.chat_rate
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .rate_at(Instant::now())
+    }
+
+    /// チャット流速の集計期間（秒）
+    pub fn chat_rate_window_secs(&self) -> u32 {
+        self.chat_rate
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .window_secs()
+    }
+
+    /// チャット流速の集計期間（秒）を更新
+    pub fn set_chat_rate_window_secs(&self, window_secs: u32) {
+        self.chat_rate
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .set_window_secs(window_secs);
+    }
+
+    /// チャット流速の集計をリセット（ポーリングの開始・停止時）
+    pub fn reset_chat_rate(&self) {
+        self.chat_rate
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .reset();
+    }
+
+    /// 現在の接続状態
+    pub fn status(&self) -> StatusPayload {
+        *self.status.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// ポーリングの状態を更新（停止時はNone）し、変わった場合は`status:update`を配信
+    pub async fn set_polling_status(&self, mode: Option<ApiMode>) {
+        self.update_status(|status| {
+            status.polling = mode.is_some();
+            status.mode = mode;
+        })
+        .await;
+    }
+
+    /// 天気の自動更新の結果を更新し、変わった場合は`status:update`を配信
+    pub async fn set_weather_status(&self, weather_ok: bool) {
+        self.update_status(|status| status.weather_ok = weather_ok).await;
+    }
+
+    async fn update_status(&self, update: impl FnOnce(&mut StatusPayload)) {
+        let changed = {
+            let mut status = self.status.lock().unwrap_or_else(|e| e.into_inner());
+            let before = *status;
+            update(&mut status);
+            (*status != before).then_some(*status)
+        };
+        if let Some(payload) = changed {
+            log::debug!("Connection status changed: {:?}", payload);
+            self.broadcast(WsMessage::Status { payload }).await;
         }
+    }
 
-        log::debug!("Broadcasted message to {} peers: {:?}", peers.len(), message);
+    /// バンドル送信が有効ならメッセージをキューに積む
+    ///
+    /// `send_to_peers`を使うFire-and-forget送信でもバンドル対象にするため、
+    /// ピア取得前に呼び出す。キューに積んだ場合は`true`を返す（呼び出し元は送信不要）。
+    /// 同期処理のみでawaitしないため、serverのガード保持中に呼び出してよい。
+    pub fn bundle_if_enabled(&self, message: &WsMessage) -> bool {
+        if !self.is_bundling() {
+            return false;
+        }
+        self.enqueue_bundle(message.clone());
+        true
+    }
+
+    /// バンドル送信キューにメッセージを追加
+    ///
+    /// キューが空だった場合のみフラッシュタスクを起動する（1ティックにつき1回）
+    fn enqueue_bundle(&self, message: WsMessage) {
+        let is_first = {
+            let mut pending = self
+                .pending_bundle
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            pending.push(message);
+            pending.len() == 1
+        };
+
+        if is_first {
+            let peers = Arc::clone(&self.peers);
+            let pending = Arc::clone(&self.pending_bundle);
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(BUNDLE_TICK_MS)).await;
+                Self::flush_bundle(&peers, &pending).await;
+            });
+        }
+    }
+
+    /// バンドル送信キューを送信
+    ///
+    /// 1件のみの場合はそのまま、複数件の場合は順序を保持して`Bundle`で送信する
+    async fn flush_bundle(peers: &PeerMap, pending: &PendingBundle) {
+        let messages = {
+            let mut pending = pending.lock().unwrap_or_else(|e| e.into_inner());
+            std::mem::take(&mut *pending)
+        };
+
+        let message = match messages.len() {
+            0 => return,
+            1 => messages.into_iter().next().expect("length checked"),
+            _ => WsMessage::Bundle { messages },
+        };
+
+        let peers: Vec<_> = {
+            let peers_guard = peers.read().await;
+            peers_guard
+                .iter()
+                .map(|(id, tx)| (*id, tx.clone()))
+                .collect()
+        };
+        Self::send_to_peers(&peers, &message);
     }
 
     /// ピアマップのArcを取得（ガード保持時間を最小化するため）
@@ -131,7 +772,9 @@ impl WebSocketState {
     /// `broadcast`メソッドは内部でRwLockガードを取得するため、
     /// 外側でガードを保持したまま呼ぶと二重ロックになる。
     /// このメソッドは事前に取得したピアリストに対して直接送信する。
-    pub fn send_to_peers(peers: &[(usize, Tx)], message: &WsMessage) {
+    ///
+    /// 購読トピックを指定したピアには、一致するメッセージのみを送信する
+    pub fn send_to_peers(peers: &[(usize, Peer)], message: &WsMessage) {
         let json = match serde_json::to_string(message) {
             Ok(j) => j,
             Err(e) => {
@@ -141,8 +784,19 @@ impl WebSocketState {
         };
 
         let msg = Message::Text(json);
-        for (peer_id, tx) in peers.iter() {
-            if let Err(e) = tx.send(msg.clone()) {
+        for (peer_id, peer) in peers.iter() {
+            let result = match peer.filter(message) {
+                None => continue,
+                Some(Cow::Borrowed(_)) => peer.tx.send(msg.clone()),
+                Some(Cow::Owned(filtered)) => match serde_json::to_string(&filtered) {
+                    Ok(json) => peer.tx.send(Message::Text(json)),
+                    Err(e) => {
+                        log::error!("Failed to serialize WebSocket message: {}", e);
+                        continue;
+                    }
+                },
+            };
+            if let Err(e) = result {
                 log::warn!("Failed to send message to peer {}: {}", peer_id, e);
             }
         }
@@ -159,16 +813,23 @@ impl Default for WebSocketState {
 
 /// WebSocketサーバーを起動
 ///
+/// permessage-deflate等の拡張はネゴシエーションしない（tungstenite 0.24が未対応）。
+/// クライアントが拡張を要求しても応答ヘッダーに含めないため、非圧縮で接続される
+///
 /// # 引数
+/// - `listener`: 起動時に確保したポート（`addresses::bind_with_fallback`）
 /// - `state`: 共有状態
 /// - `db`: データベース接続プール
+/// - `tls`: 指定した場合はWSS（TLS）で受け付ける
 pub async fn start_websocket_server(
+    listener: TcpListener,
     state: Arc<RwLock<WebSocketState>>,
     db: SqlitePool,
+    tls: Option<TlsAcceptor>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let addr = "127.0.0.1:19801";
-    let listener = TcpListener::bind(addr).await?;
-    log::info!("WebSocket server listening on ws://{}/ws", addr);
+    let addr = listener.local_addr()?;
+    let scheme = if tls.is_some() { "wss" } else { "ws" };
+    log::info!("WebSocket server listening on {}://{}/ws", scheme, addr);
 
     let db = Arc::new(db);
 
@@ -176,19 +837,44 @@ pub async fn start_websocket_server(
         log::info!("New WebSocket connection from: {}", peer_addr);
         let state_clone = Arc::clone(&state);
         let db_clone = Arc::clone(&db);
-        tokio::spawn(handle_connection(state_clone, stream, db_clone));
+        match tls.clone() {
+            Some(acceptor) => {
+                tokio::spawn(async move {
+                    match acceptor.accept(stream).await {
+                        Ok(stream) => handle_connection(state_clone, stream, peer_addr, db_clone).await,
+                        Err(e) => log::warn!("TLS handshake failed from {}: {}", peer_addr, e),
+                    }
+                });
+            }
+            None => {
+                tokio::spawn(handle_connection(state_clone, stream, peer_addr, db_clone));
+            }
+        }
     }
 
     Ok(())
 }
 
-/// WebSocket接続を処理
-async fn handle_connection(
+/// WebSocket接続を処理（`stream`はTCPまたはTLSの接続）
+async fn handle_connection<S>(
     state: Arc<RwLock<WebSocketState>>,
-    stream: TcpStream,
+    stream: S,
+    peer_addr: SocketAddr,
     db: Arc<SqlitePool>,
-) {
-    let ws_stream = match accept_async(stream).await {
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    // ハンドシェイク時に`?token=`・`?topics=`クエリを取得
+    let mut query_token = None;
+    let mut topics = None;
+    // コールバックの戻り値の型はtungsteniteが定めるため、Errが大きい警告は抑制
+    #[allow(clippy::result_large_err)]
+    let handshake = accept_hdr_async(stream, |request: &Request, response: Response| {
+        query_token = ws_auth::token_from_query(request.uri().query()).map(str::to_string);
+        topics = ws_topics::topics_from_query(request.uri().query());
+        Ok(response)
+    });
+    let mut ws_stream = match handshake.await {
         Ok(ws) => ws,
         Err(e) => {
             log::error!("WebSocket handshake failed: {}", e);
@@ -198,65 +884,42 @@ async fn handle_connection(
 
     log::info!("WebSocket handshake completed");
 
+    let auth_token = state.read().await.auth_token_handle();
+    if !authenticate(&mut ws_stream, &auth_token, query_token.as_deref()).await {
+        log::warn!("Rejected unauthenticated WebSocket connection from {}", peer_addr);
+        let _ = ws_stream.close(Some(unauthorized_close_frame())).await;
+        return;
+    }
+
     let (mut ws_sender, mut ws_receiver) = ws_stream.split();
     let (tx, mut rx) = mpsc::unbounded_channel::<Message>();
 
     // 先にセットリスト、ブランド設定、キャッシュされたコメントを取得
-    let initial_setlist = fetch_latest_setlist_message(&db).await;
-    let initial_brand = fetch_brand_settings_message(&db).await;
-    let cached_comments = {
-        let state_guard = state.read().await;
-        state_guard.get_cached_comments().await
-    };
+    let snapshot = build_snapshot(&state, &db).await;
 
     // ピアIDを取得して登録（1回のロック取得で処理）
     let peer_id = {
         let state_guard = state.read().await;
         let id = state_guard.next_id();
-        state_guard.add_peer(id, tx.clone()).await;
+        state_guard.add_peer_with_topics(id, tx.clone(), topics).await;
+        state_guard.register_client(id, peer_addr).await;
         id
     };
 
-    // 接続時に最新セットリストを送信
-    if let Some(msg) = initial_setlist {
-        if let Ok(json) = serde_json::to_string(&msg) {
-            if tx.send(Message::Text(json)).is_err() {
-                log::warn!("Failed to send initial setlist to peer {}", peer_id);
-            } else {
-                log::debug!("Sent initial setlist to peer {}", peer_id);
-            }
-        }
+    // 接続時に初期表示用データを送信
+    if let Some(peer) = state.read().await.peer(peer_id).await {
+        send_snapshot(&peer, peer_id, snapshot);
     }
 
-    // 接続時にブランド設定を送信
-    if let Some(msg) = initial_brand {
-        if let Ok(json) = serde_json::to_string(&msg) {
-            if tx.send(Message::Text(json)).is_err() {
-                log::warn!("Failed to send initial brand settings to peer {}", peer_id);
-            } else {
-                log::debug!("Sent initial brand settings to peer {}", peer_id);
-            }
-        }
-    }
+    // 最後にフレームを受信した時刻（生存確認用）
+    let last_seen = Arc::new(std::sync::Mutex::new(Instant::now()));
 
-    // 接続時にキャッシュされたコメントを送信
-    // Note: キャッシュコメントは即時表示（instant: true）で送信し、
-    // 接続直後のキャッチアップを素早く行う
-    if !cached_comments.is_empty() {
-        log::info!("Sending {} cached comments to peer {}", cached_comments.len(), peer_id);
-        for comment in cached_comments {
-            let msg = WsMessage::CommentAdd { payload: comment, instant: true, buffer_interval_ms: None };
-            if let Ok(json) = serde_json::to_string(&msg) {
-                if tx.send(Message::Text(json)).is_err() {
-                    log::warn!("Failed to send cached comment to peer {}", peer_id);
-                    break;
-                }
-            }
-        }
-    }
+    // 生存確認タスク: 定期的にPingを送り、Pongがなければ終了する
+    let heartbeat = state.read().await.heartbeat;
+    let mut heartbeat_task = tokio::spawn(run_heartbeat(tx.clone(), Arc::clone(&last_seen), peer_id, heartbeat));
 
     // 送信タスク: チャネルからメッセージを受信してWebSocketに送信
-    let send_task = tokio::spawn(async move {
+    let mut send_task = tokio::spawn(async move {
         while let Some(msg) = rx.recv().await {
             if ws_sender.send(msg).await.is_err() {
                 break;
@@ -264,17 +927,24 @@ async fn handle_connection(
         }
     });
 
-    // 受信タスク: WebSocketからメッセージを受信（現在は特に処理なし）
-    let recv_task = tokio::spawn(async move {
+    // 受信タスク: WebSocketからオーバーレイのメッセージを受信して処理
+    let recv_state = Arc::clone(&state);
+    let mut recv_task = tokio::spawn(async move {
         while let Some(result) = ws_receiver.next().await {
+            *last_seen.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
             match result {
+                Ok(Message::Text(text)) => {
+                    handle_client_message(&text, &recv_state, &db, &tx, peer_id).await;
+                }
+                Ok(Message::Pong(_)) => {
+                    recv_state.read().await.record_pong(peer_id).await;
+                }
                 Ok(msg) => {
                     if msg.is_close() {
                         log::info!("Peer {} sent close frame", peer_id);
                         break;
                     }
-                    // 今後、クライアントからのメッセージを処理する場合はここに追加
-                    log::debug!("Received message from peer {}: {:?}", peer_id, msg);
+                    log::debug!("Received non-text message from peer {}: {:?}", peer_id, msg);
                 }
                 Err(e) => {
                     log::warn!("WebSocket error from peer {}: {}", peer_id, e);
@@ -284,11 +954,15 @@ async fn handle_connection(
         }
     });
 
-    // どちらかのタスクが終了するまで待機
+    // いずれかのタスクが終了するまで待機し、残りのタスクも停止する（接続を閉じる）
     tokio::select! {
-        _ = send_task => {},
-        _ = recv_task => {},
+        _ = &mut send_task => {},
+        _ = &mut recv_task => {},
+        _ = &mut heartbeat_task => {},
     }
+    send_task.abort();
+    recv_task.abort();
+    heartbeat_task.abort();
 
     // 接続終了時にピアを削除
     {
@@ -299,6 +973,163 @@ async fn handle_connection(
     log::info!("WebSocket connection closed for peer {}", peer_id);
 }
 
+/// 生存確認: 一定間隔でPingを送信し、タイムアウトまでに応答がなければ終了する
+///
+/// 一時停止したOBSのページ等、送信が失敗しないまま応答しない接続を検出する。
+/// Pong以外のフレームの受信も応答として扱う
+async fn run_heartbeat(
+    tx: Tx,
+    last_seen: Arc<std::sync::Mutex<Instant>>,
+    peer_id: usize,
+    heartbeat: Heartbeat,
+) {
+    loop {
+        tokio::time::sleep(heartbeat.interval).await;
+        let sent_at = Instant::now();
+        if tx.send(Message::Ping(Vec::new())).is_err() {
+            return;
+        }
+        tokio::time::sleep(heartbeat.timeout).await;
+        if *last_seen.lock().unwrap_or_else(|e| e.into_inner()) < sent_at {
+            log::warn!(
+                "Peer {} did not respond to ping within {:?}, disconnecting",
+                peer_id,
+                heartbeat.timeout
+            );
+            return;
+        }
+    }
+}
+
+/// 接続を認証
+///
+/// `?token=`クエリで正しいトークンを提示していない場合は、
+/// 最初のメッセージ（`{"type":"auth","token":"..."}`）を一定時間待って検証する
+async fn authenticate<S: AsyncRead + AsyncWrite + Unpin>(
+    ws_stream: &mut WebSocketStream<S>,
+    auth_token: &AuthToken,
+    query_token: Option<&str>,
+) -> bool {
+    if ws_auth::is_authorized(auth_token, query_token) {
+        return true;
+    }
+
+    let first = tokio::time::timeout(ws_auth::AUTH_MESSAGE_TIMEOUT, ws_stream.next()).await;
+    let Ok(Some(Ok(Message::Text(text)))) = first else {
+        return false;
+    };
+    match serde_json::from_str::<ClientMessage>(&text) {
+        Ok(ClientMessage::Auth { token }) => ws_auth::is_authorized(auth_token, Some(&token)),
+        _ => false,
+    }
+}
+
+/// 認証エラーのクローズフレーム
+fn unauthorized_close_frame() -> CloseFrame<'static> {
+    CloseFrame {
+        code: CloseCode::from(ws_auth::UNAUTHORIZED_CLOSE_CODE),
+        reason: "Unauthorized".into(),
+    }
+}
+
+/// 初期表示用データ（接続状態、最新セットリスト、ブランド設定、コメントテーマ、キャッシュされたコメント、表示中のスパチャ、固定表示）を生成
+///
+/// 接続時と、オーバーレイからの再送要求（`request_snapshot`）時に使用する
+async fn build_snapshot(state: &Arc<RwLock<WebSocketState>>, db: &SqlitePool) -> Vec<WsMessage> {
+    let mut messages = vec![WsMessage::Status {
+        payload: state.read().await.status(),
+    }];
+    messages.extend(fetch_latest_setlist_message(db).await);
+    messages.extend(fetch_brand_settings_message(db).await);
+    messages.extend(fetch_comment_theme_message(db).await);
+
+    // Note: キャッシュコメントは即時表示（instant: true）で送信し、
+    // 接続直後のキャッチアップを素早く行う
+    // 表示中のスパチャは残りの表示時間で送信する（タイマーを最初からやり直さない）
+    let (cached_comments, active_superchats, pinned) = {
+        let state_guard = state.read().await;
+        (
+            state_guard.get_cached_comments().await,
+            state_guard.get_active_superchats(),
+            state_guard.pinned_message(),
+        )
+    };
+    messages.extend(cached_comments.into_iter().map(|comment| WsMessage::CommentAdd {
+        payload: comment.payload,
+        instant: true,
+        buffer_interval_ms: None,
+        is_first_time: comment.is_first_time,
+        translation: comment.translation,
+        severity: comment.severity,
+    }));
+    messages.extend(
+        active_superchats
+            .into_iter()
+            .map(|payload| WsMessage::SuperchatAdd { payload }),
+    );
+    messages.extend(pinned.map(|payload| WsMessage::PinMessage { payload }));
+
+    messages
+}
+
+/// 初期表示用データを1つのピアに送信（購読トピックに一致するメッセージのみ）
+fn send_snapshot(peer: &Peer, peer_id: usize, snapshot: Vec<WsMessage>) {
+    log::debug!("Sending snapshot ({} messages) to peer {}", snapshot.len(), peer_id);
+    for msg in snapshot.iter().filter_map(|msg| peer.filter(msg)) {
+        if let Ok(json) = serde_json::to_string(&msg) {
+            if peer.tx.send(Message::Text(json)).is_err() {
+                log::warn!("Failed to send snapshot to peer {}", peer_id);
+                break;
+            }
+        }
+    }
+}
+
+/// オーバーレイから受信したメッセージを処理
+///
+/// 未知の種別・不正なJSONはログに記録して無視する（接続は維持）
+async fn handle_client_message(
+    text: &str,
+    state: &Arc<RwLock<WebSocketState>>,
+    db: &SqlitePool,
+    tx: &Tx,
+    peer_id: usize,
+) {
+    let message = match serde_json::from_str::<ClientMessage>(text) {
+        Ok(message) => message,
+        Err(e) => {
+            log::debug!("Ignored unknown message from peer {}: {} ({})", peer_id, text, e);
+            return;
+        }
+    };
+
+    match message {
+        ClientMessage::Ack { id } => {
+            log::debug!("Peer {} acknowledged: {:?}", peer_id, id);
+        }
+        ClientMessage::RequestSnapshot => {
+            log::info!("Peer {} requested snapshot", peer_id);
+            let snapshot = build_snapshot(state, db).await;
+            let peer = state.read().await.peer(peer_id).await.unwrap_or_else(|| Peer {
+                tx: tx.clone(),
+                topics: None,
+            });
+            send_snapshot(&peer, peer_id, snapshot);
+        }
+        ClientMessage::Auth { .. } => {
+            log::debug!("Peer {} is already authenticated", peer_id);
+        }
+        ClientMessage::ReportError { message, source } => {
+            log::warn!(
+                "Overlay error reported by peer {} ({}): {}",
+                peer_id,
+                source.as_deref().unwrap_or("unknown"),
+                message
+            );
+        }
+    }
+}
+
 /// 最新セットリストを取得してWsMessageを生成
 async fn fetch_latest_setlist_message(pool: &SqlitePool) -> Option<WsMessage> {
     // 最新のセットリストIDを取得
@@ -321,10 +1152,10 @@ async fn fetch_latest_setlist_message(pool: &SqlitePool) -> Option<WsMessage> {
     };
 
     // 楽曲リスト取得
-    let songs_result = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, Option<String>)>(
+    let songs_result = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, Option<String>, Option<i64>, Option<String>)>(
         r#"
         SELECT
-            ss.id, s.title, s.artist, ss.started_at, ss.ended_at
+            ss.id, s.title, s.artist, ss.started_at, ss.ended_at, s.duration_seconds, s.song_key
         FROM setlist_songs ss
         JOIN songs s ON ss.song_id = s.id
         WHERE ss.setlist_id = ?
@@ -370,6 +1201,8 @@ async fn fetch_latest_setlist_message(pool: &SqlitePool) -> Option<WsMessage> {
                 title: row.1,
                 artist: row.2.unwrap_or_default(),
                 status,
+                duration_seconds: row.5,
+                song_key: row.6,
             }
         })
         .collect();
@@ -431,3 +1264,875 @@ async fn fetch_brand_settings_message(pool: &SqlitePool) -> Option<WsMessage> {
     log::debug!("Generated initial brand settings message");
     Some(WsMessage::BrandUpdate { payload })
 }
+
+/// 保存済みのコメントテーマのメッセージを生成（未保存の場合はNone）
+async fn fetch_comment_theme_message(pool: &SqlitePool) -> Option<WsMessage> {
+    match comment_theme::load_comment_theme(pool).await {
+        Ok(theme) => theme.map(comment_theme::comment_theme_message),
+        Err(e) => {
+            log::error!("Failed to fetch comment theme for initial message: {}", e);
+            None
+        }
+    }
+}
+
+/// アバター画像のない投稿者にチャンネルIDから生成したidenticonを割り当てる
+fn fill_fallback_avatar(message: &mut WsMessage) {
+    let (image_url, channel_id) = match message {
+        WsMessage::CommentAdd { payload, .. } => (&mut payload.author_image_url, &payload.author_channel_id),
+        WsMessage::SuperchatAdd { payload } => (&mut payload.author_image_url, &payload.author_channel_id),
+        WsMessage::MembershipAdd { payload } => (&mut payload.author_image_url, &payload.author_channel_id),
+        _ => return,
+    };
+    if image_url.is_empty() {
+        *image_url = identicon_svg(channel_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::types::{CommentRemovePayload, CommentTranslationPayload, KpiUpdatePayload};
+    use crate::youtube::types::MessageType;
+
+    fn kpi_message(main: i64) -> WsMessage {
+        WsMessage::KpiUpdate {
+            payload: KpiUpdatePayload {
+                main: Some(main),
+                label: None,
+                sub: None,
+                sub_label: None,
+            },
+        }
+    }
+
+    fn comment_remove_message(id: &str) -> WsMessage {
+        WsMessage::CommentRemove {
+            payload: CommentRemovePayload { id: id.to_string() },
+        }
+    }
+
+    /// 受信したフレームをJSONとしてすべて取り出す
+    fn drain_frames(rx: &mut mpsc::UnboundedReceiver<Message>) -> Vec<serde_json::Value> {
+        let mut frames = Vec::new();
+        while let Ok(Message::Text(json)) = rx.try_recv() {
+            frames.push(serde_json::from_str(&json).unwrap());
+        }
+        frames
+    }
+
+    async fn wait_for_tick() {
+        tokio::time::sleep(Duration::from_millis(BUNDLE_TICK_MS * 4)).await;
+    }
+
+    #[tokio::test]
+    async fn test_bundling_merges_broadcasts_within_tick() {
+        let state = WebSocketState::new();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        state.add_peer(state.next_id(), tx).await;
+        state.set_bundling(true);
+
+        state.broadcast(kpi_message(100)).await;
+        state.broadcast(comment_remove_message("c1")).await;
+        state.broadcast(kpi_message(120)).await;
+
+        // ティック経過前は送信されない
+        assert!(drain_frames(&mut rx).is_empty());
+
+        wait_for_tick().await;
+        let frames = drain_frames(&mut rx);
+
+        // 1フレームにまとめられ、種別と順序が保持される
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0]["type"], "bundle");
+        let messages = frames[0]["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0]["type"], "kpi:update");
+        assert_eq!(messages[0]["payload"]["main"], 100);
+        assert_eq!(messages[1]["type"], "comment:remove");
+        assert_eq!(messages[1]["payload"]["id"], "c1");
+        assert_eq!(messages[2]["type"], "kpi:update");
+        assert_eq!(messages[2]["payload"]["main"], 120);
+    }
+
+    #[tokio::test]
+    async fn test_bundling_single_message_is_not_wrapped() {
+        let state = WebSocketState::new();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        state.add_peer(state.next_id(), tx).await;
+        state.set_bundling(true);
+
+        state.broadcast(kpi_message(100)).await;
+        wait_for_tick().await;
+
+        let frames = drain_frames(&mut rx);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0]["type"], "kpi:update");
+
+        // 次のティックは新しいフレームとして送信される
+        state.broadcast(comment_remove_message("c2")).await;
+        wait_for_tick().await;
+        let frames = drain_frames(&mut rx);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0]["type"], "comment:remove");
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_without_bundling_sends_immediately() {
+        let state = WebSocketState::new();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        state.add_peer(state.next_id(), tx).await;
+        assert!(!state.is_bundling());
+
+        state.broadcast(kpi_message(100)).await;
+        state.broadcast(comment_remove_message("c1")).await;
+
+        let frames = drain_frames(&mut rx);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0]["type"], "kpi:update");
+        assert_eq!(frames[1]["type"], "comment:remove");
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_fills_missing_avatar_with_identicon() {
+        let state = WebSocketState::new();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        state.add_peer(state.next_id(), tx).await;
+
+        let comment = ChatMessage::test_message("c1");
+        state
+            .broadcast(WsMessage::CommentAdd { payload: comment, instant: false, buffer_interval_ms: None, is_first_time: false, translation: None, severity: Severity::Clean })
+            .await;
+
+        let expected = identicon_svg("UC_viewer");
+        let frames = drain_frames(&mut rx);
+        assert_eq!(frames[0]["payload"]["authorImageUrl"], expected.as_str());
+        assert_eq!(state.get_cached_comments().await[0].payload.author_image_url, expected);
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_keeps_missing_avatar_when_fallback_disabled() {
+        let state = WebSocketState::new();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        state.add_peer(state.next_id(), tx).await;
+        state.set_fallback_avatar_enabled(false);
+
+        let comment = ChatMessage::test_message("c1");
+        state
+            .broadcast(WsMessage::CommentAdd { payload: comment, instant: false, buffer_interval_ms: None, is_first_time: false, translation: None, severity: Severity::Clean })
+            .await;
+
+        let frames = drain_frames(&mut rx);
+        assert_eq!(frames[0]["payload"]["authorImageUrl"], "");
+        assert!(state.get_cached_comments().await[0].payload.author_image_url.is_empty());
+    }
+
+    fn cached_comment(id: &str) -> ChatMessage {
+        ChatMessage {
+            author_image_url: "https://example.com/a.png".to_string(),
+            ..ChatMessage::test_message(id)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_comment_throttle_delays_text_comments_only() {
+        let state = WebSocketState::new();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        state.add_peer(state.next_id(), tx).await;
+        state.set_comment_throttle_rate(10);
+
+        let comment_add = |payload: ChatMessage| WsMessage::CommentAdd {
+            payload,
+            instant: true,
+            buffer_interval_ms: None,
+            is_first_time: false,
+            translation: None,
+            severity: Severity::Clean,
+        };
+        state.broadcast(comment_add(cached_comment("c1"))).await;
+        state.broadcast(comment_add(cached_comment("c2"))).await;
+        let mut superchat = cached_comment("sc1");
+        superchat.message_type = MessageType::SuperChat {
+            amount: "¥1,000".to_string(),
+            currency: "JPY".to_string(),
+            amount_micros: Some(1_000_000_000),
+        };
+        state.broadcast(comment_add(superchat)).await;
+
+        // 2件目の通常コメントは遅れて配信され、スパチャは待たずに配信される
+        let ids: Vec<_> = drain_frames(&mut rx)
+            .iter()
+            .map(|frame| frame["payload"]["id"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(ids, vec!["c1", "sc1"]);
+        // 配信待ちのコメントはまだ再送キャッシュ・流速に記録しない
+        assert_eq!(cached_ids(&state).await, vec!["c1", "sc1"]);
+        assert_eq!(state.chat_rate().count, 2);
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let frames = drain_frames(&mut rx);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0]["payload"]["id"], "c2");
+        assert_eq!(cached_ids(&state).await, vec!["c1", "sc1", "c2"]);
+        assert_eq!(state.chat_rate().count, 3);
+    }
+
+    async fn cached_ids(state: &WebSocketState) -> Vec<String> {
+        state
+            .get_cached_comments()
+            .await
+            .into_iter()
+            .map(|comment| comment.payload.id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_comment_dropped_by_throttle_is_not_recorded() {
+        let state = WebSocketState::new();
+        state.set_comment_throttle_rate(1);
+
+        // 1件目はすぐに配信、以降は配信待ち（上限5件を超えたc1は破棄される）
+        for i in 0..7 {
+            state
+                .broadcast(WsMessage::CommentAdd {
+                    payload: cached_comment(&format!("c{}", i)),
+                    instant: true,
+                    buffer_interval_ms: None,
+                    is_first_time: false,
+                    translation: None,
+                    severity: Severity::Clean,
+                })
+                .await;
+        }
+
+        assert_eq!(cached_ids(&state).await, vec!["c0"]);
+        assert_eq!(state.chat_rate().count, 1);
+    }
+
+    #[test]
+    fn test_client_message_parse() {
+        assert_eq!(
+            serde_json::from_str::<ClientMessage>(r#"{"type":"request_snapshot"}"#).unwrap(),
+            ClientMessage::RequestSnapshot
+        );
+        assert_eq!(
+            serde_json::from_str::<ClientMessage>(r#"{"type":"ack","id":"ready"}"#).unwrap(),
+            ClientMessage::Ack { id: Some("ready".to_string()) }
+        );
+        assert_eq!(
+            serde_json::from_str::<ClientMessage>(r#"{"type":"report_error","message":"boom"}"#).unwrap(),
+            ClientMessage::ReportError { message: "boom".to_string(), source: None }
+        );
+        assert!(serde_json::from_str::<ClientMessage>(r#"{"type":"unknown"}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_request_snapshot_resends_to_requesting_peer() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let db = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        let state = Arc::new(RwLock::new(WebSocketState::new()));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (other_tx, mut other_rx) = mpsc::unbounded_channel();
+        {
+            let state_guard = state.read().await;
+            state_guard.add_peer(1, tx.clone()).await;
+            state_guard.add_peer(2, other_tx).await;
+            state_guard.add_to_cache(cached_comment("c1").into()).await;
+        }
+
+        // 未知の種別・不正なJSONは無視する
+        handle_client_message(r#"{"type":"unknown"}"#, &state, &db, &tx, 1).await;
+        handle_client_message("not json", &state, &db, &tx, 1).await;
+        assert!(drain_frames(&mut rx).is_empty());
+
+        handle_client_message(r#"{"type":"request_snapshot"}"#, &state, &db, &tx, 1).await;
+
+        // 要求したピアにのみ接続状態とキャッシュ済みコメントが再送される
+        let frames = drain_frames(&mut rx);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0]["type"], "status:update");
+        assert_eq!(frames[1]["type"], "comment:add");
+        assert_eq!(frames[1]["payload"]["id"], "c1");
+        assert_eq!(frames[1]["instant"], true);
+        assert!(drain_frames(&mut other_rx).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_replays_cached_comment_metadata() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let db = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        let state = Arc::new(RwLock::new(WebSocketState::new()));
+        state
+            .read()
+            .await
+            .broadcast(WsMessage::CommentAdd {
+                payload: cached_comment("c1"),
+                instant: false,
+                buffer_interval_ms: None,
+                is_first_time: true,
+                translation: None,
+                severity: Severity::Strong,
+            })
+            .await;
+        // 翻訳は後から届く
+        state
+            .read()
+            .await
+            .broadcast(WsMessage::CommentTranslation {
+                payload: CommentTranslationPayload {
+                    id: "c1".to_string(),
+                    translation: "こんにちは".to_string(),
+                },
+            })
+            .await;
+
+        // 配信時の判定結果・翻訳を再送時にも引き継ぐ
+        let snapshot = build_snapshot(&state, &db).await;
+        let (is_first_time, translation, severity) = snapshot
+            .iter()
+            .find_map(|msg| match msg {
+                WsMessage::CommentAdd { payload, is_first_time, translation, severity, .. } if payload.id == "c1" => {
+                    Some((*is_first_time, translation.clone(), *severity))
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert!(is_first_time);
+        assert_eq!(translation.as_deref(), Some("こんにちは"));
+        assert_eq!(severity, Severity::Strong);
+    }
+
+    #[tokio::test]
+    async fn test_milestone_broadcast_after_crossing_comment() {
+        let state = WebSocketState::new();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        state.add_peer(state.next_id(), tx).await;
+        state.set_milestone_thresholds(MilestoneThresholds {
+            comments: vec![2],
+            superchat_jpy: Vec::new(),
+        });
+
+        for id in ["c1", "c2", "c3"] {
+            state
+                .broadcast(WsMessage::CommentAdd { payload: cached_comment(id), instant: false, buffer_interval_ms: None, is_first_time: false, translation: None, severity: Severity::Clean })
+                .await;
+        }
+
+        // 閾値を超えたコメントの直後に1回だけ配信される
+        let types: Vec<String> = drain_frames(&mut rx)
+            .iter()
+            .map(|frame| frame["type"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(types, vec!["comment:add", "comment:add", "milestone", "comment:add"]);
+    }
+
+    #[tokio::test]
+    async fn test_test_comment_is_not_counted() {
+        let state = WebSocketState::new();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        state.add_peer(state.next_id(), tx).await;
+        state.set_milestone_thresholds(MilestoneThresholds {
+            comments: vec![1],
+            superchat_jpy: Vec::new(),
+        });
+
+        state
+            .broadcast_test_comment(WsMessage::CommentAdd { payload: cached_comment("test-1"), instant: true, buffer_interval_ms: None, is_first_time: false, translation: None, severity: Severity::Clean })
+            .await;
+
+        // テストコメントは表示するが、マイルストーン・流速・再送キャッシュには記録しない
+        let types: Vec<String> = drain_frames(&mut rx)
+            .iter()
+            .map(|frame| frame["type"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(types, vec!["comment:add"]);
+        assert_eq!(state.chat_rate().count, 0);
+        assert!(state.get_cached_comments().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_respects_subscribed_topics() {
+        let state = WebSocketState::new();
+        let (weather_tx, mut weather_rx) = mpsc::unbounded_channel();
+        let (comment_tx, mut comment_rx) = mpsc::unbounded_channel();
+        let (all_tx, mut all_rx) = mpsc::unbounded_channel();
+        state
+            .add_peer_with_topics(1, weather_tx, ws_topics::topics_from_query(Some("topics=weather,kpi")))
+            .await;
+        state
+            .add_peer_with_topics(2, comment_tx, ws_topics::topics_from_query(Some("topics=comment")))
+            .await;
+        state.add_peer(3, all_tx).await;
+
+        state
+            .broadcast(WsMessage::CommentAdd { payload: cached_comment("c1"), instant: false, buffer_interval_ms: None, is_first_time: false, translation: None, severity: Severity::Clean })
+            .await;
+        state.broadcast(kpi_message(10)).await;
+        state.broadcast(comment_remove_message("c1")).await;
+
+        let types = |frames: Vec<serde_json::Value>| -> Vec<String> {
+            frames.iter().map(|frame| frame["type"].as_str().unwrap().to_string()).collect()
+        };
+        assert_eq!(types(drain_frames(&mut weather_rx)), vec!["kpi:update"]);
+        assert_eq!(types(drain_frames(&mut comment_rx)), vec!["comment:add", "comment:remove"]);
+        // トピック未指定のピアは全メッセージを受信
+        assert_eq!(
+            types(drain_frames(&mut all_rx)),
+            vec!["comment:add", "kpi:update", "comment:remove"]
+        );
+
+        // バンドル送信時も購読トピックごとに絞り込む
+        state.set_bundling(true);
+        state.broadcast(kpi_message(20)).await;
+        state.broadcast(comment_remove_message("c2")).await;
+        state.broadcast(comment_remove_message("c3")).await;
+        wait_for_tick().await;
+
+        let frames = drain_frames(&mut weather_rx);
+        assert_eq!(types(frames.clone()), vec!["kpi:update"]);
+        assert_eq!(frames[0]["payload"]["main"], 20);
+        let frames = drain_frames(&mut comment_rx);
+        assert_eq!(types(frames.clone()), vec!["bundle"]);
+        assert_eq!(frames[0]["messages"].as_array().unwrap().len(), 2);
+        let frames = drain_frames(&mut all_rx);
+        assert_eq!(frames[0]["messages"].as_array().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_status_broadcast_only_on_change() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let db = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        let state = Arc::new(RwLock::new(WebSocketState::new()));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        state.read().await.add_peer(1, tx).await;
+
+        {
+            let state = state.read().await;
+            state.set_polling_status(Some(ApiMode::InnerTube)).await;
+            state.set_polling_status(Some(ApiMode::InnerTube)).await;
+            state.set_weather_status(true).await;
+            state.set_weather_status(false).await;
+            state.set_polling_status(None).await;
+        }
+
+        let frames = drain_frames(&mut rx);
+        let payloads: Vec<&serde_json::Value> = frames.iter().map(|frame| &frame["payload"]).collect();
+        assert!(frames.iter().all(|frame| frame["type"] == "status:update"));
+        assert_eq!(
+            payloads,
+            vec![
+                &serde_json::json!({ "polling": true, "mode": "innertube", "weatherOk": true }),
+                &serde_json::json!({ "polling": true, "mode": "innertube", "weatherOk": false }),
+                &serde_json::json!({ "polling": false, "mode": null, "weatherOk": false }),
+            ]
+        );
+
+        // 接続時のスナップショットの先頭で現在の状態を送る
+        let snapshot = build_snapshot(&state, &db).await;
+        assert!(matches!(
+            snapshot.first(),
+            Some(WsMessage::Status { payload }) if !payload.polling && !payload.weather_ok
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_replay_buffer_size_limits_cached_comments() {
+        let state = WebSocketState::new();
+        state.set_replay_buffer_size(2).await;
+        for id in ["c1", "c2", "c3"] {
+            state.add_to_cache(cached_comment(id).into()).await;
+        }
+        let ids: Vec<String> = state.get_cached_comments().await.into_iter().map(|c| c.payload.id).collect();
+        assert_eq!(ids, vec!["c2", "c3"]);
+
+        // 0の場合は再送しない
+        state.set_replay_buffer_size(0).await;
+        assert!(state.get_cached_comments().await.is_empty());
+        state.add_to_cache(cached_comment("c4").into()).await;
+        assert!(state.get_cached_comments().await.is_empty());
+
+        // 上限を超える値は最大件数に丸める
+        state.set_replay_buffer_size(usize::MAX).await;
+        assert_eq!(state.replay_buffer_size(), MAX_REPLAY_BUFFER_SIZE);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_replays_superchat_with_remaining_duration() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let db = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        let state = Arc::new(RwLock::new(WebSocketState::new()));
+
+        let mut message = cached_comment("sc1");
+        message.message_type = MessageType::SuperChat {
+            amount: "¥1,000".to_string(),
+            currency: "JPY".to_string(),
+            amount_micros: Some(1_000_000_000),
+        };
+        let payload = crate::superchat::create_superchat_payload(&message).unwrap();
+        let full_duration = payload.display_duration_ms;
+        assert!(full_duration > 3_000);
+        state
+            .read()
+            .await
+            .broadcast(WsMessage::SuperchatAdd { payload })
+            .await;
+
+        // 表示開始から3秒経過した状態にする
+        {
+            let state_guard = state.read().await;
+            let mut superchats = state_guard.active_superchats.lock().unwrap();
+            superchats[0].1 -= Duration::from_secs(3);
+        }
+
+        let snapshot = build_snapshot(&state, &db).await;
+        let replayed: Vec<&SuperchatPayload> = snapshot
+            .iter()
+            .filter_map(|message| match message {
+                WsMessage::SuperchatAdd { payload } => Some(payload),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(replayed.len(), 1);
+        assert_eq!(replayed[0].id, "sc1");
+        assert!(replayed[0].display_duration_ms <= full_duration - 3_000);
+        assert!(replayed[0].display_duration_ms > 0);
+
+        // 表示が終了したスパチャは再送しない
+        state
+            .read()
+            .await
+            .broadcast(WsMessage::SuperchatRemove {
+                payload: crate::server::types::SuperchatRemovePayload { id: "sc1".to_string() },
+            })
+            .await;
+        assert!(state.read().await.get_active_superchats().is_empty());
+
+        // 表示時間を過ぎたスパチャも再送しない
+        let mut expired = crate::superchat::create_superchat_payload(&message).unwrap();
+        expired.id = "sc2".to_string();
+        let state_guard = state.read().await;
+        state_guard.broadcast(WsMessage::SuperchatAdd { payload: expired }).await;
+        state_guard.active_superchats.lock().unwrap()[0].1 -= Duration::from_millis(full_duration);
+        assert!(state_guard.get_active_superchats().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_replays_current_pin() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let db = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        let state = Arc::new(RwLock::new(WebSocketState::new()));
+        let pin = |id: &str| PinPayload {
+            id: id.to_string(),
+            kind: crate::server::types::PinKind::Announcement,
+            text: format!("announcement {}", id),
+            comment: None,
+            pinned_at: chrono::Utc::now(),
+        };
+        let pinned_ids = |snapshot: Vec<WsMessage>| -> Vec<String> {
+            snapshot
+                .into_iter()
+                .filter_map(|message| match message {
+                    WsMessage::PinMessage { payload } => Some(payload.id),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        assert!(pinned_ids(build_snapshot(&state, &db).await).is_empty());
+
+        // 新しい固定は前の固定を置き換える
+        state.read().await.broadcast(WsMessage::PinMessage { payload: pin("a1") }).await;
+        state.read().await.broadcast(WsMessage::PinMessage { payload: pin("a2") }).await;
+        assert_eq!(pinned_ids(build_snapshot(&state, &db).await), vec!["a2"]);
+
+        state.read().await.broadcast(WsMessage::UnpinMessage).await;
+        assert!(pinned_ids(build_snapshot(&state, &db).await).is_empty());
+        assert_eq!(
+            serde_json::to_value(WsMessage::UnpinMessage).unwrap(),
+            serde_json::json!({ "type": "pin:clear" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_includes_saved_comment_theme() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let db = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        let state = Arc::new(RwLock::new(WebSocketState::new()));
+        comment_theme::apply_comment_theme(&db, &state, "terminal")
+            .await
+            .unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        handle_client_message(r#"{"type":"request_snapshot"}"#, &state, &db, &tx, 1).await;
+
+        let frames = drain_frames(&mut rx);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0]["type"], "status:update");
+        assert_eq!(frames[1]["type"], "comment:theme");
+        assert_eq!(frames[1]["payload"]["theme"], "terminal");
+    }
+
+    #[tokio::test]
+    async fn test_connected_client_listed_with_connect_time() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let db = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        let state = Arc::new(RwLock::new(WebSocketState::new()));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server_addr = listener.local_addr().unwrap();
+        let server_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            let (stream, peer_addr) = listener.accept().await.unwrap();
+            handle_connection(server_state, stream, peer_addr, Arc::new(db)).await;
+        });
+
+        let before = chrono::Utc::now();
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", server_addr))
+            .await
+            .unwrap();
+
+        let mut clients = Vec::new();
+        for _ in 0..50 {
+            clients = state.read().await.connected_clients().await;
+            if !clients.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(clients.len(), 1);
+        assert!(clients[0].remote_addr.starts_with("127.0.0.1:"));
+        assert!(clients[0].last_pong.is_none());
+        let connected_at = chrono::DateTime::parse_from_rfc3339(&clients[0].connected_at).unwrap();
+        assert!(connected_at >= before && connected_at <= chrono::Utc::now());
+
+        // 切断すると一覧から消える
+        client.close(None).await.unwrap();
+        for _ in 0..50 {
+            if state.read().await.connected_clients().await.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(state.read().await.connected_clients().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_drops_unresponsive_client() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let db = Arc::new(
+            crate::db::create_pool(temp_file.path().to_str().unwrap())
+                .await
+                .unwrap(),
+        );
+        let mut state = WebSocketState::new();
+        state.heartbeat = Heartbeat {
+            interval: Duration::from_millis(50),
+            timeout: Duration::from_millis(50),
+        };
+        let state = Arc::new(RwLock::new(state));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server_addr = listener.local_addr().unwrap();
+        let server_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            while let Ok((stream, peer_addr)) = listener.accept().await {
+                let state = Arc::clone(&server_state);
+                tokio::spawn(handle_connection(state, stream, peer_addr, Arc::clone(&db)));
+            }
+        });
+
+        // 受信を続けるクライアントはPingに自動応答するため切断されない
+        let (mut live, _) =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?topics=kpi", server_addr))
+                .await
+                .unwrap();
+        let live_task = tokio::spawn(async move { while live.next().await.is_some() {} });
+        // 受信しないクライアント（一時停止したページ相当）はPongを返さない
+        let (_suspended, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", server_addr))
+            .await
+            .unwrap();
+
+        let mut count = 0;
+        for _ in 0..50 {
+            count = state.read().await.peer_count().await;
+            if count == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(count, 2);
+
+        for _ in 0..100 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            count = state.read().await.peer_count().await;
+            if count == 1 {
+                break;
+            }
+        }
+        assert_eq!(count, 1);
+
+        // 応答し続けるクライアントは残り、購読トピックも一覧に含まれる
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let clients = state.read().await.connected_clients().await;
+        assert_eq!(clients.len(), 1);
+        assert_eq!(clients[0].topics, Some(vec!["kpi".to_string()]));
+        assert!(clients[0].last_pong.is_some());
+        live_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_auth_token_required_when_enabled() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let db = Arc::new(
+            crate::db::create_pool(temp_file.path().to_str().unwrap())
+                .await
+                .unwrap(),
+        );
+        let state = Arc::new(RwLock::new(WebSocketState::new()));
+        let token = ws_auth::generate_token();
+        state.read().await.set_auth_token(Some(token.clone()));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server_addr = listener.local_addr().unwrap();
+        let server_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            while let Ok((stream, peer_addr)) = listener.accept().await {
+                let state = Arc::clone(&server_state);
+                tokio::spawn(handle_connection(state, stream, peer_addr, Arc::clone(&db)));
+            }
+        });
+
+        // トークンなし: 最初のメッセージが認証でなければ4401で切断
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", server_addr))
+            .await
+            .unwrap();
+        client
+            .send(Message::Text(r#"{"type":"request_snapshot"}"#.to_string()))
+            .await
+            .unwrap();
+        let frame = client.next().await.unwrap().unwrap();
+        let Message::Close(Some(close)) = frame else {
+            panic!("expected close frame, got {:?}", frame);
+        };
+        assert_eq!(u16::from(close.code), ws_auth::UNAUTHORIZED_CLOSE_CODE);
+        assert!(state.read().await.connected_clients().await.is_empty());
+
+        // 誤ったトークンのクエリも拒否
+        let (mut client, _) =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?token=wrong", server_addr))
+                .await
+                .unwrap();
+        client
+            .send(Message::Text(r#"{"type":"auth","token":"wrong"}"#.to_string()))
+            .await
+            .unwrap();
+        assert!(matches!(
+            client.next().await.unwrap().unwrap(),
+            Message::Close(Some(_))
+        ));
+
+        // クエリ・最初のメッセージのどちらでも認証できる
+        let (_query_client, _) =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?token={}", server_addr, token))
+                .await
+                .unwrap();
+        let (mut message_client, _) =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws", server_addr))
+                .await
+                .unwrap();
+        message_client
+            .send(Message::Text(
+                serde_json::json!({"type": "auth", "token": token}).to_string(),
+            ))
+            .await
+            .unwrap();
+
+        let mut clients = Vec::new();
+        for _ in 0..50 {
+            clients = state.read().await.connected_clients().await;
+            if clients.len() == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(clients.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_wss_connection_with_self_signed_cert() {
+        use tokio_rustls::rustls::pki_types::pem::PemObject;
+        use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName};
+        use tokio_rustls::rustls::{crypto, ClientConfig, RootCertStore};
+
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let db = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        let state = Arc::new(RwLock::new(WebSocketState::new()));
+        let cert_dir = tempfile::tempdir().unwrap();
+        let acceptor = super::super::tls::load_or_create_acceptor(cert_dir.path()).unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server_addr = listener.local_addr().unwrap();
+        let server_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            let (stream, peer_addr) = listener.accept().await.unwrap();
+            let stream = acceptor.accept(stream).await.unwrap();
+            handle_connection(server_state, stream, peer_addr, Arc::new(db)).await;
+        });
+
+        // 生成した自己署名証明書を信頼するクライアントでWSS接続
+        let mut roots = RootCertStore::empty();
+        roots
+            .add(CertificateDer::from_pem_file(cert_dir.path().join(super::super::tls::CERT_FILE)).unwrap())
+            .unwrap();
+        let config = ClientConfig::builder_with_provider(Arc::new(crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let tcp = tokio::net::TcpStream::connect(server_addr).await.unwrap();
+        let tls_stream = tokio_rustls::TlsConnector::from(Arc::new(config))
+            .connect(ServerName::try_from("localhost").unwrap(), tcp)
+            .await
+            .unwrap();
+        let (mut client, _) = tokio_tungstenite::client_async("wss://localhost/ws", tls_stream)
+            .await
+            .unwrap();
+
+        for _ in 0..50 {
+            if state.read().await.peer_count().await == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        // 接続時のスナップショット（接続状態）に続いて、TLS越しにブロードキャストを受信できる
+        state.read().await.broadcast(kpi_message(10)).await;
+        let mut types = Vec::new();
+        for _ in 0..2 {
+            let Message::Text(text) = client.next().await.unwrap().unwrap() else {
+                panic!("expected text frame");
+            };
+            let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+            types.push(json["type"].as_str().unwrap().to_string());
+        }
+        assert_eq!(types, vec!["status:update", "kpi:update"]);
+    }
+}
@@ -0,0 +1,177 @@
+//! WebSocket接続の認証トークン
+//!
+//! 有効にすると、オーバーレイは共有トークンを`?token=`クエリ、
+//! または接続直後の最初のメッセージ（`{"type":"auth","token":"..."}`）で提示する必要がある。
+//! 正しいトークンを提示しない接続はクローズコード4401（HTTP 401相当）で切断する。
+//!
+//! - トークンは初回に生成し、OSのセキュアストレージ（keyring）に保存する
+//! - 有効/無効はsettingsテーブルに保存する（デフォルト: 無効）
+//! - HTTPサーバーのオーバーレイページも同じトークンを要求する
+
+use rand::RngCore;
+use sqlx::SqlitePool;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::keyring as secure_storage;
+
+/// 認証の有効/無効の保存キー
+const WS_AUTH_ENABLED_KEY: &str = "ws_auth_enabled";
+
+/// トークンのバイト数（16進文字列で64文字）
+const TOKEN_BYTES: usize = 32;
+
+/// 認証失敗時のクローズコード（HTTP 401相当、4000〜4999はアプリケーション定義）
+pub const UNAUTHORIZED_CLOSE_CODE: u16 = 4401;
+
+/// クエリでトークンを提示しなかった場合に、最初のメッセージを待つ時間
+pub const AUTH_MESSAGE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 接続に必要なトークン（Noneの場合は認証なし）
+///
+/// WebSocketサーバーとHTTPサーバーで共有する
+pub type AuthToken = Arc<RwLock<Option<String>>>;
+
+/// 新しいトークンを生成（OSの乱数源から32バイト、16進文字列）
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// クエリ文字列から`token`パラメータを取得
+pub fn token_from_query(query: Option<&str>) -> Option<&str> {
+    query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "token")
+        .map(|(_, value)| value)
+        .filter(|value| !value.is_empty())
+}
+
+/// 提示されたトークンを検証
+///
+/// 認証が無効（必要なトークンがない）の場合は常にtrue。
+/// 比較は一定時間で行う（タイミング攻撃対策）
+pub fn is_authorized(required: &AuthToken, presented: Option<&str>) -> bool {
+    let required = match required.read() {
+        Ok(required) => required.clone(),
+        Err(e) => {
+            log::error!("Failed to read WebSocket auth token: {}", e);
+            return false;
+        }
+    };
+    let Some(required) = required else {
+        return true;
+    };
+    presented.is_some_and(|presented| constant_time_eq(required.as_bytes(), presented.as_bytes()))
+}
+
+/// 長さ以外の情報を漏らさない比較
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// 保存済みのトークンを取得し、なければ生成して保存
+///
+/// セキュアストレージが使えない場合は保存せずに生成したトークンを返す
+/// （再起動するとトークンが変わる）
+pub async fn load_or_create_token() -> String {
+    let result = tokio::task::spawn_blocking(|| match secure_storage::get_ws_auth_token() {
+        Ok(token) => Ok(token),
+        Err(secure_storage::KeyringError::NotFound) => {
+            let token = generate_token();
+            secure_storage::save_ws_auth_token(&token).map(|()| token)
+        }
+        Err(e) => Err(e),
+    })
+    .await;
+
+    match result {
+        Ok(Ok(token)) => token,
+        Ok(Err(e)) => {
+            log::warn!(
+                "WebSocket auth token is not persisted (keyring unavailable): {}",
+                e
+            );
+            generate_token()
+        }
+        Err(e) => {
+            log::warn!("Failed to load WebSocket auth token: {}", e);
+            generate_token()
+        }
+    }
+}
+
+/// 新しいトークンを生成してセキュアストレージに保存
+pub async fn rotate_token() -> Result<String, String> {
+    tokio::task::spawn_blocking(|| {
+        let token = generate_token();
+        secure_storage::save_ws_auth_token(&token).map(|()| token)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+    .map_err(|e| format!("Keyring error: {}", e))
+}
+
+/// 認証が有効かをDBから読み込み（未保存の場合は無効）
+pub async fn load_ws_auth_enabled(pool: &SqlitePool) -> Result<bool, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(WS_AUTH_ENABLED_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    Ok(result.is_some_and(|(value,)| value == "true"))
+}
+
+/// 認証の有効/無効を保存
+pub async fn save_ws_auth_enabled(pool: &SqlitePool, enabled: bool) -> Result<(), String> {
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(WS_AUTH_ENABLED_KEY)
+    .bind(enabled.to_string())
+    .bind(&now)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_from_query() {
+        assert_eq!(token_from_query(Some("token=abc")), Some("abc"));
+        assert_eq!(token_from_query(Some("debug=true&token=abc")), Some("abc"));
+        assert_eq!(token_from_query(Some("token=")), None);
+        assert_eq!(token_from_query(Some("tokens=abc")), None);
+        assert_eq!(token_from_query(None), None);
+    }
+
+    #[test]
+    fn test_is_authorized() {
+        let required: AuthToken = Arc::new(RwLock::new(None));
+        // 認証無効時はトークンなしでも許可
+        assert!(is_authorized(&required, None));
+
+        let token = generate_token();
+        assert_eq!(token.len(), TOKEN_BYTES * 2);
+        *required.write().unwrap() = Some(token.clone());
+        assert!(is_authorized(&required, Some(&token)));
+        assert!(!is_authorized(&required, None));
+        assert!(!is_authorized(&required, Some("wrong")));
+        assert!(!is_authorized(&required, Some(&generate_token())));
+    }
+}
@@ -0,0 +1,168 @@
+//! WebSocketメッセージのトピック購読
+//!
+//! オーバーレイは接続時に`?topics=weather,kpi`のように受信するトピックを指定できる。
+//! トピックはメッセージ種別の`:`より前の部分（`comment:add` → `comment`）。
+//! 指定しない場合は従来どおり全メッセージを受信する。
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use super::types::WsMessage;
+
+/// 購読できるトピック
+pub const WS_TOPICS: &[&str] = &[
+    "comment",
+    "setlist",
+    "settings",
+    "kpi",
+    "queue",
+    "promo",
+    "weather",
+    "chat",
+    "superchat",
+    "membership",
+    "brand",
+    "session",
+    "milestone",
+    "leaderboard",
+    "goal",
+    "pin",
+    "stream",
+    "status",
+    "selftest",
+];
+
+/// 購読トピックの集合
+pub type Topics = HashSet<&'static str>;
+
+/// クエリ文字列から`topics`パラメータ（カンマ区切り）を取得
+///
+/// 未知のトピックは警告して無視する。
+/// 指定がない・有効なトピックが1つもない場合はNone（全メッセージを受信）
+pub fn topics_from_query(query: Option<&str>) -> Option<Topics> {
+    let value = query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "topics")
+        .map(|(_, value)| value)?;
+
+    let mut topics = Topics::new();
+    for name in value.replace("%2C", ",").replace("%2c", ",").split(',') {
+        let name = name.trim();
+        if name.is_empty() {
+            continue;
+        }
+        match WS_TOPICS.iter().find(|topic| **topic == name) {
+            Some(topic) => {
+                topics.insert(*topic);
+            }
+            None => log::warn!("Ignored unknown WebSocket topic: {}", name),
+        }
+    }
+
+    if topics.is_empty() {
+        None
+    } else {
+        Some(topics)
+    }
+}
+
+/// 購読トピックに関係なく全オーバーレイに配信するトピック
+const ALWAYS_DELIVERED_TOPICS: &[&str] = &["status"];
+
+/// 購読トピックに一致するメッセージのみを残す
+///
+/// `Bundle`は一致するメッセージだけに絞り込む（1件ならそのまま、0件ならNone）。
+/// 接続状態（`status`）は購読トピックに関係なく残す
+pub fn filter_message<'a>(topics: &Topics, message: &'a WsMessage) -> Option<Cow<'a, WsMessage>> {
+    let WsMessage::Bundle { messages } = message else {
+        return message
+            .topic()
+            .filter(|topic| topics.contains(topic) || ALWAYS_DELIVERED_TOPICS.contains(topic))
+            .map(|_| Cow::Borrowed(message));
+    };
+
+    let mut matched: Vec<WsMessage> = messages
+        .iter()
+        .filter_map(|message| filter_message(topics, message))
+        .map(Cow::into_owned)
+        .collect();
+    match matched.len() {
+        0 => None,
+        1 => matched.pop().map(Cow::Owned),
+        n if n == messages.len() => Some(Cow::Borrowed(message)),
+        _ => Some(Cow::Owned(WsMessage::Bundle { messages: matched })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::types::{KpiUpdatePayload, SuperchatRemovePayload};
+
+    fn kpi_message() -> WsMessage {
+        WsMessage::KpiUpdate {
+            payload: KpiUpdatePayload {
+                main: Some(1),
+                label: None,
+                sub: None,
+                sub_label: None,
+            },
+        }
+    }
+
+    fn superchat_remove_message() -> WsMessage {
+        WsMessage::SuperchatRemove {
+            payload: SuperchatRemovePayload {
+                id: "sc1".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_topics_from_query() {
+        let topics = topics_from_query(Some("token=abc&topics=weather,kpi")).unwrap();
+        assert_eq!(topics, Topics::from(["weather", "kpi"]));
+
+        let topics = topics_from_query(Some("topics=weather%2Cunknown")).unwrap();
+        assert_eq!(topics, Topics::from(["weather"]));
+
+        // 未指定・有効なトピックなしは全メッセージを受信
+        assert_eq!(topics_from_query(Some("token=abc")), None);
+        assert_eq!(topics_from_query(Some("topics=")), None);
+        assert_eq!(topics_from_query(Some("topics=unknown")), None);
+        assert_eq!(topics_from_query(None), None);
+    }
+
+    #[test]
+    fn test_filter_bundle() {
+        let topics = Topics::from(["kpi"]);
+        let bundle = WsMessage::Bundle {
+            messages: vec![kpi_message(), superchat_remove_message()],
+        };
+
+        // 一致する1件のみになった場合はBundleを外す
+        let filtered = filter_message(&topics, &bundle).unwrap();
+        assert!(matches!(filtered.as_ref(), WsMessage::KpiUpdate { .. }));
+
+        assert!(filter_message(&Topics::from(["weather"]), &bundle).is_none());
+        assert!(matches!(
+            filter_message(&Topics::from(["kpi", "superchat"]), &bundle),
+            Some(Cow::Borrowed(_))
+        ));
+    }
+
+    #[test]
+    fn test_status_is_always_delivered() {
+        let status = WsMessage::Status {
+            payload: crate::server::types::StatusPayload::default(),
+        };
+        assert!(filter_message(&Topics::from(["weather"]), &status).is_some());
+
+        let bundle = WsMessage::Bundle {
+            messages: vec![kpi_message(), status],
+        };
+        let filtered = filter_message(&Topics::from(["weather"]), &bundle).unwrap();
+        assert!(matches!(filtered.as_ref(), WsMessage::Status { .. }));
+    }
+}
@@ -0,0 +1,50 @@
+//! アプリ終了処理
+//!
+//! 終了時にポーリングを止めてから書き込みキューに残ったコメントを保存し、
+//! WALをチェックポイントしてDB接続を閉じる。
+//! 配信の最後のコメントがcomment_logsに保存されないまま終了するのを防ぐ。
+
+use std::time::Duration;
+
+use crate::commands;
+use crate::youtube::{kpi_history, write_queue};
+use crate::AppState;
+
+/// 書き込みキューの保存完了を待つ最大時間
+/// 終了が長く止まらないよう短めにする（超えた分の書き込みは破棄）
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// アプリ終了処理（RunEvent::Exitで1回だけ呼び出す）
+pub async fn graceful_shutdown(state: &AppState) {
+    log::info!("Shutting down: stopping pollers");
+    stop_pollers(state).await;
+    // 配信中のセッションがあれば終了時刻を記録する
+    commands::youtube::record_session_end(&state.db).await;
+
+    // ポーリング停止後に積まれた書き込みも含めて保存完了を待つ
+    if write_queue::flush(FLUSH_TIMEOUT).await {
+        log::info!("Shutting down: comment write queue flushed");
+    }
+
+    crate::db::close_pool(&state.db).await;
+    log::info!("Shutting down: database closed");
+}
+
+/// 実行中のポーリング・自動取得をすべて停止
+async fn stop_pollers(state: &AppState) {
+    match state.poller.lock() {
+        Ok(poller) => {
+            if let Some(poller) = poller.as_ref() {
+                poller.stop();
+            }
+        }
+        Err(e) => log::warn!("Failed to acquire poller lock: {}", e),
+    }
+
+    commands::youtube::stop_innertube_poller().await;
+    commands::youtube::stop_unified_poller().await;
+    if let Err(e) = kpi_history::stop_sampler() {
+        log::warn!("Failed to stop KPI sampler: {}", e);
+    }
+    state.weather_updater.stop();
+}
@@ -0,0 +1,485 @@
+//! スパチャの目標金額（ゴール）
+//!
+//! 「目標 ¥50,000」のような進捗バー向けに、集計開始時刻以降のスパチャ合計額（日本円換算）を集計し、
+//! 対象のスパチャを受信するたびに`goal:update`でオーバーレイへ配信する。
+//! 新規メンバー・メンバーシップギフトも設定した1件あたりの金額で加算できる（メンバー継続は対象外）。
+//!
+//! 目標はsettingsテーブルに保存する。起動時・目標の変更時は集計開始時刻以降の記録
+//! （superchat_log・comment_logs）から合計を集計し直し、以降は受信したコメントから加算する。
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use tokio::sync::RwLock;
+
+use super::{convert_to_jpy, create_superchat_payload};
+use crate::membership::membership_kind;
+use crate::server::types::{GoalPayload, MembershipKind, WsMessage};
+use crate::server::websocket::WebSocketState;
+use crate::youtube::types::{ChatMessage, MessageType};
+
+/// 目標金額の設定の保存キー
+const SUPERCHAT_GOAL_KEY: &str = "superchat_goal";
+
+/// 目標金額の上限（円）
+pub const MAX_GOAL_TARGET_JPY: u64 = 100_000_000;
+
+/// ラベルの最大文字数
+pub const MAX_GOAL_LABEL_CHARS: usize = 50;
+
+/// メンバーシップ1件あたりの金額の上限（円）
+pub const MAX_MEMBERSHIP_VALUE_JPY: u64 = 100_000;
+
+fn default_membership_value_jpy() -> u64 {
+    490
+}
+
+/// 目標金額の設定
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuperchatGoal {
+    /// 目標を表示するか
+    pub enabled: bool,
+    /// 目標金額（円）
+    pub target_jpy: u64,
+    /// 表示ラベル
+    #[serde(default)]
+    pub label: String,
+    /// 集計開始時刻（この時刻以降のスパチャを集計。保存時に未指定の場合は保存した時刻）
+    #[serde(default)]
+    pub started_at: Option<DateTime<Utc>>,
+    /// 配信開始（ポーリング開始）時に集計開始時刻を更新して合計をリセットするか
+    #[serde(default)]
+    pub reset_on_stream_start: bool,
+    /// 新規メンバー・メンバーシップギフトも合計に加算するか
+    #[serde(default)]
+    pub count_memberships: bool,
+    /// メンバーシップ1件あたりの金額（円、ギフトは件数分を加算）
+    #[serde(default = "default_membership_value_jpy")]
+    pub membership_value_jpy: u64,
+}
+
+impl Default for SuperchatGoal {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_jpy: 50_000,
+            label: String::new(),
+            started_at: None,
+            reset_on_stream_start: false,
+            count_memberships: false,
+            membership_value_jpy: default_membership_value_jpy(),
+        }
+    }
+}
+
+impl SuperchatGoal {
+    /// 設定を検証し、ラベルの前後の空白を除去・集計開始時刻を補完
+    ///
+    /// ## 入力検証
+    /// - 目標金額: 1〜100,000,000円
+    /// - ラベル: 50文字以内
+    /// - メンバーシップ1件あたりの金額: 100,000円以下
+    pub fn normalize(mut self) -> Result<Self, String> {
+        if !(1..=MAX_GOAL_TARGET_JPY).contains(&self.target_jpy) {
+            return Err(format!(
+                "目標金額は1〜{}円で指定してください: {}",
+                MAX_GOAL_TARGET_JPY, self.target_jpy
+            ));
+        }
+        self.label = self.label.trim().to_string();
+        if self.label.chars().count() > MAX_GOAL_LABEL_CHARS {
+            return Err(format!("ラベルは{}文字以内で指定してください", MAX_GOAL_LABEL_CHARS));
+        }
+        if self.membership_value_jpy > MAX_MEMBERSHIP_VALUE_JPY {
+            return Err(format!(
+                "メンバーシップ1件あたりの金額は{}円以下で指定してください: {}",
+                MAX_MEMBERSHIP_VALUE_JPY, self.membership_value_jpy
+            ));
+        }
+        self.started_at.get_or_insert_with(Utc::now);
+        Ok(self)
+    }
+
+    fn payload(&self, current_jpy: u64) -> GoalPayload {
+        GoalPayload {
+            current_jpy,
+            target_jpy: if self.enabled { self.target_jpy } else { 0 },
+            label: self.label.clone(),
+        }
+    }
+
+    /// コメントが合計に加算する金額（対象外の場合はNone）
+    fn contribution(&self, message: &ChatMessage) -> Option<u64> {
+        if self.started_at.is_some_and(|started_at| message.published_at < started_at) {
+            return None;
+        }
+        if let Some(payload) = create_superchat_payload(message) {
+            return Some(convert_to_jpy(payload.amount_micros, &payload.currency));
+        }
+        if !self.count_memberships {
+            return None;
+        }
+        let count = membership_count(&message.message_type);
+        (count > 0).then(|| count.saturating_mul(self.membership_value_jpy))
+    }
+}
+
+/// 目標に加算するメンバーシップの件数（新規メンバーは1件、ギフトは件数分、それ以外は0）
+fn membership_count(message_type: &MessageType) -> u64 {
+    match message_type {
+        MessageType::Membership { level } if membership_kind(level) == MembershipKind::Join => 1,
+        MessageType::MembershipGift { count } => u64::from(*count),
+        _ => 0,
+    }
+}
+
+/// 目標金額の設定と現在の合計
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuperchatGoalStatus {
+    pub goal: SuperchatGoal,
+    /// 集計開始以降の合計（日本円換算）
+    pub current_jpy: u64,
+}
+
+/// 目標金額の集計状態
+#[derive(Debug, Default)]
+struct GoalState {
+    goal: SuperchatGoal,
+    current_jpy: u64,
+    /// 加算済みのメッセージID
+    counted_ids: HashSet<String>,
+}
+
+impl GoalState {
+    /// コメントを集計し、合計が変わった場合は配信するペイロードを返す
+    fn record(&mut self, messages: &[ChatMessage]) -> Option<GoalPayload> {
+        if !self.goal.enabled {
+            return None;
+        }
+        let mut changed = false;
+        for message in messages {
+            let Some(amount_jpy) = self.goal.contribution(message) else {
+                continue;
+            };
+            if self.counted_ids.insert(message.id.clone()) {
+                self.current_jpy = self.current_jpy.saturating_add(amount_jpy);
+                changed = true;
+            }
+        }
+        changed.then(|| self.goal.payload(self.current_jpy))
+    }
+
+    fn status(&self) -> SuperchatGoalStatus {
+        SuperchatGoalStatus {
+            goal: self.goal.clone(),
+            current_jpy: self.current_jpy,
+        }
+    }
+}
+
+/// 現在の目標金額の集計状態
+static GOAL: Lazy<Mutex<GoalState>> = Lazy::new(|| Mutex::new(GoalState::default()));
+
+/// 目標金額の設定と現在の合計を取得
+pub fn status() -> SuperchatGoalStatus {
+    GOAL.lock().unwrap_or_else(|e| e.into_inner()).status()
+}
+
+/// 保存済みの目標金額の設定をDBから読み込み（未保存・不正な値の場合はデフォルト）
+pub async fn load_goal(pool: &SqlitePool) -> Result<SuperchatGoal, String> {
+    let result: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(SUPERCHAT_GOAL_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    let Some((json,)) = result else {
+        return Ok(SuperchatGoal::default());
+    };
+    match serde_json::from_str::<SuperchatGoal>(&json)
+        .map_err(|e| e.to_string())
+        .and_then(SuperchatGoal::normalize)
+    {
+        Ok(goal) => Ok(goal),
+        Err(e) => {
+            log::warn!("Stored superchat goal is invalid, falling back to default: {}", e);
+            Ok(SuperchatGoal::default())
+        }
+    }
+}
+
+/// 目標金額の設定を保存
+pub async fn save_goal(pool: &SqlitePool, goal: &SuperchatGoal) -> Result<(), String> {
+    let json = serde_json::to_string(goal).map_err(|e| format!("JSON serialize error: {}", e))?;
+    let now = Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(SUPERCHAT_GOAL_KEY)
+    .bind(&json)
+    .bind(&now)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+    Ok(())
+}
+
+/// 集計開始時刻以降の記録から合計を集計
+///
+/// スパチャはsuperchat_logの円換算額、メンバーシップは配信から除外していないcomment_logsから数える
+async fn total_since(pool: &SqlitePool, goal: &SuperchatGoal) -> Result<u64, String> {
+    let Some(started_at) = goal.started_at else {
+        return Ok(0);
+    };
+    let started_at = started_at.to_rfc3339();
+
+    // 配信中の集計（SuperchatGoal::contribution）と同じくメッセージの投稿時刻で絞り込む
+    let (superchat_jpy,): (i64,) = sqlx::query_as(
+        "SELECT COALESCE(SUM(amount_jpy), 0) FROM superchat_log WHERE published_at >= ?",
+    )
+    .bind(&started_at)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+    let mut total = u64::try_from(superchat_jpy).unwrap_or(0);
+
+    if goal.count_memberships {
+        let rows: Vec<(Option<String>,)> = sqlx::query_as(
+            r#"
+            SELECT message_data FROM comment_logs
+            WHERE published_at >= ?
+              AND message_type IN ('membership', 'membershipGift')
+              AND is_filtered = 0
+            "#,
+        )
+        .bind(&started_at)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+        let count: u64 = rows
+            .iter()
+            .filter_map(|(data,)| serde_json::from_str::<MessageType>(data.as_deref()?).ok())
+            .map(|message_type| membership_count(&message_type))
+            .sum();
+        total = total.saturating_add(count.saturating_mul(goal.membership_value_jpy));
+    }
+
+    Ok(total)
+}
+
+/// 目標金額の設定を反映し、集計開始時刻以降の記録から合計を集計し直して`goal:update`を配信
+///
+/// 起動時と設定の変更時に呼び出す（保存は呼び出し側で行う）
+pub async fn apply_goal(
+    pool: &SqlitePool,
+    ws_state: &Arc<RwLock<WebSocketState>>,
+    goal: SuperchatGoal,
+) -> Result<SuperchatGoalStatus, String> {
+    let current_jpy = total_since(pool, &goal).await?;
+    let status = {
+        let mut state = GOAL.lock().unwrap_or_else(|e| e.into_inner());
+        *state = GoalState {
+            goal,
+            current_jpy,
+            counted_ids: HashSet::new(),
+        };
+        state.status()
+    };
+
+    ws_state
+        .read()
+        .await
+        .broadcast(WsMessage::GoalUpdate {
+            payload: status.goal.payload(status.current_jpy),
+        })
+        .await;
+    Ok(status)
+}
+
+/// 配信開始（ポーリング開始）時の処理
+///
+/// 配信開始時にリセットする設定の場合は、集計開始時刻を現在時刻にして合計を0に戻し、保存・配信する
+pub async fn start_stream(pool: &SqlitePool, ws_state: &Arc<RwLock<WebSocketState>>) {
+    let goal = {
+        let mut state = GOAL.lock().unwrap_or_else(|e| e.into_inner());
+        if !(state.goal.enabled && state.goal.reset_on_stream_start) {
+            return;
+        }
+        state.goal.started_at = Some(Utc::now());
+        state.current_jpy = 0;
+        state.counted_ids.clear();
+        state.goal.clone()
+    };
+    log::info!("Superchat goal reset at stream start");
+
+    if let Err(e) = save_goal(pool, &goal).await {
+        log::warn!("Failed to save superchat goal: {}", e);
+    }
+    ws_state
+        .read()
+        .await
+        .broadcast(WsMessage::GoalUpdate {
+            payload: goal.payload(0),
+        })
+        .await;
+}
+
+/// コメントのうち対象のスパチャ・メンバーシップを合計に加算し、変わった場合は`goal:update`を配信
+///
+/// ブロードキャストするコメント（フィルタ済み）を渡す
+pub async fn record_contributions(ws_state: &Arc<RwLock<WebSocketState>>, messages: &[ChatMessage]) {
+    let payload = GOAL.lock().unwrap_or_else(|e| e.into_inner()).record(messages);
+    if let Some(payload) = payload {
+        ws_state
+            .read()
+            .await
+            .broadcast(WsMessage::GoalUpdate { payload })
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::create_pool;
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    fn message(id: &str, message_type: MessageType, published_at: DateTime<Utc>) -> ChatMessage {
+        ChatMessage::test_message(id)
+            .with_text("")
+            .with_type(message_type)
+            .with_published_at(published_at)
+    }
+
+    fn superchat(amount_micros: u64, currency: &str) -> MessageType {
+        MessageType::SuperChat {
+            amount: String::new(),
+            currency: currency.to_string(),
+            amount_micros: Some(amount_micros),
+        }
+    }
+
+    fn enabled_goal(started_at: DateTime<Utc>) -> SuperchatGoal {
+        SuperchatGoal {
+            enabled: true,
+            target_jpy: 10_000,
+            label: "目標".to_string(),
+            started_at: Some(started_at),
+            ..SuperchatGoal::default()
+        }
+    }
+
+    #[test]
+    fn test_normalize_validates_goal() {
+        let goal = SuperchatGoal {
+            label: "  目標  ".to_string(),
+            ..SuperchatGoal::default()
+        }
+        .normalize()
+        .unwrap();
+        assert_eq!(goal.label, "目標");
+        assert!(goal.started_at.is_some());
+
+        let invalid = [
+            SuperchatGoal { target_jpy: 0, ..SuperchatGoal::default() },
+            SuperchatGoal { target_jpy: MAX_GOAL_TARGET_JPY + 1, ..SuperchatGoal::default() },
+            SuperchatGoal { label: "あ".repeat(MAX_GOAL_LABEL_CHARS + 1), ..SuperchatGoal::default() },
+            SuperchatGoal { membership_value_jpy: MAX_MEMBERSHIP_VALUE_JPY + 1, ..SuperchatGoal::default() },
+        ];
+        for goal in invalid {
+            assert!(goal.normalize().is_err());
+        }
+    }
+
+    #[test]
+    fn test_record_counts_qualifying_messages_once() {
+        let now = Utc::now();
+        let mut state = GoalState {
+            goal: enabled_goal(now),
+            ..GoalState::default()
+        };
+
+        let messages = vec![
+            message("sc1", superchat(1_000_000_000, "JPY"), now),
+            message("sc2", superchat(5_000_000, "USD"), now),
+            // 集計開始前のスパチャは対象外
+            message("sc3", superchat(1_000_000_000, "JPY"), now - Duration::minutes(1)),
+            message("m1", MessageType::Membership { level: "新規メンバー".to_string() }, now),
+        ];
+        let payload = state.record(&messages).unwrap();
+        assert_eq!(
+            payload,
+            GoalPayload {
+                current_jpy: 1750,
+                target_jpy: 10_000,
+                label: "目標".to_string(),
+            }
+        );
+        // 同じメッセージは加算しない
+        assert_eq!(state.record(&messages[..1]), None);
+
+        // メンバーシップを対象にした場合は新規メンバー・ギフトを加算（メンバー継続は対象外）
+        state.goal.count_memberships = true;
+        state.goal.membership_value_jpy = 500;
+        let memberships = vec![
+            message("m2", MessageType::Membership { level: "新規メンバー".to_string() }, now),
+            message("m3", MessageType::Membership { level: "メンバー歴 6 か月".to_string() }, now),
+            message("g1", MessageType::MembershipGift { count: 5 }, now),
+        ];
+        assert_eq!(state.record(&memberships).unwrap().current_jpy, 1750 + 500 + 2500);
+
+        // 無効な目標は集計しない
+        state.goal.enabled = false;
+        assert_eq!(state.record(&[message("sc4", superchat(1_000_000_000, "JPY"), now)]), None);
+    }
+
+    #[tokio::test]
+    async fn test_total_since_uses_logs_after_start() {
+        let dir = TempDir::new().unwrap();
+        let pool = create_pool(dir.path().join("app.db").to_str().unwrap()).await.unwrap();
+        let now = Utc::now();
+
+        for (id, amount_jpy, published_at) in [
+            ("sc1", 1000, now - Duration::hours(2)),
+            ("sc2", 3000, now),
+            ("sc3", 2000, now + Duration::minutes(1)),
+        ] {
+            sqlx::query(
+                r#"INSERT INTO superchat_log
+                (session_id, message_id, author_channel_id, author_name, amount_micros, currency, amount_jpy, published_at)
+                VALUES (1, ?, 'UC_a', 'Alice', 0, 'JPY', ?, ?)"#,
+            )
+            .bind(id)
+            .bind(amount_jpy)
+            .bind(published_at.to_rfc3339())
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        crate::youtube::db::save_comments_to_db(
+            &pool,
+            &[
+                message("m1", MessageType::Membership { level: "新規メンバー".to_string() }, now),
+                message("g1", MessageType::MembershipGift { count: 3 }, now),
+                message("g0", MessageType::MembershipGift { count: 10 }, now - Duration::hours(2)),
+            ],
+        )
+        .await;
+
+        let mut goal = enabled_goal(now - Duration::hours(1));
+        assert_eq!(total_since(&pool, &goal).await.unwrap(), 5000);
+        goal.count_memberships = true;
+        goal.membership_value_jpy = 100;
+        assert_eq!(total_since(&pool, &goal).await.unwrap(), 5000 + 400);
+    }
+}
@@ -0,0 +1,213 @@
+//! 配信セッションのスパチャ支援者ランキング
+//!
+//! ポーリング開始時にlive_sessionsに記録した配信セッション（[`crate::youtube::live_sessions`]）ごとに、
+//! セッション中のスパチャ合計額（日本円換算）を投稿者（チャンネルID）ごとにメモリ上で集計する。
+//! 受信したスパチャはセッションIDとともにsuperchat_logテーブルにも記録し、
+//! 過去のセッションのランキングはDBから集計する。
+//! 合計額が変わった場合は`leaderboard:update`で上位をオーバーレイへ配信する。
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tokio::sync::RwLock;
+
+use super::{convert_to_jpy, create_superchat_payload};
+use crate::db::superchat_log::record_superchat;
+use crate::server::types::{LeaderboardEntry, LeaderboardPayload, SuperchatPayload, WsMessage};
+use crate::server::websocket::WebSocketState;
+use crate::youtube::types::ChatMessage;
+
+/// `leaderboard:update`で配信する件数（ランキング取得時の件数のデフォルト）
+pub const LEADERBOARD_BROADCAST_SIZE: u32 = 10;
+
+/// ランキング取得時に指定できる最大件数
+pub const MAX_LEADERBOARD_LIMIT: u32 = 100;
+
+/// 投稿者ごとの集計
+#[derive(Debug)]
+struct Supporter {
+    author_name: String,
+    total_jpy: u64,
+    count: u32,
+    /// 最初にスパチャした順番（同額時の順位付けに使用）
+    first_seen: u64,
+}
+
+/// 配信セッションの集計状態
+#[derive(Debug, Default)]
+struct LeaderboardSession {
+    /// live_sessionsのID（セッションの記録に失敗した場合はNone）
+    id: Option<i64>,
+    supporters: HashMap<String, Supporter>,
+    /// 集計済みのスパチャのメッセージID
+    counted_ids: HashSet<String>,
+}
+
+impl LeaderboardSession {
+    fn new(id: Option<i64>) -> Self {
+        Self {
+            id,
+            ..Self::default()
+        }
+    }
+
+    /// スパチャ1件を集計（集計済みのメッセージIDの場合はfalse）
+    fn record(&mut self, payload: &SuperchatPayload, amount_jpy: u64) -> bool {
+        if !self.counted_ids.insert(payload.id.clone()) {
+            return false;
+        }
+
+        let first_seen = self.supporters.len() as u64;
+        let supporter = self
+            .supporters
+            .entry(payload.author_channel_id.clone())
+            .or_insert_with(|| Supporter {
+                author_name: payload.author_name.clone(),
+                total_jpy: 0,
+                count: 0,
+                first_seen,
+            });
+        supporter.author_name = payload.author_name.clone();
+        supporter.total_jpy = supporter.total_jpy.saturating_add(amount_jpy);
+        supporter.count += 1;
+        true
+    }
+
+    /// 合計額の多い順に上位`limit`件
+    fn payload(&self, limit: u32) -> LeaderboardPayload {
+        let mut supporters: Vec<_> = self.supporters.iter().collect();
+        supporters.sort_by(|(_, a), (_, b)| b.total_jpy.cmp(&a.total_jpy).then(a.first_seen.cmp(&b.first_seen)));
+        LeaderboardPayload {
+            session_id: self.id,
+            entries: supporters
+                .into_iter()
+                .take(limit as usize)
+                .map(|(channel_id, supporter)| LeaderboardEntry {
+                    author_name: supporter.author_name.clone(),
+                    author_channel_id: channel_id.clone(),
+                    total_jpy: supporter.total_jpy,
+                    count: supporter.count,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// 現在の配信セッション
+static SESSION: Lazy<Mutex<LeaderboardSession>> = Lazy::new(|| Mutex::new(LeaderboardSession::default()));
+
+/// 新しい配信セッションを開始し、集計をリセット（ポーリング開始時）
+///
+/// `session_id`はlive_sessionsに記録したセッションのID
+pub fn reset_session(session_id: Option<i64>) {
+    match SESSION.lock() {
+        Ok(mut session) => {
+            *session = LeaderboardSession::new(session_id);
+            log::debug!("Superchat leaderboard session started: {:?}", session_id);
+        }
+        Err(e) => log::error!("Failed to reset superchat leaderboard: {}", e),
+    }
+}
+
+/// 現在の配信セッションのランキング（上位`limit`件）
+pub fn current_leaderboard(limit: u32) -> LeaderboardPayload {
+    let session = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+    session.payload(limit)
+}
+
+/// コメントのうちスパチャを集計・記録し、合計額が変わった場合は`leaderboard:update`を配信
+///
+/// ブロードキャストするコメント（フィルタ済み）を渡す。DBへの記録に失敗しても集計・配信は行う
+pub async fn record_superchats(pool: &SqlitePool, ws_state: &Arc<RwLock<WebSocketState>>, messages: &[ChatMessage]) {
+    let superchats: Vec<(SuperchatPayload, u64, DateTime<Utc>)> = messages
+        .iter()
+        .filter_map(|message| {
+            let payload = create_superchat_payload(message)?;
+            let amount_jpy = convert_to_jpy(payload.amount_micros, &payload.currency);
+            Some((payload, amount_jpy, message.published_at))
+        })
+        .collect();
+    if superchats.is_empty() {
+        return;
+    }
+
+    let (session_id, recorded, leaderboard) = {
+        let mut session = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+        let recorded: Vec<_> = superchats
+            .into_iter()
+            .filter(|(payload, amount_jpy, _)| session.record(payload, *amount_jpy))
+            .collect();
+        (session.id, recorded, session.payload(LEADERBOARD_BROADCAST_SIZE))
+    };
+    if recorded.is_empty() {
+        return;
+    }
+
+    // セッションを記録できなかった場合は集計・配信のみ行う
+    if let Some(session_id) = session_id {
+        for (payload, amount_jpy, published_at) in &recorded {
+            if let Err(e) = record_superchat(pool, session_id, payload, *amount_jpy, *published_at).await {
+                log::warn!("Failed to record superchat {}: {}", payload.id, e);
+            }
+        }
+    }
+
+    ws_state
+        .read()
+        .await
+        .broadcast(WsMessage::LeaderboardUpdate { payload: leaderboard })
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn superchat(id: &str, channel_id: &str, name: &str) -> SuperchatPayload {
+        SuperchatPayload::test_payload(id, channel_id, name)
+    }
+
+    #[test]
+    fn test_session_totals_by_author() {
+        let mut session = LeaderboardSession::new(Some(1));
+        assert!(session.record(&superchat("sc1", "UC_a", "Alice"), 500));
+        assert!(session.record(&superchat("sc2", "UC_b", "Bob"), 1000));
+        assert!(session.record(&superchat("sc3", "UC_a", "Alice (renamed)"), 500));
+        assert!(session.record(&superchat("sc4", "UC_c", "Carol"), 300));
+        // 同じメッセージIDは集計しない
+        assert!(!session.record(&superchat("sc1", "UC_a", "Alice"), 500));
+
+        let payload = session.payload(2);
+        assert_eq!(payload.session_id, Some(1));
+        assert_eq!(
+            payload.entries,
+            vec![
+                // 同額の場合は先にスパチャした投稿者が上位
+                LeaderboardEntry {
+                    author_name: "Alice (renamed)".to_string(),
+                    author_channel_id: "UC_a".to_string(),
+                    total_jpy: 1000,
+                    count: 2,
+                },
+                LeaderboardEntry {
+                    author_name: "Bob".to_string(),
+                    author_channel_id: "UC_b".to_string(),
+                    total_jpy: 1000,
+                    count: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_new_session_uses_live_session_id() {
+        let mut first = LeaderboardSession::new(Some(1));
+        assert!(first.record(&superchat("sc1", "UC_a", "Alice"), 500));
+        let second = LeaderboardSession::new(Some(2));
+        let payload = second.payload(LEADERBOARD_BROADCAST_SIZE);
+        assert_eq!(payload.session_id, Some(2));
+        assert!(payload.entries.is_empty());
+    }
+}
@@ -1,18 +1,30 @@
 //! スパチャ専用ウィジェット管理モジュール
 //!
-//! コメント欄とは別に、スパチャを専用ウィジェット(left.lowerスロット)で
+//! コメント欄とは別に、スパチャを専用ウィジェット(デフォルトはleft.lowerスロット)で
 //! 目立たせて表示するための管理機能を提供する。
 //!
 //! ## 機能
-//! - 金額からTier(1-7)を判定
-//! - Tierに基づく表示時間の計算
-//! - スパチャキューの管理
+//! - 金額からTier(1-7)を判定（閾値は設定可能、スーパーステッカーも対象）
+//! - Tierに基づく表示時間の計算（Tier別に設定可能）
+//! - 表示先slotの設定
+//! - スパチャキューの管理（1件ずつ表示、高額Tierは優先、同一投稿者の連投は後回し）
 //! - 表示完了時のremoveメッセージ送信
+//! - 配信セッションの支援者ランキング（[`leaderboard`]）
+//! - 目標金額の進捗（[`goal`]）
 
-use crate::server::types::{SuperchatPayload, SuperchatRemovePayload, WsMessage};
+pub mod goal;
+pub mod leaderboard;
+
+use crate::server::types::{
+    default_superchat_slot, SlotId, SuperchatPayload, SuperchatRemovePayload, WsMessage,
+};
 use crate::server::websocket::WebSocketState;
 use crate::youtube::types::{ChatMessage, MessageType};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 /// 通貨別の日本円換算レート
@@ -28,7 +40,40 @@ const EXCHANGE_RATES: &[(&str, f64)] = &[
     ("TWD", 4.7),
 ];
 
-/// Tier判定の閾値（日本円換算）
+/// 通貨別の小数桁数（ISO 4217）
+/// 表示文字列の小数点と桁区切りを判別するために使用
+const CURRENCY_DECIMALS: &[(&str, u32)] = &[
+    ("JPY", 0),
+    ("KRW", 0),
+    ("USD", 2),
+    ("CAD", 2),
+    ("AUD", 2),
+    ("EUR", 2),
+    ("GBP", 2),
+    ("TWD", 2),
+    ("KWD", 3),
+    ("BHD", 3),
+    ("OMR", 3),
+    ("JOD", 3),
+];
+
+/// 通貨別の表示形式（通貨記号、記号を後ろに置くか、桁区切り、小数点）
+/// 各通貨の一般的な表記に合わせる（例: JPY "¥1,000"、EUR "1.234,50 €"）
+const CURRENCY_FORMATS: &[(&str, &str, bool, char, char)] = &[
+    ("JPY", "¥", false, ',', '.'),
+    ("KRW", "₩", false, ',', '.'),
+    ("USD", "$", false, ',', '.'),
+    ("CAD", "CA$", false, ',', '.'),
+    ("AUD", "A$", false, ',', '.'),
+    ("TWD", "NT$", false, ',', '.'),
+    ("GBP", "£", false, ',', '.'),
+    ("EUR", "€", true, '.', ','),
+];
+
+/// 表示形式が未登録の通貨の小数桁数
+const DEFAULT_CURRENCY_DECIMALS: u32 = 2;
+
+/// Tier判定の閾値のデフォルト値（日本円換算）
 /// YouTube公式の金額帯に準拠
 const TIER_THRESHOLDS: &[(u64, u8)] = &[
     (10_000, 7), // ¥10,000+ → Tier 7 (Red)
@@ -40,7 +85,7 @@ const TIER_THRESHOLDS: &[(u64, u8)] = &[
     (0, 1),      // ¥100-199 → Tier 1 (Blue)
 ];
 
-/// Tier別の表示時間（ミリ秒）
+/// Tier別の表示時間のデフォルト値（ミリ秒）
 /// 高額スパチャほど長く表示
 const TIER_DISPLAY_DURATIONS: &[(u8, u64)] = &[
     (7, 300_000), // Tier 7: 5分
@@ -52,6 +97,285 @@ const TIER_DISPLAY_DURATIONS: &[(u8, u64)] = &[
     (1, 10_000),  // Tier 1: 10秒
 ];
 
+/// 表示時間の最小値（ミリ秒）: 1秒
+pub const MIN_DISPLAY_DURATION_MS: u64 = 1_000;
+
+/// 表示時間の最大値（ミリ秒）: 30分
+pub const MAX_DISPLAY_DURATION_MS: u64 = 30 * 60 * 1_000;
+
+/// Tier数
+const TIER_COUNT: usize = 7;
+
+/// 未知のTierに対する表示時間（ミリ秒）
+const FALLBACK_DISPLAY_DURATION_MS: u64 = 10_000;
+
+/// キューの優先挿入対象となる最小Tier（Tier 6 = ¥5,000以上）
+const PRIORITY_TIER_MIN: u8 = 6;
+
+/// 同一投稿者クールダウンの最大値（10分）
+pub const MAX_AUTHOR_COOLDOWN_MS: u64 = 10 * 60 * 1_000;
+
+/// YouTube公式の金額帯からの乖離を警告する割合（%）
+const THRESHOLD_DIVERGENCE_PERCENT: u64 = 50;
+
+/// スパチャ表示設定
+///
+/// settingsテーブルの`superchat_config`キーにJSONで保存される
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuperchatConfig {
+    /// Tier別の表示時間（ミリ秒）。添字0がTier 1、添字6がTier 7
+    pub tier_durations_ms: [u64; TIER_COUNT],
+    /// 同一投稿者のクールダウン（ミリ秒、0で無効）
+    /// 表示開始からこの時間内に届いた同じ投稿者のスパチャは、他の投稿者の後に表示する
+    #[serde(default)]
+    pub author_cooldown_ms: u64,
+}
+
+impl Default for SuperchatConfig {
+    fn default() -> Self {
+        let mut tier_durations_ms = [FALLBACK_DISPLAY_DURATION_MS; TIER_COUNT];
+        for &(tier, duration) in TIER_DISPLAY_DURATIONS {
+            tier_durations_ms[(tier - 1) as usize] = duration;
+        }
+        Self {
+            tier_durations_ms,
+            author_cooldown_ms: 0,
+        }
+    }
+}
+
+impl SuperchatConfig {
+    /// 各表示時間が1秒〜30分、クールダウンが10分以内かを検証
+    pub fn validate(&self) -> Result<(), String> {
+        if self.author_cooldown_ms > MAX_AUTHOR_COOLDOWN_MS {
+            return Err(format!(
+                "投稿者クールダウンは{}ミリ秒以下で指定してください: {}",
+                MAX_AUTHOR_COOLDOWN_MS, self.author_cooldown_ms
+            ));
+        }
+        for (index, &duration) in self.tier_durations_ms.iter().enumerate() {
+            if !(MIN_DISPLAY_DURATION_MS..=MAX_DISPLAY_DURATION_MS).contains(&duration) {
+                return Err(format!(
+                    "Tier {}の表示時間は{}〜{}ミリ秒の範囲で指定してください: {}",
+                    index + 1,
+                    MIN_DISPLAY_DURATION_MS,
+                    MAX_DISPLAY_DURATION_MS,
+                    duration
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Tierの表示時間を取得（範囲外のTierは10秒）
+    pub fn duration_for_tier(&self, tier: u8) -> u64 {
+        (tier as usize)
+            .checked_sub(1)
+            .and_then(|index| self.tier_durations_ms.get(index))
+            .copied()
+            .unwrap_or(FALLBACK_DISPLAY_DURATION_MS)
+    }
+}
+
+/// 現在のスパチャ表示設定
+/// 起動時にDBから読み込み、設定コマンドで更新される
+static SUPERCHAT_CONFIG: Lazy<std::sync::RwLock<SuperchatConfig>> =
+    Lazy::new(|| std::sync::RwLock::new(SuperchatConfig::default()));
+
+/// Tier判定の閾値テーブル: `(日本円換算の下限額, Tier)`を金額の降順で並べたもの
+pub type TierThresholds = Vec<(u64, u8)>;
+
+/// カスタムのTier判定閾値（未設定時はNoneで`TIER_THRESHOLDS`を使用）
+/// 起動時にDBから読み込み、設定コマンドで更新される
+static CUSTOM_TIER_THRESHOLDS: Lazy<std::sync::RwLock<Option<TierThresholds>>> =
+    Lazy::new(|| std::sync::RwLock::new(None));
+
+/// Tier判定の閾値テーブルを検証
+///
+/// - 金額（日本円換算）が厳密に降順であること
+/// - Tier 1〜7をそれぞれ1回ずつ含むこと
+pub fn validate_tier_thresholds(thresholds: &[(u64, u8)]) -> Result<(), String> {
+    if thresholds.len() != TIER_COUNT {
+        return Err(format!(
+            "Tier閾値は{}件指定してください: {}件",
+            TIER_COUNT,
+            thresholds.len()
+        ));
+    }
+
+    if let Some(pair) = thresholds.windows(2).find(|pair| pair[0].0 <= pair[1].0) {
+        return Err(format!(
+            "Tier閾値は金額の降順で指定してください: {} → {}",
+            pair[0].0, pair[1].0
+        ));
+    }
+
+    for tier in 1..=TIER_COUNT as u8 {
+        let count = thresholds.iter().filter(|(_, t)| *t == tier).count();
+        if count != 1 {
+            return Err(format!("Tier {}を1回だけ指定してください: {}回", tier, count));
+        }
+    }
+
+    Ok(())
+}
+
+/// Tier閾値の設定チェックで検出した問題の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigWarningKind {
+    /// Tierの欠落
+    MissingTier,
+    /// 同じTierの重複指定
+    DuplicateTier,
+    /// 上位Tierの下限額が下位Tier以下
+    OutOfOrder,
+    /// YouTube公式の金額帯から大きく乖離
+    Divergent,
+}
+
+/// Tier閾値の設定チェックの警告
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigWarning {
+    /// 問題の種類
+    pub kind: ConfigWarningKind,
+    /// 対象のTier
+    pub tier: u8,
+    /// 利用者向けの説明
+    pub message: String,
+}
+
+/// Tier判定の閾値をYouTube公式の金額帯（`TIER_THRESHOLDS`）と比較してチェック
+///
+/// 保存を拒否する`validate_tier_thresholds`と異なり、編集中の設定に対する注意喚起に使う。
+/// - Tierの欠落・重複
+/// - Tierの順序と下限額の大小が逆転している
+/// - 公式の下限額から`THRESHOLD_DIVERGENCE_PERCENT`%以上ずれている（Tier 1は対象外）
+pub fn check_tier_thresholds(thresholds: &[(u64, u8)]) -> Vec<ConfigWarning> {
+    let mut warnings = Vec::new();
+
+    // Tierごとの下限額（欠落・重複は警告し、以降のチェックでは最初の指定を使う）
+    let mut amounts: Vec<(u8, u64)> = Vec::with_capacity(TIER_COUNT);
+    for tier in 1..=TIER_COUNT as u8 {
+        let matches: Vec<u64> = thresholds
+            .iter()
+            .filter(|(_, t)| *t == tier)
+            .map(|(amount, _)| *amount)
+            .collect();
+        match matches.first() {
+            Some(&amount) => {
+                if matches.len() > 1 {
+                    warnings.push(ConfigWarning {
+                        kind: ConfigWarningKind::DuplicateTier,
+                        tier,
+                        message: format!("Tier {}が{}回指定されています", tier, matches.len()),
+                    });
+                }
+                amounts.push((tier, amount));
+            }
+            None => warnings.push(ConfigWarning {
+                kind: ConfigWarningKind::MissingTier,
+                tier,
+                message: format!("Tier {}が指定されていません", tier),
+            }),
+        }
+    }
+
+    for pair in amounts.windows(2) {
+        let (lower_tier, lower_amount) = pair[0];
+        let (tier, amount) = pair[1];
+        if amount <= lower_amount {
+            warnings.push(ConfigWarning {
+                kind: ConfigWarningKind::OutOfOrder,
+                tier,
+                message: format!(
+                    "Tier {}の下限額（¥{}）がTier {}の下限額（¥{}）以下です",
+                    tier, amount, lower_tier, lower_amount
+                ),
+            });
+        }
+    }
+
+    for &(tier, amount) in &amounts {
+        let Some(&(canonical, _)) = TIER_THRESHOLDS.iter().find(|(_, t)| *t == tier) else {
+            continue;
+        };
+        if canonical == 0 {
+            continue;
+        }
+        // 極端な入力でもオーバーフローしないようu128で比較
+        let diff_percent = u128::from(amount.abs_diff(canonical)) * 100;
+        if diff_percent >= u128::from(canonical) * u128::from(THRESHOLD_DIVERGENCE_PERCENT) {
+            warnings.push(ConfigWarning {
+                kind: ConfigWarningKind::Divergent,
+                tier,
+                message: format!(
+                    "Tier {}の下限額（¥{}）がYouTube公式の金額帯（¥{}）から大きく離れています",
+                    tier, amount, canonical
+                ),
+            });
+        }
+    }
+
+    warnings
+}
+
+/// 現在有効なTier判定の閾値を取得（未設定時はデフォルト値）
+pub fn get_tier_thresholds() -> TierThresholds {
+    CUSTOM_TIER_THRESHOLDS
+        .read()
+        .ok()
+        .and_then(|thresholds| thresholds.clone())
+        .unwrap_or_else(|| TIER_THRESHOLDS.to_vec())
+}
+
+/// カスタムのTier判定閾値を設定（Noneでデフォルト値に戻す）
+pub fn set_tier_thresholds(thresholds: Option<TierThresholds>) {
+    match CUSTOM_TIER_THRESHOLDS.write() {
+        Ok(mut current) => *current = thresholds,
+        Err(e) => log::error!("Failed to update tier thresholds: {}", e),
+    }
+}
+
+/// 現在のスパチャ表示設定を取得
+pub fn get_config() -> SuperchatConfig {
+    SUPERCHAT_CONFIG
+        .read()
+        .map(|config| config.clone())
+        .unwrap_or_default()
+}
+
+/// スパチャ表示設定を更新（以降に受信したスパチャから適用）
+pub fn set_config(config: SuperchatConfig) {
+    match SUPERCHAT_CONFIG.write() {
+        Ok(mut current) => *current = config,
+        Err(e) => log::error!("Failed to update superchat config: {}", e),
+    }
+}
+
+/// スパチャウィジェットの表示先slot
+/// 起動時にDBから読み込み、設定コマンドで更新される
+static SUPERCHAT_SLOT: Lazy<std::sync::RwLock<SlotId>> =
+    Lazy::new(|| std::sync::RwLock::new(default_superchat_slot()));
+
+/// 現在のスパチャウィジェット表示先slotを取得
+pub fn get_slot() -> SlotId {
+    SUPERCHAT_SLOT
+        .read()
+        .map(|slot| *slot)
+        .unwrap_or_else(|_| default_superchat_slot())
+}
+
+/// スパチャウィジェットの表示先slotを更新（以降に受信したスパチャから適用）
+pub fn set_slot(slot: SlotId) {
+    match SUPERCHAT_SLOT.write() {
+        Ok(mut current) => *current = slot,
+        Err(e) => log::error!("Failed to update superchat slot: {}", e),
+    }
+}
+
 /// 金額をマイクロ単位から通常単位に変換
 fn micros_to_amount(micros: u64) -> f64 {
     micros as f64 / 1_000_000.0
@@ -80,8 +404,21 @@ pub fn convert_to_jpy(amount_micros: u64, currency: &str) -> u64 {
 }
 
 /// 日本円換算額からTierを判定
+///
+/// カスタム閾値が設定されていればそれを、なければデフォルト値を使用する
 pub fn calculate_tier(jpy_amount: u64) -> u8 {
-    for &(threshold, tier) in TIER_THRESHOLDS {
+    match CUSTOM_TIER_THRESHOLDS.read() {
+        Ok(custom) => match custom.as_deref() {
+            Some(thresholds) => calculate_tier_with(jpy_amount, thresholds),
+            None => calculate_tier_with(jpy_amount, TIER_THRESHOLDS),
+        },
+        Err(_) => calculate_tier_with(jpy_amount, TIER_THRESHOLDS),
+    }
+}
+
+/// 指定した閾値テーブル（金額の降順）で日本円換算額からTierを判定
+fn calculate_tier_with(jpy_amount: u64, thresholds: &[(u64, u8)]) -> u8 {
+    for &(threshold, tier) in thresholds {
         if jpy_amount >= threshold {
             return tier;
         }
@@ -90,41 +427,65 @@ pub fn calculate_tier(jpy_amount: u64) -> u8 {
 }
 
 /// TierからWebSocketメッセージの表示時間を取得
+///
+/// 現在のスパチャ表示設定を参照する（未設定時はデフォルト値）
 pub fn get_display_duration(tier: u8) -> u64 {
-    TIER_DISPLAY_DURATIONS
-        .iter()
-        .find(|(t, _)| *t == tier)
-        .map(|(_, duration)| *duration)
-        .unwrap_or(10_000) // デフォルト10秒
+    SUPERCHAT_CONFIG
+        .read()
+        .map(|config| config.duration_for_tier(tier))
+        .unwrap_or(FALLBACK_DISPLAY_DURATION_MS)
 }
 
 /// ChatMessageからSuperchatPayloadを生成
-/// スパチャでない場合はNoneを返す
+/// スパチャ・金額付きスーパーステッカーでない場合はNoneを返す
 pub fn create_superchat_payload(message: &ChatMessage) -> Option<SuperchatPayload> {
-    match &message.message_type {
-        MessageType::SuperChat { amount, currency } => {
-            // 金額文字列からマイクロ単位を推定
-            // NOTE: YouTube APIからはamount_microsが取得できるが、
-            // ChatMessage型には含まれていないため、表示文字列からパース
-            let amount_micros = parse_amount_micros(amount);
-            let jpy_amount = convert_to_jpy(amount_micros, currency);
-            let tier = calculate_tier(jpy_amount);
-            let display_duration_ms = get_display_duration(tier);
-
-            Some(SuperchatPayload {
-                id: message.id.clone(),
-                author_name: message.author_name.clone(),
-                author_image_url: message.author_image_url.clone(),
-                amount: amount.clone(),
-                amount_micros,
-                currency: currency.clone(),
-                message: message.message.clone(),
-                tier,
-                display_duration_ms,
-            })
+    build_superchat_payload(message, get_slot())
+}
+
+/// 配置slotを指定してSuperchatPayloadを生成
+fn build_superchat_payload(message: &ChatMessage, slot: SlotId) -> Option<SuperchatPayload> {
+    let (amount, currency, amount_micros, sticker_image_url) = match &message.message_type {
+        MessageType::SuperChat {
+            amount,
+            currency,
+            amount_micros,
+        } => (amount, currency, *amount_micros, None),
+        // 金額対応前に保存されたステッカー（金額なし）は対象外
+        MessageType::SuperSticker {
+            amount,
+            currency,
+            amount_micros,
+            image_url,
+            ..
+        } if !amount.is_empty() || amount_micros.is_some() => {
+            (amount, currency, *amount_micros, image_url.clone())
         }
-        _ => None,
-    }
+        _ => return None,
+    };
+
+    // 公式APIのamountMicrosがあればそのまま使用し、
+    // ない場合（InnerTube経由）のみ金額文字列からマイクロ単位を推定
+    let amount_micros =
+        amount_micros.unwrap_or_else(|| parse_amount_micros_for_currency(amount, currency));
+    let jpy_amount = convert_to_jpy(amount_micros, currency);
+    let tier = calculate_tier(jpy_amount);
+    let display_duration_ms = get_display_duration(tier);
+
+    Some(SuperchatPayload {
+        id: message.id.clone(),
+        author_name: message.author_name.clone(),
+        author_channel_id: message.author_channel_id.clone(),
+        author_image_url: message.author_image_url.clone(),
+        amount: amount.clone(),
+        amount_display: format_amount(amount_micros, currency),
+        amount_micros,
+        currency: currency.clone(),
+        message: message.message.clone(),
+        tier,
+        display_duration_ms,
+        sticker_image_url,
+        slot,
+    })
 }
 
 /// 金額表示文字列からマイクロ単位の金額を推定
@@ -167,6 +528,335 @@ fn parse_amount_micros(amount_str: &str) -> u64 {
     (amount * 1_000_000.0) as u64
 }
 
+/// 通貨コードから小数桁数を取得（未登録の通貨はNone）
+fn get_currency_decimals(currency: &str) -> Option<u32> {
+    CURRENCY_DECIMALS
+        .iter()
+        .find(|(c, _)| *c == currency)
+        .map(|(_, decimals)| *decimals)
+}
+
+/// 金額（マイクロ単位）を通貨ごとの表記で整形
+/// 例: (1_000_000_000, "JPY") → "¥1,000"、(1_234_500_000, "EUR") → "1.234,50 €"
+///
+/// - 小数桁数は通貨ごと（ISO 4217）に揃え、端数は四捨五入する
+/// - 表示形式が未登録の通貨は "1,234.56 INR" のように通貨コードを後ろに付ける
+pub fn format_amount(amount_micros: u64, currency: &str) -> String {
+    let decimals = get_currency_decimals(currency).unwrap_or(DEFAULT_CURRENCY_DECIMALS);
+    let format = CURRENCY_FORMATS.iter().find(|(c, ..)| *c == currency);
+    let (group_sep, decimal_sep) = format.map(|&(_, _, _, g, d)| (g, d)).unwrap_or((',', '.'));
+
+    // 浮動小数点の丸め誤差を避けるため整数演算で最小単位に変換
+    let decimals = decimals.min(6);
+    let unit = 10u64.pow(6 - decimals);
+    let minor = amount_micros.saturating_add(unit / 2) / unit;
+    let scale = 10u64.pow(decimals);
+    let whole = (minor / scale).to_string();
+
+    let mut number = String::new();
+    for (i, c) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            number.push(group_sep);
+        }
+        number.push(c);
+    }
+    if decimals > 0 {
+        number.push(decimal_sep);
+        number.push_str(&format!("{:0width$}", minor % scale, width = decimals as usize));
+    }
+
+    match format {
+        Some(&(_, symbol, true, _, _)) => format!("{} {}", number, symbol),
+        Some(&(_, symbol, false, _, _)) => format!("{}{}", symbol, number),
+        None => format!("{} {}", number, currency),
+    }
+}
+
+/// 通貨コードを考慮して金額表示文字列からマイクロ単位の金額を算出
+/// 例: ("KWD 1.500", "KWD") → 1_500_000
+///
+/// ## 判定ルール
+/// - 最後の区切り文字（`.`/`,`）の後ろが1〜小数桁数の数字なら小数点、それ以外は桁区切り
+/// - 区切り文字がカンマのみで最後の区切りの後ろがちょうど3桁なら桁区切り
+///   （例: "KWD 1,000"は1000KWD。小数3桁の通貨でも英語形式の表示を優先する）
+/// - 小数桁数0の通貨（JPY等）は区切り文字をすべて桁区切りとして扱う
+/// - 未登録の通貨は`parse_amount_micros`のヒューリスティックにフォールバック
+pub(crate) fn parse_amount_micros_for_currency(amount_str: &str, currency: &str) -> u64 {
+    let Some(decimals) = get_currency_decimals(currency) else {
+        return parse_amount_micros(amount_str);
+    };
+
+    let digits: String = amount_str
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
+        .collect();
+
+    let (integer_part, fraction_part) = match digits.rfind(['.', ',']) {
+        Some(pos) if decimals > 0 => {
+            let after = &digits[pos + 1..];
+            let comma_grouped = after.len() == 3 && !digits.contains('.');
+            if !after.is_empty() && after.len() <= decimals as usize && !comma_grouped {
+                (&digits[..pos], after)
+            } else {
+                (digits.as_str(), "")
+            }
+        }
+        _ => (digits.as_str(), ""),
+    };
+
+    let integer: String = integer_part.chars().filter(|c| c.is_ascii_digit()).collect();
+    if integer.is_empty() && fraction_part.is_empty() {
+        return 0;
+    }
+
+    // 浮動小数点の丸め誤差を避けるため整数演算でマイクロ単位に変換
+    let whole = if integer.is_empty() {
+        0
+    } else {
+        match integer.parse::<u64>() {
+            Ok(value) => value,
+            Err(_) => {
+                log::warn!(
+                    "Failed to parse superchat amount: '{}' (currency: {})",
+                    amount_str,
+                    currency
+                );
+                return 0;
+            }
+        }
+    };
+    let fraction = format!("{:0<6}", fraction_part).parse::<u64>().unwrap_or(0);
+
+    whole.saturating_mul(1_000_000).saturating_add(fraction)
+}
+
+/// スパチャ表示キュー
+///
+/// 連投時にウィジェット上でスパチャが重ならないよう、表示を1件ずつに直列化する。
+/// 表示中のスパチャの表示時間が経過するか、removeがブロードキャストされると次を表示する。
+///
+/// 表示ごとに世代番号を振り、削除タイマーはIDと世代の両方が一致する場合のみ表示を終了する
+/// （slot変更による再ブロードキャストや、同じIDの再表示で古いタイマーが発火しないように）。
+#[derive(Debug, Clone, Default)]
+pub struct SuperchatQueue {
+    inner: Arc<std::sync::Mutex<SuperchatQueueState>>,
+}
+
+#[derive(Debug, Default)]
+struct SuperchatQueueState {
+    /// 表示待ちのスパチャ
+    pending: VecDeque<SuperchatPayload>,
+    /// 表示中のスパチャ
+    current: Option<CurrentSuperchat>,
+    /// 最後に振った表示世代
+    generation: u64,
+    /// 投稿者チャンネルIDごとの最終表示開始時刻（クールダウン判定用）
+    last_shown: HashMap<String, Instant>,
+}
+
+/// 表示中のスパチャ
+#[derive(Debug)]
+struct CurrentSuperchat {
+    payload: SuperchatPayload,
+    /// 表示開始時刻
+    shown_at: Instant,
+    /// 表示世代（削除タイマーの照合に使用）
+    generation: u64,
+}
+
+impl SuperchatQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 表示待ちに追加
+    ///
+    /// 高額Tier（`PRIORITY_TIER_MIN`以上）は、自身より低いTierの待ちより前に割り込む。
+    /// 同じTier同士は到着順を維持する。
+    pub fn push(&self, payload: SuperchatPayload) {
+        let mut state = match self.inner.lock() {
+            Ok(state) => state,
+            Err(e) => {
+                log::error!("Superchat queue lock poisoned: {}", e);
+                return;
+            }
+        };
+
+        let position = if payload.tier >= PRIORITY_TIER_MIN {
+            state
+                .pending
+                .iter()
+                .position(|queued| queued.tier < payload.tier)
+                .unwrap_or(state.pending.len())
+        } else {
+            state.pending.len()
+        };
+        state.pending.insert(position, payload);
+    }
+
+    /// 表示中のスパチャがなければ、次のスパチャを取り出して表示中にする
+    ///
+    /// 投稿者クールダウンは現在のスパチャ表示設定から取得する。
+    /// 戻り値の世代番号は削除タイマー（[`Self::finish_display`]）に渡す
+    pub fn start_next(&self) -> Option<(SuperchatPayload, u64)> {
+        let cooldown = Duration::from_millis(get_config().author_cooldown_ms);
+        self.start_next_at(Instant::now(), cooldown)
+    }
+
+    /// 指定時刻・クールダウンで次のスパチャを取り出す
+    ///
+    /// クールダウン中の投稿者のスパチャは、クールダウン外の投稿者のスパチャがあれば後回しにする。
+    /// 待ちがすべてクールダウン中の投稿者なら先頭を表示する（ウィジェットを空けない）。
+    fn start_next_at(&self, now: Instant, cooldown: Duration) -> Option<(SuperchatPayload, u64)> {
+        let mut state = self.inner.lock().ok()?;
+        if state.current.is_some() || state.pending.is_empty() {
+            return None;
+        }
+
+        state
+            .last_shown
+            .retain(|_, shown_at| now.saturating_duration_since(*shown_at) < cooldown);
+
+        let index = state
+            .pending
+            .iter()
+            .position(|queued| {
+                queued.author_channel_id.is_empty()
+                    || !state.last_shown.contains_key(&queued.author_channel_id)
+            })
+            .unwrap_or(0);
+        let payload = state.pending.remove(index)?;
+
+        if !cooldown.is_zero() && !payload.author_channel_id.is_empty() {
+            state.last_shown.insert(payload.author_channel_id.clone(), now);
+        }
+        state.generation += 1;
+        let generation = state.generation;
+        state.current = Some(CurrentSuperchat {
+            payload: payload.clone(),
+            shown_at: now,
+            generation,
+        });
+        Some((payload, generation))
+    }
+
+    /// 指定IDのスパチャが表示中かどうか（テスト用）
+    #[cfg(test)]
+    pub fn is_current(&self, id: &str) -> bool {
+        self.inner
+            .lock()
+            .map(|state| state.current.as_ref().is_some_and(|current| current.payload.id == id))
+            .unwrap_or(false)
+    }
+
+    /// 指定IDのスパチャの表示を終了（表示世代を問わない、テスト用）
+    /// 表示中のスパチャだった場合はtrueを返す
+    #[cfg(test)]
+    pub fn finish(&self, id: &str) -> bool {
+        match self.inner.lock() {
+            Ok(mut state) if state.current.as_ref().is_some_and(|current| current.payload.id == id) => {
+                state.current = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 削除タイマーから表示を終了（IDと表示世代が一致する場合のみ）
+    ///
+    /// 同じ表示に対しては1回だけtrueを返す
+    pub fn finish_display(&self, id: &str, generation: u64) -> bool {
+        match self.inner.lock() {
+            Ok(mut state)
+                if state.current.as_ref().is_some_and(|current| {
+                    current.payload.id == id && current.generation == generation
+                }) =>
+            {
+                state.current = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 表示中のスパチャを、表示時間を残り時間に置き換えて取得
+    ///
+    /// 表示時間を過ぎている場合（削除タイマーの発火直前）はNone
+    fn current_remaining_at(&self, now: Instant) -> Option<SuperchatPayload> {
+        let state = self.inner.lock().ok()?;
+        let current = state.current.as_ref()?;
+        let elapsed = now.saturating_duration_since(current.shown_at).as_millis() as u64;
+        let remaining = current.payload.display_duration_ms.checked_sub(elapsed)?;
+        (remaining > 0).then(|| SuperchatPayload {
+            display_duration_ms: remaining,
+            ..current.payload.clone()
+        })
+    }
+
+    /// 表示待ちの件数（表示中のスパチャは含まない）
+    pub fn len(&self) -> usize {
+        self.inner.lock().map(|state| state.pending.len()).unwrap_or(0)
+    }
+}
+
+/// スパチャ表示キュー（全ポーラー・テスト送信で共有）
+static SUPERCHAT_QUEUE: Lazy<SuperchatQueue> = Lazy::new(SuperchatQueue::new);
+
+/// 表示待ちのスパチャ件数を取得
+pub fn queue_length() -> usize {
+    SUPERCHAT_QUEUE.len()
+}
+
+/// スパチャを表示キューに追加
+///
+/// 表示中のスパチャがなければ即座にブロードキャストし、
+/// あれば前のスパチャの表示完了後に順番にブロードキャストする。
+pub async fn enqueue_superchat(ws_state: &Arc<RwLock<WebSocketState>>, payload: SuperchatPayload) {
+    log::debug!(
+        "スパチャをキューに追加: {} (Tier {}, 待ち {}件)",
+        payload.id,
+        payload.tier,
+        SUPERCHAT_QUEUE.len()
+    );
+    SUPERCHAT_QUEUE.push(payload);
+    display_next_superchat(ws_state).await;
+}
+
+/// キューから次のスパチャを取り出して表示
+async fn display_next_superchat(ws_state: &Arc<RwLock<WebSocketState>>) {
+    let Some((payload, generation)) = SUPERCHAT_QUEUE.start_next() else {
+        return;
+    };
+
+    let id = payload.id.clone();
+    let duration_ms = payload.display_duration_ms;
+    broadcast_superchat(ws_state, payload).await;
+    // 表示完了後にremoveメッセージを送信するタイマーをスケジュール
+    schedule_superchat_removal(Arc::clone(ws_state), id, generation, duration_ms);
+}
+
+/// 表示中のスパチャを現在のslot・Tier閾値で再ブロードキャスト
+///
+/// slot・表示時間・Tier閾値の変更時に呼び出す。表示時間は残り時間に置き換え、削除タイマーは
+/// 表示開始時にスケジュールしたもの（IDと表示世代で照合）をそのまま使う
+pub async fn rebroadcast_current_superchat(ws_state: &Arc<RwLock<WebSocketState>>) {
+    rebroadcast_current_from(&SUPERCHAT_QUEUE, ws_state, get_slot()).await;
+}
+
+async fn rebroadcast_current_from(
+    queue: &SuperchatQueue,
+    ws_state: &Arc<RwLock<WebSocketState>>,
+    slot: SlotId,
+) -> bool {
+    let Some(mut payload) = queue.current_remaining_at(Instant::now()) else {
+        return false;
+    };
+    payload.slot = slot;
+    payload.tier = calculate_tier(convert_to_jpy(payload.amount_micros, &payload.currency));
+    broadcast_superchat(ws_state, payload).await;
+    true
+}
+
 /// スパチャをWebSocketでブロードキャスト
 pub async fn broadcast_superchat(
     ws_state: &Arc<RwLock<WebSocketState>>,
@@ -198,18 +888,37 @@ pub async fn broadcast_superchat_remove(ws_state: &Arc<RwLock<WebSocketState>>,
 }
 
 /// スパチャの表示タイマーを開始
-/// 指定時間後にsuperchat:removeメッセージを送信
-pub fn schedule_superchat_removal(
+/// 表示時間経過後にsuperchat:removeメッセージを送信し、キューの次のスパチャを表示する
+///
+/// 表示が既に終了していた場合や、同じIDが別の表示として再表示されている場合は何もしない
+/// （1回の表示につきremoveは1回だけ）
+fn schedule_superchat_removal(
     ws_state: Arc<RwLock<WebSocketState>>,
     id: String,
+    generation: u64,
     duration_ms: u64,
 ) {
     tokio::spawn(async move {
         tokio::time::sleep(tokio::time::Duration::from_millis(duration_ms)).await;
-        broadcast_superchat_remove(&ws_state, id).await;
+        if SUPERCHAT_QUEUE.finish_display(&id, generation) {
+            broadcast_superchat_remove(&ws_state, id).await;
+            display_next_superchat(&ws_state).await;
+        }
     });
 }
 
+/// ブロードキャストしたコメントを支援者ランキング・目標金額の集計に反映
+///
+/// 合計が変わった場合はそれぞれ`leaderboard:update`・`goal:update`を配信する
+pub async fn record_totals(
+    pool: &sqlx::SqlitePool,
+    ws_state: &Arc<RwLock<WebSocketState>>,
+    messages: &[ChatMessage],
+) {
+    leaderboard::record_superchats(pool, ws_state, messages).await;
+    goal::record_contributions(ws_state, messages).await;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -246,6 +955,81 @@ mod tests {
         assert_eq!(parse_amount_micros("   "), 0);
     }
 
+    #[test]
+    fn test_parse_amount_micros_for_currency() {
+        // 小数3桁の通貨（クウェート・ディナール、バーレーン・ディナール）
+        assert_eq!(parse_amount_micros_for_currency("KWD 1.500", "KWD"), 1_500_000);
+        assert_eq!(
+            parse_amount_micros_for_currency("KWD 1,234.567", "KWD"),
+            1_234_567_000
+        );
+        assert_eq!(parse_amount_micros_for_currency("BHD 10.250", "BHD"), 10_250_000);
+        // カンマのみの3桁グループは桁区切り
+        assert_eq!(parse_amount_micros_for_currency("KWD 1,000", "KWD"), 1_000_000_000);
+        assert_eq!(
+            parse_amount_micros_for_currency("KWD 1,000,000", "KWD"),
+            1_000_000_000_000
+        );
+
+        // 小数0桁の通貨（日本円）: 区切りはすべて桁区切り
+        assert_eq!(parse_amount_micros_for_currency("¥1,000", "JPY"), 1_000_000_000);
+        assert_eq!(parse_amount_micros_for_currency("¥500", "JPY"), 500_000_000);
+
+        // 小数2桁の通貨（米ドル）
+        assert_eq!(parse_amount_micros_for_currency("$5.00", "USD"), 5_000_000);
+        assert_eq!(parse_amount_micros_for_currency("$1,000", "USD"), 1_000_000_000);
+        assert_eq!(parse_amount_micros_for_currency("$19.99", "USD"), 19_990_000);
+
+        // 欧州形式（千単位区切りあり）
+        assert_eq!(parse_amount_micros_for_currency("€1.000,50", "EUR"), 1_000_500_000);
+    }
+
+    #[test]
+    fn test_parse_amount_micros_for_currency_fallback() {
+        // 未登録通貨はヒューリスティックにフォールバック
+        assert_eq!(parse_amount_micros_for_currency("R$5,00", "BRL"), 5_000_000);
+        assert_eq!(parse_amount_micros_for_currency("₹1,000", "INR"), 1_000_000_000);
+
+        // 空文字列や記号のみの場合は0
+        assert_eq!(parse_amount_micros_for_currency("", "KWD"), 0);
+        assert_eq!(parse_amount_micros_for_currency("¥", "JPY"), 0);
+    }
+
+    #[test]
+    fn test_format_amount_jpy() {
+        // 小数なし・桁区切りはカンマ
+        assert_eq!(format_amount(1_000_000_000, "JPY"), "¥1,000");
+        assert_eq!(format_amount(100_000_000, "JPY"), "¥100");
+        assert_eq!(format_amount(50_000_000_000, "JPY"), "¥50,000");
+        // 端数は四捨五入
+        assert_eq!(format_amount(1_500_000, "JPY"), "¥2");
+    }
+
+    #[test]
+    fn test_format_amount_usd() {
+        // 小数2桁
+        assert_eq!(format_amount(5_000_000, "USD"), "$5.00");
+        assert_eq!(format_amount(1_234_560_000, "USD"), "$1,234.56");
+        assert_eq!(format_amount(19_990_000, "CAD"), "CA$19.99");
+        // 未登録の通貨は通貨コードを後ろに付ける
+        assert_eq!(format_amount(1_234_500_000, "INR"), "1,234.50 INR");
+    }
+
+    #[test]
+    fn test_format_amount_european_input() {
+        // 欧州形式の表示文字列から正規化した表記を生成
+        let message = superchat_message("1.234,50 €", "EUR", None);
+        let payload = create_superchat_payload(&message).unwrap();
+        assert_eq!(payload.amount, "1.234,50 €");
+        assert_eq!(payload.amount_display, "1.234,50 €");
+
+        let message = superchat_message("€5,00", "EUR", None);
+        let payload = create_superchat_payload(&message).unwrap();
+        assert_eq!(payload.amount, "€5,00");
+        assert_eq!(payload.amount_display, "5,00 €");
+        assert_eq!(serde_json::to_value(&payload).unwrap()["amountDisplay"], "5,00 €");
+    }
+
     #[test]
     fn test_convert_to_jpy() {
         // 日本円はそのまま
@@ -276,10 +1060,485 @@ mod tests {
         assert_eq!(calculate_tier(50000), 7);
     }
 
+    #[test]
+    fn test_superchat_config_default_matches_table() {
+        let config = SuperchatConfig::default();
+        assert_eq!(
+            config.tier_durations_ms,
+            [10_000, 20_000, 30_000, 60_000, 120_000, 180_000, 300_000]
+        );
+        assert_eq!(config.duration_for_tier(7), 300_000);
+        assert_eq!(config.duration_for_tier(0), 10_000);
+        assert_eq!(config.duration_for_tier(8), 10_000);
+    }
+
+    #[test]
+    fn test_superchat_config_custom_durations() {
+        let mut config = SuperchatConfig::default();
+        config.tier_durations_ms[6] = 60_000; // 短い配信向けにTier 7を60秒に
+        assert!(config.validate().is_ok());
+        assert_eq!(config.duration_for_tier(7), 60_000);
+        assert_eq!(config.duration_for_tier(6), 180_000);
+    }
+
+    #[test]
+    fn test_superchat_config_validate_bounds() {
+        let mut config = SuperchatConfig::default();
+        config.tier_durations_ms[0] = MIN_DISPLAY_DURATION_MS;
+        config.tier_durations_ms[6] = MAX_DISPLAY_DURATION_MS;
+        assert!(config.validate().is_ok());
+
+        config.tier_durations_ms[0] = 999;
+        assert!(config.validate().unwrap_err().contains("Tier 1"));
+
+        config.tier_durations_ms[0] = MIN_DISPLAY_DURATION_MS;
+        config.tier_durations_ms[6] = MAX_DISPLAY_DURATION_MS + 1;
+        assert!(config.validate().unwrap_err().contains("Tier 7"));
+    }
+
+    #[test]
+    fn test_superchat_config_serde() {
+        let json = serde_json::to_string(&SuperchatConfig::default()).unwrap();
+        assert!(json.contains("tierDurationsMs"));
+        let parsed: SuperchatConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, SuperchatConfig::default());
+    }
+
+    #[test]
+    fn test_custom_tier_thresholds_reclassify() {
+        // チャリティ配信向けの独自の金額帯
+        let custom: Vec<(u64, u8)> = vec![
+            (50_000, 7),
+            (20_000, 6),
+            (10_000, 5),
+            (5_000, 4),
+            (2_500, 3),
+            (1_000, 2),
+            (0, 1),
+        ];
+        assert!(validate_tier_thresholds(&custom).is_ok());
+
+        // デフォルトでは¥3,000はTier 5、独自の金額帯ではTier 3
+        assert_eq!(calculate_tier_with(3_000, TIER_THRESHOLDS), 5);
+        assert_eq!(calculate_tier_with(3_000, &custom), 3);
+        assert_eq!(calculate_tier_with(50_000, &custom), 7);
+        assert_eq!(calculate_tier_with(999, &custom), 1);
+    }
+
+    #[test]
+    fn test_check_tier_thresholds_default_has_no_warnings() {
+        assert!(check_tier_thresholds(TIER_THRESHOLDS).is_empty());
+    }
+
+    #[test]
+    fn test_check_tier_thresholds_divergent() {
+        // 順序は正しいが、Tier 6・7が公式の金額帯から大きく離れている
+        let divergent = vec![
+            (50_000, 7),
+            (20_000, 6),
+            (2_000, 5),
+            (1_000, 4),
+            (600, 3),
+            (200, 2),
+            (0, 1),
+        ];
+        assert!(validate_tier_thresholds(&divergent).is_ok());
+
+        let warnings = check_tier_thresholds(&divergent);
+        let flagged: Vec<(ConfigWarningKind, u8)> =
+            warnings.iter().map(|w| (w.kind, w.tier)).collect();
+        assert_eq!(
+            flagged,
+            vec![
+                (ConfigWarningKind::Divergent, 6),
+                (ConfigWarningKind::Divergent, 7)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_tier_thresholds_out_of_order() {
+        // Tier 3とTier 4の下限額が逆転している
+        let out_of_order = vec![
+            (10_000, 7),
+            (5_000, 6),
+            (2_000, 5),
+            (500, 4),
+            (1_000, 3),
+            (200, 2),
+            (0, 1),
+        ];
+
+        let warnings = check_tier_thresholds(&out_of_order);
+        assert!(warnings
+            .iter()
+            .any(|w| w.kind == ConfigWarningKind::OutOfOrder && w.tier == 4));
+        assert!(!warnings
+            .iter()
+            .any(|w| w.kind == ConfigWarningKind::OutOfOrder && w.tier != 4));
+
+        // Tierの欠落・重複も検出する
+        let missing = vec![(10_000, 7), (5_000, 7), (0, 1)];
+        let warnings = check_tier_thresholds(&missing);
+        let missing_tiers: Vec<u8> = warnings
+            .iter()
+            .filter(|w| w.kind == ConfigWarningKind::MissingTier)
+            .map(|w| w.tier)
+            .collect();
+        assert_eq!(missing_tiers, vec![2, 3, 4, 5, 6]);
+        assert!(warnings
+            .iter()
+            .any(|w| w.kind == ConfigWarningKind::DuplicateTier && w.tier == 7));
+
+        // 極端に大きい下限額でもオーバーフローせず乖離として検出する
+        let huge = vec![
+            (u64::MAX, 7),
+            (5_000, 6),
+            (2_000, 5),
+            (1_000, 4),
+            (500, 3),
+            (200, 2),
+            (0, 1),
+        ];
+        let warnings = check_tier_thresholds(&huge);
+        assert!(warnings
+            .iter()
+            .any(|w| w.kind == ConfigWarningKind::Divergent && w.tier == 7));
+    }
+
+    #[test]
+    fn test_validate_tier_thresholds() {
+        assert!(validate_tier_thresholds(TIER_THRESHOLDS).is_ok());
+
+        // 降順でない
+        let not_descending = vec![
+            (10_000, 7),
+            (10_000, 6),
+            (2_000, 5),
+            (1_000, 4),
+            (500, 3),
+            (200, 2),
+            (0, 1),
+        ];
+        assert!(validate_tier_thresholds(&not_descending)
+            .unwrap_err()
+            .contains("降順"));
+
+        // Tierの重複（Tier 1が欠落）
+        let duplicate_tier = vec![
+            (10_000, 7),
+            (5_000, 6),
+            (2_000, 5),
+            (1_000, 4),
+            (500, 3),
+            (200, 2),
+            (0, 2),
+        ];
+        assert!(validate_tier_thresholds(&duplicate_tier)
+            .unwrap_err()
+            .contains("Tier 1"));
+
+        // 件数不足
+        assert!(validate_tier_thresholds(&[(0, 1)]).is_err());
+    }
+
     #[test]
     fn test_get_display_duration() {
         assert_eq!(get_display_duration(1), 10_000);
         assert_eq!(get_display_duration(4), 60_000);
         assert_eq!(get_display_duration(7), 300_000);
     }
+
+    fn superchat_message(amount: &str, currency: &str, amount_micros: Option<u64>) -> ChatMessage {
+        ChatMessage::test_message("sc-test")
+            .with_text("テスト")
+            .with_author("Tester", "UC_test")
+            .with_type(MessageType::SuperChat {
+                amount: amount.to_string(),
+                currency: currency.to_string(),
+                amount_micros,
+            })
+    }
+
+    #[test]
+    fn test_superchat_payload_uses_api_amount_micros() {
+        // 表示文字列が解釈できない形式でも公式APIの金額を正確に使用
+        let message = superchat_message("¥ 1 万", "JPY", Some(10_000_000_000));
+        let payload = create_superchat_payload(&message).unwrap();
+        assert_eq!(payload.amount_micros, 10_000_000_000);
+        assert_eq!(payload.tier, 7);
+        assert_eq!(payload.amount, "¥ 1 万");
+    }
+
+    #[test]
+    fn test_superchat_payload_falls_back_to_display_string() {
+        // InnerTube経由（amount_microsなし）は表示文字列からパース
+        let message = superchat_message("$5.00", "USD", None);
+        let payload = create_superchat_payload(&message).unwrap();
+        assert_eq!(payload.amount_micros, 5_000_000);
+    }
+
+    #[test]
+    fn test_superchat_payload_reflects_slot() {
+        let message = superchat_message("¥1,000", "JPY", None);
+
+        let payload = build_superchat_payload(&message, SlotId::RightUpper).unwrap();
+
+        assert_eq!(payload.slot, SlotId::RightUpper);
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["slot"], "right.upper");
+
+        // slot未指定のペイロードはleft.lowerとして扱う
+        let mut legacy = json.clone();
+        legacy.as_object_mut().unwrap().remove("slot");
+        let parsed: SuperchatPayload = serde_json::from_value(legacy).unwrap();
+        assert_eq!(parsed.slot, SlotId::LeftLower);
+    }
+
+    fn queued_payload(id: &str, tier: u8) -> SuperchatPayload {
+        authored_payload(id, tier, id)
+    }
+
+    fn authored_payload(id: &str, tier: u8, author_channel_id: &str) -> SuperchatPayload {
+        SuperchatPayload {
+            amount: "¥1,000".to_string(),
+            amount_display: "¥1,000".to_string(),
+            amount_micros: 1_000_000_000,
+            tier,
+            display_duration_ms: get_display_duration(tier),
+            ..SuperchatPayload::test_payload(id, author_channel_id, "Tester")
+        }
+    }
+
+    fn drain_ids(queue: &SuperchatQueue) -> Vec<String> {
+        let mut ids = Vec::new();
+        while let Some((payload, _)) = queue.start_next() {
+            assert!(queue.finish(&payload.id));
+            ids.push(payload.id);
+        }
+        ids
+    }
+
+    #[test]
+    fn test_superchat_queue_displays_one_at_a_time() {
+        let queue = SuperchatQueue::new();
+        queue.push(queued_payload("a", 2));
+        queue.push(queued_payload("b", 3));
+        assert_eq!(queue.len(), 2);
+
+        let (first, _) = queue.start_next().unwrap();
+        assert_eq!(first.id, "a");
+        assert!(queue.is_current("a"));
+        assert_eq!(queue.len(), 1);
+
+        // 表示中は次を取り出さない
+        assert!(queue.start_next().is_none());
+
+        // 表示中でないIDの終了は無視
+        assert!(!queue.finish("b"));
+        assert!(queue.start_next().is_none());
+
+        assert!(queue.finish("a"));
+        assert!(!queue.finish("a"));
+        assert_eq!(queue.start_next().unwrap().0.id, "b");
+        assert_eq!(queue.len(), 0);
+    }
+
+    #[test]
+    fn test_superchat_queue_priority_insert() {
+        let queue = SuperchatQueue::new();
+        queue.push(queued_payload("low1", 2));
+        queue.push(queued_payload("low2", 5));
+        queue.push(queued_payload("tier6", 6));
+        queue.push(queued_payload("tier7", 7));
+        queue.push(queued_payload("tier6-2", 6));
+        queue.push(queued_payload("low3", 1));
+
+        // 高額Tierは低いTierより前に割り込み、同Tier同士は到着順
+        assert_eq!(
+            drain_ids(&queue),
+            vec!["tier7", "tier6", "tier6-2", "low1", "low2", "low3"]
+        );
+    }
+
+    #[test]
+    fn test_superchat_queue_priority_does_not_preempt_current() {
+        let queue = SuperchatQueue::new();
+        queue.push(queued_payload("low", 1));
+        assert_eq!(queue.start_next().unwrap().0.id, "low");
+
+        // 表示中のスパチャは高額Tierでも中断しない
+        queue.push(queued_payload("high", 7));
+        assert!(queue.start_next().is_none());
+        assert!(queue.finish("low"));
+        assert_eq!(queue.start_next().unwrap().0.id, "high");
+    }
+
+    #[test]
+    fn test_superchat_queue_author_cooldown_defers_same_author() {
+        let queue = SuperchatQueue::new();
+        let cooldown = Duration::from_secs(60);
+        let start = Instant::now();
+
+        queue.push(authored_payload("a1", 2, "UC_a"));
+        queue.push(authored_payload("a2", 2, "UC_a"));
+        queue.push(authored_payload("b1", 2, "UC_b"));
+
+        assert_eq!(queue.start_next_at(start, cooldown).unwrap().0.id, "a1");
+        assert!(queue.finish("a1"));
+
+        // 同じ投稿者の連投はクールダウン中のため、別の投稿者を先に表示
+        let later = start + Duration::from_secs(10);
+        assert_eq!(queue.start_next_at(later, cooldown).unwrap().0.id, "b1");
+        assert!(queue.finish("b1"));
+
+        // 待ちが同じ投稿者のみならクールダウン中でも表示する
+        assert_eq!(queue.start_next_at(later, cooldown).unwrap().0.id, "a2");
+        assert_eq!(queue.len(), 0);
+    }
+
+    #[test]
+    fn test_superchat_queue_author_cooldown_expires() {
+        let queue = SuperchatQueue::new();
+        let cooldown = Duration::from_secs(60);
+        let start = Instant::now();
+
+        queue.push(authored_payload("a1", 2, "UC_a"));
+        assert_eq!(queue.start_next_at(start, cooldown).unwrap().0.id, "a1");
+        assert!(queue.finish("a1"));
+
+        queue.push(authored_payload("a2", 2, "UC_a"));
+        queue.push(authored_payload("b1", 2, "UC_b"));
+
+        // クールダウン経過後は到着順
+        let later = start + Duration::from_secs(61);
+        assert_eq!(queue.start_next_at(later, cooldown).unwrap().0.id, "a2");
+    }
+
+    #[test]
+    fn test_superchat_queue_author_cooldown_disabled() {
+        let queue = SuperchatQueue::new();
+        let start = Instant::now();
+
+        queue.push(authored_payload("a1", 2, "UC_a"));
+        queue.push(authored_payload("a2", 2, "UC_a"));
+        queue.push(authored_payload("b1", 2, "UC_b"));
+
+        assert_eq!(queue.start_next_at(start, Duration::ZERO).unwrap().0.id, "a1");
+        assert!(queue.finish("a1"));
+        assert_eq!(queue.start_next_at(start, Duration::ZERO).unwrap().0.id, "a2");
+    }
+
+    #[test]
+    fn test_superchat_queue_removal_keyed_by_display_generation() {
+        let queue = SuperchatQueue::new();
+        queue.push(queued_payload("a", 2));
+        let (_, generation) = queue.start_next().unwrap();
+
+        // 削除タイマーは1回の表示につき1回だけ表示を終了する
+        assert!(queue.finish_display("a", generation));
+        assert!(!queue.finish_display("a", generation));
+
+        // 同じIDが再表示された場合、前の表示のタイマーでは終了しない
+        queue.push(queued_payload("a", 2));
+        let (_, regeneration) = queue.start_next().unwrap();
+        assert_ne!(generation, regeneration);
+        assert!(!queue.finish_display("a", generation));
+        assert!(queue.is_current("a"));
+        assert!(queue.finish_display("a", regeneration));
+    }
+
+    #[tokio::test]
+    async fn test_rebroadcast_after_slot_change_keeps_removal_timer() {
+        use tokio_tungstenite::tungstenite::Message;
+
+        let ws_state = Arc::new(RwLock::new(WebSocketState::new()));
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        ws_state.read().await.add_peer(1, tx).await;
+
+        let queue = SuperchatQueue::new();
+        queue.push(queued_payload("a", 4));
+        let start = Instant::now() - Duration::from_secs(2);
+        let (payload, generation) = queue.start_next_at(start, Duration::ZERO).unwrap();
+
+        // 表示中にslotを変更すると、新しいslot・残り時間で再送される
+        assert!(rebroadcast_current_from(&queue, &ws_state, SlotId::RightUpper).await);
+        let Ok(Message::Text(json)) = rx.try_recv() else {
+            panic!("superchat:add was not broadcast");
+        };
+        let frame: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(frame["type"], "superchat:add");
+        assert_eq!(frame["payload"]["id"], "a");
+        assert_eq!(frame["payload"]["slot"], "right.upper");
+        let remaining = frame["payload"]["displayDurationMs"].as_u64().unwrap();
+        assert!(remaining <= payload.display_duration_ms - 2_000);
+        assert!(rx.try_recv().is_err());
+
+        // 再送しても表示は終了せず、元の削除タイマーが1回だけ有効
+        assert!(queue.is_current("a"));
+        assert!(queue.finish_display("a", generation));
+        assert!(!queue.finish_display("a", generation));
+
+        // 表示中のスパチャがなければ再送しない
+        assert!(!rebroadcast_current_from(&queue, &ws_state, SlotId::LeftLower).await);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_superchat_config_author_cooldown_validation() {
+        let mut config = SuperchatConfig::default();
+        assert_eq!(config.author_cooldown_ms, 0);
+
+        config.author_cooldown_ms = MAX_AUTHOR_COOLDOWN_MS;
+        assert!(config.validate().is_ok());
+
+        config.author_cooldown_ms = MAX_AUTHOR_COOLDOWN_MS + 1;
+        assert!(config.validate().is_err());
+
+        // クールダウン導入前に保存された設定も読み込める
+        let legacy: SuperchatConfig =
+            serde_json::from_str(r#"{"tierDurationsMs":[1000,2000,3000,4000,5000,6000,7000]}"#)
+                .unwrap();
+        assert_eq!(legacy.author_cooldown_ms, 0);
+    }
+
+    fn sticker_message(amount: &str, amount_micros: Option<u64>, image_url: Option<&str>) -> ChatMessage {
+        superchat_message("", "JPY", None)
+            .with_text("")
+            .with_type(MessageType::SuperSticker {
+                sticker_id: "sticker-1".to_string(),
+                amount: amount.to_string(),
+                currency: "JPY".to_string(),
+                amount_micros,
+                image_url: image_url.map(str::to_string),
+            })
+    }
+
+    #[test]
+    fn test_superchat_payload_from_super_sticker() {
+        // InnerTube経由: 表示文字列からパースし、画像URLを含める
+        let message = sticker_message("2,000", None, Some("https://example.com/sticker.png"));
+        let payload = create_superchat_payload(&message).unwrap();
+        assert_eq!(payload.amount_micros, 2_000_000_000);
+        assert_eq!(payload.tier, 5);
+        assert_eq!(payload.display_duration_ms, get_display_duration(5));
+        assert_eq!(
+            payload.sticker_image_url.as_deref(),
+            Some("https://example.com/sticker.png")
+        );
+
+        // 公式API経由: amountMicrosを使用
+        let message = sticker_message("¥500", Some(500_000_000), None);
+        let payload = create_superchat_payload(&message).unwrap();
+        assert_eq!(payload.amount_micros, 500_000_000);
+        assert_eq!(payload.tier, 3);
+        assert!(payload.sticker_image_url.is_none());
+    }
+
+    #[test]
+    fn test_superchat_payload_skips_sticker_without_amount() {
+        // 金額対応前に保存されたステッカーは専用ウィジェットに表示しない
+        let message = sticker_message("", None, None);
+        assert!(create_superchat_payload(&message).is_none());
+    }
 }
@@ -1,21 +1,37 @@
 // =============================================================================
 // 天気自動更新モジュール
 // =============================================================================
-// 15分ごとに天気情報を自動取得してWebSocketでブロードキャストする
-// マルチシティモードにも対応
+// 一定間隔（デフォルト15分）で天気情報を自動取得してWebSocketでブロードキャストする
+// 間隔は実行中に変更可能。マルチシティモードにも対応
 // =============================================================================
 
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{Notify, RwLock};
+use tokio::sync::{watch, Notify, RwLock};
 
 use crate::server::types::{CityWeatherData, ServerState, WeatherMultiUpdatePayload, WsMessage};
 
 use super::WeatherClient;
 
-/// 自動更新間隔（15分 = 900秒）
-const AUTO_UPDATE_INTERVAL_SECS: u64 = 900;
+/// 自動更新間隔のデフォルト値（分）
+pub const DEFAULT_UPDATE_INTERVAL_MINUTES: u32 = 15;
+
+/// 自動更新間隔の最小値（分）
+const MIN_UPDATE_INTERVAL_MINUTES: u32 = 1;
+
+/// 自動更新間隔の最大値（分）
+const MAX_UPDATE_INTERVAL_MINUTES: u32 = 120;
+
+/// 自動更新間隔を1〜120分に丸める
+pub fn clamp_update_interval_minutes(minutes: u32) -> u32 {
+    minutes.clamp(MIN_UPDATE_INTERVAL_MINUTES, MAX_UPDATE_INTERVAL_MINUTES)
+}
+
+/// 分をDurationに変換
+fn minutes_to_duration(minutes: u32) -> Duration {
+    Duration::from_secs(u64::from(minutes) * 60)
+}
 
 /// マルチシティ設定
 #[derive(Debug, Clone)]
@@ -30,8 +46,9 @@ pub struct MultiCityConfig {
 
 /// 天気自動更新タスク
 ///
-/// アプリ起動時に開始し、15分ごとに天気を取得してWebSocketでブロードキャストする。
+/// アプリ起動時に開始し、一定間隔で天気を取得してWebSocketでブロードキャストする。
 /// 手動更新時は `reset_timer()` でタイマーをリセットできる。
+/// 間隔は `set_interval_minutes()` で変更でき、待機中のタイマーにもすぐ反映される。
 /// マルチシティモードにも対応。
 pub struct WeatherAutoUpdater {
     /// 実行中フラグ
@@ -40,6 +57,8 @@ pub struct WeatherAutoUpdater {
     reset_signal: Arc<Notify>,
     /// マルチシティ設定
     multi_city_config: Arc<RwLock<MultiCityConfig>>,
+    /// 自動更新間隔（分）の変更通知
+    interval_minutes: watch::Sender<u32>,
 }
 
 impl WeatherAutoUpdater {
@@ -48,10 +67,13 @@ impl WeatherAutoUpdater {
     /// # Arguments
     /// * `weather` - 天気クライアント
     /// * `server` - WebSocketサーバー状態
+    /// * `interval_minutes` - 自動更新間隔（分、1〜120分に丸める）
     ///
     /// # Returns
-    /// WeatherAutoUpdaterインスタンス（stop/reset_timer用）
-    pub fn start(weather: Arc<WeatherClient>, server: ServerState) -> Self {
+    /// WeatherAutoUpdaterインスタンス（stop/reset_timer/set_interval_minutes用）
+    pub fn start(weather: Arc<WeatherClient>, server: ServerState, interval_minutes: u32) -> Self {
+        let interval_minutes = clamp_update_interval_minutes(interval_minutes);
+        let (interval_tx, interval_rx) = watch::channel(interval_minutes);
         let is_running = Arc::new(AtomicBool::new(true));
         let reset_signal = Arc::new(Notify::new());
         let multi_city_config = Arc::new(RwLock::new(MultiCityConfig {
@@ -71,19 +93,48 @@ impl WeatherAutoUpdater {
                 is_running_clone,
                 reset_signal_clone,
                 multi_city_config_clone,
+                interval_rx,
             )
             .await;
         });
 
         log::info!(
-            "Weather auto-updater started (interval: {}s)",
-            AUTO_UPDATE_INTERVAL_SECS
+            "Weather auto-updater started (interval: {}min)",
+            interval_minutes
         );
 
         Self {
             is_running,
             reset_signal,
             multi_city_config,
+            interval_minutes: interval_tx,
+        }
+    }
+
+    /// 次の自動更新まで待機する
+    ///
+    /// 間隔が経過した場合はtrue。タイマーリセット・間隔の変更で中断した場合はfalse
+    /// （呼び出し元はループ先頭に戻り、新しい間隔で待ち直す）
+    async fn wait_next_tick(
+        interval_rx: &mut watch::Receiver<u32>,
+        reset_signal: &Notify,
+    ) -> bool {
+        let interval = minutes_to_duration(*interval_rx.borrow_and_update());
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => true,
+            _ = reset_signal.notified() => {
+                log::debug!("Weather auto-update timer reset");
+                false
+            }
+            changed = interval_rx.changed() => {
+                // 送信側が破棄された場合は通常の待機を続ける
+                if changed.is_err() {
+                    tokio::time::sleep(interval).await;
+                    return true;
+                }
+                log::debug!("Weather auto-update interval changed");
+                false
+            }
         }
     }
 
@@ -94,35 +145,38 @@ impl WeatherAutoUpdater {
         is_running: Arc<AtomicBool>,
         reset_signal: Arc<Notify>,
         multi_city_config: Arc<RwLock<MultiCityConfig>>,
+        mut interval_rx: watch::Receiver<u32>,
     ) {
         while is_running.load(Ordering::SeqCst) {
-            // 15分待機（reset_signalで中断可能）
-            tokio::select! {
-                _ = tokio::time::sleep(Duration::from_secs(AUTO_UPDATE_INTERVAL_SECS)) => {
-                    // 15分経過 → 天気を更新
-                }
-                _ = reset_signal.notified() => {
-                    // タイマーリセット → ループ先頭に戻る（待機時間リセット）
-                    log::debug!("Weather auto-update timer reset");
-                    continue;
-                }
+            // 間隔が経過するまで待機（タイマーリセット・間隔の変更で中断 → ループ先頭で待ち直す）
+            if !Self::wait_next_tick(&mut interval_rx, &reset_signal).await {
+                continue;
             }
 
             // 天気を取得してブロードキャスト（モードに応じて）
             let config = multi_city_config.read().await.clone();
-            if config.enabled && !config.cities.is_empty() {
+            let weather_ok = if config.enabled && !config.cities.is_empty() {
                 // マルチシティモード
-                if let Err(e) =
-                    Self::fetch_and_broadcast_multi(&weather, &server, &config).await
-                {
-                    log::warn!("Weather multi-city auto-update failed: {}", e);
+                match Self::fetch_and_broadcast_multi(&weather, &server, &config).await {
+                    Ok(()) => true,
+                    Err(e) => {
+                        log::warn!("Weather multi-city auto-update failed: {}", e);
+                        false
+                    }
                 }
             } else {
                 // 単一都市モード
-                if let Err(e) = Self::fetch_and_broadcast_single(&weather, &server).await {
-                    log::warn!("Weather auto-update failed: {}", e);
+                match Self::fetch_and_broadcast_single(&weather, &server).await {
+                    Ok(fresh) => fresh,
+                    Err(e) => {
+                        log::warn!("Weather auto-update failed: {}", e);
+                        false
+                    }
                 }
-            }
+            };
+
+            // 成功・失敗が変わった場合はオーバーレイに接続状態を通知
+            server.read().await.set_weather_status(weather_ok).await;
         }
 
         log::info!("Weather auto-updater stopped");
@@ -130,17 +184,19 @@ impl WeatherAutoUpdater {
 
     /// 単一都市モード: 天気を取得してWebSocketでブロードキャスト
     ///
+    /// 最新の天気を取得できた場合は`true`、取得に失敗して前回の値（stale）を配信した場合は`false`を返す
+    ///
     /// ## 設計ノート
     /// - Fire-and-forgetパターン: ブロードキャストは`tokio::spawn`でバックグラウンド実行
     /// - RwLockガードをawait境界をまたいで保持しないようにtokio::spawnで分離
     async fn fetch_and_broadcast_single(
         weather: &WeatherClient,
         server: &ServerState,
-    ) -> Result<(), String> {
-        // キャッシュをクリアして最新データを取得
-        weather.clear_cache().await;
+    ) -> Result<bool, String> {
+        // キャッシュを期限切れにして最新データを取得（失敗時は前回の値をstale付きで配信）
+        weather.expire_cache().await;
         let data = weather.get_weather().await.map_err(|e| e.to_string())?;
-        let temp = data.temp;
+        let temp = format!("{}{}", data.temp, data.temp_unit);
 
         // WebSocketでブロードキャスト（Fire-and-forget）
         let server = Arc::clone(server);
@@ -159,10 +215,10 @@ impl WeatherAutoUpdater {
                 .collect();
             drop(peers_guard);
             crate::server::websocket::WebSocketState::send_to_peers(&peers, &message);
-            log::debug!("Weather auto-update broadcasted: {}°C", temp);
+            log::debug!("Weather auto-update broadcasted: {}", temp);
         });
 
-        Ok(())
+        Ok(!data.stale)
     }
 
     /// マルチシティモード: 複数都市の天気を取得してWebSocketでブロードキャスト
@@ -192,10 +248,13 @@ impl WeatherAutoUpdater {
             .map(|(id, _, display_name)| (id.clone(), display_name.clone()))
             .collect();
 
-        // 成功した都市のみ抽出
+        // 成功した都市のみ抽出（失敗した都市はスキップして残りを配信）
         let weather_data: Vec<CityWeatherData> = results
             .into_iter()
-            .filter_map(|(id, _name, result)| {
+            .filter_map(|(id, name, result)| {
+                if let Err(e) = &result {
+                    log::warn!("Weather auto-update skipped city {}: {}", name, e);
+                }
                 result.ok().map(|data| {
                     let display_name = display_name_map
                         .get(&id)
@@ -206,9 +265,14 @@ impl WeatherAutoUpdater {
                         city_name: display_name,
                         icon: data.icon,
                         temp: data.temp,
+                        temp_unit: data.temp_unit,
                         description: data.description,
                         location: data.location,
                         humidity: Some(data.humidity),
+                        wind_speed: data.wind_speed,
+                        wind_speed_unit: data.wind_speed_unit,
+                        wind_direction: data.wind_direction,
+                        wind_compass: data.wind_compass,
                     }
                 })
             })
@@ -259,11 +323,26 @@ impl WeatherAutoUpdater {
 
     /// タイマーをリセットする（手動更新時に呼び出し）
     ///
-    /// 次回の自動更新までの時間を15分にリセットする。
+    /// 次回の自動更新までの時間を設定中の間隔にリセットする。
     pub fn reset_timer(&self) {
         self.reset_signal.notify_one();
     }
 
+    /// 自動更新間隔（分）を変更する（1〜120分に丸め、丸めた値を返す）
+    ///
+    /// 待機中のタイマーは新しい間隔で待ち直す。
+    pub fn set_interval_minutes(&self, minutes: u32) -> u32 {
+        let minutes = clamp_update_interval_minutes(minutes);
+        self.interval_minutes.send_replace(minutes);
+        log::info!("Weather auto-update interval changed: {}min", minutes);
+        minutes
+    }
+
+    /// 現在の自動更新間隔（分）
+    pub fn interval_minutes(&self) -> u32 {
+        *self.interval_minutes.borrow()
+    }
+
     /// マルチシティ設定を更新する
     ///
     /// 設定保存時や手動配信時に呼び出し、次回の自動更新からこの設定が使用される。
@@ -273,7 +352,7 @@ impl WeatherAutoUpdater {
     /// - 呼び出し元は即座に制御を戻され、待機なしで次の処理に進める
     /// - 設定は `tauri::async_runtime::spawn` でバックグラウンド更新される
     /// - 更新完了前に次の自動更新が発生した場合、古い設定が使用される可能性がある
-    ///   （実用上は15分間隔なので問題にならない）
+    ///   （実用上は分単位の間隔なので問題にならない）
     ///
     /// ## 代替設計案（未採用）
     /// - `async fn` にしてawaitで待機: 呼び出し元がasyncコンテキストを要求する
@@ -318,3 +397,39 @@ impl Drop for WeatherAutoUpdater {
         self.stop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamp_update_interval_minutes() {
+        assert_eq!(clamp_update_interval_minutes(0), 1);
+        assert_eq!(clamp_update_interval_minutes(5), 5);
+        assert_eq!(clamp_update_interval_minutes(60), 60);
+        assert_eq!(clamp_update_interval_minutes(500), 120);
+    }
+
+    #[tokio::test]
+    async fn test_interval_change_interrupts_wait() {
+        let (interval_tx, mut interval_rx) = watch::channel(60);
+        let reset_signal = Arc::new(Notify::new());
+
+        let reset_signal_clone = Arc::clone(&reset_signal);
+        let waiter = tokio::spawn(async move {
+            let ticked =
+                WeatherAutoUpdater::wait_next_tick(&mut interval_rx, &reset_signal_clone).await;
+            (ticked, *interval_rx.borrow())
+        });
+
+        // 60分の待機中に間隔を変更すると、待機を中断して新しい間隔で待ち直す
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        interval_tx.send_replace(5);
+        let (ticked, minutes) = tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("wait should be interrupted by interval change")
+            .unwrap();
+        assert!(!ticked);
+        assert_eq!(minutes, 5);
+    }
+}
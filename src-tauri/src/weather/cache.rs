@@ -1,41 +1,57 @@
 // =============================================================================
 // 天気情報キャッシュ
 // =============================================================================
-// 天気情報を15分間キャッシュしてAPIコールを削減
+// 天気情報を15分間（設定で1〜180分に変更可能）キャッシュしてAPIコールを削減
+// 予報データは同じ仕組みを別インスタンス・別TTLで使用する
 // =============================================================================
 
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 use super::types::WeatherData;
 
-/// キャッシュのTTL（15分）
-const CACHE_TTL_SECS: u64 = 15 * 60;
+/// 現在の天気のキャッシュTTLのデフォルト値（分）
+pub const DEFAULT_CACHE_TTL_MINUTES: u32 = 15;
+
+/// キャッシュTTLの最小値（分）
+const MIN_CACHE_TTL_MINUTES: u32 = 1;
+
+/// キャッシュTTLの最大値（分）
+const MAX_CACHE_TTL_MINUTES: u32 = 180;
+
+/// キャッシュTTLを1〜180分に丸める
+pub fn clamp_cache_ttl_minutes(minutes: u32) -> u32 {
+    minutes.clamp(MIN_CACHE_TTL_MINUTES, MAX_CACHE_TTL_MINUTES)
+}
 
 /// キャッシュエントリ
 #[derive(Debug, Clone)]
-struct CacheEntry {
+struct CacheEntry<T> {
     /// キャッシュされたデータ
-    data: WeatherData,
+    data: T,
     /// キャッシュ対象の都市名
     city: String,
     /// キャッシュ作成時刻
     created_at: Instant,
+    /// `expire`で期限切れにされたかどうか
+    expired: bool,
 }
 
-impl CacheEntry {
-    fn new(data: WeatherData, city: String) -> Self {
+impl<T> CacheEntry<T> {
+    fn new(data: T, city: String) -> Self {
         Self {
             data,
             city,
             created_at: Instant::now(),
+            expired: false,
         }
     }
 
-    /// 指定されたTTLに対して期限切れかどうかを判定
+    /// 指定されたTTLに対して期限切れかどうかを判定（`expire`済みの場合も期限切れ）
     fn is_expired(&self, ttl_secs: u64) -> bool {
-        self.created_at.elapsed() > Duration::from_secs(ttl_secs)
+        self.expired || self.created_at.elapsed() > Duration::from_secs(ttl_secs)
     }
 
     /// 指定された都市と一致するかを判定
@@ -45,39 +61,51 @@ impl CacheEntry {
 }
 
 /// 天気情報キャッシュ
+///
+/// 型パラメータ省略時は現在の天気（`WeatherData`）用
 #[derive(Debug)]
-pub struct WeatherCache {
+pub struct WeatherCache<T = WeatherData> {
     /// キャッシュエントリ（都市名でキャッシュ）
-    entry: Arc<RwLock<Option<CacheEntry>>>,
-    /// キャッシュのTTL（秒）
-    ttl_secs: u64,
+    entry: Arc<RwLock<Option<CacheEntry<T>>>>,
+    /// キャッシュのTTL（秒、実行中に変更可能）
+    ttl_secs: AtomicU64,
 }
 
 impl WeatherCache {
-    /// 新しいキャッシュを作成
+    /// 現在の天気用のキャッシュを作成（TTL: 15分）
     pub fn new() -> Self {
-        Self {
-            entry: Arc::new(RwLock::new(None)),
-            ttl_secs: CACHE_TTL_SECS,
-        }
+        Self::with_ttl(u64::from(DEFAULT_CACHE_TTL_MINUTES) * 60)
     }
+}
 
-    /// カスタムTTLでキャッシュを作成（テスト用）
-    #[cfg(test)]
+impl<T: Clone> WeatherCache<T> {
+    /// カスタムTTLでキャッシュを作成
     pub fn with_ttl(ttl_secs: u64) -> Self {
         Self {
             entry: Arc::new(RwLock::new(None)),
-            ttl_secs,
+            ttl_secs: AtomicU64::new(ttl_secs),
         }
     }
 
+    /// TTL（秒）を取得
+    pub fn ttl_secs(&self) -> u64 {
+        self.ttl_secs.load(Ordering::Relaxed)
+    }
+
+    /// TTL（秒）を変更
+    ///
+    /// 保存済みのデータにも次回の`get`・`ttl_remaining`から新しいTTLを適用する
+    pub fn set_ttl_secs(&self, ttl_secs: u64) {
+        self.ttl_secs.store(ttl_secs, Ordering::Relaxed);
+    }
+
     /// キャッシュから天気データを取得
     ///
     /// キャッシュがない、期限切れ、または都市が異なる場合はNoneを返す
-    pub async fn get(&self, city: &str) -> Option<WeatherData> {
+    pub async fn get(&self, city: &str) -> Option<T> {
         let entry = self.entry.read().await;
         match entry.as_ref() {
-            Some(e) if !e.is_expired(self.ttl_secs) && e.matches_city(city) => {
+            Some(e) if !e.is_expired(self.ttl_secs()) && e.matches_city(city) => {
                 log::debug!("Weather cache hit for city: {}", city);
                 Some(e.data.clone())
             }
@@ -100,11 +128,22 @@ impl WeatherCache {
         }
     }
 
+    /// 期限切れでもキャッシュされたデータを取得（APIの取得失敗時のフォールバック用）
+    ///
+    /// キャッシュがない、または都市が異なる場合はNoneを返す
+    pub async fn get_stale(&self, city: &str) -> Option<T> {
+        let entry = self.entry.read().await;
+        entry
+            .as_ref()
+            .filter(|e| e.matches_city(city))
+            .map(|e| e.data.clone())
+    }
+
     /// キャッシュに天気データを保存
-    pub async fn set(&self, data: WeatherData, city: String) {
+    pub async fn set(&self, data: T, city: String) {
         let mut entry = self.entry.write().await;
         *entry = Some(CacheEntry::new(data, city.clone()));
-        log::debug!("Weather data cached for city: {} (TTL: {}s)", city, self.ttl_secs);
+        log::debug!("Weather data cached for city: {} (TTL: {}s)", city, self.ttl_secs());
     }
 
     /// キャッシュをクリア
@@ -114,19 +153,26 @@ impl WeatherCache {
         log::debug!("Weather cache cleared");
     }
 
+    /// キャッシュを期限切れにする（次回の`get`はNone）
+    ///
+    /// `clear`と異なり、データは`get_stale`用に残す
+    pub async fn expire(&self) {
+        let mut entry = self.entry.write().await;
+        if let Some(e) = entry.as_mut() {
+            e.expired = true;
+        }
+        log::debug!("Weather cache expired");
+    }
+
     /// キャッシュの残り有効期限（秒）を取得
     ///
     /// キャッシュがない、期限切れ、または都市が異なる場合は0を返す
     pub async fn ttl_remaining(&self, city: &str) -> u64 {
         let entry = self.entry.read().await;
         match entry.as_ref() {
-            Some(e) if e.matches_city(city) => {
+            Some(e) if e.matches_city(city) && !e.expired => {
                 let elapsed = e.created_at.elapsed().as_secs();
-                if elapsed >= self.ttl_secs {
-                    0
-                } else {
-                    self.ttl_secs - elapsed
-                }
+                self.ttl_secs().saturating_sub(elapsed)
             }
             // 都市不一致または期限切れの場合は0
             _ => 0,
@@ -148,11 +194,17 @@ mod tests {
         WeatherData {
             icon: "☀️".to_string(),
             temp: 25.5,
+            temp_unit: "°C".to_string(),
             description: "晴天".to_string(),
             location: "Tokyo".to_string(),
             humidity: 60,
+            wind_speed: None,
+            wind_speed_unit: None,
+            wind_direction: None,
+            wind_compass: None,
             weather_code: 800,
             fetched_at: chrono::Utc::now().timestamp(),
+            stale: false,
         }
     }
 
@@ -187,6 +239,26 @@ mod tests {
         assert!(cache.get("Tokyo").await.is_none());
     }
 
+    #[tokio::test]
+    async fn test_cache_expire_keeps_stale_data() {
+        let cache = WeatherCache::new();
+        cache.set(create_test_weather_data(), "Tokyo".to_string()).await;
+
+        cache.expire().await;
+        assert!(cache.get("Tokyo").await.is_none());
+        assert_eq!(cache.ttl_remaining("Tokyo").await, 0);
+        // 期限切れでも同じ都市なら取得できる
+        assert_eq!(cache.get_stale("Tokyo").await.unwrap().location, "Tokyo");
+        assert!(cache.get_stale("Osaka").await.is_none());
+
+        // 保存し直すと期限切れが解除される
+        cache.set(create_test_weather_data(), "Tokyo".to_string()).await;
+        assert!(cache.get("Tokyo").await.is_some());
+
+        cache.clear().await;
+        assert!(cache.get_stale("Tokyo").await.is_none());
+    }
+
     #[tokio::test]
     async fn test_cache_ttl_remaining() {
         let cache = WeatherCache::with_ttl(10);
@@ -202,6 +274,32 @@ mod tests {
         assert!(ttl > 0 && ttl <= 10);
     }
 
+    #[tokio::test]
+    async fn test_set_ttl_applies_to_cached_entry() {
+        let cache = WeatherCache::with_ttl(600);
+        cache.set(create_test_weather_data(), "Tokyo".to_string()).await;
+        assert!(cache.ttl_remaining("Tokyo").await > 60);
+
+        // 短くすると保存済みのデータにもすぐに反映される
+        cache.set_ttl_secs(60);
+        assert_eq!(cache.ttl_secs(), 60);
+        let ttl = cache.ttl_remaining("Tokyo").await;
+        assert!(ttl > 0 && ttl <= 60);
+        assert!(cache.get("Tokyo").await.is_some());
+
+        cache.set_ttl_secs(0);
+        assert!(cache.get("Tokyo").await.is_none());
+        assert_eq!(cache.ttl_remaining("Tokyo").await, 0);
+    }
+
+    #[test]
+    fn test_clamp_cache_ttl_minutes() {
+        assert_eq!(clamp_cache_ttl_minutes(0), 1);
+        assert_eq!(clamp_cache_ttl_minutes(DEFAULT_CACHE_TTL_MINUTES), DEFAULT_CACHE_TTL_MINUTES);
+        assert_eq!(clamp_cache_ttl_minutes(180), 180);
+        assert_eq!(clamp_cache_ttl_minutes(1000), 180);
+    }
+
     #[tokio::test]
     async fn test_cache_ttl_remaining_city_mismatch() {
         // 都市不一致時はTTLが0を返すことを確認
@@ -287,21 +385,33 @@ mod tests {
         let tokyo_data = WeatherData {
             icon: "☀️".to_string(),
             temp: 25.5,
+            temp_unit: "°C".to_string(),
             description: "晴天".to_string(),
             location: "Tokyo".to_string(),
             humidity: 60,
+            wind_speed: None,
+            wind_speed_unit: None,
+            wind_direction: None,
+            wind_compass: None,
             weather_code: 800,
             fetched_at: chrono::Utc::now().timestamp(),
+            stale: false,
         };
 
         let osaka_data = WeatherData {
             icon: "☁️".to_string(),
             temp: 22.0,
+            temp_unit: "°C".to_string(),
             description: "曇り".to_string(),
             location: "Osaka".to_string(),
             humidity: 70,
+            wind_speed: None,
+            wind_speed_unit: None,
+            wind_direction: None,
+            wind_compass: None,
             weather_code: 803,
             fetched_at: chrono::Utc::now().timestamp(),
+            stale: false,
         };
 
         // Tokyoでキャッシュ
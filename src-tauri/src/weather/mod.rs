@@ -5,8 +5,12 @@
 //
 // 機能:
 // - 都市名で天気情報を取得（Geocoding API経由）
-// - 15分間のキャッシュでAPIコールを削減
+// - 15分間（1〜180分に変更可能）のキャッシュでAPIコールを削減
+// - 取得失敗時は前回取得した値を`stale`付きで返す（オーバーレイの表示を消さない）
+// - 3日間の天気予報（3時間キャッシュ）
 // - WMOコードから絵文字への変換
+// - 同時リクエスト数の制限（Open-Meteoへの負荷を抑える）
+// - タイムアウト・接続エラー・5xxは指数バックオフで再試行
 //
 // 使用API:
 // - Open-Meteo Geocoding API: https://open-meteo.com/en/docs/geocoding-api
@@ -17,17 +21,26 @@ mod auto_updater;
 mod cache;
 mod types;
 
-pub use auto_updater::WeatherAutoUpdater;
-pub use cache::WeatherCache;
-pub use types::{GeocodingResponse, OpenMeteoResponse, WeatherData};
+pub use auto_updater::{
+    clamp_update_interval_minutes, WeatherAutoUpdater, DEFAULT_UPDATE_INTERVAL_MINUTES,
+};
+pub use cache::{clamp_cache_ttl_minutes, WeatherCache, DEFAULT_CACHE_TTL_MINUTES};
+pub use types::{
+    CityCandidate, ForecastData, ForecastDay, GeocodingResponse, OpenMeteoForecastResponse,
+    OpenMeteoResponse, PinnedCityCoords, WeatherData, WeatherIconMap, WeatherUnits,
+};
 
 use crate::config::{http_timeout, HTTP_TIMEOUT_SECS};
+use crate::youtube::backoff::ExponentialBackoff;
 use reqwest::Client;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
-#[cfg(test)]
 use std::time::Duration;
 use thiserror::Error;
-use tokio::sync::RwLock;
+use tokio::sync::{OwnedSemaphorePermit, RwLock, Semaphore};
+use types::GeocodingResult;
 
 /// Open-Meteo Geocoding APIのベースURL
 const GEOCODING_API_URL: &str = "https://geocoding-api.open-meteo.com/v1/search";
@@ -35,6 +48,40 @@ const GEOCODING_API_URL: &str = "https://geocoding-api.open-meteo.com/v1/search"
 /// Open-Meteo Weather APIのベースURL
 const WEATHER_API_URL: &str = "https://api.open-meteo.com/v1/forecast";
 
+/// 予報キャッシュのTTL（3時間）
+/// 日別データは更新頻度が低いため現在の天気より長くキャッシュする
+const FORECAST_CACHE_TTL_SECS: u64 = 3 * 60 * 60;
+
+/// 予報日数
+const FORECAST_DAYS: &str = "3";
+
+/// 緯度経度キャッシュの最大都市数（超えた場合は全件破棄して取り直す）
+const MAX_COORDS_CACHE_ENTRIES: usize = 64;
+
+/// 都市検索の最大候補数
+const CITY_SEARCH_COUNT: &str = "10";
+
+/// 同時に送信するHTTPリクエスト数のデフォルト値
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 4;
+
+/// 同時に送信するHTTPリクエスト数の上限
+pub const MAX_CONCURRENT_REQUESTS_LIMIT: usize = 16;
+
+/// 最大試行回数（初回を含む）のデフォルト値
+pub const DEFAULT_RETRY_MAX_ATTEMPTS: u32 = 3;
+
+/// 最大試行回数の上限
+pub const MAX_RETRY_ATTEMPTS_LIMIT: u32 = 5;
+
+/// 初回リトライまでの待機時間のデフォルト値（ミリ秒）
+pub const DEFAULT_RETRY_BASE_DELAY_MS: u64 = 500;
+
+/// 初回リトライまでの待機時間の上限（ミリ秒）
+pub const MAX_RETRY_BASE_DELAY_MS: u64 = 10_000;
+
+/// リトライ間隔の上限（2倍ずつ増やした待機時間をこの値で打ち切る）
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+
 /// 天気APIエラー
 #[derive(Debug, Error)]
 pub enum WeatherError {
@@ -57,15 +104,69 @@ pub enum WeatherError {
     Timeout,
 }
 
-/// 緯度経度キャッシュエントリ
+impl WeatherError {
+    /// 一時的なエラー（時間をおいて再試行すれば回復しうる）かどうか
+    ///
+    /// タイムアウト・接続エラー・5xxのみ`true`。都市が見つからない・4xx・解析エラーは再試行しても回復しない
+    pub fn is_transient(&self) -> bool {
+        match self {
+            WeatherError::Timeout => true,
+            WeatherError::HttpError(e) => e.is_connect() || e.is_request(),
+            WeatherError::ApiError { status, .. } => *status >= 500,
+            WeatherError::CityNotConfigured
+            | WeatherError::CityNotFound(_)
+            | WeatherError::ParseError(_) => false,
+        }
+    }
+}
+
+/// 一時的なエラー時のリトライ設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeatherRetryPolicy {
+    /// 最大試行回数（初回を含む、1でリトライなし）
+    pub max_attempts: u32,
+    /// 初回リトライまでの待機時間（ミリ秒、以降は2倍ずつ増やす）
+    pub base_delay_ms: u64,
+}
+
+impl Default for WeatherRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_RETRY_MAX_ATTEMPTS,
+            base_delay_ms: DEFAULT_RETRY_BASE_DELAY_MS,
+        }
+    }
+}
+
+/// 緯度経度キャッシュエントリ（都市名ごと）
 #[derive(Debug, Clone)]
 struct CoordsCache {
-    city: String,
     latitude: f64,
     longitude: f64,
     display_name: String,
 }
 
+/// 同時リクエスト数の制限
+///
+/// 上限を超えたリクエストはセマフォの待ち行列に入り、空きが出た順に送信される
+#[derive(Debug)]
+struct RequestLimiter {
+    /// 同時リクエスト数の上限
+    limit: usize,
+    /// 上限分の許可を持つセマフォ
+    semaphore: Arc<Semaphore>,
+}
+
+impl RequestLimiter {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            semaphore: Arc::new(Semaphore::new(limit)),
+        }
+    }
+}
+
 /// 天気APIクライアント
 #[derive(Debug)]
 pub struct WeatherClient {
@@ -73,10 +174,26 @@ pub struct WeatherClient {
     client: Client,
     /// 天気情報キャッシュ
     cache: WeatherCache,
+    /// 天気予報キャッシュ
+    forecast_cache: WeatherCache<ForecastData>,
     /// 都市名（デフォルト: Tokyo）
     city: Arc<RwLock<String>>,
-    /// 緯度経度キャッシュ
-    coords_cache: Arc<RwLock<Option<CoordsCache>>>,
+    /// 単位系（デフォルト: メートル法）
+    units: Arc<RwLock<WeatherUnits>>,
+    /// WMOコードの表示上書き設定
+    icon_map: Arc<RwLock<WeatherIconMap>>,
+    /// 緯度経度キャッシュ（都市名 → 緯度経度）
+    ///
+    /// 都市名に対する緯度経度は変わらないため、都市ごとに保持してマルチシティでも再利用する
+    coords_cache: Arc<RwLock<HashMap<String, CoordsCache>>>,
+    /// 固定した緯度経度（都市名 → 緯度経度）
+    ///
+    /// 同名都市の取り違えを避けるため、設定された都市はジオコーディングせずにこの値を使う
+    pinned_coords: Arc<RwLock<HashMap<String, CoordsCache>>>,
+    /// 同時リクエスト数の制限（全リクエストで共有）
+    request_limiter: Arc<RwLock<RequestLimiter>>,
+    /// 一時的なエラー時のリトライ設定
+    retry_policy: Arc<RwLock<WeatherRetryPolicy>>,
     /// テスト用: GeocodingベースURL
     #[cfg(test)]
     geocoding_base_url: String,
@@ -97,8 +214,14 @@ impl WeatherClient {
         Self {
             client,
             cache: WeatherCache::new(),
+            forecast_cache: WeatherCache::with_ttl(FORECAST_CACHE_TTL_SECS),
             city: Arc::new(RwLock::new("Tokyo".to_string())),
-            coords_cache: Arc::new(RwLock::new(None)),
+            units: Arc::new(RwLock::new(WeatherUnits::default())),
+            icon_map: Arc::new(RwLock::new(WeatherIconMap::new())),
+            coords_cache: Arc::new(RwLock::new(HashMap::new())),
+            pinned_coords: Arc::new(RwLock::new(HashMap::new())),
+            request_limiter: Arc::new(RwLock::new(RequestLimiter::new(DEFAULT_MAX_CONCURRENT_REQUESTS))),
+            retry_policy: Arc::new(RwLock::new(WeatherRetryPolicy::default())),
             #[cfg(test)]
             geocoding_base_url: GEOCODING_API_URL.to_string(),
             #[cfg(test)]
@@ -127,8 +250,14 @@ impl WeatherClient {
         Self {
             client,
             cache: WeatherCache::new(),
+            forecast_cache: WeatherCache::with_ttl(FORECAST_CACHE_TTL_SECS),
             city: Arc::new(RwLock::new("Tokyo".to_string())),
-            coords_cache: Arc::new(RwLock::new(None)),
+            units: Arc::new(RwLock::new(WeatherUnits::default())),
+            icon_map: Arc::new(RwLock::new(WeatherIconMap::new())),
+            coords_cache: Arc::new(RwLock::new(HashMap::new())),
+            pinned_coords: Arc::new(RwLock::new(HashMap::new())),
+            request_limiter: Arc::new(RwLock::new(RequestLimiter::new(DEFAULT_MAX_CONCURRENT_REQUESTS))),
+            retry_policy: Arc::new(RwLock::new(WeatherRetryPolicy::default())),
             geocoding_base_url,
             weather_base_url,
         }
@@ -173,12 +302,10 @@ impl WeatherClient {
             old
         };
 
-        // 都市名変更時はキャッシュをクリア
+        // 都市名変更時はキャッシュをクリア（緯度経度キャッシュは都市ごとなので残す）
         if old_city != normalized_city {
             self.cache.clear().await;
-            // 緯度経度キャッシュもクリア
-            let mut coords = self.coords_cache.write().await;
-            *coords = None;
+            self.forecast_cache.clear().await;
             log::info!("Weather city changed: {} -> {}", old_city, normalized_city);
         }
     }
@@ -188,6 +315,131 @@ impl WeatherClient {
         self.city.read().await.clone()
     }
 
+    /// 単位系を設定
+    ///
+    /// 変更時はキャッシュをクリアし、旧単位系の値が返らないようにする
+    pub async fn set_units(&self, units: WeatherUnits) {
+        let old_units = {
+            let mut u = self.units.write().await;
+            std::mem::replace(&mut *u, units)
+        };
+
+        if old_units != units {
+            self.cache.clear().await;
+            self.forecast_cache.clear().await;
+            log::info!("Weather units changed: {:?} -> {:?}", old_units, units);
+        }
+    }
+
+    /// 現在の単位系を取得
+    pub async fn get_units(&self) -> WeatherUnits {
+        *self.units.read().await
+    }
+
+    /// WMOコードの表示上書き設定を置き換え
+    ///
+    /// 変更時はキャッシュをクリアし、旧設定のアイコンが返らないようにする
+    pub async fn set_icon_map(&self, icon_map: WeatherIconMap) {
+        let changed = {
+            let mut m = self.icon_map.write().await;
+            let changed = *m != icon_map;
+            *m = icon_map;
+            changed
+        };
+
+        if changed {
+            self.cache.clear().await;
+            self.forecast_cache.clear().await;
+            log::info!("Weather icon map updated");
+        }
+    }
+
+    /// 現在の表示上書き設定を取得
+    pub async fn get_icon_map(&self) -> WeatherIconMap {
+        self.icon_map.read().await.clone()
+    }
+
+    /// 同時リクエスト数の上限を設定（1〜`MAX_CONCURRENT_REQUESTS_LIMIT`に丸める）
+    ///
+    /// 送信中・待機中のリクエストは変更前の上限のまま処理される
+    pub async fn set_max_concurrent_requests(&self, limit: usize) {
+        let limit = limit.clamp(1, MAX_CONCURRENT_REQUESTS_LIMIT);
+        let mut limiter = self.request_limiter.write().await;
+        if limiter.limit != limit {
+            log::info!("Weather request concurrency changed: {} -> {}", limiter.limit, limit);
+            *limiter = RequestLimiter::new(limit);
+        }
+    }
+
+    /// 同時リクエスト数の上限を取得
+    pub async fn get_max_concurrent_requests(&self) -> usize {
+        self.request_limiter.read().await.limit
+    }
+
+    /// リトライ設定を更新（最大試行回数は1〜`MAX_RETRY_ATTEMPTS_LIMIT`、
+    /// 待機時間は`MAX_RETRY_BASE_DELAY_MS`以下に丸める）
+    pub async fn set_retry_policy(&self, policy: WeatherRetryPolicy) {
+        let policy = WeatherRetryPolicy {
+            max_attempts: policy.max_attempts.clamp(1, MAX_RETRY_ATTEMPTS_LIMIT),
+            base_delay_ms: policy.base_delay_ms.min(MAX_RETRY_BASE_DELAY_MS),
+        };
+        let mut current = self.retry_policy.write().await;
+        if *current != policy {
+            log::info!("Weather retry policy changed: {:?} -> {:?}", *current, policy);
+            *current = policy;
+        }
+    }
+
+    /// リトライ設定を取得
+    pub async fn get_retry_policy(&self) -> WeatherRetryPolicy {
+        *self.retry_policy.read().await
+    }
+
+    /// リクエストを実行し、一時的なエラー（`WeatherError::is_transient`）の場合は指数バックオフで再試行
+    ///
+    /// 送信許可は試行ごとに取り直すため、待機中も他のリクエストは送信できる
+    async fn with_retry<T, F, Fut>(&self, api_name: &str, mut request: F) -> Result<T, WeatherError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, WeatherError>>,
+    {
+        let policy = self.get_retry_policy().await;
+        let mut backoff = ExponentialBackoff::with_config(
+            Duration::from_millis(policy.base_delay_ms),
+            RETRY_MAX_DELAY,
+            policy.max_attempts.saturating_sub(1),
+        );
+
+        loop {
+            match request().await {
+                Err(e) if e.is_transient() && backoff.should_retry() => {
+                    let delay = backoff.next_delay();
+                    log::warn!(
+                        "{} API request failed, retrying in {:?} (retry {}/{}): {}",
+                        api_name,
+                        delay,
+                        backoff.attempt_count(),
+                        policy.max_attempts - 1,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// HTTPリクエストの送信許可を取得（上限に達している場合は空きが出るまで待機）
+    ///
+    /// 許可はレスポンスボディの読み込みが終わるまで保持すること
+    async fn acquire_request_permit(&self) -> OwnedSemaphorePermit {
+        let semaphore = Arc::clone(&self.request_limiter.read().await.semaphore);
+        semaphore
+            .acquire_owned()
+            .await
+            .expect("Weather request semaphore is never closed")
+    }
+
     /// 表示用の地名を構築（都市名, 行政区画, 国）
     fn build_display_name(
         name: &str,
@@ -211,24 +463,27 @@ impl WeatherClient {
         parts.join(", ")
     }
 
-    /// 都市名から緯度経度を取得（Geocoding API）
-    async fn geocode_city(&self, city: &str) -> Result<(f64, f64, String), WeatherError> {
-        // キャッシュをチェック
-        {
-            let cache = self.coords_cache.read().await;
-            if let Some(cached) = cache.as_ref() {
-                if cached.city == city {
-                    return Ok((cached.latitude, cached.longitude, cached.display_name.clone()));
-                }
-            }
-        }
-
-        log::debug!("Geocoding city: {}", city);
+    /// Geocoding APIで都市名を検索（最大`count`件、一時的なエラーは再試行）
+    async fn request_geocoding(
+        &self,
+        name: &str,
+        count: &str,
+    ) -> Result<Vec<GeocodingResult>, WeatherError> {
+        self.with_retry("Geocoding", || self.request_geocoding_once(name, count))
+            .await
+    }
 
+    /// Geocoding APIへのリクエストを1回送信
+    async fn request_geocoding_once(
+        &self,
+        name: &str,
+        count: &str,
+    ) -> Result<Vec<GeocodingResult>, WeatherError> {
+        let _permit = self.acquire_request_permit().await;
         let response = self
             .client
             .get(self.get_geocoding_base_url())
-            .query(&[("name", city), ("count", "1"), ("language", "ja")])
+            .query(&[("name", name), ("count", count), ("language", "ja")])
             .send()
             .await
             .map_err(|e| {
@@ -254,9 +509,31 @@ impl WeatherClient {
             WeatherError::ParseError(format!("Failed to parse geocoding response: {}", e))
         })?;
 
-        let result = geo_response
-            .results
-            .and_then(|r| r.into_iter().next())
+        Ok(geo_response.results.unwrap_or_default())
+    }
+
+    /// 都市名から緯度経度を取得（Geocoding API）
+    ///
+    /// 緯度経度を固定した都市はジオコーディングせずに固定値を使う
+    async fn geocode_city(&self, city: &str) -> Result<(f64, f64, String), WeatherError> {
+        // 固定値・キャッシュをチェック
+        if let Some(pinned) = self.pinned_coords.read().await.get(city) {
+            return Ok((pinned.latitude, pinned.longitude, pinned.display_name.clone()));
+        }
+        {
+            let cache = self.coords_cache.read().await;
+            if let Some(cached) = cache.get(city) {
+                return Ok((cached.latitude, cached.longitude, cached.display_name.clone()));
+            }
+        }
+
+        log::debug!("Geocoding city: {}", city);
+
+        let result = self
+            .request_geocoding(city, "1")
+            .await?
+            .into_iter()
+            .next()
             .ok_or_else(|| WeatherError::CityNotFound(city.to_string()))?;
 
         // 表示名を構築: "都市名, 行政区画, 国" の形式で同名都市の混乱を避ける
@@ -265,12 +542,17 @@ impl WeatherClient {
         // キャッシュに保存
         {
             let mut cache = self.coords_cache.write().await;
-            *cache = Some(CoordsCache {
-                city: city.to_string(),
-                latitude: result.latitude,
-                longitude: result.longitude,
-                display_name: display_name.clone(),
-            });
+            if cache.len() >= MAX_COORDS_CACHE_ENTRIES {
+                cache.clear();
+            }
+            cache.insert(
+                city.to_string(),
+                CoordsCache {
+                    latitude: result.latitude,
+                    longitude: result.longitude,
+                    display_name: display_name.clone(),
+                },
+            );
         }
 
         log::debug!(
@@ -284,6 +566,98 @@ impl WeatherClient {
         Ok((result.latitude, result.longitude, display_name))
     }
 
+    /// 都市名で検索し、同名都市を含む候補を返す（最大10件）
+    ///
+    /// 空白のみの検索語は空の結果を返す
+    pub async fn search_cities(&self, query: &str) -> Result<Vec<CityCandidate>, WeatherError> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let candidates = self
+            .request_geocoding(query, CITY_SEARCH_COUNT)
+            .await?
+            .into_iter()
+            .map(|result| CityCandidate {
+                display_name: Self::build_display_name(
+                    &result.name,
+                    &result.admin1,
+                    &result.country,
+                ),
+                name: result.name,
+                admin1: result.admin1,
+                country: result.country,
+                latitude: result.latitude,
+                longitude: result.longitude,
+            })
+            .collect();
+        Ok(candidates)
+    }
+
+    /// 都市名に対する緯度経度を固定（以降はジオコーディングしない）
+    ///
+    /// 都市名は`set_city`と同様に前後の空白を除去する。
+    /// 固定した都市の天気が旧位置のまま返らないようキャッシュをクリアする
+    pub async fn pin_city_coords(
+        &self,
+        city: &str,
+        latitude: f64,
+        longitude: f64,
+        display_name: String,
+    ) {
+        let city = city.trim().to_string();
+        log::info!(
+            "Weather coords pinned: {} -> ({}, {}) as {}",
+            city,
+            latitude,
+            longitude,
+            display_name
+        );
+        self.pinned_coords.write().await.insert(
+            city,
+            CoordsCache {
+                latitude,
+                longitude,
+                display_name,
+            },
+        );
+        self.cache.clear().await;
+        self.forecast_cache.clear().await;
+    }
+
+    /// 都市名に対する緯度経度の固定を解除（以降はジオコーディングする）
+    ///
+    /// 固定していた場合はtrueを返す
+    pub async fn unpin_city_coords(&self, city: &str) -> bool {
+        let city = city.trim();
+        if self.pinned_coords.write().await.remove(city).is_none() {
+            return false;
+        }
+        log::info!("Weather coords unpinned: {}", city);
+        self.cache.clear().await;
+        self.forecast_cache.clear().await;
+        true
+    }
+
+    /// 固定した緯度経度の一覧を都市名順で取得
+    pub async fn pinned_city_coords(&self) -> Vec<PinnedCityCoords> {
+        let mut pinned: Vec<PinnedCityCoords> = self
+            .pinned_coords
+            .read()
+            .await
+            .iter()
+            .map(|(city, coords)| PinnedCityCoords {
+                city: city.clone(),
+                latitude: coords.latitude,
+                longitude: coords.longitude,
+                display_name: coords.display_name.clone(),
+            })
+            .collect();
+        pinned.sort_by(|a, b| a.city.cmp(&b.city));
+        pinned
+    }
+
     /// 天気情報を取得（キャッシュ優先）
     pub async fn get_weather(&self) -> Result<WeatherData, WeatherError> {
         // 一度だけ都市を読み取り、同じ値をリクエストとキャッシュキーに使用
@@ -295,12 +669,23 @@ impl WeatherClient {
         }
 
         // APIから取得
-        let data = self.fetch_weather_for_city(&city).await?;
-
-        // キャッシュに保存
-        self.cache.set(data.clone(), city).await;
-
-        Ok(data)
+        match self.fetch_weather_for_city(&city).await {
+            Ok(data) => {
+                // キャッシュに保存
+                self.cache.set(data.clone(), city).await;
+                Ok(data)
+            }
+            Err(e) => {
+                // 一度でも取得できていれば、期限切れの値を`stale`付きで返してオーバーレイの表示を保つ
+                // （キャッシュは期限切れのままなので、次回の取得で再度APIを呼ぶ）
+                let Some(mut data) = self.cache.get_stale(&city).await else {
+                    return Err(e);
+                };
+                log::warn!("Weather fetch failed, serving stale data for {}: {}", city, e);
+                data.stale = true;
+                Ok(data)
+            }
+        }
     }
 
     /// 天気情報を強制的に取得（キャッシュ無視）
@@ -325,6 +710,28 @@ impl WeatherClient {
             lon
         );
 
+        let units = *self.units.read().await;
+        let api_response = self
+            .with_retry("Weather", || self.request_current_weather(lat, lon, units))
+            .await?;
+
+        let icon_map = self.icon_map.read().await;
+        Ok(WeatherData::from_open_meteo(
+            api_response,
+            location_name,
+            units,
+            &icon_map,
+        ))
+    }
+
+    /// Weather APIへ現在の天気のリクエストを1回送信
+    async fn request_current_weather(
+        &self,
+        lat: f64,
+        lon: f64,
+        units: WeatherUnits,
+    ) -> Result<OpenMeteoResponse, WeatherError> {
+        let _permit = self.acquire_request_permit().await;
         let response = self
             .client
             .get(self.get_weather_base_url())
@@ -333,9 +740,11 @@ impl WeatherClient {
                 ("longitude", lon.to_string()),
                 (
                     "current",
-                    "temperature_2m,relative_humidity_2m,weather_code,is_day".to_string(),
+                    "temperature_2m,relative_humidity_2m,weather_code,is_day,wind_speed_10m,wind_direction_10m"
+                        .to_string(),
                 ),
             ])
+            .query(units.query_params())
             .send()
             .await
             .map_err(|e| {
@@ -357,18 +766,135 @@ impl WeatherClient {
             });
         }
 
-        let api_response: OpenMeteoResponse = response.json().await.map_err(|e| {
+        response.json().await.map_err(|e| {
             WeatherError::ParseError(format!("Failed to parse weather response: {}", e))
-        })?;
+        })
+    }
+
+    /// 天気予報を取得（キャッシュ優先）
+    pub async fn get_forecast(&self) -> Result<ForecastData, WeatherError> {
+        let city = self.city.read().await.clone();
+
+        if let Some(cached) = self.forecast_cache.get(&city).await {
+            return Ok(cached);
+        }
+
+        let data = self.fetch_forecast_for_city(&city).await?;
+        self.forecast_cache.set(data.clone(), city).await;
+
+        Ok(data)
+    }
+
+    /// 天気予報を強制的に取得（キャッシュ無視）
+    pub async fn fetch_forecast(&self) -> Result<ForecastData, WeatherError> {
+        let city = self.city.read().await.clone();
+        self.fetch_forecast_for_city(&city).await
+    }
+
+    /// 指定された都市の3日間予報を取得（内部用）
+    async fn fetch_forecast_for_city(&self, city: &str) -> Result<ForecastData, WeatherError> {
+        if city.is_empty() {
+            return Err(WeatherError::CityNotConfigured);
+        }
+
+        let (lat, lon, location_name) = self.geocode_city(city).await?;
+        let units = *self.units.read().await;
+
+        log::debug!(
+            "Fetching forecast for: {} ({}, {})",
+            location_name,
+            lat,
+            lon
+        );
+
+        let api_response = self
+            .with_retry("Forecast", || self.request_forecast(lat, lon, units))
+            .await?;
+
+        let icon_map = self.icon_map.read().await;
+        Ok(ForecastData::from_open_meteo(
+            api_response,
+            location_name,
+            units,
+            &icon_map,
+        ))
+    }
+
+    /// Weather APIへ3日間予報のリクエストを1回送信
+    async fn request_forecast(
+        &self,
+        lat: f64,
+        lon: f64,
+        units: WeatherUnits,
+    ) -> Result<OpenMeteoForecastResponse, WeatherError> {
+        let _permit = self.acquire_request_permit().await;
+        let response = self
+            .client
+            .get(self.get_weather_base_url())
+            .query(&[
+                ("latitude", lat.to_string()),
+                ("longitude", lon.to_string()),
+                (
+                    "daily",
+                    "weather_code,temperature_2m_max,temperature_2m_min".to_string(),
+                ),
+                ("forecast_days", FORECAST_DAYS.to_string()),
+                ("timezone", "auto".to_string()),
+            ])
+            .query(units.query_params())
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_timeout() {
+                    log::warn!("Forecast API request timed out after {}s", HTTP_TIMEOUT_SECS);
+                    WeatherError::Timeout
+                } else {
+                    WeatherError::HttpError(e)
+                }
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            log::error!("Forecast API error: {} - {}", status, message);
+            return Err(WeatherError::ApiError {
+                status: status.as_u16(),
+                message,
+            });
+        }
 
-        Ok(WeatherData::from_open_meteo(api_response, location_name))
+        response.json().await.map_err(|e| {
+            WeatherError::ParseError(format!("Failed to parse forecast response: {}", e))
+        })
     }
 
-    /// キャッシュをクリア
+    /// キャッシュを期限切れにする（次回の`get_weather`でAPIから取得）
+    ///
+    /// 取得に失敗した場合に前回の値を返せるよう、データは残す
+    pub async fn expire_cache(&self) {
+        self.cache.expire().await;
+    }
+
+    /// キャッシュをクリア（前回の値も破棄する）
     pub async fn clear_cache(&self) {
         self.cache.clear().await;
     }
 
+    /// 現在の天気のキャッシュTTL（分）を設定（1〜180分に丸め、丸めた値を返す）
+    ///
+    /// 予報のキャッシュTTLは変更しない
+    pub fn set_cache_ttl_minutes(&self, minutes: u32) -> u32 {
+        let minutes = clamp_cache_ttl_minutes(minutes);
+        self.cache.set_ttl_secs(u64::from(minutes) * 60);
+        log::info!("Weather cache TTL changed: {}min", minutes);
+        minutes
+    }
+
+    /// 現在の天気のキャッシュTTL（分）を取得
+    pub fn cache_ttl_minutes(&self) -> u32 {
+        u32::try_from(self.cache.ttl_secs() / 60).unwrap_or(u32::MAX)
+    }
+
     /// キャッシュの残りTTLを取得（秒）
     pub async fn cache_ttl_remaining(&self) -> u64 {
         let city = self.city.read().await.clone();
@@ -378,6 +904,8 @@ impl WeatherClient {
     /// 複数都市の天気を一括取得
     ///
     /// 各都市の天気を順次取得し、結果をベクターで返す。
+    /// 都市ごとに独立して取得するため、一部の都市が失敗（ジオコーディング失敗等）しても
+    /// 残りの都市の結果は返す。緯度経度は都市ごとにキャッシュされる。
     ///
     /// ## 設計ノート
     /// - 順次処理でAPIレート制限に配慮（Open-Meteo APIは寛容）
@@ -431,23 +959,11 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_city_change_clears_coords_cache() {
+    async fn test_set_units() {
         let client = WeatherClient::new();
-
-        // 初期状態ではキャッシュは空
-        {
-            let cache = client.coords_cache.read().await;
-            assert!(cache.is_none());
-        }
-
-        // 都市を変更
-        client.set_city("Osaka".to_string()).await;
-
-        // キャッシュはまだ空（APIを呼んでいない）
-        {
-            let cache = client.coords_cache.read().await;
-            assert!(cache.is_none());
-        }
+        assert_eq!(client.get_units().await, WeatherUnits::Metric);
+        client.set_units(WeatherUnits::Imperial).await;
+        assert_eq!(client.get_units().await, WeatherUnits::Imperial);
     }
 
     #[tokio::test]
@@ -607,6 +1123,9 @@ mod tests {
     // =========================================================================
 
     use mockito::Server;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
 
     /// テスト用のセットアップを行い、(ServerGuard, WeatherClient)を返す
     async fn setup_test_client() -> (mockito::ServerGuard, WeatherClient) {
@@ -630,6 +1149,39 @@ mod tests {
             .await
     }
 
+    #[tokio::test]
+    async fn test_city_change_keeps_coords_cache() {
+        let (mut server, client) = setup_test_client().await;
+        let geocoding_mock = server
+            .mock("GET", "/v1/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"results": [{"id": 1, "name": "Tokyo", "latitude": 35.6895, "longitude": 139.6917, "country": "Japan", "admin1": "Tokyo"}]}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        // 初期状態ではキャッシュは空
+        assert!(client.coords_cache.read().await.is_empty());
+        client.geocode_city("Tokyo").await.unwrap();
+        assert!(client.coords_cache.read().await.contains_key("Tokyo"));
+
+        // 緯度経度キャッシュは都市ごとのため、都市を変更しても残る
+        client.set_city("Osaka".to_string()).await;
+        {
+            let cache = client.coords_cache.read().await;
+            assert!(cache.contains_key("Tokyo"));
+            assert!(!cache.contains_key("Osaka"));
+        }
+
+        // 元の都市に戻してもジオコーディングAPIを呼ばない
+        client.set_city("Tokyo".to_string()).await;
+        let (latitude, longitude, _) = client.geocode_city("Tokyo").await.unwrap();
+        assert_eq!((latitude, longitude), (35.6895, 139.6917));
+        geocoding_mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_geocoding_api_500_error() {
         let (mut server, client) = setup_test_client().await;
@@ -763,7 +1315,7 @@ mod tests {
             .match_query(mockito::Matcher::AllOf(vec![
                 mockito::Matcher::UrlEncoded("latitude".into(), "35.6895".into()),
                 mockito::Matcher::UrlEncoded("longitude".into(), "139.6917".into()),
-                mockito::Matcher::UrlEncoded("current".into(), "temperature_2m,relative_humidity_2m,weather_code,is_day".into()),
+                mockito::Matcher::UrlEncoded("current".into(), "temperature_2m,relative_humidity_2m,weather_code,is_day,wind_speed_10m,wind_direction_10m".into()),
             ]))
             .with_status(200)
             .with_header("content-type", "application/json")
@@ -772,7 +1324,9 @@ mod tests {
                     "temperature_2m": 25.5,
                     "relative_humidity_2m": 60,
                     "weather_code": 0,
-                    "is_day": 1
+                    "is_day": 1,
+                    "wind_speed_10m": 8.6,
+                    "wind_direction_10m": 45.0
                 }
             }"#)
             .create_async()
@@ -787,19 +1341,363 @@ mod tests {
         assert_eq!(weather.temp, 25.5);
         assert_eq!(weather.humidity, 60);
         assert_eq!(weather.location, "Tokyo, Japan");
+        assert_eq!(weather.wind_speed, Some(8.6));
+        assert_eq!(weather.wind_compass.as_deref(), Some("NE"));
     }
 
     #[tokio::test]
-    async fn test_weather_api_invalid_json() {
+    async fn test_weather_multi_skips_failed_city_and_caches_coords_per_city() {
         let (mut server, client) = setup_test_client().await;
 
-        // Geocoding APIは成功
-        let _geocoding_mock = mock_geocoding_success(&mut server).await;
-
-        // Weather APIは不正なJSON
-        let _weather_mock = server
-            .mock("GET", "/v1/forecast")
-            .match_query(mockito::Matcher::Any)
+        // Tokyoは見つかり、Atlantisはジオコーディングに失敗
+        let tokyo_mock = server
+            .mock("GET", "/v1/search")
+            .match_query(mockito::Matcher::UrlEncoded("name".into(), "Tokyo".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"results": [{"id": 1, "name": "Tokyo", "latitude": 35.6895, "longitude": 139.6917, "country": "Japan", "admin1": "Tokyo"}]}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let atlantis_mock = server
+            .mock("GET", "/v1/search")
+            .match_query(mockito::Matcher::UrlEncoded("name".into(), "Atlantis".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"results": []}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let _weather_mock = server
+            .mock("GET", "/v1/forecast")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"current": {"temperature_2m": 20.0, "relative_humidity_2m": 50, "weather_code": 0, "is_day": 1}}"#)
+            .create_async()
+            .await;
+
+        let cities = vec![
+            ("atlantis".to_string(), "Atlantis".to_string()),
+            ("tokyo".to_string(), "Tokyo".to_string()),
+        ];
+        for _ in 0..2 {
+            let results = client.get_weather_multi(&cities).await;
+            assert!(matches!(results[0].2, Err(WeatherError::CityNotFound(_))));
+            // 失敗した都市があっても残りの都市は取得できる
+            assert_eq!(results[1].2.as_ref().unwrap().location, "Tokyo, Japan");
+        }
+
+        // 緯度経度は都市ごとにキャッシュされ、2回目はジオコーディングしない
+        tokyo_mock.assert_async().await;
+        atlantis_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_search_cities_returns_all_candidates() {
+        let (mut server, client) = setup_test_client().await;
+
+        let _geocoding_mock = server
+            .mock("GET", "/v1/search")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("name".into(), "Springfield".into()),
+                mockito::Matcher::UrlEncoded("count".into(), "10".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"results": [
+                {"id": 1, "name": "Springfield", "latitude": 39.80172, "longitude": -89.64371, "country": "United States", "admin1": "Illinois"},
+                {"id": 2, "name": "Springfield", "latitude": 37.21533, "longitude": -93.29824, "country": "United States", "admin1": "Missouri"}
+            ]}"#)
+            .create_async()
+            .await;
+
+        let candidates = client.search_cities(" Springfield ").await.unwrap();
+        let names: Vec<&str> = candidates.iter().map(|c| c.display_name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Springfield, Illinois, United States",
+                "Springfield, Missouri, United States"
+            ]
+        );
+        assert_eq!(candidates[1].admin1.as_deref(), Some("Missouri"));
+        assert_eq!(candidates[1].latitude, 37.21533);
+
+        // 空白のみの検索語はAPIを呼ばない
+        assert!(client.search_cities("  ").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pinned_coords_bypass_geocoding() {
+        let (mut server, client) = setup_test_client().await;
+
+        let geocoding_mock = server
+            .mock("GET", "/v1/search")
+            .match_query(mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+        let _weather_mock = server
+            .mock("GET", "/v1/forecast")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("latitude".into(), "37.21533".into()),
+                mockito::Matcher::UrlEncoded("longitude".into(), "-93.29824".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"current": {"temperature_2m": 20.0, "relative_humidity_2m": 50, "weather_code": 0, "is_day": 1}}"#)
+            .create_async()
+            .await;
+
+        client
+            .pin_city_coords(
+                "Springfield ",
+                37.21533,
+                -93.29824,
+                "Springfield, Missouri".to_string(),
+            )
+            .await;
+        client.set_city("Springfield".to_string()).await;
+
+        let weather = client.fetch_weather().await.unwrap();
+        assert_eq!(weather.location, "Springfield, Missouri");
+        geocoding_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_unpinned_coords_fall_back_to_geocoding() {
+        let (mut server, client) = setup_test_client().await;
+
+        let geocoding_mock = server
+            .mock("GET", "/v1/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"results": [{"id": 1, "name": "Tokyo", "latitude": 35.6895, "longitude": 139.6917, "country": "Japan", "admin1": "Tokyo"}]}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let _weather_mock = server
+            .mock("GET", "/v1/forecast")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"current": {"temperature_2m": 20.0, "relative_humidity_2m": 50, "weather_code": 0, "is_day": 1}}"#)
+            .create_async()
+            .await;
+
+        client
+            .pin_city_coords("Tokyo", 35.0, 135.0, "Pinned Tokyo".to_string())
+            .await;
+        assert_eq!(client.pinned_city_coords().await.len(), 1);
+
+        assert!(client.unpin_city_coords(" Tokyo ").await);
+        assert!(!client.unpin_city_coords("Tokyo").await);
+        assert!(client.pinned_city_coords().await.is_empty());
+
+        client.set_city("Tokyo".to_string()).await;
+        let weather = client.fetch_weather().await.unwrap();
+        assert_ne!(weather.location, "Pinned Tokyo");
+        geocoding_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_weather_fetch_imperial_units() {
+        let (mut server, client) = setup_test_client().await;
+
+        let _geocoding_mock = mock_geocoding_success(&mut server).await;
+
+        // 華氏・mphのクエリパラメータが付与されること
+        let _weather_mock = server
+            .mock("GET", "/v1/forecast")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("temperature_unit".into(), "fahrenheit".into()),
+                mockito::Matcher::UrlEncoded("wind_speed_unit".into(), "mph".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{
+                "current": {
+                    "temperature_2m": 77.9,
+                    "relative_humidity_2m": 60,
+                    "weather_code": 0,
+                    "is_day": 1
+                }
+            }"#)
+            .create_async()
+            .await;
+
+        client.set_city("Tokyo".to_string()).await;
+        client.set_units(WeatherUnits::Imperial).await;
+
+        let weather = client.fetch_weather().await.unwrap();
+        assert_eq!(weather.temp, 77.9);
+        assert_eq!(weather.temp_unit, "°F");
+    }
+
+    #[tokio::test]
+    async fn test_units_change_clears_cache() {
+        let (mut server, client) = setup_test_client().await;
+
+        let _geocoding_mock = mock_geocoding_success(&mut server).await;
+        let _weather_mock = server
+            .mock("GET", "/v1/forecast")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{
+                "current": {
+                    "temperature_2m": 25.5,
+                    "relative_humidity_2m": 60,
+                    "weather_code": 0,
+                    "is_day": 1
+                }
+            }"#)
+            .create_async()
+            .await;
+
+        client.set_city("Tokyo".to_string()).await;
+        client.get_weather().await.unwrap();
+        assert!(client.cache_ttl_remaining().await > 0);
+
+        // 同じ単位系ではキャッシュを維持
+        client.set_units(WeatherUnits::Metric).await;
+        assert!(client.cache_ttl_remaining().await > 0);
+
+        // 単位系の変更でキャッシュをクリア
+        client.set_units(WeatherUnits::Imperial).await;
+        assert_eq!(client.cache_ttl_remaining().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_icon_map_override_applied_and_clears_cache() {
+        let (mut server, client) = setup_test_client().await;
+
+        let _geocoding_mock = mock_geocoding_success(&mut server).await;
+        let _weather_mock = server
+            .mock("GET", "/v1/forecast")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{
+                "current": {
+                    "temperature_2m": 18.0,
+                    "relative_humidity_2m": 80,
+                    "weather_code": 61,
+                    "is_day": 1
+                }
+            }"#)
+            .create_async()
+            .await;
+
+        client.set_city("Tokyo".to_string()).await;
+        let data = client.get_weather().await.unwrap();
+        assert_eq!(data.icon, "🌧️");
+
+        let mut icon_map = WeatherIconMap::new();
+        icon_map.insert(61, ("☔".to_string(), "小雨".to_string()));
+
+        // 上書き設定の変更でキャッシュをクリア
+        client.set_icon_map(icon_map.clone()).await;
+        assert_eq!(client.cache_ttl_remaining().await, 0);
+        assert_eq!(client.get_icon_map().await, icon_map);
+
+        let data = client.get_weather().await.unwrap();
+        assert_eq!(data.icon, "☔");
+        assert_eq!(data.description, "小雨");
+
+        // 同じ設定ではキャッシュを維持
+        client.set_icon_map(icon_map).await;
+        assert!(client.cache_ttl_remaining().await > 0);
+    }
+
+    /// 現在の天気キャッシュのTTL（15分）より長いことの確認用
+    const CACHE_TTL_GUARD_SECS: u64 = 15 * 60;
+
+    /// 予報APIの成功レスポンスボディ
+    const FORECAST_BODY: &str = r#"{
+        "daily": {
+            "time": ["2025-01-01", "2025-01-02", "2025-01-03"],
+            "weather_code": [0, 3, 61],
+            "temperature_2m_max": [12.0, 10.5, 9.0],
+            "temperature_2m_min": [2.0, 1.5, 4.0]
+        }
+    }"#;
+
+    #[tokio::test]
+    async fn test_forecast_fetch_success() {
+        let (mut server, client) = setup_test_client().await;
+
+        let _geocoding_mock = mock_geocoding_success(&mut server).await;
+        let _forecast_mock = server
+            .mock("GET", "/v1/forecast")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded(
+                    "daily".into(),
+                    "weather_code,temperature_2m_max,temperature_2m_min".into(),
+                ),
+                mockito::Matcher::UrlEncoded("forecast_days".into(), "3".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(FORECAST_BODY)
+            .create_async()
+            .await;
+
+        client.set_city("Tokyo".to_string()).await;
+
+        let forecast = client.fetch_forecast().await.unwrap();
+        assert_eq!(forecast.location, "Tokyo, Japan");
+        assert_eq!(forecast.days.len(), 3);
+        assert_eq!(forecast.days[0].date, "2025-01-01");
+        assert_eq!(forecast.days[2].description, "弱い雨");
+        assert_eq!(forecast.days[1].temp_max, 10.5);
+    }
+
+    #[tokio::test]
+    async fn test_forecast_uses_separate_cache() {
+        let (mut server, client) = setup_test_client().await;
+
+        let _geocoding_mock = mock_geocoding_success(&mut server).await;
+        // 2回取得しても予報APIへのリクエストは1回のみ
+        let forecast_mock = server
+            .mock("GET", "/v1/forecast")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(FORECAST_BODY)
+            .expect(1)
+            .create_async()
+            .await;
+
+        client.set_city("Tokyo".to_string()).await;
+
+        client.get_forecast().await.unwrap();
+        client.get_forecast().await.unwrap();
+        forecast_mock.assert_async().await;
+
+        // 現在の天気キャッシュとは独立している
+        assert_eq!(client.cache_ttl_remaining().await, 0);
+        let forecast_ttl = client.forecast_cache.ttl_remaining("Tokyo").await;
+        assert!(forecast_ttl > CACHE_TTL_GUARD_SECS);
+
+        // 都市変更で予報キャッシュもクリアされる
+        client.set_city("Osaka".to_string()).await;
+        assert_eq!(client.forecast_cache.ttl_remaining("Tokyo").await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_weather_api_invalid_json() {
+        let (mut server, client) = setup_test_client().await;
+
+        // Geocoding APIは成功
+        let _geocoding_mock = mock_geocoding_success(&mut server).await;
+
+        // Weather APIは不正なJSON
+        let _weather_mock = server
+            .mock("GET", "/v1/forecast")
+            .match_query(mockito::Matcher::Any)
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body("not valid json")
@@ -812,6 +1710,317 @@ mod tests {
         assert!(matches!(result, Err(WeatherError::ParseError(_))));
     }
 
+    // =============================================================================
+    // 同時リクエスト数の制限
+    // =============================================================================
+
+    #[tokio::test]
+    async fn test_set_cache_ttl_minutes() {
+        let client = WeatherClient::new();
+        assert_eq!(client.cache_ttl_minutes(), DEFAULT_CACHE_TTL_MINUTES);
+
+        assert_eq!(client.set_cache_ttl_minutes(0), 1);
+        assert_eq!(client.cache_ttl_minutes(), 1);
+        assert_eq!(client.set_cache_ttl_minutes(500), 180);
+        assert_eq!(client.cache_ttl_minutes(), 180);
+    }
+
+    #[tokio::test]
+    async fn test_set_max_concurrent_requests_clamps() {
+        let client = WeatherClient::new();
+        assert_eq!(client.get_max_concurrent_requests().await, DEFAULT_MAX_CONCURRENT_REQUESTS);
+
+        client.set_max_concurrent_requests(0).await;
+        assert_eq!(client.get_max_concurrent_requests().await, 1);
+
+        client.set_max_concurrent_requests(1000).await;
+        assert_eq!(client.get_max_concurrent_requests().await, MAX_CONCURRENT_REQUESTS_LIMIT);
+    }
+
+    /// 処理中のリクエスト数を計測するモックサーバーを起動し、ベースURLを返す
+    ///
+    /// mockitoはレスポンスの遅延を扱えないため、TCPで直接HTTPレスポンスを返す
+    async fn spawn_counting_server(in_flight: Arc<AtomicUsize>, max_in_flight: Arc<AtomicUsize>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    break;
+                };
+                let in_flight = Arc::clone(&in_flight);
+                let max_in_flight = Arc::clone(&max_in_flight);
+
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 1024];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match stream.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => request.extend_from_slice(&buf[..n]),
+                        }
+                    }
+
+                    let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_in_flight.fetch_max(current, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                    let body = if request.starts_with(b"GET /v1/search") {
+                        r#"{"results": [{"id": 1, "name": "Tokyo", "latitude": 35.6895, "longitude": 139.6917}]}"#
+                    } else {
+                        r#"{"current": {"temperature_2m": 20.0, "relative_humidity_2m": 50, "weather_code": 0, "is_day": 1}}"#
+                    };
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_are_capped() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let base_url = spawn_counting_server(Arc::clone(&in_flight), Arc::clone(&max_in_flight)).await;
+
+        let client = WeatherClient::new_with_base_urls(
+            format!("{}/v1/search", base_url),
+            format!("{}/v1/forecast", base_url),
+        );
+        client.set_max_concurrent_requests(2).await;
+
+        // 異なる都市を同時に取得（Geocoding + Weatherで計12リクエスト）
+        let cities: Vec<String> = (0..6).map(|i| format!("City{}", i)).collect();
+        let results = futures_util::future::join_all(
+            cities.iter().map(|city| client.fetch_weather_for_city(city)),
+        )
+        .await;
+
+        assert!(results.iter().all(|r| r.is_ok()), "{:?}", results);
+        // 上限を超えず、上限までは並列に送信される
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+        assert_eq!(in_flight.load(Ordering::SeqCst), 0);
+    }
+
+    // =============================================================================
+    // 一時的なエラーのリトライ
+    // =============================================================================
+
+    /// テスト用: 待機時間を短くしたリトライ設定
+    async fn set_fast_retry(client: &WeatherClient, max_attempts: u32) {
+        client
+            .set_retry_policy(WeatherRetryPolicy {
+                max_attempts,
+                base_delay_ms: 10,
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_weather_retries_after_503() {
+        let (mut server, client) = setup_test_client().await;
+        set_fast_retry(&client, 3).await;
+        let _geocoding_mock = mock_geocoding_success(&mut server).await;
+
+        // 1回目は503、2回目は成功
+        let unavailable_mock = server
+            .mock("GET", "/v1/forecast")
+            .match_query(mockito::Matcher::Any)
+            .with_status(503)
+            .with_body("Service Unavailable")
+            .expect(1)
+            .create_async()
+            .await;
+        let success_mock = server
+            .mock("GET", "/v1/forecast")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"current": {"temperature_2m": 20.0, "relative_humidity_2m": 50, "weather_code": 0, "is_day": 1}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        client.set_city("Tokyo".to_string()).await;
+        let data = client.fetch_weather().await.unwrap();
+        assert_eq!(data.temp, 20.0);
+
+        unavailable_mock.assert_async().await;
+        success_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_after_max_attempts() {
+        let (mut server, client) = setup_test_client().await;
+        set_fast_retry(&client, 2).await;
+
+        let mock = server
+            .mock("GET", "/v1/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(502)
+            .with_body("Bad Gateway")
+            .expect(2)
+            .create_async()
+            .await;
+
+        client.set_city("Tokyo".to_string()).await;
+        let result = client.fetch_weather().await;
+        assert!(
+            matches!(result, Err(WeatherError::ApiError { status: 502, .. })),
+            "{:?}",
+            result
+        );
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let (mut server, client) = setup_test_client().await;
+        set_fast_retry(&client, 3).await;
+
+        // 4xxは1回で諦める
+        let bad_request_mock = server
+            .mock("GET", "/v1/search")
+            .match_query(mockito::Matcher::UrlEncoded("name".into(), "Bad".into()))
+            .with_status(400)
+            .with_body("Bad Request")
+            .expect(1)
+            .create_async()
+            .await;
+        // 都市が見つからない場合も再試行しない
+        let not_found_mock = server
+            .mock("GET", "/v1/search")
+            .match_query(mockito::Matcher::UrlEncoded("name".into(), "Nowhere".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"results": []}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        client.set_city("Bad".to_string()).await;
+        assert!(matches!(
+            client.fetch_weather().await,
+            Err(WeatherError::ApiError { status: 400, .. })
+        ));
+        client.set_city("Nowhere".to_string()).await;
+        assert!(matches!(
+            client.fetch_weather().await,
+            Err(WeatherError::CityNotFound(_))
+        ));
+
+        bad_request_mock.assert_async().await;
+        not_found_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_weather_serves_stale_data_on_failure() {
+        let (mut server, client) = setup_test_client().await;
+        set_fast_retry(&client, 1).await;
+        let _geocoding_mock = mock_geocoding_success(&mut server).await;
+        client.set_city("Tokyo".to_string()).await;
+
+        // 一度も取得できていない場合はエラー
+        let unavailable_mock = server
+            .mock("GET", "/v1/forecast")
+            .match_query(mockito::Matcher::Any)
+            .with_status(503)
+            .with_body("Service Unavailable")
+            .expect(1)
+            .create_async()
+            .await;
+        assert!(matches!(
+            client.get_weather().await,
+            Err(WeatherError::ApiError { status: 503, .. })
+        ));
+        unavailable_mock.assert_async().await;
+
+        let success_mock = server
+            .mock("GET", "/v1/forecast")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"current": {"temperature_2m": 20.0, "relative_humidity_2m": 50, "weather_code": 0, "is_day": 1}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let fresh = client.get_weather().await.unwrap();
+        assert!(!fresh.stale);
+        success_mock.assert_async().await;
+
+        // 期限切れ後の取得に失敗した場合は前回の値をstale付きで返す
+        let unavailable_mock = server
+            .mock("GET", "/v1/forecast")
+            .match_query(mockito::Matcher::Any)
+            .with_status(503)
+            .with_body("Service Unavailable")
+            .expect(3)
+            .create_async()
+            .await;
+        client.expire_cache().await;
+        let stale = client.get_weather().await.unwrap();
+        assert!(stale.stale);
+        assert_eq!(stale.temp, fresh.temp);
+        assert_eq!(stale.fetched_at, fresh.fetched_at);
+
+        // キャッシュは期限切れのままなので、次回も再取得を試みる
+        assert!(client.get_weather().await.unwrap().stale);
+
+        // クリアすると前回の値も破棄される
+        client.clear_cache().await;
+        assert!(matches!(
+            client.get_weather().await,
+            Err(WeatherError::ApiError { status: 503, .. })
+        ));
+        unavailable_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_set_retry_policy_clamps() {
+        let client = WeatherClient::new();
+        assert_eq!(client.get_retry_policy().await, WeatherRetryPolicy::default());
+
+        client
+            .set_retry_policy(WeatherRetryPolicy {
+                max_attempts: 0,
+                base_delay_ms: 60_000,
+            })
+            .await;
+        assert_eq!(
+            client.get_retry_policy().await,
+            WeatherRetryPolicy {
+                max_attempts: 1,
+                base_delay_ms: MAX_RETRY_BASE_DELAY_MS,
+            }
+        );
+
+        client
+            .set_retry_policy(WeatherRetryPolicy {
+                max_attempts: 100,
+                base_delay_ms: 0,
+            })
+            .await;
+        assert_eq!(client.get_retry_policy().await.max_attempts, MAX_RETRY_ATTEMPTS_LIMIT);
+    }
+
+    #[test]
+    fn test_weather_error_is_transient() {
+        assert!(WeatherError::Timeout.is_transient());
+        assert!(WeatherError::ApiError { status: 503, message: String::new() }.is_transient());
+        assert!(!WeatherError::ApiError { status: 404, message: String::new() }.is_transient());
+        assert!(!WeatherError::CityNotFound("Atlantis".to_string()).is_transient());
+        assert!(!WeatherError::CityNotConfigured.is_transient());
+        assert!(!WeatherError::ParseError("bad".to_string()).is_transient());
+    }
+
     // =============================================================================
     // タイムアウト関連
     // =============================================================================
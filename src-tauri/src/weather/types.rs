@@ -4,8 +4,15 @@
 // Open-Meteo APIのレスポンス型とアプリ内部で使用する天気データ型を定義
 // =============================================================================
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+/// WMOコードごとの表示上書き設定（コード → (絵文字, 説明)）
+///
+/// 未登録のコードは組み込みの変換表にフォールバックする
+pub type WeatherIconMap = HashMap<u8, (String, String)>;
+
 // =============================================================================
 // Open-Meteo Geocoding API
 // =============================================================================
@@ -35,6 +42,54 @@ pub struct GeocodingResult {
     pub admin1: Option<String>,
 }
 
+/// 都市検索の候補（同名都市の選択用）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CityCandidate {
+    /// 都市名
+    pub name: String,
+    /// 行政区画（都道府県・州）
+    pub admin1: Option<String>,
+    /// 国名
+    pub country: Option<String>,
+    /// 緯度
+    pub latitude: f64,
+    /// 経度
+    pub longitude: f64,
+    /// 表示用の地名（"都市名, 行政区画, 国"）
+    pub display_name: String,
+}
+
+/// 都市名に対して固定した緯度経度（設定の保存・一覧用）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedCityCoords {
+    /// 都市名
+    pub city: String,
+    /// 緯度
+    pub latitude: f64,
+    /// 経度
+    pub longitude: f64,
+    /// 表示用の地名
+    pub display_name: String,
+}
+
+impl PinnedCityCoords {
+    /// 都市名が空白のみでないこと、緯度経度が範囲内であることを検証
+    pub fn validate(&self) -> Result<(), String> {
+        if self.city.trim().is_empty() {
+            return Err("都市名を指定してください".to_string());
+        }
+        if !(-90.0..=90.0).contains(&self.latitude) || !(-180.0..=180.0).contains(&self.longitude) {
+            return Err(format!(
+                "緯度は-90〜90、経度は-180〜180で指定してください: ({}, {})",
+                self.latitude, self.longitude
+            ));
+        }
+        Ok(())
+    }
+}
+
 // =============================================================================
 // Open-Meteo Weather API
 // =============================================================================
@@ -49,7 +104,7 @@ pub struct OpenMeteoResponse {
 /// 現在の天気データ
 #[derive(Debug, Clone, Deserialize)]
 pub struct CurrentWeather {
-    /// 気温（摂氏）
+    /// 気温（リクエスト時の単位系に従う: 摂氏 or 華氏）
     pub temperature_2m: f64,
     /// 湿度（%）
     pub relative_humidity_2m: i32,
@@ -57,49 +112,255 @@ pub struct CurrentWeather {
     pub weather_code: i32,
     /// 昼夜判定（0=夜, 1=昼）
     pub is_day: i32,
+    /// 風速（10m高、単位系に従う: km/h or mph）
+    #[serde(default)]
+    pub wind_speed_10m: Option<f64>,
+    /// 風向（10m高、度）
+    #[serde(default)]
+    pub wind_direction_10m: Option<f64>,
+}
+
+/// Weather API予報レスポンス（daily）
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenMeteoForecastResponse {
+    /// 日別の予報データ
+    pub daily: DailyForecast,
+}
+
+/// 日別の予報データ（各配列は同じ長さで日付順）
+#[derive(Debug, Clone, Deserialize)]
+pub struct DailyForecast {
+    /// 日付（YYYY-MM-DD）
+    pub time: Vec<String>,
+    /// WMO天気コード
+    pub weather_code: Vec<i32>,
+    /// 最高気温
+    pub temperature_2m_max: Vec<f64>,
+    /// 最低気温
+    pub temperature_2m_min: Vec<f64>,
 }
 
 // =============================================================================
 // アプリ内部データ型
 // =============================================================================
 
+/// 天気の単位系
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeatherUnits {
+    /// メートル法（°C, km/h）
+    #[default]
+    Metric,
+    /// ヤード・ポンド法（°F, mph）
+    Imperial,
+}
+
+impl WeatherUnits {
+    /// 気温の単位記号
+    pub fn temp_symbol(&self) -> &'static str {
+        match self {
+            WeatherUnits::Metric => "°C",
+            WeatherUnits::Imperial => "°F",
+        }
+    }
+
+    /// 風速の単位記号
+    pub fn wind_speed_symbol(&self) -> &'static str {
+        match self {
+            WeatherUnits::Metric => "km/h",
+            WeatherUnits::Imperial => "mph",
+        }
+    }
+
+    /// Open-Meteo APIに追加するクエリパラメータ
+    ///
+    /// メートル法はAPIのデフォルトのため追加パラメータなし
+    pub fn query_params(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            WeatherUnits::Metric => &[],
+            WeatherUnits::Imperial => &[
+                ("temperature_unit", "fahrenheit"),
+                ("wind_speed_unit", "mph"),
+            ],
+        }
+    }
+}
+
+/// 旧データ（単位情報なし）のデシリアライズ用デフォルト
+fn default_temp_unit() -> String {
+    WeatherUnits::Metric.temp_symbol().to_string()
+}
+
 /// アプリ内部で使用する天気データ
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WeatherData {
     /// 天気アイコン（絵文字）
     pub icon: String,
-    /// 気温（摂氏、小数点1桁）
+    /// 気温（小数点1桁、単位はtemp_unitを参照）
     pub temp: f64,
+    /// 気温の単位記号（"°C" or "°F"）
+    #[serde(default = "default_temp_unit")]
+    pub temp_unit: String,
     /// 天気の説明
     pub description: String,
     /// 地域名
     pub location: String,
     /// 湿度（%）
     pub humidity: i32,
+    /// 風速（小数点1桁、単位はwind_speed_unitを参照）
+    #[serde(default)]
+    pub wind_speed: Option<f64>,
+    /// 風速の単位記号（"km/h" or "mph"）
+    #[serde(default)]
+    pub wind_speed_unit: Option<String>,
+    /// 風向（度、0=北から時計回り）
+    #[serde(default)]
+    pub wind_direction: Option<f64>,
+    /// 風向の8方位表記（"N", "NE", ...）
+    #[serde(default)]
+    pub wind_compass: Option<String>,
     /// 天気コード（WMO）
     pub weather_code: i32,
     /// 取得時刻（UNIX timestamp）
     pub fetched_at: i64,
+    /// 最新の取得に失敗したため、前回取得した値（期限切れ）を返しているかどうか
+    #[serde(default)]
+    pub stale: bool,
+}
+
+/// 1日分の予報
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastDay {
+    /// 日付（YYYY-MM-DD）
+    pub date: String,
+    /// 天気アイコン（絵文字、昼間のアイコンを使用）
+    pub icon: String,
+    /// 天気の説明
+    pub description: String,
+    /// 天気コード（WMO）
+    pub weather_code: i32,
+    /// 最高気温（小数点1桁）
+    pub temp_max: f64,
+    /// 最低気温（小数点1桁）
+    pub temp_min: f64,
+}
+
+/// 数日分の天気予報
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastData {
+    /// 地域名
+    pub location: String,
+    /// 気温の単位記号（"°C" or "°F"）
+    pub temp_unit: String,
+    /// 日別の予報（日付順）
+    pub days: Vec<ForecastDay>,
+    /// 取得時刻（UNIX timestamp）
+    pub fetched_at: i64,
+}
+
+impl ForecastData {
+    /// Open-Meteo予報レスポンスからForecastDataを生成
+    ///
+    /// 配列長が揃っていない場合は最も短い配列に合わせる
+    pub fn from_open_meteo(
+        response: OpenMeteoForecastResponse,
+        location: String,
+        units: WeatherUnits,
+        icon_map: &WeatherIconMap,
+    ) -> Self {
+        let daily = response.daily;
+        let days = daily
+            .time
+            .into_iter()
+            .zip(daily.weather_code)
+            .zip(daily.temperature_2m_max.into_iter().zip(daily.temperature_2m_min))
+            .map(|((date, code), (max, min))| {
+                let (icon, description) = WeatherData::resolve_icon(code, true, icon_map);
+                ForecastDay {
+                    date,
+                    icon,
+                    description,
+                    weather_code: code,
+                    temp_max: (max * 10.0).round() / 10.0,
+                    temp_min: (min * 10.0).round() / 10.0,
+                }
+            })
+            .collect();
+
+        Self {
+            location,
+            temp_unit: units.temp_symbol().to_string(),
+            days,
+            fetched_at: chrono::Utc::now().timestamp(),
+        }
+    }
 }
 
 impl WeatherData {
     /// Open-MeteoレスポンスからWeatherDataを生成
-    pub fn from_open_meteo(response: OpenMeteoResponse, location: String) -> Self {
+    ///
+    /// アイコンと説明は`icon_map`の上書き設定を優先する
+    pub fn from_open_meteo(
+        response: OpenMeteoResponse,
+        location: String,
+        units: WeatherUnits,
+        icon_map: &WeatherIconMap,
+    ) -> Self {
         let current = response.current;
         let is_day = current.is_day == 1;
+        let (icon, description) = Self::resolve_icon(current.weather_code, is_day, icon_map);
 
         Self {
-            icon: Self::wmo_code_to_emoji(current.weather_code, is_day),
+            icon,
             temp: (current.temperature_2m * 10.0).round() / 10.0,
-            description: Self::wmo_code_to_description(current.weather_code),
+            temp_unit: units.temp_symbol().to_string(),
+            description,
             location,
             humidity: current.relative_humidity_2m,
+            wind_speed: current.wind_speed_10m.map(|v| (v * 10.0).round() / 10.0),
+            wind_speed_unit: current
+                .wind_speed_10m
+                .map(|_| units.wind_speed_symbol().to_string()),
+            wind_direction: current.wind_direction_10m,
+            wind_compass: current
+                .wind_direction_10m
+                .map(|deg| Self::degrees_to_compass(deg).to_string()),
             weather_code: current.weather_code,
             fetched_at: chrono::Utc::now().timestamp(),
+            stale: false,
         }
     }
 
+    /// 風向（度）を8方位に変換
+    ///
+    /// 各方位は±22.5度の範囲を受け持つ（例: 337.5〜22.5度 → "N"）。
+    /// 範囲外や負の値も360度で正規化する。
+    pub fn degrees_to_compass(degrees: f64) -> &'static str {
+        const DIRECTIONS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+        let normalized = degrees.rem_euclid(360.0);
+        let index = ((normalized + 22.5) / 45.0) as usize % 8;
+        DIRECTIONS[index]
+    }
+
+    /// WMOコードから絵文字と説明を解決
+    ///
+    /// 上書き設定があればそれを使用し（昼夜の区別なし）、なければ組み込みの変換表を使用する
+    pub fn resolve_icon(code: i32, is_day: bool, icon_map: &WeatherIconMap) -> (String, String) {
+        u8::try_from(code)
+            .ok()
+            .and_then(|c| icon_map.get(&c))
+            .cloned()
+            .unwrap_or_else(|| {
+                (
+                    Self::wmo_code_to_emoji(code, is_day),
+                    Self::wmo_code_to_description(code),
+                )
+            })
+    }
+
     /// WMOコードから絵文字に変換
     ///
     /// WMO天気コード: https://open-meteo.com/en/docs
@@ -302,10 +563,12 @@ mod tests {
                 relative_humidity_2m: 60,
                 weather_code: 0,
                 is_day: 1,
+                wind_speed_10m: None,
+                wind_direction_10m: None,
             },
         };
 
-        let data = WeatherData::from_open_meteo(response, "Tokyo".to_string());
+        let data = WeatherData::from_open_meteo(response, "Tokyo".to_string(), WeatherUnits::Metric, &WeatherIconMap::new());
 
         assert_eq!(data.icon, "☀️");
         assert_eq!(data.temp, 25.5); // 小数点1桁に丸め
@@ -324,10 +587,12 @@ mod tests {
                 relative_humidity_2m: 85,
                 weather_code: 73,
                 is_day: 1,
+                wind_speed_10m: None,
+                wind_direction_10m: None,
             },
         };
 
-        let data = WeatherData::from_open_meteo(response, "Sapporo".to_string());
+        let data = WeatherData::from_open_meteo(response, "Sapporo".to_string(), WeatherUnits::Metric, &WeatherIconMap::new());
 
         assert_eq!(data.temp, -5.7);
         assert_eq!(data.icon, "❄️");
@@ -342,11 +607,224 @@ mod tests {
                 relative_humidity_2m: 70,
                 weather_code: 0,
                 is_day: 0, // 夜
+                wind_speed_10m: None,
+                wind_direction_10m: None,
             },
         };
 
-        let data = WeatherData::from_open_meteo(response, "Osaka".to_string());
+        let data = WeatherData::from_open_meteo(response, "Osaka".to_string(), WeatherUnits::Metric, &WeatherIconMap::new());
 
         assert_eq!(data.icon, "🌙");
     }
+
+    #[test]
+    fn test_from_open_meteo_imperial_unit_symbol() {
+        let response = OpenMeteoResponse {
+            current: CurrentWeather {
+                temperature_2m: 77.9,
+                relative_humidity_2m: 60,
+                weather_code: 0,
+                is_day: 1,
+                wind_speed_10m: None,
+                wind_direction_10m: None,
+            },
+        };
+
+        let metric = WeatherData::from_open_meteo(response.clone(), "Tokyo".to_string(), WeatherUnits::Metric, &WeatherIconMap::new());
+        assert_eq!(metric.temp_unit, "°C");
+
+        let imperial = WeatherData::from_open_meteo(response, "Tokyo".to_string(), WeatherUnits::Imperial, &WeatherIconMap::new());
+        assert_eq!(imperial.temp, 77.9);
+        assert_eq!(imperial.temp_unit, "°F");
+    }
+
+    #[test]
+    fn test_weather_data_missing_temp_unit_defaults_to_celsius() {
+        // 単位情報追加前のJSONでもデシリアライズできる
+        let json = r#"{
+            "icon": "☀️",
+            "temp": 20.0,
+            "description": "晴天",
+            "location": "Tokyo",
+            "humidity": 50,
+            "weatherCode": 0,
+            "fetchedAt": 0
+        }"#;
+
+        let data: WeatherData = serde_json::from_str(json).unwrap();
+        assert_eq!(data.temp_unit, "°C");
+        // 風データのない旧キャッシュもNoneとして読める
+        assert_eq!(data.wind_speed, None);
+        assert_eq!(data.wind_compass, None);
+    }
+
+    #[test]
+    fn test_weather_units_serde_and_params() {
+        assert_eq!(serde_json::to_string(&WeatherUnits::Imperial).unwrap(), r#""imperial""#);
+        assert_eq!(
+            serde_json::from_str::<WeatherUnits>(r#""metric""#).unwrap(),
+            WeatherUnits::Metric
+        );
+        assert!(WeatherUnits::Metric.query_params().is_empty());
+        assert_eq!(
+            WeatherUnits::Imperial.query_params(),
+            &[("temperature_unit", "fahrenheit"), ("wind_speed_unit", "mph")]
+        );
+    }
+
+    #[test]
+    fn test_degrees_to_compass() {
+        assert_eq!(WeatherData::degrees_to_compass(0.0), "N");
+        assert_eq!(WeatherData::degrees_to_compass(22.4), "N");
+        assert_eq!(WeatherData::degrees_to_compass(22.5), "NE");
+        assert_eq!(WeatherData::degrees_to_compass(90.0), "E");
+        assert_eq!(WeatherData::degrees_to_compass(135.0), "SE");
+        assert_eq!(WeatherData::degrees_to_compass(180.0), "S");
+        assert_eq!(WeatherData::degrees_to_compass(225.0), "SW");
+        assert_eq!(WeatherData::degrees_to_compass(270.0), "W");
+        assert_eq!(WeatherData::degrees_to_compass(315.0), "NW");
+        assert_eq!(WeatherData::degrees_to_compass(350.0), "N");
+        // 範囲外の値も正規化される
+        assert_eq!(WeatherData::degrees_to_compass(360.0), "N");
+        assert_eq!(WeatherData::degrees_to_compass(-90.0), "W");
+    }
+
+    #[test]
+    fn test_from_open_meteo_with_wind() {
+        let json = r#"{
+            "current": {
+                "temperature_2m": 20.0,
+                "relative_humidity_2m": 55,
+                "weather_code": 1,
+                "is_day": 1,
+                "wind_speed_10m": 12.34,
+                "wind_direction_10m": 200.0
+            }
+        }"#;
+        let response: OpenMeteoResponse = serde_json::from_str(json).unwrap();
+
+        let data = WeatherData::from_open_meteo(response.clone(), "Tokyo".to_string(), WeatherUnits::Metric, &WeatherIconMap::new());
+        assert_eq!(data.wind_speed, Some(12.3));
+        assert_eq!(data.wind_speed_unit.as_deref(), Some("km/h"));
+        assert_eq!(data.wind_direction, Some(200.0));
+        assert_eq!(data.wind_compass.as_deref(), Some("S"));
+
+        let imperial = WeatherData::from_open_meteo(response, "Tokyo".to_string(), WeatherUnits::Imperial, &WeatherIconMap::new());
+        assert_eq!(imperial.wind_speed_unit.as_deref(), Some("mph"));
+    }
+
+    #[test]
+    fn test_from_open_meteo_without_wind() {
+        // 風データがないレスポンスでもパースできる
+        let json = r#"{
+            "current": {
+                "temperature_2m": 20.0,
+                "relative_humidity_2m": 55,
+                "weather_code": 1,
+                "is_day": 1
+            }
+        }"#;
+        let response: OpenMeteoResponse = serde_json::from_str(json).unwrap();
+
+        let data = WeatherData::from_open_meteo(response, "Tokyo".to_string(), WeatherUnits::Metric, &WeatherIconMap::new());
+        assert_eq!(data.wind_speed, None);
+        assert_eq!(data.wind_speed_unit, None);
+        assert_eq!(data.wind_direction, None);
+        assert_eq!(data.wind_compass, None);
+    }
+
+    #[test]
+    fn test_from_open_meteo_with_icon_override() {
+        let response: OpenMeteoResponse = serde_json::from_str(
+            r#"{
+                "current": {
+                    "temperature_2m": 18.0,
+                    "relative_humidity_2m": 80,
+                    "weather_code": 61,
+                    "is_day": 1
+                }
+            }"#,
+        )
+        .unwrap();
+        let mut icon_map = WeatherIconMap::new();
+        icon_map.insert(61, ("☔".to_string(), "Light rain".to_string()));
+
+        let data = WeatherData::from_open_meteo(response, "Tokyo".to_string(), WeatherUnits::Metric, &icon_map);
+        assert_eq!(data.icon, "☔");
+        assert_eq!(data.description, "Light rain");
+        assert_eq!(data.weather_code, 61);
+    }
+
+    #[test]
+    fn test_resolve_icon_falls_back_to_builtin() {
+        let mut icon_map = WeatherIconMap::new();
+        icon_map.insert(61, ("☔".to_string(), "Light rain".to_string()));
+
+        // 上書き設定のないコードは組み込みの変換表を使用
+        assert_eq!(
+            WeatherData::resolve_icon(63, true, &icon_map),
+            ("🌧️".to_string(), "雨".to_string())
+        );
+        // 上書き設定は昼夜を問わず適用
+        assert_eq!(
+            WeatherData::resolve_icon(61, false, &icon_map),
+            ("☔".to_string(), "Light rain".to_string())
+        );
+        // u8範囲外のコードも組み込みにフォールバック
+        assert_eq!(
+            WeatherData::resolve_icon(-1, true, &icon_map),
+            ("🌡️".to_string(), "不明".to_string())
+        );
+    }
+
+    #[test]
+    fn test_forecast_from_open_meteo() {
+        let json = r#"{
+            "daily": {
+                "time": ["2025-01-01", "2025-01-02", "2025-01-03"],
+                "weather_code": [0, 63, 73],
+                "temperature_2m_max": [10.26, 8.0, 2.5],
+                "temperature_2m_min": [1.04, -0.5, -3.2]
+            }
+        }"#;
+        let response: OpenMeteoForecastResponse = serde_json::from_str(json).unwrap();
+
+        let data = ForecastData::from_open_meteo(response, "Tokyo".to_string(), WeatherUnits::Metric, &WeatherIconMap::new());
+
+        assert_eq!(data.location, "Tokyo");
+        assert_eq!(data.temp_unit, "°C");
+        assert_eq!(data.days.len(), 3);
+        assert_eq!(
+            data.days[0],
+            ForecastDay {
+                date: "2025-01-01".to_string(),
+                icon: "☀️".to_string(),
+                description: "晴天".to_string(),
+                weather_code: 0,
+                temp_max: 10.3,
+                temp_min: 1.0,
+            }
+        );
+        assert_eq!(data.days[1].description, "雨");
+        assert_eq!(data.days[2].icon, "❄️");
+        assert_eq!(data.days[2].temp_min, -3.2);
+    }
+
+    #[test]
+    fn test_forecast_from_open_meteo_mismatched_lengths() {
+        // 配列長が揃わない場合は短い方に合わせる
+        let json = r#"{
+            "daily": {
+                "time": ["2025-01-01", "2025-01-02"],
+                "weather_code": [0],
+                "temperature_2m_max": [10.0, 8.0],
+                "temperature_2m_min": [1.0, -0.5]
+            }
+        }"#;
+        let response: OpenMeteoForecastResponse = serde_json::from_str(json).unwrap();
+
+        let data = ForecastData::from_open_meteo(response, "Tokyo".to_string(), WeatherUnits::Imperial, &WeatherIconMap::new());
+        assert_eq!(data.days.len(), 1);
+        assert_eq!(data.temp_unit, "°F");
+    }
 }
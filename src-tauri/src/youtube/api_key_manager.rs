@@ -3,25 +3,103 @@
 //! 同梱キー（Primary/Secondary）とBYOK（ユーザー提供キー）の両方をサポートする。
 //! - 同梱キー: ビルド時に環境変数から注入
 //! - BYOK: ユーザーがUI経由で設定
+//! - 環境変数: 実行時に`VTUBER_YT_API_KEY`から読み込み（CI/ヘッドレス/開発用）
 //!
 //! キーの優先順位:
 //! 1. BYOKが設定されていて、use_bundled=false の場合 → BYOK
 //! 2. それ以外 → Primary → Secondary（フォールバック）
+//! 3. いずれもない場合 → 環境変数（最低優先）
 
-use std::sync::atomic::{AtomicBool, Ordering};
+use serde::Serialize;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use crate::util::mask_api_key;
 
 /// 環境変数から同梱キーを取得（ビルド時に設定）
 /// 未設定の場合は空文字列として扱う
 const BUNDLED_PRIMARY_KEY: Option<&str> = option_env!("YOUTUBE_API_KEY_PRIMARY");
 const BUNDLED_SECONDARY_KEY: Option<&str> = option_env!("YOUTUBE_API_KEY_SECONDARY");
 
+/// 実行時にAPIキーを読み込む環境変数名
+pub const ENV_API_KEY_VAR: &str = "VTUBER_YT_API_KEY";
+
+/// 有効なキーの取得元
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiKeySource {
+    /// ユーザー提供キー（BYOK）
+    User,
+    /// 同梱Primaryキー
+    BundledPrimary,
+    /// 同梱Secondaryキー
+    BundledSecondary,
+    /// 環境変数
+    Env,
+}
+
+impl ApiKeySource {
+    /// ログ用の名前
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::User => "BYOK",
+            Self::BundledPrimary => "bundled(primary)",
+            Self::BundledSecondary => "bundled(secondary)",
+            Self::Env => "env",
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::User => 1,
+            Self::BundledPrimary => 2,
+            Self::BundledSecondary => 3,
+            Self::Env => 4,
+        }
+    }
+}
+
+/// 設定済みのキーの取得元（UIのセットアップ案内用）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ApiKeySources {
+    /// 同梱キーがビルドに含まれているか
+    pub bundled: bool,
+    /// BYOKが設定されているか
+    pub byok: bool,
+    /// 環境変数のキーが設定されているか
+    pub env: bool,
+}
+
+/// 最後にログ出力した取得元が未記録であることを表す値
+const NO_SOURCE_LOGGED: u8 = 0;
+
 /// APIキー管理構造体
-#[derive(Debug)]
 pub struct ApiKeyManager {
     /// ユーザー提供キー（BYOK）
     user_key: Option<String>,
+    /// 同梱Primaryキー
+    bundled_primary: Option<&'static str>,
+    /// 同梱Secondaryキー
+    bundled_secondary: Option<&'static str>,
+    /// 環境変数から読み込んだキー
+    env_key: Option<String>,
     /// Primaryキーが失敗してSecondaryにフォールバック中かどうか
     using_secondary: AtomicBool,
+    /// 最後にログ出力したキーの取得元（取得元が変わった時だけログを出す）
+    last_logged_source: AtomicU8,
+}
+
+/// キーはマスクして出力（`{:?}`でログに出てもキーが漏れないように）
+impl fmt::Debug for ApiKeyManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mask = |key: Option<&str>| key.map(mask_api_key);
+        f.debug_struct("ApiKeyManager")
+            .field("user_key", &mask(self.user_key.as_deref()))
+            .field("bundled_primary", &mask(self.bundled_primary))
+            .field("bundled_secondary", &mask(self.bundled_secondary))
+            .field("env_key", &mask(self.env_key.as_deref()))
+            .field("using_secondary", &self.using_secondary)
+            .finish()
+    }
 }
 
 impl Default for ApiKeyManager {
@@ -32,16 +110,50 @@ impl Default for ApiKeyManager {
 
 impl ApiKeyManager {
     /// 新しいApiKeyManagerを作成
+    ///
+    /// 環境変数`VTUBER_YT_API_KEY`はここで一度だけ読み込む
     pub fn new() -> Self {
+        Self::with_sources(
+            BUNDLED_PRIMARY_KEY,
+            BUNDLED_SECONDARY_KEY,
+            std::env::var(ENV_API_KEY_VAR).ok(),
+        )
+    }
+
+    /// 同梱キー・環境変数キーを指定して作成（空文字列は未設定として扱う）
+    fn with_sources(
+        bundled_primary: Option<&'static str>,
+        bundled_secondary: Option<&'static str>,
+        env_key: Option<String>,
+    ) -> Self {
+        let env_key = env_key
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty());
+        if let Some(key) = &env_key {
+            log::info!(
+                "API key found in {}: {}",
+                ENV_API_KEY_VAR,
+                mask_api_key(key)
+            );
+        }
         Self {
             user_key: None,
+            bundled_primary: bundled_primary.filter(|k| !k.is_empty()),
+            bundled_secondary: bundled_secondary.filter(|k| !k.is_empty()),
+            env_key,
             using_secondary: AtomicBool::new(false),
+            last_logged_source: AtomicU8::new(NO_SOURCE_LOGGED),
         }
     }
 
     /// 同梱キーが利用可能かどうか
     pub fn has_bundled_key(&self) -> bool {
-        BUNDLED_PRIMARY_KEY.is_some() && !BUNDLED_PRIMARY_KEY.unwrap().is_empty()
+        self.bundled_primary.is_some()
+    }
+
+    /// 環境変数のキーが設定されているかどうか
+    pub fn has_env_key(&self) -> bool {
+        self.env_key.is_some()
     }
 
     /// BYOKが設定されているかどうか
@@ -49,6 +161,32 @@ impl ApiKeyManager {
         self.user_key.is_some() && !self.user_key.as_ref().unwrap().is_empty()
     }
 
+    /// 設定済みのキーの取得元
+    pub fn sources(&self) -> ApiKeySources {
+        ApiKeySources {
+            bundled: self.has_bundled_key(),
+            byok: self.has_user_key(),
+            env: self.has_env_key(),
+        }
+    }
+
+    /// 起動時にキーの構成をログ出力
+    ///
+    /// 同梱キーなしでビルドされた場合（フォーク等）は、同梱キーを使うモードが
+    /// BYOK・環境変数にフォールバックすることを警告する（BYOKはポーリング開始時に設定される）
+    pub fn log_startup_summary(&self) {
+        if self.has_bundled_key() {
+            log::info!("API key sources: {}", self.status_summary());
+            return;
+        }
+        log::warn!(
+            "This build has no bundled API key (YOUTUBE_API_KEY_PRIMARY was not set at build time); \
+             bundled-key modes fall back to BYOK or {} ({})",
+            ENV_API_KEY_VAR,
+            if self.has_env_key() { "set" } else { "not set" }
+        );
+    }
+
     /// 有効なキーを取得
     ///
     /// # Arguments
@@ -58,28 +196,54 @@ impl ApiKeyManager {
     /// * `Some(&str)` - 有効なAPIキー
     /// * `None` - 利用可能なキーがない
     pub fn get_active_key(&self, prefer_bundled: bool) -> Option<&str> {
-        if prefer_bundled {
+        let (source, key) = self.resolve_key(prefer_bundled)?;
+        // 取得元が変わった時だけログ出力（ポーリングごとの出力を避ける）
+        if self
+            .last_logged_source
+            .swap(source.to_u8(), Ordering::SeqCst)
+            != source.to_u8()
+        {
+            log::info!(
+                "Using API key from {}: {}",
+                source.label(),
+                mask_api_key(key)
+            );
+        }
+        Some(key)
+    }
+
+    /// 有効なキーとその取得元を解決
+    ///
+    /// 環境変数のキーはBYOK・同梱キーのいずれもない場合のみ使用する
+    pub fn resolve_key(&self, prefer_bundled: bool) -> Option<(ApiKeySource, &str)> {
+        let user = self
+            .user_key
+            .as_deref()
+            .filter(|k| !k.is_empty())
+            .map(|k| (ApiKeySource::User, k));
+        let preferred = if prefer_bundled {
             // 同梱キー優先
-            self.get_bundled_key().or(self.user_key.as_deref())
+            self.get_bundled_key().or(user)
         } else {
             // BYOK優先
-            self.user_key
-                .as_deref()
-                .filter(|k| !k.is_empty())
-                .or_else(|| self.get_bundled_key())
-        }
+            user.or_else(|| self.get_bundled_key())
+        };
+        preferred.or_else(|| self.env_key.as_deref().map(|k| (ApiKeySource::Env, k)))
     }
 
     /// 同梱キーを取得（Primary → Secondaryのフォールバック）
-    fn get_bundled_key(&self) -> Option<&str> {
+    fn get_bundled_key(&self) -> Option<(ApiKeySource, &'static str)> {
+        let secondary = self
+            .bundled_secondary
+            .map(|k| (ApiKeySource::BundledSecondary, k));
         if self.using_secondary.load(Ordering::SeqCst) {
             // Secondaryを使用中
-            BUNDLED_SECONDARY_KEY.filter(|k| !k.is_empty())
+            secondary
         } else {
             // Primaryを使用（なければSecondary）
-            BUNDLED_PRIMARY_KEY
-                .filter(|k| !k.is_empty())
-                .or_else(|| BUNDLED_SECONDARY_KEY.filter(|k| !k.is_empty()))
+            self.bundled_primary
+                .map(|k| (ApiKeySource::BundledPrimary, k))
+                .or(secondary)
         }
     }
 
@@ -93,12 +257,25 @@ impl ApiKeyManager {
     /// Primaryキーでエラーが発生した場合に呼び出す。
     /// Secondaryキーが存在しない場合は何もしない。
     pub fn switch_to_secondary(&self) {
-        if BUNDLED_SECONDARY_KEY.is_some() {
+        if self.bundled_secondary.is_some() {
             log::warn!("Switching to secondary API key due to primary key failure");
             self.using_secondary.store(true, Ordering::SeqCst);
         }
     }
 
+    /// `current_key`で取得中にクォータ超過等が起きた場合の切り替え先（Secondaryキー）を返す
+    ///
+    /// `current_key`が同梱Primaryキーの場合のみSecondaryに切り替える。
+    /// BYOK・環境変数のキーで取得中の場合や、既に切り替え済みの場合は`None`
+    pub fn failover_from(&self, current_key: &str) -> Option<&'static str> {
+        if self.is_using_secondary() || self.bundled_primary != Some(current_key) {
+            return None;
+        }
+        let secondary = self.bundled_secondary?;
+        self.switch_to_secondary();
+        Some(secondary)
+    }
+
     /// Primaryキーに戻す
     pub fn reset_to_primary(&self) {
         if self.using_secondary.load(Ordering::SeqCst) {
@@ -130,7 +307,13 @@ impl ApiKeyManager {
             "no BYOK"
         };
 
-        format!("{}, {}", bundled_status, user_status)
+        let env_status = if self.has_env_key() {
+            "env key set"
+        } else {
+            "no env key"
+        };
+
+        format!("{}, {}, {}", bundled_status, user_status, env_status)
     }
 }
 
@@ -183,4 +366,135 @@ mod tests {
         manager.reset_to_primary();
         assert!(!manager.is_using_secondary());
     }
+
+    #[test]
+    fn test_failover_only_from_bundled_primary() {
+        let mut manager = ApiKeyManager::with_sources(
+            Some("bundled-primary"),
+            Some("bundled-secondary"),
+            None,
+        );
+        manager.set_user_key(Some("user-key".to_string()));
+
+        // BYOKで取得中のセッションは同梱キーに切り替えない
+        assert_eq!(manager.failover_from("user-key"), None);
+        assert!(!manager.is_using_secondary());
+
+        assert_eq!(
+            manager.failover_from("bundled-primary"),
+            Some("bundled-secondary")
+        );
+        assert!(manager.is_using_secondary());
+        // 切り替え済みの場合は再度切り替えない
+        assert_eq!(manager.failover_from("bundled-primary"), None);
+
+        // リセット後は再び切り替えられる
+        manager.reset_to_primary();
+        assert_eq!(
+            manager.failover_from("bundled-primary"),
+            Some("bundled-secondary")
+        );
+    }
+
+    #[test]
+    fn test_env_key_is_lowest_priority() {
+        let mut manager = ApiKeyManager::with_sources(
+            Some("bundled-primary"),
+            Some("bundled-secondary"),
+            Some("env-key".to_string()),
+        );
+
+        // 同梱キー・BYOKがある間は環境変数のキーを使わない
+        assert_eq!(
+            manager.resolve_key(true),
+            Some((ApiKeySource::BundledPrimary, "bundled-primary"))
+        );
+        manager.set_user_key(Some("user-key".to_string()));
+        assert_eq!(
+            manager.resolve_key(false),
+            Some((ApiKeySource::User, "user-key"))
+        );
+        assert_eq!(
+            manager.resolve_key(true),
+            Some((ApiKeySource::BundledPrimary, "bundled-primary"))
+        );
+
+        manager.switch_to_secondary();
+        assert_eq!(
+            manager.resolve_key(true),
+            Some((ApiKeySource::BundledSecondary, "bundled-secondary"))
+        );
+    }
+
+    #[test]
+    fn test_env_key_fallback_without_other_keys() {
+        let mut manager = ApiKeyManager::with_sources(None, None, Some(" env-key \n".to_string()));
+        assert!(manager.has_env_key());
+        assert_eq!(
+            manager.resolve_key(true),
+            Some((ApiKeySource::Env, "env-key"))
+        );
+        assert_eq!(manager.get_active_key(false), Some("env-key"));
+
+        // BYOKを設定すると環境変数より優先される
+        manager.set_user_key(Some("user-key".to_string()));
+        assert_eq!(manager.get_active_key(true), Some("user-key"));
+        manager.set_user_key(None);
+        assert_eq!(manager.get_active_key(true), Some("env-key"));
+
+        // 空の環境変数は未設定として扱う
+        let manager = ApiKeyManager::with_sources(None, Some(""), Some("  ".to_string()));
+        assert!(!manager.has_env_key());
+        assert_eq!(manager.resolve_key(false), None);
+    }
+
+    #[test]
+    fn test_sources_summary() {
+        let sources = |bundled: bool, byok: bool, env: bool| ApiKeySources { bundled, byok, env };
+
+        // 同梱キーなしのビルド・キー未設定
+        let mut manager = ApiKeyManager::with_sources(None, None, None);
+        assert_eq!(manager.sources(), sources(false, false, false));
+        manager.set_user_key(Some("user-key".to_string()));
+        assert_eq!(manager.sources(), sources(false, true, false));
+
+        let manager = ApiKeyManager::with_sources(None, None, Some("env-key".to_string()));
+        assert_eq!(manager.sources(), sources(false, false, true));
+
+        // Secondaryのみの場合は同梱キーありとして扱わない（has_bundled_keyと同じ）
+        let manager = ApiKeyManager::with_sources(Some(""), Some("bundled-secondary"), None);
+        assert_eq!(manager.sources(), sources(false, false, false));
+
+        let mut manager =
+            ApiKeyManager::with_sources(Some("bundled-primary"), None, Some("env-key".to_string()));
+        manager.set_user_key(Some("user-key".to_string()));
+        assert_eq!(manager.sources(), sources(true, true, true));
+
+        let json = serde_json::to_value(manager.sources()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "bundled": true, "byok": true, "env": true })
+        );
+    }
+
+    #[test]
+    fn test_debug_masks_keys() {
+        let mut manager = ApiKeyManager::with_sources(
+            Some("AIzaBundledPrimary0001"),
+            Some("AIzaBundledSecondary02"),
+            Some("AIzaEnvironmentKey0003".to_string()),
+        );
+        manager.set_user_key(Some("AIzaUserProvidedKey004".to_string()));
+
+        let debug = format!("{:?}", manager);
+        for key in [
+            "AIzaBundledPrimary0001",
+            "AIzaBundledSecondary02",
+            "AIzaEnvironmentKey0003",
+            "AIzaUserProvidedKey004",
+        ] {
+            assert!(!debug.contains(key), "raw key in Debug output: {}", debug);
+        }
+        assert!(debug.contains(&mask_api_key("AIzaUserProvidedKey004")));
+    }
 }
@@ -53,7 +53,6 @@ impl ExponentialBackoff {
     }
 
     /// カスタム設定でExponentialBackoffインスタンスを作成
-    #[allow(dead_code)]
     pub fn with_config(base_delay: Duration, max_delay: Duration, max_attempts: u32) -> Self {
         Self {
             base_delay,
@@ -98,7 +97,6 @@ impl ExponentialBackoff {
     }
 
     /// 現在の試行回数を取得
-    #[allow(dead_code)]
     pub fn attempt_count(&self) -> u32 {
         self.current_attempt
     }
@@ -120,6 +118,60 @@ impl Default for ExponentialBackoff {
     }
 }
 
+/// 新しいメッセージのない取得がこの回数続いたらポーリング間隔を延ばし始める
+const IDLE_BACKOFF_THRESHOLD: u32 = 3;
+
+/// 新しいメッセージのない取得が続いた場合のポーリング間隔の上限
+const MAX_IDLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// ポーリング間隔に加えるジッタの最大割合（+20%）
+/// 指定された間隔より短くしないよう、延ばす方向にのみ加える
+const POLL_JITTER_FACTOR: f64 = 0.2;
+
+/// 定常ポーリングの間隔を管理する構造体（InnerTube用）
+///
+/// 毎回同じ間隔で取得しないよう間隔にジッタを加え、新しいメッセージのない取得が続いた場合は
+/// 間隔を指数的に延ばす（上限5秒、指定された間隔の方が長い場合はその間隔）。
+/// 新しいメッセージを取得したら元の間隔に戻す。取得エラー時の待機は`ExponentialBackoff`で行う
+#[derive(Debug, Default)]
+pub struct PollIntervalBackoff {
+    /// 新しいメッセージのない取得の連続回数
+    consecutive_empty: u32,
+}
+
+impl PollIntervalBackoff {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 取得結果を記録（新しいメッセージがあればバックオフをリセット）
+    pub fn record(&mut self, has_new_messages: bool) {
+        if has_new_messages {
+            self.consecutive_empty = 0;
+        } else {
+            self.consecutive_empty = self.consecutive_empty.saturating_add(1);
+        }
+    }
+
+    /// 次のポーリングまでの待機時間（`base`はContinuation種別・APIの指定から決めた間隔）
+    pub fn next_interval(&self, base: Duration) -> Duration {
+        let interval = self.backed_off(base);
+        let jitter_ms = interval.as_millis() as f64 * POLL_JITTER_FACTOR;
+        let jitter = rand::thread_rng().gen_range(0.0..=jitter_ms);
+        interval + Duration::from_millis(jitter as u64)
+    }
+
+    /// ジッタを加える前の待機時間
+    fn backed_off(&self, base: Duration) -> Duration {
+        let exponent = self.consecutive_empty.saturating_sub(IDLE_BACKOFF_THRESHOLD - 1);
+        if exponent == 0 {
+            return base;
+        }
+        base.saturating_mul(2u32.saturating_pow(exponent))
+            .min(MAX_IDLE_INTERVAL.max(base))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -211,6 +263,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_poll_interval_backs_off_when_idle() {
+        let base = Duration::from_secs(1);
+        let mut interval = PollIntervalBackoff::new();
+
+        // 閾値までは指定された間隔のまま
+        for _ in 0..(IDLE_BACKOFF_THRESHOLD - 1) {
+            interval.record(false);
+            assert_eq!(interval.backed_off(base), base);
+        }
+        // 以降は2倍ずつ延ばし、上限5秒で止める
+        let expected = [2, 4, 5, 5];
+        for secs in expected {
+            interval.record(false);
+            assert_eq!(interval.backed_off(base), Duration::from_secs(secs));
+        }
+        // 上限より長い間隔が指定された場合はその間隔を使う
+        assert_eq!(interval.backed_off(Duration::from_secs(10)), Duration::from_secs(10));
+
+        // 新しいメッセージを取得したら元の間隔に戻す
+        interval.record(true);
+        assert_eq!(interval.backed_off(base), base);
+
+        // ジッタは延ばす方向のみ（+20%以内）
+        for _ in 0..10 {
+            let delay = interval.next_interval(base);
+            assert!(delay >= Duration::from_millis(1000) && delay <= Duration::from_millis(1200));
+        }
+    }
+
     #[test]
     fn test_jitter_progression() {
         let mut backoff = ExponentialBackoff::with_jitter();
@@ -0,0 +1,88 @@
+//! 投稿者（チャンネルID）のBANリスト
+//!
+//! BANしたチャンネルのコメントはオーバーレイ・Tauri UIのどちらにも流さない。
+//! 監査用にDB（comment_logs）には保存し、`is_filtered`フラグを付けて残す。
+
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
+use std::sync::RwLock;
+
+use super::types::ChatMessage;
+
+/// BAN中のチャンネルID
+/// 起動時にDBから読み込み、BAN/BAN解除コマンドで更新される
+static BANNED_CHANNELS: Lazy<RwLock<HashSet<String>>> = Lazy::new(|| RwLock::new(HashSet::new()));
+
+/// BAN中のチャンネルIDを置き換え
+pub fn set_banned_channels(channel_ids: HashSet<String>) {
+    match BANNED_CHANNELS.write() {
+        Ok(mut banned) => *banned = channel_ids,
+        Err(e) => log::error!("Failed to update banned channels: {}", e),
+    }
+}
+
+/// コメントをBAN対象外とBAN対象（IDのみ）に分ける
+fn split_by(banned: &HashSet<String>, messages: Vec<ChatMessage>) -> (Vec<ChatMessage>, Vec<String>) {
+    if banned.is_empty() {
+        return (messages, Vec::new());
+    }
+
+    let mut visible = Vec::with_capacity(messages.len());
+    let mut banned_ids = Vec::new();
+    for message in messages {
+        if banned.contains(&message.author_channel_id) {
+            banned_ids.push(message.id);
+        } else {
+            visible.push(message);
+        }
+    }
+    (visible, banned_ids)
+}
+
+/// 現在のBANリストでコメントを分ける（BAN対象外, BAN対象のコメントID）
+pub fn split_banned(messages: Vec<ChatMessage>) -> (Vec<ChatMessage>, Vec<String>) {
+    match BANNED_CHANNELS.read() {
+        Ok(banned) => split_by(&banned, messages),
+        Err(e) => {
+            log::error!("Failed to read banned channels: {}", e);
+            (messages, Vec::new())
+        }
+    }
+}
+
+/// BAN中の投稿者のコメントを除いたコピーを返す（Tauri UIへのイベント用）
+pub fn without_banned(messages: &[ChatMessage]) -> Vec<ChatMessage> {
+    split_banned(messages.to_vec()).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message_from(id: &str, channel_id: &str) -> ChatMessage {
+        ChatMessage::test_message(id).with_author("Viewer", channel_id)
+    }
+
+    #[test]
+    fn test_split_by_banned_channel() {
+        let banned: HashSet<String> = ["UC_troll".to_string()].into_iter().collect();
+        let messages = vec![
+            message_from("c1", "UC_viewer"),
+            message_from("c2", "UC_troll"),
+            message_from("c3", "UC_viewer"),
+        ];
+
+        let (visible, banned_ids) = split_by(&banned, messages);
+        let visible_ids: Vec<&str> = visible.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(visible_ids, vec!["c1", "c3"]);
+        assert_eq!(banned_ids, vec!["c2".to_string()]);
+    }
+
+    #[test]
+    fn test_split_by_empty_ban_list() {
+        let messages = vec![message_from("c1", "UC_troll")];
+        let (visible, banned_ids) = split_by(&HashSet::new(), messages);
+        assert_eq!(visible.len(), 1);
+        assert!(banned_ids.is_empty());
+    }
+}
@@ -0,0 +1,223 @@
+//! チャットのモデレーション設定
+//!
+//! 公式APIのイベントからメンバー限定モードの状態を追跡し、
+//! 変化があった場合にWebSocketでオーバーレイへ通知する。
+//!
+//! NOTE: 低速モードは公式APIのレスポンスに含まれないため対象外
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::server::types::WsMessage;
+use crate::server::websocket::WebSocketState;
+
+/// メンバー限定モード開始イベント（公式API: snippet.type）
+const MEMBERS_ONLY_STARTED_EVENT: &str = "sponsorOnlyModeStartedEvent";
+
+/// メンバー限定モード終了イベント（公式API: snippet.type）
+const MEMBERS_ONLY_ENDED_EVENT: &str = "sponsorOnlyModeEndedEvent";
+
+/// チャットのモデレーション設定
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatSettings {
+    /// メンバー限定モード
+    pub members_only: bool,
+}
+
+/// イベント種別からメンバー限定モードの切り替えを判定
+/// 切り替えイベントでない場合はNone
+pub fn members_only_from_event(event_type: &str) -> Option<bool> {
+    match event_type {
+        MEMBERS_ONLY_STARTED_EVENT => Some(true),
+        MEMBERS_ONLY_ENDED_EVENT => Some(false),
+        _ => None,
+    }
+}
+
+/// レスポンス内のイベント種別からメンバー限定モードを判定
+/// 複数の切り替えがある場合は最後のイベントを採用する
+pub fn detect_members_only<'a>(event_types: impl IntoIterator<Item = &'a str>) -> Option<bool> {
+    event_types
+        .into_iter()
+        .filter_map(members_only_from_event)
+        .last()
+}
+
+/// チャット設定のキャッシュ
+#[derive(Debug, Default)]
+pub struct ChatSettingsStore {
+    settings: std::sync::RwLock<ChatSettings>,
+}
+
+impl ChatSettingsStore {
+    /// 現在のチャット設定を取得
+    pub fn get(&self) -> ChatSettings {
+        self.settings.read().map(|s| *s).unwrap_or_default()
+    }
+
+    /// チャット設定を更新し、変化があった場合はtrueを返す
+    pub fn update(&self, settings: ChatSettings) -> bool {
+        match self.settings.write() {
+            Ok(mut current) if *current != settings => {
+                *current = settings;
+                true
+            }
+            Ok(_) => false,
+            Err(e) => {
+                log::error!("Failed to update chat settings: {}", e);
+                false
+            }
+        }
+    }
+
+    /// メンバー限定モードを更新し、変化があればWebSocketでブロードキャスト
+    pub async fn apply_members_only(
+        &self,
+        ws_state: &Arc<RwLock<WebSocketState>>,
+        members_only: bool,
+    ) {
+        let settings = ChatSettings { members_only };
+        if !self.update(settings) {
+            return;
+        }
+
+        log::info!("Chat settings changed: members_only={}", members_only);
+        let state = ws_state.read().await;
+        state
+            .broadcast(WsMessage::ChatSettings { payload: settings })
+            .await;
+    }
+
+    /// 新しい配信セッションの開始時に既定値へ戻す（前の配信の設定を持ち越さない）
+    ///
+    /// 変化があればWebSocketでブロードキャストする
+    pub async fn reset_session(&self, ws_state: &Arc<RwLock<WebSocketState>>) {
+        self.apply_members_only(ws_state, ChatSettings::default().members_only)
+            .await;
+    }
+}
+
+/// チャット設定のキャッシュ（全ポーラーで共有）
+pub static CHAT_SETTINGS: Lazy<ChatSettingsStore> = Lazy::new(ChatSettingsStore::default);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::youtube::types::LiveChatMessagesResponse;
+    use tokio::sync::mpsc;
+    use tokio_tungstenite::tungstenite::Message;
+
+    fn response_with_events(event_types: &[&str]) -> LiveChatMessagesResponse {
+        let items: Vec<serde_json::Value> = event_types
+            .iter()
+            .enumerate()
+            .map(|(i, event_type)| {
+                serde_json::json!({
+                    "id": format!("msg-{}", i),
+                    "snippet": {
+                        "type": event_type,
+                        "publishedAt": "2025-01-01T00:00:00Z",
+                        "displayMessage": ""
+                    },
+                    "authorDetails": {
+                        "channelId": "UC_owner",
+                        "displayName": "Owner",
+                        "profileImageUrl": "",
+                        "isVerified": false,
+                        "isChatOwner": true,
+                        "isChatSponsor": false,
+                        "isChatModerator": false
+                    }
+                })
+            })
+            .collect();
+
+        serde_json::from_value(serde_json::json!({
+            "pollingIntervalMillis": 5000,
+            "items": items
+        }))
+        .unwrap()
+    }
+
+    fn detect_from_response(response: &LiveChatMessagesResponse) -> Option<bool> {
+        detect_members_only(
+            response
+                .items
+                .iter()
+                .map(|item| item.snippet.message_type.as_str()),
+        )
+    }
+
+    #[test]
+    fn test_detect_members_only_from_response() {
+        let response = response_with_events(&["textMessageEvent", "sponsorOnlyModeStartedEvent"]);
+        assert_eq!(detect_from_response(&response), Some(true));
+
+        // 最後の切り替えイベントを採用
+        let response = response_with_events(&[
+            "sponsorOnlyModeStartedEvent",
+            "textMessageEvent",
+            "sponsorOnlyModeEndedEvent",
+        ]);
+        assert_eq!(detect_from_response(&response), Some(false));
+
+        // 切り替えイベントがなければ変更なし
+        let response = response_with_events(&["textMessageEvent", "superChatEvent"]);
+        assert_eq!(detect_from_response(&response), None);
+    }
+
+    #[tokio::test]
+    async fn test_apply_members_only_broadcasts_on_change() {
+        let ws_state = Arc::new(RwLock::new(WebSocketState::new()));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        {
+            let state = ws_state.read().await;
+            state.add_peer(state.next_id(), tx).await;
+        }
+
+        let store = ChatSettingsStore::default();
+        store.apply_members_only(&ws_state, true).await;
+        assert!(store.get().members_only);
+
+        let Ok(Message::Text(json)) = rx.try_recv() else {
+            panic!("chat:settings should be broadcast");
+        };
+        let frame: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(frame["type"], "chat:settings");
+        assert_eq!(frame["payload"]["membersOnly"], true);
+
+        // 変化がなければブロードキャストしない
+        store.apply_members_only(&ws_state, true).await;
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reset_session_clears_members_only() {
+        let ws_state = Arc::new(RwLock::new(WebSocketState::new()));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        {
+            let state = ws_state.read().await;
+            state.add_peer(state.next_id(), tx).await;
+        }
+
+        let store = ChatSettingsStore::default();
+        store.apply_members_only(&ws_state, true).await;
+        assert!(rx.try_recv().is_ok());
+
+        // 前の配信のメンバー限定モードを解除して通知
+        store.reset_session(&ws_state).await;
+        assert_eq!(store.get(), ChatSettings::default());
+        let Ok(Message::Text(json)) = rx.try_recv() else {
+            panic!("chat:settings should be broadcast");
+        };
+        let frame: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(frame["payload"]["membersOnly"], false);
+
+        // 既定値のままなら通知しない
+        store.reset_session(&ws_state).await;
+        assert!(rx.try_recv().is_err());
+    }
+}
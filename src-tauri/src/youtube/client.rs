@@ -4,7 +4,9 @@ use std::fmt;
 use std::time::Duration;
 
 use super::errors::YouTubeError;
-use super::types::{LiveChatMessagesResponse, LiveStreamStats, VideoResponse};
+use super::types::{
+    LiveChatMessagesResponse, LiveStreamStats, StreamUptime, VideoItem, VideoResponse,
+};
 use crate::config::{http_timeout, HTTP_TIMEOUT_SECS};
 
 const API_BASE: &str = "https://www.googleapis.com/youtube/v3";
@@ -67,6 +69,16 @@ impl YouTubeClient {
         }
     }
 
+    /// 認証に使っているAPIキー
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    /// 認証に使うAPIキーを差し替え（セカンダリキーへの切り替え用）
+    pub fn set_api_key(&mut self, api_key: String) {
+        self.api_key = api_key;
+    }
+
     /// APIのベースURLを取得（テスト時はbase_url、本番時はAPI_BASE）
     #[inline]
     fn get_base_url(&self) -> &str {
@@ -86,7 +98,7 @@ impl YouTubeClient {
             log::warn!("YouTube API request timed out after {}s", HTTP_TIMEOUT_SECS);
             YouTubeError::Timeout
         } else {
-            YouTubeError::HttpError(error)
+            YouTubeError::from(error)
         }
     }
 
@@ -349,16 +361,66 @@ impl YouTubeClient {
             video_id
         );
 
+        let item = self
+            .fetch_video_item(video_id, "liveStreamingDetails,statistics")
+            .await?;
+
+        let concurrent_viewers = item
+            .live_streaming_details
+            .as_ref()
+            .and_then(|d| d.concurrent_viewers.as_ref())
+            .and_then(|v| v.parse::<i64>().ok());
+
+        let like_count = item
+            .statistics
+            .as_ref()
+            .and_then(|s| s.like_count.as_ref())
+            .and_then(|v| v.parse::<i64>().ok());
+
+        let view_count = item
+            .statistics
+            .as_ref()
+            .and_then(|s| s.view_count.as_ref())
+            .and_then(|v| v.parse::<i64>().ok());
+
+        Ok(LiveStreamStats {
+            concurrent_viewers,
+            like_count,
+            view_count,
+        })
+    }
+
+    /// 配信の開始時刻と経過時間を取得（クォータ消費: 1 unit）
+    ///
+    /// `liveStreamingDetails.actualStartTime`から経過秒数を計算する。
+    /// 未開始（予約中）の配信は経過秒数がNoneになる。
+    pub async fn get_stream_uptime(&self, video_id: &str) -> Result<StreamUptime, YouTubeError> {
+        log::debug!(
+            "Fetching stream uptime for video: {} (quota cost: 1 unit)",
+            video_id
+        );
+
+        let item = self
+            .fetch_video_item(video_id, "liveStreamingDetails")
+            .await?;
+        Ok(StreamUptime::from_details(
+            item.live_streaming_details.as_ref(),
+            chrono::Utc::now(),
+        ))
+    }
+
+    /// videos APIから動画1件を取得（HTTPステータスをYouTubeErrorに変換）
+    async fn fetch_video_item(
+        &self,
+        video_id: &str,
+        part: &str,
+    ) -> Result<VideoItem, YouTubeError> {
         let url = format!("{}/videos", self.get_base_url());
 
         let response = self
             .client
             .get(&url)
-            .query(&[
-                ("part", "liveStreamingDetails,statistics"),
-                ("id", video_id),
-                ("key", &self.api_key),
-            ])
+            .query(&[("part", part), ("id", video_id), ("key", &self.api_key)])
             .send()
             .await
             .map_err(Self::convert_reqwest_error)?;
@@ -412,31 +474,10 @@ impl YouTubeClient {
 
         let data: VideoResponse = response.json().await?;
 
-        let item = data.items.first().ok_or(YouTubeError::VideoNotFound)?;
-
-        let concurrent_viewers = item
-            .live_streaming_details
-            .as_ref()
-            .and_then(|d| d.concurrent_viewers.as_ref())
-            .and_then(|v| v.parse::<i64>().ok());
-
-        let like_count = item
-            .statistics
-            .as_ref()
-            .and_then(|s| s.like_count.as_ref())
-            .and_then(|v| v.parse::<i64>().ok());
-
-        let view_count = item
-            .statistics
-            .as_ref()
-            .and_then(|s| s.view_count.as_ref())
-            .and_then(|v| v.parse::<i64>().ok());
-
-        Ok(LiveStreamStats {
-            concurrent_viewers,
-            like_count,
-            view_count,
-        })
+        data.items
+            .into_iter()
+            .next()
+            .ok_or(YouTubeError::VideoNotFound)
     }
 }
 
@@ -734,6 +775,88 @@ mod tests {
         assert_eq!(stats.concurrent_viewers, None);
     }
 
+    // =============================================================================
+    // get_stream_uptime テスト
+    // =============================================================================
+
+    #[tokio::test]
+    async fn test_get_stream_uptime_from_actual_start_time() {
+        let (mut server, client) = setup_test_client().await;
+
+        let started_at = chrono::Utc::now() - chrono::Duration::seconds(5025);
+        let response_body = serde_json::json!({
+            "items": [{
+                "liveStreamingDetails": {
+                    "actualStartTime": started_at.to_rfc3339(),
+                    "scheduledStartTime": "2025-01-01T12:00:00Z",
+                    "concurrentViewers": "50"
+                }
+            }]
+        });
+
+        let _mock = server
+            .mock("GET", "/videos")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("id".into(), "live_video".into()),
+                mockito::Matcher::UrlEncoded("part".into(), "liveStreamingDetails".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(response_body.to_string())
+            .create_async()
+            .await;
+
+        let uptime = client.get_stream_uptime("live_video").await.unwrap();
+        let seconds = uptime.uptime_seconds.expect("stream has started");
+        // テスト実行時間の分だけ増えることを許容
+        assert!((5025..5085).contains(&seconds), "uptime: {}", seconds);
+        assert!(uptime.started_at.is_some());
+        assert!(!uptime.ended);
+
+        // 終了済みの配信は配信時間を返す
+        let details: crate::youtube::types::LiveStreamingDetails =
+            serde_json::from_value(serde_json::json!({
+                "actualStartTime": "2025-01-01T12:00:00Z",
+                "actualEndTime": "2025-01-01T13:23:45Z"
+            }))
+            .unwrap();
+        let ended = StreamUptime::from_details(Some(&details), chrono::Utc::now());
+        assert_eq!(ended.uptime_seconds, Some(5025));
+        assert!(ended.ended);
+    }
+
+    #[tokio::test]
+    async fn test_get_stream_uptime_scheduled_returns_none() {
+        let (mut server, client) = setup_test_client().await;
+
+        // 予約中の配信はactualStartTimeがない
+        let response_body = serde_json::json!({
+            "items": [{
+                "liveStreamingDetails": {
+                    "scheduledStartTime": "2099-01-01T12:00:00Z"
+                }
+            }]
+        });
+
+        let _mock = server
+            .mock("GET", "/videos")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(response_body.to_string())
+            .create_async()
+            .await;
+
+        let uptime = client.get_stream_uptime("scheduled_video").await.unwrap();
+        assert_eq!(uptime.uptime_seconds, None);
+        assert_eq!(uptime.started_at, None);
+        assert_eq!(
+            uptime.scheduled_start_time.as_deref(),
+            Some("2099-01-01T12:00:00Z")
+        );
+        assert!(!uptime.ended);
+    }
+
     // =============================================================================
     // validate_api_key HTTPステータスマッピングテスト
     // =============================================================================
@@ -1389,4 +1512,18 @@ mod tests {
         // クライアントが正常に作成されることを確認（タイムアウト設定が内部で行われている）
         assert!(!client.api_key.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_http_error_does_not_contain_api_key() {
+        // 接続できないURL（reqwestのエラーメッセージには通常リクエストURLが含まれる）
+        let client = YouTubeClient::new_with_base_url(
+            "AIzaSecretKeyForHttpErrorTest".to_string(),
+            "http://127.0.0.1:1".to_string(),
+        );
+
+        let err = client.validate_api_key().await.unwrap_err();
+        assert!(matches!(err, YouTubeError::HttpError(_)), "unexpected error: {:?}", err);
+        assert!(!err.to_string().contains("AIzaSecretKeyForHttpErrorTest"));
+        assert!(!format!("{:?}", err).contains("AIzaSecretKeyForHttpErrorTest"));
+    }
 }
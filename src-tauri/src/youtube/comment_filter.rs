@@ -0,0 +1,219 @@
+//! コメントのブロックリストフィルタ
+//!
+//! ポーリングで取得したコメントのうち、ブロックリストに一致するものを
+//! オーバーレイへのブロードキャストから除外する。
+//! 除外したコメントもDBには保存し、`is_filtered`フラグを付けて残す
+//! （保存は[`super::write_queue`]経由で非同期に行う）。
+//! BANしたチャンネルのコメントも同じ経路で除外する（[`super::channel_ban`]）。
+//! ブロードキャストするコメントには不適切語マスクを適用する（[`super::profanity`]）。
+//!
+//! ## ブロックリストの書式
+//! - 1行に1パターン（前後の空白は無視、空行は無視）
+//! - 通常の行: 部分一致（大文字小文字を区別しない）
+//! - `/pattern/`形式の行: 正規表現
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use sqlx::SqlitePool;
+use std::sync::{Arc, RwLock};
+
+use super::channel_ban::split_banned;
+use super::profanity::mask_for_broadcast;
+use super::types::ChatMessage;
+use super::write_queue;
+
+/// ブロックリストの最大パターン数
+pub const MAX_BLOCKLIST_PATTERNS: usize = 500;
+
+/// ブロックリストの1パターン
+#[derive(Debug, Clone)]
+enum BlockPattern {
+    /// 部分一致（小文字化済み）
+    Substring(String),
+    /// 正規表現
+    Regex(Regex),
+}
+
+impl BlockPattern {
+    fn matches(&self, text: &str, lowercase_text: &str) -> bool {
+        match self {
+            BlockPattern::Substring(pattern) => lowercase_text.contains(pattern.as_str()),
+            BlockPattern::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+/// コメントのブロックリストフィルタ
+#[derive(Debug, Clone, Default)]
+pub struct CommentFilter {
+    patterns: Vec<BlockPattern>,
+}
+
+impl CommentFilter {
+    /// ブロックリスト（1行1パターン）をパース
+    ///
+    /// 正規表現が不正な場合は行番号付きのエラーを返す
+    pub fn parse(blocklist: &str) -> Result<Self, String> {
+        let mut patterns = Vec::new();
+
+        for (index, line) in blocklist.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let pattern = match line
+                .strip_prefix('/')
+                .and_then(|rest| rest.strip_suffix('/'))
+                .filter(|inner| !inner.is_empty())
+            {
+                Some(inner) => Regex::new(inner)
+                    .map(BlockPattern::Regex)
+                    .map_err(|e| format!("{}行目の正規表現が不正です: {}", index + 1, e))?,
+                None => BlockPattern::Substring(line.to_lowercase()),
+            };
+            patterns.push(pattern);
+        }
+
+        if patterns.len() > MAX_BLOCKLIST_PATTERNS {
+            return Err(format!(
+                "ブロックリストは{}件以下で指定してください: {}件",
+                MAX_BLOCKLIST_PATTERNS,
+                patterns.len()
+            ));
+        }
+
+        Ok(Self { patterns })
+    }
+
+    /// コメント本文がブロックリストに一致するか
+    pub fn is_blocked(&self, text: &str) -> bool {
+        if self.patterns.is_empty() {
+            return false;
+        }
+        let lowercase_text = text.to_lowercase();
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.matches(text, &lowercase_text))
+    }
+
+    /// コメントをブロードキャスト対象と除外対象（IDのみ）に分ける
+    pub fn split(&self, messages: Vec<ChatMessage>) -> (Vec<ChatMessage>, Vec<String>) {
+        if self.patterns.is_empty() {
+            return (messages, Vec::new());
+        }
+
+        let mut visible = Vec::with_capacity(messages.len());
+        let mut blocked_ids = Vec::new();
+        for message in messages {
+            if self.is_blocked(&message.message) {
+                blocked_ids.push(message.id);
+            } else {
+                visible.push(message);
+            }
+        }
+        (visible, blocked_ids)
+    }
+}
+
+/// 現在のブロックリストフィルタ
+/// 起動時にDBから読み込み、設定コマンドで更新される
+static COMMENT_FILTER: Lazy<RwLock<Arc<CommentFilter>>> =
+    Lazy::new(|| RwLock::new(Arc::new(CommentFilter::default())));
+
+/// 現在のブロックリストフィルタを取得
+pub fn current_filter() -> Arc<CommentFilter> {
+    COMMENT_FILTER
+        .read()
+        .map(|filter| Arc::clone(&filter))
+        .unwrap_or_default()
+}
+
+/// ブロックリストフィルタを更新（以降に取得したコメントから適用）
+pub fn set_filter(filter: CommentFilter) {
+    match COMMENT_FILTER.write() {
+        Ok(mut current) => *current = Arc::new(filter),
+        Err(e) => log::error!("Failed to update comment filter: {}", e),
+    }
+}
+
+/// コメントをDB書き込みキューに積み、BANリストと現在のブロックリストでフィルタする
+/// （ブロードキャスト対象のみ返す）
+///
+/// 除外したコメントもフラグ付きで保存される。書き込みは待たない。
+/// 返すコメントは不適切語をマスク済み（DBには元の本文を保存する）。
+pub fn queue_save_and_filter(pool: &SqlitePool, messages: Vec<ChatMessage>) -> Vec<ChatMessage> {
+    let all_messages = messages.clone();
+
+    let (messages, mut filtered_ids) = split_banned(messages);
+    if !filtered_ids.is_empty() {
+        log::info!("Filtered {} comments from banned channels", filtered_ids.len());
+    }
+
+    let (visible, blocked_ids) = current_filter().split(messages);
+    if !blocked_ids.is_empty() {
+        log::info!("Filtered {} comments by blocklist", blocked_ids.len());
+    }
+    filtered_ids.extend(blocked_ids);
+
+    write_queue::enqueue(pool, all_messages, filtered_ids);
+    mask_for_broadcast(visible)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_message(id: &str, text: &str) -> ChatMessage {
+        ChatMessage::test_message(id).with_text(text)
+    }
+
+    #[test]
+    fn test_parse_blocklist() {
+        let filter = CommentFilter::parse("  spam  \n\n/b[a4]d+word/\n").unwrap();
+        assert_eq!(filter.patterns.len(), 2);
+
+        // 部分一致は大文字小文字を区別しない
+        assert!(filter.is_blocked("This is SPAM!"));
+        assert!(filter.is_blocked("b4dddword here"));
+        assert!(!filter.is_blocked("こんにちは"));
+
+        assert!(CommentFilter::parse("").unwrap().patterns.is_empty());
+    }
+
+    #[test]
+    fn test_parse_blocklist_invalid_regex() {
+        let err = CommentFilter::parse("ok\n/(unclosed/").unwrap_err();
+        assert!(err.contains("2行目"));
+
+        // "/"だけの行は部分一致として扱う
+        let filter = CommentFilter::parse("/").unwrap();
+        assert!(filter.is_blocked("a/b"));
+    }
+
+    #[test]
+    fn test_parse_blocklist_too_many_patterns() {
+        let blocklist = (0..=MAX_BLOCKLIST_PATTERNS)
+            .map(|i| format!("word{}", i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(CommentFilter::parse(&blocklist).is_err());
+    }
+
+    #[test]
+    fn test_blocked_comment_removed_from_broadcast() {
+        let messages = vec![
+            text_message("c1", "こんにちは"),
+            text_message("c2", "buy cheap SPAM now"),
+            text_message("c3", "よろしく"),
+        ];
+
+        let filter = CommentFilter::parse("spam").unwrap();
+        let (visible, blocked_ids) = filter.split(messages);
+
+        let visible_ids: Vec<&str> = visible.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(visible_ids, vec!["c1", "c3"]);
+        // 除外したコメントのIDはフラグ付けのために返す
+        assert_eq!(blocked_ids, vec!["c2".to_string()]);
+    }
+}
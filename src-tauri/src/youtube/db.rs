@@ -1,5 +1,6 @@
 //! YouTube関連のDB操作を共通化したモジュール
 
+use std::collections::{BTreeMap, HashSet};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
@@ -8,6 +9,7 @@ use sqlx::SqlitePool;
 use tokio::time::{sleep, timeout};
 
 use super::types::{ChatMessage, MessageType};
+use crate::server::types::{CurrencyTotal, SessionRecapPayload, TopSupporter};
 
 // =============================================================================
 // メトリクス（将来的にPrometheus等への連携を想定）
@@ -70,7 +72,7 @@ pub struct SaveCommentsResult {
     pub saved: usize,
     /// 保存に失敗したメッセージ数（エラー発生）
     pub failed: usize,
-    /// 予算超過でスキップされたメッセージ数
+    /// 予算超過・スキーマ未準備でスキップされたメッセージ数
     pub skipped: usize,
 }
 
@@ -153,6 +155,18 @@ pub async fn save_comments_to_db_with_timeout(
         return SaveCommentsResult::default();
     }
 
+    // スキーマ自己診断に失敗している場合は書き込まない（"no such table"の連続エラーを避ける）
+    if !crate::db::is_schema_ready() {
+        log::warn!(
+            "save_comments_to_db: Database schema is not ready, skipping {} messages",
+            messages.len()
+        );
+        return SaveCommentsResult {
+            skipped: messages.len(),
+            ..Default::default()
+        };
+    }
+
     let start_time = Instant::now();
     let mut result = SaveCommentsResult::default();
 
@@ -1002,6 +1016,331 @@ where
     Ok(())
 }
 
+/// ブロックリストに一致したコメントにフラグを付ける
+///
+/// 保存済みのコメントが対象（`save_comments_to_db`の後に呼び出す）。
+/// 更新した件数を返す。
+pub async fn mark_comments_filtered(pool: &SqlitePool, youtube_ids: &[String]) -> Result<u64, String> {
+    let mut updated = 0;
+    for youtube_id in youtube_ids {
+        let result = sqlx::query("UPDATE comment_logs SET is_filtered = 1 WHERE youtube_id = ?")
+            .bind(youtube_id)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("DB error: {}", e))?;
+        updated += result.rows_affected();
+    }
+    Ok(updated)
+}
+
+// =============================================================================
+// 保持期間による削除
+// =============================================================================
+
+/// 古いコメントログ削除の1バッチあたりの行数
+/// 1回のDELETEで書き込みロックを長時間保持しないよう小分けにする
+const PRUNE_BATCH_SIZE: i64 = 1_000;
+
+/// バッチ間の待機時間（ミリ秒）
+/// 保存処理（save_comments_to_db）に書き込みロックを譲るための間隔
+const PRUNE_BATCH_PAUSE_MS: u64 = 10;
+
+/// 指定時刻より前のコメントログを削除し、削除した件数を返す
+///
+/// `cutoff`はUTCのRFC3339文字列（`published_at`と同形式）で、文字列比較で対象を絞る。
+/// `PRUNE_BATCH_SIZE`件ずつ削除し、SQLITE_BUSYの場合はバックオフ後に同じバッチを再試行する。
+/// 再試行しても解消しない場合はそこで打ち切り、それまでの件数を返す（残りは次回削除）。
+/// 1件以上削除した場合は、空き領域を回収するためにVACUUMを実行する。
+pub async fn prune_comment_logs(pool: &SqlitePool, cutoff: &str) -> Result<u64, String> {
+    let mut removed = 0u64;
+    let mut attempt = 1;
+    let mut backoff_ms = INITIAL_BACKOFF_MS;
+
+    loop {
+        // SQLiteの標準ビルドはDELETE ... LIMITに対応しないため、rowidのサブクエリで件数を絞る
+        let result = sqlx::query(
+            r#"DELETE FROM comment_logs WHERE rowid IN (
+                SELECT rowid FROM comment_logs WHERE published_at < ? LIMIT ?
+            )"#,
+        )
+        .bind(cutoff)
+        .bind(PRUNE_BATCH_SIZE)
+        .execute(pool)
+        .await;
+
+        match result {
+            Ok(result) => {
+                let deleted = result.rows_affected();
+                removed += deleted;
+                if deleted < PRUNE_BATCH_SIZE as u64 {
+                    break;
+                }
+                attempt = 1;
+                backoff_ms = INITIAL_BACKOFF_MS;
+                sleep(Duration::from_millis(PRUNE_BATCH_PAUSE_MS)).await;
+            }
+            Err(e) if is_sqlite_busy_error(&e) && attempt < MAX_ATTEMPTS => {
+                log::debug!(
+                    "prune_comment_logs: SQLITE_BUSY on attempt {}/{}, retrying after {}ms",
+                    attempt,
+                    MAX_ATTEMPTS,
+                    backoff_ms
+                );
+                sleep(Duration::from_millis(backoff_ms)).await;
+                attempt += 1;
+                backoff_ms = (backoff_ms * 2).min(MAX_BACKOFF_MS);
+            }
+            Err(e) if is_sqlite_busy_error(&e) => {
+                log::warn!(
+                    "prune_comment_logs: SQLITE_BUSY persisted, stopping after {} rows (rest will be pruned next time)",
+                    removed
+                );
+                break;
+            }
+            Err(e) => return Err(format!("DB error: {}", e)),
+        }
+    }
+
+    if removed > 0 {
+        vacuum_after_prune(pool).await;
+    }
+    Ok(removed)
+}
+
+/// 削除後の空き領域を回収
+///
+/// auto_vacuum=INCREMENTALの場合はincremental_vacuum、それ以外はVACUUMを実行する。
+/// 他の接続が使用中で失敗しても削除自体は完了しているため、警告のみ出す。
+async fn vacuum_after_prune(pool: &SqlitePool) {
+    let auto_vacuum: Result<(i64,), sqlx::Error> =
+        sqlx::query_as("PRAGMA auto_vacuum").fetch_one(pool).await;
+
+    // 2 = INCREMENTAL
+    let sql = match auto_vacuum {
+        Ok((2,)) => "PRAGMA incremental_vacuum",
+        _ => "VACUUM",
+    };
+    if let Err(e) = sqlx::query(sql).execute(pool).await {
+        log::warn!("Failed to reclaim space after pruning comment logs ({}): {}", sql, e);
+    }
+}
+
+// =============================================================================
+// セッション集計
+// =============================================================================
+
+/// スパチャの集計結果
+pub struct SuperchatAggregate {
+    /// 通貨別合計（通貨コード順）
+    pub totals: Vec<CurrencyTotal>,
+    /// 日本円換算の合計
+    pub total_jpy: u64,
+    /// 件数
+    pub count: u32,
+    /// 最多支援者（日本円換算のスパチャ合計が最大の投稿者）
+    pub top_supporter: Option<TopSupporter>,
+}
+
+/// 指定範囲のcomment_logsからスパチャを集計
+///
+/// `since`以上`until`未満（`until`省略時は上限なし）の`published_at`が対象。
+/// 時刻はUTCのRFC3339文字列（`published_at`と同形式）で、文字列比較で範囲を絞る。
+/// スパチャ金額は`message_data`の表示文字列から推定し、最多支援者は日本円換算で判定する。
+pub async fn get_superchat_aggregate(
+    pool: &SqlitePool,
+    since: &str,
+    until: Option<&str>,
+) -> Result<SuperchatAggregate, sqlx::Error> {
+    let superchat_rows: Vec<(String, String, Option<String>)> = sqlx::query_as(
+        r#"SELECT author_name, author_channel_id, message_data FROM comment_logs
+        WHERE published_at >= ? AND (? IS NULL OR published_at < ?) AND message_type = 'superChat'
+        ORDER BY published_at"#,
+    )
+    .bind(since)
+    .bind(until)
+    .bind(until)
+    .fetch_all(pool)
+    .await?;
+
+    // 通貨別合計（通貨コード順で安定させる）
+    let mut totals: BTreeMap<String, CurrencyTotal> = BTreeMap::new();
+    // 投稿者別の日本円換算合計（初出順を保持して同額時は先着を優先）
+    let mut supporters: Vec<TopSupporter> = Vec::new();
+    let mut total_jpy = 0;
+    let mut count = 0;
+
+    for (author_name, author_channel_id, message_data) in superchat_rows {
+        let Some(MessageType::SuperChat {
+            amount,
+            currency,
+            amount_micros,
+        }) = message_data
+            .as_deref()
+            .and_then(|data| serde_json::from_str::<MessageType>(data).ok())
+        else {
+            log::warn!("Skipping superchat with unreadable message_data in aggregate");
+            continue;
+        };
+
+        let amount_micros = amount_micros.unwrap_or_else(|| {
+            crate::superchat::parse_amount_micros_for_currency(&amount, &currency)
+        });
+        let total = totals.entry(currency.clone()).or_insert_with(|| CurrencyTotal {
+            currency: currency.clone(),
+            amount_micros: 0,
+            count: 0,
+        });
+        total.amount_micros += amount_micros;
+        total.count += 1;
+
+        let jpy = crate::superchat::convert_to_jpy(amount_micros, &currency);
+        total_jpy += jpy;
+        count += 1;
+        match supporters
+            .iter_mut()
+            .find(|s| s.author_channel_id == author_channel_id)
+        {
+            Some(supporter) => supporter.total_jpy += jpy,
+            None => supporters.push(TopSupporter {
+                author_name,
+                author_channel_id,
+                total_jpy: jpy,
+            }),
+        }
+    }
+
+    let top_supporter = supporters.into_iter().fold(None, |best: Option<TopSupporter>, s| {
+        match best {
+            Some(b) if b.total_jpy >= s.total_jpy => Some(b),
+            _ => Some(s),
+        }
+    });
+
+    Ok(SuperchatAggregate {
+        totals: totals.into_values().collect(),
+        total_jpy,
+        count,
+        top_supporter,
+    })
+}
+
+/// 指定時刻以降のcomment_logsから配信セッションのまとめを集計
+///
+/// `since`はUTCのRFC3339文字列（`published_at`と同形式）で、文字列比較で範囲を絞る。
+/// スパチャの集計は[`get_superchat_aggregate`]を参照。
+pub async fn get_session_recap(
+    pool: &SqlitePool,
+    since: &str,
+) -> Result<SessionRecapPayload, sqlx::Error> {
+    let (comment_count, unique_chatters): (i64, i64) = sqlx::query_as(
+        "SELECT COUNT(*), COUNT(DISTINCT author_channel_id) FROM comment_logs WHERE published_at >= ?",
+    )
+    .bind(since)
+    .fetch_one(pool)
+    .await?;
+
+    let superchats = get_superchat_aggregate(pool, since, None).await?;
+
+    let member_rows: Vec<(String, String)> = sqlx::query_as(
+        r#"SELECT author_name, author_channel_id FROM comment_logs
+        WHERE published_at >= ? AND message_type = 'membership'
+        ORDER BY published_at"#,
+    )
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+
+    let mut seen_members = HashSet::new();
+    let new_members = member_rows
+        .into_iter()
+        .filter(|(_, channel_id)| seen_members.insert(channel_id.clone()))
+        .map(|(name, _)| name)
+        .collect();
+
+    Ok(SessionRecapPayload {
+        since: since.to_string(),
+        comment_count,
+        unique_chatters,
+        superchat_totals: superchats.totals,
+        top_supporter: superchats.top_supporter,
+        new_members,
+    })
+}
+
+// =============================================================================
+// 初見判定
+// =============================================================================
+
+/// 指定時刻より前に投稿者のコメントがcomment_logsにあるか
+///
+/// `before`はUTCのRFC3339文字列（`published_at`と同形式）で、文字列比較で範囲を絞る。
+/// `idx_comment_logs_author_channel_id`インデックスで1件だけ調べる。
+pub async fn has_comment_before(
+    pool: &SqlitePool,
+    author_channel_id: &str,
+    before: &str,
+) -> Result<bool, sqlx::Error> {
+    let row: Option<(i64,)> = sqlx::query_as(
+        "SELECT 1 FROM comment_logs WHERE author_channel_id = ? AND published_at < ? LIMIT 1",
+    )
+    .bind(author_channel_id)
+    .bind(before)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.is_some())
+}
+
+// =============================================================================
+// 固定表示
+// =============================================================================
+
+/// `get_comment_by_id`で取得する列
+/// （message, author_name, author_channel_id, author_image_url, is_owner, is_moderator, is_member, message_data, published_at）
+type CommentRow = (String, String, String, Option<String>, bool, bool, bool, Option<String>, String);
+
+/// comment_logsからメッセージIDでコメントを取得（固定表示用）
+///
+/// 見つからない場合・ブロックリストに一致したコメントの場合はNone。
+/// 保存していない項目（認証済み・絵文字情報）は既定値で復元し、バッジは権限フラグから付ける。
+pub async fn get_comment_by_id(pool: &SqlitePool, youtube_id: &str) -> Result<Option<ChatMessage>, sqlx::Error> {
+    let row: Option<CommentRow> = sqlx::query_as(
+        r#"SELECT message, author_name, author_channel_id, author_image_url,
+            is_owner, is_moderator, is_member, message_data, published_at
+        FROM comment_logs
+        WHERE youtube_id = ? AND is_filtered = 0"#,
+    )
+    .bind(youtube_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(
+        |(message, author_name, author_channel_id, author_image_url, is_owner, is_moderator, is_member, message_data, published_at)| {
+            let message_type = message_data
+                .as_deref()
+                .and_then(|data| serde_json::from_str::<MessageType>(data).ok())
+                .unwrap_or(MessageType::Text);
+            let published_at = chrono::DateTime::parse_from_rfc3339(&published_at)
+                .map(|at| at.with_timezone(&chrono::Utc))
+                .unwrap_or_else(|_| chrono::Utc::now());
+            ChatMessage {
+                id: youtube_id.to_string(),
+                message,
+                author_name,
+                author_channel_id,
+                author_image_url: author_image_url.unwrap_or_default(),
+                published_at,
+                is_owner,
+                is_moderator,
+                is_member,
+                is_verified: false,
+                badges: super::types::badges_from_flags(is_owner, is_moderator, is_member, false),
+                message_type,
+                message_runs: None,
+            }
+        },
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1010,18 +1349,10 @@ mod tests {
 
     fn create_test_message(id: &str, message: &str) -> ChatMessage {
         ChatMessage {
-            id: id.to_string(),
-            message: message.to_string(),
-            message_runs: None,
-            author_name: "TestUser".to_string(),
-            author_channel_id: "UC123".to_string(),
             author_image_url: "https://example.com/icon.png".to_string(),
-            message_type: MessageType::Text,
-            is_owner: false,
-            is_moderator: false,
-            is_member: false,
-            is_verified: false,
-            published_at: Utc::now(),
+            ..ChatMessage::test_message(id)
+                .with_text(message)
+                .with_author("TestUser", "UC123")
         }
     }
 
@@ -2605,4 +2936,227 @@ mod tests {
             "All messages should be skipped with very short timeout"
         );
     }
+
+    // =========================================================================
+    // セッション集計テスト
+    // =========================================================================
+
+    /// 投稿者・種別・投稿時刻を指定してテストメッセージを作成
+    fn create_recap_message(
+        id: &str,
+        author: &str,
+        message_type: MessageType,
+        published_at: chrono::DateTime<Utc>,
+    ) -> ChatMessage {
+        create_test_message(id, "recap")
+            .with_author(author, &format!("UC_{}", author))
+            .with_type(message_type)
+            .with_published_at(published_at)
+    }
+
+    #[tokio::test]
+    async fn test_get_session_recap_from_populated_session() {
+        use tempfile::NamedTempFile;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        let session_start = Utc::now() - chrono::Duration::hours(1);
+        let t = |mins: i64| session_start + chrono::Duration::minutes(mins);
+        let superchat = |amount: &str, currency: &str| MessageType::SuperChat {
+            amount: amount.to_string(),
+            currency: currency.to_string(),
+            amount_micros: None,
+        };
+
+        let messages = vec![
+            // セッション開始前のコメント（集計対象外）
+            create_recap_message(
+                "old",
+                "Old",
+                superchat("¥50,000", "JPY"),
+                session_start - chrono::Duration::hours(2),
+            ),
+            create_recap_message("c1", "Alice", MessageType::Text, t(1)),
+            create_recap_message("c2", "Bob", MessageType::Text, t(2)),
+            create_recap_message("s1", "Alice", superchat("¥1,000", "JPY"), t(3)),
+            create_recap_message("s2", "Bob", superchat("$10.00", "USD"), t(4)),
+            create_recap_message("s3", "Alice", superchat("¥500", "JPY"), t(5)),
+            create_recap_message(
+                "m1",
+                "Carol",
+                MessageType::Membership { level: "新規".to_string() },
+                t(6),
+            ),
+            create_recap_message(
+                "m2",
+                "Carol",
+                MessageType::Membership { level: "新規".to_string() },
+                t(7),
+            ),
+        ];
+        let result = save_comments_to_db(&pool, &messages).await;
+        assert_eq!(result.saved, messages.len());
+
+        let recap = get_session_recap(&pool, &session_start.to_rfc3339())
+            .await
+            .unwrap();
+
+        assert_eq!(recap.comment_count, 7);
+        assert_eq!(recap.unique_chatters, 3);
+        assert_eq!(
+            recap.superchat_totals,
+            vec![
+                CurrencyTotal {
+                    currency: "JPY".to_string(),
+                    amount_micros: 1_500_000_000,
+                    count: 2,
+                },
+                CurrencyTotal {
+                    currency: "USD".to_string(),
+                    amount_micros: 10_000_000,
+                    count: 1,
+                },
+            ]
+        );
+        // Alice: ¥1,500, Bob: $10 = ¥1,500 → 同額は先着のAlice
+        assert_eq!(
+            recap.top_supporter,
+            Some(TopSupporter {
+                author_name: "Alice".to_string(),
+                author_channel_id: "UC_Alice".to_string(),
+                total_jpy: 1500,
+            })
+        );
+        assert_eq!(recap.new_members, vec!["Carol".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_has_comment_before() {
+        use tempfile::NamedTempFile;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        let now = Utc::now();
+        let messages = vec![create_recap_message(
+            "c1",
+            "Alice",
+            MessageType::Text,
+            now - chrono::Duration::minutes(5),
+        )];
+        assert_eq!(save_comments_to_db(&pool, &messages).await.saved, 1);
+
+        assert!(has_comment_before(&pool, "UC_Alice", &now.to_rfc3339()).await.unwrap());
+        // 自分自身のコメント（同時刻）は含めない
+        let first_at = (now - chrono::Duration::minutes(5)).to_rfc3339();
+        assert!(!has_comment_before(&pool, "UC_Alice", &first_at).await.unwrap());
+        assert!(!has_comment_before(&pool, "UC_Bob", &now.to_rfc3339()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_get_comment_by_id() {
+        use tempfile::NamedTempFile;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        let published_at = Utc::now() - chrono::Duration::minutes(5);
+        let mut text = create_recap_message("c1", "Alice", MessageType::Text, published_at);
+        text.is_moderator = true;
+        let superchat = MessageType::SuperChat {
+            amount: "¥1,000".to_string(),
+            currency: "JPY".to_string(),
+            amount_micros: Some(1_000_000_000),
+        };
+        let messages = vec![
+            text,
+            create_recap_message("c2", "Bob", superchat.clone(), published_at),
+            create_recap_message("c3", "Carol", MessageType::Text, published_at),
+        ];
+        assert_eq!(save_comments_to_db(&pool, &messages).await.saved, 3);
+        mark_comments_filtered(&pool, &["c3".to_string()]).await.unwrap();
+
+        let comment = get_comment_by_id(&pool, "c1").await.unwrap().unwrap();
+        assert_eq!(comment.author_name, "Alice");
+        assert_eq!(comment.message, "recap");
+        assert_eq!(comment.badges, vec!["moderator".to_string()]);
+        assert_eq!(comment.published_at.timestamp(), published_at.timestamp());
+        assert!(matches!(comment.message_type, MessageType::Text));
+
+        let comment = get_comment_by_id(&pool, "c2").await.unwrap().unwrap();
+        assert!(matches!(comment.message_type, MessageType::SuperChat { .. }));
+
+        // ブロックリストに一致したコメント・存在しないコメントは取得しない
+        assert!(get_comment_by_id(&pool, "c3").await.unwrap().is_none());
+        assert!(get_comment_by_id(&pool, "unknown").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_session_recap_empty_session() {
+        use tempfile::NamedTempFile;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        let recap = get_session_recap(&pool, &Utc::now().to_rfc3339())
+            .await
+            .unwrap();
+
+        assert_eq!(recap.comment_count, 0);
+        assert_eq!(recap.unique_chatters, 0);
+        assert!(recap.superchat_totals.is_empty());
+        assert!(recap.top_supporter.is_none());
+        assert!(recap.new_members.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prune_comment_logs_in_batches() {
+        use tempfile::NamedTempFile;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        // 1バッチを超える件数の古いコメント
+        let old_count = PRUNE_BATCH_SIZE + 5;
+        sqlx::query(
+            r#"WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < ?)
+            INSERT INTO comment_logs (id, youtube_id, message, author_name, author_channel_id, published_at)
+            SELECT 'old' || i, 'old' || i, 'old', 'Old', 'UC_old', '2020-01-01T00:00:00+00:00' FROM n"#,
+        )
+        .bind(old_count)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let recent = vec![
+            create_recap_message("c1", "Alice", MessageType::Text, Utc::now()),
+            create_recap_message("c2", "Bob", MessageType::Text, Utc::now()),
+        ];
+        save_comments_to_db(&pool, &recent).await;
+
+        let cutoff = (Utc::now() - chrono::Duration::days(30)).to_rfc3339();
+        let removed = prune_comment_logs(&pool, &cutoff).await.unwrap();
+        assert_eq!(removed, old_count as u64);
+
+        let remaining: Vec<(String,)> =
+            sqlx::query_as("SELECT youtube_id FROM comment_logs ORDER BY youtube_id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(remaining, vec![("c1".to_string(),), ("c2".to_string(),)]);
+
+        // 対象がなければ0件
+        assert_eq!(prune_comment_logs(&pool, &cutoff).await.unwrap(), 0);
+    }
 }
@@ -0,0 +1,256 @@
+//! InnerTubeポーリングのメッセージ重複排除
+//!
+//! InnerTubeは同じメッセージを複数回のレスポンスで返すことがあるため、
+//! 配信済みのメッセージIDを記録して再配信を防ぐ。
+//!
+//! - IDは一定時間（デフォルト10分）経過で破棄する（チャットの流速によらず同じ期間を保持）
+//! - メモリ保護のため保持件数にも上限を設ける（超えた場合は古いIDから破棄）
+//! - 重複として除外した件数を計測し、保持期間が短すぎないか確認できるようにする
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use super::types::ChatMessage;
+
+/// IDの保持期間のデフォルト（分）
+pub const DEFAULT_DEDUPE_WINDOW_MINUTES: u32 = 10;
+
+/// IDの保持期間の上限（分）
+pub const MAX_DEDUPE_WINDOW_MINUTES: u32 = 180;
+
+/// 保持するIDの最大件数のデフォルト
+pub const DEFAULT_MAX_SEEN_IDS: usize = 10_000;
+
+/// 保持するIDの最大件数の設定範囲
+pub const MIN_MAX_SEEN_IDS: usize = 1_000;
+pub const MAX_MAX_SEEN_IDS: usize = 200_000;
+
+/// 重複排除の設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DedupeSettings {
+    /// IDの保持期間（分）
+    pub window_minutes: u32,
+    /// 保持するIDの最大件数（保持期間内でも超えた分は古いIDから破棄）
+    pub max_ids: usize,
+}
+
+impl Default for DedupeSettings {
+    fn default() -> Self {
+        Self {
+            window_minutes: DEFAULT_DEDUPE_WINDOW_MINUTES,
+            max_ids: DEFAULT_MAX_SEEN_IDS,
+        }
+    }
+}
+
+impl DedupeSettings {
+    /// 設定値を検証
+    ///
+    /// ## 入力検証
+    /// - 保持期間: 1〜180分
+    /// - 最大件数: 1,000〜200,000件
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_DEDUPE_WINDOW_MINUTES).contains(&self.window_minutes) {
+            return Err(format!(
+                "重複排除の保持期間は1〜{}分で指定してください: {}",
+                MAX_DEDUPE_WINDOW_MINUTES, self.window_minutes
+            ));
+        }
+        if !(MIN_MAX_SEEN_IDS..=MAX_MAX_SEEN_IDS).contains(&self.max_ids) {
+            return Err(format!(
+                "重複排除の最大件数は{}〜{}で指定してください: {}",
+                MIN_MAX_SEEN_IDS, MAX_MAX_SEEN_IDS, self.max_ids
+            ));
+        }
+        Ok(())
+    }
+
+    fn window(&self) -> Duration {
+        Duration::from_secs(u64::from(self.window_minutes) * 60)
+    }
+}
+
+/// 重複排除の設定
+/// 起動時にDBから読み込み、設定コマンドで更新される（実行中のポーリングにも次回の取得から適用）
+static DEDUPE_SETTINGS: Lazy<RwLock<DedupeSettings>> = Lazy::new(|| RwLock::new(DedupeSettings::default()));
+
+/// 重複として除外したメッセージ数（ポーリング開始時にリセット）
+static SUPPRESSED_DUPLICATES: AtomicU64 = AtomicU64::new(0);
+
+/// 現在保持しているIDの件数
+static TRACKED_IDS: AtomicUsize = AtomicUsize::new(0);
+
+/// 現在の重複排除の設定を取得
+pub fn dedupe_settings() -> DedupeSettings {
+    DEDUPE_SETTINGS.read().map(|settings| *settings).unwrap_or_default()
+}
+
+/// 重複排除の設定を更新
+pub fn set_dedupe_settings(settings: DedupeSettings) {
+    match DEDUPE_SETTINGS.write() {
+        Ok(mut current) => *current = settings,
+        Err(e) => log::error!("Failed to update dedupe settings: {}", e),
+    }
+}
+
+/// 重複排除の統計
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DedupeStats {
+    /// ポーリング開始から重複として除外したメッセージ数
+    pub suppressed_duplicates: u64,
+    /// 現在保持しているIDの件数
+    pub tracked_ids: usize,
+    /// 現在の設定
+    pub settings: DedupeSettings,
+}
+
+/// 重複排除の統計を取得
+pub fn dedupe_stats() -> DedupeStats {
+    DedupeStats {
+        suppressed_duplicates: SUPPRESSED_DUPLICATES.load(Ordering::Relaxed),
+        tracked_ids: TRACKED_IDS.load(Ordering::Relaxed),
+        settings: dedupe_settings(),
+    }
+}
+
+/// 配信済みのメッセージID（ポーリングループごとに作成）
+#[derive(Debug, Default)]
+pub struct SeenMessageIds {
+    ids: HashSet<String>,
+    /// 記録した時刻順のID（破棄に使用）
+    order: VecDeque<(Instant, String)>,
+}
+
+impl SeenMessageIds {
+    /// 空の記録を作成し、統計をリセット
+    pub fn new() -> Self {
+        SUPPRESSED_DUPLICATES.store(0, Ordering::Relaxed);
+        TRACKED_IDS.store(0, Ordering::Relaxed);
+        Self::default()
+    }
+
+    /// 未配信のメッセージのみ返す（現在の設定で古いIDを破棄する）
+    pub fn filter_new(&mut self, messages: Vec<ChatMessage>) -> Vec<ChatMessage> {
+        self.filter_new_at(messages, Instant::now(), dedupe_settings())
+    }
+
+    fn filter_new_at(
+        &mut self,
+        messages: Vec<ChatMessage>,
+        now: Instant,
+        settings: DedupeSettings,
+    ) -> Vec<ChatMessage> {
+        self.evict(now, settings);
+
+        let total = messages.len();
+        // insertはtrueを返す＝新規追加、falseは既存（同一レスポンス内重複を含む）
+        let new_messages: Vec<ChatMessage> = messages
+            .into_iter()
+            .filter(|message| {
+                if self.ids.insert(message.id.clone()) {
+                    self.order.push_back((now, message.id.clone()));
+                    true
+                } else {
+                    false
+                }
+            })
+            .collect();
+
+        // 件数の上限を超えた分は古いIDから破棄
+        while self.ids.len() > settings.max_ids {
+            let Some((_, oldest_id)) = self.order.pop_front() else {
+                break;
+            };
+            self.ids.remove(&oldest_id);
+        }
+
+        let suppressed = total - new_messages.len();
+        if suppressed > 0 {
+            SUPPRESSED_DUPLICATES.fetch_add(suppressed as u64, Ordering::Relaxed);
+        }
+        TRACKED_IDS.store(self.ids.len(), Ordering::Relaxed);
+        new_messages
+    }
+
+    /// 保持期間を過ぎたIDを破棄
+    fn evict(&mut self, now: Instant, settings: DedupeSettings) {
+        let window = settings.window();
+        while let Some((seen_at, _)) = self.order.front() {
+            if now.saturating_duration_since(*seen_at) < window {
+                break;
+            }
+            if let Some((_, id)) = self.order.pop_front() {
+                self.ids.remove(&id);
+            }
+        }
+    }
+
+    /// 保持しているIDの件数
+    pub(crate) fn len(&self) -> usize {
+        self.ids.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: &str) -> ChatMessage {
+        ChatMessage::test_message(id)
+            .with_text("test")
+            .with_author("TestUser", "UC123")
+    }
+
+    fn ids(messages: &[ChatMessage]) -> Vec<&str> {
+        messages.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_filter_new_suppresses_duplicates_within_window() {
+        let settings = DedupeSettings::default();
+        let start = Instant::now();
+        let mut seen = SeenMessageIds::default();
+
+        let first = seen.filter_new_at(vec![message("a"), message("b"), message("a")], start, settings);
+        assert_eq!(ids(&first), vec!["a", "b"]);
+
+        let later = start + Duration::from_secs(60);
+        let second = seen.filter_new_at(vec![message("b"), message("c")], later, settings);
+        assert_eq!(ids(&second), vec!["c"]);
+        assert_eq!(seen.len(), 3);
+
+        // 保持期間を過ぎたIDは破棄され、再び新規として扱われる
+        let expired = start + settings.window();
+        let third = seen.filter_new_at(vec![message("a"), message("c")], expired, settings);
+        assert_eq!(ids(&third), vec!["a"]);
+    }
+
+    #[test]
+    fn test_filter_new_caps_tracked_ids() {
+        let settings = DedupeSettings { window_minutes: 10, max_ids: 2 };
+        let now = Instant::now();
+        let mut seen = SeenMessageIds::default();
+
+        seen.filter_new_at(vec![message("a"), message("b"), message("c")], now, settings);
+        assert_eq!(seen.len(), 2);
+
+        // 最も古いIDから破棄される
+        let again = seen.filter_new_at(vec![message("a"), message("c")], now, settings);
+        assert_eq!(ids(&again), vec!["a"]);
+    }
+
+    #[test]
+    fn test_validate_dedupe_settings() {
+        assert!(DedupeSettings::default().validate().is_ok());
+        assert!(DedupeSettings { window_minutes: 0, max_ids: 10_000 }.validate().is_err());
+        assert!(DedupeSettings { window_minutes: 181, max_ids: 10_000 }.validate().is_err());
+        assert!(DedupeSettings { window_minutes: 10, max_ids: 999 }.validate().is_err());
+        assert!(DedupeSettings { window_minutes: 10, max_ids: 200_001 }.validate().is_err());
+    }
+}
@@ -0,0 +1,335 @@
+//! 公式API向けのUnicode絵文字の分割
+//!
+//! 公式API（ポーリング・gRPC）は`displayMessage`の本文しか返さず、InnerTubeのような
+//! 絵文字のrunsがない。モードによらずオーバーレイで同じように絵文字を画像表示できるよう、
+//! 本文中のUnicode絵文字を標準の絵文字テーブルで検出して`MessageRun::Emoji`に分割する。
+//!
+//! - 画像はNoto Emoji（fonts.gstatic.com）を使用する（読み込めない場合はオーバーレイが絵文字の文字で表示）
+//! - 肌の色・ZWJ結合・国旗・キーキャップの絵文字は1つの絵文字として扱う
+//! - `:shortcut:`形式のカスタム絵文字は画像が取得できないためテキストのまま
+
+use super::types::{EmojiImage, EmojiInfo, EmojiThumbnail, MessageRun};
+
+/// 絵文字画像のURL（`{}`はコードポイントを`_`で連結した16進数、FE0Fは除く）
+const EMOJI_IMAGE_URL: &str = "https://fonts.gstatic.com/s/e/notoemoji/latest/{}/emoji.svg";
+
+/// 絵文字画像の表示サイズ（InnerTubeの絵文字に合わせる）
+const EMOJI_IMAGE_SIZE: u32 = 24;
+
+/// 異体字セレクタ（絵文字表示）
+const VARIATION_SELECTOR_16: char = '\u{FE0F}';
+
+/// ゼロ幅接合子（ZWJ結合の絵文字）
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+/// 囲みキーキャップ（1️⃣ など）
+const COMBINING_KEYCAP: char = '\u{20E3}';
+
+/// 単独で絵文字として表示される文字（Emoji_Presentation）
+const EMOJI_PRESENTATION_RANGES: &[(u32, u32)] = &[
+    (0x231A, 0x231B),
+    (0x23E9, 0x23EC),
+    (0x23F0, 0x23F0),
+    (0x23F3, 0x23F3),
+    (0x25FD, 0x25FE),
+    (0x2614, 0x2615),
+    (0x2648, 0x2653),
+    (0x267F, 0x267F),
+    (0x2693, 0x2693),
+    (0x26A1, 0x26A1),
+    (0x26AA, 0x26AB),
+    (0x26BD, 0x26BE),
+    (0x26C4, 0x26C5),
+    (0x26CE, 0x26CE),
+    (0x26D4, 0x26D4),
+    (0x26EA, 0x26EA),
+    (0x26F2, 0x26F3),
+    (0x26F5, 0x26F5),
+    (0x26FA, 0x26FA),
+    (0x26FD, 0x26FD),
+    (0x2705, 0x2705),
+    (0x270A, 0x270B),
+    (0x2728, 0x2728),
+    (0x274C, 0x274C),
+    (0x274E, 0x274E),
+    (0x2753, 0x2755),
+    (0x2757, 0x2757),
+    (0x2795, 0x2797),
+    (0x27B0, 0x27B0),
+    (0x27BF, 0x27BF),
+    (0x2B1B, 0x2B1C),
+    (0x2B50, 0x2B50),
+    (0x2B55, 0x2B55),
+    (0x1F004, 0x1F004),
+    (0x1F0CF, 0x1F0CF),
+    (0x1F18E, 0x1F18E),
+    (0x1F191, 0x1F19A),
+    (0x1F1E6, 0x1F1FF),
+    (0x1F201, 0x1F201),
+    (0x1F21A, 0x1F21A),
+    (0x1F22F, 0x1F22F),
+    (0x1F232, 0x1F236),
+    (0x1F238, 0x1F23A),
+    (0x1F250, 0x1F251),
+    (0x1F300, 0x1F320),
+    (0x1F32D, 0x1F335),
+    (0x1F337, 0x1F37C),
+    (0x1F37E, 0x1F393),
+    (0x1F3A0, 0x1F3CA),
+    (0x1F3CF, 0x1F3D3),
+    (0x1F3E0, 0x1F3F0),
+    (0x1F3F4, 0x1F3F4),
+    (0x1F3F8, 0x1F43E),
+    (0x1F440, 0x1F440),
+    (0x1F442, 0x1F4FC),
+    (0x1F4FF, 0x1F53D),
+    (0x1F54B, 0x1F54E),
+    (0x1F550, 0x1F567),
+    (0x1F57A, 0x1F57A),
+    (0x1F595, 0x1F596),
+    (0x1F5A4, 0x1F5A4),
+    (0x1F5FB, 0x1F64F),
+    (0x1F680, 0x1F6C5),
+    (0x1F6CC, 0x1F6CC),
+    (0x1F6D0, 0x1F6D2),
+    (0x1F6D5, 0x1F6D7),
+    (0x1F6DC, 0x1F6DF),
+    (0x1F6EB, 0x1F6EC),
+    (0x1F6F4, 0x1F6FC),
+    (0x1F7E0, 0x1F7EB),
+    (0x1F7F0, 0x1F7F0),
+    (0x1F90C, 0x1F93A),
+    (0x1F93C, 0x1F945),
+    (0x1F947, 0x1F9FF),
+    (0x1FA70, 0x1FA7C),
+    (0x1FA80, 0x1FA89),
+    (0x1FA8F, 0x1FAC6),
+    (0x1FACE, 0x1FADC),
+    (0x1FADF, 0x1FAE9),
+    (0x1FAF0, 0x1FAF8),
+];
+
+/// FE0Fが付いた場合のみ絵文字として表示される文字（❤️・☀️・©️ など）
+const TEXT_DEFAULT_EMOJI_RANGES: &[(u32, u32)] = &[
+    (0x00A9, 0x00A9),
+    (0x00AE, 0x00AE),
+    (0x203C, 0x203C),
+    (0x2049, 0x2049),
+    (0x2122, 0x2122),
+    (0x2139, 0x2139),
+    (0x2194, 0x2199),
+    (0x21A9, 0x21AA),
+    (0x2328, 0x2328),
+    (0x23CF, 0x23CF),
+    (0x23ED, 0x23EF),
+    (0x23F1, 0x23F2),
+    (0x23F8, 0x23FA),
+    (0x24C2, 0x24C2),
+    (0x25AA, 0x25AB),
+    (0x25B6, 0x25B6),
+    (0x25C0, 0x25C0),
+    (0x25FB, 0x25FC),
+    (0x2600, 0x27BF),
+    (0x2934, 0x2935),
+    (0x2B05, 0x2B07),
+    (0x3030, 0x3030),
+    (0x303D, 0x303D),
+    (0x3297, 0x3297),
+    (0x3299, 0x3299),
+    (0x1F000, 0x1FAFF),
+];
+
+/// 肌の色の修飾子
+const SKIN_TONE_RANGE: (u32, u32) = (0x1F3FB, 0x1F3FF);
+
+/// 国旗（地域指示子）
+const REGIONAL_INDICATOR_RANGE: (u32, u32) = (0x1F1E6, 0x1F1FF);
+
+/// サブディビジョン旗のタグ文字（🏴󠁧󠁢󠁥󠁮󠁧󠁿 など）
+const TAG_RANGE: (u32, u32) = (0xE0020, 0xE007F);
+
+fn in_ranges(c: char, ranges: &[(u32, u32)]) -> bool {
+    let c = c as u32;
+    ranges.iter().any(|&(start, end)| (start..=end).contains(&c))
+}
+
+fn in_range(c: char, (start, end): (u32, u32)) -> bool {
+    (start..=end).contains(&(c as u32))
+}
+
+/// 本文をテキストと絵文字のrunsに分割
+///
+/// 絵文字を含まない場合は`None`を返す（オーバーレイは`message`をそのまま表示する）
+pub fn build_message_runs(text: &str) -> Option<Vec<MessageRun>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut runs: Vec<MessageRun> = Vec::new();
+    let mut pending = String::new();
+    let mut has_emoji = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        match match_emoji(&chars, i) {
+            Some(len) => {
+                if !pending.is_empty() {
+                    runs.push(MessageRun::Text { text: std::mem::take(&mut pending) });
+                }
+                let emoji: String = chars[i..i + len].iter().collect();
+                runs.push(MessageRun::Emoji { emoji: emoji_info(emoji) });
+                has_emoji = true;
+                i += len;
+            }
+            None => {
+                pending.push(chars[i]);
+                i += 1;
+            }
+        }
+    }
+
+    if !has_emoji {
+        return None;
+    }
+    if !pending.is_empty() {
+        runs.push(MessageRun::Text { text: pending });
+    }
+    Some(runs)
+}
+
+/// `start`から始まる絵文字の長さ（文字数）を返す（絵文字でなければ`None`）
+fn match_emoji(chars: &[char], start: usize) -> Option<usize> {
+    let first = *chars.get(start)?;
+    let next = chars.get(start + 1).copied();
+
+    // キーキャップ: [0-9#*] FE0F? 20E3
+    if first.is_ascii_digit() || first == '#' || first == '*' {
+        let mut i = start + 1;
+        if next == Some(VARIATION_SELECTOR_16) {
+            i += 1;
+        }
+        return (chars.get(i) == Some(&COMBINING_KEYCAP)).then_some(i + 1 - start);
+    }
+
+    // 国旗: 地域指示子2文字
+    if in_range(first, REGIONAL_INDICATOR_RANGE) {
+        let is_pair = next.is_some_and(|c| in_range(c, REGIONAL_INDICATOR_RANGE));
+        return Some(if is_pair { 2 } else { 1 });
+    }
+
+    let mut i = start + match_element(chars, start)?;
+    // ZWJで結合された絵文字は1つとして扱う（👨‍👩‍👧 など）
+    while chars.get(i) == Some(&ZERO_WIDTH_JOINER) {
+        match match_element(chars, i + 1) {
+            Some(len) => i += 1 + len,
+            None => break,
+        }
+    }
+    Some(i - start)
+}
+
+/// 絵文字1要素（本体 + FE0F・肌の色・タグ）の長さ
+fn match_element(chars: &[char], start: usize) -> Option<usize> {
+    let base = *chars.get(start)?;
+    let mut i = start + 1;
+    let has_vs16 = chars.get(i) == Some(&VARIATION_SELECTOR_16);
+    if has_vs16 {
+        i += 1;
+    }
+
+    let is_emoji = in_ranges(base, EMOJI_PRESENTATION_RANGES)
+        || (has_vs16 && in_ranges(base, TEXT_DEFAULT_EMOJI_RANGES));
+    if !is_emoji {
+        return None;
+    }
+
+    if chars.get(i).is_some_and(|&c| in_range(c, SKIN_TONE_RANGE)) {
+        i += 1;
+    }
+    while chars.get(i).is_some_and(|&c| in_range(c, TAG_RANGE)) {
+        i += 1;
+    }
+    Some(i - start)
+}
+
+/// 絵文字の文字列から`EmojiInfo`を作成
+///
+/// InnerTubeの標準絵文字と同様に、IDとショートカットには絵文字そのものを使う
+/// （画像を読み込めない場合、オーバーレイはショートカットをテキストで表示する）
+fn emoji_info(emoji: String) -> EmojiInfo {
+    let codepoints = emoji
+        .chars()
+        .filter(|&c| c != VARIATION_SELECTOR_16)
+        .map(|c| format!("{:x}", c as u32))
+        .collect::<Vec<_>>()
+        .join("_");
+
+    EmojiInfo {
+        emoji_id: emoji.clone(),
+        shortcuts: vec![emoji],
+        image: EmojiImage {
+            thumbnails: vec![EmojiThumbnail {
+                url: EMOJI_IMAGE_URL.replace("{}", &codepoints),
+                width: EMOJI_IMAGE_SIZE,
+                height: EMOJI_IMAGE_SIZE,
+            }],
+        },
+        is_custom_emoji: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn emoji_ids(runs: &[MessageRun]) -> Vec<&str> {
+        runs.iter()
+            .filter_map(|run| match run {
+                MessageRun::Emoji { emoji } => Some(emoji.emoji_id.as_str()),
+                MessageRun::Text { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_build_message_runs_splits_emoji() {
+        let runs = build_message_runs("こんにちは😀です").unwrap();
+        assert_eq!(runs.len(), 3);
+        assert!(matches!(&runs[0], MessageRun::Text { text } if text == "こんにちは"));
+        assert!(matches!(&runs[2], MessageRun::Text { text } if text == "です"));
+
+        let MessageRun::Emoji { emoji } = &runs[1] else {
+            panic!("Expected Emoji run");
+        };
+        assert_eq!(emoji.emoji_id, "😀");
+        assert_eq!(emoji.shortcuts, vec!["😀"]);
+        assert!(!emoji.is_custom_emoji);
+        assert_eq!(
+            emoji.image.thumbnails[0].url,
+            "https://fonts.gstatic.com/s/e/notoemoji/latest/1f600/emoji.svg"
+        );
+    }
+
+    #[test]
+    fn test_build_message_runs_without_emoji() {
+        assert!(build_message_runs("").is_none());
+        assert!(build_message_runs("草www 123 #tag :_custom:").is_none());
+        // FE0Fなしのテキスト表示の記号は絵文字にしない
+        assert!(build_message_runs("© ♪ ☆").is_none());
+    }
+
+    #[test]
+    fn test_build_message_runs_sequences() {
+        let text = "❤️👍🏽👨‍👩‍👧🇯🇵1️⃣";
+        let runs = build_message_runs(text).unwrap();
+        assert_eq!(emoji_ids(&runs), vec!["❤️", "👍🏽", "👨‍👩‍👧", "🇯🇵", "1️⃣"]);
+        assert_eq!(runs.len(), 5);
+
+        // 画像URLはFE0Fを除いたコードポイント
+        let MessageRun::Emoji { emoji } = &runs[0] else {
+            panic!("Expected Emoji run");
+        };
+        assert!(emoji.image.thumbnails[0].url.contains("/2764/"));
+        let MessageRun::Emoji { emoji } = &runs[2] else {
+            panic!("Expected Emoji run");
+        };
+        assert!(emoji.image.thumbnails[0].url.contains("/1f468_200d_1f469_200d_1f467/"));
+    }
+}
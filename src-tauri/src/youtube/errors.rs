@@ -2,8 +2,9 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum YouTubeError {
+    /// URLはクエリにAPIキーを含むため取り除いて保持する（`From<reqwest::Error>`経由で作成）
     #[error("HTTP request failed: {0}")]
-    HttpError(#[from] reqwest::Error),
+    HttpError(reqwest::Error),
 
     #[error("API key is invalid or missing")]
     InvalidApiKey,
@@ -48,6 +49,39 @@ pub enum YouTubeError {
     InnerTubeContinuationExpired,
 }
 
+/// reqwestのエラーはメッセージにリクエストURL（`key=`クエリ）を含むため、
+/// URLを取り除いてから保持する（ログ・フロントエンドへのエラーにAPIキーが出ないように）
+impl From<reqwest::Error> for YouTubeError {
+    fn from(err: reqwest::Error) -> Self {
+        YouTubeError::HttpError(err.without_url())
+    }
+}
+
+impl YouTubeError {
+    /// 一時的なエラー（時間をおいて再試行すれば回復しうる）かどうか
+    ///
+    /// APIキー無効・チャットが存在しない・continuation失効などは再試行しても回復しないため`false`
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            YouTubeError::HttpError(_)
+            | YouTubeError::NetworkError(_)
+            | YouTubeError::Timeout
+            | YouTubeError::RateLimitExceeded
+            | YouTubeError::ParseError(_)
+            | YouTubeError::ApiError(_)
+            | YouTubeError::InvalidPageToken => true,
+            YouTubeError::InvalidApiKey
+            | YouTubeError::VideoNotFound
+            | YouTubeError::LiveChatNotFound
+            | YouTubeError::LiveChatDisabled
+            | YouTubeError::QuotaExceeded
+            | YouTubeError::PollerAlreadyRunning
+            | YouTubeError::InnerTubeNotInitialized
+            | YouTubeError::InnerTubeContinuationExpired => false,
+        }
+    }
+}
+
 impl From<YouTubeError> for String {
     fn from(err: YouTubeError) -> String {
         err.to_string()
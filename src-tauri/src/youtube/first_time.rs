@@ -0,0 +1,175 @@
+//! 初見（初めてコメントした投稿者）の判定
+//!
+//! 新しい視聴者を歓迎できるよう、初見の投稿者のコメントに`is_first_time`を付けて
+//! `comment:add`で配信する。判定範囲は「今回の配信」（ポーリング開始以降）と
+//! 「これまで全体」（comment_logsの全期間）から選べる。
+//! 今回の配信で一度判定した投稿者はメモリ上のセットで覚え、DBを調べるのは投稿者ごとに1回だけにする。
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+use std::sync::{Mutex, RwLock};
+
+use super::db::has_comment_before;
+use super::types::ChatMessage;
+
+/// 初見の判定範囲
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FirstTimeScope {
+    /// 今回の配信（ポーリング開始以降）で初めてのコメント
+    #[default]
+    Session,
+    /// comment_logsに過去のコメントがない投稿者の初めてのコメント
+    AllTime,
+}
+
+impl FirstTimeScope {
+    /// 設定の保存値
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Session => "session",
+            Self::AllTime => "all_time",
+        }
+    }
+
+    /// 設定の保存値から変換（不正な値はNone）
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "session" => Some(Self::Session),
+            "all_time" => Some(Self::AllTime),
+            _ => None,
+        }
+    }
+}
+
+/// 現在の判定範囲
+/// 起動時にDBから読み込み、設定コマンドで更新される
+static SCOPE: Lazy<RwLock<FirstTimeScope>> = Lazy::new(|| RwLock::new(FirstTimeScope::default()));
+
+/// 今回の配信で判定済みの投稿者（チャンネルID）
+static SEEN_AUTHORS: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// 判定範囲を更新
+pub fn set_scope(scope: FirstTimeScope) {
+    match SCOPE.write() {
+        Ok(mut current) => *current = scope,
+        Err(e) => log::error!("Failed to update first-time chatter scope: {}", e),
+    }
+}
+
+/// 現在の判定範囲を取得
+pub fn scope() -> FirstTimeScope {
+    match SCOPE.read() {
+        Ok(scope) => *scope,
+        Err(e) => {
+            log::error!("Failed to read first-time chatter scope: {}", e);
+            FirstTimeScope::default()
+        }
+    }
+}
+
+/// 判定済みの投稿者をリセット（ポーリング開始時）
+pub fn reset_session() {
+    match SEEN_AUTHORS.lock() {
+        Ok(mut seen) => seen.clear(),
+        Err(e) => log::error!("Failed to reset first-time chatters: {}", e),
+    }
+}
+
+/// 今回の配信で初めて見た投稿者のコメントを取り出し、判定済みとして記録
+///
+/// 同じ投稿者の複数コメントは最初の1件のみ。配信者本人（オーナー）は対象外
+fn claim_new_authors<'a>(seen: &mut HashSet<String>, messages: &'a [ChatMessage]) -> Vec<&'a ChatMessage> {
+    messages
+        .iter()
+        .filter(|message| !message.is_owner && seen.insert(message.author_channel_id.clone()))
+        .collect()
+}
+
+/// 初見の投稿者のコメントIDを取得（ブロードキャスト直前に呼び出す）
+///
+/// 判定範囲が「これまで全体」の場合は、コメントの投稿時刻より前の
+/// comment_logsを調べる（書き込みキューで保存済みの同じコメントは含めない）。
+/// DBエラー時は初見として扱わない
+pub async fn detect_first_time(pool: &SqlitePool, messages: &[ChatMessage]) -> HashSet<String> {
+    let candidates = match SEEN_AUTHORS.lock() {
+        Ok(mut seen) => claim_new_authors(&mut seen, messages),
+        Err(e) => {
+            log::error!("Failed to read first-time chatters: {}", e);
+            return HashSet::new();
+        }
+    };
+
+    let scope = scope();
+    let mut first_time_ids = HashSet::new();
+    for message in candidates {
+        let is_first_time = match scope {
+            FirstTimeScope::Session => true,
+            FirstTimeScope::AllTime => {
+                match has_comment_before(pool, &message.author_channel_id, &message.published_at.to_rfc3339())
+                    .await
+                {
+                    Ok(found) => !found,
+                    Err(e) => {
+                        log::warn!("Failed to look up previous comments: {}", e);
+                        false
+                    }
+                }
+            }
+        };
+        if is_first_time {
+            first_time_ids.insert(message.id.clone());
+        }
+    }
+    first_time_ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message_from(id: &str, channel_id: &str) -> ChatMessage {
+        ChatMessage::test_message(id).with_author("Viewer", channel_id)
+    }
+
+    #[test]
+    fn test_claim_new_authors_once_per_session() {
+        let mut seen = HashSet::new();
+        let mut owner = message_from("c4", "UC_owner");
+        owner.is_owner = true;
+        let messages = vec![
+            message_from("c1", "UC_a"),
+            message_from("c2", "UC_a"),
+            message_from("c3", "UC_b"),
+            owner,
+        ];
+
+        let ids: Vec<&str> = claim_new_authors(&mut seen, &messages)
+            .iter()
+            .map(|message| message.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["c1", "c3"]);
+
+        // 判定済みの投稿者は以降のバッチで対象外
+        let messages = vec![message_from("c5", "UC_b"), message_from("c6", "UC_c")];
+        let ids: Vec<&str> = claim_new_authors(&mut seen, &messages)
+            .iter()
+            .map(|message| message.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["c6"]);
+    }
+
+    #[test]
+    fn test_scope_roundtrip() {
+        for scope in [FirstTimeScope::Session, FirstTimeScope::AllTime] {
+            assert_eq!(FirstTimeScope::parse(scope.as_str()), Some(scope));
+            assert_eq!(
+                serde_json::to_value(scope).unwrap(),
+                serde_json::json!(scope.as_str())
+            );
+        }
+        assert_eq!(FirstTimeScope::parse("forever"), None);
+    }
+}
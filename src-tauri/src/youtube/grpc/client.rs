@@ -8,7 +8,8 @@ use super::proto::{
 };
 use crate::youtube::backoff::ExponentialBackoff;
 use crate::youtube::errors::YouTubeError;
-use crate::youtube::types::{ChatMessage, MessageType};
+use crate::youtube::labels;
+use crate::youtube::types::{badges_from_flags, ChatMessage, MessageType};
 use chrono::{DateTime, Utc};
 use std::collections::VecDeque;
 use std::time::Duration;
@@ -61,6 +62,15 @@ impl GrpcChatClient {
             .timeout(Duration::from_secs(30))
             .connect_timeout(Duration::from_secs(10));
 
+        Self::connect_endpoint(endpoint, api_key, live_chat_id).await
+    }
+
+    /// 指定したエンドポイントに接続（テスト用のモックサーバーへの接続にも使用）
+    async fn connect_endpoint(
+        endpoint: Endpoint,
+        api_key: String,
+        live_chat_id: String,
+    ) -> Result<Self, YouTubeError> {
         // Connect
         let channel = endpoint
             .connect()
@@ -69,7 +79,7 @@ impl GrpcChatClient {
 
         let client = V3DataLiveChatMessageServiceClient::new(channel);
 
-        log::info!("Connected to YouTube gRPC endpoint");
+        log::info!("Connected to gRPC endpoint: {}", endpoint.uri());
 
         Ok(Self {
             client,
@@ -188,6 +198,20 @@ impl GrpcChatClient {
         }
     }
 
+    /// Detect members-only mode changes in the response (last event wins)
+    pub fn detect_members_only(response: &LiveChatMessageListResponse) -> Option<bool> {
+        use super::proto::live_chat_message_snippet::type_wrapper::Type;
+
+        response.items.iter().rev().find_map(|item| {
+            let msg_type = item.snippet.as_ref()?.r#type?;
+            match Type::try_from(msg_type).ok()? {
+                Type::SponsorOnlyModeStartedEvent => Some(true),
+                Type::SponsorOnlyModeEndedEvent => Some(false),
+                _ => None,
+            }
+        })
+    }
+
     /// Parse a gRPC response into ChatMessage list
     pub fn parse_response(&mut self, response: LiveChatMessageListResponse) -> Vec<ChatMessage> {
         // Update next page token
@@ -271,9 +295,13 @@ impl GrpcChatClient {
         // Determine message type
         let message_type = self.parse_message_type(snippet);
 
+        // gRPC doesn't provide runs, so split unicode emoji out of the display message
+        let message = snippet.display_message.clone().unwrap_or_default();
+        let message_runs = crate::youtube::emoji::build_message_runs(&message);
+
         Some(ChatMessage {
             id: msg_id.to_string(),
-            message: snippet.display_message.clone().unwrap_or_default(),
+            message,
             author_name: author.display_name.clone().unwrap_or_default(),
             author_channel_id: author.channel_id.clone().unwrap_or_default(),
             author_image_url: author.profile_image_url.clone().unwrap_or_default(),
@@ -282,8 +310,14 @@ impl GrpcChatClient {
             is_moderator: author.is_chat_moderator.unwrap_or(false),
             is_member: author.is_chat_sponsor.unwrap_or(false),
             is_verified: author.is_verified.unwrap_or(false),
+            badges: badges_from_flags(
+                author.is_chat_owner.unwrap_or(false),
+                author.is_chat_moderator.unwrap_or(false),
+                author.is_chat_sponsor.unwrap_or(false),
+                author.is_verified.unwrap_or(false),
+            ),
             message_type,
-            message_runs: None, // gRPC doesn't provide runs
+            message_runs,
         })
     }
 
@@ -305,6 +339,7 @@ impl GrpcChatClient {
                     MessageType::SuperChat {
                         amount: details.amount_display_string.clone().unwrap_or_default(),
                         currency: details.currency.clone().unwrap_or_default(),
+                        amount_micros: details.amount_micros.filter(|micros| *micros > 0),
                     }
                 } else {
                     MessageType::Text
@@ -318,7 +353,13 @@ impl GrpcChatClient {
                         .as_ref()
                         .and_then(|m| m.sticker_id.clone())
                         .unwrap_or_default();
-                    MessageType::SuperSticker { sticker_id }
+                    MessageType::SuperSticker {
+                        sticker_id,
+                        amount: details.amount_display_string.clone().unwrap_or_default(),
+                        currency: details.currency.clone().unwrap_or_default(),
+                        amount_micros: details.amount_micros.filter(|micros| *micros > 0),
+                        image_url: None,
+                    }
                 } else {
                     MessageType::Text
                 }
@@ -330,22 +371,21 @@ impl GrpcChatClient {
                     }
                 } else {
                     MessageType::Membership {
-                        level: "New Member".to_string(),
+                        level: labels::current_locale().new_member().to_string(),
                     }
                 }
             }
             Some(Type::MemberMilestoneChatEvent) => {
                 if let Some(details) = &snippet.member_milestone_chat_details {
                     MessageType::Membership {
-                        level: format!(
-                            "{} ({}ヶ月)",
-                            details.member_level_name.as_deref().unwrap_or("Member"),
-                            details.member_month.unwrap_or(0)
+                        level: labels::current_locale().milestone_with_months(
+                            details.member_level_name.as_deref(),
+                            details.member_month.unwrap_or(0),
                         ),
                     }
                 } else {
                     MessageType::Membership {
-                        level: "Member Milestone".to_string(),
+                        level: labels::current_locale().member_milestone().to_string(),
                     }
                 }
             }
@@ -362,6 +402,13 @@ impl GrpcChatClient {
         }
     }
 
+    /// 認証に使うAPIキーを差し替え（セカンダリキーへの切り替え用）
+    ///
+    /// ページトークンと既読IDは保持したまま、次回の`stream()`から適用される
+    pub fn set_api_key(&mut self, api_key: String) {
+        self.api_key = api_key;
+    }
+
     /// Get backoff delay for reconnection
     pub fn get_backoff_delay(&mut self) -> Duration {
         self.backoff.next_delay()
@@ -389,13 +436,221 @@ impl GrpcChatClient {
 
 #[cfg(test)]
 mod tests {
-    #[allow(unused_imports)]
     use super::*;
+    use crate::youtube::grpc::proto::{
+        live_chat_message_snippet::type_wrapper::Type,
+        v3_data_live_chat_message_service_server::{
+            V3DataLiveChatMessageService, V3DataLiveChatMessageServiceServer,
+        },
+        LiveChatMemberMilestoneChatDetails, LiveChatMembershipGiftingDetails, LiveChatMessage,
+        LiveChatMessageAuthorDetails, LiveChatMessageSnippet, LiveChatNewSponsorDetails,
+        LiveChatSuperChatDetails,
+    };
+    use std::net::SocketAddr;
+    use std::sync::{Arc, Mutex};
+    use tonic::transport::server::TcpIncoming;
+    use tonic::transport::Server;
+    use tonic::Response;
+
+    /// モックサーバーが受け取ったリクエスト（APIキー, ページトークン）
+    type RecordedRequests = Arc<Mutex<Vec<(Option<String>, Option<String>)>>>;
+
+    /// StreamListごとに同じ2件のレスポンスを返して終了するモックサーバー
+    struct MockChatService {
+        requests: RecordedRequests,
+    }
+
+    fn chat_item(id: &str, msg_type: Type, text: &str) -> LiveChatMessage {
+        LiveChatMessage {
+            id: Some(id.to_string()),
+            snippet: Some(LiveChatMessageSnippet {
+                r#type: Some(msg_type as i32),
+                published_at: Some("2025-01-01T00:00:00Z".to_string()),
+                display_message: Some(text.to_string()),
+                super_chat_details: (msg_type == Type::SuperChatEvent).then(|| {
+                    LiveChatSuperChatDetails {
+                        amount_micros: Some(1_000_000_000),
+                        currency: Some("JPY".to_string()),
+                        amount_display_string: Some("¥1,000".to_string()),
+                        ..Default::default()
+                    }
+                }),
+                ..Default::default()
+            }),
+            author_details: Some(LiveChatMessageAuthorDetails {
+                channel_id: Some(format!("UC_{}", id)),
+                display_name: Some(format!("Viewer {}", id)),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[tonic::async_trait]
+    impl V3DataLiveChatMessageService for MockChatService {
+        type StreamListStream =
+            tokio_stream::Iter<std::vec::IntoIter<Result<LiveChatMessageListResponse, Status>>>;
+
+        async fn stream_list(
+            &self,
+            request: Request<LiveChatMessageListRequest>,
+        ) -> Result<Response<Self::StreamListStream>, Status> {
+            let api_key = request
+                .metadata()
+                .get("x-goog-api-key")
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            self.requests
+                .lock()
+                .unwrap()
+                .push((api_key, request.into_inner().page_token));
+
+            let responses = vec![
+                Ok(LiveChatMessageListResponse {
+                    items: vec![chat_item("m1", Type::TextMessageEvent, "こんにちは")],
+                    next_page_token: Some("page-1".to_string()),
+                    ..Default::default()
+                }),
+                Ok(LiveChatMessageListResponse {
+                    items: vec![chat_item("m2", Type::SuperChatEvent, "スパチャです")],
+                    next_page_token: Some("page-2".to_string()),
+                    ..Default::default()
+                }),
+            ];
+            Ok(Response::new(tokio_stream::iter(responses)))
+        }
+    }
+
+    /// モックサーバーを起動し、クライアントの接続先アドレスを返す
+    async fn spawn_mock_server(requests: RecordedRequests) -> SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+        tokio::spawn(
+            Server::builder()
+                .add_service(V3DataLiveChatMessageServiceServer::new(MockChatService {
+                    requests,
+                }))
+                .serve_with_incoming(incoming),
+        );
+        addr
+    }
+
+    async fn connect_mock(addr: SocketAddr) -> GrpcChatClient {
+        let endpoint = Endpoint::from_shared(format!("http://{}", addr)).unwrap();
+        GrpcChatClient::connect_endpoint(endpoint, "test-key".to_string(), "chat-1".to_string())
+            .await
+            .unwrap()
+    }
+
+    /// ストリームが終了するまで受信し、ChatMessageに変換して返す
+    async fn receive_all(client: &mut GrpcChatClient) -> Vec<ChatMessage> {
+        use tokio_stream::StreamExt;
+
+        let mut stream = client.stream().await.unwrap();
+        let mut messages = Vec::new();
+        while let Some(response) = stream.next().await {
+            messages.extend(client.parse_response(response.unwrap()));
+        }
+        messages
+    }
+
+    #[tokio::test]
+    async fn test_stream_from_mock_server() {
+        let requests = RecordedRequests::default();
+        let addr = spawn_mock_server(Arc::clone(&requests)).await;
+        let mut client = connect_mock(addr).await;
+
+        let messages = receive_all(&mut client).await;
+        assert_eq!(messages.len(), 2);
+
+        assert_eq!(messages[0].id, "m1");
+        assert_eq!(messages[0].message, "こんにちは");
+        assert_eq!(messages[0].author_name, "Viewer m1");
+        assert_eq!(messages[0].author_channel_id, "UC_m1");
+        assert!(matches!(messages[0].message_type, MessageType::Text));
+
+        assert_eq!(messages[1].id, "m2");
+        match &messages[1].message_type {
+            MessageType::SuperChat {
+                amount,
+                currency,
+                amount_micros,
+            } => {
+                assert_eq!(amount, "¥1,000");
+                assert_eq!(currency, "JPY");
+                assert_eq!(*amount_micros, Some(1_000_000_000));
+            }
+            other => panic!("unexpected message type: {:?}", other),
+        }
+
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![(Some("test-key".to_string()), None)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restream_resumes_from_page_token() {
+        let requests = RecordedRequests::default();
+        let addr = spawn_mock_server(Arc::clone(&requests)).await;
+        let mut client = connect_mock(addr).await;
+
+        assert_eq!(receive_all(&mut client).await.len(), 2);
+
+        // ストリーム終了後に同じクライアントで開き直すと、続きのページから再開し、
+        // 再送されたコメントは既読として除外される
+        client.set_api_key("secondary-key".to_string());
+        assert!(receive_all(&mut client).await.is_empty());
+
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![
+                (Some("test-key".to_string()), None),
+                (Some("secondary-key".to_string()), Some("page-2".to_string())),
+            ]
+        );
+    }
 
-    #[test]
-    #[ignore = "TODO: Phase 6でgRPCレスポンスのモックを使ったテストを実装"]
-    fn test_parse_message_type() {
-        // This test validates the message type parsing logic
-        // In real usage, this would be tested with actual gRPC responses
+    #[tokio::test]
+    async fn test_parse_message_type() {
+        let addr = spawn_mock_server(RecordedRequests::default()).await;
+        let mut client = connect_mock(addr).await;
+
+        let mut membership = chat_item("s1", Type::NewSponsorEvent, "");
+        membership.snippet.as_mut().unwrap().new_sponsor_details = Some(LiveChatNewSponsorDetails {
+            member_level_name: Some("Gold".to_string()),
+            ..Default::default()
+        });
+        let mut milestone = chat_item("s2", Type::MemberMilestoneChatEvent, "継続しました");
+        milestone.snippet.as_mut().unwrap().member_milestone_chat_details =
+            Some(LiveChatMemberMilestoneChatDetails {
+                member_level_name: Some("Gold".to_string()),
+                member_month: Some(3),
+                ..Default::default()
+            });
+        let mut gift = chat_item("s3", Type::MembershipGiftingEvent, "");
+        gift.snippet.as_mut().unwrap().membership_gifting_details =
+            Some(LiveChatMembershipGiftingDetails {
+                gift_memberships_count: Some(5),
+                ..Default::default()
+            });
+        // 詳細のないスパチャ・未対応の種別はテキストとして扱う
+        let mut superchat_without_details = chat_item("s4", Type::SuperChatEvent, "詳細なし");
+        superchat_without_details.snippet.as_mut().unwrap().super_chat_details = None;
+        let poll = chat_item("s5", Type::PollEvent, "投票");
+
+        let messages = client.parse_response(LiveChatMessageListResponse {
+            items: vec![membership, milestone, gift, superchat_without_details, poll],
+            ..Default::default()
+        });
+        let types: Vec<&MessageType> = messages.iter().map(|m| &m.message_type).collect();
+
+        assert_eq!(types.len(), 5);
+        assert!(matches!(types[0], MessageType::Membership { level } if level == "Gold"));
+        assert!(matches!(types[1], MessageType::Membership { level } if level.starts_with("Gold (3")));
+        assert!(matches!(types[2], MessageType::MembershipGift { count: 5 }));
+        assert!(matches!(types[3], MessageType::Text));
+        assert!(matches!(types[4], MessageType::Text));
     }
 }
@@ -9,13 +9,19 @@
 //! 3. SQLite保存 → コメントログ
 
 use super::client::GrpcChatClient;
+use crate::membership::{create_membership_payload, show_membership};
 use crate::server::types::WsMessage;
 use crate::server::WebSocketState;
-use crate::superchat::{broadcast_superchat, create_superchat_payload, schedule_superchat_removal};
+use crate::superchat::{create_superchat_payload, enqueue_superchat, record_totals};
 use crate::youtube::api_key_manager::get_api_key_manager;
 use crate::youtube::backoff::ExponentialBackoff;
-use crate::youtube::db::save_comments_to_db;
+use crate::youtube::channel_ban::without_banned;
+use crate::youtube::chat_settings::CHAT_SETTINGS;
+use crate::youtube::comment_filter::queue_save_and_filter;
 use crate::youtube::errors::YouTubeError;
+use crate::youtube::first_time::detect_first_time;
+use crate::youtube::severity::classify_comments;
+use crate::youtube::translation::spawn_translations;
 use sqlx::SqlitePool;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -121,6 +127,10 @@ impl Drop for GrpcPoller {
 /// - `connection_backoff`: gRPCエンドポイントへの接続失敗時に使用
 /// - `client.get_backoff_delay()`: ストリーム開始失敗・切断後の再接続時に使用
 ///   （クライアント内部で成功時にリセットされる）
+///
+/// # 再接続
+/// ストリームが終了・切断した場合は同じクライアントでストリームを開き直す。
+/// ページトークンと既読IDを引き継ぐため、再接続後に過去のコメントが重複して配信されない。
 async fn run_grpc_stream(
     live_chat_id: String,
     api_key: String,
@@ -134,6 +144,8 @@ async fn run_grpc_stream(
     // gRPCエンドポイントへの接続失敗時のバックオフ（ジッタ付き）
     // ストリーム開始・再接続のバックオフはclient.get_backoff_delay()を使用
     let mut connection_backoff = ExponentialBackoff::with_jitter();
+    // 接続済みのクライアント（ストリーム再接続時も再利用）
+    let mut grpc_client: Option<GrpcChatClient> = None;
 
     loop {
         if stop_signal.load(Ordering::SeqCst) {
@@ -151,6 +163,9 @@ async fn run_grpc_stream(
                         log::info!("Switching to secondary API key");
                         current_api_key = secondary.to_string();
                         retry_with_secondary = false;
+                        if let Some(client) = grpc_client.as_mut() {
+                            client.set_api_key(current_api_key.clone());
+                        }
                     } else {
                         log::error!("No secondary API key available");
                         return Err(YouTubeError::InvalidApiKey);
@@ -169,30 +184,30 @@ async fn run_grpc_stream(
             }
         }
 
-        // Connect to gRPC endpoint
-        let mut client = match GrpcChatClient::connect(
-            current_api_key.clone(),
-            live_chat_id.clone(),
-        )
-        .await
-        {
-            Ok(c) => {
-                // 接続成功時はバックオフをリセット
-                connection_backoff.reset();
-                c
-            }
-            Err(YouTubeError::InvalidApiKey) => {
-                retry_with_secondary = true;
-                continue;
-            }
-            Err(e) => {
-                log::error!("Failed to connect to gRPC: {:?}", e);
-                // 指数バックオフで待機
-                let delay = connection_backoff.next_delay();
-                log::info!("Retrying connection in {:?}", delay);
-                tokio::time::sleep(delay).await;
-                continue;
+        // Connect to gRPC endpoint（未接続の場合のみ）
+        if grpc_client.is_none() {
+            match GrpcChatClient::connect(current_api_key.clone(), live_chat_id.clone()).await {
+                Ok(c) => {
+                    // 接続成功時はバックオフをリセット
+                    connection_backoff.reset();
+                    grpc_client = Some(c);
+                }
+                Err(YouTubeError::InvalidApiKey) => {
+                    retry_with_secondary = true;
+                    continue;
+                }
+                Err(e) => {
+                    log::error!("Failed to connect to gRPC: {:?}", e);
+                    // 指数バックオフで待機
+                    let delay = connection_backoff.next_delay();
+                    log::info!("Retrying connection in {:?}", delay);
+                    tokio::time::sleep(delay).await;
+                    continue;
+                }
             }
+        }
+        let Some(client) = grpc_client.as_mut() else {
+            continue;
         };
 
         // Start streaming
@@ -248,40 +263,50 @@ async fn run_grpc_stream(
                         if has_next_page { "present" } else { "empty" }
                     );
 
+                    // メンバー限定モードの切り替えを検出
+                    if let Some(members_only) = GrpcChatClient::detect_members_only(&response) {
+                        CHAT_SETTINGS.apply_members_only(&server_state, members_only).await;
+                    }
+
                     // Parse and broadcast messages
                     let messages = client.parse_response(response);
                     message_count += messages.len() as u64;
 
                     if !messages.is_empty() {
+                        // Emit to frontend via Tauri event (BAN中の投稿者は除外)
+                        let _ = app_handle.emit("chat-messages", &without_banned(&messages));
+
+                        // DBへの保存は書き込みキュー経由（ブロードキャストは書き込みを待たない）
+                        // ブロックリスト・BANで除外したコメントは配信しない（DBにはフラグ付きで保存）
+                        let severities = classify_comments(&messages);
+                        let messages = queue_save_and_filter(&db_pool, messages);
+                        let first_time_ids = detect_first_time(&db_pool, &messages).await;
                         let broadcast_count = messages.len();
 
-                        // Emit to frontend via Tauri event
-                        let _ = app_handle.emit("chat-messages", &messages);
-
-                        // DBに保存
-                        let save_result = save_comments_to_db(&db_pool, &messages).await;
-                        if save_result.failed > 0 || save_result.skipped > 0 {
-                            log::warn!(
-                                "save_comments_to_db: {} saved, {} failed, {} skipped",
-                                save_result.saved, save_result.failed, save_result.skipped
-                            );
-                        }
-
                         // Broadcast to WebSocket clients (for overlays) - gRPCは即時表示
                         let state_lock = server_state.read().await;
                         for msg in &messages {
                             // コメント欄にブロードキャスト
-                            state_lock.broadcast(WsMessage::CommentAdd { payload: msg.clone(), instant: true, buffer_interval_ms: None }).await;
+                            state_lock.broadcast(WsMessage::CommentAdd { payload: msg.clone(), instant: true, buffer_interval_ms: None, is_first_time: first_time_ids.contains(&msg.id), translation: None, severity: severities.get(&msg.id).copied().unwrap_or_default() }).await;
 
                             // スパチャの場合は専用ウィジェットにもブロードキャスト
                             if let Some(superchat_payload) = create_superchat_payload(msg) {
-                                let display_duration = superchat_payload.display_duration_ms;
-                                let superchat_id = superchat_payload.id.clone();
-                                broadcast_superchat(&server_state, superchat_payload).await;
-                                // 表示完了後にremoveメッセージを送信するタイマーをスケジュール
-                                schedule_superchat_removal(Arc::clone(&server_state), superchat_id, display_duration);
+                                // 表示中のスパチャがあれば表示完了後に順番に表示
+                                enqueue_superchat(&server_state, superchat_payload).await;
+                            }
+
+                            // メンバーシップ・ギフトの場合は専用ウィジェットにもブロードキャスト
+                            if let Some(membership_payload) = create_membership_payload(msg) {
+                                show_membership(&server_state, membership_payload).await;
                             }
                         }
+                        drop(state_lock);
+
+                        // 翻訳は応答を待たずに後から配信
+                        spawn_translations(&server_state, &messages);
+
+                        // 支援者ランキング・目標金額を集計（合計が変わった場合は配信）
+                        record_totals(&db_pool, &server_state, &messages).await;
 
                         log::info!("Broadcast {} chat messages to WebSocket (total: {})", broadcast_count, message_count);
                     }
@@ -3,19 +3,27 @@
 use regex::Regex;
 use reqwest::Client;
 use serde_json::json;
-use std::sync::OnceLock;
-
-use super::types::{ContinuationType, InnerTubeChatResponse, InnerTubePlayerResponse, VideoDetails};
+use std::sync::{Arc, OnceLock};
+
+use super::emoji_cache::EmojiCache;
+use super::overrides::{overrides, InnerTubeOverrides};
+use super::parser::to_emoji_info;
+use super::types::{
+    ClientVersionSource, ContinuationSource, ContinuationType, InnerTubeChatResponse, InnerTubeEmoji,
+    InnerTubePlayerResponse, VideoDetails,
+};
 use crate::youtube::errors::YouTubeError;
+use crate::youtube::types::EmojiInfo;
 
 const INNERTUBE_API_URL: &str = "https://www.youtube.com/youtubei/v1/live_chat/get_live_chat";
 const INNERTUBE_PLAYER_URL: &str = "https://www.youtube.com/youtubei/v1/player";
-const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+/// User-Agent（設定で上書きできる、[`super::overrides`]）
+pub(crate) const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
 
 /// InnerTubeクライアントバージョン（フォールバック用）
 /// YouTube側で定期的に更新されるため、動的取得を優先する
-/// このハードコーディング値は動的取得に失敗した場合のフォールバック
-const FALLBACK_CLIENT_VERSION: &str = "2.20251201.01.00";
+/// このハードコーディング値は動的取得に失敗した場合のフォールバック（設定で上書きできる）
+pub(crate) const FALLBACK_CLIENT_VERSION: &str = "2.20251201.01.00";
 
 /// continuationトークン抽出用の最小長さ
 /// 有効なcontinuationトークンは通常100文字以上のBase64エンコード文字列
@@ -113,8 +121,24 @@ pub struct InnerTubeClient {
     api_key: Option<String>,
     /// 動的に取得したクライアントバージョン（取得失敗時はFALLBACK_CLIENT_VERSIONを使用）
     client_version: String,
+    /// 作成時点のUser-Agent・クライアントバージョンの上書き設定
+    overrides: InnerTubeOverrides,
     /// 現在のContinuation種別（ポーリング間隔制御に使用）
     continuation_type: ContinuationType,
+    /// この配信の絵文字キャッシュ（パース時に`parse_chat_response`へ渡す）
+    emoji_cache: Arc<EmojiCache>,
+    /// 直近のチャット取得のHTTPステータス（診断用）
+    last_chat_status: Option<u16>,
+}
+
+/// ライブチャットページから抽出した結果（診断用）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageExtraction {
+    pub api_key_extracted: bool,
+    /// continuationトークンを抽出したパターン（抽出できなかった場合はNone）
+    pub continuation_source: Option<ContinuationSource>,
+    pub client_version_source: ClientVersionSource,
+    pub emoji_catalog_size: usize,
 }
 
 impl InnerTubeClient {
@@ -123,8 +147,9 @@ impl InnerTubeClient {
     /// # Errors
     /// HTTPクライアントのビルドに失敗した場合にエラーを返す
     pub fn new(video_id: String) -> Result<Self, YouTubeError> {
+        let overrides = overrides();
         let client = Client::builder()
-            .user_agent(USER_AGENT)
+            .user_agent(overrides.user_agent.as_deref().unwrap_or(USER_AGENT))
             .build()
             .map_err(|e| YouTubeError::NetworkError(format!("Failed to build HTTP client: {}", e)))?;
 
@@ -134,13 +159,93 @@ impl InnerTubeClient {
             continuation: None,
             timeout_ms: 5000,
             api_key: None,
-            client_version: FALLBACK_CLIENT_VERSION.to_string(),
+            client_version: overrides
+                .client_version
+                .clone()
+                .unwrap_or_else(|| FALLBACK_CLIENT_VERSION.to_string()),
+            overrides,
             continuation_type: ContinuationType::default(),
+            emoji_cache: Arc::new(EmojiCache::new()),
+            last_chat_status: None,
         })
     }
 
     /// 初期化: ライブチャットページからcontinuationトークンを取得
+    ///
+    /// ページに絵文字カタログがあれば絵文字キャッシュに登録する
     pub async fn initialize(&mut self) -> Result<(), YouTubeError> {
+        let body = self.fetch_live_chat_page().await?;
+        self.apply_live_chat_page(&body);
+
+        if self.continuation.is_some() {
+            log::info!("InnerTube client initialized successfully");
+            Ok(())
+        } else {
+            Err(YouTubeError::InnerTubeNotInitialized)
+        }
+    }
+
+    /// ライブチャットページからcontinuation・APIキー・クライアントバージョン・絵文字カタログを抽出して反映
+    pub(crate) fn apply_live_chat_page(&mut self, body: &str) -> PageExtraction {
+        // ytInitialDataからcontinuationを抽出
+        let continuation = Self::extract_continuation_with_source(body);
+        let continuation_source = continuation.as_ref().map(|(_, source)| *source);
+        self.continuation = continuation.map(|(continuation, _)| continuation);
+        // INNERTUBE_API_KEYを抽出
+        self.api_key = Self::extract_api_key(body);
+        // CLIENT_VERSIONを抽出（動的取得、設定で上書きしている場合はその値を使う）
+        let client_version_source = if self.overrides.client_version.is_some() {
+            log::info!("Using client version from settings: {}", self.client_version);
+            ClientVersionSource::Settings
+        } else if let Some(version) = Self::extract_client_version(body) {
+            log::info!("Dynamically extracted client version: {}", version);
+            self.client_version = version;
+            ClientVersionSource::Page
+        } else {
+            log::warn!(
+                "Failed to extract client version, using fallback: {}",
+                FALLBACK_CLIENT_VERSION
+            );
+            self.client_version = FALLBACK_CLIENT_VERSION.to_string();
+            ClientVersionSource::Fallback
+        };
+        match &self.overrides.user_agent {
+            Some(user_agent) => log::info!("Using User-Agent from settings: {}", user_agent),
+            None => log::info!("Using built-in User-Agent: {}", USER_AGENT),
+        }
+        // 絵文字カタログを登録（ない場合はチャットに出現した絵文字から登録する）
+        let catalog = Self::extract_emoji_catalog(body);
+        if catalog.is_empty() {
+            log::info!("Emoji catalog not found, emojis will be cached as they appear in chat");
+        } else {
+            log::info!("Preloaded {} emojis from catalog", catalog.len());
+            self.emoji_cache.preload(&catalog);
+        }
+
+        PageExtraction {
+            api_key_extracted: self.api_key.is_some(),
+            continuation_source,
+            client_version_source,
+            emoji_catalog_size: catalog.len(),
+        }
+    }
+
+    /// チャンネルのカスタム絵文字（メンバースタンプ）のカタログを取得
+    ///
+    /// カタログがない場合は空を返す
+    pub async fn fetch_emoji_catalog(&self) -> Result<Vec<EmojiInfo>, YouTubeError> {
+        let body = self.fetch_live_chat_page().await?;
+        Ok(Self::extract_emoji_catalog(&body))
+    }
+
+    /// ライブチャットページのHTMLを取得
+    async fn fetch_live_chat_page(&self) -> Result<String, YouTubeError> {
+        let response = self.request_live_chat_page().await?;
+        Self::read_live_chat_page(response).await
+    }
+
+    /// ライブチャットページをリクエスト（ステータスは確認しない）
+    pub(crate) async fn request_live_chat_page(&self) -> Result<reqwest::Response, YouTubeError> {
         let url = format!(
             "https://www.youtube.com/live_chat?is_popout=1&v={}",
             self.video_id
@@ -148,13 +253,15 @@ impl InnerTubeClient {
 
         log::info!("Fetching live chat page: {}", url);
 
-        let response = self
-            .client
+        self.client
             .get(&url)
             .send()
             .await
-            .map_err(|e| YouTubeError::NetworkError(e.to_string()))?;
+            .map_err(|e| YouTubeError::NetworkError(e.to_string()))
+    }
 
+    /// ライブチャットページのレスポンスからHTMLを読み込む（成功ステータス以外はエラー）
+    pub(crate) async fn read_live_chat_page(response: reqwest::Response) -> Result<String, YouTubeError> {
         if !response.status().is_success() {
             return Err(YouTubeError::ApiError(format!(
                 "Failed to fetch live chat page: {}",
@@ -162,32 +269,37 @@ impl InnerTubeClient {
             )));
         }
 
-        let body = response
+        response
             .text()
             .await
-            .map_err(|e| YouTubeError::NetworkError(e.to_string()))?;
-
-        // ytInitialDataからcontinuationを抽出
-        self.continuation = Self::extract_continuation(&body);
-        // INNERTUBE_API_KEYを抽出
-        self.api_key = Self::extract_api_key(&body);
-        // CLIENT_VERSIONを抽出（動的取得）
-        if let Some(version) = Self::extract_client_version(&body) {
-            log::info!("Dynamically extracted client version: {}", version);
-            self.client_version = version;
-        } else {
-            log::warn!(
-                "Failed to extract client version, using fallback: {}",
-                FALLBACK_CLIENT_VERSION
-            );
-        }
+            .map_err(|e| YouTubeError::NetworkError(e.to_string()))
+    }
 
-        if self.continuation.is_some() {
-            log::info!("InnerTube client initialized successfully");
-            Ok(())
-        } else {
-            Err(YouTubeError::InnerTubeNotInitialized)
-        }
+    /// 絵文字カタログ（ytInitialDataの`liveChatRenderer.emojis`）を抽出
+    ///
+    /// カタログがない配信・形式が不正な場合は空を返す（不正な要素のみスキップする）
+    fn extract_emoji_catalog(html: &str) -> Vec<EmojiInfo> {
+        const KEY: &str = r#""emojis":"#;
+        let Some(start) = html.find(r#""emojis":["#) else {
+            return Vec::new();
+        };
+        // 配列の終わりまでを読み込み、以降のHTMLは無視する
+        let items = match serde_json::Deserializer::from_str(&html[start + KEY.len()..])
+            .into_iter::<Vec<serde_json::Value>>()
+            .next()
+        {
+            Some(Ok(items)) => items,
+            Some(Err(e)) => {
+                log::warn!("Failed to parse emoji catalog: {}", e);
+                return Vec::new();
+            }
+            None => return Vec::new(),
+        };
+        items
+            .into_iter()
+            .filter_map(|item| serde_json::from_value::<InnerTubeEmoji>(item).ok())
+            .filter_map(|emoji| to_emoji_info(&emoji))
+            .collect()
     }
 
     /// continuationトークンを抽出（ライブチャット専用コンテキストを優先）
@@ -198,6 +310,11 @@ impl InnerTubeClient {
     /// 3. reloadContinuationData - リロード用トークン
     /// 4. 汎用continuation - フォールバック（他のcontinuationと混同するリスクあり）
     fn extract_continuation(html: &str) -> Option<String> {
+        Self::extract_continuation_with_source(html).map(|(continuation, _)| continuation)
+    }
+
+    /// continuationトークンと一致したパターンを抽出（優先順位は`extract_continuation`と同じ）
+    fn extract_continuation_with_source(html: &str) -> Option<(String, ContinuationSource)> {
         // 優先度1: invalidationContinuationData（ライブチャット専用、最も確実）
         let re1 = get_invalidation_continuation_regex();
         if let Some(caps) = re1.captures(html) {
@@ -208,7 +325,7 @@ impl InnerTubeClient {
                         "Found invalidationContinuationData token (length: {}, priority: 1)",
                         continuation.len()
                     );
-                    return Some(continuation);
+                    return Some((continuation, ContinuationSource::Invalidation));
                 }
             }
         }
@@ -223,7 +340,7 @@ impl InnerTubeClient {
                         "Found timedContinuationData token (length: {}, priority: 2)",
                         continuation.len()
                     );
-                    return Some(continuation);
+                    return Some((continuation, ContinuationSource::Timed));
                 }
             }
         }
@@ -238,7 +355,7 @@ impl InnerTubeClient {
                         "Found reloadContinuationData token (length: {}, priority: 3)",
                         continuation.len()
                     );
-                    return Some(continuation);
+                    return Some((continuation, ContinuationSource::Reload));
                 }
             }
         }
@@ -254,7 +371,7 @@ impl InnerTubeClient {
                         "Using generic continuation token (length: {}, priority: 4) - may not be live chat specific",
                         continuation.len()
                     );
-                    return Some(continuation);
+                    return Some((continuation, ContinuationSource::Generic));
                 }
             }
         }
@@ -347,7 +464,8 @@ impl InnerTubeClient {
             .json(&request_body)
             .send()
             .await
-            .map_err(|e| YouTubeError::NetworkError(e.to_string()))?;
+            .map_err(|e| YouTubeError::NetworkError(e.without_url().to_string()))?;
+        self.last_chat_status = Some(response.status().as_u16());
 
         if !response.status().is_success() {
             let status = response.status();
@@ -426,7 +544,7 @@ impl InnerTubeClient {
             .json(&request_body)
             .send()
             .await
-            .map_err(|e| YouTubeError::NetworkError(e.to_string()))?;
+            .map_err(|e| YouTubeError::NetworkError(e.without_url().to_string()))?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -479,6 +597,28 @@ impl InnerTubeClient {
         self.continuation_type
     }
 
+    /// この配信の絵文字キャッシュを取得
+    ///
+    /// `reset`しても同じ配信のためキャッシュは維持する
+    pub fn emoji_cache(&self) -> &Arc<EmojiCache> {
+        &self.emoji_cache
+    }
+
+    /// 使用するクライアントバージョン
+    pub fn client_version(&self) -> &str {
+        &self.client_version
+    }
+
+    /// User-Agentを設定で上書きしているか
+    pub fn has_user_agent_override(&self) -> bool {
+        self.overrides.user_agent.is_some()
+    }
+
+    /// 直近のチャット取得のHTTPステータス（未取得・通信エラーの場合はNone）
+    pub fn last_chat_status(&self) -> Option<u16> {
+        self.last_chat_status
+    }
+
     /// 初期化済みかどうか
     pub fn is_initialized(&self) -> bool {
         self.continuation.is_some()
@@ -656,6 +796,63 @@ mod tests {
         let result = InnerTubeClient::extract_client_version(html);
         assert_eq!(result, Some("3.20260101.00.00".to_string()));
     }
+
+    #[test]
+    fn test_apply_live_chat_page_reports_extraction() {
+        let long_token = "Cop4BxoYQ2dncl9jb21tb25fY2hhdF9tZXNzYWdlcw==".repeat(3);
+        let html = format!(
+            r#"{{"timedContinuationData":{{"timeoutMs":5000,"continuation":"{}"}},"INNERTUBE_API_KEY":"AIzaTest","clientVersion":"2.20260101.00.00"}}"#,
+            long_token
+        );
+        let mut client = InnerTubeClient::new("video123".to_string()).unwrap();
+        let extraction = client.apply_live_chat_page(&html);
+        assert_eq!(
+            extraction,
+            PageExtraction {
+                api_key_extracted: true,
+                continuation_source: Some(ContinuationSource::Timed),
+                client_version_source: ClientVersionSource::Page,
+                emoji_catalog_size: 0,
+            }
+        );
+        assert!(client.is_initialized());
+        assert_eq!(client.client_version(), "2.20260101.00.00");
+
+        // 抽出できなかった段階が分かる
+        let extraction = client.apply_live_chat_page("<html></html>");
+        assert!(!extraction.api_key_extracted);
+        assert_eq!(extraction.continuation_source, None);
+        assert_eq!(extraction.client_version_source, ClientVersionSource::Fallback);
+        assert!(!client.is_initialized());
+        assert_eq!(client.client_version(), FALLBACK_CLIENT_VERSION);
+    }
+
+    #[test]
+    fn test_extract_emoji_catalog() {
+        let html = r#"<script>window["ytInitialData"] = {"contents":{"liveChatRenderer":{"emojis":[
+            {"emojiId":"UC_channel/stamp1","shortcuts":[":_hello:",":_hi:"],"image":{"thumbnails":[{"url":"https://yt3.ggpht.com/stamp1","width":24,"height":24}]},"isCustomEmoji":true},
+            {"emojiId":"","shortcuts":[":_empty:"],"image":{"thumbnails":[]}},
+            {"emojiId":"UC_channel/broken","shortcuts":[":_broken:"]}
+        ],"actions":[]}}};</script><script>var other = ["emojis"];</script>"#;
+        let catalog = InnerTubeClient::extract_emoji_catalog(html);
+        // 空のemoji_id・画像のない要素はスキップ
+        assert_eq!(catalog.len(), 1);
+        assert_eq!(catalog[0].emoji_id, "UC_channel/stamp1");
+        assert!(catalog[0].is_custom_emoji);
+
+        // 登録したカタログの絵文字は初めて使われても画像に変換される
+        let cache = EmojiCache::new();
+        cache.preload(&catalog);
+        assert_eq!(cache.len(), 2);
+        assert!(matches!(
+            cache.convert_text(":_hi:").as_slice(),
+            [crate::youtube::types::MessageRun::Emoji { .. }]
+        ));
+
+        // カタログがない・形式が不正な場合は空
+        assert!(InnerTubeClient::extract_emoji_catalog(r#"{"continuation":"abc"}"#).is_empty());
+        assert!(InnerTubeClient::extract_emoji_catalog(r#"{"emojis":[{"emojiId":"#).is_empty());
+    }
 }
 
 
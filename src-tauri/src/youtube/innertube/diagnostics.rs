@@ -0,0 +1,141 @@
+//! InnerTube接続の診断
+//!
+//! InnerTubeの取得に失敗した場合に「初期化に失敗しました」だけでは原因が分からないため、
+//! ライブチャットページの取得・各値の抽出・チャット取得のどの段階で失敗したかを構造化して返す。
+//! 不具合報告にそのまま貼り付けられるよう、APIキー・continuationトークン自体は含めない。
+
+use serde::Serialize;
+
+use super::client::InnerTubeClient;
+use super::parser::parse_chat_response;
+use super::types::{ClientVersionSource, ContinuationSource};
+use crate::youtube::errors::YouTubeError;
+use crate::youtube::types::MessageRun;
+
+/// 診断で失敗した段階
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticStep {
+    /// HTTPクライアントの作成（User-Agentの上書きが不正等）
+    Client,
+    /// ライブチャットページの取得
+    LiveChatPage,
+    /// continuationトークンの抽出
+    Continuation,
+    /// チャットの取得・パース
+    ChatFetch,
+}
+
+/// InnerTube接続の診断結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InnerTubeDiagnostics {
+    pub video_id: String,
+    /// ライブチャットページのHTTPステータス（リクエスト自体が失敗した場合はNone）
+    pub page_status: Option<u16>,
+    /// INNERTUBE_API_KEYを抽出できたか
+    pub api_key_extracted: bool,
+    /// continuationトークンを抽出したパターン（抽出できなかった場合はNone）
+    pub continuation_source: Option<ContinuationSource>,
+    /// 使用したクライアントバージョン
+    pub client_version: Option<String>,
+    pub client_version_source: Option<ClientVersionSource>,
+    /// User-Agentを設定で上書きしているか
+    pub user_agent_overridden: bool,
+    /// ページから登録した絵文字カタログの件数
+    pub emoji_catalog_size: usize,
+    /// チャット取得のHTTPステータス（取得していない・通信エラーの場合はNone）
+    pub chat_status: Option<u16>,
+    /// 取得したメッセージ数
+    pub message_count: Option<usize>,
+    /// 取得したメッセージに含まれるカスタム絵文字の数
+    pub emoji_count: Option<usize>,
+    /// 失敗した段階（全て成功した場合はNone）
+    pub failed_step: Option<DiagnosticStep>,
+    /// 失敗した段階のエラー
+    pub error: Option<String>,
+}
+
+impl InnerTubeDiagnostics {
+    fn new(video_id: String) -> Self {
+        Self {
+            video_id,
+            page_status: None,
+            api_key_extracted: false,
+            continuation_source: None,
+            client_version: None,
+            client_version_source: None,
+            user_agent_overridden: false,
+            emoji_catalog_size: 0,
+            chat_status: None,
+            message_count: None,
+            emoji_count: None,
+            failed_step: None,
+            error: None,
+        }
+    }
+
+    fn fail(mut self, step: DiagnosticStep, error: &YouTubeError) -> Self {
+        log::warn!("InnerTube diagnostics failed at {:?}: {}", step, error);
+        self.failed_step = Some(step);
+        self.error = Some(error.to_string());
+        self
+    }
+}
+
+/// 配信のInnerTube接続を診断（ページ取得 → 各値の抽出 → チャット取得を1回ずつ行う）
+///
+/// 途中の段階で失敗した場合も、それまでに分かった結果を返す
+pub async fn diagnose(video_id: String) -> InnerTubeDiagnostics {
+    let report = InnerTubeDiagnostics::new(video_id.clone());
+    let mut client = match InnerTubeClient::new(video_id) {
+        Ok(client) => client,
+        Err(e) => return report.fail(DiagnosticStep::Client, &e),
+    };
+    let mut report = InnerTubeDiagnostics {
+        user_agent_overridden: client.has_user_agent_override(),
+        ..report
+    };
+
+    // ライブチャットページの取得
+    let response = match client.request_live_chat_page().await {
+        Ok(response) => response,
+        Err(e) => return report.fail(DiagnosticStep::LiveChatPage, &e),
+    };
+    report.page_status = Some(response.status().as_u16());
+    let body = match InnerTubeClient::read_live_chat_page(response).await {
+        Ok(body) => body,
+        Err(e) => return report.fail(DiagnosticStep::LiveChatPage, &e),
+    };
+
+    // 各値の抽出
+    let extraction = client.apply_live_chat_page(&body);
+    report.api_key_extracted = extraction.api_key_extracted;
+    report.continuation_source = extraction.continuation_source;
+    report.client_version = Some(client.client_version().to_string());
+    report.client_version_source = Some(extraction.client_version_source);
+    report.emoji_catalog_size = extraction.emoji_catalog_size;
+    if extraction.continuation_source.is_none() {
+        return report.fail(DiagnosticStep::Continuation, &YouTubeError::InnerTubeNotInitialized);
+    }
+
+    // チャットの取得
+    let result = client.get_chat_messages().await;
+    report.chat_status = client.last_chat_status();
+    let response = match result {
+        Ok(response) => response,
+        Err(e) => return report.fail(DiagnosticStep::ChatFetch, &e),
+    };
+    let messages = parse_chat_response(response, client.emoji_cache());
+    report.message_count = Some(messages.len());
+    report.emoji_count = Some(
+        messages
+            .iter()
+            .filter_map(|message| message.message_runs.as_ref())
+            .flatten()
+            .filter(|run| matches!(run, MessageRun::Emoji { .. }))
+            .count(),
+    );
+    log::info!("InnerTube diagnostics succeeded: {:?}", report);
+    report
+}
@@ -0,0 +1,919 @@
+//! InnerTube 絵文字キャッシュ
+//!
+//! InnerTubeレスポンスで取得した絵文字情報（ショートカット -> EmojiInfo）をLRUでキャッシュし、
+//! テキストトークンで送られてきた絵文字ショートカットを画像に変換するために使用する。
+//!
+//! 配信ごとに別の絵文字（メンバースタンプ）が使われるため、キャッシュは
+//! `InnerTubeClient`（= 1配信）ごとに持つ。配信を切り替えると新しいキャッシュから始まる。
+//! 初期化時にライブチャットページの絵文字カタログを登録し（[`EmojiCache::preload`]）、
+//! カタログがない場合はチャットに出現した絵文字から順に登録する。
+
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::youtube::types::{EmojiInfo, MessageRun};
+
+/// 絵文字キャッシュの最大サイズ
+/// YouTube絵文字は通常数百程度なので、2000で十分
+pub const EMOJI_CACHE_MAX_SIZE: usize = 2000;
+
+/// 絵文字ショートカットパターン（:_xxx:形式）
+static EMOJI_SHORTCUT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r":_[^:]+:").expect("Failed to compile emoji shortcut regex")
+});
+
+/// 現在ポーリング中の配信の絵文字キャッシュ（コメント表示のプレビュー・デバッグ用）
+static ACTIVE_EMOJI_CACHE: Lazy<RwLock<Option<Arc<EmojiCache>>>> = Lazy::new(|| RwLock::new(None));
+
+/// 絵文字キャッシュの統計（デバッグ用、開発ビルドのみ）
+#[cfg(debug_assertions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmojiCacheStats {
+    /// 現在のキャッシュ件数
+    pub size: usize,
+    /// キャッシュの最大件数（超えると最も使われていない絵文字から破棄）
+    pub max_size: usize,
+    /// テキスト内の絵文字ショートカットを画像に変換できた回数
+    pub hits: u64,
+    /// テキスト内の絵文字ショートカットがキャッシュになくテキストのままになった回数
+    pub misses: u64,
+}
+
+/// 絵文字キャッシュ: ショートカット -> EmojiInfo (LRUキャッシュ)
+pub struct EmojiCache {
+    entries: Mutex<LruCache<String, EmojiInfo>>,
+    /// ヒット数（デバッグビルドのみ計測）
+    hits: AtomicU64,
+    /// ミス数（デバッグビルドのみ計測）
+    misses: AtomicU64,
+}
+
+impl Default for EmojiCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EmojiCache {
+    /// 空のキャッシュを作成
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(NonZeroUsize::new(EMOJI_CACHE_MAX_SIZE).unwrap())),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// キャッシュをクリア（ヒット・ミスの計測もリセットする）
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+
+    /// キャッシュ件数
+    pub fn len(&self) -> usize {
+        self.entries.lock().map(|c| c.len()).unwrap_or(0)
+    }
+
+    /// キャッシュが空か
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// キャッシュの統計を取得（デバッグ用、開発ビルドのみ）
+    #[cfg(debug_assertions)]
+    pub fn stats(&self) -> EmojiCacheStats {
+        EmojiCacheStats {
+            size: self.len(),
+            max_size: EMOJI_CACHE_MAX_SIZE,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 絵文字をキャッシュに追加/更新（ショートカットごとに登録、常に最新を反映）
+    pub fn put(&self, emoji: &EmojiInfo) {
+        if let Ok(mut entries) = self.entries.lock() {
+            for shortcut in &emoji.shortcuts {
+                entries.put(shortcut.clone(), emoji.clone());
+            }
+        }
+    }
+
+    /// 絵文字カタログを一括登録（ポーリング開始時に、初めて使われる絵文字も画像で表示できるようにする）
+    pub fn preload(&self, emojis: &[EmojiInfo]) {
+        if let Ok(mut entries) = self.entries.lock() {
+            for emoji in emojis {
+                for shortcut in &emoji.shortcuts {
+                    entries.put(shortcut.clone(), emoji.clone());
+                }
+            }
+        }
+    }
+
+    /// ヒット・ミスを計測
+    ///
+    /// ホットパスのためリリースビルドでは何もしない（`cfg!`によりコンパイル時に除去される）
+    #[inline]
+    fn record_lookups(&self, hits: usize, misses: usize) {
+        if cfg!(debug_assertions) {
+            self.hits.fetch_add(hits as u64, Ordering::Relaxed);
+            self.misses.fetch_add(misses as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// テキスト内の:_xxx:パターンを絵文字キャッシュから画像に変換
+    ///
+    /// 例: "こんにちは:_草lol:です" → [Text("こんにちは"), Emoji(...), Text("です")]
+    ///
+    /// ロック範囲を最小化するため:
+    /// 0. キャッシュ空チェック（try_lock）- cold-cache時の正規表現スキャンを回避
+    /// 1. 正規表現でマッチを検出（ロック外）
+    /// 2. ユニークなショートカットを抽出（ロック外）- 重複排除でget()回数削減
+    /// 3. キャッシュからユニークなショートカットのみ一括取得（ロック範囲最小）
+    /// 4. 結果を組み立て（ロック外）
+    pub fn convert_text(&self, text: &str) -> Vec<MessageRun> {
+        // Step 0: キャッシュが空なら正規表現スキャンをスキップ（cold-cache最適化）
+        // try_lockを使用してブロッキングせずにチェック
+        if let Ok(cache) = self.entries.try_lock() {
+            if cache.is_empty() {
+                drop(cache);
+                // デバッグビルドではキャッシュ未登録による変換漏れもミスとして数える
+                if cfg!(debug_assertions) {
+                    self.record_lookups(0, EMOJI_SHORTCUT_REGEX.find_iter(text).count());
+                }
+                return vec![MessageRun::Text { text: text.to_string() }];
+            }
+        }
+        // try_lockが失敗した場合は他のスレッドがキャッシュを使用中なので続行
+
+        // Step 1: 正規表現でマッチを検出（ロック外）
+        let matches: Vec<_> = EMOJI_SHORTCUT_REGEX
+            .find_iter(text)
+            .map(|m| (m.start(), m.end()))
+            .collect();
+
+        // マッチがなければテキストをそのまま返す
+        if matches.is_empty() {
+            return vec![MessageRun::Text { text: text.to_string() }];
+        }
+
+        // Step 2: ユニークなショートカットを抽出（ロック外）
+        // 同じ絵文字が複数回使用されても、キャッシュget()は1回だけで済む
+        let unique_shortcuts: HashSet<&str> = matches
+            .iter()
+            .map(|&(start, end)| &text[start..end])
+            .collect();
+
+        // Step 3: キャッシュからユニークなショートカットのみ一括取得
+        // ショートカット -> EmojiInfo のマッピングを構築
+        let emoji_map: HashMap<String, EmojiInfo> = {
+            let mut cache = match self.entries.lock() {
+                Ok(c) => c,
+                Err(_) => return vec![MessageRun::Text { text: text.to_string() }],
+            };
+
+            // キャッシュが空ならそのままテキストを返す（Step 0でtry_lockが失敗した場合のフォールバック）
+            if cache.is_empty() {
+                self.record_lookups(0, unique_shortcuts.len());
+                return vec![MessageRun::Text { text: text.to_string() }];
+            }
+
+            // get()を使用してLRU順序を更新（頻繁にアクセスされる絵文字は残る）
+            //
+            // 設計判断: get() vs peek()
+            // - get(): LRU順序を更新（最近使用した項目は残る）
+            // - peek(): LRU順序を更新しない（挿入順でのみeviction）
+            //
+            // get()を選択した理由:
+            // 1. 長時間配信で頻繁に使われる絵文字（ホット絵文字）がevictされると
+            //    :_emoji:テキストに戻りユーザー体験が悪化
+            // 2. ロックは既に取得済みなので追加のロックオーバーヘッドはない
+            // 3. get()はLRU順序の更新のみで、内部的にはポインタ操作のみ（軽量）
+            // 4. キャッシュサイズ2000で通常の配信では十分だが、長時間・多絵文字の
+            //    配信でもホット絵文字を保持できる方がユーザー体験が良い
+            //
+            // 重複排除により、N個の絵文字使用があっても、ユニークなM個のみget()を呼び出す
+            // 例: ":_emoji1: :_emoji1: :_emoji2:" → 2回のget()で済む（3回ではなく）
+            let emoji_map: HashMap<String, EmojiInfo> = unique_shortcuts
+                .iter()
+                .filter_map(|&shortcut| {
+                    cache.get(shortcut).cloned().map(|emoji| (shortcut.to_string(), emoji))
+                })
+                .collect();
+            // ヒット・ミスはユニークなショートカット単位で数える
+            self.record_lookups(emoji_map.len(), unique_shortcuts.len() - emoji_map.len());
+            emoji_map
+            // ここでロック解放
+        };
+
+        // Step 4: 結果を組み立て（ロック外）
+        // 元のマッチ位置を基にemoji_mapから取得
+        let matches_with_emoji: Vec<(usize, usize, Option<EmojiInfo>)> = matches
+            .iter()
+            .map(|&(start, end)| {
+                let shortcut = &text[start..end];
+                let emoji_info = emoji_map.get(shortcut).cloned();
+                (start, end, emoji_info)
+            })
+            .collect();
+
+        // Step 5: 結果を組み立て（ロック外）
+        let mut result: Vec<MessageRun> = Vec::new();
+        let mut last_end = 0;
+
+        for (start, end, emoji_info) in matches_with_emoji {
+            // マッチ前のテキストを追加
+            if start > last_end {
+                let prefix = &text[last_end..start];
+                if !prefix.is_empty() {
+                    result.push(MessageRun::Text { text: prefix.to_string() });
+                }
+            }
+
+            // キャッシュに絵文字があれば画像に変換、なければテキストのまま
+            if let Some(emoji) = emoji_info {
+                // ホットパスのため、デバッグログ有効時のみフォーマットコストを払う
+                if log::log_enabled!(log::Level::Debug) {
+                    log::debug!("Converted text emoji from cache: {}", &text[start..end]);
+                }
+                result.push(MessageRun::Emoji { emoji });
+            } else {
+                result.push(MessageRun::Text { text: text[start..end].to_string() });
+            }
+
+            last_end = end;
+        }
+
+        // 残りのテキストを追加
+        if last_end < text.len() {
+            let suffix = &text[last_end..];
+            if !suffix.is_empty() {
+                result.push(MessageRun::Text { text: suffix.to_string() });
+            }
+        }
+
+        result
+    }
+}
+
+/// 現在ポーリング中の配信の絵文字キャッシュを登録
+///
+/// 配信を切り替えた際に新しいクライアントのキャッシュで置き換える（`None`で解除）
+pub fn set_active_emoji_cache(cache: Option<Arc<EmojiCache>>) {
+    *ACTIVE_EMOJI_CACHE.write().unwrap_or_else(|e| e.into_inner()) = cache;
+}
+
+/// 現在ポーリング中の配信の絵文字キャッシュ（未接続の場合は`None`）
+pub fn active_emoji_cache() -> Option<Arc<EmojiCache>> {
+    ACTIVE_EMOJI_CACHE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::youtube::types::{EmojiImage, EmojiThumbnail};
+
+    // ========================================
+    // LRUキャッシュテスト
+    // ========================================
+
+    #[test]
+    fn test_emoji_cache_is_per_instance() {
+        // 配信ごとのキャッシュは互いに影響しない
+        let stream_a = EmojiCache::new();
+        let stream_b = EmojiCache::new();
+        stream_a.put(&EmojiInfo {
+            emoji_id: "smile".to_string(),
+            shortcuts: vec![":_smile:".to_string(), ":_smiley:".to_string()],
+            image: EmojiImage { thumbnails: vec![] },
+            is_custom_emoji: true,
+        });
+
+        assert_eq!(stream_a.len(), 2);
+        assert!(stream_b.is_empty());
+        assert!(matches!(
+            stream_a.convert_text(":_smile:").as_slice(),
+            [MessageRun::Emoji { .. }]
+        ));
+        assert!(matches!(
+            stream_b.convert_text(":_smile:").as_slice(),
+            [MessageRun::Text { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_emoji_cache_size_limit() {
+        let emoji_cache = EmojiCache::new();
+
+        // 最大サイズ + 10 個のエントリを追加
+        for i in 0..(EMOJI_CACHE_MAX_SIZE + 10) {
+            let shortcut = format!(":_test{}:", i);
+            let emoji_info = EmojiInfo {
+                emoji_id: format!("emoji_{}", i),
+                shortcuts: vec![shortcut.clone()],
+                image: EmojiImage { thumbnails: vec![] },
+                is_custom_emoji: true,
+            };
+
+            if let Ok(mut cache) = emoji_cache.entries.lock() {
+                cache.put(shortcut, emoji_info);
+            }
+        }
+
+        // キャッシュサイズが最大値を超えないことを確認
+        let size = emoji_cache.len();
+        assert!(size <= EMOJI_CACHE_MAX_SIZE,
+            "Cache size {} should not exceed max size {}", size, EMOJI_CACHE_MAX_SIZE);
+
+        // 最新のエントリが存在することを確認
+        let latest_shortcut = format!(":_test{}:", EMOJI_CACHE_MAX_SIZE + 9);
+        if let Ok(mut cache) = emoji_cache.entries.lock() {
+            assert!(cache.get(&latest_shortcut).is_some(),
+                "Latest entry should be in cache");
+        }
+
+        // 最も古いエントリがLRUで削除されていることを確認
+        let oldest_shortcut = ":_test0:".to_string();
+        let mut cache = emoji_cache.entries.lock().unwrap();
+        assert!(cache.get(&oldest_shortcut).is_none(),
+            "Oldest entry should have been evicted");
+    }
+
+    #[test]
+    fn test_emoji_cache_lru_update() {
+        let emoji_cache = EmojiCache::new();
+
+        // 3つのエントリを追加
+        for i in 0..3 {
+            let shortcut = format!(":_lru{}:", i);
+            let emoji_info = EmojiInfo {
+                emoji_id: format!("lru_{}", i),
+                shortcuts: vec![shortcut.clone()],
+                image: EmojiImage { thumbnails: vec![] },
+                is_custom_emoji: true,
+            };
+
+            if let Ok(mut cache) = emoji_cache.entries.lock() {
+                cache.put(shortcut, emoji_info);
+            }
+        }
+
+        // 最初のエントリにアクセス（LRUが更新される）
+        if let Ok(mut cache) = emoji_cache.entries.lock() {
+            let _ = cache.get(":_lru0:");
+        }
+
+        // すべてのエントリが存在することを確認
+        let mut cache = emoji_cache.entries.lock().unwrap();
+        assert!(cache.get(":_lru0:").is_some());
+        assert!(cache.get(":_lru1:").is_some());
+        assert!(cache.get(":_lru2:").is_some());
+    }
+
+    #[test]
+    fn test_convert_text_with_emoji_cache_mixed() {
+        let emoji_cache = EmojiCache::new();
+
+        // テスト用の絵文字をキャッシュに登録
+        let test_emoji = EmojiInfo {
+            emoji_id: "test_smile".to_string(),
+            shortcuts: vec![":_smile:".to_string()],
+            image: EmojiImage {
+                thumbnails: vec![EmojiThumbnail {
+                    url: "https://example.com/smile.png".to_string(),
+                    width: 24,
+                    height: 24,
+                }],
+            },
+            is_custom_emoji: true,
+        };
+        if let Ok(mut cache) = emoji_cache.entries.lock() {
+            cache.put(":_smile:".to_string(), test_emoji);
+        }
+
+        // テキスト + 絵文字 + テキストの混合入力
+        let result = emoji_cache.convert_text("Hello :_smile: World");
+
+        // 結果の検証: [Text("Hello "), Emoji, Text(" World")]
+        assert_eq!(result.len(), 3, "Should have 3 runs");
+
+        // 1つ目: テキスト "Hello "
+        match &result[0] {
+            MessageRun::Text { text } => assert_eq!(text, "Hello "),
+            _ => panic!("First run should be Text"),
+        }
+
+        // 2つ目: 絵文字
+        match &result[1] {
+            MessageRun::Emoji { emoji } => {
+                assert_eq!(emoji.emoji_id, "test_smile");
+            }
+            _ => panic!("Second run should be Emoji"),
+        }
+
+        // 3つ目: テキスト " World"
+        match &result[2] {
+            MessageRun::Text { text } => assert_eq!(text, " World"),
+            _ => panic!("Third run should be Text"),
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_emoji_cache_stats() {
+        let emoji_cache = EmojiCache::new();
+
+        // キャッシュが空の場合もミスとして数える
+        emoji_cache.convert_text("Hello :_smile:");
+        let emoji = EmojiInfo {
+            emoji_id: "smile".to_string(),
+            shortcuts: vec![":_smile:".to_string()],
+            image: EmojiImage { thumbnails: vec![] },
+            is_custom_emoji: true,
+        };
+        if let Ok(mut cache) = emoji_cache.entries.lock() {
+            cache.put(":_smile:".to_string(), emoji);
+        }
+        // 同じショートカットの繰り返しは1回として数える
+        emoji_cache.convert_text(":_smile: :_smile: :_unknown:");
+
+        assert_eq!(
+            emoji_cache.stats(),
+            EmojiCacheStats {
+                size: 1,
+                max_size: EMOJI_CACHE_MAX_SIZE,
+                hits: 1,
+                misses: 2,
+            }
+        );
+
+        // クリアすると計測もリセットされる
+        emoji_cache.clear();
+        assert_eq!((emoji_cache.stats().hits, emoji_cache.stats().misses), (0, 0));
+    }
+
+    #[test]
+    fn test_convert_text_with_emoji_cache_miss() {
+        let emoji_cache = EmojiCache::new();
+
+        // ダミーの絵文字を1つ登録（キャッシュが空だと早期リターンするため）
+        let dummy_emoji = EmojiInfo {
+            emoji_id: "dummy".to_string(),
+            shortcuts: vec![":_dummy:".to_string()],
+            image: EmojiImage { thumbnails: vec![] },
+            is_custom_emoji: true,
+        };
+        if let Ok(mut cache) = emoji_cache.entries.lock() {
+            cache.put(":_dummy:".to_string(), dummy_emoji);
+        }
+
+        // キャッシュにない絵文字ショートカットはテキストのまま
+        let result = emoji_cache.convert_text("Hello :_unknown: World");
+
+        // 結果の検証: [Text("Hello "), Text(":_unknown:"), Text(" World")]
+        assert_eq!(result.len(), 3, "Should have 3 runs");
+
+        match &result[0] {
+            MessageRun::Text { text } => assert_eq!(text, "Hello "),
+            _ => panic!("First run should be Text"),
+        }
+
+        match &result[1] {
+            MessageRun::Text { text } => assert_eq!(text, ":_unknown:"),
+            _ => panic!("Second run should be Text (cache miss)"),
+        }
+
+        match &result[2] {
+            MessageRun::Text { text } => assert_eq!(text, " World"),
+            _ => panic!("Third run should be Text"),
+        }
+    }
+
+    #[test]
+    fn test_convert_text_with_empty_cache() {
+        let emoji_cache = EmojiCache::new();
+
+        // キャッシュが空の場合、テキストはそのまま返される
+        let result = emoji_cache.convert_text("Hello :_emoji: World");
+
+        // 結果の検証: [Text("Hello :_emoji: World")]（キャッシュ空→早期リターン）
+        assert_eq!(result.len(), 1, "Should have 1 run when cache is empty");
+
+        match &result[0] {
+            MessageRun::Text { text } => assert_eq!(text, "Hello :_emoji: World"),
+            _ => panic!("Should be Text"),
+        }
+    }
+
+    #[test]
+    fn test_convert_text_evicted_shortcut_stays_as_text() {
+        let emoji_cache = EmojiCache::new();
+
+        // 最初のエントリを登録（後でevictされる）
+        let evicted_shortcut = ":_evicted:";
+        let evicted_emoji = EmojiInfo {
+            emoji_id: "evicted_emoji".to_string(),
+            shortcuts: vec![evicted_shortcut.to_string()],
+            image: EmojiImage {
+                thumbnails: vec![EmojiThumbnail {
+                    url: "https://example.com/evicted.png".to_string(),
+                    width: 24,
+                    height: 24,
+                }],
+            },
+            is_custom_emoji: true,
+        };
+        if let Ok(mut cache) = emoji_cache.entries.lock() {
+            cache.put(evicted_shortcut.to_string(), evicted_emoji);
+        }
+
+        // キャッシュを最大サイズまで埋めてevictを発生させる
+        for i in 0..EMOJI_CACHE_MAX_SIZE {
+            let shortcut = format!(":_fill{}:", i);
+            let emoji_info = EmojiInfo {
+                emoji_id: format!("fill_{}", i),
+                shortcuts: vec![shortcut.clone()],
+                image: EmojiImage { thumbnails: vec![] },
+                is_custom_emoji: true,
+            };
+
+            if let Ok(mut cache) = emoji_cache.entries.lock() {
+                cache.put(shortcut, emoji_info);
+            }
+        }
+
+        // 最初のエントリがevictされていることを確認
+        if let Ok(mut cache) = emoji_cache.entries.lock() {
+            assert!(
+                cache.get(evicted_shortcut).is_none(),
+                "Evicted shortcut should not be in cache"
+            );
+        }
+
+        // evictされたショートカットを含むテキストを変換
+        let result = emoji_cache.convert_text("Test :_evicted: emoji");
+
+        // 結果の検証: evictされたショートカットはテキストとして残る
+        assert_eq!(result.len(), 3, "Should have 3 runs");
+
+        match &result[0] {
+            MessageRun::Text { text } => assert_eq!(text, "Test "),
+            _ => panic!("First run should be Text"),
+        }
+
+        // evictされたショートカットはテキストのまま
+        match &result[1] {
+            MessageRun::Text { text } => assert_eq!(text, evicted_shortcut),
+            _ => panic!("Second run should be Text (evicted shortcut)"),
+        }
+
+        match &result[2] {
+            MessageRun::Text { text } => assert_eq!(text, " emoji"),
+            _ => panic!("Third run should be Text"),
+        }
+
+        // キャッシュに残っているエントリは絵文字に変換されることを確認
+        // 注: :_fill0:はまだキャッシュにあるはずだが、LRUなので
+        // 大量のエントリを追加した後は最初のfillエントリもevictされている可能性がある
+        // 最後に追加したエントリで確認
+        let last_shortcut = format!(":_fill{}:", EMOJI_CACHE_MAX_SIZE - 1);
+        let result_last = emoji_cache.convert_text(&format!("Test {} emoji", last_shortcut));
+
+        assert_eq!(result_last.len(), 3, "Should have 3 runs for surviving emoji");
+        match &result_last[1] {
+            MessageRun::Emoji { emoji } => {
+                assert_eq!(emoji.emoji_id, format!("fill_{}", EMOJI_CACHE_MAX_SIZE - 1));
+            }
+            _ => panic!("Second run should be Emoji for surviving shortcut"),
+        }
+    }
+
+    /// get()による真のLRU eviction動作のテスト
+    ///
+    /// 設計判断: convert_text_with_emoji_cacheはget()を使用するため、
+    /// 頻繁にアクセスされる絵文字はLRU順序が更新され、evictされにくくなる。
+    /// これにより、長時間配信でもホット絵文字が保持され、ユーザー体験が向上。
+    #[test]
+    fn test_hot_emoji_survives_with_lru() {
+        let emoji_cache = EmojiCache::new();
+
+        // ホットエントリ（頻繁にアクセスされる）を最初に登録
+        let hot_shortcut = ":_hot_emoji:";
+        let hot_emoji = EmojiInfo {
+            emoji_id: "hot_emoji".to_string(),
+            shortcuts: vec![hot_shortcut.to_string()],
+            image: EmojiImage {
+                thumbnails: vec![EmojiThumbnail {
+                    url: "https://example.com/hot.png".to_string(),
+                    width: 24,
+                    height: 24,
+                }],
+            },
+            is_custom_emoji: true,
+        };
+        if let Ok(mut cache) = emoji_cache.entries.lock() {
+            cache.put(hot_shortcut.to_string(), hot_emoji);
+        }
+
+        // キャッシュを半分埋めつつ、ホットエントリを定期的にアクセス
+        // get()によりLRU順序が更新されるため、ホットエントリは最近使用として扱われる
+        for i in 0..EMOJI_CACHE_MAX_SIZE {
+            let shortcut = format!(":_newemoji{}:", i);
+            let emoji_info = EmojiInfo {
+                emoji_id: format!("new_{}", i),
+                shortcuts: vec![shortcut.clone()],
+                image: EmojiImage { thumbnails: vec![] },
+                is_custom_emoji: true,
+            };
+
+            if let Ok(mut cache) = emoji_cache.entries.lock() {
+                cache.put(shortcut, emoji_info);
+            }
+
+            // 10回に1回ホットエントリにアクセス（LRU更新）
+            if i % 10 == 0 {
+                let _ = emoji_cache.convert_text(&format!("Test {} text", hot_shortcut));
+            }
+        }
+
+        // ホットエントリがまだキャッシュに存在することを確認
+        // get()を使用しているため、頻繁なアクセスでLRU順序が更新されevictを防ぐ
+        let cache = emoji_cache.entries.lock().unwrap();
+        assert!(
+            cache.peek(hot_shortcut).is_some(),
+            "Hot emoji should survive due to frequent access (get updates LRU)"
+        );
+    }
+
+    #[test]
+    fn test_convert_text_with_multibyte_characters() {
+        let emoji_cache = EmojiCache::new();
+
+        // テスト用の絵文字をキャッシュに登録
+        let test_emoji = EmojiInfo {
+            emoji_id: "smile_emoji".to_string(),
+            shortcuts: vec![":_smile:".to_string()],
+            image: EmojiImage {
+                thumbnails: vec![EmojiThumbnail {
+                    url: "https://example.com/smile.png".to_string(),
+                    width: 24,
+                    height: 24,
+                }],
+            },
+            is_custom_emoji: true,
+        };
+        if let Ok(mut cache) = emoji_cache.entries.lock() {
+            cache.put(":_smile:".to_string(), test_emoji);
+        }
+
+        // マルチバイト文字（日本語）+ 絵文字ショートカット + マルチバイト文字
+        let result = emoji_cache.convert_text("こんにちは:_smile:世界");
+
+        // 結果の検証: [Text("こんにちは"), Emoji, Text("世界")]
+        assert_eq!(result.len(), 3, "Should have 3 runs with multibyte text");
+
+        // 1つ目: 日本語テキスト "こんにちは"
+        match &result[0] {
+            MessageRun::Text { text } => assert_eq!(text, "こんにちは"),
+            _ => panic!("First run should be Text"),
+        }
+
+        // 2つ目: 絵文字
+        match &result[1] {
+            MessageRun::Emoji { emoji } => {
+                assert_eq!(emoji.emoji_id, "smile_emoji");
+            }
+            _ => panic!("Second run should be Emoji"),
+        }
+
+        // 3つ目: 日本語テキスト "世界"
+        match &result[2] {
+            MessageRun::Text { text } => assert_eq!(text, "世界"),
+            _ => panic!("Third run should be Text"),
+        }
+
+        // 絵文字が連続する場合（マルチバイト文字の間）
+        let result2 = emoji_cache.convert_text("日本語:_smile::_smile:テスト");
+
+        // :_smile: が2つ連続するが、キャッシュには1つしか登録されていないので両方変換される
+        assert_eq!(result2.len(), 4, "Should have 4 runs with consecutive emojis");
+
+        match &result2[0] {
+            MessageRun::Text { text } => assert_eq!(text, "日本語"),
+            _ => panic!("First run should be Text '日本語'"),
+        }
+
+        match &result2[1] {
+            MessageRun::Emoji { emoji } => assert_eq!(emoji.emoji_id, "smile_emoji"),
+            _ => panic!("Second run should be Emoji"),
+        }
+
+        match &result2[2] {
+            MessageRun::Emoji { emoji } => assert_eq!(emoji.emoji_id, "smile_emoji"),
+            _ => panic!("Third run should be Emoji"),
+        }
+
+        match &result2[3] {
+            MessageRun::Text { text } => assert_eq!(text, "テスト"),
+            _ => panic!("Fourth run should be Text 'テスト'"),
+        }
+    }
+
+    // =========================================================================
+    // ストレステスト/ベンチマーク
+    // =========================================================================
+
+    /// 並行アクセスによるロック競合テスト
+    ///
+    /// 複数スレッドから同じキャッシュのconvert_textを同時に呼び出し、
+    /// ロック競合によるパフォーマンス劣化を検出する。
+    #[test]
+    fn test_concurrent_emoji_cache_access() {
+        use std::sync::Arc;
+        use std::thread;
+        use std::time::{Duration, Instant};
+
+        let emoji_cache = Arc::new(EmojiCache::new());
+
+        // テスト用の絵文字を複数登録
+        for i in 0..10 {
+            let shortcut = format!(":_test{}:", i);
+            let emoji_info = EmojiInfo {
+                emoji_id: format!("test_{}", i),
+                shortcuts: vec![shortcut.clone()],
+                image: EmojiImage {
+                    thumbnails: vec![EmojiThumbnail {
+                        url: format!("https://example.com/test{}.png", i),
+                        width: 24,
+                        height: 24,
+                    }],
+                },
+                is_custom_emoji: true,
+            };
+            if let Ok(mut cache) = emoji_cache.entries.lock() {
+                cache.put(shortcut, emoji_info);
+            }
+        }
+
+        // 並行スレッド数と各スレッドの呼び出し回数
+        let num_threads = 8;
+        let iterations_per_thread = 100;
+
+        // スレッドの待機用
+        let barrier = Arc::new(std::sync::Barrier::new(num_threads));
+
+        let handles: Vec<_> = (0..num_threads)
+            .map(|thread_id| {
+                let barrier = Arc::clone(&barrier);
+                let emoji_cache = Arc::clone(&emoji_cache);
+                thread::spawn(move || {
+                    // 全スレッドが揃うまで待機（同時実行を保証）
+                    barrier.wait();
+
+                    let start = Instant::now();
+                    let mut success_count = 0;
+
+                    for i in 0..iterations_per_thread {
+                        // 様々なパターンのテキストを変換
+                        let text = format!(
+                            "Thread {} iter {}: こんにちは:_test{}:世界:_test{}:テスト",
+                            thread_id,
+                            i,
+                            i % 10,
+                            (i + 1) % 10
+                        );
+                        let result = emoji_cache.convert_text(&text);
+
+                        // 結果が期待通りか確認（5パーツ: Text, Emoji, Text, Emoji, Text）
+                        if result.len() == 5 {
+                            success_count += 1;
+                        }
+                    }
+
+                    let elapsed = start.elapsed();
+                    (thread_id, success_count, elapsed)
+                })
+            })
+            .collect();
+
+        // 結果を収集
+        let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+        // すべてのスレッドが正常に完了したか確認
+        for (thread_id, success_count, elapsed) in &results {
+            assert_eq!(
+                *success_count, iterations_per_thread,
+                "Thread {} failed: only {} of {} succeeded",
+                thread_id, success_count, iterations_per_thread
+            );
+
+            // 各スレッドの処理時間が妥当か確認（1秒以内）
+            assert!(
+                *elapsed < Duration::from_secs(1),
+                "Thread {} took too long: {:?}",
+                thread_id,
+                elapsed
+            );
+        }
+
+        // 全体の統計
+        let total_elapsed: Duration = results.iter().map(|(_, _, e)| *e).sum();
+        let avg_elapsed = total_elapsed / num_threads as u32;
+        let total_calls = num_threads * iterations_per_thread;
+
+        // 平均処理時間をログ出力（ベンチマーク情報）
+        println!(
+            "Concurrent stress test: {} threads × {} iterations = {} total calls",
+            num_threads, iterations_per_thread, total_calls
+        );
+        println!("Average elapsed per thread: {:?}", avg_elapsed);
+        println!(
+            "Throughput: {:.0} calls/sec",
+            total_calls as f64 / avg_elapsed.as_secs_f64()
+        );
+    }
+
+    /// 多数の絵文字マッチ時のレイテンシテスト
+    ///
+    /// 1つのテキストに多数の絵文字ショートカットが含まれる場合の
+    /// 処理時間を測定し、レイテンシが許容範囲内か確認する。
+    #[test]
+    fn test_many_emoji_matches_latency() {
+        use std::time::Instant;
+
+        let emoji_cache = EmojiCache::new();
+
+        // テスト用の絵文字を50個登録
+        let emoji_count = 50;
+        for i in 0..emoji_count {
+            let shortcut = format!(":_emoji{}:", i);
+            let emoji_info = EmojiInfo {
+                emoji_id: format!("emoji_{}", i),
+                shortcuts: vec![shortcut.clone()],
+                image: EmojiImage {
+                    thumbnails: vec![EmojiThumbnail {
+                        url: format!("https://example.com/emoji{}.png", i),
+                        width: 24,
+                        height: 24,
+                    }],
+                },
+                is_custom_emoji: true,
+            };
+            if let Ok(mut cache) = emoji_cache.entries.lock() {
+                cache.put(shortcut, emoji_info);
+            }
+        }
+
+        // 多数の絵文字を含むテキストを生成
+        let mut text_parts: Vec<String> = Vec::new();
+        for i in 0..emoji_count {
+            text_parts.push(format!("text{}:_emoji{}:", i, i));
+        }
+        let long_text = text_parts.join("");
+
+        // 処理時間を測定（複数回実行して平均を取る）
+        let iterations = 100;
+        let start = Instant::now();
+
+        for _ in 0..iterations {
+            let result = emoji_cache.convert_text(&long_text);
+            // 結果が期待通りか確認（各絵文字の前にテキストがあるので、emoji_count * 2パーツ）
+            assert_eq!(
+                result.len(),
+                emoji_count * 2,
+                "Expected {} runs, got {}",
+                emoji_count * 2,
+                result.len()
+            );
+        }
+
+        let elapsed = start.elapsed();
+        let avg_latency = elapsed / iterations as u32;
+
+        println!(
+            "Many emoji matches test: {} emojis × {} iterations",
+            emoji_count, iterations
+        );
+        println!("Total elapsed: {:?}", elapsed);
+        println!("Average latency per call: {:?}", avg_latency);
+
+        // レイテンシが許容範囲内か確認（1回あたり10ms以内）
+        assert!(
+            avg_latency < std::time::Duration::from_millis(10),
+            "Average latency ({:?}) exceeds threshold (10ms)",
+            avg_latency
+        );
+    }
+}
@@ -19,11 +19,15 @@
 #![allow(dead_code)]
 
 pub mod client;
+pub mod diagnostics;
+pub mod emoji_cache;
+pub mod overrides;
 pub mod parser;
 pub mod types;
 
 pub use client::InnerTubeClient;
-pub use parser::{parse_chat_response, clear_emoji_cache};
+pub use emoji_cache::EmojiCache;
+pub use parser::parse_chat_response;
 pub use types::INNERTUBE_BUFFER_INTERVAL_MS;
 // types::*は現在InnerTubeポーリングでのみ内部使用されるため、
 // 外部からの使用はない。将来のフル統合に向けて保持。
@@ -0,0 +1,118 @@
+//! InnerTubeクライアントのUser-Agent・クライアントバージョンの上書き
+//!
+//! YouTube側の仕様変更でInnerTubeの取得が失敗するようになった場合に、アップデートを待たずに
+//! 設定から回避できるようにする。未指定の場合は組み込みの値（[`super::client::USER_AGENT`]、
+//! ページから抽出したバージョンまたは[`super::client::FALLBACK_CLIENT_VERSION`]）を使う。
+//! 変更は次に作成するクライアント（ポーリング開始時）から適用する。
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+/// User-Agentの最大文字数
+pub const MAX_USER_AGENT_CHARS: usize = 512;
+
+/// クライアントバージョンの形式（X.YYYYMMDD.XX.XX）
+static CLIENT_VERSION_FORMAT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\d+\.\d{8}\.\d{2}\.\d{2}$").expect("Failed to compile client version format regex")
+});
+
+/// InnerTubeクライアントの上書き設定
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InnerTubeOverrides {
+    /// User-Agent（Noneは組み込みの値）
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// クライアントバージョン（Noneはページから抽出した値、抽出できない場合は組み込みの値）
+    #[serde(default)]
+    pub client_version: Option<String>,
+}
+
+impl InnerTubeOverrides {
+    /// 設定を検証し、前後の空白を除去（空の値は未指定として扱う）
+    ///
+    /// ## 入力検証
+    /// - User-Agent: 512文字以内、制御文字を含まない
+    /// - クライアントバージョン: X.YYYYMMDD.XX.XX形式（例: 2.20251201.01.00）
+    pub fn normalize(self) -> Result<Self, String> {
+        let user_agent = non_empty(self.user_agent);
+        if let Some(user_agent) = &user_agent {
+            if user_agent.chars().count() > MAX_USER_AGENT_CHARS {
+                return Err(format!("User-Agentは{}文字以内で指定してください", MAX_USER_AGENT_CHARS));
+            }
+            if user_agent.chars().any(char::is_control) {
+                return Err("User-Agentに制御文字は使用できません".to_string());
+            }
+        }
+        let client_version = non_empty(self.client_version);
+        if let Some(version) = &client_version {
+            if !CLIENT_VERSION_FORMAT.is_match(version) {
+                return Err(format!(
+                    "クライアントバージョンはX.YYYYMMDD.XX.XX形式で指定してください: {}",
+                    version
+                ));
+            }
+        }
+        Ok(Self {
+            user_agent,
+            client_version,
+        })
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// 現在の上書き設定
+/// 起動時にDBから読み込み、設定コマンドで更新される
+static OVERRIDES: Lazy<RwLock<InnerTubeOverrides>> = Lazy::new(|| RwLock::new(InnerTubeOverrides::default()));
+
+/// 現在の上書き設定を取得
+pub fn overrides() -> InnerTubeOverrides {
+    OVERRIDES.read().map(|overrides| overrides.clone()).unwrap_or_default()
+}
+
+/// 上書き設定を更新（次に作成するクライアントから適用）
+pub fn set_overrides(overrides: InnerTubeOverrides) {
+    match OVERRIDES.write() {
+        Ok(mut current) => *current = overrides,
+        Err(e) => log::error!("Failed to update InnerTube overrides: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_overrides() {
+        let overrides = InnerTubeOverrides {
+            user_agent: Some("  Mozilla/5.0 (X11; Linux x86_64)  ".to_string()),
+            client_version: Some("   ".to_string()),
+        }
+        .normalize()
+        .unwrap();
+        assert_eq!(overrides.user_agent.as_deref(), Some("Mozilla/5.0 (X11; Linux x86_64)"));
+        assert_eq!(overrides.client_version, None);
+
+        let version = |version: &str| InnerTubeOverrides {
+            client_version: Some(version.to_string()),
+            ..InnerTubeOverrides::default()
+        };
+        assert!(version("2.20260101.00.00").normalize().is_ok());
+        assert!(version("2.2026.00.00").normalize().is_err());
+        assert!(version("2.20260101.00.00\"").normalize().is_err());
+
+        let user_agent = |user_agent: String| InnerTubeOverrides {
+            user_agent: Some(user_agent),
+            ..InnerTubeOverrides::default()
+        };
+        assert!(user_agent("Mozilla/5.0\r\nX-Injected: 1".to_string()).normalize().is_err());
+        assert!(user_agent("a".repeat(MAX_USER_AGENT_CHARS + 1)).normalize().is_err());
+    }
+}
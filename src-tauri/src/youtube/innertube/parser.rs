@@ -1,48 +1,19 @@
 //! InnerTube レスポンスパーサー
 
 use chrono::{TimeZone, Utc};
-use std::collections::HashSet;
-use std::sync::Mutex;
-use once_cell::sync::Lazy;
-use regex::Regex;
-use lru::LruCache;
-use std::num::NonZeroUsize;
 
+use super::emoji_cache::EmojiCache;
 use super::types::*;
-use crate::youtube::types::{ChatMessage, EmojiImage, EmojiInfo, EmojiThumbnail, MessageRun, MessageType};
-
-/// 絵文字キャッシュの最大サイズ
-/// YouTube絵文字は通常数百程度なので、2000で十分
-const EMOJI_CACHE_MAX_SIZE: usize = 2000;
-
-/// 絵文字キャッシュ: ショートカット -> EmojiInfo (LRUキャッシュ)
-/// InnerTubeレスポンスで取得した絵文字情報をキャッシュし、
-/// テキストトークンで送られてきた絵文字ショートカットを画像に変換するために使用
-static EMOJI_CACHE: Lazy<Mutex<LruCache<String, EmojiInfo>>> = Lazy::new(|| {
-    Mutex::new(LruCache::new(NonZeroUsize::new(EMOJI_CACHE_MAX_SIZE).unwrap()))
-});
-
-/// 絵文字ショートカットパターン（:_xxx:形式）
-static EMOJI_SHORTCUT_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r":_[^:]+:").expect("Failed to compile emoji shortcut regex")
-});
-
-/// 絵文字キャッシュをクリア（テスト用・デバッグ用）
-#[allow(dead_code)]
-pub fn clear_emoji_cache() {
-    if let Ok(mut cache) = EMOJI_CACHE.lock() {
-        cache.clear();
-    }
-}
-
-/// 絵文字キャッシュのサイズを取得（デバッグ用）
-#[allow(dead_code)]
-pub fn get_emoji_cache_size() -> usize {
-    EMOJI_CACHE.lock().map(|c| c.len()).unwrap_or(0)
-}
+use crate::youtube::labels::{self, LabelLocale};
+use crate::youtube::types::{
+    badges_from_flags, ChatMessage, EmojiImage, EmojiInfo, EmojiThumbnail, MessageRun, MessageType, BADGE_MEMBER,
+    BADGE_MODERATOR, BADGE_OWNER, BADGE_VERIFIED,
+};
 
 /// InnerTubeレスポンスをChatMessageリストに変換
-pub fn parse_chat_response(response: InnerTubeChatResponse) -> Vec<ChatMessage> {
+///
+/// `cache`は配信ごとの絵文字キャッシュ（`InnerTubeClient::emoji_cache`）
+pub fn parse_chat_response(response: InnerTubeChatResponse, cache: &EmojiCache) -> Vec<ChatMessage> {
     let Some(contents) = response.continuation_contents else {
         return vec![];
     };
@@ -56,7 +27,7 @@ pub fn parse_chat_response(response: InnerTubeChatResponse) -> Vec<ChatMessage>
     // flat_mapを使用してparse_actionが返す複数メッセージを統合
     actions
         .into_iter()
-        .flat_map(parse_action)
+        .flat_map(|action| parse_action(action, cache))
         .collect()
 }
 
@@ -64,10 +35,10 @@ pub fn parse_chat_response(response: InnerTubeChatResponse) -> Vec<ChatMessage>
 ///
 /// リプレイアクションには複数のメッセージが含まれる場合があるため、
 /// Vec<ChatMessage>を返す設計に変更。
-fn parse_action(action: ChatAction) -> Vec<ChatMessage> {
+fn parse_action(action: ChatAction, cache: &EmojiCache) -> Vec<ChatMessage> {
     // 通常のメッセージ追加
     if let Some(add_action) = action.add_chat_item_action {
-        if let Some(msg) = parse_chat_item(add_action.item) {
+        if let Some(msg) = parse_chat_item(add_action.item, cache) {
             return vec![msg];
         }
         return vec![];
@@ -81,7 +52,7 @@ fn parse_action(action: ChatAction) -> Vec<ChatMessage> {
                 .into_iter()
                 .filter_map(|inner_action| {
                     inner_action.add_chat_item_action
-                        .and_then(|add_action| parse_chat_item(add_action.item))
+                        .and_then(|add_action| parse_chat_item(add_action.item, cache))
                 })
                 .collect();
             return messages;
@@ -92,40 +63,43 @@ fn parse_action(action: ChatAction) -> Vec<ChatMessage> {
 }
 
 /// チャットアイテムをパース
-fn parse_chat_item(item: ChatItem) -> Option<ChatMessage> {
+fn parse_chat_item(item: ChatItem, cache: &EmojiCache) -> Option<ChatMessage> {
+    // デフォルトラベルの言語（アイテムごとに1回だけ参照する）
+    let locale = labels::current_locale();
+
     // テキストメッセージ
     if let Some(text_msg) = item.live_chat_text_message_renderer {
-        return Some(parse_text_message(text_msg));
+        return Some(parse_text_message(text_msg, cache));
     }
 
     // スーパーチャット
     if let Some(paid_msg) = item.live_chat_paid_message_renderer {
-        return Some(parse_paid_message(paid_msg));
+        return Some(parse_paid_message(paid_msg, cache));
     }
 
     // スーパーステッカー
     if let Some(sticker_msg) = item.live_chat_paid_sticker_renderer {
-        return Some(parse_sticker_message(sticker_msg));
+        return Some(parse_sticker_message(sticker_msg, locale));
     }
 
     // メンバーシップ
     if let Some(member_msg) = item.live_chat_membership_item_renderer {
-        return Some(parse_membership_message(member_msg));
+        return Some(parse_membership_message(member_msg, locale, cache));
     }
 
     // メンバーシップギフト
     if let Some(gift_msg) = item.live_chat_sponsor_gift_announcement_renderer {
-        return Some(parse_gift_message(gift_msg));
+        return Some(parse_gift_message(gift_msg, locale));
     }
 
     None
 }
 
 /// テキストメッセージをパース
-fn parse_text_message(msg: LiveChatTextMessageRenderer) -> ChatMessage {
-    let message_runs = msg.message.as_ref().and_then(|m| parse_runs(&m.runs));
+fn parse_text_message(msg: LiveChatTextMessageRenderer, cache: &EmojiCache) -> ChatMessage {
+    let message_runs = msg.message.as_ref().and_then(|m| parse_runs(&m.runs, cache));
     let message_text = extract_plain_text(&message_runs);
-    let (is_owner, is_moderator, is_member) = parse_author_badges(&msg.author_badges);
+    let author = parse_author_badges(&msg.author_badges);
     let published_at = parse_timestamp(&msg.timestamp_usec);
 
     ChatMessage {
@@ -141,20 +115,21 @@ fn parse_text_message(msg: LiveChatTextMessageRenderer) -> ChatMessage {
             .and_then(|p| p.thumbnails.first().map(|t| t.url.clone()))
             .unwrap_or_default(),
         published_at,
-        is_owner,
-        is_moderator,
-        is_member,
-        is_verified: false,
+        is_owner: author.is_owner,
+        is_moderator: author.is_moderator,
+        is_member: author.is_member,
+        is_verified: author.is_verified,
+        badges: author.badges,
         message_type: MessageType::Text,
         message_runs,
     }
 }
 
 /// スーパーチャットをパース
-fn parse_paid_message(msg: LiveChatPaidMessageRenderer) -> ChatMessage {
-    let message_runs = msg.message.as_ref().and_then(|m| parse_runs(&m.runs));
+fn parse_paid_message(msg: LiveChatPaidMessageRenderer, cache: &EmojiCache) -> ChatMessage {
+    let message_runs = msg.message.as_ref().and_then(|m| parse_runs(&m.runs, cache));
     let message_text = extract_plain_text(&message_runs);
-    let (is_owner, is_moderator, is_member) = parse_author_badges(&msg.author_badges);
+    let author = parse_author_badges(&msg.author_badges);
     let published_at = parse_timestamp(&msg.timestamp_usec);
 
     // 金額テキストをパース（例: "¥1,000" -> amount="1,000", currency="JPY"）
@@ -177,29 +152,50 @@ fn parse_paid_message(msg: LiveChatPaidMessageRenderer) -> ChatMessage {
             .and_then(|p| p.thumbnails.first().map(|t| t.url.clone()))
             .unwrap_or_default(),
         published_at,
-        is_owner,
-        is_moderator,
-        is_member,
-        is_verified: false,
-        message_type: MessageType::SuperChat { amount, currency },
+        is_owner: author.is_owner,
+        is_moderator: author.is_moderator,
+        is_member: author.is_member,
+        is_verified: author.is_verified,
+        badges: author.badges,
+        // InnerTubeは表示文字列のみのため、金額はスパチャ処理側でパースする
+        message_type: MessageType::SuperChat {
+            amount,
+            currency,
+            amount_micros: None,
+        },
         message_runs,
     }
 }
 
 /// スーパーステッカーをパース
-fn parse_sticker_message(msg: LiveChatPaidStickerRenderer) -> ChatMessage {
-    let (is_owner, is_moderator, is_member) = parse_author_badges(&msg.author_badges);
+fn parse_sticker_message(msg: LiveChatPaidStickerRenderer, locale: LabelLocale) -> ChatMessage {
+    let author = parse_author_badges(&msg.author_badges);
     let published_at = parse_timestamp(&msg.timestamp_usec);
 
-    // ステッカーIDを抽出
+    // ステッカーIDを抽出（InnerTubeではIDがないため画像URLを使用）
     let sticker_id = msg
         .sticker
         .and_then(|s| s.thumbnails.first().map(|t| t.url.clone()))
         .unwrap_or_default();
+    let image_url = normalize_sticker_url(&sticker_id);
+
+    // 金額テキストをパース（スパチャと同じ形式）
+    let amount_text = msg
+        .purchase_amount_text
+        .map(|t| t.get_text())
+        .unwrap_or_default();
+    let (amount, currency) = parse_amount(&amount_text);
+
+    // 画像が取得できない場合は代替表示の文言を本文にする
+    let message = if image_url.is_none() {
+        locale.super_sticker().to_string()
+    } else {
+        String::new()
+    };
 
     ChatMessage {
         id: msg.id,
-        message: String::new(),
+        message,
         author_name: msg
             .author_name
             .map(|n| n.get_text())
@@ -210,27 +206,62 @@ fn parse_sticker_message(msg: LiveChatPaidStickerRenderer) -> ChatMessage {
             .and_then(|p| p.thumbnails.first().map(|t| t.url.clone()))
             .unwrap_or_default(),
         published_at,
-        is_owner,
-        is_moderator,
-        is_member,
-        is_verified: false,
-        message_type: MessageType::SuperSticker { sticker_id },
+        is_owner: author.is_owner,
+        is_moderator: author.is_moderator,
+        is_member: author.is_member,
+        is_verified: author.is_verified,
+        badges: author.badges,
+        message_type: MessageType::SuperSticker {
+            sticker_id,
+            amount,
+            currency,
+            amount_micros: None,
+            image_url,
+        },
         message_runs: None,
     }
 }
 
+/// ステッカー画像URLを正規化（プロトコル相対URLはhttpsに補完）
+fn normalize_sticker_url(url: &str) -> Option<String> {
+    if url.is_empty() {
+        None
+    } else if url.starts_with("//") {
+        Some(format!("https:{}", url))
+    } else {
+        Some(url.to_string())
+    }
+}
+
 /// メンバーシップメッセージをパース
-fn parse_membership_message(msg: LiveChatMembershipItemRenderer) -> ChatMessage {
-    let message_runs = msg.message.as_ref().and_then(|m| parse_runs(&m.runs));
+fn parse_membership_message(
+    msg: LiveChatMembershipItemRenderer,
+    locale: LabelLocale,
+    cache: &EmojiCache,
+) -> ChatMessage {
+    let message_runs = msg.message.as_ref().and_then(|m| parse_runs(&m.runs, cache));
     let message_text = extract_plain_text(&message_runs);
-    let (is_owner, is_moderator, _) = parse_author_badges(&msg.author_badges);
+    let mut author = parse_author_badges(&msg.author_badges);
+    // メンバーシップの投稿者はメンバー（加入直後はメンバーバッジが付いていない場合がある）
+    if !author.is_member {
+        author.is_member = true;
+        author.badges.push(BADGE_MEMBER.to_string());
+    }
     let published_at = parse_timestamp(&msg.timestamp_usec);
 
     // メンバーシップレベルを抽出
-    let level = msg
-        .header_sub_text
-        .and_then(|t| t.runs.and_then(|r| r.first().and_then(|i| i.text.clone())))
-        .unwrap_or_else(|| "新規メンバー".to_string());
+    // マイルストーンは継続期間（headerPrimaryText、数字が別runに分かれるため連結）を優先
+    let milestone = msg
+        .header_primary_text
+        .and_then(|t| t.runs)
+        .map(|runs| runs.iter().filter_map(|run| run.text.as_deref()).collect::<String>())
+        .filter(|text| !text.trim().is_empty());
+    let level = milestone
+        .or_else(|| {
+            msg.header_sub_text
+                .and_then(|t| t.runs.and_then(|r| r.first().and_then(|i| i.text.clone())))
+        })
+        .unwrap_or_else(|| locale.new_member().to_string());
 
     ChatMessage {
         id: msg.id,
@@ -245,17 +276,18 @@ fn parse_membership_message(msg: LiveChatMembershipItemRenderer) -> ChatMessage
             .and_then(|p| p.thumbnails.first().map(|t| t.url.clone()))
             .unwrap_or_default(),
         published_at,
-        is_owner,
-        is_moderator,
-        is_member: true,
-        is_verified: false,
+        is_owner: author.is_owner,
+        is_moderator: author.is_moderator,
+        is_member: author.is_member,
+        is_verified: author.is_verified,
+        badges: author.badges,
         message_type: MessageType::Membership { level },
         message_runs,
     }
 }
 
 /// メンバーシップギフトをパース
-fn parse_gift_message(msg: LiveChatSponsorGiftRenderer) -> ChatMessage {
+fn parse_gift_message(msg: LiveChatSponsorGiftRenderer, locale: LabelLocale) -> ChatMessage {
     let published_at = parse_timestamp(&msg.timestamp_usec);
 
     // ギフト数を抽出（例: "5件のメンバーシップをギフトしました"）
@@ -272,6 +304,13 @@ fn parse_gift_message(msg: LiveChatSponsorGiftRenderer) -> ChatMessage {
         .parse()
         .unwrap_or(1);
 
+    // 表示文字列が取得できない場合は設定言語のデフォルト文言
+    let gift_text = if gift_text.is_empty() {
+        locale.membership_gift(count)
+    } else {
+        gift_text
+    };
+
     ChatMessage {
         id: msg.id,
         message: gift_text,
@@ -289,6 +328,7 @@ fn parse_gift_message(msg: LiveChatSponsorGiftRenderer) -> ChatMessage {
         is_moderator: false,
         is_member: true,
         is_verified: false,
+        badges: badges_from_flags(false, false, true, false),
         message_type: MessageType::MembershipGift { count },
         message_runs: None,
     }
@@ -300,7 +340,7 @@ fn parse_gift_message(msg: LiveChatSponsorGiftRenderer) -> ChatMessage {
 /// 絵文字キャッシュ機能:
 /// 1. 絵文字オブジェクトを受信したらショートカット→EmojiInfoをキャッシュ
 /// 2. テキストトークン内の:_xxx:パターンをキャッシュから画像に変換
-fn parse_runs(runs: &Option<Vec<RunItem>>) -> Option<Vec<MessageRun>> {
+fn parse_runs(runs: &Option<Vec<RunItem>>, cache: &EmojiCache) -> Option<Vec<MessageRun>> {
     let runs = runs.as_ref()?;
     if runs.is_empty() {
         return None;
@@ -310,41 +350,18 @@ fn parse_runs(runs: &Option<Vec<RunItem>>) -> Option<Vec<MessageRun>> {
 
     for run in runs {
         if let Some(emoji) = &run.emoji {
-            // 空のemoji_idは無効なのでスキップ
-            if emoji.emoji_id.is_empty() {
+            let Some(emoji_info) = to_emoji_info(emoji) else {
                 log::debug!("Skipping emoji with empty emoji_id");
                 continue;
-            }
-
-            let emoji_info = EmojiInfo {
-                emoji_id: emoji.emoji_id.clone(),
-                shortcuts: emoji.shortcuts.clone().unwrap_or_default(),
-                image: EmojiImage {
-                    thumbnails: emoji
-                        .image
-                        .thumbnails
-                        .iter()
-                        .map(|t| EmojiThumbnail {
-                            url: t.url.clone(),
-                            width: t.width.unwrap_or(24),
-                            height: t.height.unwrap_or(24),
-                        })
-                        .collect(),
-                },
-                is_custom_emoji: emoji.is_custom_emoji.unwrap_or(false),
             };
 
             // キャッシュに追加/更新（ショートカットごとに登録、常に最新を反映）
-            if let Ok(mut cache) = EMOJI_CACHE.lock() {
-                for shortcut in &emoji_info.shortcuts {
-                    cache.put(shortcut.clone(), emoji_info.clone());
-                }
-            }
+            cache.put(&emoji_info);
 
             parsed.push(MessageRun::Emoji { emoji: emoji_info });
         } else if let Some(text) = &run.text {
             // テキストトークン内の:_xxx:パターンをキャッシュから画像に変換
-            let converted = convert_text_with_emoji_cache(text);
+            let converted = cache.convert_text(text);
             parsed.extend(converted);
         }
     }
@@ -356,132 +373,30 @@ fn parse_runs(runs: &Option<Vec<RunItem>>) -> Option<Vec<MessageRun>> {
     }
 }
 
-/// テキスト内の:_xxx:パターンを絵文字キャッシュから画像に変換
-///
-/// 例: "こんにちは:_草lol:です" → [Text("こんにちは"), Emoji(...), Text("です")]
-///
-/// ロック範囲を最小化するため:
-/// 0. キャッシュ空チェック（try_lock）- cold-cache時の正規表現スキャンを回避
-/// 1. 正規表現でマッチを検出（ロック外）
-/// 2. ユニークなショートカットを抽出（ロック外）- 重複排除でget()回数削減
-/// 3. キャッシュからユニークなショートカットのみ一括取得（ロック範囲最小）
-/// 4. 結果を組み立て（ロック外）
-fn convert_text_with_emoji_cache(text: &str) -> Vec<MessageRun> {
-    // Step 0: キャッシュが空なら正規表現スキャンをスキップ（cold-cache最適化）
-    // try_lockを使用してブロッキングせずにチェック
-    if let Ok(cache) = EMOJI_CACHE.try_lock() {
-        if cache.is_empty() {
-            return vec![MessageRun::Text { text: text.to_string() }];
-        }
-    }
-    // try_lockが失敗した場合は他のスレッドがキャッシュを使用中なので続行
-
-    // Step 1: 正規表現でマッチを検出（ロック外）
-    let matches: Vec<_> = EMOJI_SHORTCUT_REGEX
-        .find_iter(text)
-        .map(|m| (m.start(), m.end()))
-        .collect();
-
-    // マッチがなければテキストをそのまま返す
-    if matches.is_empty() {
-        return vec![MessageRun::Text { text: text.to_string() }];
-    }
-
-    // Step 2: ユニークなショートカットを抽出（ロック外）
-    // 同じ絵文字が複数回使用されても、キャッシュget()は1回だけで済む
-    let unique_shortcuts: HashSet<&str> = matches
-        .iter()
-        .map(|&(start, end)| &text[start..end])
-        .collect();
-
-    // Step 3: キャッシュからユニークなショートカットのみ一括取得
-    // ショートカット -> EmojiInfo のマッピングを構築
-    let emoji_map: std::collections::HashMap<String, EmojiInfo> = {
-        let mut cache = match EMOJI_CACHE.lock() {
-            Ok(c) => c,
-            Err(_) => return vec![MessageRun::Text { text: text.to_string() }],
-        };
-
-        // キャッシュが空ならそのままテキストを返す（Step 0でtry_lockが失敗した場合のフォールバック）
-        if cache.is_empty() {
-            return vec![MessageRun::Text { text: text.to_string() }];
-        }
-
-        // get()を使用してLRU順序を更新（頻繁にアクセスされる絵文字は残る）
-        //
-        // 設計判断: get() vs peek()
-        // - get(): LRU順序を更新（最近使用した項目は残る）
-        // - peek(): LRU順序を更新しない（挿入順でのみeviction）
-        //
-        // get()を選択した理由:
-        // 1. 長時間配信で頻繁に使われる絵文字（ホット絵文字）がevictされると
-        //    :_emoji:テキストに戻りユーザー体験が悪化
-        // 2. ロックは既に取得済みなので追加のロックオーバーヘッドはない
-        // 3. get()はLRU順序の更新のみで、内部的にはポインタ操作のみ（軽量）
-        // 4. キャッシュサイズ2000で通常の配信では十分だが、長時間・多絵文字の
-        //    配信でもホット絵文字を保持できる方がユーザー体験が良い
-        //
-        // 重複排除により、N個の絵文字使用があっても、ユニークなM個のみget()を呼び出す
-        // 例: ":_emoji1: :_emoji1: :_emoji2:" → 2回のget()で済む（3回ではなく）
-        unique_shortcuts
-            .iter()
-            .filter_map(|&shortcut| {
-                cache.get(shortcut).cloned().map(|emoji| (shortcut.to_string(), emoji))
-            })
-            .collect()
-        // ここでロック解放
-    };
-
-    // Step 4: 結果を組み立て（ロック外）
-    // 元のマッチ位置を基にemoji_mapから取得
-    let matches_with_emoji: Vec<(usize, usize, Option<EmojiInfo>)> = matches
-        .iter()
-        .map(|&(start, end)| {
-            let shortcut = &text[start..end];
-            let emoji_info = emoji_map.get(shortcut).cloned();
-            (start, end, emoji_info)
-        })
-        .collect();
-
-    // Step 5: 結果を組み立て（ロック外）
-    let mut result: Vec<MessageRun> = Vec::new();
-    let mut last_end = 0;
-
-    for (start, end, emoji_info) in matches_with_emoji {
-        // マッチ前のテキストを追加
-        if start > last_end {
-            let prefix = &text[last_end..start];
-            if !prefix.is_empty() {
-                result.push(MessageRun::Text { text: prefix.to_string() });
-            }
-        }
-
-        // キャッシュに絵文字があれば画像に変換、なければテキストのまま
-        if let Some(emoji) = emoji_info {
-            // ホットパスのため、デバッグログ有効時のみフォーマットコストを払う
-            if log::log_enabled!(log::Level::Debug) {
-                log::debug!("Converted text emoji from cache: {}", &text[start..end]);
-            }
-            result.push(MessageRun::Emoji { emoji });
-        } else {
-            result.push(MessageRun::Text { text: text[start..end].to_string() });
-        }
-
-        last_end = end;
-    }
-
-    // 残りのテキストを追加
-    if last_end < text.len() {
-        let suffix = &text[last_end..];
-        if !suffix.is_empty() {
-            result.push(MessageRun::Text { text: suffix.to_string() });
-        }
+/// InnerTubeの絵文字をEmojiInfoに変換（空のemoji_idは無効なのでNone）
+pub(crate) fn to_emoji_info(emoji: &InnerTubeEmoji) -> Option<EmojiInfo> {
+    if emoji.emoji_id.is_empty() {
+        return None;
     }
-
-    result
+    Some(EmojiInfo {
+        emoji_id: emoji.emoji_id.clone(),
+        shortcuts: emoji.shortcuts.clone().unwrap_or_default(),
+        image: EmojiImage {
+            thumbnails: emoji
+                .image
+                .thumbnails
+                .iter()
+                .map(|t| EmojiThumbnail {
+                    url: t.url.clone(),
+                    width: t.width.unwrap_or(24),
+                    height: t.height.unwrap_or(24),
+                })
+                .collect(),
+        },
+        is_custom_emoji: emoji.is_custom_emoji.unwrap_or(false),
+    })
 }
 
-
 /// MessageRunリストからプレーンテキストを抽出
 fn extract_plain_text(runs: &Option<Vec<MessageRun>>) -> String {
     runs.as_ref()
@@ -500,34 +415,63 @@ fn extract_plain_text(runs: &Option<Vec<MessageRun>>) -> String {
         .unwrap_or_default()
 }
 
-/// 投稿者バッジからフラグを判定
-fn parse_author_badges(badges: &Option<Vec<AuthorBadge>>) -> (bool, bool, bool) {
+/// 投稿者バッジから判定したフラグとバッジ種別
+#[derive(Debug, Default, PartialEq, Eq)]
+struct AuthorBadges {
+    is_owner: bool,
+    is_moderator: bool,
+    is_member: bool,
+    is_verified: bool,
+    /// 検出したバッジ種別（重複なし、バッジの並び順）
+    badges: Vec<String>,
+}
+
+impl AuthorBadges {
+    fn add(&mut self, badge: &str) {
+        if !self.badges.iter().any(|b| b == badge) {
+            self.badges.push(badge.to_string());
+        }
+    }
+}
+
+/// 投稿者バッジからフラグとバッジ種別を判定
+///
+/// 未知のアイコン種別はフラグを立てず、小文字にしてバッジ種別にのみ含める
+fn parse_author_badges(badges: &Option<Vec<AuthorBadge>>) -> AuthorBadges {
+    let mut author = AuthorBadges::default();
     let Some(badges) = badges else {
-        return (false, false, false);
+        return author;
     };
 
-    let mut is_owner = false;
-    let mut is_moderator = false;
-    let mut is_member = false;
-
     for badge in badges {
         if let Some(renderer) = &badge.live_chat_author_badge_renderer {
             if let Some(icon) = &renderer.icon {
                 match icon.icon_type.as_str() {
-                    "OWNER" => is_owner = true,
-                    "MODERATOR" => is_moderator = true,
-                    "VERIFIED" => {} // is_verifiedは別途設定
+                    "OWNER" => {
+                        author.is_owner = true;
+                        author.add(BADGE_OWNER);
+                    }
+                    "MODERATOR" => {
+                        author.is_moderator = true;
+                        author.add(BADGE_MODERATOR);
+                    }
+                    "VERIFIED" => {
+                        author.is_verified = true;
+                        author.add(BADGE_VERIFIED);
+                    }
+                    other if !other.is_empty() => author.add(&other.to_lowercase()),
                     _ => {}
                 }
             }
             // カスタムサムネイルがある場合はメンバー
             if renderer.custom_thumbnail.is_some() {
-                is_member = true;
+                author.is_member = true;
+                author.add(BADGE_MEMBER);
             }
         }
     }
 
-    (is_owner, is_moderator, is_member)
+    author
 }
 
 /// タイムスタンプをパース（マイクロ秒 -> DateTime<Utc>）
@@ -552,20 +496,35 @@ fn parse_timestamp(timestamp_usec: &Option<String>) -> chrono::DateTime<Utc> {
     }
 }
 
+/// 通貨記号と通貨コードの対応
+const CURRENCY_SYMBOLS: &[(char, &str)] = &[
+    ('¥', "JPY"),
+    ('￥', "JPY"),
+    ('円', "JPY"),
+    ('$', "USD"),
+    ('€', "EUR"),
+    ('£', "GBP"),
+];
+
+/// 通貨記号から通貨コードを取得
+fn currency_from_symbol(symbol: char) -> Option<&'static str> {
+    CURRENCY_SYMBOLS
+        .iter()
+        .find(|(s, _)| *s == symbol)
+        .map(|(_, code)| *code)
+}
+
 /// 金額テキストをパース（例: "¥1,000" -> ("1,000", "JPY")）
+///
+/// 通貨記号は先頭を優先して判定し、なければ末尾（"1,000円"、"1.000 €"など）で判定する
 fn parse_amount(text: &str) -> (String, String) {
-    // 通貨記号を判定
-    let currency = if text.starts_with('¥') || text.starts_with("￥") {
-        "JPY"
-    } else if text.starts_with('$') {
-        "USD"
-    } else if text.starts_with('€') {
-        "EUR"
-    } else if text.starts_with('£') {
-        "GBP"
-    } else {
-        "USD"
-    };
+    let trimmed = text.trim();
+    let currency = trimmed
+        .chars()
+        .next()
+        .and_then(currency_from_symbol)
+        .or_else(|| trimmed.chars().next_back().and_then(currency_from_symbol))
+        .unwrap_or("USD");
 
     // 数字とカンマ、ピリオドのみ抽出
     let amount: String = text
@@ -580,16 +539,6 @@ fn parse_amount(text: &str) -> (String, String) {
 mod tests {
     use super::*;
     use crate::youtube::innertube::types::*;
-    use std::sync::Mutex as StdMutex;
-
-    /// グローバルキャッシュを変更するテストを直列化するためのミューテックス
-    /// 並列テスト実行時のフレーク防止
-    static CACHE_TEST_MUTEX: StdMutex<()> = StdMutex::new(());
-
-    /// ミューテックスをロック（poisoned状態からも回復）
-    fn lock_cache_test_mutex() -> std::sync::MutexGuard<'static, ()> {
-        CACHE_TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner())
-    }
 
     #[test]
     fn test_parse_amount() {
@@ -621,6 +570,25 @@ mod tests {
         assert_eq!(currency, "USD");
     }
 
+    #[test]
+    fn test_parse_amount_suffix_currency() {
+        let (amount, currency) = parse_amount("1,000円");
+        assert_eq!(amount, "1,000");
+        assert_eq!(currency, "JPY");
+
+        let (amount, currency) = parse_amount("1.000 €");
+        assert_eq!(amount, "1.000");
+        assert_eq!(currency, "EUR");
+
+        // 先頭の記号を優先
+        let (_, currency) = parse_amount("$5.00 €");
+        assert_eq!(currency, "USD");
+
+        let (amount, currency) = parse_amount("￥500");
+        assert_eq!(amount, "500");
+        assert_eq!(currency, "JPY");
+    }
+
     #[test]
     fn test_extract_plain_text() {
         let runs = Some(vec![
@@ -661,7 +629,7 @@ mod tests {
         let response = InnerTubeChatResponse {
             continuation_contents: None,
         };
-        let messages = parse_chat_response(response);
+        let messages = parse_chat_response(response, &EmojiCache::new());
         assert!(messages.is_empty());
     }
 
@@ -673,7 +641,7 @@ mod tests {
                 live_chat_continuation: None,
             }),
         };
-        let messages = parse_chat_response(response);
+        let messages = parse_chat_response(response, &EmojiCache::new());
         assert!(messages.is_empty());
     }
 
@@ -688,7 +656,7 @@ mod tests {
                 }),
             }),
         };
-        let messages = parse_chat_response(response);
+        let messages = parse_chat_response(response, &EmojiCache::new());
         assert!(messages.is_empty());
     }
 
@@ -703,7 +671,7 @@ mod tests {
                 }),
             }),
         };
-        let messages = parse_chat_response(response);
+        let messages = parse_chat_response(response, &EmojiCache::new());
         assert!(messages.is_empty());
     }
 
@@ -746,7 +714,7 @@ mod tests {
             }),
         };
 
-        let messages = parse_chat_response(response);
+        let messages = parse_chat_response(response, &EmojiCache::new());
         assert_eq!(messages.len(), 1);
         assert_eq!(messages[0].id, "test-id");
         assert_eq!(messages[0].message, "Hello World");
@@ -759,7 +727,7 @@ mod tests {
 
     #[test]
     fn test_parse_author_badges_none() {
-        let (is_owner, is_moderator, is_member) = parse_author_badges(&None);
+        let AuthorBadges { is_owner, is_moderator, is_member, .. } = parse_author_badges(&None);
         assert!(!is_owner);
         assert!(!is_moderator);
         assert!(!is_member);
@@ -768,7 +736,7 @@ mod tests {
     #[test]
     fn test_parse_author_badges_empty() {
         let badges: Vec<AuthorBadge> = vec![];
-        let (is_owner, is_moderator, is_member) = parse_author_badges(&Some(badges));
+        let AuthorBadges { is_owner, is_moderator, is_member, .. } = parse_author_badges(&Some(badges));
         assert!(!is_owner);
         assert!(!is_moderator);
         assert!(!is_member);
@@ -785,7 +753,7 @@ mod tests {
                 tooltip: None,
             }),
         }];
-        let (is_owner, is_moderator, is_member) = parse_author_badges(&Some(badges));
+        let AuthorBadges { is_owner, is_moderator, is_member, .. } = parse_author_badges(&Some(badges));
         assert!(is_owner);
         assert!(!is_moderator);
         assert!(!is_member);
@@ -802,7 +770,7 @@ mod tests {
                 tooltip: None,
             }),
         }];
-        let (is_owner, is_moderator, is_member) = parse_author_badges(&Some(badges));
+        let AuthorBadges { is_owner, is_moderator, is_member, .. } = parse_author_badges(&Some(badges));
         assert!(!is_owner);
         assert!(is_moderator);
         assert!(!is_member);
@@ -823,7 +791,7 @@ mod tests {
                 tooltip: Some("メンバー（1か月）".to_string()),
             }),
         }];
-        let (is_owner, is_moderator, is_member) = parse_author_badges(&Some(badges));
+        let AuthorBadges { is_owner, is_moderator, is_member, .. } = parse_author_badges(&Some(badges));
         assert!(!is_owner);
         assert!(!is_moderator);
         assert!(is_member);
@@ -865,15 +833,17 @@ mod tests {
                 }),
             },
         ];
-        let (is_owner, is_moderator, is_member) = parse_author_badges(&Some(badges));
-        assert!(is_owner);
-        assert!(is_moderator);
-        assert!(is_member);
+        let author = parse_author_badges(&Some(badges));
+        assert!(author.is_owner);
+        assert!(author.is_moderator);
+        assert!(author.is_member);
+        assert!(!author.is_verified);
+        assert_eq!(author.badges, vec!["owner", "moderator", "member"]);
     }
 
     #[test]
     fn test_parse_author_badges_verified_not_owner() {
-        // VERIFIEDはis_ownerにならない
+        // VERIFIEDはis_verifiedになり、is_ownerにはならない
         let badges = vec![AuthorBadge {
             live_chat_author_badge_renderer: Some(BadgeRenderer {
                 custom_thumbnail: None,
@@ -883,10 +853,37 @@ mod tests {
                 tooltip: None,
             }),
         }];
-        let (is_owner, is_moderator, is_member) = parse_author_badges(&Some(badges));
-        assert!(!is_owner);
-        assert!(!is_moderator);
-        assert!(!is_member);
+        let author = parse_author_badges(&Some(badges));
+        assert!(!author.is_owner);
+        assert!(!author.is_moderator);
+        assert!(!author.is_member);
+        assert!(author.is_verified);
+        assert_eq!(author.badges, vec!["verified"]);
+    }
+
+    #[test]
+    fn test_parse_text_message_verified_badge() {
+        let msg = parse_text_message(
+            serde_json::from_value(serde_json::json!({
+                "id": "v1",
+                "message": { "runs": [{ "text": "こんにちは" }] },
+                "authorBadges": [
+                    { "liveChatAuthorBadgeRenderer": { "icon": { "iconType": "VERIFIED" }, "tooltip": "確認済み" } },
+                    { "liveChatAuthorBadgeRenderer": { "icon": { "iconType": "MODERATOR" }, "tooltip": "モデレーター" } }
+                ]
+            }))
+            .unwrap(),
+            &EmojiCache::new(),
+        );
+        assert!(msg.is_verified);
+        assert!(msg.is_moderator);
+        assert!(!msg.is_owner);
+        assert_eq!(msg.badges, vec!["verified", "moderator"]);
+
+        // 配信ペイロードにもバッジ種別が含まれる
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["isVerified"], true);
+        assert_eq!(json["badges"], serde_json::json!(["verified", "moderator"]));
     }
 
     #[test]
@@ -901,10 +898,13 @@ mod tests {
                 tooltip: None,
             }),
         }];
-        let (is_owner, is_moderator, is_member) = parse_author_badges(&Some(badges));
-        assert!(!is_owner);
-        assert!(!is_moderator);
-        assert!(!is_member);
+        let author = parse_author_badges(&Some(badges));
+        assert!(!author.is_owner);
+        assert!(!author.is_moderator);
+        assert!(!author.is_member);
+        assert!(!author.is_verified);
+        // フラグは立てず、バッジ種別としてそのまま渡す
+        assert_eq!(author.badges, vec!["unknown_new_type"]);
     }
 
     // ========================================
@@ -932,20 +932,83 @@ mod tests {
         assert!(ts.timestamp() > 0);
     }
 
+    // ========================================
+    // parse_sticker_message テスト
+    // ========================================
+
+    #[test]
+    fn test_parse_sticker_message_with_amount() {
+        let msg = LiveChatPaidStickerRenderer {
+            id: "sticker-1".to_string(),
+            author_name: Some(SimpleText {
+                simple_text: Some("Sticker User".to_string()),
+                runs: None,
+            }),
+            author_photo: None,
+            author_external_channel_id: Some("UC_sticker".to_string()),
+            timestamp_usec: Some("1703145600000000".to_string()),
+            author_badges: None,
+            purchase_amount_text: Some(SimpleText {
+                simple_text: Some("¥500".to_string()),
+                runs: None,
+            }),
+            sticker: Some(ThumbnailContainer {
+                thumbnails: vec![Thumbnail {
+                    url: "//lh3.googleusercontent.com/sticker=s40".to_string(),
+                    width: Some(40),
+                    height: Some(40),
+                }],
+            }),
+            sticker_display_width: Some(40),
+            sticker_display_height: Some(40),
+        };
+
+        let message = parse_sticker_message(msg, LabelLocale::Ja);
+        match message.message_type {
+            MessageType::SuperSticker {
+                sticker_id,
+                amount,
+                currency,
+                amount_micros,
+                image_url,
+            } => {
+                assert_eq!(sticker_id, "//lh3.googleusercontent.com/sticker=s40");
+                assert_eq!(amount, "500");
+                assert_eq!(currency, "JPY");
+                assert_eq!(amount_micros, None);
+                // プロトコル相対URLはhttpsに補完
+                assert_eq!(
+                    image_url.as_deref(),
+                    Some("https://lh3.googleusercontent.com/sticker=s40")
+                );
+            }
+            other => panic!("Expected SuperSticker, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_normalize_sticker_url() {
+        assert_eq!(normalize_sticker_url(""), None);
+        assert_eq!(
+            normalize_sticker_url("https://example.com/a.png").as_deref(),
+            Some("https://example.com/a.png")
+        );
+    }
+
     // ========================================
     // parse_runs テスト
     // ========================================
 
     #[test]
     fn test_parse_runs_none() {
-        let result = parse_runs(&None);
+        let result = parse_runs(&None, &EmojiCache::new());
         assert!(result.is_none());
     }
 
     #[test]
     fn test_parse_runs_empty() {
         let runs: Vec<RunItem> = vec![];
-        let result = parse_runs(&Some(runs));
+        let result = parse_runs(&Some(runs), &EmojiCache::new());
         assert!(result.is_none());
     }
 
@@ -973,7 +1036,7 @@ mod tests {
                 }),
             },
         ];
-        let result = parse_runs(&Some(runs));
+        let result = parse_runs(&Some(runs), &EmojiCache::new());
         assert!(result.is_some());
         let parsed = result.unwrap();
         assert_eq!(parsed.len(), 2);
@@ -1005,7 +1068,7 @@ mod tests {
                 is_custom_emoji: Some(false),
             }),
         }];
-        let result = parse_runs(&Some(runs));
+        let result = parse_runs(&Some(runs), &EmojiCache::new());
         assert!(result.is_none()); // 空なのでNone
     }
 
@@ -1083,7 +1146,7 @@ mod tests {
             }),
         };
 
-        let messages = parse_action(replay_action);
+        let messages = parse_action(replay_action, &EmojiCache::new());
 
         // 2つのメッセージが返されるべき
         assert_eq!(messages.len(), 2);
@@ -1101,7 +1164,7 @@ mod tests {
             replay_chat_item_action: Some(ReplayChatItemAction { actions: None }),
         };
 
-        let messages = parse_action(replay_action);
+        let messages = parse_action(replay_action, &EmojiCache::new());
         assert!(messages.is_empty());
     }
 
@@ -1137,655 +1200,56 @@ mod tests {
             replay_chat_item_action: None,
         };
 
-        let messages = parse_action(action);
+        let messages = parse_action(action, &EmojiCache::new());
         assert_eq!(messages.len(), 1);
         assert_eq!(messages[0].id, "single-msg");
     }
 
-    // ========================================
-    // LRUキャッシュテスト（直列化）
-    // ========================================
-
-    #[test]
-    fn test_emoji_cache_size_limit() {
-        // グローバルキャッシュを変更するテストは直列化
-        let _lock = lock_cache_test_mutex();
-
-        // キャッシュをクリア
-        clear_emoji_cache();
-
-        // 最大サイズ + 10 個のエントリを追加
-        for i in 0..(EMOJI_CACHE_MAX_SIZE + 10) {
-            let shortcut = format!(":_test{}:", i);
-            let emoji_info = EmojiInfo {
-                emoji_id: format!("emoji_{}", i),
-                shortcuts: vec![shortcut.clone()],
-                image: EmojiImage { thumbnails: vec![] },
-                is_custom_emoji: true,
-            };
-
-            if let Ok(mut cache) = EMOJI_CACHE.lock() {
-                cache.put(shortcut, emoji_info);
-            }
-        }
-
-        // キャッシュサイズが最大値を超えないことを確認
-        let size = get_emoji_cache_size();
-        assert!(size <= EMOJI_CACHE_MAX_SIZE,
-            "Cache size {} should not exceed max size {}", size, EMOJI_CACHE_MAX_SIZE);
-
-        // 最新のエントリが存在することを確認
-        let latest_shortcut = format!(":_test{}:", EMOJI_CACHE_MAX_SIZE + 9);
-        if let Ok(mut cache) = EMOJI_CACHE.lock() {
-            assert!(cache.get(&latest_shortcut).is_some(),
-                "Latest entry should be in cache");
-        }
-
-        // 最も古いエントリがLRUで削除されていることを確認
-        let oldest_shortcut = ":_test0:".to_string();
-        if let Ok(mut cache) = EMOJI_CACHE.lock() {
-            assert!(cache.get(&oldest_shortcut).is_none(),
-                "Oldest entry should have been evicted");
-        }
-
-        // クリーンアップ
-        clear_emoji_cache();
-    }
-
-    #[test]
-    fn test_emoji_cache_lru_update() {
-        // グローバルキャッシュを変更するテストは直列化
-        let _lock = lock_cache_test_mutex();
-
-        // キャッシュをクリア
-        clear_emoji_cache();
-
-        // 3つのエントリを追加
-        for i in 0..3 {
-            let shortcut = format!(":_lru{}:", i);
-            let emoji_info = EmojiInfo {
-                emoji_id: format!("lru_{}", i),
-                shortcuts: vec![shortcut.clone()],
-                image: EmojiImage { thumbnails: vec![] },
-                is_custom_emoji: true,
-            };
-
-            if let Ok(mut cache) = EMOJI_CACHE.lock() {
-                cache.put(shortcut, emoji_info);
-            }
-        }
-
-        // 最初のエントリにアクセス（LRUが更新される）
-        if let Ok(mut cache) = EMOJI_CACHE.lock() {
-            let _ = cache.get(":_lru0:");
-        }
-
-        // すべてのエントリが存在することを確認
-        if let Ok(mut cache) = EMOJI_CACHE.lock() {
-            assert!(cache.get(":_lru0:").is_some());
-            assert!(cache.get(":_lru1:").is_some());
-            assert!(cache.get(":_lru2:").is_some());
-        }
-
-        // クリーンアップ
-        clear_emoji_cache();
-    }
-
-    #[test]
-    fn test_convert_text_with_emoji_cache_mixed() {
-        // グローバルキャッシュを変更するテストは直列化
-        let _lock = lock_cache_test_mutex();
-
-        // キャッシュをクリア
-        clear_emoji_cache();
-
-        // テスト用の絵文字をキャッシュに登録
-        let test_emoji = EmojiInfo {
-            emoji_id: "test_smile".to_string(),
-            shortcuts: vec![":_smile:".to_string()],
-            image: EmojiImage {
-                thumbnails: vec![EmojiThumbnail {
-                    url: "https://example.com/smile.png".to_string(),
-                    width: 24,
-                    height: 24,
-                }],
-            },
-            is_custom_emoji: true,
-        };
-        if let Ok(mut cache) = EMOJI_CACHE.lock() {
-            cache.put(":_smile:".to_string(), test_emoji);
-        }
-
-        // テキスト + 絵文字 + テキストの混合入力
-        let result = convert_text_with_emoji_cache("Hello :_smile: World");
-
-        // 結果の検証: [Text("Hello "), Emoji, Text(" World")]
-        assert_eq!(result.len(), 3, "Should have 3 runs");
-
-        // 1つ目: テキスト "Hello "
-        match &result[0] {
-            MessageRun::Text { text } => assert_eq!(text, "Hello "),
-            _ => panic!("First run should be Text"),
-        }
-
-        // 2つ目: 絵文字
-        match &result[1] {
-            MessageRun::Emoji { emoji } => {
-                assert_eq!(emoji.emoji_id, "test_smile");
-            }
-            _ => panic!("Second run should be Emoji"),
-        }
-
-        // 3つ目: テキスト " World"
-        match &result[2] {
-            MessageRun::Text { text } => assert_eq!(text, " World"),
-            _ => panic!("Third run should be Text"),
-        }
-
-        // クリーンアップ
-        clear_emoji_cache();
-    }
-
-    #[test]
-    fn test_convert_text_with_emoji_cache_miss() {
-        // グローバルキャッシュを変更するテストは直列化
-        let _lock = lock_cache_test_mutex();
-
-        // キャッシュをクリア
-        clear_emoji_cache();
-
-        // ダミーの絵文字を1つ登録（キャッシュが空だと早期リターンするため）
-        let dummy_emoji = EmojiInfo {
-            emoji_id: "dummy".to_string(),
-            shortcuts: vec![":_dummy:".to_string()],
-            image: EmojiImage { thumbnails: vec![] },
-            is_custom_emoji: true,
-        };
-        if let Ok(mut cache) = EMOJI_CACHE.lock() {
-            cache.put(":_dummy:".to_string(), dummy_emoji);
-        }
-
-        // キャッシュにない絵文字ショートカットはテキストのまま
-        let result = convert_text_with_emoji_cache("Hello :_unknown: World");
-
-        // 結果の検証: [Text("Hello "), Text(":_unknown:"), Text(" World")]
-        assert_eq!(result.len(), 3, "Should have 3 runs");
-
-        match &result[0] {
-            MessageRun::Text { text } => assert_eq!(text, "Hello "),
-            _ => panic!("First run should be Text"),
-        }
-
-        match &result[1] {
-            MessageRun::Text { text } => assert_eq!(text, ":_unknown:"),
-            _ => panic!("Second run should be Text (cache miss)"),
-        }
-
-        match &result[2] {
-            MessageRun::Text { text } => assert_eq!(text, " World"),
-            _ => panic!("Third run should be Text"),
-        }
-
-        // クリーンアップ
-        clear_emoji_cache();
-    }
-
     #[test]
-    fn test_convert_text_with_empty_cache() {
-        // グローバルキャッシュを変更するテストは直列化
-        let _lock = lock_cache_test_mutex();
-
-        // キャッシュを空にする
-        clear_emoji_cache();
-
-        // キャッシュが空の場合、テキストはそのまま返される
-        let result = convert_text_with_emoji_cache("Hello :_emoji: World");
-
-        // 結果の検証: [Text("Hello :_emoji: World")]（キャッシュ空→早期リターン）
-        assert_eq!(result.len(), 1, "Should have 1 run when cache is empty");
-
-        match &result[0] {
-            MessageRun::Text { text } => assert_eq!(text, "Hello :_emoji: World"),
-            _ => panic!("Should be Text"),
-        }
+    fn test_parse_membership_milestone_header() {
+        let msg = parse_membership_message(
+            serde_json::from_value(serde_json::json!({
+                "id": "m2",
+                "headerPrimaryText": { "runs": [
+                    { "text": "メンバー歴 " }, { "text": "6" }, { "text": " か月" }
+                ] },
+                "headerSubText": { "runs": [{ "text": "ゴールド" }] },
+                "message": { "runs": [{ "text": "いつもありがとう" }] }
+            }))
+            .unwrap(),
+            LabelLocale::Ja,
+            &EmojiCache::new(),
+        );
 
-        // クリーンアップ
-        clear_emoji_cache();
+        // 継続期間をレベル名より優先する
+        assert!(matches!(&msg.message_type, MessageType::Membership { level } if level == "メンバー歴 6 か月"));
+        assert_eq!(msg.message, "いつもありがとう");
     }
 
     #[test]
-    fn test_convert_text_evicted_shortcut_stays_as_text() {
-        // グローバルキャッシュを変更するテストは直列化
-        let _lock = lock_cache_test_mutex();
-
-        // キャッシュをクリア
-        clear_emoji_cache();
-
-        // 最初のエントリを登録（後でevictされる）
-        let evicted_shortcut = ":_evicted:";
-        let evicted_emoji = EmojiInfo {
-            emoji_id: "evicted_emoji".to_string(),
-            shortcuts: vec![evicted_shortcut.to_string()],
-            image: EmojiImage {
-                thumbnails: vec![EmojiThumbnail {
-                    url: "https://example.com/evicted.png".to_string(),
-                    width: 24,
-                    height: 24,
-                }],
-            },
-            is_custom_emoji: true,
+    fn test_default_labels_follow_locale() {
+        let membership = |locale| {
+            parse_membership_message(
+                serde_json::from_value(serde_json::json!({ "id": "m1" })).unwrap(),
+                locale,
+                &EmojiCache::new(),
+            )
         };
-        if let Ok(mut cache) = EMOJI_CACHE.lock() {
-            cache.put(evicted_shortcut.to_string(), evicted_emoji);
-        }
-
-        // キャッシュを最大サイズまで埋めてevictを発生させる
-        for i in 0..EMOJI_CACHE_MAX_SIZE {
-            let shortcut = format!(":_fill{}:", i);
-            let emoji_info = EmojiInfo {
-                emoji_id: format!("fill_{}", i),
-                shortcuts: vec![shortcut.clone()],
-                image: EmojiImage { thumbnails: vec![] },
-                is_custom_emoji: true,
-            };
-
-            if let Ok(mut cache) = EMOJI_CACHE.lock() {
-                cache.put(shortcut, emoji_info);
-            }
-        }
-
-        // 最初のエントリがevictされていることを確認
-        if let Ok(mut cache) = EMOJI_CACHE.lock() {
-            assert!(
-                cache.get(evicted_shortcut).is_none(),
-                "Evicted shortcut should not be in cache"
-            );
-        }
-
-        // evictされたショートカットを含むテキストを変換
-        let result = convert_text_with_emoji_cache("Test :_evicted: emoji");
-
-        // 結果の検証: evictされたショートカットはテキストとして残る
-        assert_eq!(result.len(), 3, "Should have 3 runs");
-
-        match &result[0] {
-            MessageRun::Text { text } => assert_eq!(text, "Test "),
-            _ => panic!("First run should be Text"),
-        }
-
-        // evictされたショートカットはテキストのまま
-        match &result[1] {
-            MessageRun::Text { text } => assert_eq!(text, evicted_shortcut),
-            _ => panic!("Second run should be Text (evicted shortcut)"),
-        }
-
-        match &result[2] {
-            MessageRun::Text { text } => assert_eq!(text, " emoji"),
-            _ => panic!("Third run should be Text"),
-        }
-
-        // キャッシュに残っているエントリは絵文字に変換されることを確認
-        // 注: :_fill0:はまだキャッシュにあるはずだが、LRUなので
-        // 大量のエントリを追加した後は最初のfillエントリもevictされている可能性がある
-        // 最後に追加したエントリで確認
-        let last_shortcut = format!(":_fill{}:", EMOJI_CACHE_MAX_SIZE - 1);
-        let result_last = convert_text_with_emoji_cache(&format!("Test {} emoji", last_shortcut));
-
-        assert_eq!(result_last.len(), 3, "Should have 3 runs for surviving emoji");
-        match &result_last[1] {
-            MessageRun::Emoji { emoji } => {
-                assert_eq!(emoji.emoji_id, format!("fill_{}", EMOJI_CACHE_MAX_SIZE - 1));
-            }
-            _ => panic!("Second run should be Emoji for surviving shortcut"),
-        }
-
-        // クリーンアップ
-        clear_emoji_cache();
-    }
-
-    /// get()による真のLRU eviction動作のテスト
-    ///
-    /// 設計判断: convert_text_with_emoji_cacheはget()を使用するため、
-    /// 頻繁にアクセスされる絵文字はLRU順序が更新され、evictされにくくなる。
-    /// これにより、長時間配信でもホット絵文字が保持され、ユーザー体験が向上。
-    #[test]
-    fn test_hot_emoji_survives_with_lru() {
-        // グローバルキャッシュを変更するテストは直列化
-        let _lock = lock_cache_test_mutex();
-
-        // キャッシュをクリア
-        clear_emoji_cache();
-
-        // ホットエントリ（頻繁にアクセスされる）を最初に登録
-        let hot_shortcut = ":_hot_emoji:";
-        let hot_emoji = EmojiInfo {
-            emoji_id: "hot_emoji".to_string(),
-            shortcuts: vec![hot_shortcut.to_string()],
-            image: EmojiImage {
-                thumbnails: vec![EmojiThumbnail {
-                    url: "https://example.com/hot.png".to_string(),
-                    width: 24,
-                    height: 24,
-                }],
-            },
-            is_custom_emoji: true,
+        let gift = |locale| {
+            parse_gift_message(
+                serde_json::from_value(serde_json::json!({ "id": "g1" })).unwrap(),
+                locale,
+            )
         };
-        if let Ok(mut cache) = EMOJI_CACHE.lock() {
-            cache.put(hot_shortcut.to_string(), hot_emoji);
-        }
-
-        // キャッシュを半分埋めつつ、ホットエントリを定期的にアクセス
-        // get()によりLRU順序が更新されるため、ホットエントリは最近使用として扱われる
-        for i in 0..EMOJI_CACHE_MAX_SIZE {
-            let shortcut = format!(":_newemoji{}:", i);
-            let emoji_info = EmojiInfo {
-                emoji_id: format!("new_{}", i),
-                shortcuts: vec![shortcut.clone()],
-                image: EmojiImage { thumbnails: vec![] },
-                is_custom_emoji: true,
-            };
-
-            if let Ok(mut cache) = EMOJI_CACHE.lock() {
-                cache.put(shortcut, emoji_info);
-            }
-
-            // 10回に1回ホットエントリにアクセス（LRU更新）
-            if i % 10 == 0 {
-                let _ = convert_text_with_emoji_cache(&format!("Test {} text", hot_shortcut));
-            }
-        }
-
-        // ホットエントリがまだキャッシュに存在することを確認
-        // get()を使用しているため、頻繁なアクセスでLRU順序が更新されevictを防ぐ
-        if let Ok(cache) = EMOJI_CACHE.lock() {
-            assert!(
-                cache.peek(hot_shortcut).is_some(),
-                "Hot emoji should survive due to frequent access (get updates LRU)"
-            );
-        }
-
-        // クリーンアップ
-        clear_emoji_cache();
-    }
-
-    #[test]
-    fn test_convert_text_with_multibyte_characters() {
-        // グローバルキャッシュを変更するテストは直列化
-        let _lock = lock_cache_test_mutex();
-
-        // キャッシュをクリア
-        clear_emoji_cache();
-
-        // テスト用の絵文字をキャッシュに登録
-        let test_emoji = EmojiInfo {
-            emoji_id: "smile_emoji".to_string(),
-            shortcuts: vec![":_smile:".to_string()],
-            image: EmojiImage {
-                thumbnails: vec![EmojiThumbnail {
-                    url: "https://example.com/smile.png".to_string(),
-                    width: 24,
-                    height: 24,
-                }],
-            },
-            is_custom_emoji: true,
+        let level_of = |msg: ChatMessage| match msg.message_type {
+            MessageType::Membership { level } => level,
+            other => panic!("unexpected message type: {:?}", other),
         };
-        if let Ok(mut cache) = EMOJI_CACHE.lock() {
-            cache.put(":_smile:".to_string(), test_emoji);
-        }
-
-        // マルチバイト文字（日本語）+ 絵文字ショートカット + マルチバイト文字
-        let result = convert_text_with_emoji_cache("こんにちは:_smile:世界");
-
-        // 結果の検証: [Text("こんにちは"), Emoji, Text("世界")]
-        assert_eq!(result.len(), 3, "Should have 3 runs with multibyte text");
-
-        // 1つ目: 日本語テキスト "こんにちは"
-        match &result[0] {
-            MessageRun::Text { text } => assert_eq!(text, "こんにちは"),
-            _ => panic!("First run should be Text"),
-        }
-
-        // 2つ目: 絵文字
-        match &result[1] {
-            MessageRun::Emoji { emoji } => {
-                assert_eq!(emoji.emoji_id, "smile_emoji");
-            }
-            _ => panic!("Second run should be Emoji"),
-        }
-
-        // 3つ目: 日本語テキスト "世界"
-        match &result[2] {
-            MessageRun::Text { text } => assert_eq!(text, "世界"),
-            _ => panic!("Third run should be Text"),
-        }
-
-        // 絵文字が連続する場合（マルチバイト文字の間）
-        let result2 = convert_text_with_emoji_cache("日本語:_smile::_smile:テスト");
-
-        // :_smile: が2つ連続するが、キャッシュには1つしか登録されていないので両方変換される
-        assert_eq!(result2.len(), 4, "Should have 4 runs with consecutive emojis");
-
-        match &result2[0] {
-            MessageRun::Text { text } => assert_eq!(text, "日本語"),
-            _ => panic!("First run should be Text '日本語'"),
-        }
-
-        match &result2[1] {
-            MessageRun::Emoji { emoji } => assert_eq!(emoji.emoji_id, "smile_emoji"),
-            _ => panic!("Second run should be Emoji"),
-        }
-
-        match &result2[2] {
-            MessageRun::Emoji { emoji } => assert_eq!(emoji.emoji_id, "smile_emoji"),
-            _ => panic!("Third run should be Emoji"),
-        }
-
-        match &result2[3] {
-            MessageRun::Text { text } => assert_eq!(text, "テスト"),
-            _ => panic!("Fourth run should be Text 'テスト'"),
-        }
-
-        // クリーンアップ
-        clear_emoji_cache();
-    }
-
-    // =========================================================================
-    // ストレステスト/ベンチマーク
-    // =========================================================================
-
-    /// 並行アクセスによるロック競合テスト
-    ///
-    /// 複数スレッドから同時にconvert_text_with_emoji_cacheを呼び出し、
-    /// ロック競合によるパフォーマンス劣化を検出する。
-    #[test]
-    fn test_concurrent_emoji_cache_access() {
-        use std::sync::Arc;
-        use std::thread;
-        use std::time::{Duration, Instant};
-
-        // グローバルキャッシュを変更するテストは直列化
-        let _lock = lock_cache_test_mutex();
-
-        // キャッシュをクリアして初期化
-        clear_emoji_cache();
-
-        // テスト用の絵文字を複数登録
-        for i in 0..10 {
-            let shortcut = format!(":_test{}:", i);
-            let emoji_info = EmojiInfo {
-                emoji_id: format!("test_{}", i),
-                shortcuts: vec![shortcut.clone()],
-                image: EmojiImage {
-                    thumbnails: vec![EmojiThumbnail {
-                        url: format!("https://example.com/test{}.png", i),
-                        width: 24,
-                        height: 24,
-                    }],
-                },
-                is_custom_emoji: true,
-            };
-            if let Ok(mut cache) = EMOJI_CACHE.lock() {
-                cache.put(shortcut, emoji_info);
-            }
-        }
-
-        // 並行スレッド数と各スレッドの呼び出し回数
-        let num_threads = 8;
-        let iterations_per_thread = 100;
-
-        // スレッドの待機用
-        let barrier = Arc::new(std::sync::Barrier::new(num_threads));
-
-        let handles: Vec<_> = (0..num_threads)
-            .map(|thread_id| {
-                let barrier = Arc::clone(&barrier);
-                thread::spawn(move || {
-                    // 全スレッドが揃うまで待機（同時実行を保証）
-                    barrier.wait();
-
-                    let start = Instant::now();
-                    let mut success_count = 0;
-
-                    for i in 0..iterations_per_thread {
-                        // 様々なパターンのテキストを変換
-                        let text = format!(
-                            "Thread {} iter {}: こんにちは:_test{}:世界:_test{}:テスト",
-                            thread_id,
-                            i,
-                            i % 10,
-                            (i + 1) % 10
-                        );
-                        let result = convert_text_with_emoji_cache(&text);
-
-                        // 結果が期待通りか確認（5パーツ: Text, Emoji, Text, Emoji, Text）
-                        if result.len() == 5 {
-                            success_count += 1;
-                        }
-                    }
-
-                    let elapsed = start.elapsed();
-                    (thread_id, success_count, elapsed)
-                })
-            })
-            .collect();
-
-        // 結果を収集
-        let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
-
-        // すべてのスレッドが正常に完了したか確認
-        for (thread_id, success_count, elapsed) in &results {
-            assert_eq!(
-                *success_count, iterations_per_thread,
-                "Thread {} failed: only {} of {} succeeded",
-                thread_id, success_count, iterations_per_thread
-            );
-
-            // 各スレッドの処理時間が妥当か確認（1秒以内）
-            assert!(
-                *elapsed < Duration::from_secs(1),
-                "Thread {} took too long: {:?}",
-                thread_id,
-                elapsed
-            );
-        }
-
-        // 全体の統計
-        let total_elapsed: Duration = results.iter().map(|(_, _, e)| *e).sum();
-        let avg_elapsed = total_elapsed / num_threads as u32;
-        let total_calls = num_threads * iterations_per_thread;
 
-        // 平均処理時間をログ出力（ベンチマーク情報）
-        println!(
-            "Concurrent stress test: {} threads × {} iterations = {} total calls",
-            num_threads, iterations_per_thread, total_calls
-        );
-        println!("Average elapsed per thread: {:?}", avg_elapsed);
-        println!(
-            "Throughput: {:.0} calls/sec",
-            total_calls as f64 / avg_elapsed.as_secs_f64()
-        );
+        assert_eq!(level_of(membership(LabelLocale::Ja)), "新規メンバー");
+        assert_eq!(gift(LabelLocale::Ja).message, "1件のメンバーシップをギフトしました");
 
-        // クリーンアップ
-        clear_emoji_cache();
-    }
-
-    /// 多数の絵文字マッチ時のレイテンシテスト
-    ///
-    /// 1つのテキストに多数の絵文字ショートカットが含まれる場合の
-    /// 処理時間を測定し、レイテンシが許容範囲内か確認する。
-    #[test]
-    fn test_many_emoji_matches_latency() {
-        use std::time::Instant;
-
-        // グローバルキャッシュを変更するテストは直列化
-        let _lock = lock_cache_test_mutex();
-
-        // キャッシュをクリアして初期化
-        clear_emoji_cache();
-
-        // テスト用の絵文字を50個登録
-        let emoji_count = 50;
-        for i in 0..emoji_count {
-            let shortcut = format!(":_emoji{}:", i);
-            let emoji_info = EmojiInfo {
-                emoji_id: format!("emoji_{}", i),
-                shortcuts: vec![shortcut.clone()],
-                image: EmojiImage {
-                    thumbnails: vec![EmojiThumbnail {
-                        url: format!("https://example.com/emoji{}.png", i),
-                        width: 24,
-                        height: 24,
-                    }],
-                },
-                is_custom_emoji: true,
-            };
-            if let Ok(mut cache) = EMOJI_CACHE.lock() {
-                cache.put(shortcut, emoji_info);
-            }
-        }
-
-        // 多数の絵文字を含むテキストを生成
-        let mut text_parts: Vec<String> = Vec::new();
-        for i in 0..emoji_count {
-            text_parts.push(format!("text{}:_emoji{}:", i, i));
-        }
-        let long_text = text_parts.join("");
-
-        // 処理時間を測定（複数回実行して平均を取る）
-        let iterations = 100;
-        let start = Instant::now();
-
-        for _ in 0..iterations {
-            let result = convert_text_with_emoji_cache(&long_text);
-            // 結果が期待通りか確認（各絵文字の前にテキストがあるので、emoji_count * 2パーツ）
-            assert_eq!(
-                result.len(),
-                emoji_count * 2,
-                "Expected {} runs, got {}",
-                emoji_count * 2,
-                result.len()
-            );
-        }
-
-        let elapsed = start.elapsed();
-        let avg_latency = elapsed / iterations as u32;
-
-        println!(
-            "Many emoji matches test: {} emojis × {} iterations",
-            emoji_count, iterations
-        );
-        println!("Total elapsed: {:?}", elapsed);
-        println!("Average latency per call: {:?}", avg_latency);
-
-        // レイテンシが許容範囲内か確認（1回あたり10ms以内）
-        assert!(
-            avg_latency < std::time::Duration::from_millis(10),
-            "Average latency ({:?}) exceeds threshold (10ms)",
-            avg_latency
-        );
-
-        // クリーンアップ
-        clear_emoji_cache();
+        assert_eq!(level_of(membership(LabelLocale::En)), "New Member");
+        assert_eq!(gift(LabelLocale::En).message, "Gifted 1 membership");
     }
 }
-
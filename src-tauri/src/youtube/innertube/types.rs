@@ -20,6 +20,32 @@ pub enum ContinuationType {
     Reload,
 }
 
+/// ライブチャットページでcontinuationトークンを抽出したパターン（診断用）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContinuationSource {
+    /// invalidationContinuationData（優先度1）
+    Invalidation,
+    /// timedContinuationData（優先度2）
+    Timed,
+    /// reloadContinuationData（優先度3）
+    Reload,
+    /// 汎用の"continuation"（優先度4、ライブチャット以外のトークンの可能性あり）
+    Generic,
+}
+
+/// 使用しているクライアントバージョンの取得元（診断用）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientVersionSource {
+    /// 設定で上書きした値
+    Settings,
+    /// ライブチャットページから抽出した値
+    Page,
+    /// 組み込みのフォールバック値
+    Fallback,
+}
+
 /// ポーリング間隔の最大値（30秒）
 ///
 /// 根拠:
@@ -164,6 +190,9 @@ pub struct LiveChatMembershipItemRenderer {
     pub author_external_channel_id: Option<String>,
     pub timestamp_usec: Option<String>,
     pub author_badges: Option<Vec<AuthorBadge>>,
+    /// メンバー継続（マイルストーン）時のみ（例: "メンバー歴 6 か月"）
+    pub header_primary_text: Option<MessageContent>,
+    /// 新規メンバー時は歓迎メッセージ、マイルストーン時はメンバーシップレベル名
     pub header_sub_text: Option<MessageContent>,
 }
 
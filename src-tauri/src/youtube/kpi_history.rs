@@ -0,0 +1,351 @@
+//! KPI（視聴者数等）の履歴と自動取得
+//!
+//! 統計情報を取得するたびにkpi_historyテーブルへ記録し、視聴者数グラフ用の系列を提供する。
+//! 系列は動画ID（1配信）ごとにまとまる。
+//! 配信中は[`KpiAutoSampler`]が一定間隔で統計情報を取得し、記録とオーバーレイへの配信を行う。
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use super::api_key_manager::get_api_key_manager;
+use super::client::YouTubeClient;
+use super::errors::YouTubeError;
+use super::types::LiveStreamStats;
+use crate::server::types::{KpiUpdatePayload, ServerState, WsMessage};
+
+/// KPI履歴の1サンプル
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct KpiSample {
+    /// 記録時刻（UTC、RFC3339）
+    pub recorded_at: String,
+    /// 同時視聴者数
+    pub concurrent_viewers: Option<i64>,
+    /// 高評価数
+    pub like_count: Option<i64>,
+    /// 総再生回数
+    pub view_count: Option<i64>,
+}
+
+/// 統計情報を1件記録
+pub async fn record_sample(
+    pool: &SqlitePool,
+    video_id: &str,
+    stats: &LiveStreamStats,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        INSERT INTO kpi_history (video_id, recorded_at, concurrent_viewers, like_count, view_count)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(video_id)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .bind(stats.concurrent_viewers)
+    .bind(stats.like_count)
+    .bind(stats.view_count)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+    Ok(())
+}
+
+/// KPI履歴を記録順に取得
+///
+/// `video_id`を省略した場合は最後に記録した動画（現在の配信）の系列を返す。記録がなければ空。
+pub async fn load_history(
+    pool: &SqlitePool,
+    video_id: Option<&str>,
+) -> Result<Vec<KpiSample>, String> {
+    let video_id = match video_id {
+        Some(video_id) => video_id.to_string(),
+        None => {
+            let latest: Option<(String,)> =
+                sqlx::query_as("SELECT video_id FROM kpi_history ORDER BY id DESC LIMIT 1")
+                    .fetch_optional(pool)
+                    .await
+                    .map_err(|e| format!("DB error: {}", e))?;
+            match latest {
+                Some((video_id,)) => video_id,
+                None => return Ok(Vec::new()),
+            }
+        }
+    };
+
+    sqlx::query_as::<_, KpiSample>(
+        r#"
+        SELECT recorded_at, concurrent_viewers, like_count, view_count
+        FROM kpi_history
+        WHERE video_id = ?
+        ORDER BY id
+        "#,
+    )
+    .bind(&video_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))
+}
+
+/// 統計情報をオーバーレイ用のKPIペイロードに変換（視聴者数・高評価数）
+pub fn kpi_payload(stats: &LiveStreamStats) -> KpiUpdatePayload {
+    KpiUpdatePayload {
+        main: stats.concurrent_viewers,
+        label: Some("視聴者".to_string()),
+        sub: stats.like_count,
+        sub_label: stats.like_count.map(|_| "高評価".to_string()),
+    }
+}
+
+/// 統計情報をWebSocketでブロードキャスト
+///
+/// ## 設計ノート
+/// - Fire-and-forgetパターン: ブロードキャストは`tokio::spawn`でバックグラウンド実行
+/// - RwLockガードをawait境界をまたいで保持しないようにtokio::spawnで分離
+pub fn broadcast_stats(server: &ServerState, stats: &LiveStreamStats) {
+    let server = Arc::clone(server);
+    let message = WsMessage::KpiUpdate {
+        payload: kpi_payload(stats),
+    };
+    tokio::spawn(async move {
+        let peers_arc = {
+            let ws_state = server.read().await;
+            // バンドル送信が有効ならコメント等とまとめて送信
+            if ws_state.bundle_if_enabled(&message) {
+                return;
+            }
+            ws_state.get_peers_arc()
+        };
+        let peers_guard = peers_arc.read().await;
+        let peers: Vec<_> = peers_guard
+            .iter()
+            .map(|(id, tx)| (*id, tx.clone()))
+            .collect();
+        drop(peers_guard);
+        crate::server::websocket::WebSocketState::send_to_peers(&peers, &message);
+        log::trace!("Viewer count broadcasted");
+    });
+}
+
+/// 自動取得を続けるか（配信終了を検出したら止める）
+///
+/// 一度でも同時視聴者数を取得した後に取得できなくなった場合は配信終了とみなす
+/// （配信開始前は同時視聴者数がないため、取得できるまで待つ）
+fn stream_ended(seen_live: bool, stats: &LiveStreamStats) -> bool {
+    seen_live && stats.concurrent_viewers.is_none()
+}
+
+/// KPI自動取得タスク
+///
+/// 配信中に一定間隔で統計情報を取得し、KPI履歴への記録とオーバーレイへの配信を行う。
+/// 配信終了（同時視聴者数がなくなる・動画が見つからない）を検出すると自動で停止する。
+pub struct KpiAutoSampler {
+    /// 実行中フラグ
+    is_running: Arc<AtomicBool>,
+    /// 停止通知
+    stop_signal: Arc<Notify>,
+}
+
+impl KpiAutoSampler {
+    /// 自動取得タスクを開始する
+    pub fn start(
+        pool: SqlitePool,
+        server: ServerState,
+        video_id: String,
+        use_bundled_key: bool,
+        interval: Duration,
+    ) -> Self {
+        let is_running = Arc::new(AtomicBool::new(true));
+        let stop_signal = Arc::new(Notify::new());
+
+        let is_running_clone = Arc::clone(&is_running);
+        let stop_signal_clone = Arc::clone(&stop_signal);
+        log::info!(
+            "KPI auto-sampler started: video_id={}, interval={}s",
+            video_id,
+            interval.as_secs()
+        );
+        tauri::async_runtime::spawn(async move {
+            Self::sample_loop(
+                pool,
+                server,
+                video_id,
+                use_bundled_key,
+                interval,
+                is_running_clone,
+                stop_signal_clone,
+            )
+            .await;
+        });
+
+        Self {
+            is_running,
+            stop_signal,
+        }
+    }
+
+    /// 自動取得ループ（初回は即座に取得）
+    async fn sample_loop(
+        pool: SqlitePool,
+        server: ServerState,
+        video_id: String,
+        use_bundled_key: bool,
+        interval: Duration,
+        is_running: Arc<AtomicBool>,
+        stop_signal: Arc<Notify>,
+    ) {
+        let mut seen_live = false;
+
+        while is_running.load(Ordering::SeqCst) {
+            let api_key = get_api_key_manager()
+                .read()
+                .ok()
+                .and_then(|manager| manager.get_active_key(use_bundled_key).map(str::to_string));
+
+            match api_key {
+                Some(api_key) => match YouTubeClient::new(api_key)
+                    .get_live_stream_stats(&video_id)
+                    .await
+                {
+                    Ok(stats) => {
+                        if stream_ended(seen_live, &stats) {
+                            log::info!("Stream ended, stopping KPI auto-sampler: {}", video_id);
+                            break;
+                        }
+                        seen_live |= stats.concurrent_viewers.is_some();
+
+                        if let Err(e) = record_sample(&pool, &video_id, &stats).await {
+                            log::warn!("Failed to record KPI sample: {}", e);
+                        }
+                        broadcast_stats(&server, &stats);
+                    }
+                    Err(YouTubeError::VideoNotFound) => {
+                        log::warn!("Video not found, stopping KPI auto-sampler: {}", video_id);
+                        break;
+                    }
+                    Err(e) => log::warn!("KPI auto-sample failed: {}", e),
+                },
+                None => log::warn!("KPI auto-sample skipped: API key is not set"),
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = stop_signal.notified() => {}
+            }
+        }
+
+        is_running.store(false, Ordering::SeqCst);
+        log::info!("KPI auto-sampler stopped");
+    }
+
+    /// 自動取得を停止する
+    pub fn stop(&self) {
+        self.is_running.store(false, Ordering::SeqCst);
+        // 待機中のループをすぐに終了させる
+        self.stop_signal.notify_one();
+    }
+
+    /// 実行中かどうか（配信終了で自動停止した場合はfalse）
+    pub fn is_running(&self) -> bool {
+        self.is_running.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for KpiAutoSampler {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// 現在の自動取得タスク（同時に1つのみ）
+static KPI_SAMPLER: Lazy<Mutex<Option<KpiAutoSampler>>> = Lazy::new(|| Mutex::new(None));
+
+/// 自動取得を開始（実行中のタスクは停止して置き換える）
+pub fn start_sampler(
+    pool: SqlitePool,
+    server: ServerState,
+    video_id: String,
+    use_bundled_key: bool,
+    interval: Duration,
+) -> Result<(), String> {
+    let mut sampler = KPI_SAMPLER
+        .lock()
+        .map_err(|e| format!("Failed to lock KPI sampler: {}", e))?;
+    *sampler = Some(KpiAutoSampler::start(
+        pool,
+        server,
+        video_id,
+        use_bundled_key,
+        interval,
+    ));
+    Ok(())
+}
+
+/// 自動取得を停止（実行中だった場合はtrue）
+pub fn stop_sampler() -> Result<bool, String> {
+    let mut sampler = KPI_SAMPLER
+        .lock()
+        .map_err(|e| format!("Failed to lock KPI sampler: {}", e))?;
+    Ok(sampler.take().is_some_and(|sampler| sampler.is_running()))
+}
+
+/// 自動取得が実行中かどうか
+pub fn is_sampler_running() -> bool {
+    KPI_SAMPLER
+        .lock()
+        .map(|sampler| sampler.as_ref().is_some_and(KpiAutoSampler::is_running))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn stats(concurrent_viewers: Option<i64>) -> LiveStreamStats {
+        LiveStreamStats {
+            concurrent_viewers,
+            like_count: Some(10),
+            view_count: Some(100),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_kpi_history_series_per_video() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        assert!(load_history(&pool, None).await.unwrap().is_empty());
+
+        record_sample(&pool, "old-video", &stats(Some(5))).await.unwrap();
+        record_sample(&pool, "live-video", &stats(Some(120))).await.unwrap();
+        record_sample(&pool, "live-video", &stats(Some(150))).await.unwrap();
+
+        // 省略時は最後に記録した配信の系列
+        let viewers: Vec<Option<i64>> = load_history(&pool, None)
+            .await
+            .unwrap()
+            .iter()
+            .map(|s| s.concurrent_viewers)
+            .collect();
+        assert_eq!(viewers, vec![Some(120), Some(150)]);
+
+        let history = load_history(&pool, Some("old-video")).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].like_count, Some(10));
+        assert_eq!(history[0].view_count, Some(100));
+    }
+
+    #[test]
+    fn test_stream_ended_after_live() {
+        // 配信開始前は同時視聴者数がなくても待つ
+        assert!(!stream_ended(false, &stats(None)));
+        assert!(!stream_ended(true, &stats(Some(0))));
+        assert!(stream_ended(true, &stats(None)));
+    }
+}
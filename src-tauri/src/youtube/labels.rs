@@ -0,0 +1,118 @@
+//! チャット種別の表示ラベル
+//!
+//! メンバーシップ・ギフト・スーパーステッカーで、YouTubeから表示文字列が
+//! 取得できない場合に補うデフォルトのラベルを言語別に提供する。
+//! 言語はsettingsテーブルの`chat_label_locale`に保存され、デフォルトは日本語。
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+/// ラベルの表示言語
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelLocale {
+    /// 日本語（デフォルト）
+    #[default]
+    Ja,
+    /// 英語
+    En,
+}
+
+impl LabelLocale {
+    /// 新規メンバー（レベル名が取得できない場合）
+    pub fn new_member(self) -> &'static str {
+        match self {
+            LabelLocale::Ja => "新規メンバー",
+            LabelLocale::En => "New Member",
+        }
+    }
+
+    /// メンバー継続（レベル名・月数が取得できない場合）
+    pub fn member_milestone(self) -> &'static str {
+        match self {
+            LabelLocale::Ja => "メンバー継続",
+            LabelLocale::En => "Member Milestone",
+        }
+    }
+
+    /// メンバー継続のレベル名と月数
+    pub fn milestone_with_months(self, level: Option<&str>, months: u32) -> String {
+        match self {
+            LabelLocale::Ja => format!("{} ({}ヶ月)", level.unwrap_or("メンバー"), months),
+            LabelLocale::En => format!(
+                "{} ({} month{})",
+                level.unwrap_or("Member"),
+                months,
+                if months == 1 { "" } else { "s" }
+            ),
+        }
+    }
+
+    /// メンバーシップギフトの本文（表示文字列が取得できない場合）
+    pub fn membership_gift(self, count: u32) -> String {
+        match self {
+            LabelLocale::Ja => format!("{}件のメンバーシップをギフトしました", count),
+            LabelLocale::En => format!(
+                "Gifted {} membership{}",
+                count,
+                if count == 1 { "" } else { "s" }
+            ),
+        }
+    }
+
+    /// スーパーステッカーの本文（ステッカーには本文がないため代替表示）
+    pub fn super_sticker(self) -> &'static str {
+        match self {
+            LabelLocale::Ja => "スーパーステッカー",
+            LabelLocale::En => "Super Sticker",
+        }
+    }
+}
+
+/// 現在のラベル言語
+/// 起動時にDBから読み込み、設定コマンドで更新される
+static LABEL_LOCALE: Lazy<RwLock<LabelLocale>> = Lazy::new(|| RwLock::new(LabelLocale::default()));
+
+/// 現在のラベル言語を取得
+pub fn current_locale() -> LabelLocale {
+    LABEL_LOCALE
+        .read()
+        .map(|locale| *locale)
+        .unwrap_or_default()
+}
+
+/// ラベル言語を更新（以降に受信したメッセージから適用）
+pub fn set_locale(locale: LabelLocale) {
+    match LABEL_LOCALE.write() {
+        Ok(mut current) => *current = locale,
+        Err(e) => log::error!("Failed to update label locale: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_membership_and_gift_labels_follow_locale() {
+        assert_eq!(LabelLocale::default(), LabelLocale::Ja);
+
+        assert_eq!(LabelLocale::Ja.new_member(), "新規メンバー");
+        assert_eq!(LabelLocale::En.new_member(), "New Member");
+
+        assert_eq!(LabelLocale::Ja.membership_gift(5), "5件のメンバーシップをギフトしました");
+        assert_eq!(LabelLocale::En.membership_gift(5), "Gifted 5 memberships");
+        assert_eq!(LabelLocale::En.membership_gift(1), "Gifted 1 membership");
+
+        assert_eq!(LabelLocale::Ja.milestone_with_months(Some("Gold"), 12), "Gold (12ヶ月)");
+        assert_eq!(LabelLocale::En.milestone_with_months(None, 1), "Member (1 month)");
+    }
+
+    #[test]
+    fn test_label_locale_serde() {
+        assert_eq!(serde_json::to_string(&LabelLocale::En).unwrap(), "\"en\"");
+        assert_eq!(serde_json::from_str::<LabelLocale>("\"ja\"").unwrap(), LabelLocale::Ja);
+        assert!(serde_json::from_str::<LabelLocale>("\"fr\"").is_err());
+    }
+}
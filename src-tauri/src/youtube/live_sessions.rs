@@ -0,0 +1,189 @@
+//! 配信セッションの記録とスパチャ集計履歴
+//!
+//! ポーリングの開始から停止までを1つの配信セッションとしてlive_sessionsテーブルに記録し、
+//! セッションごとのスパチャ集計（日本円換算の合計・件数・最多支援者）を提供する。
+//! 集計はセッションの時間範囲のcomment_logsが対象（[`super::db::get_superchat_aggregate`]）。
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+use super::db::get_superchat_aggregate;
+use crate::server::types::TopSupporter;
+
+/// 配信セッションごとのスパチャ集計
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSuperchatSummary {
+    /// セッションID
+    pub session_id: i64,
+    /// 動画ID（公式APIをlive_chat_id指定で開始した場合はNone）
+    pub video_id: Option<String>,
+    /// 開始時刻（UTC、RFC3339）
+    pub started_at: String,
+    /// 終了時刻（配信中はNone）
+    pub ended_at: Option<String>,
+    /// スパチャ合計（日本円換算）
+    pub total_jpy: u64,
+    /// スパチャ件数
+    pub count: u32,
+    /// 最多支援者
+    pub top_supporter: Option<TopSupporter>,
+}
+
+/// 配信セッションの開始を記録
+///
+/// 終了していないセッション（停止せずにアプリを終了した場合等）は同じ時刻で終了扱いにする
+pub async fn start_session(pool: &SqlitePool, video_id: Option<&str>) -> Result<i64, String> {
+    start_session_at(pool, video_id, &chrono::Utc::now().to_rfc3339()).await
+}
+
+async fn start_session_at(
+    pool: &SqlitePool,
+    video_id: Option<&str>,
+    started_at: &str,
+) -> Result<i64, String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    sqlx::query("UPDATE live_sessions SET ended_at = ? WHERE ended_at IS NULL")
+        .bind(started_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    let session_id = sqlx::query("INSERT INTO live_sessions (video_id, started_at) VALUES (?, ?)")
+        .bind(video_id)
+        .bind(started_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?
+        .last_insert_rowid();
+
+    tx.commit().await.map_err(|e| format!("DB error: {}", e))?;
+    Ok(session_id)
+}
+
+/// 配信中のセッションの終了を記録（配信中のセッションがなければ何もしない）
+pub async fn end_session(pool: &SqlitePool) -> Result<(), String> {
+    end_session_at(pool, &chrono::Utc::now().to_rfc3339()).await
+}
+
+async fn end_session_at(pool: &SqlitePool, ended_at: &str) -> Result<(), String> {
+    sqlx::query("UPDATE live_sessions SET ended_at = ? WHERE ended_at IS NULL")
+        .bind(ended_at)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+    Ok(())
+}
+
+/// 配信セッションごとのスパチャ集計を新しい順に取得
+pub async fn load_superchat_history(
+    pool: &SqlitePool,
+) -> Result<Vec<SessionSuperchatSummary>, String> {
+    let sessions: Vec<(i64, Option<String>, String, Option<String>)> = sqlx::query_as(
+        "SELECT id, video_id, started_at, ended_at FROM live_sessions ORDER BY started_at DESC, id DESC",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    let mut history = Vec::with_capacity(sessions.len());
+    for (session_id, video_id, started_at, ended_at) in sessions {
+        let superchats = get_superchat_aggregate(pool, &started_at, ended_at.as_deref())
+            .await
+            .map_err(|e| format!("DB error: {}", e))?;
+        history.push(SessionSuperchatSummary {
+            session_id,
+            video_id,
+            started_at,
+            ended_at,
+            total_jpy: superchats.total_jpy,
+            count: superchats.count,
+            top_supporter: superchats.top_supporter,
+        });
+    }
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::youtube::db::save_comments_to_db;
+    use crate::youtube::types::{ChatMessage, MessageType};
+    use chrono::{DateTime, Utc};
+    use tempfile::NamedTempFile;
+
+    fn superchat(id: &str, author: &str, amount: &str, currency: &str, at: &str) -> ChatMessage {
+        ChatMessage::test_message(id)
+            .with_text("")
+            .with_author(author, &format!("UC_{}", author))
+            .with_type(MessageType::SuperChat {
+                amount: amount.to_string(),
+                currency: currency.to_string(),
+                amount_micros: None,
+            })
+            .with_published_at(at.parse::<DateTime<Utc>>().unwrap())
+    }
+
+    fn utc(at: &str) -> String {
+        at.parse::<DateTime<Utc>>().unwrap().to_rfc3339()
+    }
+
+    #[tokio::test]
+    async fn test_superchat_history_per_session() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        assert!(load_superchat_history(&pool).await.unwrap().is_empty());
+
+        let first = start_session_at(&pool, Some("video-1"), &utc("2025-01-01T10:00:00Z"))
+            .await
+            .unwrap();
+        end_session_at(&pool, &utc("2025-01-01T12:00:00Z")).await.unwrap();
+        // 2回目は配信中のまま
+        let second = start_session_at(&pool, Some("video-2"), &utc("2025-01-02T10:00:00Z"))
+            .await
+            .unwrap();
+
+        save_comments_to_db(
+            &pool,
+            &[
+                // セッション1: Aliceが合計¥1,500で最多
+                superchat("sc1", "Alice", "¥1,000", "JPY", "2025-01-01T10:30:00Z"),
+                superchat("sc2", "Bob", "¥500", "JPY", "2025-01-01T11:00:00Z"),
+                superchat("sc3", "Alice", "¥500", "JPY", "2025-01-01T11:30:00Z"),
+                // セッション外（セッション1の終了後）
+                superchat("sc4", "Alice", "¥10,000", "JPY", "2025-01-01T13:00:00Z"),
+                // セッション2: Bobのみ
+                superchat("sc5", "Bob", "¥2,000", "JPY", "2025-01-02T10:10:00Z"),
+            ],
+        )
+        .await;
+
+        let history = load_superchat_history(&pool).await.unwrap();
+        assert_eq!(history.len(), 2);
+
+        // 新しい順
+        assert_eq!(history[0].session_id, second);
+        assert_eq!(history[0].video_id.as_deref(), Some("video-2"));
+        assert_eq!(history[0].ended_at, None);
+        assert_eq!(history[0].total_jpy, 2_000);
+        assert_eq!(history[0].count, 1);
+        assert_eq!(
+            history[0].top_supporter.as_ref().map(|s| s.author_name.as_str()),
+            Some("Bob")
+        );
+
+        assert_eq!(history[1].session_id, first);
+        assert_eq!(history[1].ended_at, Some(utc("2025-01-01T12:00:00Z")));
+        assert_eq!(history[1].total_jpy, 2_000);
+        assert_eq!(history[1].count, 3);
+        let top = history[1].top_supporter.as_ref().unwrap();
+        assert_eq!(top.author_name, "Alice");
+        assert_eq!(top.total_jpy, 1_500);
+    }
+}
@@ -1,11 +1,24 @@
 pub mod api_key_manager;
 pub mod backoff;
+pub mod channel_ban;
+pub mod chat_settings;
 pub mod client;
+pub mod comment_filter;
 pub mod db;
+pub mod dedupe;
+pub mod emoji;
 pub mod errors;
+pub mod first_time;
 pub mod grpc;
 pub mod innertube;
+pub mod kpi_history;
+pub mod labels;
+pub mod live_sessions;
 pub mod poller;
+pub mod profanity;
+pub mod severity;
 pub mod state;
+pub mod translation;
 pub mod types;
 pub mod unified_poller;
+pub mod write_queue;
@@ -1,6 +1,6 @@
 use super::{
-    backoff::ExponentialBackoff, client::YouTubeClient, errors::YouTubeError, state::PollingState,
-    types::ChatMessage,
+    api_key_manager::get_api_key_manager, backoff::ExponentialBackoff, client::YouTubeClient,
+    errors::YouTubeError, state::PollingState, types::{badges_from_flags, ChatMessage},
 };
 use serde::{Deserialize, Serialize};
 use std::sync::{
@@ -9,6 +9,38 @@ use std::sync::{
 };
 use tokio::time::sleep;
 
+/// 一時停止中に再開・停止を確認する間隔
+const PAUSE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// ライブチャットが見つからない場合にライブチャットIDを再取得する最大回数
+/// 配信の一時的な中断・再開（同じ動画ID）に追従するため
+pub const MAX_RECONNECT_ATTEMPTS: u32 = 5;
+
+/// ライブチャットIDの再取得の初回待機時間（以降は倍々に延ばす）
+const RECONNECT_BASE_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// ライブチャットIDの再取得の最大待機時間
+const RECONNECT_MAX_DELAY: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// クォータ超過・レート制限時に切り替えるフォールバックキーを返す関数
+///
+/// 引数は取得中のAPIキー。切り替え先がない場合は`None`を返す
+type FallbackKeyProvider = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// 同梱Primaryキーで取得中の場合のみ、APIキーマネージャーでセカンダリキーに切り替えてそのキーを返す
+///
+/// BYOK・環境変数のキーで取得中の場合、セカンダリキーがない場合・既に切り替え済みの場合は`None`
+fn secondary_key_from_manager(current_key: &str) -> Option<String> {
+    let manager = match get_api_key_manager().read() {
+        Ok(manager) => manager,
+        Err(e) => {
+            log::error!("Failed to read API key manager: {}", e);
+            return None;
+        }
+    };
+    manager.failover_from(current_key).map(str::to_string)
+}
+
 /// ポーリングイベント
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
@@ -25,9 +57,16 @@ pub enum PollingEvent {
     #[serde(rename = "stopped")]
     Stopped { reason: String },
 
-    /// エラー発生（自動再試行する）
+    /// エラー発生
+    ///
+    /// - `retrying`: ポーリングを継続して自動で再試行するか（falseの場合は停止する）
+    /// - `recoverable`: 一時的なエラーか（trueならUIは再接続中として表示できる）
     #[serde(rename = "error")]
-    Error { message: String, retrying: bool },
+    Error {
+        message: String,
+        retrying: bool,
+        recoverable: bool,
+    },
 
     /// クォータ不足（停止）
     #[serde(rename = "quotaExceeded")]
@@ -37,6 +76,14 @@ pub enum PollingEvent {
     #[serde(rename = "streamEnded")]
     StreamEnded,
 
+    /// 一時停止（状態は保持）
+    #[serde(rename = "paused")]
+    Paused,
+
+    /// 一時停止から再開
+    #[serde(rename = "resumed")]
+    Resumed,
+
     /// 状態更新
     #[serde(rename = "stateUpdate")]
     StateUpdate {
@@ -46,6 +93,27 @@ pub enum PollingEvent {
         next_page_token: Option<String>,
         polling_interval_millis: u64,
     },
+
+    /// チャット設定の切り替え（メンバー限定モード）
+    #[serde(rename = "chatSettings")]
+    ChatSettings { members_only: bool },
+
+    /// クォータ超過・レート制限のためセカンダリキーに切り替えた（ポーリングは継続）
+    #[serde(rename = "keySwitched")]
+    KeySwitched { reason: String },
+
+    /// 残りクォータが少ないためポーリング間隔を延ばした（ポーリングは継続）
+    #[serde(rename = "quotaWarning")]
+    QuotaWarning {
+        remaining_quota: i64,
+        polling_interval_millis: u64,
+    },
+
+    /// ライブチャットが見つからないため、動画IDからライブチャットIDを再取得中
+    ///
+    /// 再取得できれば`Started`、上限回数に達した場合は`StreamEnded`・`Stopped`が続く
+    #[serde(rename = "reconnecting")]
+    Reconnecting { attempt: u32, max_attempts: u32 },
 }
 
 /// YouTubeコメントポーリングマネージャー
@@ -53,20 +121,55 @@ pub struct ChatPoller {
     client: YouTubeClient,
     state: Arc<Mutex<Option<PollingState>>>,
     is_running: Arc<AtomicBool>,
+    is_paused: Arc<AtomicBool>,
     backoff: Arc<Mutex<ExponentialBackoff>>,
+    fallback_key: FallbackKeyProvider,
+    /// 動画ID（指定時はライブチャットが見つからない場合にライブチャットIDを再取得する）
+    video_id: Option<String>,
+    /// ライブチャットIDの再取得の初回待機時間
+    reconnect_delay: std::time::Duration,
 }
 
 impl ChatPoller {
     /// 新しいポーラーを作成
     pub fn new(api_key: String) -> Self {
+        Self::with_client(YouTubeClient::new(api_key))
+    }
+
+    /// 指定したクライアントでポーラーを作成
+    fn with_client(client: YouTubeClient) -> Self {
         Self {
-            client: YouTubeClient::new(api_key),
+            client,
             state: Arc::new(Mutex::new(None)),
             is_running: Arc::new(AtomicBool::new(false)),
+            is_paused: Arc::new(AtomicBool::new(false)),
             backoff: Arc::new(Mutex::new(ExponentialBackoff::new())),
+            fallback_key: Arc::new(secondary_key_from_manager),
+            video_id: None,
+            reconnect_delay: RECONNECT_BASE_DELAY,
         }
     }
 
+    /// 動画IDを指定（配信が一時的に中断した場合にライブチャットIDを再取得して再接続する）
+    pub fn with_video_id(mut self, video_id: String) -> Self {
+        self.video_id = Some(video_id);
+        self
+    }
+
+    /// テスト用: 再接続の待機時間を差し替え
+    #[cfg(test)]
+    fn with_reconnect_delay(mut self, delay: std::time::Duration) -> Self {
+        self.reconnect_delay = delay;
+        self
+    }
+
+    /// テスト用: フォールバックキーの取得方法を差し替え
+    #[cfg(test)]
+    fn with_fallback_key(mut self, provider: FallbackKeyProvider) -> Self {
+        self.fallback_key = provider;
+        self
+    }
+
     /// ポーリングを開始
     ///
     /// # 引数
@@ -143,10 +246,26 @@ impl ChatPoller {
         let client = self.client.clone();
         let state = Arc::clone(&self.state);
         let is_running = Arc::clone(&self.is_running);
+        let is_paused = Arc::clone(&self.is_paused);
         let backoff = Arc::clone(&self.backoff);
+        let fallback_key = Arc::clone(&self.fallback_key);
+        let reconnect = self.video_id.clone().map(|video_id| Reconnect {
+            video_id,
+            base_delay: self.reconnect_delay,
+        });
 
         tokio::spawn(async move {
-            Self::polling_loop(client, state, is_running, backoff, event_callback).await;
+            Self::polling_loop(
+                client,
+                state,
+                is_running,
+                is_paused,
+                backoff,
+                fallback_key,
+                reconnect,
+                event_callback,
+            )
+            .await;
         });
 
         Ok(())
@@ -155,6 +274,20 @@ impl ChatPoller {
     /// ポーリングを停止
     pub fn stop(&self) {
         self.is_running.store(false, Ordering::SeqCst);
+        self.is_paused.store(false, Ordering::SeqCst);
+    }
+
+    /// ポーリングを一時停止
+    ///
+    /// ページトークン・クォータ等の状態は保持したまま取得を止める。
+    /// 開始前に呼んだ場合は一時停止状態で開始する。
+    pub fn pause(&self) {
+        self.is_paused.store(true, Ordering::SeqCst);
+    }
+
+    /// 一時停止を解除
+    pub fn resume(&self) {
+        self.is_paused.store(false, Ordering::SeqCst);
     }
 
     /// ポーリング中かどうかを確認
@@ -162,6 +295,11 @@ impl ChatPoller {
         self.is_running.load(Ordering::SeqCst)
     }
 
+    /// 一時停止中かどうかを確認
+    pub fn is_paused(&self) -> bool {
+        self.is_paused.load(Ordering::SeqCst)
+    }
+
     /// 現在の状態を取得
     pub fn get_state(&self) -> Option<PollingState> {
         self.state
@@ -171,16 +309,40 @@ impl ChatPoller {
     }
 
     /// ポーリングループ（内部実装）
+    ///
+    /// クォータ超過・レート制限時は、停止・待機する前に一度だけフォールバックキーへ切り替えて再試行する。
+    /// 動画IDが指定されていれば、ライブチャットが見つからない場合にライブチャットIDを再取得する
+    #[allow(clippy::too_many_arguments)]
     async fn polling_loop<F>(
-        client: YouTubeClient,
+        mut client: YouTubeClient,
         state: Arc<Mutex<Option<PollingState>>>,
         is_running: Arc<AtomicBool>,
+        is_paused: Arc<AtomicBool>,
         backoff: Arc<Mutex<ExponentialBackoff>>,
+        fallback_key: FallbackKeyProvider,
+        reconnect: Option<Reconnect>,
         event_callback: F,
     ) where
         F: Fn(PollingEvent) + Send + Sync + 'static,
     {
+        // フォールバックキーに切り替え済みか（切り替えは1セッションにつき1回）
+        let mut key_switched = false;
+        // 直前のポーリング間隔の倍率（上がった時だけ警告する）
+        let mut quota_throttle = 1;
+
         while is_running.load(Ordering::SeqCst) {
+            // 一時停止中は取得せずに待機（状態はそのまま保持）
+            if is_paused.load(Ordering::SeqCst) {
+                event_callback(PollingEvent::Paused);
+                while is_paused.load(Ordering::SeqCst) && is_running.load(Ordering::SeqCst) {
+                    sleep(PAUSE_CHECK_INTERVAL).await;
+                }
+                if !is_running.load(Ordering::SeqCst) {
+                    break;
+                }
+                event_callback(PollingEvent::Resumed);
+            }
+
             // 現在の状態を取得
             let (live_chat_id, page_token, polling_interval) = {
                 let state_lock = match state.lock() {
@@ -190,6 +352,7 @@ impl ChatPoller {
                         event_callback(PollingEvent::Error {
                             message: "内部エラーが発生しました".to_string(),
                             retrying: false,
+                            recoverable: false,
                         });
                         break;
                     }
@@ -217,6 +380,16 @@ impl ChatPoller {
                         backoff_lock.reset();
                     }
 
+                    // メンバー限定モードの切り替えを検出
+                    if let Some(members_only) = crate::youtube::chat_settings::detect_members_only(
+                        response
+                            .items
+                            .iter()
+                            .map(|item| item.snippet.message_type.as_str()),
+                    ) {
+                        event_callback(PollingEvent::ChatSettings { members_only });
+                    }
+
                     // メッセージがあればイベント送信
                     if !response.items.is_empty() {
                         let messages: Vec<ChatMessage> = response
@@ -243,6 +416,10 @@ impl ChatPoller {
                                 let message_type =
                                     crate::youtube::types::parse_message_type(&item.snippet);
 
+                                // 本文のUnicode絵文字を画像表示できるよう分割
+                                let message_runs =
+                                    crate::youtube::emoji::build_message_runs(&item.snippet.display_message);
+
                                 Some(ChatMessage {
                                     id: item.id,
                                     message: item.snippet.display_message,
@@ -254,8 +431,14 @@ impl ChatPoller {
                                     is_moderator: item.author_details.is_chat_moderator,
                                     is_member: item.author_details.is_chat_sponsor,
                                     is_verified: item.author_details.is_verified,
+                                    badges: badges_from_flags(
+                                        item.author_details.is_chat_owner,
+                                        item.author_details.is_chat_moderator,
+                                        item.author_details.is_chat_sponsor,
+                                        item.author_details.is_verified,
+                                    ),
                                     message_type,
-                                    message_runs: None, // 公式APIでは絵文字情報なし
+                                    message_runs,
                                 })
                             })
                             .collect();
@@ -284,6 +467,22 @@ impl ChatPoller {
                                         polling_interval_millis: s.polling_interval_millis,
                                     });
                                 }
+                                // 残りクォータが閾値を下回ったら間隔を延ばしたことを通知
+                                let multiplier = s.quota_throttle_multiplier();
+                                if multiplier > quota_throttle {
+                                    log::warn!(
+                                        "Remaining quota is low ({} units), polling interval x{}",
+                                        s.estimated_remaining_quota(),
+                                        multiplier
+                                    );
+                                    event_callback(PollingEvent::QuotaWarning {
+                                        remaining_quota: s.estimated_remaining_quota(),
+                                        polling_interval_millis: s.polling_interval().as_millis()
+                                            as u64,
+                                    });
+                                }
+                                quota_throttle = multiplier;
+
                                 // 更新後の新しいポーリング間隔を返す
                                 Some(s.polling_interval())
                             } else {
@@ -302,6 +501,26 @@ impl ChatPoller {
                 Err(e) => {
                     log::error!("Polling error: {}", e);
 
+                    // クォータ超過・レート制限: セカンダリキーがあれば切り替えて即再試行
+                    if matches!(e, YouTubeError::QuotaExceeded | YouTubeError::RateLimitExceeded)
+                        && !key_switched
+                    {
+                        if let Some(key) = fallback_key(client.api_key()) {
+                            log::warn!("Switching to fallback API key after: {}", e);
+                            client.set_api_key(key);
+                            key_switched = true;
+                            event_callback(PollingEvent::KeySwitched {
+                                reason: match e {
+                                    YouTubeError::QuotaExceeded => {
+                                        "クォータ超過のためセカンダリキーに切り替えました".to_string()
+                                    }
+                                    _ => "レート制限のためセカンダリキーに切り替えました".to_string(),
+                                },
+                            });
+                            continue;
+                        }
+                    }
+
                     match e {
                         YouTubeError::QuotaExceeded => {
                             // クォータ超過: 停止
@@ -313,6 +532,30 @@ impl ChatPoller {
                             break;
                         }
                         YouTubeError::LiveChatNotFound | YouTubeError::LiveChatDisabled => {
+                            // 配信の一時的な中断の可能性: ライブチャットIDを再取得して再接続
+                            if let (YouTubeError::LiveChatNotFound, Some(reconnect)) =
+                                (&e, reconnect.as_ref())
+                            {
+                                match reconnect.resolve(&client, &is_running, &event_callback).await {
+                                    Some(live_chat_id) => {
+                                        log::info!("Reconnected to live chat: {}", live_chat_id);
+                                        if let Ok(mut state_lock) = state.lock() {
+                                            if let Some(s) = state_lock.as_mut() {
+                                                s.live_chat_id = live_chat_id.clone();
+                                                s.reset_page_token();
+                                            }
+                                        } else {
+                                            log::error!("Failed to acquire state lock for reconnect");
+                                        }
+                                        event_callback(PollingEvent::Started { live_chat_id });
+                                        continue;
+                                    }
+                                    // 再接続待ちの間に停止された
+                                    None if !is_running.load(Ordering::SeqCst) => break,
+                                    None => {}
+                                }
+                            }
+
                             // 配信終了またはチャット無効: 停止
                             let reason = match e {
                                 YouTubeError::LiveChatDisabled => {
@@ -343,6 +586,7 @@ impl ChatPoller {
                             event_callback(PollingEvent::Error {
                                 message: "ページトークンが無効です。最初から取得し直します".to_string(),
                                 retrying: true,
+                                recoverable: true,
                             });
 
                             // 短い待機後に続行
@@ -369,6 +613,7 @@ impl ChatPoller {
                                 event_callback(PollingEvent::Error {
                                     message: "最大リトライ回数に達しました".to_string(),
                                     retrying: false,
+                                    recoverable: true,
                                 });
                                 event_callback(PollingEvent::Stopped {
                                     reason: "レート制限のリトライ上限に達しました".to_string(),
@@ -385,6 +630,7 @@ impl ChatPoller {
                                     delay.as_secs()
                                 ),
                                 retrying: true,
+                                recoverable: true,
                             });
 
                             sleep(delay).await;
@@ -394,6 +640,7 @@ impl ChatPoller {
                             event_callback(PollingEvent::Error {
                                 message: "APIキーが無効です".to_string(),
                                 retrying: false,
+                                recoverable: false,
                             });
                             event_callback(PollingEvent::Stopped {
                                 reason: "APIキーが無効です".to_string(),
@@ -425,6 +672,7 @@ impl ChatPoller {
                                         e
                                     ),
                                     retrying: false,
+                                    recoverable: e.is_recoverable(),
                                 });
                                 event_callback(PollingEvent::Stopped {
                                     reason: "リトライ上限に達しました".to_string(),
@@ -438,6 +686,7 @@ impl ChatPoller {
                             event_callback(PollingEvent::Error {
                                 message: format!("エラーが発生しました: {}", e),
                                 retrying: true,
+                                recoverable: e.is_recoverable(),
                             });
 
                             // ページトークンをリセット
@@ -468,7 +717,475 @@ impl Clone for ChatPoller {
             client: self.client.clone(),
             state: Arc::clone(&self.state),
             is_running: Arc::clone(&self.is_running),
+            is_paused: Arc::clone(&self.is_paused),
             backoff: Arc::clone(&self.backoff),
+            fallback_key: Arc::clone(&self.fallback_key),
+            video_id: self.video_id.clone(),
+            reconnect_delay: self.reconnect_delay,
+        }
+    }
+}
+
+/// ライブチャットが見つからない場合の再接続（動画IDからライブチャットIDを再取得）
+struct Reconnect {
+    video_id: String,
+    base_delay: std::time::Duration,
+}
+
+impl Reconnect {
+    /// 指数バックオフで待機しながら最大`MAX_RECONNECT_ATTEMPTS`回ライブチャットIDを再取得
+    ///
+    /// 取得できた場合はそのIDを、上限回数に達した・停止された場合はNoneを返す
+    async fn resolve<F>(
+        &self,
+        client: &YouTubeClient,
+        is_running: &AtomicBool,
+        event_callback: &F,
+    ) -> Option<String>
+    where
+        F: Fn(PollingEvent),
+    {
+        let mut backoff =
+            ExponentialBackoff::with_config(self.base_delay, RECONNECT_MAX_DELAY, MAX_RECONNECT_ATTEMPTS);
+        for attempt in 1..=MAX_RECONNECT_ATTEMPTS {
+            event_callback(PollingEvent::Reconnecting {
+                attempt,
+                max_attempts: MAX_RECONNECT_ATTEMPTS,
+            });
+            let delay = backoff.next_delay();
+            log::warn!(
+                "Live chat not found, re-resolving live chat ID in {:?} ({}/{})",
+                delay,
+                attempt,
+                MAX_RECONNECT_ATTEMPTS
+            );
+            sleep(delay).await;
+            if !is_running.load(Ordering::SeqCst) {
+                return None;
+            }
+
+            match client.get_live_chat_id(&self.video_id).await {
+                Ok(live_chat_id) => return Some(live_chat_id),
+                Err(e) => log::warn!("Failed to re-resolve live chat ID: {}", e),
+            }
         }
+        log::error!("Live chat not found after {} reconnect attempts", MAX_RECONNECT_ATTEMPTS);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+    use std::time::Duration;
+
+    /// イベント種別を記録するコールバックを作成
+    fn recording_callback() -> (Arc<Mutex<Vec<String>>>, impl Fn(PollingEvent) + Send + Sync + 'static) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_clone = Arc::clone(&events);
+        let callback = move |event: PollingEvent| {
+            let name = match event {
+                PollingEvent::Messages { .. } => "messages",
+                PollingEvent::Started { .. } => "started",
+                PollingEvent::Stopped { .. } => "stopped",
+                PollingEvent::Error { .. } => "error",
+                PollingEvent::QuotaExceeded => "quotaExceeded",
+                PollingEvent::StreamEnded => "streamEnded",
+                PollingEvent::Paused => "paused",
+                PollingEvent::Resumed => "resumed",
+                PollingEvent::StateUpdate { .. } => "stateUpdate",
+                PollingEvent::ChatSettings { members_only: true } => "membersOnlyOn",
+                PollingEvent::ChatSettings { members_only: false } => "membersOnlyOff",
+                PollingEvent::KeySwitched { .. } => "keySwitched",
+                PollingEvent::QuotaWarning { .. } => "quotaWarning",
+                PollingEvent::Reconnecting { .. } => "reconnecting",
+            };
+            events_clone.lock().unwrap().push(name.to_string());
+        };
+        (events, callback)
+    }
+
+    #[test]
+    fn test_pause_resume_flags() {
+        let poller = ChatPoller::new("test_api_key".to_string());
+        assert!(!poller.is_paused());
+
+        poller.pause();
+        assert!(poller.is_paused());
+
+        poller.resume();
+        assert!(!poller.is_paused());
+
+        // stopは一時停止状態も解除する
+        poller.pause();
+        poller.stop();
+        assert!(!poller.is_paused());
+    }
+
+    #[tokio::test]
+    async fn test_pause_halts_fetch_and_resume_keeps_state() {
+        let mut server = Server::new_async().await;
+        let poller = ChatPoller::with_client(YouTubeClient::new_with_base_url(
+            "test_api_key".to_string(),
+            server.url(),
+        ));
+
+        // 保存済みトークンで取得されることを検証
+        let mock = server
+            .mock("GET", "/liveChat/messages")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "pageToken".into(),
+                "saved-token".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"pollingIntervalMillis": 5000, "nextPageToken": "next-token", "items": []}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let (events, callback) = recording_callback();
+
+        poller.pause();
+        poller
+            .start_with_state(
+                "chat-id".to_string(),
+                Some("saved-token".to_string()),
+                25,
+                None,
+                callback,
+            )
+            .await
+            .unwrap();
+
+        // 一時停止中は取得しない
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(!mock.matched_async().await);
+        assert!(poller.is_running());
+        let state = poller.get_state().unwrap();
+        assert_eq!(state.next_page_token.as_deref(), Some("saved-token"));
+        assert_eq!(state.quota_used, 25);
+        assert_eq!(*events.lock().unwrap(), vec!["started", "paused"]);
+
+        // 再開すると保持したトークンで取得が続く
+        poller.resume();
+        for _ in 0..50 {
+            if poller.get_state().unwrap().poll_count > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        poller.stop();
+
+        mock.assert_async().await;
+        let state = poller.get_state().unwrap();
+        assert_eq!(state.next_page_token.as_deref(), Some("next-token"));
+        assert_eq!(state.quota_used, 30);
+        assert_eq!(*events.lock().unwrap(), vec!["started", "paused", "resumed"]);
+    }
+
+    #[tokio::test]
+    async fn test_members_only_event_emits_chat_settings() {
+        let mut server = Server::new_async().await;
+        let poller = ChatPoller::with_client(YouTubeClient::new_with_base_url(
+            "test_api_key".to_string(),
+            server.url(),
+        ));
+
+        let body = serde_json::json!({
+            "pollingIntervalMillis": 5000,
+            "items": [{
+                "id": "mode-1",
+                "snippet": {
+                    "type": "sponsorOnlyModeStartedEvent",
+                    "publishedAt": "2025-01-01T00:00:00Z",
+                    "displayMessage": ""
+                },
+                "authorDetails": {
+                    "channelId": "UC_owner",
+                    "displayName": "Owner",
+                    "profileImageUrl": "",
+                    "isVerified": false,
+                    "isChatOwner": true,
+                    "isChatSponsor": false,
+                    "isChatModerator": false
+                }
+            }]
+        });
+        let mock = server
+            .mock("GET", "/liveChat/messages")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .create_async()
+            .await;
+
+        let (events, callback) = recording_callback();
+        poller
+            .start_with_state("chat-id".to_string(), None, 0, None, callback)
+            .await
+            .unwrap();
+
+        for _ in 0..50 {
+            if poller.get_state().unwrap().poll_count > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        poller.stop();
+
+        mock.assert_async().await;
+        let events = events.lock().unwrap();
+        assert!(events.contains(&"membersOnlyOn".to_string()));
+        // チャット設定の通知はメッセージより先に送る
+        let settings_index = events.iter().position(|e| e == "membersOnlyOn").unwrap();
+        let messages_index = events.iter().position(|e| e == "messages").unwrap();
+        assert!(settings_index < messages_index);
+    }
+
+    #[tokio::test]
+    async fn test_quota_exceeded_switches_to_fallback_key() {
+        let mut server = Server::new_async().await;
+        let poller = ChatPoller::with_client(YouTubeClient::new_with_base_url(
+            "primary-key".to_string(),
+            server.url(),
+        ))
+        .with_fallback_key(Arc::new(|_: &str| Some("secondary-key".to_string())));
+
+        let quota_mock = server
+            .mock("GET", "/liveChat/messages")
+            .match_query(mockito::Matcher::UrlEncoded("key".into(), "primary-key".into()))
+            .with_status(403)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"error": {"code": 403, "errors": [{"reason": "quotaExceeded"}]}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let secondary_mock = server
+            .mock("GET", "/liveChat/messages")
+            .match_query(mockito::Matcher::UrlEncoded("key".into(), "secondary-key".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"pollingIntervalMillis": 5000, "items": []}"#)
+            .create_async()
+            .await;
+
+        let (events, callback) = recording_callback();
+        poller
+            .start_with_state("chat-id".to_string(), None, 0, None, callback)
+            .await
+            .unwrap();
+
+        for _ in 0..50 {
+            if poller.get_state().unwrap().poll_count > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        poller.stop();
+
+        quota_mock.assert_async().await;
+        secondary_mock.assert_async().await;
+        // 停止せずにキー切り替えを通知して取得を続ける
+        assert!(poller.get_state().unwrap().poll_count > 0);
+        assert_eq!(*events.lock().unwrap(), vec!["started", "keySwitched"]);
+    }
+
+    #[tokio::test]
+    async fn test_quota_exceeded_without_fallback_key_stops() {
+        let mut server = Server::new_async().await;
+        let poller = ChatPoller::with_client(YouTubeClient::new_with_base_url(
+            "primary-key".to_string(),
+            server.url(),
+        ))
+        .with_fallback_key(Arc::new(|_: &str| None));
+
+        let mock = server
+            .mock("GET", "/liveChat/messages")
+            .match_query(mockito::Matcher::Any)
+            .with_status(403)
+            .with_body(r#"{"error": {"errors": [{"reason": "quotaExceeded"}]}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let (events, callback) = recording_callback();
+        poller
+            .start_with_state("chat-id".to_string(), None, 0, None, callback)
+            .await
+            .unwrap();
+
+        for _ in 0..50 {
+            if !poller.is_running() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        mock.assert_async().await;
+        assert!(!poller.is_running());
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec!["started", "quotaExceeded", "stopped"]
+        );
+    }
+    #[tokio::test]
+    async fn test_low_quota_emits_warning_and_lengthens_interval() {
+        let mut server = Server::new_async().await;
+        let poller = ChatPoller::with_client(YouTubeClient::new_with_base_url(
+            "test_api_key".to_string(),
+            server.url(),
+        ));
+
+        let mock = server
+            .mock("GET", "/liveChat/messages")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"pollingIntervalMillis": 5000, "items": []}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let (events, callback) = recording_callback();
+        // 1回のポーリングで残りが2000 units未満になる
+        poller
+            .start_with_state("chat-id".to_string(), None, 7_996, None, callback)
+            .await
+            .unwrap();
+
+        for _ in 0..50 {
+            if poller.get_state().unwrap().poll_count > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        poller.stop();
+
+        mock.assert_async().await;
+        assert_eq!(*events.lock().unwrap(), vec!["started", "quotaWarning"]);
+        assert_eq!(
+            poller.get_state().unwrap().polling_interval(),
+            Duration::from_secs(10)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_live_chat_not_found_reconnects_with_new_live_chat_id() {
+        let mut server = Server::new_async().await;
+        let poller = ChatPoller::with_client(YouTubeClient::new_with_base_url(
+            "test_api_key".to_string(),
+            server.url(),
+        ))
+        .with_video_id("video-id".to_string())
+        .with_reconnect_delay(Duration::from_millis(10));
+
+        let old_chat_mock = server
+            .mock("GET", "/liveChat/messages")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "liveChatId".into(),
+                "old-chat-id".into(),
+            ))
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+        let videos_mock = server
+            .mock("GET", "/videos")
+            .match_query(mockito::Matcher::UrlEncoded("id".into(), "video-id".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"items": [{"liveStreamingDetails": {"activeLiveChatId": "new-chat-id"}}]}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let new_chat_mock = server
+            .mock("GET", "/liveChat/messages")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "liveChatId".into(),
+                "new-chat-id".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"pollingIntervalMillis": 5000, "items": []}"#)
+            .create_async()
+            .await;
+
+        let (events, callback) = recording_callback();
+        poller
+            .start_with_state("old-chat-id".to_string(), None, 0, None, callback)
+            .await
+            .unwrap();
+
+        for _ in 0..50 {
+            if poller.get_state().unwrap().poll_count > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        poller.stop();
+
+        old_chat_mock.assert_async().await;
+        videos_mock.assert_async().await;
+        new_chat_mock.assert_async().await;
+        assert_eq!(poller.get_state().unwrap().live_chat_id, "new-chat-id");
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec!["started", "reconnecting", "started"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_live_chat_not_found_stops_after_reconnect_attempts() {
+        let mut server = Server::new_async().await;
+        let poller = ChatPoller::with_client(YouTubeClient::new_with_base_url(
+            "test_api_key".to_string(),
+            server.url(),
+        ))
+        .with_video_id("video-id".to_string())
+        .with_reconnect_delay(Duration::from_millis(1));
+
+        let messages_mock = server
+            .mock("GET", "/liveChat/messages")
+            .match_query(mockito::Matcher::Any)
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+        // 配信が終了しactiveLiveChatIdがなくなった
+        let videos_mock = server
+            .mock("GET", "/videos")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"items": [{"liveStreamingDetails": {}}]}"#)
+            .expect(MAX_RECONNECT_ATTEMPTS as usize)
+            .create_async()
+            .await;
+
+        let (events, callback) = recording_callback();
+        poller
+            .start_with_state("chat-id".to_string(), None, 0, None, callback)
+            .await
+            .unwrap();
+
+        for _ in 0..50 {
+            if !poller.is_running() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        messages_mock.assert_async().await;
+        videos_mock.assert_async().await;
+        assert!(!poller.is_running());
+        let mut expected = vec!["started"];
+        expected.extend(std::iter::repeat("reconnecting").take(MAX_RECONNECT_ATTEMPTS as usize));
+        expected.extend(["streamEnded", "stopped"]);
+        assert_eq!(*events.lock().unwrap(), expected);
     }
 }
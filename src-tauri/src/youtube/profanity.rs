@@ -0,0 +1,278 @@
+//! コメントの不適切語マスク
+//!
+//! ブロックリスト（[`super::comment_filter`]）のようにコメントごと除外するのではなく、
+//! 一致した語だけを同じ文字数の`*`に置き換えてオーバーレイに配信する。
+//! DBには元の本文のまま保存する（マスクはブロードキャスト直前にのみ適用）。
+//!
+//! ## 語リスト
+//! - 組み込みの語リスト（日本語・英語）を言語ごとに有効化できる
+//! - 任意の語を追加できる（大文字小文字を区別しない）
+//! - 英数字のみの語は単語の途中には一致しない（"class"の"ass"等は伏せない）
+//! - 日本語は単語境界がないため、除外リストの語の一部としての一致は伏せない
+//!   （"やくそく"の"くそ"等）
+
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+
+use super::labels::LabelLocale;
+use super::types::{ChatMessage, MessageRun};
+
+/// 追加できる語の最大数
+pub const MAX_PROFANITY_WORDS: usize = 500;
+
+/// 組み込みの語リスト（日本語）
+///
+/// 短いひらがなの語（"しね"・"くそ"等）は通常の語の一部に含まれやすいため入れない
+const BUILTIN_WORDS_JA: &[&str] = &[
+    "死ね",
+    "殺すぞ",
+    "ころすぞ",
+    "きもい",
+    "キモい",
+    "うざい",
+    "ウザい",
+    "クソ",
+];
+
+/// 一致しても伏せない日本語の語（この語の一部として一致した場合は除外）
+const EXCLUDED_WORDS_JA: &[&str] = &["やくそく", "ほくそえ", "ほくそ笑", "どくそう"];
+
+/// 組み込みの語リスト（英語）
+const BUILTIN_WORDS_EN: &[&str] = &[
+    "fuck", "fucking", "shit", "bitch", "bastard", "asshole", "dick", "cunt",
+];
+
+/// 不適切語マスクの設定
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfanityConfig {
+    /// マスクを有効にするか
+    pub enabled: bool,
+    /// 有効にする組み込みの語リストの言語
+    #[serde(default)]
+    pub builtin_locales: Vec<LabelLocale>,
+    /// 追加の語（空白のみの語は無視）
+    #[serde(default)]
+    pub words: Vec<String>,
+}
+
+/// 不適切語マスク
+#[derive(Debug, Clone, Default)]
+pub struct ProfanityMasker {
+    /// 全ての語をまとめた正規表現（語がない・無効の場合はNone）
+    pattern: Option<Regex>,
+}
+
+impl ProfanityMasker {
+    /// 設定からマスクを作成
+    pub fn new(config: &ProfanityConfig) -> Result<Self, String> {
+        if config.words.len() > MAX_PROFANITY_WORDS {
+            return Err(format!(
+                "マスクする語は{}件以下で指定してください: {}件",
+                MAX_PROFANITY_WORDS,
+                config.words.len()
+            ));
+        }
+        if !config.enabled {
+            return Ok(Self::default());
+        }
+
+        let builtin = config
+            .builtin_locales
+            .iter()
+            .flat_map(|locale| match locale {
+                LabelLocale::Ja => BUILTIN_WORDS_JA,
+                LabelLocale::En => BUILTIN_WORDS_EN,
+            });
+        let mut words: Vec<&str> = builtin
+            .copied()
+            .chain(config.words.iter().map(|w| w.trim()))
+            .filter(|w| !w.is_empty())
+            .collect();
+        if words.is_empty() {
+            return Ok(Self::default());
+        }
+
+        // 長い語を優先して一致させる（"fucking"を"fuck"より先に）
+        words.sort_by_key(|w| std::cmp::Reverse(w.chars().count()));
+        words.dedup();
+        let alternation = words
+            .iter()
+            .map(|w| regex::escape(w))
+            .collect::<Vec<_>>()
+            .join("|");
+        let pattern = Regex::new(&format!("(?i){}", alternation))
+            .map_err(|e| format!("マスクする語が不正です: {}", e))?;
+        Ok(Self {
+            pattern: Some(pattern),
+        })
+    }
+
+    /// 一致した語を同じ文字数の`*`に置き換える
+    pub fn mask(&self, text: &str) -> String {
+        let Some(pattern) = &self.pattern else {
+            return text.to_string();
+        };
+        pattern
+            .replace_all(text, |caps: &Captures| {
+                let matched = caps.get(0).expect("group 0 always exists");
+                if is_excluded_match(text, matched.start(), matched.end()) {
+                    matched.as_str().to_string()
+                } else {
+                    "*".repeat(matched.as_str().chars().count())
+                }
+            })
+            .into_owned()
+    }
+
+    /// コメント本文とテキストランをマスク
+    pub fn mask_message(&self, mut message: ChatMessage) -> ChatMessage {
+        if self.pattern.is_none() {
+            return message;
+        }
+        message.message = self.mask(&message.message);
+        if let Some(runs) = message.message_runs.as_mut() {
+            for run in runs {
+                if let MessageRun::Text { text } = run {
+                    *text = self.mask(text);
+                }
+            }
+        }
+        message
+    }
+}
+
+/// 英数字の語が単語の途中に一致したか（前後が英数字に続いている）
+///
+/// 日本語など英数字以外を含む語は単語境界がないため常にfalse
+pub(crate) fn is_within_word(text: &str, start: usize, end: usize) -> bool {
+    let matched = &text[start..end];
+    if !matched.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return false;
+    }
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    before.is_some_and(|c| c.is_ascii_alphanumeric())
+        || after.is_some_and(|c| c.is_ascii_alphanumeric())
+}
+
+/// 一致を伏せずに残すか
+///
+/// 英数字の語が単語の途中に一致した場合と、除外リストの語の一部として一致した場合
+fn is_excluded_match(text: &str, start: usize, end: usize) -> bool {
+    is_within_word(text, start, end)
+        || EXCLUDED_WORDS_JA.iter().any(|excluded| {
+            text.match_indices(excluded)
+                .any(|(pos, word)| pos <= start && end <= pos + word.len())
+        })
+}
+
+/// 現在の不適切語マスク
+/// 起動時にDBから読み込み、設定コマンドで更新される
+static PROFANITY_MASKER: Lazy<RwLock<Arc<ProfanityMasker>>> =
+    Lazy::new(|| RwLock::new(Arc::new(ProfanityMasker::default())));
+
+/// 現在の不適切語マスクを取得
+pub fn current_masker() -> Arc<ProfanityMasker> {
+    PROFANITY_MASKER
+        .read()
+        .map(|masker| Arc::clone(&masker))
+        .unwrap_or_default()
+}
+
+/// 不適切語マスクを更新（以降に取得したコメントから適用）
+pub fn set_masker(masker: ProfanityMasker) {
+    match PROFANITY_MASKER.write() {
+        Ok(mut current) => *current = Arc::new(masker),
+        Err(e) => log::error!("Failed to update profanity masker: {}", e),
+    }
+}
+
+/// ブロードキャストするコメントに現在の不適切語マスクを適用
+pub fn mask_for_broadcast(messages: Vec<ChatMessage>) -> Vec<ChatMessage> {
+    let masker = current_masker();
+    if masker.pattern.is_none() {
+        return messages;
+    }
+    messages
+        .into_iter()
+        .map(|message| masker.mask_message(message))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn masker(builtin_locales: Vec<LabelLocale>, words: &[&str]) -> ProfanityMasker {
+        ProfanityMasker::new(&ProfanityConfig {
+            enabled: true,
+            builtin_locales,
+            words: words.iter().map(|w| w.to_string()).collect(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mask_english_and_japanese_terms() {
+        let masker = masker(vec![LabelLocale::Ja, LabelLocale::En], &[]);
+
+        assert_eq!(masker.mask("What the FUCK"), "What the ****");
+        assert_eq!(masker.mask("fucking great"), "******* great");
+        assert_eq!(masker.mask("お前もう死ね"), "お前もう**");
+        // 日本語に続く英単語も伏せる
+        assert_eq!(masker.mask("shitだわ"), "****だわ");
+    }
+
+    #[test]
+    fn test_clean_text_untouched() {
+        let masker = masker(vec![LabelLocale::En], &["  ", "ばーか"]);
+
+        assert_eq!(masker.mask("こんにちは！"), "こんにちは！");
+        // 英単語の途中には一致しない
+        assert_eq!(
+            masker.mask("Scunthorpe class dickens"),
+            "Scunthorpe class dickens"
+        );
+        assert_eq!(masker.mask("ばーかばーか"), "******");
+
+        // 無効の場合は何もしない
+        let disabled = ProfanityMasker::new(&ProfanityConfig {
+            enabled: false,
+            builtin_locales: vec![LabelLocale::En],
+            words: Vec::new(),
+        })
+        .unwrap();
+        assert_eq!(disabled.mask("shit"), "shit");
+    }
+
+    #[test]
+    fn test_excluded_words_untouched() {
+        // 除外リストの語の一部としての一致は伏せない
+        let masker = masker(vec![LabelLocale::Ja], &["くそ"]);
+
+        assert_eq!(masker.mask("明日のやくそく"), "明日のやくそく");
+        assert_eq!(masker.mask("ほくそ笑む"), "ほくそ笑む");
+        assert_eq!(masker.mask("くそゲーだ、やくそく"), "**ゲーだ、やくそく");
+    }
+
+    #[test]
+    fn test_mask_message_runs() {
+        let masker = masker(Vec::new(), &["spam"]);
+        let message = ChatMessage {
+            message_runs: Some(vec![MessageRun::Text {
+                text: "no SPAM please".to_string(),
+            }]),
+            ..ChatMessage::test_message("c1").with_text("no SPAM please")
+        };
+
+        let masked = masker.mask_message(message);
+        assert_eq!(masked.message, "no **** please");
+        assert!(matches!(
+            masked.message_runs.as_deref(),
+            Some([MessageRun::Text { text }]) if text == "no **** please"
+        ));
+    }
+}
@@ -0,0 +1,337 @@
+//! コメントの不適切度（重み付きスコア）判定
+//!
+//! ブロックリスト（[`super::comment_filter`]）の全除外・不適切語マスク（[`super::profanity`]）の伏せ字とは別に、
+//! 語ごとの重みの合計からコメントを`clean` / `mild` / `strong`に分類し、`comment:add`に`severity`として付けて配信する。
+//! オーバーレイは`strong`の本文をぼかし表示にし、除外せずに段階的に扱う。
+//! ブロックリストに一致したコメントは従来どおり配信しない（最上位の段階）。
+//!
+//! ## 判定
+//! - 一致した語の重みを出現ごとに合計し、`strong_threshold`以上で`strong`、`mild_threshold`以上で`mild`
+//! - 組み込みの語リスト（日本語・英語、重み付き）を言語ごとに有効化でき、任意の語を重み付きで追加できる
+//! - 追加した語が組み込みの語と同じ場合は追加した重みを使う（重み0で組み込みの語を無効化）
+//! - 大文字小文字を区別せず、英数字のみの語は単語の途中には一致しない
+//! - 不適切語マスクより前の本文で判定する
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use super::labels::LabelLocale;
+use super::profanity::is_within_word;
+use super::types::ChatMessage;
+
+/// 追加できる語の最大数
+pub const MAX_SEVERITY_WORDS: usize = 500;
+
+/// 語の重みの最大値
+pub const MAX_SEVERITY_WEIGHT: u32 = 10;
+
+/// 閾値の最大値
+pub const MAX_SEVERITY_THRESHOLD: u32 = 100;
+
+/// 組み込みの語リスト（日本語）と重み
+const BUILTIN_WEIGHTS_JA: &[(&str, u32)] = &[
+    ("死ね", 5),
+    ("しね", 5),
+    ("殺すぞ", 5),
+    ("ころすぞ", 5),
+    ("きもい", 2),
+    ("キモい", 2),
+    ("うざい", 2),
+    ("ウザい", 2),
+    ("くそ", 1),
+    ("クソ", 1),
+];
+
+/// 組み込みの語リスト（英語）と重み
+const BUILTIN_WEIGHTS_EN: &[(&str, u32)] = &[
+    ("fuck", 3),
+    ("fucking", 3),
+    ("shit", 2),
+    ("bitch", 4),
+    ("bastard", 3),
+    ("asshole", 4),
+    ("dick", 2),
+    ("cunt", 5),
+];
+
+/// コメントの不適切度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// 問題なし
+    #[default]
+    Clean,
+    /// 軽度（オーバーレイはそのまま表示）
+    Mild,
+    /// 重度（オーバーレイは本文・翻訳をぼかし表示にする）
+    Strong,
+}
+
+/// 重み付きの語
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeverityWord {
+    pub word: String,
+    /// 重み（0〜10、0は判定に使わない）
+    pub weight: u32,
+}
+
+fn default_mild_threshold() -> u32 {
+    1
+}
+
+fn default_strong_threshold() -> u32 {
+    5
+}
+
+/// 不適切度判定の設定
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeverityConfig {
+    /// 判定を有効にするか
+    pub enabled: bool,
+    /// 有効にする組み込みの語リストの言語
+    #[serde(default)]
+    pub builtin_locales: Vec<LabelLocale>,
+    /// 追加の語と重み（空白のみの語は無視）
+    #[serde(default)]
+    pub words: Vec<SeverityWord>,
+    /// `mild`と判定する重みの合計（デフォルト: 1）
+    #[serde(default = "default_mild_threshold")]
+    pub mild_threshold: u32,
+    /// `strong`と判定する重みの合計（デフォルト: 5）
+    #[serde(default = "default_strong_threshold")]
+    pub strong_threshold: u32,
+}
+
+impl Default for SeverityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            builtin_locales: Vec::new(),
+            words: Vec::new(),
+            mild_threshold: default_mild_threshold(),
+            strong_threshold: default_strong_threshold(),
+        }
+    }
+}
+
+/// 不適切度の判定器
+#[derive(Debug, Clone, Default)]
+pub struct SeverityClassifier {
+    /// 全ての語をまとめた正規表現（語がない・無効の場合はNone）
+    pattern: Option<Regex>,
+    /// 語（小文字化済み）ごとの重み
+    weights: HashMap<String, u32>,
+    mild_threshold: u32,
+    strong_threshold: u32,
+}
+
+impl SeverityClassifier {
+    /// 設定から判定器を作成
+    ///
+    /// ## 入力検証
+    /// - 追加の語: 500件以下、重みは0〜10
+    /// - 閾値: 1 ≤ mild ≤ strong ≤ 100
+    pub fn new(config: &SeverityConfig) -> Result<Self, String> {
+        if config.words.len() > MAX_SEVERITY_WORDS {
+            return Err(format!(
+                "重み付きの語は{}件以下で指定してください: {}件",
+                MAX_SEVERITY_WORDS,
+                config.words.len()
+            ));
+        }
+        if let Some(word) = config.words.iter().find(|w| w.weight > MAX_SEVERITY_WEIGHT) {
+            return Err(format!(
+                "語の重みは0〜{}で指定してください: {} ({})",
+                MAX_SEVERITY_WEIGHT, word.word, word.weight
+            ));
+        }
+        if config.mild_threshold == 0
+            || config.mild_threshold > config.strong_threshold
+            || config.strong_threshold > MAX_SEVERITY_THRESHOLD
+        {
+            return Err(format!(
+                "閾値は1 ≤ mild ≤ strong ≤ {}で指定してください: mild={}, strong={}",
+                MAX_SEVERITY_THRESHOLD, config.mild_threshold, config.strong_threshold
+            ));
+        }
+        if !config.enabled {
+            return Ok(Self::default());
+        }
+
+        // 組み込みの語の後に追加の語を入れ、同じ語は追加した重みで上書きする
+        let builtin = config
+            .builtin_locales
+            .iter()
+            .flat_map(|locale| match locale {
+                LabelLocale::Ja => BUILTIN_WEIGHTS_JA,
+                LabelLocale::En => BUILTIN_WEIGHTS_EN,
+            })
+            .map(|(word, weight)| (word.to_string(), *weight));
+        let custom = config.words.iter().map(|w| (w.word.trim().to_string(), w.weight));
+        let mut weights = HashMap::new();
+        for (word, weight) in builtin.chain(custom).filter(|(word, _)| !word.is_empty()) {
+            weights.insert(word.to_lowercase(), weight);
+        }
+        weights.retain(|_, weight| *weight > 0);
+        if weights.is_empty() {
+            return Ok(Self::default());
+        }
+
+        // 長い語を優先して一致させる（"fucking"を"fuck"より先に）
+        let mut words: Vec<&str> = weights.keys().map(String::as_str).collect();
+        words.sort_by_key(|w| (std::cmp::Reverse(w.chars().count()), *w));
+        let alternation = words
+            .iter()
+            .map(|w| regex::escape(w))
+            .collect::<Vec<_>>()
+            .join("|");
+        let pattern = Regex::new(&format!("(?i){}", alternation))
+            .map_err(|e| format!("重み付きの語が不正です: {}", e))?;
+        Ok(Self {
+            pattern: Some(pattern),
+            weights,
+            mild_threshold: config.mild_threshold,
+            strong_threshold: config.strong_threshold,
+        })
+    }
+
+    /// 本文に一致した語の重みの合計
+    pub fn score(&self, text: &str) -> u32 {
+        let Some(pattern) = &self.pattern else {
+            return 0;
+        };
+        pattern
+            .find_iter(text)
+            .filter(|matched| !is_within_word(text, matched.start(), matched.end()))
+            .filter_map(|matched| self.weights.get(&matched.as_str().to_lowercase()))
+            .fold(0u32, |total, weight| total.saturating_add(*weight))
+    }
+
+    /// 本文の不適切度
+    pub fn classify(&self, text: &str) -> Severity {
+        if self.pattern.is_none() {
+            return Severity::Clean;
+        }
+        match self.score(text) {
+            score if score >= self.strong_threshold => Severity::Strong,
+            score if score >= self.mild_threshold => Severity::Mild,
+            _ => Severity::Clean,
+        }
+    }
+}
+
+/// 現在の不適切度の判定器
+/// 起動時にDBから読み込み、設定コマンドで更新される
+static SEVERITY_CLASSIFIER: Lazy<RwLock<Arc<SeverityClassifier>>> =
+    Lazy::new(|| RwLock::new(Arc::new(SeverityClassifier::default())));
+
+/// 現在の判定器を取得
+pub fn current_classifier() -> Arc<SeverityClassifier> {
+    SEVERITY_CLASSIFIER
+        .read()
+        .map(|classifier| Arc::clone(&classifier))
+        .unwrap_or_default()
+}
+
+/// 判定器を更新（以降に取得したコメントから適用）
+pub fn set_classifier(classifier: SeverityClassifier) {
+    match SEVERITY_CLASSIFIER.write() {
+        Ok(mut current) => *current = Arc::new(classifier),
+        Err(e) => log::error!("Failed to update severity classifier: {}", e),
+    }
+}
+
+/// コメントの不適切度を判定（コメントID → 不適切度、`clean`以外のみ）
+///
+/// 不適切語マスクを適用する前のコメントを渡す（[`super::comment_filter::queue_save_and_filter`]の前に呼び出す）
+pub fn classify_comments(messages: &[ChatMessage]) -> HashMap<String, Severity> {
+    let classifier = current_classifier();
+    if classifier.pattern.is_none() {
+        return HashMap::new();
+    }
+    messages
+        .iter()
+        .filter_map(|message| match classifier.classify(&message.message) {
+            Severity::Clean => None,
+            severity => Some((message.id.clone(), severity)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(word: &str, weight: u32) -> SeverityWord {
+        SeverityWord {
+            word: word.to_string(),
+            weight,
+        }
+    }
+
+    fn classifier(builtin_locales: Vec<LabelLocale>, words: Vec<SeverityWord>) -> SeverityClassifier {
+        SeverityClassifier::new(&SeverityConfig {
+            enabled: true,
+            builtin_locales,
+            words,
+            ..SeverityConfig::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_classify_by_total_weight() {
+        let classifier = classifier(vec![LabelLocale::Ja, LabelLocale::En], Vec::new());
+
+        assert_eq!(classifier.classify("こんにちは！"), Severity::Clean);
+        assert_eq!(classifier.classify("クソゲーで草"), Severity::Mild);
+        assert_eq!(classifier.classify("お前もう死ね"), Severity::Strong);
+        // 出現ごとに合計する（shit 2 + FUCKING 3）
+        assert_eq!(classifier.score("shit, FUCKING shit"), 7);
+        assert_eq!(classifier.classify("shit, FUCKING shit"), Severity::Strong);
+        // 英単語の途中には一致しない
+        assert_eq!(classifier.classify("Scunthorpe class dickens"), Severity::Clean);
+    }
+
+    #[test]
+    fn test_custom_weights_override_builtin() {
+        let classifier = classifier(
+            vec![LabelLocale::Ja],
+            vec![word("くそ", 0), word("クソ", 0), word("ばーか", 3), word("  ", 10)],
+        );
+
+        // 重み0で組み込みの語を無効化
+        assert_eq!(classifier.classify("クソゲー"), Severity::Clean);
+        assert_eq!(classifier.score("ばーかばーか"), 6);
+        assert_eq!(classifier.classify("ばーかばーか"), Severity::Strong);
+
+        // 無効の場合は判定しない
+        let disabled = SeverityClassifier::new(&SeverityConfig {
+            builtin_locales: vec![LabelLocale::Ja],
+            ..SeverityConfig::default()
+        })
+        .unwrap();
+        assert_eq!(disabled.classify("死ね"), Severity::Clean);
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let config = |words: Vec<SeverityWord>, mild_threshold: u32, strong_threshold: u32| SeverityConfig {
+            enabled: true,
+            builtin_locales: Vec::new(),
+            words,
+            mild_threshold,
+            strong_threshold,
+        };
+        assert!(SeverityClassifier::new(&config(vec![word("spam", MAX_SEVERITY_WEIGHT + 1)], 1, 5)).is_err());
+        assert!(SeverityClassifier::new(&config(Vec::new(), 0, 5)).is_err());
+        assert!(SeverityClassifier::new(&config(Vec::new(), 6, 5)).is_err());
+        assert!(SeverityClassifier::new(&config(Vec::new(), 1, MAX_SEVERITY_THRESHOLD + 1)).is_err());
+        assert!(SeverityClassifier::new(&config(Vec::new(), 3, 3)).is_ok());
+    }
+}
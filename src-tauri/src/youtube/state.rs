@@ -1,6 +1,50 @@
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
 use std::time::Duration;
 
+/// 1日のクォータ予算のデフォルト値（YouTube Data APIの標準割り当て）
+pub const DEFAULT_DAILY_QUOTA_BUDGET: u64 = 10_000;
+
+/// クォータ残量に応じて延ばしたポーリング間隔の上限（ミリ秒）
+const MAX_THROTTLED_POLLING_INTERVAL_MILLIS: u64 = 60_000;
+
+/// 1日のクォータ予算
+/// 起動時にDBから読み込み、設定コマンドで更新される
+static DAILY_QUOTA_BUDGET: Lazy<RwLock<u64>> =
+    Lazy::new(|| RwLock::new(DEFAULT_DAILY_QUOTA_BUDGET));
+
+/// 現在の1日のクォータ予算を取得
+pub fn daily_quota_budget() -> u64 {
+    DAILY_QUOTA_BUDGET
+        .read()
+        .map(|budget| *budget)
+        .unwrap_or(DEFAULT_DAILY_QUOTA_BUDGET)
+}
+
+/// 1日のクォータ予算を更新（実行中のポーラーにも次回のポーリングから適用）
+pub fn set_daily_quota_budget(budget: u64) {
+    match DAILY_QUOTA_BUDGET.write() {
+        Ok(mut current) => *current = budget,
+        Err(e) => log::error!("Failed to update daily quota budget: {}", e),
+    }
+}
+
+/// 残りクォータに応じたポーリング間隔の倍率
+///
+/// - 残りが予算の20%未満: 2倍
+/// - 残りが予算の5%未満: 4倍
+fn quota_throttle_multiplier(remaining_quota: i64, budget: u64) -> u64 {
+    let budget = budget as i64;
+    if remaining_quota < budget / 20 {
+        4
+    } else if remaining_quota < budget / 5 {
+        2
+    } else {
+        1
+    }
+}
+
 /// ポーリング状態を管理する構造体
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PollingState {
@@ -46,10 +90,17 @@ impl PollingState {
 
     /// ポーリング間隔をDurationとして取得
     ///
-    /// 最低5秒を保証
+    /// 最低5秒を保証。残りクォータが少ない場合は間隔を延ばす（[`Self::quota_throttle_multiplier`]、最大60秒）
     pub fn polling_interval(&self) -> Duration {
         let millis = self.polling_interval_millis.max(5000);
-        Duration::from_millis(millis)
+        let throttled = (millis * self.quota_throttle_multiplier())
+            .min(MAX_THROTTLED_POLLING_INTERVAL_MILLIS.max(millis));
+        Duration::from_millis(throttled)
+    }
+
+    /// 残りクォータに応じたポーリング間隔の倍率（1は通常どおり）
+    pub fn quota_throttle_multiplier(&self) -> u64 {
+        quota_throttle_multiplier(self.estimated_remaining_quota(), daily_quota_budget())
     }
 
     /// 状態を更新（API レスポンス受信後に呼び出す）
@@ -72,10 +123,9 @@ impl PollingState {
         self.quota_used = 0;
     }
 
-    /// 残りクォータを推定（デフォルト10,000 units）
+    /// 残りクォータを推定（1日のクォータ予算から算出、デフォルト10,000 units）
     pub fn estimated_remaining_quota(&self) -> i64 {
-        const DAILY_QUOTA: i64 = 10_000;
-        DAILY_QUOTA - self.quota_used as i64
+        daily_quota_budget() as i64 - self.quota_used as i64
     }
 
     /// あと何回ポーリングできるかを推定
@@ -178,4 +228,33 @@ mod tests {
         assert_eq!(state.polling_interval_millis, 5000);
         assert_eq!(state.polling_interval(), Duration::from_secs(5));
     }
+    #[test]
+    fn test_polling_interval_grows_when_quota_is_low() {
+        // 残り2000 units以上は通常どおり
+        let state = PollingState::with_saved_state("test-chat-id".to_string(), None, 8000, None);
+        assert_eq!(state.quota_throttle_multiplier(), 1);
+        assert_eq!(state.polling_interval(), Duration::from_secs(5));
+
+        // 残り2000 units未満で2倍
+        let state = PollingState::with_saved_state("test-chat-id".to_string(), None, 8500, None);
+        assert_eq!(state.quota_throttle_multiplier(), 2);
+        assert_eq!(state.polling_interval(), Duration::from_secs(10));
+
+        // 残り500 units未満で4倍（上限60秒）
+        let state =
+            PollingState::with_saved_state("test-chat-id".to_string(), None, 9600, Some(20000));
+        assert_eq!(state.quota_throttle_multiplier(), 4);
+        assert_eq!(state.polling_interval(), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_quota_throttle_multiplier_follows_budget() {
+        assert_eq!(quota_throttle_multiplier(2000, 10_000), 1);
+        assert_eq!(quota_throttle_multiplier(1999, 10_000), 2);
+        assert_eq!(quota_throttle_multiplier(499, 10_000), 4);
+        assert_eq!(quota_throttle_multiplier(-5, 10_000), 4);
+
+        // 予算を増やすと同じ残量でも早めに延ばす
+        assert_eq!(quota_throttle_multiplier(9000, 50_000), 2);
+    }
 }
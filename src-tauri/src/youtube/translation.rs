@@ -0,0 +1,430 @@
+//! コメントの翻訳
+//!
+//! 有効時は、翻訳先言語以外で書かれたコメントを翻訳APIで翻訳し、`comment:add`の配信後に
+//! `comment:translation`として配信する。オーバーレイはコメントの本文の下に翻訳を表示する。
+//! コメント本文が外部に送信されるため、デフォルトは無効（オプトイン）。
+//!
+//! - 翻訳APIの応答は待たずにコメントを配信する（翻訳は別タスクで取得し、後から配信）
+//! - ひらがな・カタカナ・ハングルを含むコメントは文字種から言語を判定し、翻訳先と同じならAPIを呼ばない
+//! - それ以外はAPIが検出した言語が翻訳先と同じ場合に翻訳なしとする
+//! - 翻訳結果は本文ごとにキャッシュし、同じ本文は再翻訳しない
+//! - APIエラー時は翻訳を配信しない（キャッシュしないため次回は再試行する）
+//!
+//! ## 翻訳APIの接続先
+//! デフォルトはGoogle翻訳のWeb用エンドポイント（`translate_a/single?client=gtx`）。
+//! 公開・文書化されたAPIではないため、予告なくブロック・レート制限・仕様変更される可能性がある。
+//! 設定の`apiUrl`で、同じ形式のクエリ・応答に対応した接続先（プロキシ等）に変更できる。
+
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use super::types::{ChatMessage, MessageType};
+use crate::server::types::{CommentTranslationPayload, ServerState, WsMessage};
+
+/// 翻訳先言語のデフォルト
+pub const DEFAULT_TARGET_LANGUAGE: &str = "ja";
+
+/// 翻訳APIのURLのデフォルト
+pub const DEFAULT_TRANSLATE_API_URL: &str = "https://translate.googleapis.com/translate_a/single";
+
+/// 翻訳APIのタイムアウト（配信の遅れを抑えるため短めにする）
+const TRANSLATION_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 翻訳結果のキャッシュ件数
+const TRANSLATION_CACHE_SIZE: usize = 1000;
+
+/// 1回の取得で翻訳APIを呼ぶ本文の上限（超えた分は翻訳なしで配信）
+const MAX_TRANSLATIONS_PER_BATCH: usize = 20;
+
+/// コメント翻訳の設定
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationSettings {
+    /// 翻訳を有効にするか（デフォルト: 無効）
+    pub enabled: bool,
+    /// 翻訳先言語（"ja" "en" "zh-TW" 等の言語コード）
+    pub target_language: String,
+    /// 翻訳APIのURL（デフォルト: Google翻訳のWeb用エンドポイント）
+    #[serde(default = "default_api_url")]
+    pub api_url: String,
+}
+
+fn default_api_url() -> String {
+    DEFAULT_TRANSLATE_API_URL.to_string()
+}
+
+impl Default for TranslationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_language: DEFAULT_TARGET_LANGUAGE.to_string(),
+            api_url: default_api_url(),
+        }
+    }
+}
+
+impl TranslationSettings {
+    /// 設定を検証
+    ///
+    /// ## 入力検証
+    /// - 翻訳先言語: 2〜3文字の英小文字、または`-`に続けて2〜4文字の英数字の地域・文字種（"zh-TW"等）
+    /// - 翻訳APIのURL: http(s)のURL
+    pub fn validate(&self) -> Result<(), String> {
+        let mut parts = self.target_language.splitn(2, '-');
+        let language = parts.next().unwrap_or_default();
+        let valid_language = (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_lowercase());
+        let valid_region = parts
+            .next()
+            .map_or(true, |region| (2..=4).contains(&region.len()) && region.chars().all(|c| c.is_ascii_alphanumeric()));
+        if !valid_language || !valid_region {
+            return Err(format!("翻訳先の言語コードが不正です: {}", self.target_language));
+        }
+        let valid_url = reqwest::Url::parse(&self.api_url)
+            .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.host_str().is_some());
+        if !valid_url {
+            return Err(format!("翻訳APIのURLが不正です: {}", self.api_url));
+        }
+        Ok(())
+    }
+}
+
+/// 現在の設定
+/// 起動時にDBから読み込み、設定コマンドで更新される
+static SETTINGS: Lazy<RwLock<TranslationSettings>> = Lazy::new(|| RwLock::new(TranslationSettings::default()));
+
+/// 翻訳結果のキャッシュ（翻訳先言語と本文 → 翻訳。翻訳不要だった本文はNone）
+type TranslationCache = LruCache<(String, String), Option<String>>;
+
+static CACHE: Lazy<Mutex<TranslationCache>> =
+    Lazy::new(|| Mutex::new(LruCache::new(NonZeroUsize::new(TRANSLATION_CACHE_SIZE).unwrap())));
+
+static CLIENT: Lazy<TranslationClient> = Lazy::new(TranslationClient::new);
+
+/// 設定を更新
+pub fn set_settings(settings: TranslationSettings) {
+    match SETTINGS.write() {
+        Ok(mut current) => *current = settings,
+        Err(e) => log::error!("Failed to update translation settings: {}", e),
+    }
+}
+
+/// 現在の設定を取得
+pub fn settings() -> TranslationSettings {
+    match SETTINGS.read() {
+        Ok(settings) => settings.clone(),
+        Err(e) => {
+            log::error!("Failed to read translation settings: {}", e);
+            TranslationSettings::default()
+        }
+    }
+}
+
+/// 言語コードの主言語部分（"zh-TW" → "zh"）
+fn primary_language(code: &str) -> &str {
+    code.split(['-', '_']).next().unwrap_or(code)
+}
+
+/// 文字種から判定できる言語（ひらがな・カタカナ → ja、ハングル → ko）
+///
+/// 漢字のみ・ラテン文字等は判定できないためNone（APIの検出に任せる）
+fn detect_script_language(text: &str) -> Option<&'static str> {
+    if text
+        .chars()
+        .any(|c| matches!(c, '\u{3041}'..='\u{309F}' | '\u{30A1}'..='\u{30FA}'))
+    {
+        return Some("ja");
+    }
+    if text
+        .chars()
+        .any(|c| matches!(c, '\u{AC00}'..='\u{D7A3}' | '\u{1100}'..='\u{11FF}' | '\u{3131}'..='\u{318E}'))
+    {
+        return Some("ko");
+    }
+    None
+}
+
+/// 翻訳APIに渡すコメント本文（翻訳の対象外の場合はNone）
+///
+/// 通常のコメントとスパチャのメッセージが対象。文字を含まない本文（絵文字・数字のみ等）と、
+/// 文字種から翻訳先言語と判定できる本文は対象外
+fn translatable_text<'a>(message: &'a ChatMessage, target_language: &str) -> Option<&'a str> {
+    if !matches!(message.message_type, MessageType::Text | MessageType::SuperChat { .. }) {
+        return None;
+    }
+    let text = message.message.trim();
+    if !text.chars().any(char::is_alphabetic) {
+        return None;
+    }
+    if detect_script_language(text) == Some(primary_language(target_language)) {
+        return None;
+    }
+    Some(text)
+}
+
+/// 翻訳APIのレスポンスから翻訳を取り出す
+///
+/// レスポンスは`[[["翻訳", "原文", ...], ...], null, "検出した言語", ...]`の形式。
+/// 検出した言語が翻訳先と同じ場合・翻訳が原文と同じ場合はNone
+fn parse_response(value: &serde_json::Value, text: &str, target_language: &str) -> Result<Option<String>, String> {
+    let segments = value
+        .get(0)
+        .and_then(|segments| segments.as_array())
+        .ok_or_else(|| "Unexpected translation response".to_string())?;
+    let translated: String = segments
+        .iter()
+        .filter_map(|segment| segment.get(0).and_then(|part| part.as_str()))
+        .collect();
+
+    let source_language = value.get(2).and_then(|language| language.as_str());
+    if source_language.is_some_and(|source| primary_language(source).eq_ignore_ascii_case(primary_language(target_language))) {
+        return Ok(None);
+    }
+    let translated = translated.trim();
+    if translated.is_empty() || translated == text.trim() {
+        return Ok(None);
+    }
+    Ok(Some(translated.to_string()))
+}
+
+/// 翻訳APIクライアント
+pub struct TranslationClient {
+    client: Client,
+}
+
+impl TranslationClient {
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .timeout(TRANSLATION_TIMEOUT)
+            .build()
+            .expect("Failed to build HTTP client with timeout - this should never fail");
+        Self { client }
+    }
+
+    /// 本文を翻訳先言語に翻訳（翻訳不要と判定した場合はNone）
+    pub async fn translate(&self, api_url: &str, text: &str, target_language: &str) -> Result<Option<String>, String> {
+        let response = self
+            .client
+            .get(api_url)
+            .query(&[("client", "gtx"), ("sl", "auto"), ("tl", target_language), ("dt", "t"), ("q", text)])
+            .send()
+            .await
+            .map_err(|e| format!("Translation request failed: {}", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("Translation API error: {}", status));
+        }
+
+        let value: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse translation response: {}", e))?;
+        parse_response(&value, text, target_language)
+    }
+}
+
+impl Default for TranslationClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// コメントの翻訳を別タスクで取得し、`comment:translation`として配信（ブロードキャスト直後に呼び出す）
+///
+/// 翻訳APIの応答を待たずに戻るため、コメントの配信は遅れない。
+/// 無効時・翻訳不要・APIエラーのコメントは配信しない
+pub fn spawn_translations(server_state: &ServerState, messages: &[ChatMessage]) {
+    let settings = settings();
+    if !settings.enabled || messages.is_empty() {
+        return;
+    }
+    let server_state = Arc::clone(server_state);
+    let messages = messages.to_vec();
+    tokio::spawn(async move {
+        let translations = translate_with(&CLIENT, &settings, &messages).await;
+        if translations.is_empty() {
+            return;
+        }
+        // コメントの配信順に送る
+        let state_lock = server_state.read().await;
+        for message in &messages {
+            if let Some(translation) = translations.get(&message.id) {
+                state_lock
+                    .broadcast(WsMessage::CommentTranslation {
+                        payload: CommentTranslationPayload {
+                            id: message.id.clone(),
+                            translation: translation.clone(),
+                        },
+                    })
+                    .await;
+            }
+        }
+    });
+}
+
+/// コメントの翻訳を取得（コメントID → 翻訳）
+///
+/// 翻訳不要・APIエラーのコメントは含めない
+async fn translate_with(
+    client: &TranslationClient,
+    settings: &TranslationSettings,
+    messages: &[ChatMessage],
+) -> HashMap<String, String> {
+    let target_language = settings.target_language.as_str();
+    let cache_key = |text: &str| (target_language.to_string(), text.to_string());
+
+    // キャッシュにない本文を重複なく集める
+    let mut pending: Vec<&str> = Vec::new();
+    {
+        let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        for text in messages.iter().filter_map(|message| translatable_text(message, target_language)) {
+            if cache.get(&cache_key(text)).is_none() && !pending.contains(&text) {
+                pending.push(text);
+            }
+        }
+    }
+    if pending.len() > MAX_TRANSLATIONS_PER_BATCH {
+        log::debug!(
+            "Skipping translation of {} comments (limit {})",
+            pending.len() - MAX_TRANSLATIONS_PER_BATCH,
+            MAX_TRANSLATIONS_PER_BATCH
+        );
+        pending.truncate(MAX_TRANSLATIONS_PER_BATCH);
+    }
+
+    let results =
+        futures::future::join_all(pending.iter().map(|text| client.translate(&settings.api_url, text, target_language)))
+            .await;
+
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    for (text, result) in pending.iter().zip(results) {
+        match result {
+            Ok(translation) => {
+                cache.put(cache_key(text), translation);
+            }
+            Err(e) => log::warn!("Failed to translate comment: {}", e),
+        }
+    }
+
+    messages
+        .iter()
+        .filter_map(|message| {
+            let text = translatable_text(message, target_language)?;
+            let translation = cache.peek(&cache_key(text))?.clone()?;
+            Some((message.id.clone(), translation))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+
+    fn message(id: &str, text: &str) -> ChatMessage {
+        ChatMessage::test_message(id).with_text(text)
+    }
+
+    #[test]
+    fn test_translatable_text() {
+        assert_eq!(translatable_text(&message("c1", " Hello! "), "ja"), Some("Hello!"));
+        // 文字種から翻訳先言語と判定できる本文・文字を含まない本文は対象外
+        assert_eq!(translatable_text(&message("c2", "こんにちは"), "ja"), None);
+        assert_eq!(translatable_text(&message("c3", "8888 👏"), "ja"), None);
+        assert_eq!(translatable_text(&message("c4", "こんにちは"), "en"), Some("こんにちは"));
+        assert_eq!(translatable_text(&message("c5", "안녕하세요"), "ko-KR"), None);
+
+        let mut membership = message("c6", "Welcome");
+        membership.message_type = MessageType::Membership { level: "Member".to_string() };
+        assert_eq!(translatable_text(&membership, "ja"), None);
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let value = serde_json::json!([[["こんにちは", "Hello", null, null, 10]], null, "en"]);
+        assert_eq!(parse_response(&value, "Hello", "ja").unwrap(), Some("こんにちは".to_string()));
+
+        // 翻訳先と同じ言語・原文と同じ翻訳は翻訳なし
+        let value = serde_json::json!([[["草", "草", null, null, 10]], null, "ja"]);
+        assert_eq!(parse_response(&value, "草", "ja").unwrap(), None);
+        let value = serde_json::json!([[["GG", "GG", null, null, 10]], null, "en"]);
+        assert_eq!(parse_response(&value, "GG", "ja").unwrap(), None);
+
+        assert!(parse_response(&serde_json::json!({}), "Hello", "ja").is_err());
+    }
+
+    #[test]
+    fn test_validate_settings() {
+        let settings = |target_language: &str| TranslationSettings {
+            enabled: true,
+            target_language: target_language.to_string(),
+            ..Default::default()
+        };
+        assert!(settings("ja").validate().is_ok());
+        assert!(settings("zh-TW").validate().is_ok());
+        assert!(settings("").validate().is_err());
+        assert!(settings("JA").validate().is_err());
+        assert!(settings("ja&tl=en").validate().is_err());
+
+        let with_url = |api_url: &str| TranslationSettings {
+            api_url: api_url.to_string(),
+            ..settings("ja")
+        };
+        assert!(with_url("http://localhost:5000/translate_a/single").validate().is_ok());
+        assert!(with_url("ftp://example.com/translate").validate().is_err());
+        assert!(with_url("not a url").validate().is_err());
+
+        // 保存済みの設定にURLがない場合はデフォルトの接続先
+        let saved: TranslationSettings = serde_json::from_str(r#"{"enabled":true,"targetLanguage":"ja"}"#).unwrap();
+        assert_eq!(saved.api_url, DEFAULT_TRANSLATE_API_URL);
+    }
+
+    #[tokio::test]
+    async fn test_translate_with_caches_and_degrades_on_error() {
+        let mut server = Server::new_async().await;
+        let client = TranslationClient::new();
+        let settings = TranslationSettings {
+            enabled: true,
+            target_language: "xx".to_string(),
+            api_url: format!("{}/translate", server.url()),
+        };
+
+        let success = server
+            .mock("GET", "/translate")
+            .match_query(mockito::Matcher::UrlEncoded("q".into(), "Bonjour".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[[["Hello","Bonjour",null,null,10]],null,"fr"]"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let failure = server
+            .mock("GET", "/translate")
+            .match_query(mockito::Matcher::UrlEncoded("q".into(), "Hola".into()))
+            .with_status(500)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let messages = vec![message("c1", "Bonjour"), message("c2", "Bonjour"), message("c3", "Hola")];
+        let translations = translate_with(&client, &settings, &messages).await;
+        assert_eq!(translations.get("c1").map(String::as_str), Some("Hello"));
+        assert_eq!(translations.get("c2").map(String::as_str), Some("Hello"));
+        // APIエラーのコメントは翻訳なし
+        assert!(!translations.contains_key("c3"));
+
+        // 同じ本文はキャッシュから返し、エラーだった本文は再試行する
+        let translations = translate_with(&client, &settings, &[message("c4", "Bonjour"), message("c5", "Hola")]).await;
+        assert_eq!(translations.get("c4").map(String::as_str), Some("Hello"));
+        assert!(!translations.contains_key("c5"));
+
+        success.assert_async().await;
+        failure.assert_async().await;
+    }
+}
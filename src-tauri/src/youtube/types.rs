@@ -36,12 +36,110 @@ pub struct ChatMessage {
     pub is_moderator: bool,        // → isModerator
     pub is_member: bool,           // → isMember (isChatSponsor)
     pub is_verified: bool,         // → isVerified
+    /// 投稿者のバッジ種別（"owner" "moderator" "member" "verified"、InnerTubeの未知のバッジはアイコン種別を小文字で）
+    /// オーバーレイが独自のアイコンを表示するために使う
+    #[serde(default)]
+    pub badges: Vec<String>,
     pub message_type: MessageType, // → messageType
-    /// InnerTube API使用時のみ設定される構造化メッセージ（絵文字情報を含む）
+    /// 構造化メッセージ（絵文字情報を含む）
+    /// InnerTubeはrunsから、公式APIは本文のUnicode絵文字を分割して設定（絵文字がなければNone）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_runs: Option<Vec<MessageRun>>,
 }
 
+/// 投稿者バッジの種別（`ChatMessage::badges`の値）
+pub const BADGE_OWNER: &str = "owner";
+pub const BADGE_MODERATOR: &str = "moderator";
+pub const BADGE_MEMBER: &str = "member";
+pub const BADGE_VERIFIED: &str = "verified";
+
+/// 権限フラグからバッジ種別の一覧を作成（公式API・gRPC用。InnerTubeはバッジから直接取得する）
+pub fn badges_from_flags(is_owner: bool, is_moderator: bool, is_member: bool, is_verified: bool) -> Vec<String> {
+    [
+        (is_owner, BADGE_OWNER),
+        (is_moderator, BADGE_MODERATOR),
+        (is_member, BADGE_MEMBER),
+        (is_verified, BADGE_VERIFIED),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| *flag)
+    .map(|(_, badge)| badge.to_string())
+    .collect()
+}
+
+/// 投稿者名の表示色（オーバーレイのバッジ色と揃える）
+const AUTHOR_COLOR_OWNER: &str = "#fbbf24";
+const AUTHOR_COLOR_MODERATOR: &str = "#3b82f6";
+const AUTHOR_COLOR_MEMBER: &str = "#10b981";
+const AUTHOR_COLOR_DEFAULT: &str = "#ffffff";
+
+impl ChatMessage {
+    /// 投稿者の権限に応じた表示色を取得
+    ///
+    /// 優先順位: オーナー > モデレーター > メンバー > 一般
+    pub fn author_color(&self) -> &'static str {
+        if self.is_owner {
+            AUTHOR_COLOR_OWNER
+        } else if self.is_moderator {
+            AUTHOR_COLOR_MODERATOR
+        } else if self.is_member {
+            AUTHOR_COLOR_MEMBER
+        } else {
+            AUTHOR_COLOR_DEFAULT
+        }
+    }
+}
+
+/// テスト用のコメントを組み立てる
+///
+/// 既定値は一般の視聴者（"Viewer" / "UC_viewer"）の通常のテキストコメント（本文"hello"）。
+/// ここにない項目は構造体更新構文（`ChatMessage { is_member: true, ..ChatMessage::test_message("c1") }`）で変更する
+#[cfg(test)]
+impl ChatMessage {
+    pub(crate) fn test_message(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            message: "hello".to_string(),
+            author_name: "Viewer".to_string(),
+            author_channel_id: "UC_viewer".to_string(),
+            author_image_url: String::new(),
+            published_at: Utc::now(),
+            is_owner: false,
+            is_moderator: false,
+            is_member: false,
+            is_verified: false,
+            badges: Vec::new(),
+            message_type: MessageType::Text,
+            message_runs: None,
+        }
+    }
+
+    /// 本文を変更
+    pub(crate) fn with_text(mut self, text: &str) -> Self {
+        self.message = text.to_string();
+        self
+    }
+
+    /// メッセージ種別を変更
+    pub(crate) fn with_type(mut self, message_type: MessageType) -> Self {
+        self.message_type = message_type;
+        self
+    }
+
+    /// 投稿者名・チャンネルIDを変更
+    pub(crate) fn with_author(mut self, name: &str, channel_id: &str) -> Self {
+        self.author_name = name.to_string();
+        self.author_channel_id = channel_id.to_string();
+        self
+    }
+
+    /// 投稿時刻を変更
+    pub(crate) fn with_published_at(mut self, published_at: DateTime<Utc>) -> Self {
+        self.published_at = published_at;
+        self
+    }
+}
+
 /// メッセージのruns配列要素（テキストまたは絵文字）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -80,9 +178,29 @@ pub enum MessageType {
     #[serde(rename = "text")]
     Text,
     #[serde(rename = "superChat")]
-    SuperChat { amount: String, currency: String },
+    SuperChat {
+        amount: String,
+        currency: String,
+        /// 金額（マイクロ単位）。公式APIのみ取得可能で、InnerTube経由ではNone
+        #[serde(rename = "amountMicros", default, skip_serializing_if = "Option::is_none")]
+        amount_micros: Option<u64>,
+    },
     #[serde(rename = "superSticker")]
-    SuperSticker { sticker_id: String },
+    SuperSticker {
+        sticker_id: String,
+        /// 金額表示文字列（金額対応前に保存されたデータでは空）
+        #[serde(default)]
+        amount: String,
+        /// 通貨コード
+        #[serde(default)]
+        currency: String,
+        /// 金額（マイクロ単位）。公式APIのみ取得可能で、InnerTube経由ではNone
+        #[serde(rename = "amountMicros", default, skip_serializing_if = "Option::is_none")]
+        amount_micros: Option<u64>,
+        /// ステッカー画像URL（InnerTube経由のみ）
+        #[serde(rename = "imageUrl", default, skip_serializing_if = "Option::is_none")]
+        image_url: Option<String>,
+    },
     #[serde(rename = "membership")]
     Membership { level: String },
     #[serde(rename = "membershipGift")]
@@ -202,6 +320,15 @@ pub struct LiveStreamingDetails {
     /// 同時視聴者数（配信中のみ）
     #[serde(rename = "concurrentViewers")]
     pub concurrent_viewers: Option<String>,
+    /// 実際の配信開始時刻（RFC3339、開始前はなし）
+    #[serde(rename = "actualStartTime")]
+    pub actual_start_time: Option<String>,
+    /// 実際の配信終了時刻（RFC3339、終了前はなし）
+    #[serde(rename = "actualEndTime")]
+    pub actual_end_time: Option<String>,
+    /// 予約された開始時刻（RFC3339）
+    #[serde(rename = "scheduledStartTime")]
+    pub scheduled_start_time: Option<String>,
 }
 
 /// 動画統計情報
@@ -232,6 +359,47 @@ pub struct LiveStreamStats {
     pub view_count: Option<i64>,
 }
 
+/// 配信の経過時間（「配信開始から1:23:45」表示用）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamUptime {
+    /// 実際の配信開始時刻（RFC3339、未開始の場合はNone）
+    pub started_at: Option<String>,
+    /// 予約された開始時刻（RFC3339）
+    pub scheduled_start_time: Option<String>,
+    /// 配信開始からの経過秒数（未開始の場合はNone、終了済みの場合は配信時間）
+    pub uptime_seconds: Option<u64>,
+    /// 配信が終了しているか
+    pub ended: bool,
+}
+
+impl StreamUptime {
+    /// liveStreamingDetailsから経過時間を計算
+    ///
+    /// 時刻をパースできない場合は未開始として扱う
+    pub fn from_details(details: Option<&LiveStreamingDetails>, now: DateTime<Utc>) -> Self {
+        let parse = |time: Option<&String>| {
+            time.and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.with_timezone(&Utc))
+        };
+        let started_at = details.and_then(|d| parse(d.actual_start_time.as_ref()));
+        let ended_at = details.and_then(|d| parse(d.actual_end_time.as_ref()));
+
+        let uptime_seconds = started_at.map(|start| {
+            let end = ended_at.unwrap_or(now);
+            // 時計のずれで負にならないよう0で下限
+            (end - start).num_seconds().max(0) as u64
+        });
+
+        Self {
+            started_at: started_at.map(|t| t.to_rfc3339()),
+            scheduled_start_time: details.and_then(|d| d.scheduled_start_time.clone()),
+            uptime_seconds,
+            ended: started_at.is_some() && ended_at.is_some(),
+        }
+    }
+}
+
 /// YouTube APIのメッセージタイプを解析してMessageTypeに変換
 pub fn parse_message_type(snippet: &MessageSnippet) -> MessageType {
     match snippet.message_type.as_str() {
@@ -241,6 +409,8 @@ pub fn parse_message_type(snippet: &MessageSnippet) -> MessageType {
                 MessageType::SuperChat {
                     amount: details.amount_display_string.clone(),
                     currency: details.currency.clone(),
+                    // 0は金額未設定とみなし、表示文字列からのパースにフォールバック
+                    amount_micros: Some(details.amount_micros).filter(|micros| *micros > 0),
                 }
             } else {
                 log::warn!(
@@ -256,28 +426,38 @@ pub fn parse_message_type(snippet: &MessageSnippet) -> MessageType {
                     .as_ref()
                     .map(|m| m.sticker_id.clone())
                     .unwrap_or_default();
-                MessageType::SuperSticker { sticker_id }
+                MessageType::SuperSticker {
+                    sticker_id,
+                    amount: details.amount_display_string.clone(),
+                    currency: details.currency.clone(),
+                    amount_micros: Some(details.amount_micros).filter(|micros| *micros > 0),
+                    image_url: None,
+                }
             } else {
                 log::warn!(
                     "superStickerEvent without superStickerDetails, using empty sticker_id"
                 );
                 MessageType::SuperSticker {
                     sticker_id: String::new(),
+                    amount: String::new(),
+                    currency: String::new(),
+                    amount_micros: None,
+                    image_url: None,
                 }
             }
         }
         "newSponsorEvent" => {
             // YouTube APIではnewSponsorEventにメンバーシップレベル情報は含まれない
             // レベル情報は別途memberships APIで取得する必要があるが、
-            // 現時点では空文字列で対応
+            // 現時点では設定言語の「新規メンバー」で対応
             MessageType::Membership {
-                level: String::new(),
+                level: super::labels::current_locale().new_member().to_string(),
             }
         }
         "memberMilestoneChatEvent" => {
             // メンバー継続のマイルストーンイベント
             MessageType::Membership {
-                level: "milestone".to_string(),
+                level: super::labels::current_locale().member_milestone().to_string(),
             }
         }
         "membershipGiftingEvent" => {
@@ -302,3 +482,64 @@ pub fn parse_message_type(snippet: &MessageSnippet) -> MessageType {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn superchat_snippet(amount_micros: &str) -> MessageSnippet {
+        serde_json::from_value(serde_json::json!({
+            "type": "superChatEvent",
+            "publishedAt": "2025-01-01T00:00:00Z",
+            "displayMessage": "テスト",
+            "superChatDetails": {
+                "amountDisplayString": "KWD 1.500",
+                "currency": "KWD",
+                "amountMicros": amount_micros
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_message_type_keeps_amount_micros() {
+        match parse_message_type(&superchat_snippet("1500000")) {
+            MessageType::SuperChat {
+                amount,
+                currency,
+                amount_micros,
+            } => {
+                assert_eq!(amount, "KWD 1.500");
+                assert_eq!(currency, "KWD");
+                assert_eq!(amount_micros, Some(1_500_000));
+            }
+            other => panic!("Expected SuperChat, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_message_type_zero_amount_micros_is_none() {
+        match parse_message_type(&superchat_snippet("0")) {
+            MessageType::SuperChat { amount_micros, .. } => assert_eq!(amount_micros, None),
+            other => panic!("Expected SuperChat, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_superchat_message_type_serde_without_amount_micros() {
+        // amountMicros導入前に保存されたmessage_dataも読み込める
+        let parsed: MessageType =
+            serde_json::from_str(r#"{"type":"superChat","amount":"¥500","currency":"JPY"}"#)
+                .unwrap();
+        assert!(matches!(
+            parsed,
+            MessageType::SuperChat {
+                amount_micros: None,
+                ..
+            }
+        ));
+
+        // Noneはシリアライズ時に省略
+        let json = serde_json::to_string(&parsed).unwrap();
+        assert!(!json.contains("amountMicros"));
+    }
+}
@@ -10,27 +10,32 @@
 //! 3. SQLite保存 → コメントログ
 
 use super::api_key_manager::get_api_key_manager;
-use super::backoff::ExponentialBackoff;
-use super::db::save_comments_to_db;
+use super::backoff::{ExponentialBackoff, PollIntervalBackoff};
+use super::channel_ban::without_banned;
+use super::chat_settings::CHAT_SETTINGS;
+use super::comment_filter::queue_save_and_filter;
+use super::first_time::detect_first_time;
+use super::severity::classify_comments;
+use super::translation::spawn_translations;
 use super::errors::YouTubeError;
 use super::grpc::GrpcPoller;
 use super::innertube::InnerTubeClient;
+use super::dedupe::SeenMessageIds;
 use super::poller::{ChatPoller, PollingEvent};
-use super::types::ChatMessage;
 use crate::commands::youtube::ApiMode;
+use crate::membership::{create_membership_payload, show_membership};
 use crate::server::types::WsMessage;
 use crate::server::WebSocketState;
-use crate::superchat::{broadcast_superchat, create_superchat_payload, schedule_superchat_removal};
+use crate::superchat::{create_superchat_payload, enqueue_superchat, record_totals};
 use sqlx::SqlitePool;
-use std::collections::{HashSet, VecDeque};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tauri::async_runtime::JoinHandle;
 use tauri::{AppHandle, Emitter};
 use tokio::sync::{Mutex, RwLock};
 
-/// 重複排除用のメッセージIDの最大保持数
-const MAX_SEEN_IDS: usize = 10000;
+/// 一時停止中に再開・停止を確認する間隔
+const PAUSE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
 
 /// 統合ポーラー
 ///
@@ -41,6 +46,8 @@ pub struct UnifiedPoller {
     mode: Arc<Mutex<Option<ApiMode>>>,
     /// 実行中フラグ
     running: Arc<AtomicBool>,
+    /// 一時停止フラグ（InnerTubeモードのループが参照）
+    paused: Arc<AtomicBool>,
     /// ポーリングタスクハンドル
     task_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
     /// gRPCポーラー（gRPCモード時のみ使用）
@@ -55,6 +62,7 @@ impl UnifiedPoller {
         Self {
             mode: Arc::new(Mutex::new(None)),
             running: Arc::new(AtomicBool::new(false)),
+            paused: Arc::new(AtomicBool::new(false)),
             task_handle: Arc::new(Mutex::new(None)),
             grpc_poller: Arc::new(Mutex::new(None)),
             official_poller: Arc::new(Mutex::new(None)),
@@ -71,9 +79,55 @@ impl UnifiedPoller {
         self.running.load(Ordering::SeqCst)
     }
 
+    /// 一時停止中かどうか
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// ポーリングを一時停止（取得状態は保持）
+    ///
+    /// gRPCはサーバーストリーミングのため一時停止に対応しない
+    pub async fn pause(&self) -> Result<(), YouTubeError> {
+        self.set_paused(true).await
+    }
+
+    /// 一時停止を解除
+    pub async fn resume(&self) -> Result<(), YouTubeError> {
+        self.set_paused(false).await
+    }
+
+    async fn set_paused(&self, paused: bool) -> Result<(), YouTubeError> {
+        if !self.is_running() {
+            return Err(YouTubeError::ApiError("Polling is not running".to_string()));
+        }
+
+        match *self.mode.lock().await {
+            Some(ApiMode::Grpc) => {
+                return Err(YouTubeError::ApiError(
+                    "gRPCモードは一時停止に対応していません".to_string(),
+                ));
+            }
+            Some(ApiMode::Official) => {
+                if let Some(poller) = self.official_poller.lock().await.as_ref() {
+                    if paused {
+                        poller.pause();
+                    } else {
+                        poller.resume();
+                    }
+                }
+            }
+            Some(ApiMode::InnerTube) | None => {}
+        }
+
+        self.paused.store(paused, Ordering::SeqCst);
+        log::info!("Unified poller {}", if paused { "paused" } else { "resumed" });
+        Ok(())
+    }
+
     /// ポーリングを停止
     pub async fn stop(&self) {
         self.running.store(false, Ordering::SeqCst);
+        self.paused.store(false, Ordering::SeqCst);
 
         // gRPCポーラーを停止
         if let Some(mut poller) = self.grpc_poller.lock().await.take() {
@@ -94,6 +148,9 @@ impl UnifiedPoller {
         // モードをリセット
         *self.mode.lock().await = None;
 
+        // 停止した配信の絵文字キャッシュはプレビューに使わない
+        super::innertube::emoji_cache::set_active_emoji_cache(None);
+
         log::info!("Unified poller stopped");
     }
 
@@ -111,12 +168,17 @@ impl UnifiedPoller {
         self.running.store(true, Ordering::SeqCst);
 
         let running = Arc::clone(&self.running);
+        let paused = Arc::clone(&self.paused);
+        server_state.read().await.set_polling_status(Some(ApiMode::InnerTube)).await;
 
         let handle = tauri::async_runtime::spawn(async move {
-            if let Err(e) = run_innertube_loop(video_id, running.clone(), app_handle, db_pool, server_state).await {
+            let status_server = Arc::clone(&server_state);
+            if let Err(e) = run_innertube_loop(video_id, running.clone(), paused, app_handle, db_pool, server_state).await {
                 log::error!("InnerTube polling error: {:?}", e);
             }
             running.store(false, Ordering::SeqCst);
+            // stop()でabortした場合はここに到達しない（停止コマンド側で通知する）
+            status_server.read().await.set_polling_status(None).await;
         });
 
         *self.task_handle.lock().await = Some(handle);
@@ -126,8 +188,12 @@ impl UnifiedPoller {
     }
 
     /// 公式APIモード（ポーリング）で開始
+    ///
+    /// 配信が一時的に中断してライブチャットが見つからなくなった場合は、
+    /// `video_id`からライブチャットIDを再取得して再接続する
     pub async fn start_official(
         &self,
+        video_id: String,
         live_chat_id: String,
         api_key: String,
         app_handle: AppHandle,
@@ -139,7 +205,7 @@ impl UnifiedPoller {
         *self.mode.lock().await = Some(ApiMode::Official);
         self.running.store(true, Ordering::SeqCst);
 
-        let poller = ChatPoller::new(api_key);
+        let poller = ChatPoller::new(api_key).with_video_id(video_id);
         let handle = app_handle.clone();
         let db_pool_for_callback = db_pool.clone();
         let server_state_for_callback: Arc<RwLock<WebSocketState>> = Arc::clone(&server_state);
@@ -151,36 +217,43 @@ impl UnifiedPoller {
 
                 match event {
                     PollingEvent::Messages { messages } => {
-                        // フロントエンドへのイベント発火
-                        let _ = handle.emit("chat-messages", &messages);
+                        // フロントエンドへのイベント発火（BAN中の投稿者は除外）
+                        let _ = handle.emit("chat-messages", &without_banned(&messages));
 
                         // WS/DB連携（非同期タスクで処理）
                         let messages_clone = messages.clone();
                         tokio::spawn(async move {
-                            // DBに保存
-                            let save_result = save_comments_to_db(&db_pool, &messages_clone).await;
-                            if save_result.failed > 0 || save_result.skipped > 0 {
-                                log::warn!(
-                                    "save_comments_to_db: {} saved, {} failed, {} skipped",
-                                    save_result.saved, save_result.failed, save_result.skipped
-                                );
-                            }
+                            // DBへの保存は書き込みキュー経由（ブロードキャストは書き込みを待たない）
+                            // ブロックリスト・BANで除外したコメントは配信しない（DBにはフラグ付きで保存）
+                            let severities = classify_comments(&messages_clone);
+                            let messages_clone = queue_save_and_filter(&db_pool, messages_clone);
+                            let first_time_ids = detect_first_time(&db_pool, &messages_clone).await;
 
                             // WebSocketでブロードキャスト（公式APIはバッファリング表示、デフォルト5秒）
                             let state_lock = server_state.read().await;
-                            for msg in messages_clone {
+                            for msg in &messages_clone {
                                 // コメント欄にブロードキャスト
-                                state_lock.broadcast(WsMessage::CommentAdd { payload: msg.clone(), instant: false, buffer_interval_ms: None }).await;
+                                let is_first_time = first_time_ids.contains(&msg.id);
+                                state_lock.broadcast(WsMessage::CommentAdd { payload: msg.clone(), instant: false, buffer_interval_ms: None, is_first_time, translation: None, severity: severities.get(&msg.id).copied().unwrap_or_default() }).await;
 
                                 // スパチャの場合は専用ウィジェットにもブロードキャスト
-                                if let Some(superchat_payload) = create_superchat_payload(&msg) {
-                                    let display_duration = superchat_payload.display_duration_ms;
-                                    let superchat_id = superchat_payload.id.clone();
-                                    broadcast_superchat(&server_state, superchat_payload).await;
-                                    // 表示完了後にremoveメッセージを送信するタイマーをスケジュール
-                                    schedule_superchat_removal(Arc::clone(&server_state), superchat_id, display_duration);
+                                if let Some(superchat_payload) = create_superchat_payload(msg) {
+                                    // 表示中のスパチャがあれば表示完了後に順番に表示
+                                    enqueue_superchat(&server_state, superchat_payload).await;
+                                }
+
+                                // メンバーシップ・ギフトの場合は専用ウィジェットにもブロードキャスト
+                                if let Some(membership_payload) = create_membership_payload(msg) {
+                                    show_membership(&server_state, membership_payload).await;
                                 }
                             }
+                            drop(state_lock);
+
+                            // 翻訳は応答を待たずに後から配信
+                            spawn_translations(&server_state, &messages_clone);
+
+                            // 支援者ランキング・目標金額を集計（合計が変わった場合は配信）
+                            record_totals(&db_pool, &server_state, &messages_clone).await;
                         });
                     }
                     PollingEvent::Started { live_chat_id } => {
@@ -195,12 +268,16 @@ impl UnifiedPoller {
                             "stopped": true,
                             "reason": reason
                         }));
+                        tokio::spawn(async move {
+                            server_state.read().await.set_polling_status(None).await;
+                        });
                     }
-                    PollingEvent::Error { message, retrying } => {
+                    PollingEvent::Error { message, retrying, recoverable } => {
                         let _ = handle.emit("official-status", serde_json::json!({
                             "connected": false,
                             "error": message,
-                            "retrying": retrying
+                            "retrying": retrying,
+                            "recoverable": recoverable
                         }));
                     }
                     PollingEvent::QuotaExceeded => {
@@ -216,6 +293,18 @@ impl UnifiedPoller {
                             "streamEnded": true
                         }));
                     }
+                    PollingEvent::Paused => {
+                        let _ = handle.emit("official-status", serde_json::json!({
+                            "connected": true,
+                            "paused": true
+                        }));
+                    }
+                    PollingEvent::Resumed => {
+                        let _ = handle.emit("official-status", serde_json::json!({
+                            "connected": true,
+                            "paused": false
+                        }));
+                    }
                     PollingEvent::StateUpdate { quota_used, remaining_quota, poll_count, .. } => {
                         let _ = handle.emit("official-status", serde_json::json!({
                             "connected": true,
@@ -224,11 +313,40 @@ impl UnifiedPoller {
                             "pollCount": poll_count
                         }));
                     }
+                    PollingEvent::KeySwitched { reason } => {
+                        let _ = handle.emit("official-status", serde_json::json!({
+                            "connected": true,
+                            "keySwitched": true,
+                            "reason": reason
+                        }));
+                    }
+                    PollingEvent::QuotaWarning { remaining_quota, polling_interval_millis } => {
+                        let _ = handle.emit("official-status", serde_json::json!({
+                            "connected": true,
+                            "quotaWarning": true,
+                            "remainingQuota": remaining_quota,
+                            "pollingIntervalMillis": polling_interval_millis
+                        }));
+                    }
+                    PollingEvent::Reconnecting { attempt, max_attempts } => {
+                        let _ = handle.emit("official-status", serde_json::json!({
+                            "connected": false,
+                            "reconnecting": true,
+                            "attempt": attempt,
+                            "maxAttempts": max_attempts
+                        }));
+                    }
+                    PollingEvent::ChatSettings { members_only } => {
+                        tokio::spawn(async move {
+                            CHAT_SETTINGS.apply_members_only(&server_state, members_only).await;
+                        });
+                    }
                 }
             })
             .await?;
 
         *self.official_poller.lock().await = Some(poller);
+        server_state.read().await.set_polling_status(Some(ApiMode::Official)).await;
 
         log::info!("Started Official API polling");
         Ok(())
@@ -249,9 +367,12 @@ impl UnifiedPoller {
         self.running.store(true, Ordering::SeqCst);
 
         let mut poller = GrpcPoller::new();
-        poller.start(live_chat_id, api_key, app_handle, db_pool, server_state).await?;
+        poller
+            .start(live_chat_id, api_key, app_handle, db_pool, Arc::clone(&server_state))
+            .await?;
 
         *self.grpc_poller.lock().await = Some(poller);
+        server_state.read().await.set_polling_status(Some(ApiMode::Grpc)).await;
 
         log::info!("Started gRPC streaming");
         Ok(())
@@ -288,7 +409,7 @@ impl UnifiedPoller {
                 // video_idからlive_chat_idを取得
                 let client = super::client::YouTubeClient::new(api_key.clone());
                 let live_chat_id = client.get_live_chat_id(&video_id).await?;
-                self.start_official(live_chat_id, api_key, app_handle, db_pool, server_state).await
+                self.start_official(video_id, live_chat_id, api_key, app_handle, db_pool, server_state).await
             }
             ApiMode::Grpc => {
                 // APIキーを取得
@@ -357,6 +478,7 @@ fn get_api_key_for_mode(
 async fn run_innertube_loop(
     video_id: String,
     running: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
     app_handle: AppHandle,
     db_pool: SqlitePool,
     server_state: Arc<RwLock<WebSocketState>>,
@@ -365,11 +487,14 @@ async fn run_innertube_loop(
 
     let mut client = InnerTubeClient::new(video_id)?;
     client.initialize().await?;
+    // 絵文字キャッシュは配信ごとに持つため、プレビュー用に新しい配信のキャッシュへ切り替える
+    super::innertube::emoji_cache::set_active_emoji_cache(Some(Arc::clone(client.emoji_cache())));
 
-    let mut seen_ids: HashSet<String> = HashSet::new();
-    let mut seen_order: VecDeque<String> = VecDeque::new();
+    let mut seen_ids = SeenMessageIds::new();
     // エラー時の指数バックオフ（ジッタ付き）
     let mut error_backoff = ExponentialBackoff::with_jitter();
+    // 定常ポーリングの間隔（ジッタ付き、新しいメッセージのない取得が続いたら延ばす）
+    let mut poll_interval = PollIntervalBackoff::new();
 
     log::info!("InnerTube polling loop started");
 
@@ -379,49 +504,46 @@ async fn run_innertube_loop(
     }));
 
     while running.load(Ordering::SeqCst) {
+        // 一時停止中は取得せずに待機（continuation・重複排除セットは保持）
+        if paused.load(Ordering::SeqCst) {
+            let _ = app_handle.emit("innertube-status", serde_json::json!({
+                "connected": true,
+                "paused": true
+            }));
+            while paused.load(Ordering::SeqCst) && running.load(Ordering::SeqCst) {
+                tokio::time::sleep(PAUSE_CHECK_INTERVAL).await;
+            }
+            if !running.load(Ordering::SeqCst) {
+                break;
+            }
+            let _ = app_handle.emit("innertube-status", serde_json::json!({
+                "connected": true,
+                "paused": false
+            }));
+        }
+
         match client.get_chat_messages().await {
             Ok(response) => {
                 // 成功時はバックオフをリセット
                 error_backoff.reset();
 
-                let messages = parse_chat_response(response);
-
-                // 重複排除（HashSet::insertの戻り値を利用して簡素化）
-                let new_messages: Vec<ChatMessage> = messages
-                    .into_iter()
-                    .filter(|msg| {
-                        if seen_ids.insert(msg.id.clone()) {
-                            seen_order.push_back(msg.id.clone());
-                            true
-                        } else {
-                            false
-                        }
-                    })
-                    .collect();
+                let messages = parse_chat_response(response, client.emoji_cache());
 
-                // FIFO eviction
-                while seen_ids.len() > MAX_SEEN_IDS {
-                    if let Some(oldest_id) = seen_order.pop_front() {
-                        seen_ids.remove(&oldest_id);
-                    } else {
-                        break;
-                    }
-                }
+                // 重複排除（保持期間を過ぎたIDは破棄）
+                let new_messages = seen_ids.filter_new(messages);
+                poll_interval.record(!new_messages.is_empty());
 
                 if !new_messages.is_empty() {
-                    // フロントエンドへのイベント発火
-                    let _ = app_handle.emit("chat-messages", &new_messages);
+                    // フロントエンドへのイベント発火（BAN中の投稿者は除外）
+                    let _ = app_handle.emit("chat-messages", &without_banned(&new_messages));
                     log::debug!("InnerTube: {} new messages", new_messages.len());
 
                     // WS/DB連携
-                    // DBに保存
-                    let save_result = save_comments_to_db(&db_pool, &new_messages).await;
-                    if save_result.failed > 0 || save_result.skipped > 0 {
-                        log::warn!(
-                            "save_comments_to_db: {} saved, {} failed, {} skipped",
-                            save_result.saved, save_result.failed, save_result.skipped
-                        );
-                    }
+                    // DBへの保存は書き込みキュー経由（ブロードキャストは書き込みを待たない）
+                    // ブロックリスト・BANで除外したコメントは配信しない（DBにはフラグ付きで保存）
+                    let severities = classify_comments(&new_messages);
+                    let new_messages = queue_save_and_filter(&db_pool, new_messages);
+                    let first_time_ids = detect_first_time(&db_pool, &new_messages).await;
 
                     // WebSocketでブロードキャスト（InnerTubeはバッファリング表示）
                     use crate::youtube::innertube::INNERTUBE_BUFFER_INTERVAL_MS;
@@ -432,17 +554,29 @@ async fn run_innertube_loop(
                             payload: msg.clone(),
                             instant: false,
                             buffer_interval_ms: Some(INNERTUBE_BUFFER_INTERVAL_MS),
+                            is_first_time: first_time_ids.contains(&msg.id),
+                            translation: None,
+                            severity: severities.get(&msg.id).copied().unwrap_or_default(),
                         }).await;
 
                         // スパチャの場合は専用ウィジェットにもブロードキャスト
                         if let Some(superchat_payload) = create_superchat_payload(msg) {
-                            let display_duration = superchat_payload.display_duration_ms;
-                            let superchat_id = superchat_payload.id.clone();
-                            broadcast_superchat(&server_state, superchat_payload).await;
-                            // 表示完了後にremoveメッセージを送信するタイマーをスケジュール
-                            schedule_superchat_removal(Arc::clone(&server_state), superchat_id, display_duration);
+                            // 表示中のスパチャがあれば表示完了後に順番に表示
+                            enqueue_superchat(&server_state, superchat_payload).await;
+                        }
+
+                        // メンバーシップ・ギフトの場合は専用ウィジェットにもブロードキャスト
+                        if let Some(membership_payload) = create_membership_payload(msg) {
+                            show_membership(&server_state, membership_payload).await;
                         }
                     }
+                    drop(state_lock);
+
+                    // 翻訳は応答を待たずに後から配信
+                    spawn_translations(&server_state, &new_messages);
+
+                    // 支援者ランキング・目標金額を集計（合計が変わった場合は配信）
+                    record_totals(&db_pool, &server_state, &new_messages).await;
                 }
 
                 // 次のポーリングまで待機
@@ -450,13 +584,15 @@ async fn run_innertube_loop(
                 let api_timeout = client.get_timeout_ms();
                 let cont_type = client.get_continuation_type();
                 let timeout_ms = cont_type.effective_timeout_ms(api_timeout);
+                let wait = poll_interval.next_interval(std::time::Duration::from_millis(timeout_ms));
                 log::debug!(
-                    "InnerTube: next poll in {}ms (API: {}ms, type: {:?})",
+                    "InnerTube: next poll in {:?} (base: {}ms, API: {}ms, type: {:?})",
+                    wait,
                     timeout_ms,
                     api_timeout,
                     cont_type
                 );
-                tokio::time::sleep(std::time::Duration::from_millis(timeout_ms)).await;
+                tokio::time::sleep(wait).await;
             }
             Err(e) => {
                 log::error!("InnerTube fetch error: {:?}", e);
@@ -493,5 +629,44 @@ mod tests {
     fn test_unified_poller_new() {
         let poller = UnifiedPoller::new();
         assert!(!poller.is_running());
+        assert!(!poller.is_paused());
+    }
+
+    #[tokio::test]
+    async fn test_pause_requires_running_poller() {
+        let poller = UnifiedPoller::new();
+        assert!(poller.pause().await.is_err());
+        assert!(poller.resume().await.is_err());
+        assert!(!poller.is_paused());
+    }
+
+    #[tokio::test]
+    async fn test_pause_rejected_in_grpc_mode() {
+        let poller = UnifiedPoller::new();
+        *poller.mode.lock().await = Some(ApiMode::Grpc);
+        poller.running.store(true, Ordering::SeqCst);
+
+        assert!(poller.pause().await.is_err());
+        assert!(!poller.is_paused());
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume_innertube_mode() {
+        let poller = UnifiedPoller::new();
+        *poller.mode.lock().await = Some(ApiMode::InnerTube);
+        poller.running.store(true, Ordering::SeqCst);
+
+        poller.pause().await.unwrap();
+        assert!(poller.is_paused());
+        assert!(poller.is_running());
+
+        poller.resume().await.unwrap();
+        assert!(!poller.is_paused());
+
+        // 停止時は一時停止状態も解除される
+        poller.pause().await.unwrap();
+        poller.stop().await;
+        assert!(!poller.is_paused());
+        assert!(!poller.is_running());
     }
 }
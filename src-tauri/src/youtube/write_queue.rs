@@ -0,0 +1,303 @@
+//! コメントログのDB書き込みキュー
+//!
+//! チャットが非常に多い配信でDB書き込みが追いつかない場合に備え、
+//! ポーリング処理とDB書き込みの間に上限付きのキューを挟む。
+//! オーバーレイへのブロードキャストはキューを経由せず、書き込みを待たない。
+//!
+//! ## 満杯時の方針
+//! キューが満杯の場合は最も古い書き込みを破棄して新しい書き込みを積む（drop-oldest）。
+//! 破棄したコメント数は[`stats`]で取得でき、メモリを際限なく使うことはない。
+//!
+//! ## 終了時
+//! アプリ終了時は[`flush`]で書き込み待ち・書き込み中のコメントの保存完了を待つ。
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use super::db::{mark_comments_filtered, save_comments_to_db};
+use super::types::ChatMessage;
+
+/// キューに積める書き込みの最大数（1回のポーリング結果が1件）
+pub const WRITE_QUEUE_CAPACITY: usize = 1000;
+
+/// flush時に書き込み完了を確認する間隔
+const FLUSH_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// 1回分の書き込み
+struct WriteJob {
+    pool: SqlitePool,
+    /// 保存するコメント
+    messages: Vec<ChatMessage>,
+    /// 配信から除外したコメントのID（保存後に`is_filtered`フラグを付ける）
+    filtered_ids: Vec<String>,
+}
+
+/// 書き込みキューの状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteQueueStats {
+    /// 書き込み待ちの件数
+    pub pending: usize,
+    /// キューが満杯のため保存せずに破棄したコメント数（起動からの累計）
+    pub dropped_comments: u64,
+}
+
+/// 上限付きの書き込みキュー
+struct WriteQueue {
+    jobs: Mutex<VecDeque<WriteJob>>,
+    capacity: usize,
+    notify: Notify,
+    dropped_comments: AtomicU64,
+    /// 取り出し済みで書き込み中の件数
+    in_flight: AtomicUsize,
+}
+
+impl WriteQueue {
+    fn new(capacity: usize) -> Self {
+        Self {
+            jobs: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            notify: Notify::new(),
+            dropped_comments: AtomicU64::new(0),
+            in_flight: AtomicUsize::new(0),
+        }
+    }
+
+    /// 書き込みを積む（満杯の場合は最も古い書き込みを破棄）
+    fn push(&self, job: WriteJob) {
+        {
+            let mut jobs = match self.jobs.lock() {
+                Ok(jobs) => jobs,
+                Err(e) => {
+                    log::error!("Failed to lock comment write queue: {}", e);
+                    return;
+                }
+            };
+            while jobs.len() >= self.capacity {
+                let Some(dropped) = jobs.pop_front() else {
+                    break;
+                };
+                self.dropped_comments
+                    .fetch_add(dropped.messages.len() as u64, Ordering::Relaxed);
+                log::warn!(
+                    "Comment write queue is full, dropped {} comments",
+                    dropped.messages.len()
+                );
+            }
+            jobs.push_back(job);
+        }
+        self.notify.notify_one();
+    }
+
+    /// 最も古い書き込みを取り出す
+    ///
+    /// 取り出した書き込みは[`WriteQueue::finish`]を呼ぶまで書き込み中として数える
+    /// （キューが空になってから書き込みが終わるまでの間もflushが待てるよう、ロック内で数える）
+    fn pop(&self) -> Option<WriteJob> {
+        let mut jobs = self.jobs.lock().ok()?;
+        let job = jobs.pop_front()?;
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        Some(job)
+    }
+
+    /// 取り出した書き込みの完了を記録
+    fn finish(&self) {
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// 書き込み待ち・書き込み中がないか
+    fn is_idle(&self) -> bool {
+        self.jobs
+            .lock()
+            .map(|jobs| jobs.is_empty() && self.in_flight.load(Ordering::SeqCst) == 0)
+            .unwrap_or(true)
+    }
+
+    /// 書き込み待ち・書き込み中の保存が完了するまで待つ（タイムアウト時はfalse）
+    async fn wait_idle(&self, timeout: Duration) -> bool {
+        tokio::time::timeout(timeout, async {
+            while !self.is_idle() {
+                tokio::time::sleep(FLUSH_POLL_INTERVAL).await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+
+    fn stats(&self) -> WriteQueueStats {
+        WriteQueueStats {
+            pending: self.jobs.lock().map(|jobs| jobs.len()).unwrap_or(0),
+            dropped_comments: self.dropped_comments.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// 書き込みを1件実行（保存してから除外フラグを付ける）
+async fn execute(job: WriteJob) {
+    let save_result = save_comments_to_db(&job.pool, &job.messages).await;
+    if save_result.failed > 0 || save_result.skipped > 0 {
+        log::warn!(
+            "save_comments_to_db: {} saved, {} failed, {} skipped",
+            save_result.saved,
+            save_result.failed,
+            save_result.skipped
+        );
+    }
+
+    if !job.filtered_ids.is_empty() {
+        if let Err(e) = mark_comments_filtered(&job.pool, &job.filtered_ids).await {
+            log::warn!("Failed to flag filtered comments: {}", e);
+        }
+    }
+}
+
+/// キューから順に取り出して書き込むワーカー
+async fn run_worker(queue: Arc<WriteQueue>) {
+    loop {
+        while let Some(job) = queue.pop() {
+            execute(job).await;
+            queue.finish();
+        }
+        queue.notify.notified().await;
+    }
+}
+
+static WRITE_QUEUE: Lazy<Arc<WriteQueue>> =
+    Lazy::new(|| Arc::new(WriteQueue::new(WRITE_QUEUE_CAPACITY)));
+
+/// ワーカーを起動済みか
+static WORKER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// コメントの保存をキューに積む（書き込みを待たずに戻る）
+///
+/// `filtered_ids`には配信から除外したコメントのIDを渡す。保存後に`is_filtered`フラグを付ける。
+pub fn enqueue(pool: &SqlitePool, messages: Vec<ChatMessage>, filtered_ids: Vec<String>) {
+    if messages.is_empty() {
+        return;
+    }
+
+    if !WORKER_STARTED.swap(true, Ordering::SeqCst) {
+        tauri::async_runtime::spawn(run_worker(Arc::clone(&WRITE_QUEUE)));
+    }
+
+    WRITE_QUEUE.push(WriteJob {
+        pool: pool.clone(),
+        messages,
+        filtered_ids,
+    });
+}
+
+/// 書き込みキューの状態を取得
+pub fn stats() -> WriteQueueStats {
+    WRITE_QUEUE.stats()
+}
+
+/// 書き込み待ち・書き込み中のコメントの保存完了を待つ（アプリ終了時に使用）
+///
+/// タイムアウトまでに完了しなかった場合はfalseを返す（残りの書き込みは保存されない）
+pub async fn flush(timeout: Duration) -> bool {
+    let completed = WRITE_QUEUE.wait_idle(timeout).await;
+    if !completed {
+        log::warn!(
+            "Comment write queue flush timed out, {} writes pending",
+            WRITE_QUEUE.stats().pending
+        );
+    }
+    completed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn job(pool: &SqlitePool, ids: &[&str]) -> WriteJob {
+        WriteJob {
+            pool: pool.clone(),
+            messages: ids.iter().map(|id| ChatMessage::test_message(id)).collect(),
+            filtered_ids: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_queue_drops_oldest_when_full() {
+        let pool = SqlitePool::connect_lazy("sqlite::memory:").unwrap();
+        let queue = WriteQueue::new(2);
+
+        queue.push(job(&pool, &["a1", "a2"]));
+        queue.push(job(&pool, &["b1"]));
+        assert_eq!(
+            queue.stats(),
+            WriteQueueStats {
+                pending: 2,
+                dropped_comments: 0
+            }
+        );
+
+        // 満杯なので最も古い書き込み（2件）を破棄
+        queue.push(job(&pool, &["c1"]));
+        assert_eq!(
+            queue.stats(),
+            WriteQueueStats {
+                pending: 2,
+                dropped_comments: 2
+            }
+        );
+
+        let remaining: Vec<String> = std::iter::from_fn(|| queue.pop())
+            .flat_map(|job| job.messages.into_iter().map(|m| m.id))
+            .collect();
+        assert_eq!(remaining, vec!["b1", "c1"]);
+    }
+
+    #[tokio::test]
+    async fn test_wait_idle_waits_for_pending_writes() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        let queue = Arc::new(WriteQueue::new(10));
+        assert!(queue.wait_idle(Duration::from_millis(10)).await);
+
+        queue.push(job(&pool, &["a1", "a2"]));
+        queue.push(job(&pool, &["b1"]));
+
+        // ワーカーがいなければ書き込みは終わらない
+        assert!(!queue.wait_idle(Duration::from_millis(50)).await);
+
+        let worker = tokio::spawn(run_worker(Arc::clone(&queue)));
+        assert!(queue.wait_idle(Duration::from_secs(5)).await);
+        worker.abort();
+
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM comment_logs")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+    }
+
+    #[tokio::test]
+    async fn test_execute_saves_and_flags_filtered() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let pool = crate::db::create_pool(temp_file.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        let mut write = job(&pool, &["c1", "c2"]);
+        write.filtered_ids = vec!["c2".to_string()];
+        execute(write).await;
+
+        let rows: Vec<(String, i64)> =
+            sqlx::query_as("SELECT youtube_id, is_filtered FROM comment_logs ORDER BY youtube_id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(rows, vec![("c1".to_string(), 0), ("c2".to_string(), 1)]);
+    }
+}
@@ -3,11 +3,13 @@ import { invoke } from '@tauri-apps/api/core';
 import { CommentControlPanel } from './components/CommentControlPanel';
 import { SongList } from './components/SongList';
 import { SetlistList } from './components/SetlistList';
+import { PlayHistory } from './components/PlayHistory';
 import { TestModeButton } from './components/TestModeButton';
 import { OverlaySettings } from './components/settings';
 import Wizard from './components/wizard/Wizard';
 import { UpdateChecker } from './components/UpdateChecker';
 import { VideoIdModal } from './components/VideoIdModal';
+import { handleTauriError } from './utils/errorMessages';
 
 type Tab = 'comment' | 'setlist' | 'settings';
 type AppMode = 'wizard' | 'main';
@@ -68,7 +70,7 @@ function App() {
       setIsPolling(true);
       showStatus('success', 'コメント取得を開始しました');
     } catch (e) {
-      showStatus('error', 'エラー: ' + (e instanceof Error ? e.message : String(e)));
+      showStatus('error', 'エラー: ' + handleTauriError(e));
     }
   }, [showStatus, setIsPolling]);
 
@@ -263,6 +265,7 @@ function App() {
           <div className="space-y-8">
             <SongList />
             <SetlistList />
+            <PlayHistory />
           </div>
         )}
         {activeTab === 'settings' && (
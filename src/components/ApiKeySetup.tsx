@@ -126,7 +126,9 @@ export function ApiKeySetup({ onSettingsChange }: ApiKeySetupProps) {
 
       setSuccess('設定を保存しました。コントロールパネルに反映されました。');
     } catch (err) {
-      setError(`エラー: ${err}`);
+      const errorMessage = handleTauriError(err, 'チャットIDの取得に失敗しました');
+      setError(errorMessage);
+      console.error('Live chat ID fetch error:', err);
     } finally {
       setLoading(false);
     }
@@ -155,7 +157,9 @@ export function ApiKeySetup({ onSettingsChange }: ApiKeySetupProps) {
         `${newMessages.length}件のメッセージを取得しました（ポーリング間隔: ${pollingInterval}ms）`
       );
     } catch (err) {
-      setError(`エラー: ${err}`);
+      const errorMessage = handleTauriError(err, 'メッセージの取得に失敗しました');
+      setError(errorMessage);
+      console.error('Chat messages fetch error:', err);
     } finally {
       setLoading(false);
     }
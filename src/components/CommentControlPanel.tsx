@@ -4,6 +4,7 @@ import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import type { ChatMessage } from '../types/chat';
 import type { ApiMode, InnerTubeStatusEvent, GrpcStatusEvent, OfficialStatusEvent } from '../types/api';
 import { API_MODE_INFO } from '../types/api';
+import { handleTauriError } from '../utils/errorMessages';
 
 // YouTube API クォータ定数
 const DAILY_QUOTA_LIMIT = 10000; // 1日のクォータ上限
@@ -34,9 +35,15 @@ type PollingEventType =
       next_page_token: string | null;
       polling_interval_millis: number;
     }
-  | { type: 'error'; message: string; retrying: boolean }
+  | { type: 'error'; message: string; retrying: boolean; recoverable: boolean }
   | { type: 'quotaExceeded' }
-  | { type: 'streamEnded' };
+  | { type: 'streamEnded' }
+  | { type: 'paused' }
+  | { type: 'resumed' }
+  | { type: 'chatSettings'; members_only: boolean }
+  | { type: 'keySwitched'; reason: string }
+  | { type: 'quotaWarning'; remaining_quota: number; polling_interval_millis: number }
+  | { type: 'reconnecting'; attempt: number; max_attempts: number };
 
 interface SavedPollingState {
   live_chat_id: string;
@@ -239,7 +246,7 @@ export function CommentControlPanel({
 
         unlistenOfficial = await listen<OfficialStatusEvent>('official-status', (event) => {
           if (!isMountedRef.current) return;
-          const { connected, error: statusError, stopped, quotaExceeded, streamEnded, retrying } = event.payload;
+          const { connected, error: statusError, stopped, quotaExceeded, streamEnded, retrying, reconnecting, attempt, maxAttempts } = event.payload;
           if (connected) {
             setConnectionStatus('connected');
             setError(null);
@@ -247,6 +254,9 @@ export function CommentControlPanel({
           } else if (stopped) {
             setConnectionStatus('disconnected');
             setLastEvent('公式API停止');
+          } else if (reconnecting) {
+            setConnectionStatus('connected');
+            setLastEvent(`配信が見つかりません - 再接続中 (${attempt}/${maxAttempts})`);
           } else if (quotaExceeded) {
             setConnectionStatus('error');
             setError('クォータ超過 - 翌日まで待機してください');
@@ -310,7 +320,9 @@ export function CommentControlPanel({
               break;
             case 'error':
               setError(payload.message);
-              if (payload.retrying) {
+              if (payload.retrying && payload.recoverable) {
+                setLastEvent(`エラー: ${payload.message} (再接続中)`);
+              } else if (payload.retrying) {
                 setLastEvent(`エラー: ${payload.message} (再試行中)`);
               } else {
                 setLastEvent(`エラー: ${payload.message}`);
@@ -325,6 +337,18 @@ export function CommentControlPanel({
               setIsPolling(false);
               setLastEvent('配信が終了しました');
               break;
+            case 'keySwitched':
+              setLastEvent(`警告: ${payload.reason}`);
+              break;
+            case 'quotaWarning':
+              setLastEvent(`警告: 残りクォータが少ないためポーリング間隔を${payload.polling_interval_millis / 1000}秒に延ばしました（残り${payload.remaining_quota} units）`);
+              break;
+            case 'reconnecting':
+              setLastEvent(`配信が見つかりません - 再接続中 (${payload.attempt}/${payload.max_attempts})`);
+              break;
+            case 'chatSettings':
+              setLastEvent(payload.members_only ? 'メンバー限定モードが有効になりました' : 'メンバー限定モードが解除されました');
+              break;
           }
         });
       } catch (err) {
@@ -405,7 +429,7 @@ export function CommentControlPanel({
         }
       } catch (err) {
         if (isMountedRef.current) {
-          const errorMessage = err instanceof Error ? err.message : String(err);
+          const errorMessage = handleTauriError(err);
           setError(`ポーリング開始エラー: ${errorMessage}`);
           setConnectionStatus('error');
         }
@@ -431,7 +455,7 @@ export function CommentControlPanel({
       }
     } catch (err) {
       if (isMountedRef.current) {
-        const errorMessage = err instanceof Error ? err.message : String(err);
+        const errorMessage = handleTauriError(err);
         setError(`ポーリング停止エラー: ${errorMessage}`);
       }
     } finally {
@@ -454,7 +478,7 @@ export function CommentControlPanel({
       setError(null);
       setLastEvent(`モードを${API_MODE_INFO[newMode].label}に変更しました`);
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : String(err);
+      const errorMessage = handleTauriError(err);
       setError(`モード変更エラー: ${errorMessage}`);
     }
   }, [isPolling]);
@@ -517,7 +541,7 @@ export function CommentControlPanel({
       setLastEvent('動画IDを更新しました');
     } catch (err) {
       if (isMountedRef.current) {
-        const errorMessage = err instanceof Error ? err.message : String(err);
+        const errorMessage = handleTauriError(err);
         setError(`動画ID更新エラー: ${errorMessage}`);
       }
     } finally {
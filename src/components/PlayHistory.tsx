@@ -0,0 +1,110 @@
+import { useCallback, useEffect, useState } from 'react';
+import { getPlayHistory } from '../types/commands';
+import type { SongPlay } from '../types/setlist';
+
+/** ローカル日付の`YYYY-MM-DD`表記 */
+function toDateInputValue(date: Date): string {
+  const month = String(date.getMonth() + 1).padStart(2, '0');
+  const day = String(date.getDate()).padStart(2, '0');
+  return `${date.getFullYear()}-${month}-${day}`;
+}
+
+/**
+ * 歌唱履歴（指定日に現在の曲にした曲の一覧）
+ * 配信後に「今日歌った曲」を確認・コピーするためのもの
+ */
+export function PlayHistory() {
+  const [date, setDate] = useState(() => toDateInputValue(new Date()));
+  const [plays, setPlays] = useState<SongPlay[]>([]);
+  const [error, setError] = useState('');
+  const [copied, setCopied] = useState(false);
+
+  const loadHistory = useCallback(async () => {
+    // 選択した日のローカル時刻0時から翌日0時まで
+    const from = new Date(`${date}T00:00:00`);
+    if (Number.isNaN(from.getTime())) return;
+    const to = new Date(from);
+    to.setDate(to.getDate() + 1);
+
+    try {
+      setPlays(await getPlayHistory(from.toISOString(), to.toISOString()));
+      setError('');
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }, [date]);
+
+  useEffect(() => {
+    loadHistory();
+  }, [loadHistory]);
+
+  const formatPlay = (play: SongPlay) => {
+    const time = new Date(play.playedAt).toLocaleTimeString('ja-JP', {
+      hour: '2-digit',
+      minute: '2-digit',
+    });
+    return `${time} ${play.title}${play.artist ? ` / ${play.artist}` : ''}`;
+  };
+
+  const handleCopy = async () => {
+    try {
+      await navigator.clipboard.writeText(plays.map(formatPlay).join('\n'));
+      setCopied(true);
+      setTimeout(() => setCopied(false), 2000);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  };
+
+  return (
+    <div className="space-y-4">
+      <div className="flex justify-between items-center">
+        <h2 className="text-2xl font-bold text-gray-900">歌唱履歴</h2>
+        <div className="flex gap-2 items-center">
+          <input
+            type="date"
+            value={date}
+            onChange={(e) => setDate(e.target.value)}
+            className="px-3 py-2 border border-gray-300 rounded-lg text-gray-900"
+          />
+          <button
+            onClick={loadHistory}
+            className="px-4 py-2 border border-gray-300 text-gray-700 rounded-lg hover:bg-gray-50 transition-colors"
+          >
+            更新
+          </button>
+          <button
+            onClick={handleCopy}
+            disabled={plays.length === 0}
+            className="px-4 py-2 bg-blue-600 text-white rounded-lg hover:bg-blue-700 transition-colors disabled:opacity-50"
+          >
+            {copied ? 'コピーしました' : 'コピー'}
+          </button>
+        </div>
+      </div>
+
+      {error && (
+        <div className="p-4 bg-red-50 border border-red-200 rounded-lg">
+          <p className="text-red-600">{error}</p>
+        </div>
+      )}
+
+      {plays.length === 0 ? (
+        <div className="text-center py-8 bg-white rounded-lg border border-gray-200">
+          <p className="text-gray-500">この日の歌唱履歴はありません</p>
+        </div>
+      ) : (
+        <ul className="bg-white rounded-lg border border-gray-200 divide-y divide-gray-200">
+          {plays.map((play) => (
+            <li key={play.id} className="px-6 py-3 flex justify-between gap-4">
+              <span className="text-gray-900">{formatPlay(play)}</span>
+              {play.setlistName && (
+                <span className="text-sm text-gray-400 whitespace-nowrap">{play.setlistName}</span>
+              )}
+            </li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+}
@@ -468,6 +468,7 @@ function SetlistSongItem({ setlistSong, index, onRemove, onSetCurrent, dragHandl
               | {Math.floor(song.durationSeconds / 60)}:{String(song.durationSeconds % 60).padStart(2, '0')}
             </span>
           )}
+          {song.songKey && <span className="text-gray-400">| Key: {song.songKey}</span>}
         </div>
         {tags.length > 0 && (
           <div className="flex flex-wrap gap-1 mt-2">
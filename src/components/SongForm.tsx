@@ -16,6 +16,7 @@ export function SongForm({ song, onClose }: SongFormProps) {
   const [tagInput, setTagInput] = useState('');
   const [durationMinutes, setDurationMinutes] = useState('');
   const [durationSeconds, setDurationSeconds] = useState('');
+  const [songKey, setSongKey] = useState('');
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState('');
 
@@ -29,6 +30,7 @@ export function SongForm({ song, onClose }: SongFormProps) {
         setDurationMinutes(String(Math.floor(song.durationSeconds / 60)));
         setDurationSeconds(String(song.durationSeconds % 60));
       }
+      setSongKey(song.songKey || '');
     }
   }, [song]);
 
@@ -67,6 +69,7 @@ export function SongForm({ song, onClose }: SongFormProps) {
         category: category.trim() || null,
         tags: tags.length > 0 ? tags : null,
         duration_seconds: durationSecondsTotal > 0 ? durationSecondsTotal : null,
+        song_key: songKey.trim() || null,
       };
 
       if (song) {
@@ -209,6 +212,20 @@ export function SongForm({ song, onClose }: SongFormProps) {
             </div>
           </div>
 
+          <div>
+            <label className="block text-sm font-medium text-gray-700 mb-1">
+              キー
+            </label>
+            <input
+              type="text"
+              value={songKey}
+              onChange={(e) => setSongKey(e.target.value)}
+              className="w-32 px-4 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-blue-500 text-gray-900 placeholder:text-gray-400"
+              placeholder="例: F#m"
+              maxLength={16}
+            />
+          </div>
+
           <div className="flex justify-end gap-2 pt-4">
             <button
               type="button"
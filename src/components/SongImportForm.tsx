@@ -0,0 +1,121 @@
+import { useState, type FormEvent } from 'react';
+import { importSongs } from '../types/commands';
+import type { SongImportResult } from '../types/song';
+
+interface SongImportFormProps {
+  onClose: (imported: boolean) => void;
+}
+
+/**
+ * 楽曲の一括インポート
+ * 1行1曲（タイトル, アーティスト, 曲の長さ）のテキスト / CSVを貼り付けて登録する
+ */
+export function SongImportForm({ onClose }: SongImportFormProps) {
+  const [text, setText] = useState('');
+  const [setlistName, setSetlistName] = useState('');
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState('');
+  const [result, setResult] = useState<SongImportResult | null>(null);
+
+  const handleSubmit = async (e: FormEvent) => {
+    e.preventDefault();
+    if (!text.trim()) {
+      setError('インポートする曲を入力してください');
+      return;
+    }
+
+    setLoading(true);
+    setError('');
+
+    try {
+      setResult(await importSongs(text, setlistName.trim() || null));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const imported = result !== null && (result.created > 0 || result.addedToSetlist > 0);
+
+  return (
+    <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center p-4 z-50">
+      <div className="bg-white rounded-lg max-w-2xl w-full p-6">
+        <h2 className="text-2xl font-bold text-gray-900 mb-4">楽曲をインポート</h2>
+
+        {error && (
+          <div className="mb-4 p-4 bg-red-50 border border-red-200 rounded-lg">
+            <p className="text-red-600">{error}</p>
+          </div>
+        )}
+
+        {result && (
+          <div className="mb-4 p-4 bg-green-50 border border-green-200 rounded-lg space-y-2">
+            <p className="text-green-700">
+              作成: {result.created}曲 / 登録済み: {result.skipped}曲
+              {result.setlistId && ` / セットリストに追加: ${result.addedToSetlist}曲`}
+            </p>
+            {result.errors.length > 0 && (
+              <ul className="text-sm text-red-600 max-h-32 overflow-y-auto">
+                {result.errors.map((rowError) => (
+                  <li key={rowError.line}>
+                    {rowError.line}行目: {rowError.message}
+                  </li>
+                ))}
+              </ul>
+            )}
+          </div>
+        )}
+
+        <form onSubmit={handleSubmit} className="space-y-4">
+          <div>
+            <label className="block text-sm font-medium text-gray-700 mb-1">
+              曲リスト <span className="text-red-500">*</span>
+            </label>
+            <textarea
+              value={text}
+              onChange={(e) => setText(e.target.value)}
+              rows={10}
+              className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-blue-500 text-gray-900 placeholder:text-gray-400 font-mono text-sm"
+              placeholder={'1行1曲: タイトル, アーティスト, 曲の長さ\n例: 夜に駆ける, YOASOBI, 4:21'}
+            />
+            <p className="text-xs text-gray-500 mt-1">
+              アーティスト・曲の長さ（秒数または分:秒）は省略できます。登録済みの曲（タイトル・アーティストが一致）は再利用します
+            </p>
+          </div>
+
+          <div>
+            <label className="block text-sm font-medium text-gray-700 mb-1">
+              追加先のセットリスト名
+            </label>
+            <input
+              type="text"
+              value={setlistName}
+              onChange={(e) => setSetlistName(e.target.value)}
+              className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-blue-500 text-gray-900 placeholder:text-gray-400"
+              placeholder="省略すると楽曲の登録のみ（存在しない名前は新規作成）"
+            />
+          </div>
+
+          <div className="flex justify-end gap-2 pt-4">
+            <button
+              type="button"
+              onClick={() => onClose(imported)}
+              className="px-4 py-2 border border-gray-300 text-gray-700 rounded-lg hover:bg-gray-50 transition-colors"
+              disabled={loading}
+            >
+              {result ? '閉じる' : 'キャンセル'}
+            </button>
+            <button
+              type="submit"
+              className="px-4 py-2 bg-blue-600 text-white rounded-lg hover:bg-blue-700 transition-colors disabled:opacity-50"
+              disabled={loading}
+            >
+              {loading ? 'インポート中...' : 'インポート'}
+            </button>
+          </div>
+        </form>
+      </div>
+    </div>
+  );
+}
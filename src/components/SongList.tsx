@@ -3,6 +3,7 @@ import { getSongs, deleteSong } from '../types/commands';
 import type { Song } from '../types/song';
 import { parseTags } from '../types/song';
 import { SongForm } from './SongForm';
+import { SongImportForm } from './SongImportForm';
 
 export function SongList() {
   const [songs, setSongs] = useState<Song[]>([]);
@@ -10,6 +11,7 @@ export function SongList() {
   const [error, setError] = useState<string>('');
   const [showForm, setShowForm] = useState(false);
   const [editingSong, setEditingSong] = useState<Song | null>(null);
+  const [showImport, setShowImport] = useState(false);
 
   const loadSongs = async () => {
     try {
@@ -52,6 +54,13 @@ export function SongList() {
     }
   };
 
+  const handleImportClose = async (imported: boolean) => {
+    setShowImport(false);
+    if (imported) {
+      await loadSongs();
+    }
+  };
+
   if (loading) {
     return <div className="text-gray-600">読み込み中...</div>;
   }
@@ -60,12 +69,20 @@ export function SongList() {
     <div className="space-y-4">
       <div className="flex justify-between items-center">
         <h2 className="text-2xl font-bold text-gray-900">楽曲管理</h2>
-        <button
-          onClick={() => setShowForm(true)}
-          className="px-4 py-2 bg-blue-600 text-white rounded-lg hover:bg-blue-700 transition-colors"
-        >
-          楽曲を追加
-        </button>
+        <div className="flex gap-2">
+          <button
+            onClick={() => setShowImport(true)}
+            className="px-4 py-2 border border-blue-600 text-blue-600 rounded-lg hover:bg-blue-50 transition-colors"
+          >
+            インポート
+          </button>
+          <button
+            onClick={() => setShowForm(true)}
+            className="px-4 py-2 bg-blue-600 text-white rounded-lg hover:bg-blue-700 transition-colors"
+          >
+            楽曲を追加
+          </button>
+        </div>
       </div>
 
       {error && (
@@ -167,6 +184,8 @@ export function SongList() {
           onClose={handleFormClose}
         />
       )}
+
+      {showImport && <SongImportForm onClose={handleImportClose} />}
     </div>
   );
 }
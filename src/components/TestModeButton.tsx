@@ -1,6 +1,7 @@
 import { useState } from 'react';
 import { sendTestComment } from '../types/commands';
 import type { TestMessageType } from '../types/commands';
+import { handleTauriError } from '../utils/errorMessages';
 import { SUPERCHAT_PREVIEW_EVENT, SUPERCHAT_REMOVE_PREVIEW_EVENT } from './settings/OverlayPreview';
 
 // プレビュー用スパチャペイロード型
@@ -107,7 +108,7 @@ export function TestModeButton() {
         setCommentText('');
       }, 2000);
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : String(err);
+      const errorMessage = handleTauriError(err);
       setMessage(`エラー: ${errorMessage}`);
     } finally {
       setSending(false);
@@ -1,5 +1,6 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { handleTauriError } from '../../utils/errorMessages';
 
 /**
  * YouTube APIキー設定パネル
@@ -55,7 +56,7 @@ export function ApiKeySettingsPanel() {
         setError('APIキーが無効です');
       }
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = handleTauriError(err);
       setError(`エラー: ${message}`);
     } finally {
       setLoading(false);
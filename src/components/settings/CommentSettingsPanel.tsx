@@ -1,6 +1,7 @@
 import { useState } from 'react';
 import type { CommentSettings, CommentPosition } from '../../types/overlaySettings';
 import { sendTestComment } from '../../types/commands';
+import { handleTauriError } from '../../utils/errorMessages';
 
 interface CommentSettingsPanelProps {
   settings: CommentSettings;
@@ -32,7 +33,7 @@ export function CommentSettingsPanel({ settings, onChange }: CommentSettingsPane
       setTestMessage('✓ コメントを送信しました');
       setTimeout(() => setTestMessage(''), 2000);
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : String(err);
+      const errorMessage = handleTauriError(err);
       setTestMessage(`エラー: ${errorMessage}`);
     } finally {
       setSending(false);
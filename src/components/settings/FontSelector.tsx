@@ -2,6 +2,13 @@ import { useEffect, useState, useCallback, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { FONT_PRESETS, type FontPresetName, type ThemeSettings } from '../../types/overlaySettings';
 
+/** システムフォントの情報（get_system_fonts） */
+interface SystemFont {
+  family: string;
+  monospace: boolean;
+  cjk: boolean; // 日本語（CJK）の文字を含むか
+}
+
 interface FontSelectorProps {
   themeSettings: ThemeSettings;
   onChange: (settings: ThemeSettings) => void;
@@ -78,21 +85,23 @@ async function loadGoogleFont(fontSpec: string): Promise<void> {
  * issues/013: アクセシビリティ対応（id, htmlFor, aria-label）
  */
 export function FontSelector({ themeSettings, onChange }: FontSelectorProps) {
-  const [systemFonts, setSystemFonts] = useState<string[]>([]);
+  const [systemFonts, setSystemFonts] = useState<SystemFont[]>([]);
+  const [cjkOnly, setCjkOnly] = useState(true);
   const [isLoadingFonts, setIsLoadingFonts] = useState(false);
   const [isLoadingGoogleFont, setIsLoadingGoogleFont] = useState(false);
   const [fontError, setFontError] = useState<string | null>(null);
   const fontsLoadedRef = useRef(false);
 
   // システムフォント取得（useCallbackでメモ化）
-  const loadSystemFonts = useCallback(async () => {
-    if (fontsLoadedRef.current) return; // 読み込み済み
+  // refresh: trueの場合はバックエンドのキャッシュを破棄して再取得（フォント追加後など）
+  const loadSystemFonts = useCallback(async (refresh = false) => {
+    if (fontsLoadedRef.current && !refresh) return; // 読み込み済み
 
     setIsLoadingFonts(true);
     setFontError(null);
 
     try {
-      const fonts = await invoke<string[]>('get_system_fonts');
+      const fonts = await invoke<SystemFont[]>(refresh ? 'refresh_system_fonts' : 'get_system_fonts');
       setSystemFonts(fonts);
       fontsLoadedRef.current = true;
     } catch (error) {
@@ -211,13 +220,34 @@ export function FontSelector({ themeSettings, onChange }: FontSelectorProps) {
               className="w-full px-3 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
             >
               <option value="">選択してください</option>
-              {systemFonts.map((font) => (
-                <option key={font} value={font}>
-                  {font}
-                </option>
-              ))}
+              {systemFonts
+                .filter((font) => !cjkOnly || font.cjk || font.family === themeSettings.customFontFamily)
+                .map((font) => (
+                  <option key={font.family} value={font.family}>
+                    {font.family}
+                    {font.monospace ? '（等幅）' : ''}
+                  </option>
+                ))}
             </select>
           )}
+          <div className="flex items-center justify-between">
+            <label className="flex items-center gap-2 text-sm text-gray-700">
+              <input
+                type="checkbox"
+                checked={cjkOnly}
+                onChange={(e) => setCjkOnly(e.target.checked)}
+              />
+              日本語対応フォントのみ表示
+            </label>
+            <button
+              type="button"
+              onClick={() => loadSystemFonts(true)}
+              disabled={isLoadingFonts}
+              className="text-sm text-blue-600 hover:text-blue-700 disabled:opacity-50"
+            >
+              フォント一覧を再取得
+            </button>
+          </div>
         </div>
       )}
 
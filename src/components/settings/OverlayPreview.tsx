@@ -4,6 +4,8 @@ import type { OverlaySettings } from '../../types/overlaySettings';
 import { LAYOUT_PRESETS } from '../../types/overlaySettings';
 import type { LiveStreamStats } from '../../types/weather';
 import type { WizardSettingsData } from '../../types/wizard';
+import { buildOverlayUrl, getServerAddresses, getWsAuthSettings, withOverlayToken } from '../../types/commands';
+import type { ServerAddresses } from '../../types/commands';
 
 // KPIデータ型（オーバーレイへのpostMessage用）
 interface KpiData {
@@ -38,7 +40,7 @@ const OBS_WIDTH = 1920;
 const OBS_HEIGHT = 1080;
 
 // プレビュー用定数
-const PREVIEW_ORIGIN = 'http://localhost:19800';  // iframeのorigin（postMessage送信先）
+const DEFAULT_PREVIEW_ORIGIN = 'http://localhost:19800';  // iframeのorigin（postMessage送信先、起動中のポート・TLSモードで変わる）
 const DEBOUNCE_DELAY_MS = 50;  // スライダー操作時のデバウンス遅延
 
 // スパチャプレビュー用カスタムイベント名
@@ -92,6 +94,27 @@ export function OverlayPreview({ settings, activePanel, mode = 'combined' }: Ove
   // コンポーネントマウント時に一度だけ生成（useState遅延初期化で不純関数を安全に呼び出し）
   const [cacheKey] = useState(() => Date.now().toString());
 
+  // WebSocket認証が有効な場合はプレビュー・OBS用URLにトークンを付与
+  const [authToken, setAuthToken] = useState<string | null>(null);
+  useEffect(() => {
+    getWsAuthSettings()
+      .then((auth) => setAuthToken(auth.token))
+      .catch((err) => console.error('Failed to load WebSocket auth settings:', err));
+  }, []);
+
+  // 実際に起動したポート・TLSモード（HTTPS/WSS）を反映したURLを使用
+  const [addresses, setAddresses] = useState<ServerAddresses | null>(null);
+  // イベントリスナー内から最新のoriginを参照するためのref
+  const previewOriginRef = useRef(DEFAULT_PREVIEW_ORIGIN);
+  useEffect(() => {
+    getServerAddresses()
+      .then((current) => {
+        previewOriginRef.current = current.httpBaseUrl ?? DEFAULT_PREVIEW_ORIGIN;
+        setAddresses(current);
+      })
+      .catch((err) => console.error('Failed to load server addresses:', err));
+  }, []);
+
   // previewUrl: iframeの再作成を最小限にするため、URLパラメータは最小限に
   // 他の設定（カラー、フォントサイズ等）はpostMessageで即時反映
   const previewUrl = useMemo(() => {
@@ -104,14 +127,11 @@ export function OverlayPreview({ settings, activePanel, mode = 'combined' }: Ove
       });
       // v2レイアウトの場合はcombined-v2を使用
       const endpoint = isV2Layout ? '/overlay/combined-v2' : '/overlay/combined';
-      return `http://localhost:19800${endpoint}?${params.toString()}`;
+      return withOverlayToken(buildOverlayUrl(addresses, `${endpoint}?${params.toString()}`), authToken);
     }
 
     // 個別オーバーレイ
-    const base =
-      activePanel === 'comment'
-        ? 'http://localhost:19800/overlay/comment'
-        : 'http://localhost:19800/overlay/setlist';
+    const panelEndpoint = activePanel === 'comment' ? '/overlay/comment' : '/overlay/setlist';
 
     // 個別オーバーレイも最小限のパラメータのみ
     const params = new URLSearchParams({
@@ -119,8 +139,8 @@ export function OverlayPreview({ settings, activePanel, mode = 'combined' }: Ove
       _v: cacheKey, // キャッシュバスター
     });
 
-    return `${base}?${params.toString()}`;
-  }, [settings.layout, activePanel, mode, isV2Layout, cacheKey]);
+    return withOverlayToken(buildOverlayUrl(addresses, `${panelEndpoint}?${params.toString()}`), authToken);
+  }, [settings.layout, activePanel, mode, isV2Layout, cacheKey, authToken, addresses]);
 
   // loadedUrlとpreviewUrlを比較してiframeがロード済みかを判定
   // これによりuseEffect内でsetStateを呼ぶ必要がなくなる（react-hooks/set-state-in-effect回避）
@@ -154,7 +174,7 @@ export function OverlayPreview({ settings, activePanel, mode = 'combined' }: Ove
     };
 
     // iframeのcontentWindowに送信（セキュリティ: targetOriginを明示）
-    iframeRef.current.contentWindow.postMessage(message, PREVIEW_ORIGIN);
+    iframeRef.current.contentWindow.postMessage(message, previewOriginRef.current);
   }, [debouncedSettings, iframeLoaded]);
 
   // プレビューモード時にKPIデータを取得してiframeに送信
@@ -175,7 +195,7 @@ export function OverlayPreview({ settings, activePanel, mode = 'combined' }: Ove
         iframeRef.current?.contentWindow?.postMessage({
           type: 'preview:kpi:update',
           payload: kpiData,
-        }, PREVIEW_ORIGIN);
+        }, previewOriginRef.current);
       };
 
       try {
@@ -236,7 +256,7 @@ export function OverlayPreview({ settings, activePanel, mode = 'combined' }: Ove
       iframeRef.current.contentWindow.postMessage({
         type: 'preview:superchat:add',
         payload: customEvent.detail,
-      }, PREVIEW_ORIGIN);
+      }, previewOriginRef.current);
     };
 
     const handleSuperchatRemove = (event: Event) => {
@@ -249,7 +269,7 @@ export function OverlayPreview({ settings, activePanel, mode = 'combined' }: Ove
       iframeRef.current.contentWindow.postMessage({
         type: 'preview:superchat:remove',
         payload: { id: customEvent.detail.id },
-      }, PREVIEW_ORIGIN);
+      }, previewOriginRef.current);
     };
 
     window.addEventListener(SUPERCHAT_PREVIEW_EVENT, handleSuperchatAdd);
@@ -272,11 +292,17 @@ export function OverlayPreview({ settings, activePanel, mode = 'combined' }: Ove
     ? (isV2Layout ? '3カラム統合オーバーレイ' : '統合オーバーレイ')
     : activePanel === 'comment' ? 'コメントオーバーレイ' : 'セットリストオーバーレイ';
 
-  const obsUrl = mode === 'combined'
-    ? (isV2Layout ? 'http://localhost:19800/overlay/combined-v2' : 'http://localhost:19800/overlay/combined')
-    : activePanel === 'comment'
-      ? 'http://localhost:19800/overlay/comment'
-      : 'http://localhost:19800/overlay/setlist';
+  const obsUrl = withOverlayToken(
+    buildOverlayUrl(
+      addresses,
+      mode === 'combined'
+        ? (isV2Layout ? '/overlay/combined-v2' : '/overlay/combined')
+        : activePanel === 'comment'
+          ? '/overlay/comment'
+          : '/overlay/setlist',
+    ),
+    authToken,
+  );
 
   return (
     <div className="bg-gray-900 rounded-lg overflow-hidden h-full flex flex-col">
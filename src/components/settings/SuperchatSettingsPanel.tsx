@@ -1,6 +1,7 @@
 import { useState } from 'react';
 import type { SuperchatSettings } from '../../types/overlaySettings';
 import { sendTestComment } from '../../types/commands';
+import { handleTauriError } from '../../utils/errorMessages';
 import { SUPERCHAT_PREVIEW_EVENT, SUPERCHAT_REMOVE_PREVIEW_EVENT } from './OverlayPreview';
 
 interface SuperchatSettingsPanelProps {
@@ -82,7 +83,7 @@ export function SuperchatSettingsPanel({ settings, onChange }: SuperchatSettings
       setTestMessage(`✓ ${preset.label}を送信しました`);
       setTimeout(() => setTestMessage(''), 2000);
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : String(err);
+      const errorMessage = handleTauriError(err);
       setTestMessage(`エラー: ${errorMessage}`);
     } finally {
       setSending(false);
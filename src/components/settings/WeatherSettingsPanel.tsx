@@ -10,6 +10,7 @@ import {
   type WeatherData,
   type CityTuple,
 } from '../../types/weather';
+import { handleTauriError } from '../../utils/errorMessages';
 import type {
   WeatherSettings,
   WeatherPosition,
@@ -205,7 +206,7 @@ export function WeatherSettingsPanel({ className = '', settings, onChange }: Wea
       const data = await setWeatherCityAndBroadcast(city.trim());
       setWeather(data);
     } catch (err) {
-      setError('都市名の設定に失敗しました');
+      setError(`都市名の設定に失敗しました: ${handleTauriError(err)}`);
       console.error(err);
     } finally {
       setLoading(false);
@@ -220,7 +221,7 @@ export function WeatherSettingsPanel({ className = '', settings, onChange }: Wea
       const data = await refreshWeather();
       setWeather(data);
     } catch (err) {
-      setError('天気情報の更新に失敗しました');
+      setError(`天気情報の更新に失敗しました: ${handleTauriError(err)}`);
       console.error(err);
     } finally {
       setLoading(false);
@@ -237,7 +238,7 @@ export function WeatherSettingsPanel({ className = '', settings, onChange }: Wea
 
       await broadcastWeather();
     } catch (err) {
-      setError('配信に失敗しました');
+      setError(`配信に失敗しました: ${handleTauriError(err)}`);
       console.error(err);
     } finally {
       setLoading(false);
@@ -308,7 +309,7 @@ export function WeatherSettingsPanel({ className = '', settings, onChange }: Wea
         showSuccessInfo(`${successCount}都市の天気を取得しました`);
       }
     } catch (err) {
-      setError('マルチシティ配信に失敗しました');
+      setError(`マルチシティ配信に失敗しました: ${handleTauriError(err)}`);
       console.error(err);
     } finally {
       setLoading(false);
@@ -459,7 +460,7 @@ export function WeatherSettingsPanel({ className = '', settings, onChange }: Wea
                 <div className="flex items-center gap-4">
                   <span className="text-4xl">{weather.icon}</span>
                   <div>
-                    <p className="text-2xl font-bold">{weather.temp.toFixed(1)}°C</p>
+                    <p className="text-2xl font-bold">{weather.temp.toFixed(1)}{weather.tempUnit}</p>
                     <p className="text-sm text-gray-600">{weather.description}</p>
                     <p className="text-xs text-gray-500">{weather.location}</p>
                   </div>
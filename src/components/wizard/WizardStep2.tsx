@@ -1,5 +1,6 @@
 import { useState, useEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { handleTauriError } from '../../utils/errorMessages';
 
 interface WizardStep2Props {
   apiKey: string;
@@ -67,7 +68,7 @@ export default function WizardStep2({
         }
       } catch (err) {
         if (!currentController.signal.aborted) {
-          const errorMessage = err instanceof Error ? err.message : String(err);
+          const errorMessage = handleTauriError(err);
           console.error('Error fetching chat ID:', errorMessage);
           setError(`エラー: ${errorMessage}`);
           onLiveChatIdChange(null);
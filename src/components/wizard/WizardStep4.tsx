@@ -1,19 +1,38 @@
-import { useState } from 'react';
+import { useEffect, useState } from 'react';
 import { open } from '@tauri-apps/plugin-shell';
+import { buildOverlayUrl, getServerAddresses, getWsAuthSettings, withOverlayToken } from '../../types/commands';
+import type { ServerAddresses } from '../../types/commands';
 
 export default function WizardStep4() {
   const [copiedUrl, setCopiedUrl] = useState<string | null>(null);
   const [error, setError] = useState<string | null>(null);
+  // WebSocket認証が有効な場合はURLにトークンを付与
+  const [authToken, setAuthToken] = useState<string | null>(null);
+
+  useEffect(() => {
+    getWsAuthSettings()
+      .then((settings) => setAuthToken(settings.token))
+      .catch((err) => console.error('Failed to load WebSocket auth settings:', err));
+  }, []);
+
+  // 実際に起動したポート・TLSモード（HTTPS/WSS）を反映したURLを案内
+  const [addresses, setAddresses] = useState<ServerAddresses | null>(null);
+  useEffect(() => {
+    getServerAddresses()
+      .then(setAddresses)
+      .catch((err) => console.error('Failed to load server addresses:', err));
+  }, []);
+  const serverError = addresses?.httpError ?? addresses?.wsError;
 
   const overlayUrls = [
     {
       name: 'コメント表示オーバーレイ',
-      url: 'http://localhost:19800/overlay/comment',
+      url: withOverlayToken(buildOverlayUrl(addresses, '/overlay/comment'), authToken),
       description: 'ライブチャットのコメントを表示します',
     },
     {
       name: 'セットリスト表示オーバーレイ',
-      url: 'http://localhost:19800/overlay/setlist',
+      url: withOverlayToken(buildOverlayUrl(addresses, '/overlay/setlist'), authToken),
       description: '演奏曲のセットリストを表示します',
     },
   ];
@@ -49,6 +68,13 @@ export default function WizardStep4() {
         OBS Studioでオーバーレイを表示するための設定方法です。
       </p>
 
+      {/* サーバーを起動できなかった場合（ポートが使用中等） */}
+      {serverError && (
+        <div className="mb-4 p-3 bg-red-50 border border-red-200 rounded-lg text-red-700 text-sm">
+          オーバーレイ用サーバーを起動できませんでした: {serverError}
+        </div>
+      )}
+
       {/* エラー表示 */}
       {error && (
         <div className="mb-4 p-3 bg-red-50 border border-red-200 rounded-lg text-red-700 text-sm">
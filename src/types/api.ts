@@ -51,8 +51,21 @@ export interface OfficialStatusEvent {
   stopped?: boolean;
   reason?: string;
   retrying?: boolean;
+  /** 一時的なエラーかどうか（trueなら再接続中として表示できる） */
+  recoverable?: boolean;
   quotaExceeded?: boolean;
+  /** クォータ超過・レート制限のためセカンダリキーに切り替えたか（reasonに理由） */
+  keySwitched?: boolean;
+  /** 残りクォータが少ないためポーリング間隔を延ばしたか（pollingIntervalMillisに延長後の間隔） */
+  quotaWarning?: boolean;
+  pollingIntervalMillis?: number;
   streamEnded?: boolean;
+  /** 配信が一時的に見つからないためライブチャットIDを再取得中か（attempt/maxAttemptsに試行回数） */
+  reconnecting?: boolean;
+  attempt?: number;
+  maxAttempts?: number;
+  /** 一時停止中かどうか（pause_polling/resume_polling） */
+  paused?: boolean;
   quotaUsed?: number;
   remainingQuota?: number;
   pollCount?: number;
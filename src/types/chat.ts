@@ -3,8 +3,8 @@
  */
 export type MessageType =
   | { type: 'text' }
-  | { type: 'superChat'; amount: string; currency: string }
-  | { type: 'superSticker'; stickerId: string }
+  | { type: 'superChat'; amount: string; currency: string; amountMicros?: number }
+  | { type: 'superSticker'; stickerId: string; amount?: string; currency?: string; amountMicros?: number; imageUrl?: string }
   | { type: 'membership'; level: string }
   | { type: 'membershipGift'; count: number };
 
@@ -22,5 +22,7 @@ export interface ChatMessage {
   isModerator: boolean;
   isMember: boolean;
   isVerified: boolean;
+  /** 投稿者のバッジ種別（'owner' | 'moderator' | 'member' | 'verified'、InnerTubeの未知のバッジはアイコン種別を小文字で） */
+  badges?: string[];
   messageType: MessageType;
 }
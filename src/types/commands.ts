@@ -1,6 +1,8 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { Song, CreateSongInput, UpdateSongInput } from './song';
-import type { Setlist, SetlistWithSongs, CreateSetlistInput } from './setlist';
+import type { Song, CreateSongInput, UpdateSongInput, SongImportResult } from './song';
+import type { Setlist, SetlistWithSongs, CreateSetlistInput, SongPlay } from './setlist';
+import type { SlotId } from './slot';
+import type { ChatMessage } from './chat';
 
 // Song commands
 export const getSongs = () => invoke<Song[]>('get_songs');
@@ -14,6 +16,10 @@ export const updateSong = (input: UpdateSongInput) =>
 export const deleteSong = (id: string) =>
   invoke<void>('delete_song', { id });
 
+/** テキスト / CSV（タイトル, アーティスト, 曲の長さ）から楽曲をインポート（セットリスト名を指定すると末尾に追加） */
+export const importSongs = (text: string, setlistName?: string | null) =>
+  invoke<SongImportResult>('import_songs', { text, setlist_name: setlistName ?? null });
+
 // Setlist commands
 export const getSetlists = () => invoke<Setlist[]>('get_setlists');
 
@@ -37,14 +43,23 @@ export const removeSongFromSetlist = (setlistId: string, setlistSongId: string)
 export const setCurrentSong = (setlistId: string, position: number) =>
   invoke<void>('set_current_song', { setlist_id: setlistId, position });
 
+/** 曲順の何番目か（0始まり）を指定して現在の曲にジャンプ */
+export const jumpToSongIndex = (setlistId: string, index: number) =>
+  invoke<void>('jump_to_song_index', { setlist_id: setlistId, index });
+
 export const nextSong = (setlistId: string) =>
   invoke<void>('next_song', { setlist_id: setlistId });
 
 export const previousSong = (setlistId: string) =>
   invoke<void>('previous_song', { setlist_id: setlistId });
 
+/** 歌唱履歴を古い順に取得（from以上・to未満、RFC3339。省略した側は制限しない） */
+export const getPlayHistory = (from?: string | null, to?: string | null) =>
+  invoke<SongPlay[]>('get_play_history', { from: from ?? null, to: to ?? null });
+
+/** セットリストの全曲を並び替え（並び替え後の曲順を返す） */
 export const reorderSetlistSongs = (setlistId: string, setlistSongIds: string[]) =>
-  invoke<void>('reorder_setlist_songs', { setlist_id: setlistId, setlist_song_ids: setlistSongIds });
+  invoke<string[]>('reorder_setlist_songs', { setlist_id: setlistId, setlist_song_ids: setlistSongIds });
 
 export const broadcastSetlistUpdate = (setlistId: string) =>
   invoke<void>('broadcast_setlist_update', { setlist_id: setlistId });
@@ -59,3 +74,649 @@ export const sendTestComment = (
   amount?: string
 ) =>
   invoke<void>('send_test_comment', { comment_text: commentText, author_name: authorName, message_type_name: messageTypeName, amount });
+
+/** ダミーのスパチャをスパチャ専用ウィジェットに送信（tier指定時はそのTierの色・表示時間で表示） */
+export const sendTestSuperchat = (
+  amount: string,
+  currency: string,
+  authorName: string,
+  message: string,
+  tier?: number
+) =>
+  invoke<void>('send_test_superchat', { amount, currency, author_name: authorName, message, tier });
+
+// Superchat display duration commands
+
+/** スパチャ表示設定（tierDurationsMs[0]がTier 1、[6]がTier 7） */
+export interface SuperchatConfig {
+  tierDurationsMs: [number, number, number, number, number, number, number];
+  /** 同一投稿者のクールダウン（ミリ秒、0で無効、最大10分） */
+  authorCooldownMs: number;
+}
+
+/** Tier別の表示時間・投稿者クールダウンを保存（表示時間は各1秒〜30分、ミリ秒） */
+export const setSuperchatDurations = (config: SuperchatConfig) =>
+  invoke<SuperchatConfig>('set_superchat_durations', { config });
+
+export const getSuperchatDurations = () =>
+  invoke<SuperchatConfig>('get_superchat_durations');
+
+/** Tier判定閾値: [日本円換算の下限額, Tier]（金額の降順） */
+export type TierThreshold = [minJpy: number, tier: number];
+
+/** Tier判定閾値を保存（空配列でデフォルトに戻す） */
+export const setTierThresholds = (thresholds: TierThreshold[]) =>
+  invoke<TierThreshold[]>('set_tier_thresholds', { thresholds });
+
+export const getTierThresholds = () =>
+  invoke<TierThreshold[]>('get_tier_thresholds');
+
+/** Tier閾値チェックの警告（YouTube公式の金額帯との比較） */
+export interface ConfigWarning {
+  kind: 'missingTier' | 'duplicateTier' | 'outOfOrder' | 'divergent';
+  tier: number;
+  message: string;
+}
+
+/** Tier閾値をチェック（省略時は現在有効な閾値） */
+export const validateSuperchatConfig = (thresholds?: TierThreshold[]) =>
+  invoke<ConfigWarning[]>('validate_superchat_config', { thresholds: thresholds ?? null });
+
+/** スパチャウィジェットの表示先slotを保存（デフォルト: left.lower） */
+export const setSuperchatSlot = (slot: SlotId) =>
+  invoke<SlotId>('set_superchat_slot', { slot });
+
+export const getSuperchatSlot = () =>
+  invoke<SlotId>('get_superchat_slot');
+
+/** 表示待ちのスパチャ件数（表示中のスパチャは含まない） */
+export const getSuperchatQueueLength = () =>
+  invoke<number>('superchat_queue_length');
+
+// Chat settings commands
+
+/** チャット設定（公式APIの切り替えイベントから追跡） */
+export interface ChatSettings {
+  membersOnly: boolean;
+}
+
+export const getChatSettings = () =>
+  invoke<ChatSettings>('get_chat_settings');
+
+/** チャット種別ラベル（新規メンバー・ギフト等）の表示言語 */
+export type ChatLabelLocale = 'ja' | 'en';
+
+/** チャット種別ラベルの表示言語を保存（以降に受信したメッセージから適用） */
+export const setChatLabelLocale = (locale: ChatLabelLocale) =>
+  invoke<void>('set_chat_label_locale', { locale });
+
+/** チャット種別ラベルの表示言語を取得 */
+export const getChatLabelLocale = () =>
+  invoke<ChatLabelLocale>('get_chat_label_locale');
+
+/** 1日のクォータ予算（units）を保存（残りが少なくなると公式APIのポーリング間隔を延ばす） */
+export const setQuotaBudget = (budget: number) =>
+  invoke<void>('set_quota_budget', { budget });
+
+/** 1日のクォータ予算（units）を取得 */
+export const getQuotaBudget = () =>
+  invoke<number>('get_quota_budget');
+
+/** InnerTubeの重複排除設定 */
+export interface DedupeSettings {
+  /** IDの保持期間（分、1〜180） */
+  windowMinutes: number;
+  /** 保持するIDの最大件数（1,000〜200,000） */
+  maxIds: number;
+}
+
+/** InnerTubeの重複排除の統計 */
+export interface DedupeStats {
+  /** ポーリング開始から重複として除外したメッセージ数 */
+  suppressedDuplicates: number;
+  /** 現在保持しているIDの件数 */
+  trackedIds: number;
+  settings: DedupeSettings;
+}
+
+/** InnerTubeの重複排除設定を保存（実行中のポーリングにも次回の取得から適用） */
+export const setDedupeSettings = (settings: DedupeSettings) =>
+  invoke<void>('set_dedupe_settings', { settings });
+
+/** InnerTubeの重複排除設定を取得 */
+export const getDedupeSettings = () =>
+  invoke<DedupeSettings>('get_dedupe_settings');
+
+/** InnerTubeの重複排除の統計を取得 */
+export const getDedupeStats = () =>
+  invoke<DedupeStats>('get_dedupe_stats');
+
+/** カスタム絵文字（メンバースタンプ） */
+export interface EmojiInfo {
+  emoji_id: string;
+  /** ショートカット（`:_xxx:`形式） */
+  shortcuts: string[];
+  image: { thumbnails: { url: string; width: number; height: number }[] };
+  is_custom_emoji: boolean;
+}
+
+/** 配信チャンネルのカスタム絵文字のカタログを取得（カタログがない場合は空。ポーリング開始時には自動で登録される） */
+export const fetchEmojiCatalog = (videoId: string) =>
+  invoke<EmojiInfo[]>('fetch_emoji_catalog', { video_id: videoId });
+
+/** InnerTubeのUser-Agent・クライアントバージョンの上書き（未指定は組み込みの値） */
+export interface InnerTubeOverrides {
+  /** User-Agent（512文字以内） */
+  userAgent?: string | null;
+  /** クライアントバージョン（X.YYYYMMDD.XX.XX形式、未指定はページから抽出した値） */
+  clientVersion?: string | null;
+}
+
+/** InnerTubeの上書きを保存し、正規化後の値を返す（次にポーリングを開始したときから適用） */
+export const setInnerTubeOverrides = (overrides: InnerTubeOverrides) =>
+  invoke<InnerTubeOverrides>('set_innertube_overrides', { overrides });
+
+/** InnerTubeの上書きを解除して組み込みの値に戻す */
+export const resetInnerTubeOverrides = () =>
+  invoke<void>('reset_innertube_overrides');
+
+export const getInnerTubeOverrides = () =>
+  invoke<InnerTubeOverrides>('get_innertube_overrides');
+
+/** InnerTube接続の診断で失敗した段階 */
+export type InnerTubeDiagnosticStep = 'client' | 'live_chat_page' | 'continuation' | 'chat_fetch';
+
+/** InnerTube接続の診断結果（APIキー・continuationトークン自体は含まない） */
+export interface InnerTubeDiagnostics {
+  videoId: string;
+  /** ライブチャットページのHTTPステータス */
+  pageStatus: number | null;
+  apiKeyExtracted: boolean;
+  /** continuationトークンを抽出したパターン */
+  continuationSource: 'invalidation' | 'timed' | 'reload' | 'generic' | null;
+  clientVersion: string | null;
+  clientVersionSource: 'settings' | 'page' | 'fallback' | null;
+  userAgentOverridden: boolean;
+  emojiCatalogSize: number;
+  /** チャット取得のHTTPステータス */
+  chatStatus: number | null;
+  messageCount: number | null;
+  emojiCount: number | null;
+  /** 失敗した段階（全て成功した場合はnull） */
+  failedStep: InnerTubeDiagnosticStep | null;
+  error: string | null;
+}
+
+/** InnerTube接続を診断（不具合報告用） */
+export const diagnoseInnerTube = (videoId: string) =>
+  invoke<InnerTubeDiagnostics>('diagnose_innertube', { video_id: videoId });
+
+// Comment blocklist commands
+
+/** ブロックリスト（1行1パターン、`/pattern/`形式は正規表現）を保存 */
+export const setCommentBlocklist = (blocklist: string) =>
+  invoke<void>('set_comment_blocklist', { blocklist });
+
+export const getCommentBlocklist = () =>
+  invoke<string>('get_comment_blocklist');
+
+/** チャンネルをBAN（コメントをオーバーレイ・UIに表示しない。DBには保存される） */
+export const banChannel = (channelId: string) =>
+  invoke<void>('ban_channel', { channel_id: channelId });
+
+export const unbanChannel = (channelId: string) =>
+  invoke<void>('unban_channel', { channel_id: channelId });
+
+export const listBannedChannels = () =>
+  invoke<string[]>('list_banned_channels');
+
+/** 不適切語マスクの設定（一致した語を同じ文字数の`*`に置き換えて配信） */
+export interface ProfanityMaskConfig {
+  enabled: boolean;
+  /** 有効にする組み込みの語リストの言語 */
+  builtinLocales: ChatLabelLocale[];
+  /** 追加の語（大文字小文字を区別しない） */
+  words: string[];
+}
+
+/** 不適切語マスクの設定を保存（DBには元の本文を保存する） */
+export const setProfanityMaskConfig = (config: ProfanityMaskConfig) =>
+  invoke<void>('set_profanity_mask_config', { config });
+
+export const getProfanityMaskConfig = () =>
+  invoke<ProfanityMaskConfig>('get_profanity_mask_config');
+
+/** コメントの不適切度（comment:addのseverity） */
+export type CommentSeverity = 'clean' | 'mild' | 'strong';
+
+/** 重み付きの語 */
+export interface SeverityWord {
+  word: string;
+  /** 重み（0〜10、0は判定に使わない。組み込みの語と同じ語は上書き） */
+  weight: number;
+}
+
+/** 不適切度判定の設定（一致した語の重みの合計で判定） */
+export interface SeverityConfig {
+  enabled: boolean;
+  /** 有効にする組み込みの語リストの言語 */
+  builtinLocales: ChatLabelLocale[];
+  /** 追加の語と重み（最大500件） */
+  words: SeverityWord[];
+  /** mildと判定する重みの合計（1以上） */
+  mildThreshold: number;
+  /** strongと判定する重みの合計（mildThreshold以上、100以下） */
+  strongThreshold: number;
+}
+
+/** 不適切度判定の設定を保存（以降に取得したコメントから適用） */
+export const setSeverityConfig = (config: SeverityConfig) =>
+  invoke<void>('set_severity_config', { config });
+
+export const getSeverityConfig = () =>
+  invoke<SeverityConfig>('get_severity_config');
+
+// Comment log export commands
+
+/** コメントログのエクスポート形式（jsonはNDJSON） */
+export type CommentLogExportFormat = 'csv' | 'json';
+
+/** コメントログをファイルに書き出し、件数を返す（start/endはRFC3339、省略で全期間） */
+export const exportCommentLogs = (
+  format: CommentLogExportFormat,
+  path: string,
+  start?: string,
+  end?: string,
+) => invoke<number>('export_comment_logs', { format, path, start: start ?? null, end: end ?? null });
+
+// Database backup commands
+
+/** DB全体のバックアップを作成（既存ファイルは上書きしない） */
+export const backupDatabase = (destPath: string) =>
+  invoke<void>('backup_database', { dest_path: destPath });
+
+/** バックアップからDBを復元（スキーマを検証し、アプリの再起動後に適用） */
+export const restoreDatabase = (srcPath: string) =>
+  invoke<void>('restore_database', { src_path: srcPath });
+
+// Settings export/import commands
+
+/** 設定のインポート結果（ドライランの場合は変更される内容） */
+export interface SettingsImportReport {
+  added: string[];
+  updated: string[];
+  unchanged: string[];
+  /** 実際に反映したか（ドライランの場合はfalse） */
+  applied: boolean;
+}
+
+/** 設定をJSONファイルに書き出し、件数を返す（APIキーは含めない、既存ファイルは上書きしない） */
+export const exportSettings = (destPath: string) =>
+  invoke<number>('export_settings', { dest_path: destPath });
+
+/** JSONファイルから設定をインポート（dryRunで変更内容のみ確認、反映は再起動後） */
+export const importSettings = (srcPath: string, dryRun: boolean) =>
+  invoke<SettingsImportReport>('import_settings', { src_path: srcPath, dry_run: dryRun });
+
+// Comment log retention commands
+
+/** コメントログの保持日数を保存（0で無期限、デフォルト30日） */
+export const setCommentLogRetentionDays = (days: number) =>
+  invoke<void>('set_comment_log_retention_days', { days });
+
+export const getCommentLogRetentionDays = () =>
+  invoke<number>('get_comment_log_retention_days');
+
+/** 保持日数を過ぎたコメントログを削除し、削除件数を返す */
+export const pruneCommentLogs = () =>
+  invoke<number>('prune_comment_logs');
+
+/** コメント数ヒストグラムの1区間 */
+export interface CommentHistogramBucket {
+  /** 区間の開始時刻（UTC、RFC3339） */
+  bucketStart: string;
+  count: number;
+}
+
+/** 時間帯ごとのコメント数を取得（bucketSeconds: 1〜86400秒、since: RFC3339） */
+export const getCommentHistogram = (bucketSeconds: number, since?: string) =>
+  invoke<CommentHistogramBucket[]>('get_comment_histogram', {
+    bucket_seconds: bucketSeconds,
+    since: since ?? null,
+  });
+
+/** コメントログの書き込みキューの状態 */
+export interface CommentWriteQueueStats {
+  /** 書き込み待ちの件数 */
+  pending: number;
+  /** キューが満杯のため保存せずに破棄したコメント数（起動からの累計） */
+  droppedComments: number;
+}
+
+export const getCommentWriteQueueStats = () =>
+  invoke<CommentWriteQueueStats>('get_comment_write_queue_stats');
+
+// Overlay connection commands
+
+/** 接続中のオーバーレイ（WebSocketクライアント） */
+export interface ConnectedOverlay {
+  peerId: number;
+  /** 接続元アドレス */
+  remoteAddr: string;
+  /** 接続時刻（RFC3339） */
+  connectedAt: string;
+  /** 最後にPongを受信した時刻（RFC3339） */
+  lastPong: string | null;
+  /** 購読トピック（全メッセージを受信する場合はnull） */
+  topics: string[] | null;
+}
+
+/** 接続中のオーバーレイ一覧を取得 */
+export const getConnectedOverlays = () =>
+  invoke<ConnectedOverlay[]>('get_connected_overlays');
+
+/** 接続中のオーバーレイ数を取得（Ping/Pongに応答しない接続は切断済み） */
+export const connectedOverlayCount = () =>
+  invoke<number>('connected_overlay_count');
+
+/** WebSocket認証の設定 */
+export interface WsAuthSettings {
+  enabled: boolean;
+  /** オーバーレイURLに`?token=`として付与するトークン（無効の場合はnull） */
+  token: string | null;
+}
+
+export const getWsAuthSettings = () =>
+  invoke<WsAuthSettings>('get_ws_auth_settings');
+
+/** WebSocket認証の有効/無効を保存（有効にすると接続中のオーバーレイは再接続が必要） */
+export const setWsAuthEnabled = (enabled: boolean) =>
+  invoke<WsAuthSettings>('set_ws_auth_enabled', { enabled });
+
+/** WebSocket認証トークンを再生成（OBSのURLを更新する必要がある） */
+export const rotateWsAuthToken = () =>
+  invoke<string>('rotate_ws_auth_token');
+
+/** オーバーレイ配信のTLS（HTTPS/WSS）設定 */
+export interface ServerTlsSettings {
+  /** 保存済みの設定（次回起動時に反映） */
+  enabled: boolean;
+  /** 起動中のサーバーがTLSで配信しているか */
+  active: boolean;
+  /** 起動中のサーバーのオーバーレイページのベースURL（例: https://localhost:19800、起動できなかった場合はnull） */
+  httpBaseUrl: string | null;
+  /** 起動中のサーバーのWebSocket URL（例: wss://localhost:19801/ws、起動できなかった場合はnull） */
+  wsUrl: string | null;
+  /** 自己署名証明書のパス（未生成の場合はnull） */
+  certPath: string | null;
+}
+
+export const getServerTlsSettings = () =>
+  invoke<ServerTlsSettings>('get_server_tls_settings');
+
+/** セルフテストの1項目の結果 */
+export interface SelfTestCheck {
+  ok: boolean;
+  /** 結果の説明（失敗時は原因） */
+  message: string;
+  elapsedMs: number;
+}
+
+/** セルフテストの結果 */
+export interface SelfTestReport {
+  /** すべての確認に成功したか */
+  ok: boolean;
+  /** HTTPサーバー（/api/health）の応答 */
+  http: SelfTestCheck;
+  /** WebSocketの送受信（自分自身に接続して確認用メッセージを受信） */
+  websocket: SelfTestCheck;
+  /** DBへの書き込み */
+  database: SelfTestCheck;
+}
+
+/** 配信前のセルフテスト（HTTP・WebSocket・DB）を実行 */
+export const runSelfTest = () =>
+  invoke<SelfTestReport>('run_self_test');
+
+/** スパチャの支援者ランキングの1件 */
+export interface LeaderboardEntry {
+  authorName: string;
+  authorChannelId: string;
+  /** スパチャ合計（日本円換算） */
+  totalJpy: number;
+  count: number;
+}
+
+/** 配信セッションのスパチャ支援者ランキング */
+export interface Leaderboard {
+  /** 配信セッションID（getSessionSuperchatHistoryのsessionId。セッションの記録に失敗した場合はnull） */
+  sessionId: number | null;
+  /** 合計額の多い順 */
+  entries: LeaderboardEntry[];
+}
+
+/** スパチャの支援者ランキングを取得（sessionId未指定時は現在のセッション） */
+export const getSuperchatLeaderboard = (limit?: number, sessionId?: number) =>
+  invoke<Leaderboard>('get_superchat_leaderboard', {
+    limit: limit ?? null,
+    session_id: sessionId ?? null,
+  });
+
+/** スパチャの目標金額の設定 */
+export interface SuperchatGoal {
+  enabled: boolean;
+  /** 目標金額（円、1〜100,000,000） */
+  targetJpy: number;
+  /** 表示ラベル（50文字以内） */
+  label: string;
+  /** 集計開始時刻（RFC3339、保存時に未指定の場合は保存した時刻） */
+  startedAt?: string | null;
+  /** 配信開始（ポーリング開始）時に合計をリセットするか */
+  resetOnStreamStart: boolean;
+  /** 新規メンバー・メンバーシップギフトも合計に加算するか */
+  countMemberships: boolean;
+  /** メンバーシップ1件あたりの金額（円） */
+  membershipValueJpy: number;
+}
+
+/** スパチャの目標金額の設定と現在の合計 */
+export interface SuperchatGoalStatus {
+  goal: SuperchatGoal;
+  /** 集計開始以降の合計（日本円換算） */
+  currentJpy: number;
+}
+
+/** スパチャの目標金額を保存（集計開始時刻以降の合計を集計し直してオーバーレイに配信） */
+export const setSuperchatGoal = (goal: SuperchatGoal) =>
+  invoke<SuperchatGoalStatus>('set_superchat_goal', { goal });
+
+export const getSuperchatGoal = () =>
+  invoke<SuperchatGoalStatus>('get_superchat_goal');
+
+/** コメント翻訳の設定（有効時はコメント本文が外部の翻訳APIに送信される） */
+export interface TranslationSettings {
+  enabled: boolean;
+  /** 翻訳先言語（"ja" "en" "zh-TW" 等） */
+  targetLanguage: string;
+  /** 翻訳APIのURL（デフォルト: Google翻訳のWeb用エンドポイント。非公開APIのため予告なく使えなくなる場合がある） */
+  apiUrl: string;
+}
+
+export const getTranslationSettings = () =>
+  invoke<TranslationSettings>('get_translation_settings');
+
+export const setTranslationSettings = (settings: TranslationSettings) =>
+  invoke<void>('set_translation_settings', { settings });
+
+/** 固定表示（コメントまたは告知、同時に1つ） */
+export interface PinnedMessage {
+  /** コメントの場合はメッセージID、告知の場合は生成したID */
+  id: string;
+  kind: 'comment' | 'announcement';
+  /** 表示する本文 */
+  text: string;
+  /** 固定したコメント（告知の場合は省略） */
+  comment?: ChatMessage;
+  pinnedAt: string;
+}
+
+/** 保存済みのコメントを固定表示（前の固定を置き換える） */
+export const pinComment = (messageId: string) =>
+  invoke<PinnedMessage>('pin_comment', { message_id: messageId });
+
+/** 任意の告知を固定表示（200文字以内、前の固定を置き換える） */
+export const announce = (text: string) =>
+  invoke<PinnedMessage>('announce', { text });
+
+export const unpinMessage = () =>
+  invoke<void>('unpin_message');
+
+export const getPinnedMessage = () =>
+  invoke<PinnedMessage | null>('get_pinned_message');
+
+/** オーバーレイ配信のTLSの有効/無効を保存（アプリ再起動後に反映） */
+export const setServerTlsEnabled = (enabled: boolean) =>
+  invoke<ServerTlsSettings>('set_server_tls_enabled', { enabled });
+
+/** 起動中のHTTP/WebSocketサーバーのアドレス */
+export interface ServerAddresses {
+  /** TLS（HTTPS/WSS）で配信しているか */
+  tls: boolean;
+  /** 待ち受けアドレス（例: 127.0.0.1、0.0.0.0） */
+  bindAddress: string;
+  /** HTTPサーバーのポート（起動できなかった場合はnull） */
+  httpPort: number | null;
+  /** WebSocketサーバーのポート（起動できなかった場合はnull） */
+  wsPort: number | null;
+  /** オーバーレイページのベースURL（例: http://localhost:19800） */
+  httpBaseUrl: string | null;
+  /** WebSocketのURL（例: ws://localhost:19801/ws） */
+  wsUrl: string | null;
+  /** HTTPサーバーを起動できなかった理由 */
+  httpError: string | null;
+  /** WebSocketサーバーを起動できなかった理由 */
+  wsError: string | null;
+}
+
+/** サーバーのポート設定 */
+export interface ServerPorts {
+  http: number;
+  ws: number;
+}
+
+/** 起動中のサーバーのアドレス（保存済みのポートが使用中の場合は実際に起動したポート） */
+export const getServerAddresses = () =>
+  invoke<ServerAddresses>('get_server_addresses');
+
+export const getServerPorts = () =>
+  invoke<ServerPorts>('get_server_ports');
+
+/** サーバーのポート設定を保存（1024〜65535、アプリ再起動後に反映） */
+export const setServerPorts = (httpPort: number, wsPort: number) =>
+  invoke<void>('set_server_ports', { http_port: httpPort, ws_port: wsPort });
+
+/** 保存済みのサーバーの待ち受けアドレス（デフォルト: 127.0.0.1） */
+export const getServerBindAddress = () =>
+  invoke<string>('get_server_bind_address');
+
+/** サーバーの待ち受けアドレスを保存（別PCのOBSから参照する場合は0.0.0.0等、アプリ再起動後に反映） */
+export const setServerBindAddress = (address: string) =>
+  invoke<void>('set_server_bind_address', { address });
+
+/** CORSで追加許可しているオリジン（ループバックのオリジンは常に許可） */
+export const getCorsAllowedOrigins = () =>
+  invoke<string[]>('get_cors_allowed_origins');
+
+/** CORSで追加許可するオリジンを保存（http(s)://ホスト[:ポート]形式、最大20件、即時反映） */
+export const setCorsAllowedOrigins = (origins: string[]) =>
+  invoke<string[]>('set_cors_allowed_origins', { origins });
+
+/** オーバーレイページのデフォルトのベースURL（サーバーのアドレス取得前の表示用） */
+export const DEFAULT_OVERLAY_BASE_URL = 'http://localhost:19800';
+const DEFAULT_WS_PORT = 19801;
+
+/** 起動中のサーバーのオーバーレイURLを組み立て（WebSocketがデフォルト以外のポートの場合は ?wsPort= を付与） */
+export const buildOverlayUrl = (addresses: ServerAddresses | null, path: string) => {
+  const url = `${addresses?.httpBaseUrl ?? DEFAULT_OVERLAY_BASE_URL}${path}`;
+  const wsPort = addresses?.wsPort;
+  if (!wsPort || wsPort === DEFAULT_WS_PORT) return url;
+  const separator = url.includes('?') ? '&' : '?';
+  return `${url}${separator}wsPort=${wsPort}`;
+};
+
+/** オーバーレイURLに認証トークンを付与（トークンがない場合はそのまま） */
+export const withOverlayToken = (url: string, token: string | null | undefined) => {
+  if (!token) return url;
+  const separator = url.includes('?') ? '&' : '?';
+  return `${url}${separator}token=${encodeURIComponent(token)}`;
+};
+
+/** 設定済みのYouTube APIキーの取得元 */
+export interface ApiKeySources {
+  /** 同梱キーがビルドに含まれているか（フォーク等では含まれない） */
+  bundled: boolean;
+  /** BYOK（ユーザー提供キー）が設定されているか */
+  byok: boolean;
+  /** 環境変数VTUBER_YT_API_KEYが設定されているか */
+  env: boolean;
+}
+
+export const getApiKeySources = () =>
+  invoke<ApiKeySources>('get_api_key_sources');
+
+/** 保存したウィザード設定（動画ID等）の有効期限（時間、0〜720、0で無期限） */
+export const getWizardSettingsExpiryHours = () =>
+  invoke<number>('get_wizard_settings_expiry_hours');
+
+export const setWizardSettingsExpiryHours = (hours: number) =>
+  invoke<void>('set_wizard_settings_expiry_hours', { hours });
+
+/** オーバーレイ再接続時に再送するコメント・表示中スパチャの件数（0〜200、0で再送しない） */
+export const getReplayBufferSize = () =>
+  invoke<number>('get_replay_buffer_size');
+
+export const setReplayBufferSize = (size: number) =>
+  invoke<void>('set_replay_buffer_size', { size });
+
+/** アバター画像のない投稿者にidenticonを割り当てるか（未設定時は有効） */
+export const getFallbackAvatarEnabled = () =>
+  invoke<boolean>('get_fallback_avatar_enabled');
+
+export const setFallbackAvatarEnabled = (enabled: boolean) =>
+  invoke<void>('set_fallback_avatar_enabled', { enabled });
+
+/** チャットの流速（chat:rateのpayloadと同じ形式） */
+export interface ChatRate {
+  /** 1分あたりのコメント数（集計期間のコメント数から換算） */
+  messagesPerMinute: number;
+  /** 集計期間内のコメント数 */
+  count: number;
+  /** 集計期間（秒） */
+  windowSecs: number;
+}
+
+export const getChatRate = () =>
+  invoke<ChatRate>('get_chat_rate');
+
+/** チャット流速の集計期間（秒、10〜600） */
+export const getChatRateWindow = () =>
+  invoke<number>('get_chat_rate_window');
+
+export const setChatRateWindow = (windowSecs: number) =>
+  invoke<void>('set_chat_rate_window', { window_secs: windowSecs });
+
+/** コメント配信のスローモード（1秒あたりの最大件数、0は無効・最大50） */
+export const getCommentThrottle = () =>
+  invoke<number>('get_comment_throttle');
+
+export const setCommentThrottle = (rate: number) =>
+  invoke<void>('set_comment_throttle', { rate });
+
+/** 初見の判定範囲（session: 今回の配信, all_time: これまで全体） */
+export type FirstTimeScope = 'session' | 'all_time';
+
+export const getFirstTimeScope = () =>
+  invoke<FirstTimeScope>('get_first_time_scope');
+
+export const setFirstTimeScope = (scope: FirstTimeScope) =>
+  invoke<void>('set_first_time_scope', { scope });
@@ -261,6 +261,8 @@ export interface WidgetVisibilitySettings {
 
 // オーバーレイ設定全体
 export interface OverlaySettings {
+  /** 保存形式のバージョン（保存時にバックエンドが付与するため、フロントエンドからは省略してよい） */
+  version?: number;
   theme: ThemeName;
   layout: LayoutPreset;
   common: CommonSettings;
@@ -329,3 +331,30 @@ export const loadOverlaySettings = () =>
 
 export const broadcastSettingsUpdate = (settings: OverlaySettings) =>
   invoke<void>('broadcast_settings_update', { settings });
+
+/** コメントオーバーレイの組み込みテーマ */
+export type CommentTheme = 'standard' | 'minimal' | 'bubble' | 'terminal';
+
+/** コメント表示テーマを設定（変更時はオーバーレイへ配信） */
+export const setCommentTheme = (name: CommentTheme) =>
+  invoke<CommentTheme>('set_comment_theme', { name });
+
+export const getCommentTheme = () =>
+  invoke<CommentTheme>('get_comment_theme');
+
+/** マイルストーンの閾値（コメント数・スパチャ合計額[円]） */
+export interface MilestoneThresholds {
+  comments: number[];
+  superchatJpy: number[];
+}
+
+/** マイルストーンの閾値を保存（昇順・重複なしに正規化した値を返す） */
+export const setMilestoneThresholds = (thresholds: MilestoneThresholds) =>
+  invoke<MilestoneThresholds>('set_milestone_thresholds', { thresholds });
+
+export const getMilestoneThresholds = () =>
+  invoke<MilestoneThresholds>('get_milestone_thresholds');
+
+/** マイルストーンの集計をリセット（ポーリング開始時にも自動でリセット） */
+export const resetMilestones = () =>
+  invoke<void>('reset_milestones');
@@ -19,6 +19,17 @@ export interface SetlistSong {
 
 export type SongStatus = 'pending' | 'current' | 'done';
 
+/** 歌唱履歴の1件（曲が現在の曲になった記録） */
+export interface SongPlay {
+  id: number;
+  setlistId: string;
+  setlistName: string | null; // セットリスト削除済みの場合はnull
+  songId: string;
+  title: string; // 記録時点の曲名
+  artist: string | null;
+  playedAt: string; // RFC3339
+}
+
 export interface SetlistWithSongs {
   setlist: Setlist;
   songs: SetlistSong[];
@@ -5,6 +5,7 @@ export interface Song {
   category: string | null;
   tags: string | null; // JSON string from Rust
   durationSeconds: number | null;
+  songKey: string | null; // キー（調）: 例 "C", "F#m"
   createdAt: string;
   updatedAt: string;
 }
@@ -15,6 +16,7 @@ export interface CreateSongInput {
   category?: string | null;
   tags?: string[] | null;
   duration_seconds?: number | null;
+  song_key?: string | null;
   [key: string]: unknown;
 }
 
@@ -22,6 +24,21 @@ export interface UpdateSongInput extends CreateSongInput {
   id: string;
 }
 
+/** 楽曲インポートで取り込まなかった行 */
+export interface SongImportError {
+  line: number; // 1始まり
+  message: string;
+}
+
+/** 楽曲インポートの結果 */
+export interface SongImportResult {
+  created: number; // 新規作成した楽曲数
+  skipped: number; // 登録済みのため作成しなかった楽曲数
+  addedToSetlist: number;
+  setlistId: string | null;
+  errors: SongImportError[];
+}
+
 /**
  * Parse tags from JSON string to array
  */
@@ -446,3 +446,40 @@ export const DEFAULT_TEMPLATE: Template = {
   },
   components: [],
 };
+
+// ===== テキストテンプレート（プレースホルダー） =====
+
+/** テキストテンプレート名 */
+export type TextTemplateName = 'comment' | 'superchat' | 'kpi' | 'weather';
+
+/** 条件ブロック（`{{#if flag}}...{{/if}}`）で使用可能なフラグ（comment/superchatのみ） */
+export type TemplateConditionFlag = 'is_owner' | 'is_moderator' | 'is_member' | 'is_superchat';
+
+/** テキストテンプレートの検証エラー（Rust側 TemplateError に対応） */
+export type TemplateError =
+  | { type: 'unknownTemplate'; name: string }
+  | { type: 'unknownPlaceholder'; name: string; position: number; suggestion?: string }
+  | { type: 'unclosedPlaceholder'; position: number }
+  | { type: 'emptyPlaceholder'; position: number }
+  | { type: 'unknownCondition'; name: string; position: number; suggestion?: string }
+  | { type: 'unclosedCondition'; position: number }
+  | { type: 'unmatchedEndIf'; position: number };
+
+/** validate_all_templatesコマンドの結果（テンプレート名 → エラー一覧） */
+export type TemplateValidationResults = Record<string, TemplateError[]>;
+
+/** コンポーネント設定内のプレースホルダーの検証エラー */
+export interface ComponentPlaceholderError {
+  componentId: string;
+  /** エラーのあった項目（例: "style.format", "style.lines[0]"） */
+  field: string;
+  error: TemplateError;
+}
+
+/** validate_templateコマンドの結果 */
+export interface TemplateValidationResult {
+  /** クランプ済みのテンプレート */
+  template: Template;
+  /** 未知・タイプミスのプレースホルダー（描画時に空になる） */
+  placeholderErrors: ComponentPlaceholderError[];
+}
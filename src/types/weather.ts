@@ -4,11 +4,23 @@ import { invoke } from '@tauri-apps/api/core';
 export interface WeatherData {
   icon: string;
   temp: number;
+  /** 気温の単位記号（"°C" or "°F"） */
+  tempUnit: string;
   description: string;
   location: string;
   humidity: number;
+  /** 風速（単位はwindSpeedUnit） */
+  windSpeed: number | null;
+  /** 風速の単位記号（"km/h" or "mph"） */
+  windSpeedUnit: string | null;
+  /** 風向（度） */
+  windDirection: number | null;
+  /** 風向の8方位表記（"N", "NE", ...） */
+  windCompass: string | null;
   weatherCode: number;
   fetchedAt: number;
+  /** 最新の取得に失敗し、前回取得した値を返している場合はtrue */
+  stale?: boolean;
 }
 
 // ライブ配信統計情報
@@ -25,6 +37,103 @@ export const setWeatherCity = (city: string) =>
 export const getWeatherCity = () =>
   invoke<string>('get_weather_city');
 
+/** 都市検索の候補（同名都市の選択用） */
+export interface CityCandidate {
+  name: string;
+  admin1: string | null;
+  country: string | null;
+  latitude: number;
+  longitude: number;
+  /** 表示用の地名（"都市名, 行政区画, 国"） */
+  displayName: string;
+}
+
+/** 都市名に対して固定した緯度経度 */
+export interface PinnedCityCoords {
+  city: string;
+  latitude: number;
+  longitude: number;
+  displayName: string;
+}
+
+/** 都市名で検索し、同名都市を含む候補（最大10件）を取得 */
+export const searchCities = (query: string) =>
+  invoke<CityCandidate[]>('search_cities', { query });
+
+/** 都市名に対する緯度経度を固定（以降はジオコーディングしない） */
+export const setCityCoords = (
+  city: string,
+  latitude: number,
+  longitude: number,
+  displayName?: string,
+) =>
+  invoke<void>('set_city_coords', {
+    city,
+    latitude,
+    longitude,
+    display_name: displayName ?? null,
+  });
+
+/** 都市名に対する緯度経度の固定を解除（以降はジオコーディングする） */
+export const unpinCityCoords = (city: string) =>
+  invoke<void>('unpin_city_coords', { city });
+
+/** 固定した緯度経度の一覧を取得 */
+export const getPinnedCityCoords = () =>
+  invoke<PinnedCityCoords[]>('get_pinned_city_coords');
+
+/** 単位系（metric: °C/km/h, imperial: °F/mph） */
+export type WeatherUnits = 'metric' | 'imperial';
+
+/** 単位系を設定（変更時はキャッシュがクリアされる） */
+export const setWeatherUnits = (units: WeatherUnits) =>
+  invoke<void>('set_weather_units', { units });
+
+export const getWeatherUnits = () =>
+  invoke<WeatherUnits>('get_weather_units');
+
+/** 天気APIへの同時リクエスト数の上限を設定（1〜16、超過分は順番待ち） */
+export const setWeatherMaxConcurrentRequests = (limit: number) =>
+  invoke<void>('set_weather_max_concurrent_requests', { limit });
+
+export const getWeatherMaxConcurrentRequests = () =>
+  invoke<number>('get_weather_max_concurrent_requests');
+
+/** 天気APIの一時的なエラー（タイムアウト・接続エラー・5xx）時のリトライ設定 */
+export interface WeatherRetryPolicy {
+  /** 最大試行回数（初回を含む、1〜5） */
+  maxAttempts: number;
+  /** 初回リトライまでの待機時間（ミリ秒、以降2倍ずつ増やす、10000以下） */
+  baseDelayMs: number;
+}
+
+export const setWeatherRetryPolicy = (policy: WeatherRetryPolicy) =>
+  invoke<void>('set_weather_retry_policy', {
+    max_attempts: policy.maxAttempts,
+    base_delay_ms: policy.baseDelayMs,
+  });
+
+export const getWeatherRetryPolicy = () =>
+  invoke<WeatherRetryPolicy>('get_weather_retry_policy');
+
+/** WMOコードごとの表示上書き設定（コード → [絵文字, 説明]） */
+export type WeatherIconMap = Record<number, [emoji: string, description: string]>;
+
+/** WMOコードのアイコン・説明の上書き設定を保存（空で組み込みに戻す） */
+export const setWeatherIconMap = (iconMap: WeatherIconMap) =>
+  invoke<void>('set_weather_icon_map', { icon_map: iconMap });
+
+export const getWeatherIconMap = () =>
+  invoke<WeatherIconMap>('get_weather_icon_map');
+
+/** 天気の自動更新間隔（分）を保存（1〜120分に丸め、丸めた値を返す） */
+export const setWeatherUpdateInterval = (minutes: number) =>
+  invoke<number>('set_weather_update_interval', { minutes });
+
+/** 天気の自動更新間隔（分）を取得 */
+export const getWeatherUpdateInterval = () =>
+  invoke<number>('get_weather_update_interval');
+
 export const getWeather = () =>
   invoke<WeatherData>('get_weather');
 
@@ -37,9 +146,18 @@ export const broadcastWeatherUpdate = (forceRefresh?: boolean) =>
 export const clearWeatherCache = () =>
   invoke<void>('clear_weather_cache');
 
+/** 天気キャッシュの残り有効期限（秒）を取得 */
 export const getWeatherCacheTtl = () =>
   invoke<number>('get_weather_cache_ttl');
 
+/** 天気キャッシュのTTL（分）を保存（1〜180分に丸め、丸めた値を返す） */
+export const setWeatherCacheTtl = (minutes: number) =>
+  invoke<number>('set_weather_cache_ttl', { minutes });
+
+/** 天気キャッシュのTTL（分）の設定値を取得 */
+export const getWeatherCacheTtlMinutes = () =>
+  invoke<number>('get_weather_cache_ttl_minutes');
+
 // 新UI用コマンド（2ボタン化対応）
 
 /** 天気を手動更新（キャッシュクリア + 取得 + タイマーリセット） */
@@ -54,6 +172,34 @@ export const broadcastWeather = () =>
 export const setWeatherCityAndBroadcast = (city: string) =>
   invoke<WeatherData>('set_weather_city_and_broadcast', { city });
 
+// 天気予報（3日間）
+
+/** 1日分の予報 */
+export interface ForecastDay {
+  date: string;
+  icon: string;
+  description: string;
+  weatherCode: number;
+  tempMax: number;
+  tempMin: number;
+}
+
+/** 天気予報データ */
+export interface ForecastData {
+  location: string;
+  tempUnit: string;
+  days: ForecastDay[];
+  fetchedAt: number;
+}
+
+/** 3日間の天気予報を取得（キャッシュ優先） */
+export const getWeatherForecast = () =>
+  invoke<ForecastData>('get_weather_forecast');
+
+/** 天気予報をオーバーレイに配信 */
+export const broadcastWeatherForecast = () =>
+  invoke<void>('broadcast_weather_forecast');
+
 // マルチシティモード用の型と関数
 
 /** 都市タプル: [cityId, cityName, displayName] */
@@ -65,9 +211,14 @@ export interface CityWeatherData {
   cityName: string;
   icon: string;
   temp: number;
+  tempUnit: string;
   description: string;
   location: string;
   humidity: number | null;
+  windSpeed: number | null;
+  windSpeedUnit: string | null;
+  windDirection: number | null;
+  windCompass: string | null;
 }
 
 /** マルチシティ配信結果 */
@@ -116,3 +267,77 @@ export const broadcastKpiUpdate = (
   subLabel: string | null
 ) =>
   invoke<void>('broadcast_kpi_update', { main, label, sub, sub_label: subLabel });
+
+/** KPI履歴の1サンプル（視聴者数グラフ用） */
+export interface KpiSample {
+  /** 記録時刻（UTC、RFC3339） */
+  recordedAt: string;
+  concurrentViewers: number | null;
+  likeCount: number | null;
+  viewCount: number | null;
+}
+
+/** KPI履歴を記録順に取得（videoId省略時は最後に記録した配信） */
+export const getKpiHistory = (videoId?: string) =>
+  invoke<KpiSample[]>('get_kpi_history', { video_id: videoId ?? null });
+
+/** 最多支援者 */
+export interface TopSupporter {
+  authorName: string;
+  authorChannelId: string;
+  /** スパチャ合計（日本円換算） */
+  totalJpy: number;
+}
+
+/** 配信セッションごとのスパチャ集計 */
+export interface SessionSuperchatSummary {
+  sessionId: number;
+  /** 動画ID（公式APIをlive_chat_id指定で開始した場合はnull） */
+  videoId: string | null;
+  /** 開始時刻（UTC、RFC3339） */
+  startedAt: string;
+  /** 終了時刻（配信中はnull） */
+  endedAt: string | null;
+  /** スパチャ合計（日本円換算） */
+  totalJpy: number;
+  count: number;
+  topSupporter: TopSupporter | null;
+}
+
+/** 配信セッションごとのスパチャ集計履歴を新しい順に取得 */
+export const getSessionSuperchatHistory = () =>
+  invoke<SessionSuperchatSummary[]>('get_session_superchat_history');
+
+/** KPIの自動取得を開始（intervalMinutesごとに記録・配信し、配信終了で自動停止） */
+export const startKpiSampler = (videoId: string, useBundledKey: boolean, intervalMinutes: number) =>
+  invoke<void>('start_kpi_sampler', {
+    video_id: videoId,
+    use_bundled_key: useBundledKey,
+    interval_minutes: intervalMinutes,
+  });
+
+/** KPIの自動取得を停止（実行中だった場合はtrue） */
+export const stopKpiSampler = () =>
+  invoke<boolean>('stop_kpi_sampler');
+
+export const isKpiSamplerRunning = () =>
+  invoke<boolean>('is_kpi_sampler_running');
+
+/** 配信の経過時間 */
+export interface StreamUptime {
+  /** 実際の配信開始時刻（RFC3339、未開始の場合はnull） */
+  startedAt: string | null;
+  /** 予約された開始時刻（RFC3339） */
+  scheduledStartTime: string | null;
+  /** 配信開始からの経過秒数（未開始の場合はnull、終了済みの場合は配信時間） */
+  uptimeSeconds: number | null;
+  ended: boolean;
+}
+
+/** 配信の経過時間を取得（クォータ消費: 1 unit） */
+export const getStreamUptime = (videoId: string, useBundledKey: boolean) =>
+  invoke<StreamUptime>('get_stream_uptime', { video_id: videoId, use_bundled_key: useBundledKey });
+
+/** 配信の経過時間を取得してオーバーレイに配信（未開始の場合は配信しない） */
+export const broadcastStreamUptime = (videoId: string, useBundledKey: boolean) =>
+  invoke<StreamUptime>('broadcast_stream_uptime', { video_id: videoId, use_bundled_key: useBundledKey });
@@ -1,3 +1,47 @@
+/**
+ * Tauriコマンドのエラー種別（src-tauri/src/commands/error.rs の CommandError と対応）
+ */
+export type CommandErrorKind =
+  | 'validation'
+  | 'invalidApiKey'
+  | 'quotaExceeded'
+  | 'rateLimited'
+  | 'notFound'
+  | 'network'
+  | 'api'
+  | 'database'
+  | 'internal';
+
+/**
+ * Tauriコマンドのエラー（youtube/weatherコマンドは { kind, message } で返す）
+ */
+export interface CommandError {
+  kind: CommandErrorKind;
+  message: string;
+}
+
+/**
+ * Tauriコマンドのエラー（CommandError）かどうか
+ */
+export function isCommandError(err: unknown): err is CommandError {
+  return (
+    !!err &&
+    typeof err === 'object' &&
+    typeof (err as { kind?: unknown }).kind === 'string' &&
+    typeof (err as { message?: unknown }).message === 'string'
+  );
+}
+
+/**
+ * エラー種別ごとの案内（種別だけで対処がわかるもの）
+ */
+const COMMAND_ERROR_GUIDANCE: Partial<Record<CommandErrorKind, string>> = {
+  invalidApiKey: 'APIキーが無効です。正しいAPIキーを入力してください。',
+  quotaExceeded: 'APIクォータが超過しています。明日再度お試しください。',
+  rateLimited: 'レート制限に達しました。しばらく待ってから再度お試しください。',
+  network: 'ネットワークエラーが発生しました。インターネット接続を確認してください。',
+};
+
 /**
  * Tauriエラーからエラーメッセージを抽出
  */
@@ -35,6 +79,12 @@ export function formatYouTubeApiError(errorMessage: string): string {
  * Tauriエラーを処理してユーザーフレンドリーなメッセージを返す
  */
 export function handleTauriError(err: unknown, defaultMessage = 'エラーが発生しました'): string {
+  if (isCommandError(err)) {
+    const guidance = COMMAND_ERROR_GUIDANCE[err.kind];
+    if (guidance) {
+      return guidance;
+    }
+  }
   const rawMessage = extractErrorMessage(err);
   if (!rawMessage || rawMessage === 'undefined' || rawMessage === '[object Object]') {
     return defaultMessage;